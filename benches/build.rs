@@ -0,0 +1,5 @@
+use ceno_build::{BuildOptions, build_program};
+
+fn main() {
+    build_program("guests", &BuildOptions::default());
+}
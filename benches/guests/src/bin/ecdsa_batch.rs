@@ -0,0 +1,125 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use ceno_guest::{secp256k1_add, secp256k1_double, uint256_mulmod};
+
+/// The order of the group of secp256k1.
+const N: [u32; 8] = [
+    0xD036_4141,
+    0xBFD2_5E8C,
+    0xAF48_A03B,
+    0xBAAE_DCE6,
+    0xFFFF_FFFE,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+];
+
+/// The generator of secp256k1.
+const G: [u32; 16] = [
+    0x16F8_1798,
+    0x59F2_815B,
+    0x2DCE_28D9,
+    0x029B_FCDB,
+    0xCE87_0B07,
+    0x55A0_6295,
+    0xF9DC_BBAC,
+    0x79BE_667E,
+    0xFB10_D4B8,
+    0x9C47_D08F,
+    0xA685_5419,
+    0xFD17_B448,
+    0x0E11_08A8,
+    0x5DA4_FBFC,
+    0x26A3_C465,
+    0x483A_DA77,
+];
+
+/// A public key, a message digest, and the signature `(r, s)` of the digest.
+type Signature = ([u32; 16], [u32; 8], [u32; 8], [u32; 8]);
+
+ceno_guest::entry!(main);
+fn main() {
+    let signatures: Vec<Signature> = ceno_guest::read();
+    let valid = signatures.iter().filter(|sig| verify(sig)).count() as u32;
+    ceno_guest::commit(&valid);
+}
+
+/// Verify an ECDSA signature. The cases where a partial sum is the point at infinity, which
+/// honest signatures only reach with negligible probability, are rejected.
+fn verify((public_key, digest, r, s): &Signature) -> bool {
+    if [r, s].iter().any(|x| **x == [0; 8] || !lt(x, &N)) {
+        return false;
+    }
+    let mut n_minus_2 = N;
+    n_minus_2[0] -= 2;
+    let w = pow(s, &n_minus_2);
+    let u1 = uint256_mulmod(&reduce(digest), &w, &N);
+    let u2 = uint256_mulmod(r, &w, &N);
+    let (Some(mut point), Some(other)) = (mul(&G, &u1), mul(public_key, &u2)) else {
+        return false;
+    };
+    if point[..8] != other[..8] {
+        secp256k1_add(&mut point, &other);
+    } else if point[8..] == other[8..] {
+        secp256k1_double(&mut point);
+    } else {
+        return false;
+    }
+    reduce(point[..8].try_into().unwrap()) == *r
+}
+
+/// Whether `x < y`.
+fn lt(x: &[u32; 8], y: &[u32; 8]) -> bool {
+    x.iter().rev().lt(y.iter().rev())
+}
+
+/// `x mod N`, for any 256-bit `x`, which is less than `2N`.
+fn reduce(x: &[u32; 8]) -> [u32; 8] {
+    if lt(x, &N) {
+        return *x;
+    }
+    let mut result = [0; 8];
+    let mut borrow = false;
+    for i in 0..8 {
+        let (diff, b1) = x[i].overflowing_sub(N[i]);
+        let (diff, b2) = diff.overflowing_sub(borrow as u32);
+        result[i] = diff;
+        borrow = b1 || b2;
+    }
+    result
+}
+
+fn bit(x: &[u32; 8], i: usize) -> bool {
+    (x[i / 32] >> (i % 32)) & 1 == 1
+}
+
+/// `x^e mod N`, by squaring and multiplying.
+fn pow(x: &[u32; 8], e: &[u32; 8]) -> [u32; 8] {
+    let mut result = [0; 8];
+    result[0] = 1;
+    for i in (0..256).rev() {
+        result = uint256_mulmod(&result, &result, &N);
+        if bit(e, i) {
+            result = uint256_mulmod(&result, x, &N);
+        }
+    }
+    result
+}
+
+/// `k p` by doubling and adding, or `None` for `k = 0`. For `k < N`, the partial sums never
+/// meet `p`, so that each addition is of distinct abscissas.
+fn mul(p: &[u32; 16], k: &[u32; 8]) -> Option<[u32; 16]> {
+    let top = (0..256).rev().find(|&i| bit(k, i))?;
+    let mut result = *p;
+    for i in (0..top).rev() {
+        secp256k1_double(&mut result);
+        if bit(k, i) {
+            secp256k1_add(&mut result, p);
+        }
+    }
+    Some(result)
+}
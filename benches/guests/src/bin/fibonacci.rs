@@ -0,0 +1,13 @@
+#![no_main]
+#![no_std]
+
+ceno_guest::entry!(main);
+fn main() {
+    // The n-th Fibonacci number modulo 2^32.
+    let n: u32 = ceno_guest::read();
+    let (mut a, mut b) = (0u32, 1u32);
+    for _ in 0..n {
+        (a, b) = (b, a.wrapping_add(b));
+    }
+    ceno_guest::commit(&a);
+}
@@ -0,0 +1,26 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::vec;
+
+ceno_guest::entry!(main);
+fn main() {
+    // Copy a buffer of `len` bytes `n` times, changing one byte between copies so that none of
+    // them can be skipped.
+    let (len, n): (u32, u32) = ceno_guest::read();
+    let mut src = vec![0u8; len as usize];
+    for (i, byte) in src.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let mut dst = vec![0u8; len as usize];
+    for round in 0..n as usize {
+        dst.copy_from_slice(&src);
+        dst[round % len as usize] ^= 1;
+        core::mem::swap(&mut src, &mut dst);
+    }
+    let checksum = src.iter().fold(0u32, |acc, &byte| {
+        acc.wrapping_mul(31).wrapping_add(byte as u32)
+    });
+    ceno_guest::commit(&checksum);
+}
@@ -0,0 +1,137 @@
+//! Prove standard guest workloads, and report their cycles, constraints, proving time and proof
+//! size, optionally failing on a regression over the report of an earlier run.
+
+use std::{fs, path::PathBuf, process, time::Instant};
+
+use ceno_emul::Program;
+use ceno_zkvm::{
+    ProveOptions,
+    e2e::{run_e2e_gen_witness, run_e2e_proof, run_e2e_verify},
+    scheme::pcs::{PcsKind, PolynomialCommitmentScheme},
+    serialization::to_bytes,
+    with_pcs,
+};
+use clap::Parser;
+use goldilocks::GoldilocksExt2;
+use tracing_subscriber::EnvFilter;
+
+mod report;
+use report::{Metrics, print_table, regressions};
+
+mod workloads;
+use workloads::{WORKLOADS, Workload, workloads};
+
+type E = GoldilocksExt2;
+
+/// Benchmark the proving of standard guest workloads.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// The workloads to run, all by default.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = clap::builder::PossibleValuesParser::new(WORKLOADS)
+    )]
+    workloads: Vec<String>,
+
+    /// Multiply the input size of every workload.
+    #[arg(long, default_value = "1")]
+    scale: u32,
+
+    /// The polynomial commitment scheme: basefold-rs or basefold-basecode.
+    #[arg(long, default_value = "basefold-rs")]
+    pcs: PcsKind,
+
+    /// Where to write the report, as JSON.
+    #[arg(long)]
+    json: Option<PathBuf>,
+
+    /// The JSON report of an earlier run, to fail on a regression of any metric.
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// The relative increase of a metric over the baseline which counts as a regression.
+    #[arg(long, default_value = "0.1")]
+    tolerance: f64,
+}
+
+fn main() {
+    tracing_subscriber::fmt()
+        .compact()
+        .without_time()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+    let args = Args::parse();
+
+    let report = workloads(&args.workloads, args.scale)
+        .iter()
+        .map(|workload| with_pcs!(args.pcs, E, Pcs => run::<Pcs>(workload)))
+        .collect::<Vec<_>>();
+    print_table(&report);
+
+    if let Some(path) = &args.json {
+        fs::write(path, serde_json::to_vec_pretty(&report).unwrap()).expect("write report");
+    }
+    if let Some(path) = &args.baseline {
+        let baseline: Vec<Metrics> =
+            serde_json::from_slice(&fs::read(path).expect("read baseline"))
+                .expect("parse baseline");
+        let regressions = regressions(&report, &baseline, args.tolerance);
+        if !regressions.is_empty() {
+            for regression in regressions {
+                println!("Regression of {regression}");
+            }
+            process::exit(1);
+        }
+    }
+}
+
+/// Prove and verify the execution of `workload`, which must exit with code 0.
+fn run<Pcs: PolynomialCommitmentScheme<E>>(workload: &Workload) -> Metrics {
+    let options = ProveOptions::default();
+    let program = Program::load_elf(workload.elf, u32::MAX).expect("load elf");
+    let (prover, verifier, witness, pi, cycles, start, exit_code, guest_panic) =
+        run_e2e_gen_witness::<E, Pcs>(
+            program,
+            options.platform,
+            options.stack_size,
+            options.heap_size,
+            workload.stdin.to_words(),
+            options.max_steps,
+            None,
+            options.transcript_hash,
+        )
+        .expect("witness generation failed");
+    assert_eq!(
+        exit_code,
+        Some(0),
+        "the workload {} failed: {guest_panic:?}",
+        workload.name
+    );
+    let witness_time = start.elapsed().as_secs_f64();
+
+    let constraints = witness
+        .num_instances()
+        .iter()
+        .map(|(name, instances)| {
+            let stats = prover.pk.circuit_pks[name].get_cs().stats();
+            instances * stats.constraints.values().sum::<usize>()
+        })
+        .sum();
+
+    let timer = Instant::now();
+    let proof = run_e2e_proof(prover, witness, pi).expect("create_proof failed");
+    let proving_time = timer.elapsed().as_secs_f64();
+    let proof_size = to_bytes(&proof).expect("serialize proof").len();
+    run_e2e_verify(&verifier, proof, exit_code, options.max_steps);
+
+    Metrics {
+        workload: workload.name.to_string(),
+        cycles,
+        constraints,
+        witness_time,
+        proving_time,
+        proof_size,
+    }
+}
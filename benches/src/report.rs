@@ -0,0 +1,115 @@
+//! The metrics of a run of the benchmarks, and their comparison with those of an earlier run.
+
+use prettytable::{Table, row};
+use serde::{Deserialize, Serialize};
+
+/// The metrics of one workload.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    pub workload: String,
+    pub cycles: usize,
+    /// The zero constraints of all circuit instances, which the prover proves by sumcheck.
+    pub constraints: usize,
+    /// The emulation and witness assignment, in seconds.
+    pub witness_time: f64,
+    /// The proving of the witnesses, in seconds.
+    pub proving_time: f64,
+    /// The size of the serialized proof, in bytes.
+    pub proof_size: usize,
+}
+
+impl Metrics {
+    /// The metrics by name, lower being better.
+    fn values(&self) -> [(&'static str, f64); 5] {
+        [
+            ("cycles", self.cycles as f64),
+            ("constraints", self.constraints as f64),
+            ("witness_time", self.witness_time),
+            ("proving_time", self.proving_time),
+            ("proof_size", self.proof_size as f64),
+        ]
+    }
+}
+
+pub fn print_table(report: &[Metrics]) {
+    let mut table = Table::new();
+    table.add_row(row![
+        "workload",
+        "cycles",
+        "constraints",
+        "witness_time (s)",
+        "proving_time (s)",
+        "proof_size (bytes)"
+    ]);
+    for metrics in report {
+        table.add_row(row![
+            metrics.workload,
+            metrics.cycles,
+            metrics.constraints,
+            format!("{:.3}", metrics.witness_time),
+            format!("{:.3}", metrics.proving_time),
+            metrics.proof_size
+        ]);
+    }
+    table.printstd();
+}
+
+/// The regressions of `report` over `baseline`: the metrics of a workload of both which exceed
+/// their baseline by more than `tolerance`, relatively.
+pub fn regressions(report: &[Metrics], baseline: &[Metrics], tolerance: f64) -> Vec<String> {
+    report
+        .iter()
+        .filter_map(|metrics| {
+            let base = baseline.iter().find(|b| b.workload == metrics.workload)?;
+            Some((metrics, base))
+        })
+        .flat_map(|(metrics, base)| {
+            metrics
+                .values()
+                .into_iter()
+                .zip(base.values())
+                .filter(|((_, value), (_, base))| *value > base * (1.0 + tolerance))
+                .map(|((name, value), (_, base))| {
+                    format!(
+                        "{}: {name} {value} over the baseline {base}",
+                        metrics.workload
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regressions() {
+        let baseline = Metrics {
+            workload: "fibonacci".into(),
+            cycles: 1000,
+            constraints: 5000,
+            witness_time: 1.0,
+            proving_time: 10.0,
+            proof_size: 100,
+        };
+        let report = Metrics {
+            cycles: 1050,
+            proving_time: 12.0,
+            ..baseline.clone()
+        };
+        assert_eq!(
+            regressions(&[report.clone()], &[baseline.clone()], 0.1),
+            vec!["fibonacci: proving_time 12 over the baseline 10"]
+        );
+        assert!(regressions(&[report.clone()], &[baseline], 0.25).is_empty());
+
+        // A workload missing from the baseline has nothing to regress from.
+        let other = Metrics {
+            workload: "memcpy".into(),
+            ..report
+        };
+        assert!(regressions(&[other], &[], 0.0).is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+//! The guests of the benchmarks and their inputs, which are deterministic so that runs compare.
+
+use ceno_build::include_elf;
+use ceno_emul::{
+    SECP256K1_POINT_WORDS, UINT256_WORDS, Word, secp256k1_add, secp256k1_double, uint256_addmod,
+    uint256_invmod, uint256_mulmod,
+};
+use ceno_guest::CenoStdin;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// A guest and its input. The size of the input scales the work of the guest linearly.
+pub struct Workload {
+    pub name: &'static str,
+    pub elf: &'static [u8],
+    pub stdin: CenoStdin,
+}
+
+/// The names of all workloads, in the order of [`workloads`].
+pub const WORKLOADS: [&str; 4] = ["fibonacci", "sha256_chain", "ecdsa_batch", "memcpy"];
+
+/// The workloads of `names`, with their default sizes multiplied by `scale`.
+pub fn workloads(names: &[String], scale: u32) -> Vec<Workload> {
+    WORKLOADS
+        .into_iter()
+        .filter(|name| names.is_empty() || names.iter().any(|n| n == name))
+        .map(|name| {
+            let (elf, stdin): (&[u8], _) = match name {
+                "fibonacci" => (include_elf!("fibonacci"), input(&((1u32 << 16) * scale))),
+                "sha256_chain" => (
+                    include_elf!("sha256_chain"),
+                    input(&([7u8; 32], 256 * scale)),
+                ),
+                "ecdsa_batch" => (
+                    include_elf!("ecdsa_batch"),
+                    input(&signatures(4 * scale as usize)),
+                ),
+                "memcpy" => (include_elf!("memcpy"), input(&(16u32 << 10, 64 * scale))),
+                _ => unreachable!(),
+            };
+            Workload { name, elf, stdin }
+        })
+        .collect()
+}
+
+fn input<T: serde::Serialize + ?Sized>(value: &T) -> CenoStdin {
+    let mut stdin = CenoStdin::new();
+    stdin.write(value).expect("serialize input");
+    stdin
+}
+
+type Scalar = [Word; UINT256_WORDS];
+type Point = [Word; SECP256K1_POINT_WORDS];
+
+/// The order of the group of secp256k1.
+const N: Scalar = [
+    0xD036_4141,
+    0xBFD2_5E8C,
+    0xAF48_A03B,
+    0xBAAE_DCE6,
+    0xFFFF_FFFE,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+];
+
+/// The generator of secp256k1.
+const G: Point = [
+    0x16F8_1798,
+    0x59F2_815B,
+    0x2DCE_28D9,
+    0x029B_FCDB,
+    0xCE87_0B07,
+    0x55A0_6295,
+    0xF9DC_BBAC,
+    0x79BE_667E,
+    0xFB10_D4B8,
+    0x9C47_D08F,
+    0xA685_5419,
+    0xFD17_B448,
+    0x0E11_08A8,
+    0x5DA4_FBFC,
+    0x26A3_C465,
+    0x483A_DA77,
+];
+
+/// `count` ECDSA signatures of random digests, each by its own key, as the guest `ecdsa_batch`
+/// reads them: the public key, the digest, `r` and `s`.
+fn signatures(count: usize) -> Vec<(Point, Scalar, Scalar, Scalar)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut scalar = || loop {
+        let k = uint256_addmod(&rng.gen(), &[0; UINT256_WORDS], &N);
+        if k != [0; UINT256_WORDS] {
+            break k;
+        }
+    };
+    (0..count)
+        .map(|_| {
+            let (key, digest, nonce) = (scalar(), scalar(), scalar());
+            let nonce_x = mul(&nonce)[..UINT256_WORDS].try_into().unwrap();
+            let r = uint256_addmod(&nonce_x, &[0; UINT256_WORDS], &N);
+            // s = (digest + r key) / nonce
+            let s = uint256_mulmod(
+                &uint256_addmod(&digest, &uint256_mulmod(&r, &key, &N), &N),
+                &uint256_invmod(&nonce, &N),
+                &N,
+            );
+            assert!(r != [0; UINT256_WORDS] && s != [0; UINT256_WORDS]);
+            (mul(&key), digest, r, s)
+        })
+        .collect()
+}
+
+/// `k G`, by doubling and adding, for a non-zero `k < N`.
+fn mul(k: &Scalar) -> Point {
+    let bit = |i: usize| (k[i / 32] >> (i % 32)) & 1 == 1;
+    let top = (0..256).rev().find(|&i| bit(i)).unwrap();
+    let mut point = G;
+    for i in (0..top).rev() {
+        assert!(secp256k1_double(&mut point));
+        if bit(i) {
+            assert!(secp256k1_add(&mut point, &G));
+        }
+    }
+    point
+}
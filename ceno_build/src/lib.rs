@@ -0,0 +1,161 @@
+//! Build guest programs from the build scripts of host crates, see [`build_program`] and
+//! [`include_elf!`].
+//!
+//! A guest is a Cargo project built for [`GUEST_TARGET`], whose linker script and memory layout
+//! come from the build script of `ceno_rt`. It runs its own Cargo, outside of the build of the
+//! host, so that it uses the toolchain of its own `rust-toolchain.toml`.
+#![deny(clippy::cargo)]
+
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde_json::Value;
+
+/// The target of guest programs.
+pub const GUEST_TARGET: &str = "riscv32im-unknown-none-elf";
+
+/// The flags linking a guest with the scripts of `ceno_rt`.
+const GUEST_RUSTFLAGS: &[&str] = &["-C", "link-arg=-Tmemory.x", "-C", "link-arg=-Tceno_link.x"];
+
+/// The parameters of [`build_guest`].
+#[derive(Clone, Debug)]
+pub struct BuildOptions {
+    /// The Cargo profile.
+    pub profile: String,
+    pub features: Vec<String>,
+    /// Build the examples of the project rather than its binaries.
+    pub examples: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            profile: "release".into(),
+            features: vec![],
+            examples: false,
+        }
+    }
+}
+
+/// Build the guest Cargo project at `dir`, and return the paths of the ELFs of its binaries, or
+/// of its examples, by name. Panic if the build fails, with the output of Cargo.
+pub fn build_guest(dir: impl AsRef<Path>, options: &BuildOptions) -> BTreeMap<String, PathBuf> {
+    let dir = dir.as_ref();
+    let metadata = cargo(dir)
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .expect("cargo command failed to run");
+    check(&metadata, "cargo metadata of the guest");
+    let metadata: Value =
+        serde_json::from_slice(&metadata.stdout).expect("invalid output of cargo metadata");
+
+    let kind = if options.examples { "example" } else { "bin" };
+    let names = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|package| package["targets"].as_array().into_iter().flatten())
+        .filter(|target| {
+            target["kind"]
+                .as_array()
+                .is_some_and(|kinds| kinds.iter().any(|k| k == kind))
+        })
+        .filter_map(|target| target["name"].as_str().map(String::from))
+        .collect::<Vec<_>>();
+
+    let mut build = cargo(dir);
+    build
+        .args([
+            "build",
+            "--target",
+            GUEST_TARGET,
+            "--profile",
+            &options.profile,
+        ])
+        .arg(if options.examples {
+            "--examples"
+        } else {
+            "--bins"
+        })
+        .env("CARGO_ENCODED_RUSTFLAGS", GUEST_RUSTFLAGS.join("\x1f"));
+    if !options.features.is_empty() {
+        build.args(["--features", &options.features.join(",")]);
+    }
+    check(
+        &build.output().expect("cargo command failed to run"),
+        "cargo build of the guest",
+    );
+
+    let profile_dir = match options.profile.as_str() {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        profile => profile,
+    };
+    let mut out_dir = PathBuf::from(
+        metadata["target_directory"]
+            .as_str()
+            .expect("no target directory in cargo metadata"),
+    )
+    .join(GUEST_TARGET)
+    .join(profile_dir);
+    if options.examples {
+        out_dir.push("examples");
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            let elf = out_dir.join(&name);
+            (name, elf)
+        })
+        .collect()
+}
+
+/// In a build script, build the guest Cargo project at `dir`, relative to the manifest of the
+/// host crate, and embed the ELF of each binary or example `name` with `include_elf!(name)`. The
+/// guest is built again whenever its sources change.
+pub fn build_program(dir: impl AsRef<Path>, options: &BuildOptions) {
+    let dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join(dir);
+    for (name, elf) in build_guest(&dir, options) {
+        println!("cargo:rustc-env=CENO_ELF_{name}={}", elf.display());
+    }
+    for source in ["Cargo.toml", "Cargo.lock", "src", "examples", "build.rs"] {
+        println!("cargo:rerun-if-changed={}", dir.join(source).display());
+    }
+}
+
+/// The ELF of the guest binary or example `name`, built by [`build_program`] in the build script
+/// of the calling crate.
+#[macro_export]
+macro_rules! include_elf {
+    ($name:literal) => {
+        include_bytes!(env!(concat!("CENO_ELF_", $name)))
+    };
+}
+
+/// A Cargo command in `dir`, without the environment of the Cargo running the build script, which
+/// would otherwise select its toolchain and its flags.
+fn cargo(dir: &Path) -> Command {
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(dir)
+        .env_clear()
+        .envs(env::vars().filter(|(key, _)| {
+            !key.starts_with("CARGO") && !key.starts_with("RUSTC") && key != "RUSTUP_TOOLCHAIN"
+        }));
+    command
+}
+
+/// Panic with the output of `command` if it failed.
+fn check(output: &std::process::Output, command: &str) {
+    if !output.status.success() {
+        panic!(
+            "{command} failed:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt};
+
+use crate::{
+    addr::{ByteAddr, Cycle, WORD_SIZE, Word},
+    rv32im::{DecodedInstruction, TrapCause},
+};
+
+/// A panic reported by the guest with the ecall PANIC, see `VMState::guest_panic`. Unlike a
+/// trap, the guest then halts normally, with `Platform::code_panic`, which is provable.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestPanic {
+    /// The message of the panic, with its location in the guest source.
+    pub message: String,
+    /// The pc of the ecall, in the panic handler of the guest.
+    pub pc: ByteAddr,
+}
+
+impl fmt::Display for GuestPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Guest panic at pc {:?}: {}", self.pc, self.message)
+    }
+}
+
+impl Error for GuestPanic {}
+
+/// What a [`CrashReport`] includes, see `VMState::set_crash_report_config`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrashReportConfig {
+    /// The number of memory words dumped on each side of the faulting address, or of the stack
+    /// pointer if the trap has no address.
+    pub memory_window: u32,
+    /// The number of pcs kept of the last executed instructions.
+    pub history: usize,
+}
+
+impl Default for CrashReportConfig {
+    fn default() -> Self {
+        Self {
+            memory_window: 8,
+            history: 16,
+        }
+    }
+}
+
+/// The state of the machine when the guest trapped. It is the error of the step which trapped,
+/// to get with `err.downcast_ref::<CrashReport>()`, and its message is the full report.
+#[derive(Clone, Debug)]
+pub struct CrashReport {
+    pub cause: TrapCause,
+    pub pc: ByteAddr,
+    pub cycle: Cycle,
+    /// The encoding of the faulting instruction, unless the pc is out of the program.
+    pub insn_code: Option<Word>,
+    pub registers: [Word; 32],
+    /// The memory words around the faulting address, in order.
+    pub memory: Vec<(ByteAddr, Word)>,
+    /// The pcs of the instructions executed before the faulting one, oldest first.
+    pub recent_pcs: Vec<Word>,
+}
+
+impl CrashReport {
+    /// The assembly of the faulting instruction.
+    pub fn disassembly(&self) -> Option<String> {
+        self.insn_code
+            .map(|insn_code| DecodedInstruction::new(insn_code).to_string())
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Trap {:?} at pc {:?}, cycle {}",
+            self.cause, self.pc, self.cycle
+        )?;
+        match self.insn_code {
+            Some(insn_code) => writeln!(
+                f,
+                "instruction: {insn_code:#010x} {}",
+                DecodedInstruction::new(insn_code)
+            )?,
+            None => writeln!(f, "instruction: out of the program")?,
+        }
+        write!(f, "registers:")?;
+        for (i, value) in self.registers.iter().enumerate() {
+            if i % 4 == 0 {
+                write!(f, "\n ")?;
+            }
+            write!(f, " {:>4} = {value:#010x}", format!("x{i}"))?;
+        }
+        write!(f, "\nmemory:")?;
+        let fault = self.cause.address().map(|addr| addr.waddr());
+        for (addr, value) in &self.memory {
+            let marker = if Some(addr.waddr()) == fault {
+                " <-"
+            } else {
+                ""
+            };
+            write!(f, "\n  {addr:?}: {value:#010x}{marker}")?;
+        }
+        write!(f, "\nrecent pcs:")?;
+        for pc in &self.recent_pcs {
+            write!(f, "\n  {pc:#010x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for CrashReport {}
+
+/// The addresses of the words of a memory window of `window` words on each side of `center`.
+pub(crate) fn memory_window(center: ByteAddr, window: u32) -> impl Iterator<Item = ByteAddr> {
+    let center = center.waddr().baddr().0;
+    let start = center.saturating_sub(window * WORD_SIZE as u32);
+    let end = center.saturating_add(window * WORD_SIZE as u32);
+    (start..=end).step_by(WORD_SIZE).map(ByteAddr)
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+
+use crate::{
+    addr::{ByteAddr, RegIdx, Word, WordAddr},
+    rv32im::{EmuContext, Emulator},
+    tracer::{StepRecord, WriteOp},
+    vm_state::VMState,
+};
+
+/// Why [`Debugger::resume`] stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The next instruction is at a breakpoint.
+    Breakpoint(Word),
+    /// The last instruction accessed a watched memory word.
+    Watchpoint(WriteOp),
+    Halted,
+    /// The execution is paused by `VMState::set_max_cycles`.
+    Paused,
+}
+
+/// Runs a guest with breakpoints on pcs and watchpoints on memory words, in the emulator that
+/// produces the traces to prove.
+pub struct Debugger<'a> {
+    vm: &'a mut VMState,
+    emu: Emulator,
+    breakpoints: BTreeSet<Word>,
+    watchpoints: BTreeSet<WordAddr>,
+    last_step: Option<StepRecord>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(vm: &'a mut VMState) -> Self {
+        Self {
+            vm,
+            emu: Emulator::new(),
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+            last_step: None,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, pc: Word) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: Word) {
+        self.breakpoints.remove(&pc);
+    }
+
+    /// Watch the loads and stores of the word at `addr`.
+    pub fn add_watchpoint(&mut self, addr: ByteAddr) {
+        self.watchpoints.insert(addr.waddr());
+    }
+
+    pub fn remove_watchpoint(&mut self, addr: ByteAddr) {
+        self.watchpoints.remove(&addr.waddr());
+    }
+
+    /// Run a single instruction, regardless of breakpoints and watchpoints.
+    pub fn step(&mut self) -> Result<&StepRecord> {
+        let step = self.vm.step(&self.emu)?;
+        Ok(self.last_step.insert(step))
+    }
+
+    /// Run until the next instruction is at a breakpoint, an instruction accesses a watched
+    /// word, or the execution halts or pauses. The instruction at the current pc runs even if
+    /// it is at a breakpoint, so that resuming from a breakpoint moves on.
+    pub fn resume(&mut self) -> Result<Stop> {
+        loop {
+            if self.vm.halted() {
+                return Ok(Stop::Halted);
+            }
+            if self.vm.paused() {
+                return Ok(Stop::Paused);
+            }
+            self.step()?;
+            if let Some(op) = self
+                .last_step
+                .iter()
+                .flat_map(|step| step.memory_ops())
+                .find(|op| self.watchpoints.contains(&op.addr))
+            {
+                return Ok(Stop::Watchpoint(op));
+            }
+            let pc = self.vm.get_pc().0;
+            if !self.vm.halted() && self.breakpoints.contains(&pc) {
+                return Ok(Stop::Breakpoint(pc));
+            }
+        }
+    }
+
+    /// The last instruction run by the debugger.
+    pub fn last_step(&self) -> Option<&StepRecord> {
+        self.last_step.as_ref()
+    }
+
+    pub fn pc(&self) -> Word {
+        self.vm.get_pc().0
+    }
+
+    pub fn register(&self, idx: RegIdx) -> Word {
+        self.vm.peek_register(idx)
+    }
+
+    pub fn memory(&self, addr: ByteAddr) -> Word {
+        self.vm.peek_memory(addr.waddr())
+    }
+
+    /// The machine state, for any other inspection.
+    pub fn vm(&self) -> &VMState {
+        self.vm
+    }
+}
@@ -0,0 +1,157 @@
+//! The group law of the twisted Edwards curve of ed25519, `-x^2 + y^2 = 1 + d x^2 y^2` over the
+//! integers modulo `ED25519_P`, computed natively for the ecall ED25519_ADD, see
+//! `Platform::ecall_ed25519_add`. A point is in affine coordinates: `x` then `y`, each as 8
+//! little-endian words.
+
+use crate::{
+    Word,
+    uint256::{
+        UINT256_WORDS, uint256_addmod, uint256_invmod, uint256_lt, uint256_mulmod, uint256_submod,
+    },
+};
+
+/// The prime of the field of ed25519, `2^255 - 19`.
+pub const ED25519_P: [Word; UINT256_WORDS] = [
+    0xFFFF_FFED,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0x7FFF_FFFF,
+];
+
+/// The coefficient `d = -121665 / 121666` of the curve.
+pub const ED25519_D: [Word; UINT256_WORDS] = [
+    0x1359_78A3,
+    0x75EB_4DCA,
+    0x4141_D8AB,
+    0x0070_0A4D,
+    0x7779_E898,
+    0x8CC7_4079,
+    0x2B6F_FE73,
+    0x5203_6CEE,
+];
+
+/// The number of words of a point.
+pub const ED25519_POINT_WORDS: usize = 2 * UINT256_WORDS;
+
+/// `p = p + q`. The addition law is complete, so that it also doubles, and adds the neutral
+/// point `(0, 1)`. Return false, leaving `p` unchanged, if a coordinate is not reduced, or if a
+/// denominator of the law is 0, which only happens for points not on the curve.
+pub fn ed25519_add(p: &mut [Word; ED25519_POINT_WORDS], q: &[Word; ED25519_POINT_WORDS]) -> bool {
+    let [x1, y1, x2, y2]: [[Word; UINT256_WORDS]; 4] = std::array::from_fn(|i| {
+        let point = if i < 2 { &*p } else { q };
+        point[i % 2 * UINT256_WORDS..][..UINT256_WORDS]
+            .try_into()
+            .unwrap()
+    });
+    if ![x1, y1, x2, y2].iter().all(|c| uint256_lt(c, &ED25519_P)) {
+        return false;
+    }
+    let m = &ED25519_P;
+    let mut one = [0; UINT256_WORDS];
+    one[0] = 1;
+    // t = d x1 x2 y1 y2
+    let x1_y2 = uint256_mulmod(&x1, &y2, m);
+    let y1_x2 = uint256_mulmod(&y1, &x2, m);
+    let t = uint256_mulmod(&ED25519_D, &uint256_mulmod(&x1_y2, &y1_x2, m), m);
+    let [x_den, y_den] = [uint256_addmod(&one, &t, m), uint256_submod(&one, &t, m)];
+    if x_den == [0; UINT256_WORDS] || y_den == [0; UINT256_WORDS] {
+        return false;
+    }
+    let x3 = uint256_mulmod(
+        &uint256_addmod(&x1_y2, &y1_x2, m),
+        &uint256_invmod(&x_den, m),
+        m,
+    );
+    let y3 = uint256_mulmod(
+        &uint256_addmod(
+            &uint256_mulmod(&y1, &y2, m),
+            &uint256_mulmod(&x1, &x2, m),
+            m,
+        ),
+        &uint256_invmod(&y_den, m),
+        m,
+    );
+    p[..UINT256_WORDS].copy_from_slice(&x3);
+    p[UINT256_WORDS..].copy_from_slice(&y3);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_multiples() {
+        // The base point B, and its multiples 2B and 3B.
+        let b = [
+            0x8F25_D51A,
+            0xC956_2D60,
+            0x9525_A7B2,
+            0x692C_C760,
+            0xFDD6_DC5C,
+            0xC0A4_E231,
+            0xCD6E_53FE,
+            0x2169_36D3,
+            0x6666_6658,
+            0x6666_6666,
+            0x6666_6666,
+            0x6666_6666,
+            0x6666_6666,
+            0x6666_6666,
+            0x6666_6666,
+            0x6666_6666,
+        ];
+        let b2 = [
+            0x2843_CE0E,
+            0x83C5_A14E,
+            0x15D7_A45F,
+            0x080D_8E45,
+            0x1833_E7AC,
+            0x3D04_3B7D,
+            0x9F5A_046C,
+            0x36AB_384C,
+            0x6AF8_A3C9,
+            0x0E5F_46AE,
+            0x6438_5156,
+            0x9739_0F51,
+            0xC9A2_1F56,
+            0x1DA2_5EE8,
+            0x0923_29C2,
+            0x2260_CDF3,
+        ];
+        let b3 = [
+            0xD3F8_E25C,
+            0xAC62_485F,
+            0x8162_4886,
+            0x6343_9819,
+            0x3EDA_C83A,
+            0x1FF4_AE74,
+            0x2292_8F49,
+            0x67AE_9C4A,
+            0x78F5_B4D4,
+            0x02C3_6848,
+            0x6724_0304,
+            0x9F16_EC17,
+            0x6026_9EF7,
+            0xA126_A18E,
+            0x77EE_69AB,
+            0x1267_B1D1,
+        ];
+
+        let mut p = b;
+        assert!(ed25519_add(&mut p, &b));
+        assert_eq!(p, b2);
+        assert!(ed25519_add(&mut p, &b));
+        assert_eq!(p, b3);
+
+        // The neutral point.
+        let mut neutral = [0; ED25519_POINT_WORDS];
+        neutral[UINT256_WORDS] = 1;
+        assert!(ed25519_add(&mut p, &neutral));
+        assert_eq!(p, b3);
+    }
+}
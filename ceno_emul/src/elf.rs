@@ -26,9 +26,17 @@ use elf::{
     endian::LittleEndian,
     file::Class,
 };
+use serde::{Deserialize, Serialize};
+
+/// `e_flags` of a RISC-V ELF using compressed instructions.
+const EF_RISCV_RVC: u32 = 0x0001;
+/// `e_flags` mask of the floating point ABI, zero for soft float.
+const EF_RISCV_FLOAT_ABI: u32 = 0x0006;
+/// `e_flags` of a RISC-V ELF for the RV32E base, with 16 registers.
+const EF_RISCV_RVE: u32 = 0x0008;
 
 /// A RISC Zero program
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Program {
     /// The entrypoint of the program
     pub entry: u32,
@@ -72,6 +80,17 @@ impl Program {
         if elf.ehdr.e_type != elf::abi::ET_EXEC {
             bail!("Invalid ELF type, must be executable");
         }
+        // The emulator implements rv32im only.
+        let e_flags = elf.ehdr.e_flags;
+        if e_flags & EF_RISCV_RVC != 0 {
+            bail!("Compressed instructions are not supported, build for rv32im");
+        }
+        if e_flags & EF_RISCV_FLOAT_ABI != 0 {
+            bail!("Floating point ABI is not supported, build for rv32im");
+        }
+        if e_flags & EF_RISCV_RVE != 0 {
+            bail!("RV32E is not supported, build for rv32im");
+        }
         let entry: u32 = elf
             .ehdr
             .e_entry
@@ -155,12 +174,11 @@ impl Program {
             }
         }
 
-        if base_address.is_none() {
-            return Err(anyhow!("does not have executable segment"));
+        let base_address = base_address.ok_or(anyhow!("does not have executable segment"))?;
+        if entry < base_address || (entry - base_address) as usize >= instructions.len() * WORD_SIZE
+        {
+            bail!("Entrypoint 0x{entry:08x} is outside of the executable segment");
         }
-        let base_address = base_address.unwrap();
-        assert!(entry >= base_address);
-        assert!((entry - base_address) as usize <= instructions.len() * WORD_SIZE);
 
         Ok(Program {
             entry,
@@ -0,0 +1,104 @@
+//! The Keccak-f[1600] permutation, computed natively for the ecall KECCAK_PERMUTE, see
+//! `Platform::ecall_keccak_permute`.
+
+/// The number of 64-bit lanes of the state.
+pub const KECCAK_LANES: usize = 25;
+/// The number of 32-bit words of the state in memory, the low word of each lane first.
+pub const KECCAK_STATE_WORDS: usize = 2 * KECCAK_LANES;
+pub const KECCAK_ROUNDS: usize = 24;
+
+/// Round constants of the iota step.
+pub const KECCAK_ROUND_CONSTANTS: [u64; KECCAK_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of the rho step, indexed by lane `x + 5 * y`.
+pub const KECCAK_RHO_OFFSETS: [u64; KECCAK_LANES] = [
+    0, 1, 62, 28, 27, //
+    36, 44, 6, 55, 20, //
+    3, 10, 43, 25, 39, //
+    41, 45, 15, 21, 8, //
+    18, 2, 61, 56, 14,
+];
+
+/// The lane `x + 5 * y` of the state.
+pub const fn keccak_lane(x: usize, y: usize) -> usize {
+    x % 5 + 5 * (y % 5)
+}
+
+/// The lane to which the pi step moves the lane `x + 5 * y`, that is `(y, 2x + 3y)`.
+pub const fn keccak_pi(x: usize, y: usize) -> usize {
+    keccak_lane(y, 2 * x + 3 * y)
+}
+
+/// Apply the permutation to `state`, indexed by lane `x + 5 * y`.
+pub fn keccak_f(state: &mut [u64; KECCAK_LANES]) {
+    for rc in KECCAK_ROUND_CONSTANTS {
+        // theta
+        let c: [u64; 5] =
+            std::array::from_fn(|x| (0..5).fold(0, |c, y| c ^ state[keccak_lane(x, y)]));
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[keccak_lane(x, y)] ^= d;
+            }
+        }
+        // rho and pi
+        let mut b = [0; KECCAK_LANES];
+        for x in 0..5 {
+            for y in 0..5 {
+                let lane = keccak_lane(x, y);
+                b[keccak_pi(x, y)] = state[lane].rotate_left(KECCAK_RHO_OFFSETS[lane] as u32);
+            }
+        }
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[keccak_lane(x, y)] =
+                    b[keccak_lane(x, y)] ^ (!b[keccak_lane(x + 1, y)] & b[keccak_lane(x + 2, y)]);
+            }
+        }
+        // iota
+        state[0] ^= rc;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak_f_zero() {
+        // The first lanes of Keccak-f[1600] applied to the zero state.
+        let mut state = [0; KECCAK_LANES];
+        keccak_f(&mut state);
+        assert_eq!(state[..3], [
+            0xF1258F7940E1DDE7,
+            0x84D5CCF933C0478A,
+            0xD598261EA65AA9EE
+        ]);
+    }
+}
@@ -3,16 +3,66 @@ mod addr;
 pub use addr::*;
 
 mod platform;
-pub use platform::{CENO_PLATFORM, Platform};
+pub use platform::{CENO_PLATFORM, MemoryLayout, Platform};
 
 mod tracer;
 pub use tracer::{Change, MemOp, ReadOp, StepRecord, Tracer, WriteOp};
 
+mod segment;
+pub use segment::{
+    PAGE_SIZE, Segment, SegmentBoundary, SegmentReport, StateDigest, register_digest, state_digest,
+};
+
 mod vm_state;
-pub use vm_state::VMState;
+pub use vm_state::{Snapshot, VMState};
+
+mod crash;
+pub use crash::{CrashReport, CrashReportConfig, GuestPanic};
+
+mod debugger;
+pub use debugger::{Debugger, Stop};
+
+mod profiler;
+pub use profiler::{CostModel, CostTable, Profile, ProfileCosts, Symbols};
+
+mod trace_io;
+pub use trace_io::{TRACE_FORMAT_VERSION, TraceBatch, read_trace_batches, write_trace_batch};
+
+mod syscalls;
+pub use syscalls::{CapturedOutput, Output, SyscallHandler};
+
+mod keccak;
+pub use keccak::{
+    KECCAK_LANES, KECCAK_RHO_OFFSETS, KECCAK_ROUND_CONSTANTS, KECCAK_ROUNDS, KECCAK_STATE_WORDS,
+    keccak_f, keccak_lane, keccak_pi,
+};
+
+mod uint256;
+pub use uint256::{
+    UINT256_WORDS, uint256_add_wide, uint256_addmod, uint256_divrem, uint256_invmod, uint256_lt,
+    uint256_mul_wide, uint256_mulmod, uint256_submod,
+};
+
+mod secp256k1;
+pub use secp256k1::{SECP256K1_P, SECP256K1_POINT_WORDS, secp256k1_add, secp256k1_double};
+
+mod ed25519;
+pub use ed25519::{ED25519_D, ED25519_P, ED25519_POINT_WORDS, ed25519_add};
+
+mod poseidon2;
+pub use self::poseidon2::{POSEIDON2_STATE_WORDS, POSEIDON2_WIDTH, poseidon2_permute};
+
+mod sha256;
+pub use sha256::{
+    SHA256_BLOCK_WORDS, SHA256_INIT, SHA256_K, SHA256_ROUNDS, SHA256_STATE_WORDS, sha256_compress,
+    sha256_digest, sha256_schedule,
+};
 
 mod rv32im;
-pub use rv32im::{DecodedInstruction, EmuContext, InsnCategory, InsnCodes, InsnFormat, InsnKind};
+pub use rv32im::{
+    DecodedInstruction, DivRem, EmuContext, InsnCategory, InsnCodes, InsnFormat, InsnKind,
+    TrapCause,
+};
 
 mod elf;
 pub use elf::Program;
@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-use crate::addr::{Addr, RegIdx};
+use crate::addr::{Addr, RegIdx, WORD_SIZE};
 
 /// The Platform struct holds the parameters of the VM.
 /// It defines:
@@ -28,6 +28,11 @@ pub const CENO_PLATFORM: Platform = Platform {
 };
 
 impl Platform {
+    /// The file descriptor of the standard output, for `ecall_write`.
+    pub const FD_STDOUT: u32 = 1;
+    /// The file descriptor of the standard error, for `ecall_write`.
+    pub const FD_STDERR: u32 = 2;
+
     // Virtual memory layout.
 
     pub fn is_rom(&self, addr: Addr) -> bool {
@@ -99,10 +104,152 @@ impl Platform {
         0
     }
 
+    /// The code of ecall WRITE, see `SyscallHandler::write`.
+    pub const fn ecall_write() -> u32 {
+        1
+    }
+
+    /// The code of ecall READ_HINT, see `VMState::write_hints`.
+    pub const fn ecall_read_hint() -> u32 {
+        2
+    }
+
+    /// The code of ecall CLOCK, see `SyscallHandler::clock`.
+    pub const fn ecall_clock() -> u32 {
+        3
+    }
+
+    /// The code of ecall COMMIT, see `VMState::committed_output`.
+    pub const fn ecall_commit() -> u32 {
+        4
+    }
+
+    /// The code of ecall RANDOM, see `VMState::set_entropy_seed`.
+    pub const fn ecall_random() -> u32 {
+        5
+    }
+
+    /// The code of ecall SHA256: compress one block into a SHA-256 state. `a0` holds the
+    /// address of the 8 words of the state, followed by the 16 words of the block, and the
+    /// state is updated in place, see `sha256_compress`.
+    pub const fn ecall_sha256() -> u32 {
+        6
+    }
+
+    /// The code of ecall KECCAK_PERMUTE: apply Keccak-f[1600] to a state. `a0` holds the
+    /// address of the 25 lanes of the state, each as its low word then its high word, and the
+    /// state is updated in place, see `keccak_f`.
+    pub const fn ecall_keccak_permute() -> u32 {
+        7
+    }
+
+    /// The code of ecall UINT256_ADDMOD: `x = (x + y) mod m` for 256-bit numbers. `a0` holds the
+    /// address of `x`, `y` and `m`, each as 8 little-endian words, and `x` is updated in place.
+    /// The operands must be reduced, `x < m` and `y < m`, or the ecall traps.
+    pub const fn ecall_uint256_addmod() -> u32 {
+        8
+    }
+
+    /// The code of ecall UINT256_MULMOD: `x = (x * y) mod m`, with the operands of
+    /// `ecall_uint256_addmod`.
+    pub const fn ecall_uint256_mulmod() -> u32 {
+        9
+    }
+
+    /// The code of ecall SECP256K1_ADD: `p = p + q` for points of the curve secp256k1, in affine
+    /// coordinates. `a0` holds the address of `p` then `q`, each as `x` then `y` of 8
+    /// little-endian words, and `p` is updated in place. The coordinates must be reduced and the
+    /// abscissas distinct, or the ecall traps, see `secp256k1_add`.
+    pub const fn ecall_secp256k1_add() -> u32 {
+        10
+    }
+
+    /// The code of ecall SECP256K1_DOUBLE: `p = 2p`, with `p` as in `ecall_secp256k1_add`. The
+    /// coordinates must be reduced and the ordinate non-zero, or the ecall traps, see
+    /// `secp256k1_double`.
+    pub const fn ecall_secp256k1_double() -> u32 {
+        11
+    }
+
+    /// The code of ecall ED25519_ADD: `p = p + q` for points of the twisted Edwards curve of
+    /// ed25519, in affine coordinates, with the layout of `ecall_secp256k1_add`. The addition
+    /// law is complete, so that `p + p` doubles. The coordinates must be reduced, or the ecall
+    /// traps, see `ed25519_add`.
+    pub const fn ecall_ed25519_add() -> u32 {
+        12
+    }
+
+    /// The code of ecall POSEIDON2_PERMUTE: apply the Poseidon2 permutation to a
+    /// state of 12 elements of the Goldilocks field. `a0` holds the address of the elements, each
+    /// as its low word then its high word, and the state is updated in place. The elements must
+    /// be canonical, or the ecall traps, see `poseidon2_permute`.
+    pub const fn ecall_poseidon2_permute() -> u32 {
+        13
+    }
+
+    /// The code of ecall PANIC: report the panic message of the guest, before it halts with
+    /// `code_panic`. `a0` holds the address of the message, as its length in bytes followed by
+    /// the bytes, see `VMState::guest_panic`.
+    pub const fn ecall_panic() -> u32 {
+        14
+    }
+
+    /// The code of ecall COMMIT_DIGEST: attest the SHA-256 digest of the output committed with
+    /// `ecall_commit`, once, before halting. `a0` holds the address of the 8 words of the digest,
+    /// as computed by the guest, which are read only. The digest must be that of
+    /// `VMState::committed_output`, or the ecall traps, see `sha256_digest`.
+    pub const fn ecall_commit_digest() -> u32 {
+        15
+    }
+
     /// The code of success.
     pub const fn code_success() -> u32 {
         0
     }
+
+    /// The exit code of a guest which panicked, the same as for a Rust program.
+    pub const fn code_panic() -> u32 {
+        101
+    }
+}
+
+/// The RAM words held by the memory tables of the circuits, that is the static memory: the
+/// program data, the stack and the heap, and their padding. An access to RAM outside of them
+/// cannot be proven, see `VMState::set_memory_layout`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryLayout {
+    /// Sorted, disjoint and not adjacent.
+    ranges: Vec<Range<Addr>>,
+}
+
+impl MemoryLayout {
+    /// The layout of the words at the byte addresses `addrs`, in any order.
+    pub fn from_addresses(addrs: impl IntoIterator<Item = Addr>) -> Self {
+        let mut addrs = addrs.into_iter().collect::<Vec<_>>();
+        addrs.sort_unstable();
+        let mut ranges: Vec<Range<Addr>> = vec![];
+        for addr in addrs {
+            let word = addr - addr % WORD_SIZE as Addr;
+            match ranges.last_mut() {
+                Some(last) if word <= last.end => {
+                    last.end = last.end.max(word + WORD_SIZE as Addr);
+                }
+                _ => ranges.push(word..word + WORD_SIZE as Addr),
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn ranges(&self) -> &[Range<Addr>] {
+        &self.ranges
+    }
+
+    pub fn contains(&self, addr: Addr) -> bool {
+        let i = self.ranges.partition_point(|range| range.end <= addr);
+        self.ranges
+            .get(i)
+            .is_some_and(|range| range.contains(&addr))
+    }
 }
 
 #[cfg(test)]
@@ -128,4 +275,13 @@ mod tests {
             assert!(!p.is_ram(reg));
         }
     }
+
+    #[test]
+    fn test_memory_layout() {
+        let layout = MemoryLayout::from_addresses([0x108, 0x100, 0x104, 0x200, 0x10c]);
+        assert_eq!(layout.ranges(), &[0x100..0x110, 0x200..0x204]);
+        assert!(layout.contains(0x100) && layout.contains(0x10f) && layout.contains(0x203));
+        assert!(!layout.contains(0xfc) && !layout.contains(0x110) && !layout.contains(0x204));
+        assert!(!MemoryLayout::default().contains(0));
+    }
 }
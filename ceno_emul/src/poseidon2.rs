@@ -0,0 +1,53 @@
+//! The Poseidon2 permutation over the Goldilocks field, computed natively for the ecall
+//! POSEIDON2_PERMUTE, see `Platform::ecall_poseidon2_permute` and `::poseidon::poseidon2`.
+
+use ::poseidon::poseidon2::{WIDTH, poseidon2};
+use goldilocks::{Goldilocks, MODULUS, SmallField};
+
+/// The number of field elements of the state.
+pub const POSEIDON2_WIDTH: usize = WIDTH;
+/// The number of 32-bit words of the state in memory, the low word of each element first.
+pub const POSEIDON2_STATE_WORDS: usize = 2 * POSEIDON2_WIDTH;
+
+/// Apply the permutation to a state of canonical field elements. Return false, leaving `state`
+/// unchanged, if an element is not canonical, that is not less than the modulus.
+pub fn poseidon2_permute(state: &mut [u64; POSEIDON2_WIDTH]) -> bool {
+    if !state.iter().all(|&x| x < MODULUS) {
+        return false;
+    }
+    let output = poseidon2(state.map(Goldilocks::from));
+    *state = output.map(|x| x.to_canonical_u64());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poseidon2_permute() {
+        // The permutation of 0, 1, ..., 11.
+        let mut state = std::array::from_fn(|i| i as u64);
+        assert!(poseidon2_permute(&mut state));
+        assert_eq!(state, [
+            0x5286DD7462C67B85,
+            0xCED2DC81725A0D27,
+            0xEE99234891120E0D,
+            0x16613FE1F932BA29,
+            0x6693FC3D1BF463E4,
+            0x1C1FF4A5A11DAA23,
+            0x948B8BF49EA3AC6D,
+            0x31FA539817ED2376,
+            0x0141D6B3A9A45D17,
+            0x00259EBC5F0AEC9A,
+            0xDD7D12E849B7C312,
+            0x0FE2C1F41ED4FB85,
+        ]);
+
+        // The modulus is not canonical.
+        let mut state = [0; POSEIDON2_WIDTH];
+        state[3] = MODULUS;
+        assert!(!poseidon2_permute(&mut state));
+        assert_eq!(state[3], MODULUS);
+    }
+}
@@ -0,0 +1,228 @@
+//! Counts of the executed instructions, by kind and by pc, to find which parts of a guest
+//! dominate its cycles, and thus its proving cost.
+//!
+//! The instructions of different kinds cost differently to prove, e.g. a division much more than
+//! an addition, so a [`CostModel`] weighs the counts into "prover cycles".
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use anyhow::{Result, anyhow};
+use elf::{ElfBytes, abi::STT_FUNC, endian::LittleEndian};
+
+use crate::{addr::Word, rv32im::InsnKind, tracer::StepRecord};
+
+/// The function symbols of an ELF, to attribute a pc to its function.
+#[derive(Clone, Debug, Default)]
+pub struct Symbols {
+    /// By start address: the end address and the name.
+    functions: BTreeMap<Word, (Word, String)>,
+}
+
+impl Symbols {
+    /// Read the function symbols of `elf`. A stripped ELF has no symbols.
+    pub fn from_elf(elf: &[u8]) -> Result<Self> {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(elf)
+            .map_err(|err| anyhow!("Elf parse error: {err}"))?;
+        let Some((symtab, strtab)) = elf
+            .symbol_table()
+            .map_err(|err| anyhow!("Elf symbol table error: {err}"))?
+        else {
+            return Ok(Self::default());
+        };
+        let functions = symtab
+            .iter()
+            .filter(|sym| sym.st_symtype() == STT_FUNC && sym.st_size > 0)
+            .map(|sym| {
+                let name = strtab
+                    .get(sym.st_name as usize)
+                    .map_err(|err| anyhow!("Elf symbol name error: {err}"))?;
+                let end = sym.st_value + sym.st_size;
+                Ok((sym.st_value as Word, (end as Word, name.to_string())))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { functions })
+    }
+
+    /// The name of the function containing `pc`.
+    pub fn lookup(&self, pc: Word) -> Option<&str> {
+        let (_, (end, name)) = self.functions.range(..=pc).next_back()?;
+        (pc < *end).then_some(name.as_str())
+    }
+}
+
+/// The proving cost of an instruction of each kind, in prover cycles.
+pub trait CostModel {
+    fn cost(&self, kind: InsnKind) -> u64;
+}
+
+impl<F: Fn(InsnKind) -> u64> CostModel for F {
+    fn cost(&self, kind: InsnKind) -> u64 {
+        self(kind)
+    }
+}
+
+/// The costs of some instruction kinds, and a default cost for the others, e.g. as measured
+/// from the circuits by `ZKVMConstraintSystem::cost_table` of ceno_zkvm. The default table costs
+/// one per instruction, so that prover cycles are raw cycles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostTable {
+    costs: BTreeMap<InsnKind, u64>,
+    default_cost: u64,
+}
+
+impl CostTable {
+    pub fn new(default_cost: u64) -> Self {
+        Self {
+            costs: BTreeMap::new(),
+            default_cost,
+        }
+    }
+
+    pub fn set_cost(&mut self, kind: InsnKind, cost: u64) {
+        self.costs.insert(kind, cost);
+    }
+}
+
+impl Default for CostTable {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl CostModel for CostTable {
+    fn cost(&self, kind: InsnKind) -> u64 {
+        self.costs.get(&kind).copied().unwrap_or(self.default_cost)
+    }
+}
+
+/// Counts of executed instructions, by kind and by pc.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub by_kind: BTreeMap<InsnKind, u64>,
+    pub by_pc: HashMap<Word, u64>,
+}
+
+impl Profile {
+    pub fn from_steps<'a>(steps: impl IntoIterator<Item = &'a StepRecord>) -> Self {
+        let mut profile = Self::default();
+        for step in steps {
+            profile.record(step);
+        }
+        profile
+    }
+
+    /// The counts by kind of `steps`, as in [`Self::by_kind`], without counting them by pc.
+    pub fn kind_histogram<'a>(
+        steps: impl IntoIterator<Item = &'a StepRecord>,
+    ) -> BTreeMap<InsnKind, u64> {
+        let mut histogram = BTreeMap::new();
+        for step in steps {
+            *histogram.entry(step.insn().codes().kind).or_default() += 1;
+        }
+        histogram
+    }
+
+    pub fn record(&mut self, step: &StepRecord) {
+        *self.by_kind.entry(step.insn().codes().kind).or_default() += 1;
+        *self.by_pc.entry(step.pc().before.0).or_default() += 1;
+    }
+
+    /// The number of executed instructions.
+    pub fn total(&self) -> u64 {
+        self.by_kind.values().sum()
+    }
+
+    /// The proving cost of the executed instructions.
+    pub fn prover_cycles(&self, model: &dyn CostModel) -> u64 {
+        self.by_kind
+            .iter()
+            .map(|(&kind, &count)| count * model.cost(kind))
+            .sum()
+    }
+
+    /// The histogram of instructions with their proving costs, most costly kinds first.
+    pub fn with_costs<'a>(&'a self, model: &'a dyn CostModel) -> ProfileCosts<'a> {
+        ProfileCosts {
+            profile: self,
+            model,
+        }
+    }
+
+    /// The instruction counts by function, most executed first. The instructions outside of
+    /// the functions of `symbols` are counted by pc, named in hexadecimal.
+    pub fn hotspots(&self, symbols: &Symbols) -> Vec<(String, u64)> {
+        let mut counts = HashMap::<String, u64>::new();
+        for (&pc, &count) in &self.by_pc {
+            let name = symbols
+                .lookup(pc)
+                .map_or_else(|| format!("{pc:#x}"), str::to_string);
+            *counts.entry(name).or_default() += count;
+        }
+        let mut hotspots = counts.into_iter().collect::<Vec<_>>();
+        hotspots.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        hotspots
+    }
+}
+
+/// The instruction histogram, most executed kinds first.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total().max(1);
+        let mut kinds = self.by_kind.iter().collect::<Vec<_>>();
+        kinds.sort_by(|(_, a), (_, b)| b.cmp(a));
+        for (kind, &count) in kinds {
+            writeln!(
+                f,
+                "{:<8} {:>12} {:>6.2}%",
+                kind.to_string(),
+                count,
+                100.0 * count as f64 / total as f64
+            )?;
+        }
+        write!(f, "{:<8} {:>12}", "total", self.total())
+    }
+}
+
+/// A [`Profile`] displayed with the prover cycles of each kind, see [`Profile::with_costs`].
+pub struct ProfileCosts<'a> {
+    profile: &'a Profile,
+    model: &'a dyn CostModel,
+}
+
+impl fmt::Display for ProfileCosts<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.profile.prover_cycles(self.model).max(1);
+        let mut kinds = self
+            .profile
+            .by_kind
+            .iter()
+            .map(|(&kind, &count)| (kind, count, count * self.model.cost(kind)))
+            .collect::<Vec<_>>();
+        kinds.sort_by(|(_, _, a), (_, _, b)| b.cmp(a));
+        writeln!(
+            f,
+            "{:<8} {:>12} {:>16} {:>7}",
+            "kind", "cycles", "prover cycles", ""
+        )?;
+        for (kind, count, cost) in kinds {
+            writeln!(
+                f,
+                "{:<8} {:>12} {:>16} {:>6.2}%",
+                kind.to_string(),
+                count,
+                cost,
+                100.0 * cost as f64 / total as f64
+            )?;
+        }
+        write!(
+            f,
+            "{:<8} {:>12} {:>16}",
+            "total",
+            self.profile.total(),
+            self.profile.prover_cycles(self.model)
+        )
+    }
+}
@@ -17,7 +17,7 @@
 use anyhow::{Result, anyhow};
 use itertools::enumerate;
 use num_derive::ToPrimitive;
-use std::sync::OnceLock;
+use std::{fmt, sync::OnceLock};
 use strum_macros::{Display, EnumIter};
 
 use super::addr::{ByteAddr, RegIdx, WORD_SIZE, Word, WordAddr};
@@ -85,19 +85,32 @@ pub struct Emulator {
     table: &'static FastDecodeTable,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TrapCause {
     InstructionAddressMisaligned,
     InstructionAccessFault,
     IllegalInstruction(u32),
     Breakpoint,
-    LoadAddressMisaligned,
+    LoadAddressMisaligned(ByteAddr),
     LoadAccessFault(ByteAddr),
     StoreAddressMisaligned(ByteAddr),
-    StoreAccessFault,
+    StoreAccessFault(ByteAddr),
     EcallError,
 }
 
+impl TrapCause {
+    /// The data address of a faulting load or store.
+    pub fn address(&self) -> Option<ByteAddr> {
+        match self {
+            TrapCause::LoadAddressMisaligned(addr)
+            | TrapCause::LoadAccessFault(addr)
+            | TrapCause::StoreAddressMisaligned(addr)
+            | TrapCause::StoreAccessFault(addr) => Some(*addr),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct DecodedInstruction {
     insn: u32,
@@ -387,6 +400,34 @@ fn test_isa_table() {
     }
 }
 
+/// The assembly of the instruction, e.g. `lw x3, 4(x10)`, with signed decimal offsets.
+impl fmt::Display for DecodedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let codes = self.codes();
+        let mnemonic = codes.kind.to_string().to_lowercase();
+        let (rd, rs1, rs2) = (self.rd, self.rs1, self.rs2);
+        let imm = self.immediate() as i32;
+        match (codes.kind, codes.format) {
+            (InsnKind::INVALID, _) => write!(f, "unknown {:#010x}", self.insn),
+            (InsnKind::EANY, _) if self.immediate() == 1 => write!(f, "ebreak"),
+            (InsnKind::EANY, _) => write!(f, "ecall"),
+            (InsnKind::SLLI | InsnKind::SRLI | InsnKind::SRAI, _) => {
+                write!(f, "{mnemonic} x{rd}, x{rs1}, {rs2}")
+            }
+            (InsnKind::JALR, _) => write!(f, "{mnemonic} x{rd}, {imm}(x{rs1})"),
+            (_, I) if matches!(codes.category, Load) => {
+                write!(f, "{mnemonic} x{rd}, {imm}(x{rs1})")
+            }
+            (_, R) => write!(f, "{mnemonic} x{rd}, x{rs1}, x{rs2}"),
+            (_, I) => write!(f, "{mnemonic} x{rd}, x{rs1}, {imm}"),
+            (_, S) => write!(f, "{mnemonic} x{rs2}, {imm}(x{rs1})"),
+            (_, B) => write!(f, "{mnemonic} x{rs1}, x{rs2}, {imm}"),
+            (_, U) => write!(f, "{mnemonic} x{rd}, {:#x}", self.immediate() >> 12),
+            (_, J) => write!(f, "{mnemonic} x{rd}, {imm}"),
+        }
+    }
+}
+
 // RISC-V instruction are determined by 3 parts:
 // - Opcode: 7 bits
 // - Func3: 3 bits
@@ -601,34 +642,10 @@ impl Emulator {
                             }
                             MULHSU => (sign_extend_u32(rs1).wrapping_mul(rs2 as i64) >> 32) as u32,
                             MULHU => (((rs1 as u64).wrapping_mul(rs2 as u64)) >> 32) as u32,
-                            DIV => {
-                                if rs2 == 0 {
-                                    u32::MAX
-                                } else {
-                                    ((rs1 as i32).wrapping_div(rs2 as i32)) as u32
-                                }
-                            }
-                            DIVU => {
-                                if rs2 == 0 {
-                                    u32::MAX
-                                } else {
-                                    rs1 / rs2
-                                }
-                            }
-                            REM => {
-                                if rs2 == 0 {
-                                    rs1
-                                } else {
-                                    ((rs1 as i32).wrapping_rem(rs2 as i32)) as u32
-                                }
-                            }
-                            REMU => {
-                                if rs2 == 0 {
-                                    rs1
-                                } else {
-                                    rs1 % rs2
-                                }
-                            }
+                            DIV => DivRem::signed(rs1, rs2).quotient,
+                            DIVU => DivRem::unsigned(rs1, rs2).quotient,
+                            REM => DivRem::signed(rs1, rs2).remainder,
+                            REMU => DivRem::unsigned(rs1, rs2).remainder,
 
                             _ => unreachable!("Illegal compute instruction: {:?}", kind),
                         }
@@ -703,7 +720,7 @@ impl Emulator {
             }
             InsnKind::LH => {
                 if addr.0 & 0x01 != 0 {
-                    return ctx.trap(TrapCause::LoadAddressMisaligned);
+                    return ctx.trap(TrapCause::LoadAddressMisaligned(addr));
                 }
                 let mut out = (data >> shift) & 0xffff;
                 if out & 0x8000 != 0 {
@@ -713,14 +730,14 @@ impl Emulator {
             }
             InsnKind::LW => {
                 if addr.0 & 0x03 != 0 {
-                    return ctx.trap(TrapCause::LoadAddressMisaligned);
+                    return ctx.trap(TrapCause::LoadAddressMisaligned(addr));
                 }
                 data
             }
             InsnKind::LBU => (data >> shift) & 0xff,
             InsnKind::LHU => {
                 if addr.0 & 0x01 != 0 {
-                    return ctx.trap(TrapCause::LoadAddressMisaligned);
+                    return ctx.trap(TrapCause::LoadAddressMisaligned(addr));
                 }
                 (data >> shift) & 0xffff
             }
@@ -742,8 +759,7 @@ impl Emulator {
         let addr = ByteAddr(rs1.wrapping_add(decoded.imm_s()));
         let shift = 8 * (addr.0 & 3);
         if !ctx.check_data_store(addr) {
-            tracing::error!("mstore: addr={:x?},rs1={:x}", addr, rs1);
-            return ctx.trap(TrapCause::StoreAccessFault);
+            return ctx.trap(TrapCause::StoreAccessFault(addr));
         }
         let mut data = ctx.peek_memory(addr.waddr());
         match kind {
@@ -793,3 +809,58 @@ impl Emulator {
 fn sign_extend_u32(x: u32) -> i64 {
     (x as i32) as i64
 }
+
+/// The quotient and remainder of a RV32M division, with the corner cases of the spec: a division
+/// by zero gives a quotient with all bits set and the dividend as remainder, and the signed
+/// overflow `i32::MIN / -1` gives `i32::MIN` and a remainder of zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DivRem {
+    pub quotient: Word,
+    pub remainder: Word,
+    /// Whether the dividend is negative, always false for unsigned divisions.
+    pub dividend_negative: bool,
+    /// Whether the divisor is negative, always false for unsigned divisions.
+    pub divisor_negative: bool,
+    pub divisor_zero: bool,
+    /// Whether the signed division overflows, that is `i32::MIN / -1`.
+    pub overflow: bool,
+}
+
+impl DivRem {
+    /// The division of DIV and REM.
+    pub fn signed(dividend: Word, divisor: Word) -> Self {
+        let (dividend, divisor) = (dividend as i32, divisor as i32);
+        let (quotient, remainder) = if divisor == 0 {
+            (-1, dividend)
+        } else {
+            (
+                dividend.wrapping_div(divisor),
+                dividend.wrapping_rem(divisor),
+            )
+        };
+        DivRem {
+            quotient: quotient as Word,
+            remainder: remainder as Word,
+            dividend_negative: dividend < 0,
+            divisor_negative: divisor < 0,
+            divisor_zero: divisor == 0,
+            overflow: dividend == i32::MIN && divisor == -1,
+        }
+    }
+
+    /// The division of DIVU and REMU.
+    pub fn unsigned(dividend: Word, divisor: Word) -> Self {
+        let (quotient, remainder) = dividend
+            .checked_div(divisor)
+            .zip(dividend.checked_rem(divisor))
+            .unwrap_or((Word::MAX, dividend));
+        DivRem {
+            quotient,
+            remainder,
+            dividend_negative: false,
+            divisor_negative: false,
+            divisor_zero: divisor == 0,
+            overflow: false,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! The group law of the curve secp256k1, `y^2 = x^3 + 7` over the integers modulo
+//! `SECP256K1_P`, computed natively for the ecalls SECP256K1_ADD and SECP256K1_DOUBLE, see
+//! `Platform::ecall_secp256k1_add`. A point is in affine coordinates: `x` then `y`, each as 8
+//! little-endian words.
+
+use crate::{
+    Word,
+    uint256::{
+        UINT256_WORDS, uint256_addmod, uint256_invmod, uint256_lt, uint256_mulmod, uint256_submod,
+    },
+};
+
+/// The prime of the field of secp256k1, `2^256 - 2^32 - 977`.
+pub const SECP256K1_P: [Word; UINT256_WORDS] = [
+    0xFFFF_FC2F,
+    0xFFFF_FFFE,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+    0xFFFF_FFFF,
+];
+
+/// The number of words of a point.
+pub const SECP256K1_POINT_WORDS: usize = 2 * UINT256_WORDS;
+
+/// The coordinates of `point`, or `None` if they are not reduced modulo `SECP256K1_P`.
+fn coordinates(
+    point: &[Word; SECP256K1_POINT_WORDS],
+) -> Option<([Word; UINT256_WORDS], [Word; UINT256_WORDS])> {
+    let x: [Word; UINT256_WORDS] = point[..UINT256_WORDS].try_into().unwrap();
+    let y: [Word; UINT256_WORDS] = point[UINT256_WORDS..].try_into().unwrap();
+    (uint256_lt(&x, &SECP256K1_P) && uint256_lt(&y, &SECP256K1_P)).then_some((x, y))
+}
+
+/// The point of the chord or the tangent of slope `lambda` through `(x1, y1)` and a second point
+/// of abscissa `x2`, negated.
+fn third_point(
+    lambda: &[Word; UINT256_WORDS],
+    (x1, y1): &([Word; UINT256_WORDS], [Word; UINT256_WORDS]),
+    x2: &[Word; UINT256_WORDS],
+) -> [Word; SECP256K1_POINT_WORDS] {
+    let p = &SECP256K1_P;
+    let x3 = uint256_submod(
+        &uint256_mulmod(lambda, lambda, p),
+        &uint256_addmod(x1, x2, p),
+        p,
+    );
+    let y3 = uint256_submod(
+        &uint256_mulmod(lambda, &uint256_submod(x1, &x3, p), p),
+        y1,
+        p,
+    );
+    let mut point = [0; SECP256K1_POINT_WORDS];
+    point[..UINT256_WORDS].copy_from_slice(&x3);
+    point[UINT256_WORDS..].copy_from_slice(&y3);
+    point
+}
+
+/// `p = p + q`, for points of distinct abscissas. Return false, leaving `p` unchanged, if the
+/// abscissas are equal, the sum then being a doubling or the point at infinity, or if a
+/// coordinate is not reduced.
+///
+/// The points are not checked to be on the curve: the result is then that of the same formula.
+pub fn secp256k1_add(
+    p: &mut [Word; SECP256K1_POINT_WORDS],
+    q: &[Word; SECP256K1_POINT_WORDS],
+) -> bool {
+    let (Some(p1), Some((x2, y2))) = (coordinates(p), coordinates(q)) else {
+        return false;
+    };
+    if p1.0 == x2 {
+        return false;
+    }
+    let m = &SECP256K1_P;
+    let lambda = uint256_mulmod(
+        &uint256_submod(&y2, &p1.1, m),
+        &uint256_invmod(&uint256_submod(&x2, &p1.0, m), m),
+        m,
+    );
+    *p = third_point(&lambda, &p1, &x2);
+    true
+}
+
+/// `p = 2p`. Return false, leaving `p` unchanged, if the ordinate is 0, the double then being the
+/// point at infinity, or if a coordinate is not reduced.
+pub fn secp256k1_double(p: &mut [Word; SECP256K1_POINT_WORDS]) -> bool {
+    let Some(p1) = coordinates(p) else {
+        return false;
+    };
+    if p1.1 == [0; UINT256_WORDS] {
+        return false;
+    }
+    let m = &SECP256K1_P;
+    let xx = uint256_mulmod(&p1.0, &p1.0, m);
+    let lambda = uint256_mulmod(
+        &uint256_addmod(&uint256_addmod(&xx, &xx, m), &xx, m),
+        &uint256_invmod(&uint256_addmod(&p1.1, &p1.1, m), m),
+        m,
+    );
+    *p = third_point(&lambda, &p1, &p1.0);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The point of hexadecimal coordinates `x` and `y`.
+    fn point(x: &str, y: &str) -> [Word; SECP256K1_POINT_WORDS] {
+        let mut point = [0; SECP256K1_POINT_WORDS];
+        for (i, coordinate) in [x, y].into_iter().enumerate() {
+            for j in 0..UINT256_WORDS {
+                let end = coordinate.len() - 8 * j;
+                point[i * UINT256_WORDS + j] =
+                    Word::from_str_radix(&coordinate[end - 8..end], 16).unwrap();
+            }
+        }
+        point
+    }
+
+    #[test]
+    fn test_secp256k1_multiples() {
+        let g = point(
+            "79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+            "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+        );
+        let g2 = point(
+            "C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5",
+            "1AE168FEA63DC339A3C58419466CEAEEF7F632653266D0E1236431A950CFE52A",
+        );
+        let g3 = point(
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "388F7B0F632DE8140FE337E62A37F3566500A99934C2231B6CB9FD7584B8E672",
+        );
+
+        let mut p = g;
+        assert!(secp256k1_double(&mut p));
+        assert_eq!(p, g2);
+        assert!(secp256k1_add(&mut p, &g));
+        assert_eq!(p, g3);
+
+        // G + G is a doubling, which the addition rejects.
+        let mut p = g;
+        assert!(!secp256k1_add(&mut p, &g));
+        assert_eq!(p, g);
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    addr::{Addr, Cycle, Word, WordAddr},
+    tracer::StepRecord,
+    vm_state::VMState,
+};
+
+/// The machine state between two segments of an execution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SegmentBoundary {
+    /// The pc of the next instruction, or 0 after halting.
+    pub pc: Word,
+    /// The cycle of the next instruction.
+    pub cycle: Cycle,
+    /// A digest of the registers and of the memory, see [`state_digest`].
+    pub state_digest: StateDigest,
+}
+
+/// A chunk of an execution, with everything needed to prove it on its own.
+///
+/// The steps are rebased on the start of the segment: accesses made by earlier segments look like
+/// initialization, that is `previous_cycle = 0`, and the values at the start of the segment play
+/// the role of the initial memory.
+#[derive(Clone, Debug)]
+pub struct Segment {
+    pub start: SegmentBoundary,
+    pub end: SegmentBoundary,
+    /// The registers at the start of the segment, indexed by `RegIdx`.
+    pub init_registers: [Word; VMState::REG_COUNT],
+    /// The memory at the start of the segment. Missing words are zero.
+    pub init_memory: HashMap<WordAddr, Word>,
+    pub steps: Vec<StepRecord>,
+    /// The addresses accessed in this segment, and the cycle when they were last accessed.
+    pub final_accesses: HashMap<WordAddr, Cycle>,
+    /// Whether the execution halted in this segment.
+    pub halted: bool,
+    pub report: SegmentReport,
+}
+
+/// The size in bytes of the memory pages of a [`SegmentReport`].
+pub const PAGE_SIZE: Addr = 1 << 12;
+
+/// The parts of the machine state which a segment reads and changes, to plan the proof of a
+/// segment and to stitch consecutive segments together.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentReport {
+    /// The start addresses of the memory pages of [`PAGE_SIZE`] bytes which the loads and stores
+    /// of the segment access.
+    pub touched_pages: BTreeSet<Addr>,
+    /// The [`register_digest`] of the registers at the start of the segment.
+    pub registers_in: StateDigest,
+    /// The [`register_digest`] of the registers at the end of the segment, which is the
+    /// `registers_in` of the next segment.
+    pub registers_out: StateDigest,
+}
+
+impl SegmentReport {
+    pub fn new(steps: &[StepRecord], init_registers: &[Word], final_registers: &[Word]) -> Self {
+        let touched_pages = steps
+            .iter()
+            .flat_map(|step| step.memory_ops())
+            .map(|op| {
+                let addr = Addr::from(op.addr);
+                addr - addr % PAGE_SIZE
+            })
+            .collect();
+        SegmentReport {
+            touched_pages,
+            registers_in: register_digest(init_registers),
+            registers_out: register_digest(final_registers),
+        }
+    }
+}
+
+/// A Blake3 digest of machine state, the same across builds and hosts.
+pub type StateDigest = [u8; 32];
+
+/// The digest of the register file: the Blake3 hash of the registers as little-endian words.
+pub fn register_digest(registers: &[Word]) -> StateDigest {
+    let mut hasher = blake3::Hasher::new();
+    hash_words(&mut hasher, registers);
+    *hasher.finalize().as_bytes()
+}
+
+/// The digest of the registers and of the non-zero memory words: the Blake3 hash of the
+/// registers, then the address and value of each word by increasing address, all
+/// as little-endian words.
+/// It is not a commitment, but it lets the segments of an execution be matched to each other.
+pub fn state_digest(registers: &[Word], memory: &HashMap<WordAddr, Word>) -> StateDigest {
+    let mut hasher = blake3::Hasher::new();
+    hash_words(&mut hasher, registers);
+    let mut words = memory
+        .iter()
+        .filter(|(_, value)| **value != 0)
+        .map(|(addr, value)| (Addr::from(*addr), *value))
+        .collect::<Vec<_>>();
+    words.sort_unstable();
+    for (addr, value) in words {
+        hash_words(&mut hasher, &[addr, value]);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+fn hash_words(hasher: &mut blake3::Hasher, words: &[Word]) {
+    for word in words {
+        hasher.update(&word.to_le_bytes());
+    }
+}
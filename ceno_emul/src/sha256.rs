@@ -0,0 +1,126 @@
+//! The SHA-256 compression function, computed natively for the ecall SHA256, see
+//! `Platform::ecall_sha256`.
+
+use crate::addr::Word;
+
+/// The number of words of the state of SHA-256.
+pub const SHA256_STATE_WORDS: usize = 8;
+/// The number of words of a block of SHA-256.
+pub const SHA256_BLOCK_WORDS: usize = 16;
+/// The number of rounds of the compression function, one per word of the message schedule.
+pub const SHA256_ROUNDS: usize = 64;
+
+/// The round constants.
+pub const SHA256_K: [Word; SHA256_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The initial state, before the first block of a message.
+pub const SHA256_INIT: [Word; SHA256_STATE_WORDS] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The message schedule of a block: the block itself, followed by the words derived from it.
+pub fn sha256_schedule(block: &[Word; SHA256_BLOCK_WORDS]) -> [Word; SHA256_ROUNDS] {
+    let mut w = [0; SHA256_ROUNDS];
+    w[..SHA256_BLOCK_WORDS].copy_from_slice(block);
+    for t in SHA256_BLOCK_WORDS..SHA256_ROUNDS {
+        let s0 = w[t - 15].rotate_right(7) ^ w[t - 15].rotate_right(18) ^ (w[t - 15] >> 3);
+        let s1 = w[t - 2].rotate_right(17) ^ w[t - 2].rotate_right(19) ^ (w[t - 2] >> 10);
+        w[t] = s1
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 16]);
+    }
+    w
+}
+
+/// Update `state` with one block of a message.
+pub fn sha256_compress(state: &mut [Word; SHA256_STATE_WORDS], block: &[Word; SHA256_BLOCK_WORDS]) {
+    let w = sha256_schedule(block);
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..SHA256_ROUNDS {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[t])
+            .wrapping_add(w[t]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        (h, g, f, e) = (g, f, e, d.wrapping_add(t1));
+        (d, c, b, a) = (c, b, a, t1.wrapping_add(t2));
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+/// The SHA-256 digest of `data`, as the words of the final state. Its bytes are the big-endian
+/// bytes of the words.
+pub fn sha256_digest(data: &[u8]) -> [Word; SHA256_STATE_WORDS] {
+    // The padding: a one bit, zeros, and the length in bits, to a whole number of blocks.
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    let mut state = SHA256_INIT;
+    for block in padded.chunks_exact(64) {
+        let block = std::array::from_fn(|t| {
+            Word::from_be_bytes(block[4 * t..4 * t + 4].try_into().unwrap())
+        });
+        sha256_compress(&mut state, &block);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_compress_abc() {
+        // The single padded block of the message "abc".
+        let mut block = [0; SHA256_BLOCK_WORDS];
+        block[0] = 0x61626380;
+        block[15] = 24;
+        let mut state = SHA256_INIT;
+        sha256_compress(&mut state, &block);
+        assert_eq!(state, [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ]);
+    }
+
+    #[test]
+    fn test_sha256_digest() {
+        assert_eq!(sha256_digest(b"abc"), [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ]);
+        // two blocks once padded
+        assert_eq!(
+            sha256_digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            [
+                0x248d6a61, 0xd20638b8, 0xe5c02693, 0x0c3e6039, 0xa33ce459, 0x64ff2167, 0xf6ecedd4,
+                0x19db06c1,
+            ]
+        );
+        assert_eq!(sha256_digest(b""), [
+            0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b,
+            0x7852b855,
+        ]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, bail};
+
+use crate::{
+    addr::{Cycle, Word},
+    platform::Platform,
+};
+
+/// The host side of the environment calls of a guest, see `VMState::set_syscall_handler`. The
+/// ecalls READ_HINT, COMMIT, RANDOM and CLOCK are served by the VM itself, see
+/// `VMState::write_hints`, `VMState::committed_output` and `VMState::set_entropy_seed`, the
+/// handler only setting the time of CLOCK. So are the precompiles SHA256, KECCAK_PERMUTE,
+/// UINT256_ADDMOD, UINT256_MULMOD, SECP256K1_ADD, SECP256K1_DOUBLE, ED25519_ADD,
+/// POSEIDON2_PERMUTE and COMMIT_DIGEST, see `Platform::ecall_sha256`,
+/// `Platform::ecall_keccak_permute`, `Platform::ecall_uint256_addmod`,
+/// `Platform::ecall_secp256k1_add`, `Platform::ecall_ed25519_add`,
+/// `Platform::ecall_poseidon2_permute` and `Platform::ecall_commit_digest`.
+///
+/// A syscall takes the address of a word in memory in `a0`, which it reads or writes. Its step
+/// then has the shape of every ecall: reads of `t0` and `a0`, a write to `x0`, and one memory
+/// access, so that the ecall circuits can consume it.
+pub trait SyscallHandler {
+    /// `Platform::ecall_write`: output the bytes of the guest to the file descriptor `fd`, e.g.
+    /// `Platform::FD_STDOUT`, in `a1`. The bytes are stored at `a0` as a length in bytes followed
+    /// by the bytes. The length word is the memory access of the step; `fd` and the bytes are
+    /// read without being recorded, as they do not affect the state of the guest.
+    fn write(&mut self, fd: u32, bytes: &[u8]) -> Result<()>;
+
+    /// `Platform::ecall_halt`: the guest exits with `exit_code`.
+    fn exit(&mut self, _exit_code: u32) {}
+
+    /// `Platform::ecall_clock`: the time for the guest at `cycle`, stored at `a0`. The VM keeps
+    /// the clock monotonic: a time earlier than the previous one reads as the previous one.
+    fn clock(&mut self, cycle: Cycle) -> Word {
+        cycle as Word
+    }
+}
+
+/// The output of a guest, captured by the handler [`CapturedOutput`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Output {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// The exit code, once the guest has halted.
+    pub exit_code: Option<u32>,
+}
+
+/// A syscall handler capturing the standard output and error of a guest in buffers, for tests.
+/// The clones of a handler share its buffers, so a test keeps one to inspect the output of the
+/// VM running the other, see `VMState::capture_output`.
+#[derive(Clone, Debug, Default)]
+pub struct CapturedOutput {
+    output: Arc<Mutex<Output>>,
+}
+
+impl CapturedOutput {
+    /// The output captured so far.
+    pub fn output(&self) -> Output {
+        self.output.lock().unwrap().clone()
+    }
+
+    pub fn stdout(&self) -> String {
+        String::from_utf8_lossy(&self.output.lock().unwrap().stdout).into_owned()
+    }
+
+    pub fn stderr(&self) -> String {
+        String::from_utf8_lossy(&self.output.lock().unwrap().stderr).into_owned()
+    }
+
+    /// Panic, showing both outputs, unless the standard output is `expected`.
+    #[track_caller]
+    pub fn assert_stdout(&self, expected: &str) {
+        assert_eq!(
+            self.stdout(),
+            expected,
+            "unexpected stdout of the guest, whose stderr is {:?}",
+            self.stderr()
+        );
+    }
+
+    /// Panic, showing both outputs, unless the standard error is `expected`.
+    #[track_caller]
+    pub fn assert_stderr(&self, expected: &str) {
+        assert_eq!(
+            self.stderr(),
+            expected,
+            "unexpected stderr of the guest, whose stdout is {:?}",
+            self.stdout()
+        );
+    }
+}
+
+impl SyscallHandler for CapturedOutput {
+    fn write(&mut self, fd: u32, bytes: &[u8]) -> Result<()> {
+        let mut output = self.output.lock().unwrap();
+        match fd {
+            Platform::FD_STDOUT => output.stdout.extend_from_slice(bytes),
+            Platform::FD_STDERR => output.stderr.extend_from_slice(bytes),
+            _ => bail!("write to the unknown file descriptor {fd}"),
+        }
+        Ok(())
+    }
+
+    fn exit(&mut self, exit_code: u32) {
+        self.output.lock().unwrap().exit_code = Some(exit_code);
+    }
+}
@@ -0,0 +1,84 @@
+//! Export and import of execution traces, for external analyzers, visualizers and fuzzers.
+//!
+//! A trace is written as JSON Lines: one [`TraceBatch`] object per line, e.g. one per batch of
+//! `VMState::iter_batches`, so that long traces are streamed. A batch is
+//!
+//! ```json
+//! {"version": 2, "steps": [<step>, ...]}
+//! ```
+//!
+//! and a step, see `StepRecord`, is
+//!
+//! ```json
+//! {
+//!   "cycle": 4,
+//!   "pc": {"before": 536870912, "after": 536870916},
+//!   "insn_code": 10485907,
+//!   "rs1": {"addr": 0, "value": 0, "previous_cycle": 0},
+//!   "rs2": null,
+//!   "rd": {"addr": 64, "value": {"before": 0, "after": 10}, "previous_cycle": 0},
+//!   "memory_op": null,
+//!   "syscall_ops": []
+//! }
+//! ```
+//!
+//! with byte addresses for `pc`, and word addresses (byte addresses divided by 4) for `addr`.
+//! Registers are at the word addresses of `Platform::register_vma`. The sub-records are `null`
+//! for the operations the instruction does not make. `syscall_ops` lists the memory accesses of
+//! a precompile ecall, e.g. SHA256 or KECCAK_PERMUTE, and is empty for the other instructions.
+//!
+//! The types derive `serde` traits, so that other formats, e.g. CBOR, can be used with the
+//! corresponding `serde` crate.
+
+use std::io::{BufRead, Write};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::tracer::StepRecord;
+
+/// Version of the trace schema, bumped on any change to the serialized types.
+pub const TRACE_FORMAT_VERSION: u32 = 2;
+
+/// A line of an exported trace.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceBatch {
+    pub version: u32,
+    pub steps: Vec<StepRecord>,
+}
+
+/// A [`TraceBatch`] borrowing its steps, to write them without a copy.
+#[derive(Serialize)]
+struct TraceBatchRef<'a> {
+    version: u32,
+    steps: &'a [StepRecord],
+}
+
+/// Append `steps` to the trace written to `writer`, as one batch.
+pub fn write_trace_batch(mut writer: impl Write, steps: &[StepRecord]) -> Result<()> {
+    let batch = TraceBatchRef {
+        version: TRACE_FORMAT_VERSION,
+        steps,
+    };
+    serde_json::to_writer(&mut writer, &batch)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Read the batches of a trace written by [`write_trace_batch`], in order.
+pub fn read_trace_batches(reader: impl BufRead) -> impl Iterator<Item = Result<Vec<StepRecord>>> {
+    reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| {
+            let batch: TraceBatch = serde_json::from_str(&line?)?;
+            if batch.version != TRACE_FORMAT_VERSION {
+                return Err(anyhow!(
+                    "trace format version {} is not supported, expected {}",
+                    batch.version,
+                    TRACE_FORMAT_VERSION
+                ));
+            }
+            Ok(batch.steps)
+        })
+}
@@ -1,10 +1,12 @@
 use std::{collections::HashMap, fmt, mem};
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     CENO_PLATFORM, InsnKind, PC_STEP_SIZE, Platform,
     addr::{ByteAddr, Cycle, RegIdx, Word, WordAddr},
     encode_rv32,
-    rv32im::DecodedInstruction,
+    rv32im::{DecodedInstruction, DivRem},
 };
 
 /// An instruction and its context in an execution trace. That is concrete values of registers and memory.
@@ -18,7 +20,7 @@ use crate::{
 /// - Any of `rs1 / rs2 / rd` **may be `x0`**. The trace handles this like any register, including the value that was _supposed_ to be stored. The circuits must handle this case: either **store `0` or skip `x0` operations**.
 ///
 /// - Any pair of `rs1 / rs2 / rd` **may be the same**. Then, one op will point to the other op in the same instruction but a different subcycle. The circuits may follow the operations **without special handling** of repeated registers.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StepRecord {
     cycle: Cycle,
     pc: Change<ByteAddr>,
@@ -30,9 +32,13 @@ pub struct StepRecord {
     rd: Option<WriteOp>,
 
     memory_op: Option<WriteOp>,
+
+    /// The memory accesses of a precompile ecall, in the order of its circuit, all at the
+    /// memory subcycle.
+    syscall_ops: Vec<WriteOp>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MemOp<T> {
     /// Virtual Memory Address.
     /// For registers, get it from `Platform::register_vma(idx)`.
@@ -242,6 +248,7 @@ impl StepRecord {
                 previous_cycle,
             }),
             memory_op,
+            syscall_ops: vec![],
         }
     }
 
@@ -279,12 +286,56 @@ impl StepRecord {
         self.memory_op.clone()
     }
 
+    /// The memory accesses of a precompile ecall, e.g. `Platform::ecall_sha256`.
+    pub fn syscall_ops(&self) -> &[WriteOp] {
+        &self.syscall_ops
+    }
+
+    /// All the memory accesses of the step: `memory_op`, then `syscall_ops`.
+    pub fn memory_ops(&self) -> impl Iterator<Item = WriteOp> + '_ {
+        self.memory_op.iter().chain(&self.syscall_ops).cloned()
+    }
+
+    /// The quotient, remainder and sign flags of a DIV, DIVU, REM or REMU instruction, which
+    /// the circuits assign beside the result in `rd`.
+    pub fn div_rem(&self) -> Option<DivRem> {
+        let (rs1, rs2) = (self.rs1.as_ref()?.value, self.rs2.as_ref()?.value);
+        match self.insn().codes().kind {
+            InsnKind::DIV | InsnKind::REM => Some(DivRem::signed(rs1, rs2)),
+            InsnKind::DIVU | InsnKind::REMU => Some(DivRem::unsigned(rs1, rs2)),
+            _ => None,
+        }
+    }
+
     pub fn is_busy_loop(&self) -> bool {
         self.pc.before == self.pc.after
     }
+
+    /// Treat accesses made before `cycle` as initialization, that is `previous_cycle = 0`.
+    /// This makes the step look as if the execution started at `cycle`.
+    pub(crate) fn forget_accesses_before(&mut self, cycle: Cycle) {
+        let forget = |previous_cycle: &mut Cycle| {
+            if *previous_cycle < cycle {
+                *previous_cycle = 0;
+            }
+        };
+        self.rs1
+            .iter_mut()
+            .for_each(|op| forget(&mut op.previous_cycle));
+        self.rs2
+            .iter_mut()
+            .for_each(|op| forget(&mut op.previous_cycle));
+        self.rd
+            .iter_mut()
+            .for_each(|op| forget(&mut op.previous_cycle));
+        self.memory_op
+            .iter_mut()
+            .chain(&mut self.syscall_ops)
+            .for_each(|op| forget(&mut op.previous_cycle));
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tracer {
     record: StepRecord,
 
@@ -386,6 +437,16 @@ impl Tracer {
         });
     }
 
+    /// Record a memory access of a precompile ecall, see `StepRecord::syscall_ops`.
+    pub fn syscall_memory(&mut self, addr: WordAddr, value: Change<Word>) {
+        let previous_cycle = self.track_access(addr, Self::SUBCYCLE_MEM);
+        self.record.syscall_ops.push(WriteOp {
+            addr,
+            value,
+            previous_cycle,
+        });
+    }
+
     /// - Return the cycle when an address was last accessed.
     /// - Return 0 if this is the first access.
     /// - Record the current instruction as the origin of the latest access.
@@ -407,7 +468,7 @@ impl Tracer {
     }
 }
 
-#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Change<T> {
     pub before: T,
     pub after: T,
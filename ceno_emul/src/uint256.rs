@@ -0,0 +1,167 @@
+//! 256-bit modular arithmetic, computed natively for the ecalls UINT256_ADDMOD and
+//! UINT256_MULMOD, see `Platform::ecall_uint256_addmod`, and for the curve operations of
+//! `secp256k1`. Numbers are little-endian words.
+
+use crate::Word;
+
+/// The number of words of a 256-bit number.
+pub const UINT256_WORDS: usize = 8;
+
+/// Whether `x < y`.
+pub fn uint256_lt(x: &[Word; UINT256_WORDS], y: &[Word; UINT256_WORDS]) -> bool {
+    x.iter().rev().lt(y.iter().rev())
+}
+
+/// The 512-bit sum `x + y`.
+pub fn uint256_add_wide(
+    x: &[Word; UINT256_WORDS],
+    y: &[Word; UINT256_WORDS],
+) -> [Word; 2 * UINT256_WORDS] {
+    let mut sum = [0; 2 * UINT256_WORDS];
+    let mut carry = 0;
+    for i in 0..UINT256_WORDS {
+        let s = x[i] as u64 + y[i] as u64 + carry;
+        sum[i] = s as Word;
+        carry = s >> 32;
+    }
+    sum[UINT256_WORDS] = carry as Word;
+    sum
+}
+
+/// The 512-bit product `x * y`.
+pub fn uint256_mul_wide(
+    x: &[Word; UINT256_WORDS],
+    y: &[Word; UINT256_WORDS],
+) -> [Word; 2 * UINT256_WORDS] {
+    let mut product = [0; 2 * UINT256_WORDS];
+    for i in 0..UINT256_WORDS {
+        let mut carry = 0;
+        for j in 0..UINT256_WORDS {
+            let p = x[i] as u64 * y[j] as u64 + product[i + j] as u64 + carry;
+            product[i + j] = p as Word;
+            carry = p >> 32;
+        }
+        product[i + UINT256_WORDS] = carry as Word;
+    }
+    product
+}
+
+/// The quotient and the remainder of `n` by a non-zero `m`, the quotient being truncated to 256
+/// bits, which holds it when `n < m * 2^256`.
+pub fn uint256_divrem(
+    n: &[Word; 2 * UINT256_WORDS],
+    m: &[Word; UINT256_WORDS],
+) -> ([Word; UINT256_WORDS], [Word; UINT256_WORDS]) {
+    assert!(m.iter().any(|&word| word != 0), "division by zero");
+    // Long division, one bit at a time: the remainder stays below `2m`, within 257 bits.
+    let mut q = [0; UINT256_WORDS];
+    let mut r = [0; UINT256_WORDS];
+    for bit in (0..64 * UINT256_WORDS).rev() {
+        let top = r[UINT256_WORDS - 1] >> 31;
+        for i in (1..UINT256_WORDS).rev() {
+            r[i] = r[i] << 1 | r[i - 1] >> 31;
+        }
+        r[0] = r[0] << 1 | (n[bit / 32] >> (bit % 32)) & 1;
+        if top == 1 || !uint256_lt(&r, m) {
+            // Subtract `m`, modulo 2^256 since the top bit is cleared by the borrow.
+            let mut borrow = 0;
+            for i in 0..UINT256_WORDS {
+                let d = r[i] as i64 - m[i] as i64 - borrow;
+                r[i] = d as Word;
+                borrow = (d < 0) as i64;
+            }
+            if bit < 32 * UINT256_WORDS {
+                q[bit / 32] |= 1 << (bit % 32);
+            }
+        }
+    }
+    (q, r)
+}
+
+/// `(x + y) mod m`.
+pub fn uint256_addmod(
+    x: &[Word; UINT256_WORDS],
+    y: &[Word; UINT256_WORDS],
+    m: &[Word; UINT256_WORDS],
+) -> [Word; UINT256_WORDS] {
+    uint256_divrem(&uint256_add_wide(x, y), m).1
+}
+
+/// `(x - y) mod m`, for a reduced `y < m`.
+pub fn uint256_submod(
+    x: &[Word; UINT256_WORDS],
+    y: &[Word; UINT256_WORDS],
+    m: &[Word; UINT256_WORDS],
+) -> [Word; UINT256_WORDS] {
+    // Add `m - y`, which does not borrow.
+    let mut neg_y = [0; UINT256_WORDS];
+    let mut borrow = 0;
+    for i in 0..UINT256_WORDS {
+        let d = m[i] as i64 - y[i] as i64 - borrow;
+        neg_y[i] = d as Word;
+        borrow = (d < 0) as i64;
+    }
+    uint256_addmod(x, &neg_y, m)
+}
+
+/// `(x * y) mod m`.
+pub fn uint256_mulmod(
+    x: &[Word; UINT256_WORDS],
+    y: &[Word; UINT256_WORDS],
+    m: &[Word; UINT256_WORDS],
+) -> [Word; UINT256_WORDS] {
+    uint256_divrem(&uint256_mul_wide(x, y), m).1
+}
+
+/// The inverse of `x` modulo a prime `m`, as `x^(m - 2)`, or 0 when `x` is 0 modulo `m`.
+pub fn uint256_invmod(
+    x: &[Word; UINT256_WORDS],
+    m: &[Word; UINT256_WORDS],
+) -> [Word; UINT256_WORDS] {
+    let mut exponent = *m;
+    let mut borrow = 2;
+    for word in exponent.iter_mut() {
+        let (d, b) = word.overflowing_sub(borrow);
+        *word = d;
+        borrow = b as Word;
+    }
+    let base = uint256_divrem(&uint256_add_wide(x, &[0; UINT256_WORDS]), m).1;
+    let mut power = [0; UINT256_WORDS];
+    power[0] = 1;
+    for bit in (0..32 * UINT256_WORDS).rev() {
+        power = uint256_mulmod(&power, &power, m);
+        if (exponent[bit / 32] >> (bit % 32)) & 1 == 1 {
+            power = uint256_mulmod(&power, &base, m);
+        }
+    }
+    power
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uint256_mulmod() {
+        // The prime of the field of secp256k1, 2^256 - 2^32 - 977.
+        let mut p = [Word::MAX; UINT256_WORDS];
+        p[0] = 0xFFFF_FC2F;
+        p[1] = 0xFFFF_FFFE;
+        // 2^255 * 2 = 2^256 = 2^32 + 977 modulo p.
+        let mut x = [0; UINT256_WORDS];
+        x[7] = 1 << 31;
+        let mut y = [0; UINT256_WORDS];
+        y[0] = 2;
+        let (q, r) = uint256_divrem(&uint256_mul_wide(&x, &y), &p);
+        assert_eq!(r, [977, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(q, [1, 0, 0, 0, 0, 0, 0, 0]);
+
+        // (p - 1) + (p - 1) = p - 2 modulo p.
+        let mut minus_one = p;
+        minus_one[0] -= 1;
+        let (q, r) = uint256_divrem(&uint256_add_wide(&minus_one, &minus_one), &p);
+        let mut minus_two = p;
+        minus_two[0] -= 2;
+        assert_eq!((q, r), ([1, 0, 0, 0, 0, 0, 0, 0], minus_two));
+    }
+}
@@ -1,12 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
 
 use super::rv32im::EmuContext;
 use crate::{
     PC_STEP_SIZE, Program, WORD_SIZE,
-    addr::{ByteAddr, RegIdx, Word, WordAddr},
-    platform::Platform,
+    addr::{ByteAddr, Cycle, RegIdx, Word, WordAddr},
+    crash::{CrashReport, CrashReportConfig, GuestPanic, memory_window},
+    ed25519::{ED25519_POINT_WORDS, ed25519_add},
+    keccak::{KECCAK_LANES, KECCAK_STATE_WORDS, keccak_f},
+    platform::{MemoryLayout, Platform},
+    poseidon2::{POSEIDON2_STATE_WORDS, POSEIDON2_WIDTH, poseidon2_permute},
     rv32im::{DecodedInstruction, Emulator, TrapCause},
+    secp256k1::{SECP256K1_POINT_WORDS, secp256k1_add, secp256k1_double},
+    segment::{Segment, SegmentBoundary, SegmentReport, state_digest},
+    sha256::{SHA256_BLOCK_WORDS, SHA256_STATE_WORDS, sha256_compress, sha256_digest},
+    syscalls::{CapturedOutput, SyscallHandler},
     tracer::{Change, StepRecord, Tracer},
+    uint256::{UINT256_WORDS, uint256_add_wide, uint256_divrem, uint256_lt, uint256_mul_wide},
 };
 use anyhow::{Result, anyhow};
 use std::{iter::from_fn, ops::Deref, sync::Arc};
@@ -22,6 +33,48 @@ pub struct VMState {
     // Termination.
     halted: bool,
     tracer: Tracer,
+    syscalls: Option<Box<dyn SyscallHandler + Send>>,
+    hints: VecDeque<u8>,
+    output: Vec<u8>,
+    panic: Option<GuestPanic>,
+    /// The state of the generator of RANDOM.
+    entropy: u64,
+    /// The last time read by CLOCK.
+    clock: Word,
+    max_cycles: Option<Cycle>,
+    memory_layout: Option<MemoryLayout>,
+    crash_report_config: CrashReportConfig,
+    /// The pcs of the last executed instructions, for crash reports.
+    recent_pcs: VecDeque<Word>,
+}
+
+/// A copy of the machine state, to resume an execution from it, see [`VMState::snapshot`]. It
+/// serializes with its program, to resume in another process.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    program: Arc<Program>,
+    pc: Word,
+    memory: HashMap<WordAddr, Word>,
+    registers: [Word; VMState::REG_COUNT],
+    halted: bool,
+    tracer: Tracer,
+    hints: VecDeque<u8>,
+    output: Vec<u8>,
+    panic: Option<GuestPanic>,
+    entropy: u64,
+    clock: Word,
+}
+
+impl Snapshot {
+    /// The pc of the next instruction.
+    pub fn pc(&self) -> Word {
+        self.pc
+    }
+
+    /// The cycle of the next instruction.
+    pub fn cycle(&self) -> Cycle {
+        self.tracer.cycle()
+    }
 }
 
 impl VMState {
@@ -41,6 +94,16 @@ impl VMState {
             registers: [0; VMState::REG_COUNT],
             halted: false,
             tracer: Tracer::new(),
+            syscalls: None,
+            hints: VecDeque::new(),
+            output: Vec::new(),
+            panic: None,
+            entropy: 0,
+            clock: 0,
+            max_cycles: None,
+            memory_layout: None,
+            crash_report_config: CrashReportConfig::default(),
+            recent_pcs: VecDeque::new(),
         };
 
         // init memory from program.image
@@ -56,10 +119,156 @@ impl VMState {
         Ok(Self::new(platform, program))
     }
 
+    /// Handle the syscalls of the guest with `handler`. Without a handler, an ecall other than
+    /// HALT traps, or is ignored under `Platform::unsafe_ecall_nop`.
+    pub fn set_syscall_handler(&mut self, handler: Box<dyn SyscallHandler + Send>) {
+        self.syscalls = Some(handler);
+    }
+
+    /// Capture the standard output and error of the guest, replacing the syscall handler.
+    pub fn capture_output(&mut self) -> CapturedOutput {
+        let captured = CapturedOutput::default();
+        self.set_syscall_handler(Box::new(captured.clone()));
+        captured
+    }
+
+    /// Append `bytes` to the hint tape, which the guest reads a word at a time, in order, with
+    /// the ecall READ_HINT. Hints are advice from the prover: they only reach the guest memory,
+    /// a private witness, so the guest must check whatever it relies on.
+    pub fn write_hints(&mut self, bytes: &[u8]) {
+        self.hints.extend(bytes);
+    }
+
+    /// Store the public input of the guest at the start of `Platform::public_io`, as its length
+    /// in bytes followed by the bytes, zero-padded to a word.
+    pub fn write_public_input(&mut self, bytes: &[u8]) -> Result<()> {
+        let words = bytes.len().div_ceil(WORD_SIZE);
+        if 1 + words > self.platform.public_io.len() / WORD_SIZE {
+            return Err(anyhow!(
+                "public input of {} bytes does not fit in the public IO",
+                bytes.len()
+            ));
+        }
+        let start = ByteAddr(self.platform.public_io.start).waddr();
+        self.init_memory(start, bytes.len() as Word);
+        for (i, chunk) in bytes.chunks(WORD_SIZE).enumerate() {
+            let mut word = [0; WORD_SIZE];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.init_memory(start + (1 + i), Word::from_le_bytes(word));
+        }
+        Ok(())
+    }
+
+    /// Seed the bytes which the guest draws with the ecall RANDOM, a word at a time. They are
+    /// pseudo-random, not secret: a given seed always gives the same bytes, so that an execution
+    /// replays identically for witness generation. The seed is 0 by default.
+    pub fn set_entropy_seed(&mut self, seed: u64) {
+        self.entropy = seed;
+    }
+
+    /// The bytes committed by the guest with the ecall COMMIT, in order. Their SHA-256 digest, as
+    /// attested by the guest with the ecall COMMIT_DIGEST, is a public value of the proof.
+    pub fn committed_output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// The panic reported by the guest with the ecall PANIC, if it panicked. The guest then halts
+    /// with the exit code `Platform::code_panic`.
+    pub fn guest_panic(&self) -> Option<&GuestPanic> {
+        self.panic.as_ref()
+    }
+
     pub fn halted(&self) -> bool {
         self.halted
     }
 
+    /// Pause the execution before the first instruction at or after `max_cycles`: the steps
+    /// stop there without halting, and resume once the limit is raised or removed. The limit
+    /// applies to all the ways of running the VM, e.g. `iter_until_halt` and `next_segment`.
+    pub fn set_max_cycles(&mut self, max_cycles: Option<Cycle>) {
+        self.max_cycles = max_cycles;
+    }
+
+    /// Fault on the accesses to RAM outside of `layout`, which the circuits cannot prove,
+    /// instead of failing later to prove them. Without a layout, all of `Platform::ram` is
+    /// accessible.
+    pub fn set_memory_layout(&mut self, layout: MemoryLayout) {
+        self.memory_layout = Some(layout);
+    }
+
+    /// Choose what the [`CrashReport`] of a trap includes.
+    pub fn set_crash_report_config(&mut self, config: CrashReportConfig) {
+        // keep the most recent pcs
+        let excess = self.recent_pcs.len().saturating_sub(config.history);
+        self.recent_pcs.drain(..excess);
+        self.crash_report_config = config;
+    }
+
+    /// Whether the data at `addr` is in the memory layout, if it is in RAM.
+    fn in_memory_layout(&self, addr: ByteAddr) -> bool {
+        !self.platform.is_ram(addr.0)
+            || self
+                .memory_layout
+                .as_ref()
+                .is_none_or(|layout| layout.contains(addr.0))
+    }
+
+    /// Whether the execution is paused by `set_max_cycles`.
+    pub fn paused(&self) -> bool {
+        !self.halted
+            && self
+                .max_cycles
+                .is_some_and(|max_cycles| self.tracer.cycle() >= max_cycles)
+    }
+
+    /// Capture the machine state: the registers, the pc, the memory, the cycle and the access
+    /// history of the tracer, what remains of the hint tape, and the state of RANDOM and CLOCK.
+    /// The syscall handler is not part of it.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            program: self.program.clone(),
+            pc: self.pc,
+            memory: self.memory.clone(),
+            registers: self.registers,
+            halted: self.halted,
+            tracer: self.tracer.clone(),
+            hints: self.hints.clone(),
+            output: self.output.clone(),
+            panic: self.panic.clone(),
+            entropy: self.entropy,
+            clock: self.clock,
+        }
+    }
+
+    /// Return to the state of `snapshot`, so that the execution continues from there with the
+    /// same steps as after the snapshot was taken.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            program,
+            pc,
+            memory,
+            registers,
+            halted,
+            tracer,
+            hints,
+            output,
+            panic,
+            entropy,
+            clock,
+        } = snapshot;
+        self.program = program;
+        self.pc = pc;
+        self.memory = memory;
+        self.registers = registers;
+        self.halted = halted;
+        self.tracer = tracer;
+        self.hints = hints;
+        self.output = output;
+        self.panic = panic;
+        self.entropy = entropy;
+        self.clock = clock;
+    }
+
     pub fn tracer(&self) -> &Tracer {
         &self.tracer
     }
@@ -80,7 +289,7 @@ impl VMState {
     pub fn iter_until_halt(&mut self) -> impl Iterator<Item = Result<StepRecord>> + '_ {
         let emu = Emulator::new();
         from_fn(move || {
-            if self.halted() {
+            if self.halted() || self.paused() {
                 None
             } else {
                 Some(self.step(&emu))
@@ -88,9 +297,112 @@ impl VMState {
         })
     }
 
-    fn step(&mut self, emu: &Emulator) -> Result<StepRecord> {
+    /// Run until the program halts, or for up to `max_steps` steps, and yield the steps in
+    /// batches of `batch_size`, the last one possibly shorter. Only one batch is held at a time,
+    /// so that witness assignment can consume long executions in bounded memory.
+    ///
+    /// `batch_size` must be a power of two, like the number of instances the circuits are
+    /// padded to, so that full batches need no padding of their own.
+    pub fn iter_batches(
+        &mut self,
+        max_steps: usize,
+        batch_size: usize,
+    ) -> impl Iterator<Item = Result<Vec<StepRecord>>> + '_ {
+        assert!(
+            batch_size.is_power_of_two(),
+            "batch size {batch_size} is not a power of two"
+        );
+        let mut steps = self.iter_until_halt().take(max_steps);
+        let mut failed = false;
+        from_fn(move || {
+            if failed {
+                return None;
+            }
+            match steps.by_ref().take(batch_size).collect::<Result<Vec<_>>>() {
+                Ok(batch) if batch.is_empty() => None,
+                batch => {
+                    failed = batch.is_err();
+                    Some(batch)
+                }
+            }
+        })
+    }
+
+    /// Run up to `max_steps` steps and return them as a segment, along with the state at its
+    /// start. Return `None` once the program has halted, or is paused.
+    pub fn next_segment(&mut self, max_steps: usize) -> Result<Option<Segment>> {
+        assert!(max_steps > 0, "segments must have at least one step");
+        let end_cycle = self.tracer.cycle() + max_steps as Cycle * Tracer::SUBCYCLES_PER_INSN;
+        self.next_segment_until(end_cycle)
+    }
+
+    /// Run the steps before `end_cycle` and return them as a segment, which thus ends exactly at
+    /// the first instruction at or after `end_cycle`. Return `None` once the program has halted,
+    /// or is paused.
+    pub fn next_segment_until(&mut self, end_cycle: Cycle) -> Result<Option<Segment>> {
+        assert!(
+            end_cycle > self.tracer.cycle(),
+            "segments must have at least one step"
+        );
+        if self.halted() || self.paused() {
+            return Ok(None);
+        }
+
+        let start = self.boundary();
+        let init_registers = self.registers;
+        let init_memory = self.memory.clone();
+
+        let mut steps = vec![];
+        for step in self.iter_until_halt() {
+            let step = step?;
+            let end = step.cycle() + Tracer::SUBCYCLES_PER_INSN >= end_cycle;
+            steps.push(step);
+            if end {
+                break;
+            }
+        }
+        for step in &mut steps {
+            step.forget_accesses_before(start.cycle);
+        }
+
+        let final_accesses = self
+            .tracer
+            .final_accesses()
+            .iter()
+            .filter(|(_, cycle)| **cycle >= start.cycle)
+            .map(|(&addr, &cycle)| (addr, cycle))
+            .collect();
+
+        let report = SegmentReport::new(&steps, &init_registers, &self.registers);
+        Ok(Some(Segment {
+            start,
+            end: self.boundary(),
+            init_registers,
+            init_memory,
+            steps,
+            final_accesses,
+            halted: self.halted(),
+            report,
+        }))
+    }
+
+    fn boundary(&self) -> SegmentBoundary {
+        SegmentBoundary {
+            pc: self.pc,
+            cycle: self.tracer.cycle(),
+            state_digest: state_digest(&self.registers, &self.memory),
+        }
+    }
+
+    pub(crate) fn step(&mut self, emu: &Emulator) -> Result<StepRecord> {
         emu.step(self)?;
         let step = self.tracer.advance();
+        if self.crash_report_config.history > 0 {
+            if self.recent_pcs.len() == self.crash_report_config.history {
+                self.recent_pcs.pop_front();
+            }
+            self.recent_pcs.push_back(step.pc().before.0);
+        }
         if step.is_busy_loop() && !self.halted() {
             Err(anyhow!("Stuck in loop {}", "{}"))
         } else {
@@ -102,22 +414,350 @@ impl VMState {
         self.registers[idx] = value;
     }
 
+    fn crash_report(&self, cause: TrapCause) -> CrashReport {
+        // The stack pointer, x2.
+        let center = cause.address().unwrap_or(ByteAddr(self.registers[2]));
+        let memory = memory_window(center, self.crash_report_config.memory_window)
+            .map(|addr| (addr, self.peek_memory(addr.waddr())))
+            .collect();
+        let relative_pc = self.pc.wrapping_sub(self.program.base_address);
+        CrashReport {
+            cause,
+            pc: ByteAddr(self.pc),
+            cycle: self.tracer.cycle(),
+            insn_code: self
+                .program
+                .instructions
+                .get((relative_pc / WORD_SIZE as u32) as usize)
+                .copied(),
+            registers: self.registers[..32].try_into().unwrap(),
+            memory,
+            recent_pcs: self.recent_pcs.iter().copied().collect(),
+        }
+    }
+
     fn halt(&mut self) {
         self.set_pc(0.into());
         self.halted = true;
     }
+
+    /// Run a syscall, see `SyscallHandler`.
+    fn syscall(
+        &mut self,
+        handler: Option<&mut dyn SyscallHandler>,
+        function: u32,
+        arg0: Word,
+    ) -> Result<bool> {
+        let addr = ByteAddr(arg0);
+        if !addr.is_aligned() || !self.check_data_load(addr) {
+            return self.trap(TrapCause::EcallError);
+        }
+        let addr = addr.waddr();
+        let value = match (function, handler) {
+            (f, Some(handler)) if f == Platform::ecall_write() => {
+                let bytes = self.load_frame(addr)?;
+                handler.write(self.peek_register(Platform::reg_arg1()), &bytes)?;
+                None
+            }
+            (f, _) if f == Platform::ecall_commit() => {
+                let bytes = self.load_frame(addr)?;
+                self.output.extend(bytes);
+                None
+            }
+            (f, _) if f == Platform::ecall_panic() => {
+                let bytes = self.load_frame(addr)?;
+                self.panic = Some(GuestPanic {
+                    message: String::from_utf8_lossy(&bytes).into_owned(),
+                    pc: ByteAddr(self.pc),
+                });
+                None
+            }
+            (f, _) if f == Platform::ecall_read_hint() => Some(self.read_hint()?),
+            (f, _) if f == Platform::ecall_random() => Some(self.next_random()),
+            (f, handler) if f == Platform::ecall_clock() => {
+                let cycle = self.tracer.cycle();
+                let time = handler.map_or(cycle as Word, |handler| handler.clock(cycle));
+                self.clock = self.clock.max(time);
+                Some(self.clock)
+            }
+            _ => return self.trap(TrapCause::EcallError),
+        };
+        if let Some(value) = value {
+            if !self.check_data_store(ByteAddr(arg0)) {
+                return self.trap(TrapCause::EcallError);
+            }
+            self.store_memory(addr, value)?;
+        }
+        self.store_register(DecodedInstruction::RD_NULL as RegIdx, 0)?;
+        self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+        Ok(true)
+    }
+
+    /// Run a precompile on the `len` words at `arg0`: `f` updates the first `writes` of them in
+    /// place, or returns false if they are not valid operands, which traps. The step records every
+    /// word as `syscall_ops`, in order, with no write to `x0` nor `memory_op`.
+    fn precompile(
+        &mut self,
+        arg0: Word,
+        len: usize,
+        writes: usize,
+        f: impl FnOnce(&mut [Word]) -> bool,
+    ) -> Result<bool> {
+        let Some(end) = arg0.checked_add((len * WORD_SIZE) as u32) else {
+            return self.trap(TrapCause::EcallError);
+        };
+        let addrs = (arg0..end)
+            .step_by(WORD_SIZE)
+            .map(|addr| ByteAddr(addr).waddr())
+            .collect::<Vec<_>>();
+        if !ByteAddr(arg0).is_aligned()
+            || !addrs.iter().all(|&addr| self.check_data_load(addr.into()))
+            || !addrs[..writes]
+                .iter()
+                .all(|&addr| self.check_data_store(addr.into()))
+        {
+            return self.trap(TrapCause::EcallError);
+        }
+
+        let before = addrs
+            .iter()
+            .map(|&addr| self.peek_memory(addr))
+            .collect::<Vec<_>>();
+        let mut after = before.clone();
+        if !f(&mut after) {
+            return self.trap(TrapCause::EcallError);
+        }
+        for (i, &addr) in addrs.iter().enumerate() {
+            self.tracer
+                .syscall_memory(addr, Change::new(before[i], after[i]));
+            if i < writes {
+                self.memory.insert(addr, after[i]);
+            }
+        }
+        self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+        Ok(true)
+    }
+
+    /// Compress the SHA-256 block at `arg0` into the state before it, see
+    /// `Platform::ecall_sha256`.
+    fn sha256(&mut self, arg0: Word) -> Result<bool> {
+        let len = SHA256_STATE_WORDS + SHA256_BLOCK_WORDS;
+        self.precompile(arg0, len, SHA256_STATE_WORDS, |words| {
+            let (state, block) = words.split_at_mut(SHA256_STATE_WORDS);
+            sha256_compress(state.try_into().unwrap(), (&*block).try_into().unwrap());
+            true
+        })
+    }
+
+    /// Permute the Keccak state at `arg0`, see `Platform::ecall_keccak_permute`.
+    fn keccak_permute(&mut self, arg0: Word) -> Result<bool> {
+        self.precompile(arg0, KECCAK_STATE_WORDS, KECCAK_STATE_WORDS, |words| {
+            let mut state: [u64; KECCAK_LANES] =
+                std::array::from_fn(|i| words[2 * i] as u64 | (words[2 * i + 1] as u64) << 32);
+            keccak_f(&mut state);
+            for (lane, value) in words.chunks_exact_mut(2).zip(state) {
+                lane[0] = value as Word;
+                lane[1] = (value >> 32) as Word;
+            }
+            true
+        })
+    }
+
+    /// Reduce `op` of the numbers at `arg0` by the modulus after them, see
+    /// `Platform::ecall_uint256_addmod`.
+    fn uint256_modop(
+        &mut self,
+        arg0: Word,
+        op: fn(&[Word; UINT256_WORDS], &[Word; UINT256_WORDS]) -> [Word; 2 * UINT256_WORDS],
+    ) -> Result<bool> {
+        self.precompile(arg0, 3 * UINT256_WORDS, UINT256_WORDS, |words| {
+            let [x, y, m]: [[Word; UINT256_WORDS]; 3] = std::array::from_fn(|i| {
+                words[i * UINT256_WORDS..][..UINT256_WORDS]
+                    .try_into()
+                    .unwrap()
+            });
+            if !uint256_lt(&x, &m) || !uint256_lt(&y, &m) {
+                return false;
+            }
+            let (_, r) = uint256_divrem(&op(&x, &y), &m);
+            words[..UINT256_WORDS].copy_from_slice(&r);
+            true
+        })
+    }
+
+    /// Add the two secp256k1 points at `arg0` into the first one, see
+    /// `Platform::ecall_secp256k1_add`.
+    fn secp256k1_add(&mut self, arg0: Word) -> Result<bool> {
+        let len = 2 * SECP256K1_POINT_WORDS;
+        self.precompile(arg0, len, SECP256K1_POINT_WORDS, |words| {
+            let (p, q) = words.split_at_mut(SECP256K1_POINT_WORDS);
+            secp256k1_add(p.try_into().unwrap(), (&*q).try_into().unwrap())
+        })
+    }
+
+    /// Double the secp256k1 point at `arg0`, see `Platform::ecall_secp256k1_double`.
+    fn secp256k1_double(&mut self, arg0: Word) -> Result<bool> {
+        let len = SECP256K1_POINT_WORDS;
+        self.precompile(arg0, len, len, |words| {
+            secp256k1_double(words.try_into().unwrap())
+        })
+    }
+
+    /// Add the two ed25519 points at `arg0` into the first one, see
+    /// `Platform::ecall_ed25519_add`.
+    fn ed25519_add(&mut self, arg0: Word) -> Result<bool> {
+        let len = 2 * ED25519_POINT_WORDS;
+        self.precompile(arg0, len, ED25519_POINT_WORDS, |words| {
+            let (p, q) = words.split_at_mut(ED25519_POINT_WORDS);
+            ed25519_add(p.try_into().unwrap(), (&*q).try_into().unwrap())
+        })
+    }
+
+    /// Permute the Poseidon2 state at `arg0`, see `Platform::ecall_poseidon2_permute`.
+    fn poseidon2_permute(&mut self, arg0: Word) -> Result<bool> {
+        let len = POSEIDON2_STATE_WORDS;
+        self.precompile(arg0, len, len, |words| {
+            let mut state: [u64; POSEIDON2_WIDTH] =
+                std::array::from_fn(|i| words[2 * i] as u64 | (words[2 * i + 1] as u64) << 32);
+            if !poseidon2_permute(&mut state) {
+                return false;
+            }
+            for (element, value) in words.chunks_exact_mut(2).zip(state) {
+                element[0] = value as Word;
+                element[1] = (value >> 32) as Word;
+            }
+            true
+        })
+    }
+
+    /// Read the length in bytes stored at `addr`, recording that access, and the bytes which
+    /// follow it, without recording them. Trap if any of those bytes is not readable.
+    fn load_frame(&mut self, addr: WordAddr) -> Result<Vec<u8>> {
+        let len = self.load_memory(addr)?;
+        let start = u64::from(ByteAddr::from(addr).0) + WORD_SIZE as u64;
+        let words = (start..start + u64::from(len)).step_by(WORD_SIZE);
+        if start + u64::from(len) > 1 << u32::BITS
+            || !words
+                .clone()
+                .all(|word| self.check_data_load(ByteAddr(word as u32)))
+        {
+            self.trap(TrapCause::EcallError)?;
+        }
+        Ok(words
+            .flat_map(|word| {
+                self.peek_memory(ByteAddr(word as u32).waddr())
+                    .to_le_bytes()
+            })
+            .take(len as usize)
+            .collect())
+    }
+
+    /// The next word of RANDOM, from the SplitMix64 generator.
+    fn next_random(&mut self) -> Word {
+        self.entropy = self.entropy.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.entropy;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        (z ^ (z >> 31)) as Word
+    }
+
+    /// Take the next word from the hint tape, padding the last one with zeros.
+    fn read_hint(&mut self) -> Result<Word> {
+        if self.hints.is_empty() {
+            return Err(anyhow!("hint tape exhausted"));
+        }
+        let mut word = [0; WORD_SIZE];
+        let len = self.hints.len().min(WORD_SIZE);
+        for (byte, hint) in word.iter_mut().zip(self.hints.drain(..len)) {
+            *byte = hint;
+        }
+        Ok(Word::from_le_bytes(word))
+    }
+
+    /// Check the digest at `arg0` against the committed output, see
+    /// `Platform::ecall_commit_digest`. The step records the words of the digest as
+    /// `syscall_ops`, with no write to `x0` nor `memory_op`.
+    fn commit_digest(&mut self, arg0: Word) -> Result<bool> {
+        let Some(end) = arg0.checked_add((SHA256_STATE_WORDS * WORD_SIZE) as u32) else {
+            return self.trap(TrapCause::EcallError);
+        };
+        let addrs = (arg0..end)
+            .step_by(WORD_SIZE)
+            .map(|addr| ByteAddr(addr).waddr())
+            .collect::<Vec<_>>();
+        if !ByteAddr(arg0).is_aligned()
+            || !addrs.iter().all(|&addr| self.check_data_load(addr.into()))
+        {
+            return self.trap(TrapCause::EcallError);
+        }
+
+        let words = addrs
+            .iter()
+            .map(|&addr| self.peek_memory(addr))
+            .collect::<Vec<_>>();
+        if words != sha256_digest(&self.output) {
+            return self.trap(TrapCause::EcallError);
+        }
+        for (&addr, &value) in addrs.iter().zip(&words) {
+            self.tracer.syscall_memory(addr, Change::new(value, value));
+        }
+        self.set_pc(ByteAddr(self.pc) + PC_STEP_SIZE);
+        Ok(true)
+    }
 }
 
 impl EmuContext for VMState {
-    // Expect an ecall to terminate the program: function HALT with argument exit_code.
+    // Expect an ecall to terminate the program: function HALT with argument exit_code, or a
+    // syscall served by the VM or by the handler.
     fn ecall(&mut self) -> Result<bool> {
         let function = self.load_register(Platform::reg_ecall())?;
         let arg0 = self.load_register(Platform::reg_arg0())?;
         if function == Platform::ecall_halt() {
             tracing::debug!("halt with exit_code={}", arg0);
 
+            if let Some(handler) = &mut self.syscalls {
+                handler.exit(arg0);
+            }
             self.halt();
             Ok(true)
+        } else if function == Platform::ecall_sha256() {
+            self.sha256(arg0)
+        } else if function == Platform::ecall_keccak_permute() {
+            self.keccak_permute(arg0)
+        } else if function == Platform::ecall_uint256_addmod() {
+            self.uint256_modop(arg0, uint256_add_wide)
+        } else if function == Platform::ecall_uint256_mulmod() {
+            self.uint256_modop(arg0, uint256_mul_wide)
+        } else if function == Platform::ecall_secp256k1_add() {
+            self.secp256k1_add(arg0)
+        } else if function == Platform::ecall_secp256k1_double() {
+            self.secp256k1_double(arg0)
+        } else if function == Platform::ecall_ed25519_add() {
+            self.ed25519_add(arg0)
+        } else if function == Platform::ecall_poseidon2_permute() {
+            self.poseidon2_permute(arg0)
+        } else if function == Platform::ecall_commit_digest() {
+            self.commit_digest(arg0)
+        } else if [
+            Platform::ecall_read_hint(),
+            Platform::ecall_commit(),
+            Platform::ecall_panic(),
+            Platform::ecall_random(),
+            Platform::ecall_clock(),
+        ]
+        .contains(&function)
+            || self.syscalls.is_some()
+        {
+            let mut handler = self.syscalls.take();
+            let result = self.syscall(
+                handler
+                    .as_deref_mut()
+                    .map(|handler| handler as &mut dyn SyscallHandler),
+                function,
+                arg0,
+            );
+            self.syscalls = handler;
+            result
         } else if self.platform.unsafe_ecall_nop {
             // Treat unknown ecalls as all powerful instructions:
             // Read two registers, write one register, write one memory word, and branch.
@@ -134,7 +774,7 @@ impl EmuContext for VMState {
     }
 
     fn trap(&self, cause: TrapCause) -> Result<bool> {
-        Err(anyhow!("Trap {:?}", cause)) // Crash.
+        Err(self.crash_report(cause).into()) // Crash.
     }
 
     fn on_normal_end(&mut self, _decoded: &DecodedInstruction) {
@@ -201,11 +841,11 @@ impl EmuContext for VMState {
     }
 
     fn check_data_load(&self, addr: ByteAddr) -> bool {
-        self.platform.can_read(addr.0)
+        self.platform.can_read(addr.0) && self.in_memory_layout(addr)
     }
 
     fn check_data_store(&self, addr: ByteAddr) -> bool {
-        self.platform.can_write(addr.0)
+        self.platform.can_write(addr.0) && self.in_memory_layout(addr)
     }
 
     fn check_insn_load(&self, addr: ByteAddr) -> bool {
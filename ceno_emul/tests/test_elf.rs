@@ -1,11 +1,24 @@
 use anyhow::Result;
-use ceno_emul::{ByteAddr, CENO_PLATFORM, EmuContext, InsnKind, Platform, StepRecord, VMState};
+use ceno_emul::{
+    ByteAddr, CENO_PLATFORM, EmuContext, InsnKind, Platform, Profile, Program, StepRecord, Symbols,
+    VMState, poseidon2_permute, sha256_digest,
+};
 
 #[test]
 fn test_ceno_rt_mini() -> Result<()> {
     let program_elf = ceno_examples::ceno_rt_mini;
     let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
-    let _steps = run(&mut state)?;
+    let steps = run(&mut state)?;
+
+    // The runtime entry point runs most of the instructions.
+    let symbols = Symbols::from_elf(program_elf)?;
+    let profile = Profile::from_steps(&steps);
+    let hotspots = profile.hotspots(&symbols);
+    assert_eq!(
+        hotspots.iter().map(|(_, count)| count).sum::<u64>(),
+        profile.total()
+    );
+    assert!(hotspots.iter().any(|(name, _)| name == "_start_rust"));
     Ok(())
 }
 
@@ -17,7 +30,12 @@ fn test_ceno_rt_panic() -> Result<()> {
     let last = steps.last().unwrap();
     assert_eq!(last.insn().codes().kind, InsnKind::EANY);
     assert_eq!(last.rs1().unwrap().value, Platform::ecall_halt());
-    assert_eq!(last.rs2().unwrap().value, 1); // panic / halt(1)
+    assert_eq!(last.rs2().unwrap().value, Platform::code_panic());
+
+    // The panic handler reports the message and its location before halting.
+    let panic = state.guest_panic().unwrap();
+    assert!(panic.message.contains("ceno_rt_panic.rs"));
+    assert!(panic.message.ends_with("This is a panic message!"));
     Ok(())
 }
 
@@ -72,12 +90,339 @@ fn test_ceno_rt_io() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_ceno_rt_public_io() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_public_io;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    state.write_public_input(b"ceno")?;
+    let steps = run(&mut state)?;
+
+    assert_eq!(state.committed_output(), b"onec");
+    // the runtime attests the digest of the output when it halts
+    let digest = steps
+        .iter()
+        .find(|step| step.rs1().unwrap().value == Platform::ecall_commit_digest())
+        .unwrap();
+    let words = digest
+        .syscall_ops()
+        .iter()
+        .map(|op| op.value.before)
+        .collect::<Vec<_>>();
+    assert_eq!(words, sha256_digest(b"onec"));
+    assert!(precompile_steps(&steps, state.committed_output()).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_io() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_io;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    state.write_public_input(&ceno_guest::to_public_input("ceno").unwrap())?;
+    let mut stdin = ceno_guest::CenoStdin::new();
+    stdin
+        .write(&vec![1u32, 2, 3])
+        .unwrap()
+        .write(&10u32)
+        .unwrap()
+        .write_bytes(b"hints");
+    state.write_hints(stdin.as_bytes());
+    let _steps = run(&mut state)?;
+
+    let mut output = state.committed_output();
+    assert_eq!(ceno_guest::take_committed::<u32>(&mut output).unwrap(), 4);
+    assert_eq!(ceno_guest::take_committed::<u32>(&mut output).unwrap(), 60);
+    assert_eq!(
+        ceno_guest::take_committed::<u32>(&mut output).unwrap(),
+        b"hints".len() as u32
+    );
+    assert!(output.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_sha256() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_sha256;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    // Two blocks once padded.
+    let message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec();
+    state.write_hints(&ceno_guest::to_hint_frame(&message).unwrap());
+    let steps = run(&mut state)?;
+
+    let sha_steps = precompile_steps(&steps, state.committed_output());
+    assert_eq!(sha_steps.len(), 2);
+    for step in sha_steps {
+        assert_eq!(step.rs1().unwrap().value, Platform::ecall_sha256());
+        assert_eq!(step.syscall_ops().len(), 24);
+    }
+
+    let mut output = state.committed_output();
+    let digest: [u8; 32] = ceno_guest::take_committed(&mut output).unwrap();
+    assert_eq!(digest, [
+        0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e, 0x60,
+        0x39, 0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4, 0x19, 0xdb,
+        0x06, 0xc1,
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_keccak() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_keccak;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    // Two blocks once padded.
+    let message = vec![b'a'; 200];
+    state.write_hints(&ceno_guest::to_hint_frame(&message).unwrap());
+    let steps = run(&mut state)?;
+
+    let keccak_steps = precompile_steps(&steps, state.committed_output());
+    assert_eq!(keccak_steps.len(), 2);
+    for step in keccak_steps {
+        assert_eq!(step.rs1().unwrap().value, Platform::ecall_keccak_permute());
+        assert_eq!(step.syscall_ops().len(), 50);
+    }
+
+    let mut output = state.committed_output();
+    let digest: [u8; 32] = ceno_guest::take_committed(&mut output).unwrap();
+    assert_eq!(digest, [
+        0x96, 0xea, 0x54, 0x06, 0x1d, 0xef, 0x93, 0x6c, 0x4b, 0xe9, 0x0b, 0x51, 0x89, 0x92, 0xfd,
+        0xc6, 0xf1, 0x2f, 0x53, 0x50, 0x68, 0xa2, 0x56, 0x22, 0x9a, 0xca, 0x54, 0x26, 0x7b, 0x4d,
+        0x08, 0x4d,
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_poseidon2() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_poseidon2;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    // A leaf at index 0b101 of a tree of depth 3.
+    let leaf = [1u64, 2, 3, 4];
+    let path = vec![[5u64, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16]];
+    state.write_hints(&ceno_guest::to_hint_frame(&(leaf, 0b101u32, &path)).unwrap());
+    let steps = run(&mut state)?;
+
+    let poseidon2_steps = precompile_steps(&steps, state.committed_output());
+    assert_eq!(poseidon2_steps.len(), path.len());
+    for step in poseidon2_steps {
+        assert_eq!(
+            step.rs1().unwrap().value,
+            Platform::ecall_poseidon2_permute()
+        );
+        assert_eq!(step.syscall_ops().len(), 24);
+    }
+
+    let compress = |left: &[u64; 4], right: &[u64; 4]| {
+        let mut state = [0; 12];
+        state[..4].copy_from_slice(left);
+        state[4..8].copy_from_slice(right);
+        assert!(poseidon2_permute(&mut state));
+        <[u64; 4]>::try_from(&state[..4]).unwrap()
+    };
+    let root = compress(&path[2], &compress(&compress(&path[0], &leaf), &path[1]));
+
+    let mut output = state.committed_output();
+    let result: [u64; 4] = ceno_guest::take_committed(&mut output).unwrap();
+    assert_eq!(result, root);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_ed25519() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_ed25519;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    // 3B, for the base point B of ed25519.
+    let b: [u32; 16] = [
+        0x8F25_D51A,
+        0xC956_2D60,
+        0x9525_A7B2,
+        0x692C_C760,
+        0xFDD6_DC5C,
+        0xC0A4_E231,
+        0xCD6E_53FE,
+        0x2169_36D3,
+        0x6666_6658,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+    ];
+    state.write_hints(&ceno_guest::to_hint_frame(&(b, 3u32)).unwrap());
+    let steps = run(&mut state)?;
+
+    // One doubling, then one addition, both with the addition law.
+    let curve_steps = precompile_steps(&steps, state.committed_output())
+        .into_iter()
+        .map(|step| (step.rs1().unwrap().value, step.syscall_ops().len()))
+        .collect::<Vec<_>>();
+    assert_eq!(curve_steps, [(Platform::ecall_ed25519_add(), 32); 2]);
+
+    let mut output = state.committed_output();
+    let result: [u32; 16] = ceno_guest::take_committed(&mut output).unwrap();
+    assert_eq!(result, [
+        0xD3F8_E25C,
+        0xAC62_485F,
+        0x8162_4886,
+        0x6343_9819,
+        0x3EDA_C83A,
+        0x1FF4_AE74,
+        0x2292_8F49,
+        0x67AE_9C4A,
+        0x78F5_B4D4,
+        0x02C3_6848,
+        0x6724_0304,
+        0x9F16_EC17,
+        0x6026_9EF7,
+        0xA126_A18E,
+        0x77EE_69AB,
+        0x1267_B1D1,
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_secp256k1() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_secp256k1;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    // 3G, for the generator G of secp256k1.
+    let g: [u32; 16] = [
+        0x16F8_1798,
+        0x59F2_815B,
+        0x2DCE_28D9,
+        0x029B_FCDB,
+        0xCE87_0B07,
+        0x55A0_6295,
+        0xF9DC_BBAC,
+        0x79BE_667E,
+        0xFB10_D4B8,
+        0x9C47_D08F,
+        0xA685_5419,
+        0xFD17_B448,
+        0x0E11_08A8,
+        0x5DA4_FBFC,
+        0x26A3_C465,
+        0x483A_DA77,
+    ];
+    state.write_hints(&ceno_guest::to_hint_frame(&(g, 3u32)).unwrap());
+    let steps = run(&mut state)?;
+
+    // One doubling, then one addition.
+    let curve_steps = precompile_steps(&steps, state.committed_output())
+        .into_iter()
+        .map(|step| (step.rs1().unwrap().value, step.syscall_ops().len()))
+        .collect::<Vec<_>>();
+    assert_eq!(curve_steps, [
+        (Platform::ecall_secp256k1_double(), 16),
+        (Platform::ecall_secp256k1_add(), 32)
+    ]);
+
+    let mut output = state.committed_output();
+    let result: [u32; 16] = ceno_guest::take_committed(&mut output).unwrap();
+    assert_eq!(result, [
+        0xBCE0_36F9,
+        0x8601_F113,
+        0x836F_99B0,
+        0xB531_C845,
+        0xF89D_5229,
+        0x4934_4F85,
+        0x9258_C310,
+        0xF930_8A01,
+        0x84B8_E672,
+        0x6CB9_FD75,
+        0x34C2_231B,
+        0x6500_A999,
+        0x2A37_F356,
+        0x0FE3_37E6,
+        0x632D_E814,
+        0x388F_7B0F,
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_guest_uint256() -> Result<()> {
+    let program_elf = ceno_examples::ceno_guest_uint256;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    // 2^256 modulo the prime of secp256k1, 2^256 - 2^32 - 977.
+    let p: [u32; 8] = [0xFFFF_FC2F, 0xFFFF_FFFE, !0, !0, !0, !0, !0, !0];
+    let input = ([2u32, 0, 0, 0, 0, 0, 0, 0], 256u32, p);
+    state.write_hints(&ceno_guest::to_hint_frame(&input).unwrap());
+    let steps = run(&mut state)?;
+
+    // One squaring per bit of the exponent, and one multiplication per one bit.
+    let mulmod_steps = precompile_steps(&steps, state.committed_output());
+    assert_eq!(mulmod_steps.len(), 10);
+    for step in mulmod_steps {
+        assert_eq!(step.rs1().unwrap().value, Platform::ecall_uint256_mulmod());
+        assert_eq!(step.syscall_ops().len(), 24);
+    }
+
+    let mut output = state.committed_output();
+    let result: [u32; 8] = ceno_guest::take_committed(&mut output).unwrap();
+    assert_eq!(result, [977, 1, 0, 0, 0, 0, 0, 0]);
+    Ok(())
+}
+
+#[test]
+fn test_ceno_rt_stdout() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_stdout;
+    let mut state = VMState::new_from_elf(CENO_PLATFORM, program_elf)?;
+    let output = state.capture_output();
+    let _steps = run(&mut state)?;
+
+    output.assert_stdout("hello world\n");
+    output.assert_stderr("0 warnings");
+    assert_eq!(output.output().exit_code, Some(0));
+    Ok(())
+}
+
+#[test]
+fn test_load_elf() -> Result<()> {
+    let program_elf = ceno_examples::ceno_rt_mini;
+    let program = Program::load_elf(program_elf, u32::MAX)?;
+
+    // the program table is built from the instructions, laid out from the base address
+    assert!(program.entry >= program.base_address);
+    assert!(
+        ((program.entry - program.base_address) as usize) < program.instructions.len() * WORD_SIZE
+    );
+    for (i, &insn) in program.instructions.iter().enumerate() {
+        let addr = program.base_address + (i * WORD_SIZE) as u32;
+        assert_eq!(program.image[&addr], insn);
+    }
+
+    // e_flags, at offset 36 of the ELF32 header, marking compressed instructions
+    let mut rvc_elf = program_elf.to_vec();
+    rvc_elf[36] |= 1;
+    assert!(Program::load_elf(&rvc_elf, u32::MAX).is_err());
+
+    assert!(Program::load_elf(&program_elf[..32], u32::MAX).is_err());
+    Ok(())
+}
+
 fn run(state: &mut VMState) -> Result<Vec<StepRecord>> {
     let steps = state.iter_until_halt().collect::<Result<Vec<_>>>()?;
     eprintln!("Emulator ran for {} steps.", steps.len());
     Ok(steps)
 }
 
+/// The steps of the precompiles called by the guest, without those of the digest of its
+/// committed `output`, which the runtime hashes and attests when it halts.
+fn precompile_steps<'a>(steps: &'a [StepRecord], output: &[u8]) -> Vec<&'a StepRecord> {
+    let mut steps = steps
+        .iter()
+        .filter(|step| !step.syscall_ops().is_empty())
+        .collect::<Vec<_>>();
+    let digest = steps.pop().unwrap();
+    assert_eq!(digest.rs1().unwrap().value, Platform::ecall_commit_digest());
+    // one compression per block of the padded output
+    let blocks = (output.len() + 9).div_ceil(64);
+    steps.truncate(steps.len() - blocks);
+    steps
+}
+
 const WORD_SIZE: usize = 4;
 const INFO_OUT_ADDR: u32 = 0xC000_0000;
 
@@ -1,30 +1,22 @@
 #![allow(clippy::unusual_byte_groupings)]
 use anyhow::Result;
-use std::collections::{BTreeMap, HashMap};
+use itertools::{Itertools, chain, enumerate};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::{Arc, Mutex},
+};
 
 use ceno_emul::{
-    CENO_PLATFORM, Cycle, EmuContext, InsnKind, Platform, Program, StepRecord, Tracer, VMState,
-    WORD_SIZE, WordAddr,
+    ByteAddr, CENO_PLATFORM, CostTable, CrashReport, CrashReportConfig, Cycle, Debugger,
+    EmuContext, InsnKind, MemoryLayout, Output, Platform, Profile, Program, SHA256_STATE_WORDS,
+    StepRecord, Stop, Symbols, SyscallHandler, TRACE_FORMAT_VERSION, Tracer, TrapCause, VMState,
+    WORD_SIZE, Word, WordAddr, encode_rv32, read_trace_batches, register_digest, sha256_digest,
+    state_digest, write_trace_batch,
 };
 
 #[test]
 fn test_vm_trace() -> Result<()> {
-    let program = Program::new(
-        CENO_PLATFORM.pc_base(),
-        CENO_PLATFORM.pc_base(),
-        PROGRAM_FIBONACCI_20.to_vec(),
-        PROGRAM_FIBONACCI_20
-            .iter()
-            .enumerate()
-            .map(|(insn_idx, &insn)| {
-                (
-                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
-                    insn,
-                )
-            })
-            .collect(),
-    );
-    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
 
     let steps = run(&mut ctx)?;
 
@@ -36,6 +28,33 @@ fn test_vm_trace() -> Result<()> {
     let ops: Vec<InsnKind> = steps.iter().map(|step| step.insn().codes().kind).collect();
     assert_eq!(ops, expected_ops_fibonacci_20());
 
+    let profile = Profile::from_steps(&steps);
+    assert_eq!(profile.total(), steps.len() as u64);
+    assert_eq!(profile.by_kind[&InsnKind::EANY], 1);
+    assert_eq!(profile.by_pc.len(), PROGRAM_FIBONACCI_20.len());
+    assert_eq!(Profile::kind_histogram(&steps), profile.by_kind);
+    // Without symbols, the hotspots are the pcs of the loop.
+    let hotspots = profile.hotspots(&Symbols::default());
+    assert_eq!(hotspots[0].1, 10);
+
+    // By default, prover cycles are raw cycles.
+    assert_eq!(
+        profile.prover_cycles(&CostTable::default()),
+        profile.total()
+    );
+    let mut costs = CostTable::new(2);
+    costs.set_cost(InsnKind::EANY, 100);
+    assert_eq!(
+        profile.prover_cycles(&costs),
+        2 * (profile.total() - 1) + 100
+    );
+    let report = profile.with_costs(&costs).to_string();
+    assert!(report.starts_with("kind"), "{report}");
+    assert!(
+        report.lines().nth(1).unwrap().starts_with("EANY"),
+        "{report}"
+    );
+
     assert_eq!(
         ctx.tracer().final_accesses(),
         &expected_final_accesses_fibonacci_20()
@@ -58,6 +77,845 @@ fn test_empty_program() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_vm_batches() -> Result<()> {
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+
+    let batches = ctx
+        .iter_batches(usize::MAX, 4)
+        .collect::<Result<Vec<_>>>()?;
+    assert!(batches.len() > 1);
+    let (last, full) = batches.split_last().unwrap();
+    assert!(full.iter().all(|batch| batch.len() == 4));
+    assert!(!last.is_empty() && last.len() <= 4);
+    assert!(ctx.halted());
+
+    let ops = batches
+        .iter()
+        .flatten()
+        .map(|step| step.insn().codes().kind)
+        .collect_vec();
+    assert_eq!(ops, expected_ops_fibonacci_20());
+
+    // The steps are limited to `max_steps`.
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let batches = ctx.iter_batches(6, 4).collect::<Result<Vec<_>>>()?;
+    assert_eq!(batches.iter().map(Vec::len).collect_vec(), vec![4, 2]);
+    assert!(!ctx.halted());
+
+    // An error ends the batches.
+    let mut ctx = VMState::new(
+        CENO_PLATFORM,
+        Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            vec![],
+            BTreeMap::new(),
+        ),
+    );
+    let mut batches = ctx.iter_batches(usize::MAX, 4);
+    assert!(batches.next().unwrap().is_err());
+    assert!(batches.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_div_rem() -> Result<()> {
+    use InsnKind::*;
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+    let (t0, x1, x2) = (Platform::reg_ecall() as u32, 1, 2);
+    let cases: [(Word, Word); 4] = [
+        (i32::MIN as Word, -1i32 as Word),
+        (7, 0),
+        (-7i32 as Word, 2),
+        (u32::MAX, 2),
+    ];
+    let kinds = [DIV, DIVU, REM, REMU];
+    // LUI and the sign-extended immediate of ADDI
+    let load = |reg: u32, value: Word| {
+        [
+            encode_rv32(LUI, 0, 0, reg, value.wrapping_add(0x800) & !0xfff),
+            encode_rv32(ADDI, reg, 0, reg, value & 0xfff),
+        ]
+    };
+    let mut instructions = vec![];
+    for (dividend, divisor) in cases {
+        instructions.extend(load(x1, dividend));
+        instructions.extend(load(x2, divisor));
+        instructions.extend(kinds.map(|kind| encode_rv32(kind, x1, x2, 3, 0)));
+    }
+    instructions.extend([
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let image = enumerate(&instructions)
+        .map(|(i, &insn)| (pc(i), insn))
+        .collect();
+    let program = Program::new(pc(0), pc(0), instructions, image);
+    let steps = run(&mut VMState::new(CENO_PLATFORM, program))?;
+
+    let results = steps
+        .iter()
+        .filter_map(|step| {
+            let div_rem = step.div_rem()?;
+            Some((step.rd().unwrap().value.after, div_rem))
+        })
+        .collect_vec();
+    let rd = results.iter().map(|(rd, _)| *rd as i32).collect_vec();
+    #[rustfmt::skip]
+    assert_eq!(rd, [
+        i32::MIN, 0, 0, i32::MIN,
+        -1, -1, 7, 7,
+        -3, 0x7fff_fffc, -1, 1,
+        0, 0x7fff_ffff, -1, 1,
+    ]);
+    // The result is the quotient or the remainder of the auxiliary values.
+    for (i, (rd, div_rem)) in enumerate(&results) {
+        let expected = if i % 4 < 2 {
+            div_rem.quotient
+        } else {
+            div_rem.remainder
+        };
+        assert_eq!(*rd, expected);
+    }
+    assert!(results[0].1.overflow && results[0].1.dividend_negative);
+    assert!(!results[1].1.overflow && !results[1].1.dividend_negative);
+    assert!(results[4].1.divisor_zero && results[5].1.divisor_zero);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_layout() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let ram = CENO_PLATFORM.ram.start;
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+    let instructions = vec![
+        encode_rv32(LUI, 0, 0, a0, ram),
+        encode_rv32(SW, a0, 0, 0, 4),
+        encode_rv32(SW, a0, 0, 0, 8),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ];
+    let image = enumerate(&instructions)
+        .map(|(i, &insn)| (pc(i), insn))
+        .collect();
+    let program = Program::new(pc(0), pc(0), instructions, image);
+
+    // All of the RAM is accessible by default.
+    let segment = VMState::new(CENO_PLATFORM, program.clone())
+        .next_segment(100)?
+        .unwrap();
+    assert!(segment.halted);
+    assert_eq!(segment.report.touched_pages, BTreeSet::from([ram]));
+
+    // The store out of the layout faults, with its pc and address.
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    ctx.set_memory_layout(MemoryLayout::from_addresses([ram, ram + 4]));
+    let err = run(&mut ctx).unwrap_err().to_string();
+    assert!(err.contains("StoreAccessFault"), "{err}");
+    assert!(err.contains(&format!("{:?}", ByteAddr(ram + 8))), "{err}");
+    assert!(
+        err.contains(&format!("at pc {:?}", ByteAddr(pc(2)))),
+        "{err}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_crash_report() -> Result<()> {
+    use InsnKind::*;
+    let a0 = Platform::reg_arg0() as u32;
+    let ram = CENO_PLATFORM.ram.start;
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+    let instructions = vec![
+        encode_rv32(ADDI, 0, 0, 1, 7),
+        encode_rv32(LUI, 0, 0, a0, ram),
+        encode_rv32(SW, a0, 1, 0, 4),
+        encode_rv32(LW, a0, 0, 3, 6),
+    ];
+    let image = enumerate(&instructions)
+        .map(|(i, &insn)| (pc(i), insn))
+        .collect();
+    let program = Program::new(pc(0), pc(0), instructions, image);
+
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    // the history shrinks to the most recent pcs
+    ctx.iter_until_halt().take(3).collect::<Result<Vec<_>>>()?;
+    ctx.set_crash_report_config(CrashReportConfig {
+        memory_window: 1,
+        history: 2,
+    });
+    let err = run(&mut ctx).unwrap_err();
+    let report = err.downcast_ref::<CrashReport>().unwrap();
+    assert_eq!(
+        report.cause,
+        TrapCause::LoadAddressMisaligned(ByteAddr(ram + 6))
+    );
+    assert_eq!(report.pc, ByteAddr(pc(3)));
+    assert_eq!(report.disassembly().unwrap(), "lw x3, 6(x10)");
+    assert_eq!(
+        (report.registers[1], report.registers[a0 as usize]),
+        (7, ram)
+    );
+    assert_eq!(report.memory, vec![
+        (ByteAddr(ram), 0),
+        (ByteAddr(ram + 4), 7),
+        (ByteAddr(ram + 8), 0),
+    ]);
+    assert_eq!(report.recent_pcs, vec![pc(1), pc(2)]);
+
+    let err = err.to_string();
+    assert!(
+        err.contains(&format!("at pc {:?}", ByteAddr(pc(3)))),
+        "{err}"
+    );
+    assert!(err.contains("0x80000004: 0x00000007 <-"), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn test_trace_io() -> Result<()> {
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let mut trace = vec![];
+    let mut steps = vec![];
+    for batch in ctx.iter_batches(usize::MAX, 8) {
+        let batch = batch?;
+        write_trace_batch(&mut trace, &batch)?;
+        steps.extend(batch);
+    }
+
+    let batches = read_trace_batches(trace.as_slice()).collect::<Result<Vec<_>>>()?;
+    assert_eq!(batches.len(), steps.len().div_ceil(8));
+    assert_eq!(batches.concat(), steps);
+
+    // Traces of another schema version are rejected.
+    let trace = String::from_utf8(trace)?.replacen(
+        &format!("\"version\":{TRACE_FORMAT_VERSION}"),
+        "\"version\":0",
+        1,
+    );
+    assert!(
+        read_trace_batches(trace.as_bytes())
+            .next()
+            .unwrap()
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_debugger() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let ram = CENO_PLATFORM.ram.start;
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+
+    // Breakpoints stop before their instruction, every time it is reached.
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let mut debugger = Debugger::new(&mut ctx);
+    debugger.add_breakpoint(pc(2));
+    let mut hits = 0;
+    while let Stop::Breakpoint(at) = debugger.resume()? {
+        assert_eq!((at, debugger.pc()), (pc(2), pc(2)));
+        assert_eq!(debugger.register(1), 10 - hits);
+        hits += 1;
+    }
+    assert_eq!(hits, 10);
+    assert_eq!(debugger.resume()?, Stop::Halted);
+    let (_, _, x3) = expected_fibonacci_20();
+    assert_eq!(debugger.register(3), x3);
+
+    // Single steps ignore the breakpoints.
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let mut debugger = Debugger::new(&mut ctx);
+    debugger.add_breakpoint(pc(1));
+    assert_eq!(debugger.step()?.pc().before.0, pc(0));
+    assert_eq!(debugger.step()?.pc().before.0, pc(1));
+    assert_eq!(debugger.register(3), 1);
+
+    // Watchpoints stop after an access to their word.
+    let instructions = vec![
+        encode_rv32(LUI, 0, 0, a0, ram),
+        encode_rv32(ADDI, 0, 0, t0, 7),
+        encode_rv32(SW, a0, t0, 0, 0),
+        encode_rv32(SW, a0, t0, 0, 4),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ];
+    let image = enumerate(&instructions)
+        .map(|(i, &insn)| (pc(i), insn))
+        .collect();
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        instructions,
+        image,
+    );
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    let mut debugger = Debugger::new(&mut ctx);
+    debugger.add_watchpoint(ByteAddr(ram + 4));
+    let Stop::Watchpoint(op) = debugger.resume()? else {
+        panic!("the watchpoint is not hit");
+    };
+    assert_eq!(op.addr, ByteAddr(ram + 4).waddr());
+    assert_eq!(op.value.after, 7);
+    assert_eq!(debugger.pc(), pc(4));
+    assert_eq!(debugger.memory(ByteAddr(ram)), 7);
+    assert_eq!(debugger.resume()?, Stop::Halted);
+
+    Ok(())
+}
+
+#[test]
+fn test_max_cycles() -> Result<()> {
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let start = ctx.tracer().cycle();
+
+    // The execution pauses at the first instruction at or after the limit.
+    ctx.set_max_cycles(Some(start + 5 * Tracer::SUBCYCLES_PER_INSN + 1));
+    let head = run(&mut ctx)?;
+    assert_eq!(head.len(), 6);
+    assert!(ctx.paused() && !ctx.halted());
+    assert_eq!(ctx.tracer().cycle(), start + 6 * Tracer::SUBCYCLES_PER_INSN);
+    assert!(ctx.next_segment(1)?.is_none());
+
+    // and resumes once the limit is lifted.
+    ctx.set_max_cycles(None);
+    let tail = run(&mut ctx)?;
+    assert!(ctx.halted() && !ctx.paused());
+    let ops = chain!(&head, &tail)
+        .map(|step| step.insn().codes().kind)
+        .collect_vec();
+    assert_eq!(ops, expected_ops_fibonacci_20());
+
+    // Segments end at the requested cycle boundaries.
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let segment = ctx
+        .next_segment_until(start + 3 * Tracer::SUBCYCLES_PER_INSN)?
+        .unwrap();
+    assert_eq!(segment.steps.len(), 3);
+    assert_eq!(segment.end.cycle, start + 3 * Tracer::SUBCYCLES_PER_INSN);
+    let segment = ctx
+        .next_segment_until(segment.end.cycle + Tracer::SUBCYCLES_PER_INSN + 1)?
+        .unwrap();
+    assert_eq!(segment.steps.len(), 2);
+    assert_eq!(
+        (segment.end.pc, segment.end.cycle),
+        (ctx.get_pc().0, ctx.tracer().cycle())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot() -> Result<()> {
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+    let head = ctx.iter_batches(5, 8).collect::<Result<Vec<_>>>()?;
+    let snapshot = ctx.snapshot();
+    assert_eq!(snapshot.pc(), ctx.get_pc().0);
+    assert_eq!(snapshot.cycle(), ctx.tracer().cycle());
+
+    let tail = run(&mut ctx)?;
+    let (x1, x2, x3) = expected_fibonacci_20();
+    assert_eq!(ctx.peek_register(3), x3);
+
+    // Resuming from the snapshot, also once serialized, replays the same steps, to the same final
+    // state.
+    let snapshot = serde_json::from_str(&serde_json::to_string(&snapshot)?)?;
+    ctx.restore(snapshot);
+    assert!(!ctx.halted());
+    assert_eq!(run(&mut ctx)?, tail);
+    assert_eq!(
+        (
+            ctx.peek_register(1),
+            ctx.peek_register(2),
+            ctx.peek_register(3)
+        ),
+        (x1, x2, x3)
+    );
+    assert_eq!(
+        ctx.tracer().final_accesses(),
+        &expected_final_accesses_fibonacci_20()
+    );
+
+    let ops = chain!(head.iter().flatten(), &tail)
+        .map(|step| step.insn().codes().kind)
+        .collect_vec();
+    assert_eq!(ops, expected_ops_fibonacci_20());
+
+    Ok(())
+}
+
+#[test]
+fn test_vm_segments() -> Result<()> {
+    let mut ctx = VMState::new(CENO_PLATFORM, program_fibonacci_20());
+
+    let mut segments = vec![];
+    while let Some(segment) = ctx.next_segment(5)? {
+        segments.push(segment);
+    }
+    assert!(segments.len() > 1);
+    assert!(segments.iter().all(|segment| segment.steps.len() <= 5));
+
+    // The segments chain up, and only the last one halts.
+    assert_eq!(segments[0].start.pc, CENO_PLATFORM.pc_base());
+    assert_eq!(segments[0].start.cycle, Tracer::SUBCYCLES_PER_INSN);
+    for (prev, next) in segments.iter().tuple_windows() {
+        assert_eq!(prev.end, next.start);
+        assert!(!prev.halted);
+    }
+    assert!(segments.last().unwrap().halted);
+
+    let ops = segments
+        .iter()
+        .flat_map(|segment| &segment.steps)
+        .map(|step| step.insn().codes().kind)
+        .collect_vec();
+    assert_eq!(ops, expected_ops_fibonacci_20());
+
+    // Accesses from previous segments look like initialization.
+    for segment in &segments {
+        let ops = segment.steps.iter().flat_map(|step| {
+            chain!(
+                step.rs1().map(|op| op.previous_cycle),
+                step.rs2().map(|op| op.previous_cycle),
+                step.rd().map(|op| op.previous_cycle),
+            )
+        });
+        for previous_cycle in ops {
+            assert!(previous_cycle == 0 || previous_cycle >= segment.start.cycle);
+        }
+        assert!(
+            segment
+                .final_accesses
+                .values()
+                .all(|&cycle| cycle >= segment.start.cycle)
+        );
+    }
+
+    // The snapshots match the digests of the boundaries, and the register digests chain up.
+    for segment in &segments {
+        assert_eq!(
+            state_digest(&segment.init_registers, &segment.init_memory),
+            segment.start.state_digest
+        );
+        assert_eq!(
+            segment.report.registers_in,
+            register_digest(&segment.init_registers)
+        );
+        assert!(segment.report.touched_pages.is_empty());
+    }
+    for (prev, next) in segments.iter().tuple_windows() {
+        assert_eq!(prev.report.registers_out, next.report.registers_in);
+    }
+    assert_eq!(ctx.peek_register(3), expected_fibonacci_20().2);
+
+    Ok(())
+}
+
+#[test]
+fn test_random_and_clock() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let ram = CENO_PLATFORM.ram.start;
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+    // two random words, then two clock readings, into the RAM at a0
+    let mut instructions = vec![encode_rv32(LUI, 0, 0, a0, ram)];
+    for function in [
+        Platform::ecall_random(),
+        Platform::ecall_random(),
+        Platform::ecall_clock(),
+        Platform::ecall_clock(),
+    ] {
+        instructions.extend([
+            encode_rv32(ADDI, 0, 0, t0, function),
+            encode_rv32(EANY, 0, 0, 0, 0),
+            encode_rv32(ADDI, a0, 0, a0, WORD_SIZE as u32),
+        ]);
+    }
+    instructions.extend([
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let image = enumerate(&instructions)
+        .map(|(i, &insn)| (pc(i), insn))
+        .collect();
+    let program = Program::new(pc(0), pc(0), instructions, image);
+
+    let run_with = |seed: u64, handler: Option<Box<dyn SyscallHandler + Send>>| -> Result<_> {
+        let mut ctx = VMState::new(CENO_PLATFORM, program.clone());
+        ctx.set_entropy_seed(seed);
+        if let Some(handler) = handler {
+            ctx.set_syscall_handler(handler);
+        }
+        let steps = run(&mut ctx)?;
+        let words = (0..4)
+            .map(|i| ctx.peek_memory(ByteAddr(ram).waddr() + i as u32))
+            .collect_vec();
+        Ok((steps, words))
+    };
+
+    // The values land in the trace, and replay identically.
+    let (steps, words) = run_with(1, None)?;
+    let written = steps
+        .iter()
+        .filter(|step| step.insn().codes().kind == EANY)
+        .filter_map(|step| Some(step.memory_op()?.value.after))
+        .collect_vec();
+    assert_eq!(written, words);
+    assert_eq!(run_with(1, None)?.1, words);
+    assert_ne!(words[0], words[1]);
+    assert_ne!(run_with(2, None)?.1[..2], words[..2]);
+
+    // Without a handler, the clock is the cycle.
+    assert_eq!(words[2] as Cycle, steps[8].cycle());
+    assert!(words[2] < words[3]);
+
+    // A handler sets the time, which stays monotonic.
+    struct Countdown(Word);
+    impl SyscallHandler for Countdown {
+        fn write(&mut self, _: u32, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+        fn clock(&mut self, _: Cycle) -> Word {
+            self.0 -= 1;
+            self.0
+        }
+    }
+    let (_, words) = run_with(1, Some(Box::new(Countdown(100))))?;
+    assert_eq!(words[2..], [99, 99]);
+
+    Ok(())
+}
+
+#[test]
+fn test_captured_output() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0, a1) = (
+        Platform::reg_ecall() as u32,
+        Platform::reg_arg0() as u32,
+        Platform::reg_arg1() as u32,
+    );
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+    let message = CENO_PLATFORM.ram.start;
+    // write the message to the file descriptor `fd`
+    let program = |fd: u32| {
+        let instructions = vec![
+            encode_rv32(LUI, 0, 0, a0, message),
+            encode_rv32(ADDI, 0, 0, t0, Platform::ecall_write()),
+            encode_rv32(ADDI, 0, 0, a1, fd),
+            encode_rv32(EANY, 0, 0, 0, 0),
+            encode_rv32(ADDI, 0, 0, a1, Platform::FD_STDERR),
+            encode_rv32(EANY, 0, 0, 0, 0),
+            encode_rv32(ADDI, 0, 0, a0, 0),
+            encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+            encode_rv32(EANY, 0, 0, 0, 0),
+        ];
+        let image = chain!(enumerate(&instructions).map(|(i, &insn)| (pc(i), insn)), [
+            (message, 2),
+            (message + 4, u32::from_le_bytes(*b"hi\0\0")),
+        ])
+        .collect();
+        Program::new(pc(0), pc(0), instructions, image)
+    };
+
+    let mut ctx = VMState::new(CENO_PLATFORM, program(Platform::FD_STDOUT));
+    let output = ctx.capture_output();
+    run(&mut ctx)?;
+    output.assert_stdout("hi");
+    output.assert_stderr("hi");
+    assert_eq!(output.output().exit_code, Some(0));
+
+    // Writes to other file descriptors fail.
+    let mut ctx = VMState::new(CENO_PLATFORM, program(3));
+    let output = ctx.capture_output();
+    assert!(run(&mut ctx).is_err());
+    assert_eq!(output.output(), Output::default());
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct TestSyscalls {
+    output: Arc<Mutex<Vec<u8>>>,
+    exit_code: Arc<Mutex<Option<u32>>>,
+}
+
+impl SyscallHandler for TestSyscalls {
+    fn write(&mut self, _fd: u32, bytes: &[u8]) -> Result<()> {
+        self.output.lock().unwrap().extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn exit(&mut self, exit_code: u32) {
+        *self.exit_code.lock().unwrap() = Some(exit_code);
+    }
+}
+
+#[test]
+fn test_syscalls() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let ram = CENO_PLATFORM.ram.start;
+    let message = ram + 0x100;
+    let instructions = vec![
+        // read a hint, then the clock, into the RAM at a0
+        encode_rv32(LUI, 0, 0, a0, ram),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_read_hint()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(ADDI, a0, 0, a0, WORD_SIZE as u32),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_clock()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        // write the message at a0
+        encode_rv32(ADDI, a0, 0, a0, 0x100 - WORD_SIZE as u32),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_write()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        // halt with exit code 3
+        encode_rv32(ADDI, 0, 0, a0, 3),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ];
+    let image = chain!(
+        instructions
+            .iter()
+            .enumerate()
+            .map(|(i, &insn)| { (CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32, insn) }),
+        [
+            (message, 5),
+            (message + 4, u32::from_le_bytes(*b"hell")),
+            (message + 8, b'o' as u32),
+        ]
+    )
+    .collect();
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        instructions,
+        image,
+    );
+
+    let syscalls = TestSyscalls::default();
+    let (output, exit_code) = (syscalls.output.clone(), syscalls.exit_code.clone());
+    let mut ctx = VMState::new(CENO_PLATFORM, program.clone());
+    ctx.set_syscall_handler(Box::new(syscalls));
+    ctx.write_hints(&[0xfe, 0xca]);
+    let steps = run(&mut ctx)?;
+
+    assert_eq!(ctx.peek_memory(ByteAddr(ram).waddr()), 0xcafe);
+    assert_eq!(
+        ctx.peek_memory(ByteAddr(ram).waddr() + 1u32),
+        steps[5].cycle() as Word
+    );
+    assert_eq!(output.lock().unwrap().as_slice(), b"hello");
+    assert_eq!(*exit_code.lock().unwrap(), Some(3));
+
+    // syscalls have the shape of other ecalls: two register reads, a register write, and a
+    // memory access at a0
+    for step in [&steps[2], &steps[5], &steps[8]] {
+        assert!(step.rs1().is_some() && step.rs2().is_some());
+        assert!(step.rd().is_some());
+        let memory_op = step.memory_op().unwrap();
+        assert_eq!(memory_op.addr, ByteAddr(step.rs2().unwrap().value).waddr());
+    }
+    assert_eq!(steps[8].memory_op().unwrap().value.after, 5);
+
+    // a message running past the RAM traps
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    ctx.set_syscall_handler(Box::new(TestSyscalls::default()));
+    ctx.write_hints(&[0xfe, 0xca]);
+    ctx.init_memory(ByteAddr(message).waddr(), CENO_PLATFORM.ram.len() as u32);
+    assert!(run(&mut ctx).is_err());
+
+    // hints need no handler, and reading past the end of the tape fails
+    let program = |instructions: Vec<u32>| {
+        let image = instructions
+            .iter()
+            .enumerate()
+            .map(|(i, &insn)| (CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32, insn))
+            .collect();
+        Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            instructions,
+            image,
+        )
+    };
+    let read_hints = program(vec![
+        encode_rv32(LUI, 0, 0, a0, ram),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_read_hint()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ]);
+    let mut ctx = VMState::new(CENO_PLATFORM, read_hints.clone());
+    ctx.write_hints(&[1, 2, 3, 4, 5]);
+    run(&mut ctx)?;
+    assert_eq!(ctx.peek_memory(ByteAddr(ram).waddr()), 5);
+    let mut ctx = VMState::new(CENO_PLATFORM, read_hints);
+    ctx.write_hints(&[1, 2, 3, 4]);
+    assert!(run(&mut ctx).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_public_io() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    // commit the public input as it is
+    let instructions = vec![
+        encode_rv32(LUI, 0, 0, a0, CENO_PLATFORM.public_io.start),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_commit()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ];
+    let image = instructions
+        .iter()
+        .enumerate()
+        .map(|(i, &insn)| (CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32, insn))
+        .collect();
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        instructions,
+        image,
+    );
+
+    let mut ctx = VMState::new(CENO_PLATFORM, program);
+    ctx.write_public_input(b"public input")?;
+    let steps = run(&mut ctx)?;
+    assert_eq!(ctx.committed_output(), b"public input");
+    let memory_op = steps[2].memory_op().unwrap();
+    assert_eq!(
+        memory_op.addr,
+        ByteAddr(CENO_PLATFORM.public_io.start).waddr()
+    );
+    assert_eq!(memory_op.value.after, 12);
+
+    assert!(
+        ctx.write_public_input(&vec![0; CENO_PLATFORM.public_io.len()])
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_commit_digest() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let digest = CENO_PLATFORM.ram.start;
+    // commit the public input, then attest its digest
+    let instructions = vec![
+        encode_rv32(LUI, 0, 0, a0, CENO_PLATFORM.public_io.start),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_commit()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(LUI, 0, 0, a0, digest),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_commit_digest()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ];
+    let image = instructions
+        .iter()
+        .enumerate()
+        .map(|(i, &insn)| (CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32, insn))
+        .collect();
+    let program = Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        instructions,
+        image,
+    );
+    let run_with_digest = |words: [Word; SHA256_STATE_WORDS]| {
+        let mut ctx = VMState::new(CENO_PLATFORM, program.clone());
+        ctx.write_public_input(b"public input")?;
+        for (i, word) in words.into_iter().enumerate() {
+            ctx.init_memory(ByteAddr(digest).waddr() + i, word);
+        }
+        run(&mut ctx)
+    };
+
+    let steps = run_with_digest(sha256_digest(b"public input"))?;
+    let ops = steps[5].syscall_ops();
+    assert_eq!(ops.len(), SHA256_STATE_WORDS);
+    assert!(ops.iter().all(|op| op.value.before == op.value.after));
+
+    // a digest of another output traps
+    let err = run_with_digest(sha256_digest(b"")).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<CrashReport>().unwrap().cause,
+        TrapCause::EcallError
+    );
+    Ok(())
+}
+
+#[test]
+fn test_guest_panic() -> Result<()> {
+    use InsnKind::*;
+    let (t0, a0) = (Platform::reg_ecall() as u32, Platform::reg_arg0() as u32);
+    let pc = |i: usize| CENO_PLATFORM.pc_base() + (WORD_SIZE * i) as u32;
+    let message = CENO_PLATFORM.ram.start;
+    // report the message, then halt as a panicking guest does
+    let instructions = vec![
+        encode_rv32(LUI, 0, 0, a0, message),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_panic()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+        encode_rv32(ADDI, 0, 0, a0, Platform::code_panic()),
+        encode_rv32(ADDI, 0, 0, t0, Platform::ecall_halt()),
+        encode_rv32(EANY, 0, 0, 0, 0),
+    ];
+    let image = chain!(enumerate(&instructions).map(|(i, &insn)| (pc(i), insn)), [
+        (message, 4),
+        (message + 4, u32::from_le_bytes(*b"boom")),
+    ])
+    .collect();
+    let mut ctx = VMState::new(
+        CENO_PLATFORM,
+        Program::new(pc(0), pc(0), instructions, image),
+    );
+    let output = ctx.capture_output();
+    let steps = run(&mut ctx)?;
+
+    let panic = ctx.guest_panic().unwrap();
+    assert_eq!(panic.message, "boom");
+    assert_eq!(panic.pc, ByteAddr(pc(2)));
+    assert_eq!(
+        panic.to_string(),
+        format!("Guest panic at pc {:?}: boom", ByteAddr(pc(2)))
+    );
+    assert_eq!(output.output().exit_code, Some(Platform::code_panic()));
+    // the ecall reads the length of the message, like other syscalls
+    assert_eq!(steps[2].memory_op().unwrap().value.after, 4);
+
+    Ok(())
+}
+
+fn program_fibonacci_20() -> Program {
+    Program::new(
+        CENO_PLATFORM.pc_base(),
+        CENO_PLATFORM.pc_base(),
+        PROGRAM_FIBONACCI_20.to_vec(),
+        PROGRAM_FIBONACCI_20
+            .iter()
+            .enumerate()
+            .map(|(insn_idx, &insn)| {
+                (
+                    CENO_PLATFORM.pc_base() + (WORD_SIZE * insn_idx) as u32,
+                    insn,
+                )
+            })
+            .collect(),
+    )
+}
+
 fn run(state: &mut VMState) -> Result<Vec<StepRecord>> {
     state.iter_until_halt().collect()
 }
@@ -0,0 +1,198 @@
+#ifndef CENO_H
+#define CENO_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The polynomial commitment scheme of the prover, see `PcsKind`. It is passed as a `uint32_t`,
+// as a C caller may pass any value.
+enum CenoPcs
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  CENO_PCS_BASEFOLD_RS = 0,
+  CENO_PCS_BASEFOLD_BASECODE = 1,
+};
+#ifndef __cplusplus
+typedef uint32_t CenoPcs;
+#endif // __cplusplus
+
+// The outcome of a call.
+typedef enum CenoStatus {
+  CENO_STATUS_OK = 0,
+  // A null pointer where a value is required, or an argument which is not valid.
+  CENO_STATUS_INVALID_ARGUMENT,
+  // A guest which cannot be loaded, panics, or does not halt within `max_steps`.
+  CENO_STATUS_GUEST_ERROR,
+  // A proof which does not verify against the key.
+  CENO_STATUS_REJECTED,
+  // Bytes which are not a key or proof of this version of the prover.
+  CENO_STATUS_SERIALIZATION,
+  // Any other error of the prover.
+  CENO_STATUS_ERROR,
+  // The prover panicked.
+  CENO_STATUS_PANIC,
+} CenoStatus;
+
+// A proof with its public values, see `ProofBundle`.
+typedef struct CenoProof CenoProof;
+
+// The verifying key of a program, for the scheme `pcs`.
+typedef struct CenoVerifyingKey CenoVerifyingKey;
+
+// The parameters of [`ceno_keygen`] and [`ceno_prove`], see `ProveOptions`. Start from
+// [`ceno_prove_options_default`] to set only some of them.
+typedef struct CenoProveOptions {
+  // A `CenoPcs`.
+  uint32_t pcs;
+  // Stack size in bytes.
+  uint32_t stack_size;
+  // Heap size in bytes.
+  uint32_t heap_size;
+  // The guest must halt within this many steps.
+  uint64_t max_steps;
+  // Directory caching proving keys across runs, as a NUL-terminated UTF-8 path, or null.
+  const char *key_cache;
+  // Prove a guest which panics rather than fail with `CENO_STATUS_GUEST_ERROR`.
+  bool prove_panics;
+} CenoProveOptions;
+
+// A heap buffer of bytes owned by the caller, to be released by [`ceno_bytes_free`].
+typedef struct CenoBytes {
+  uint8_t *data;
+  size_t len;
+} CenoBytes;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The message of the last error of the calling thread, or null if no call failed. The string
+// is owned by the library, and valid until the next failing call on the thread.
+const char *ceno_last_error(void);
+
+// The default options, as those of `ProveOptions::default` with the default scheme.
+struct CenoProveOptions ceno_prove_options_default(void);
+
+// Generate the verifying key of the guest `elf` into `vk_out`, see `sdk::keygen`.
+//
+// # Safety
+//
+// `elf` points to `elf_len` bytes, `options` is null or valid, and `vk_out` is writable.
+enum CenoStatus ceno_keygen(const uint8_t *elf,
+                            size_t elf_len,
+                            const struct CenoProveOptions *options,
+                            struct CenoVerifyingKey **vk_out);
+
+// Run the guest `elf` with the hints `stdin` until it halts, and prove its execution into
+// `proof_out`, see `sdk::prove`. The verifying key of the program is written to `vk_out`, unless
+// null; a verifier obtains it from [`ceno_keygen`] rather than from the prover.
+//
+// # Safety
+//
+// `elf` and `stdin` point to `elf_len` and `stdin_len` bytes, `options` is null or valid,
+// `proof_out` is writable, and `vk_out` is null or writable.
+enum CenoStatus ceno_prove(const uint8_t *elf,
+                           size_t elf_len,
+                           const uint8_t *stdin,
+                           size_t stdin_len,
+                           const struct CenoProveOptions *options,
+                           struct CenoProof **proof_out,
+                           struct CenoVerifyingKey **vk_out);
+
+// Verify `proof` against the verifying key `vk` of its program. Returns `CENO_STATUS_OK` if the
+// proof is valid, and `CENO_STATUS_REJECTED` if not. That the proof is of the expected program
+// is up to the caller, see [`ceno_proof_program_digest`].
+//
+// # Safety
+//
+// `proof` and `vk` are valid handles.
+enum CenoStatus ceno_verify(const struct CenoProof *proof, const struct CenoVerifyingKey *vk);
+
+// Write the 32-byte digest of the program of `proof`, see `program_digest`, to `digest_out`.
+//
+// # Safety
+//
+// `proof` is a valid handle, and `digest_out` points to 32 writable bytes.
+enum CenoStatus ceno_proof_program_digest(const struct CenoProof *proof, uint8_t *digest_out);
+
+// Write to `has_output_out` whether the output committed by the guest of `proof` is the
+// `output_len` bytes at `output`, according to its public values. To be trusted after
+// [`ceno_verify`] only. A guest which does not attest the digest of its output, as `ceno_rt`
+// does when it halts, has no output.
+//
+// # Safety
+//
+// `proof` is a valid handle, `output` points to `output_len` bytes, and `has_output_out` is
+// writable.
+enum CenoStatus ceno_proof_has_output(const struct CenoProof *proof,
+                                      const uint8_t *output,
+                                      size_t output_len,
+                                      bool *has_output_out);
+
+// Serialize `proof` as a `.ceno` bundle into `bytes_out`.
+//
+// # Safety
+//
+// `proof` is a valid handle, and `bytes_out` is writable.
+enum CenoStatus ceno_proof_to_bytes(const struct CenoProof *proof, struct CenoBytes *bytes_out);
+
+// Deserialize a proof of [`ceno_proof_to_bytes`] into `proof_out`.
+//
+// # Safety
+//
+// `data` points to `len` bytes, and `proof_out` is writable.
+enum CenoStatus ceno_proof_from_bytes(const uint8_t *data,
+                                      size_t len,
+                                      struct CenoProof **proof_out);
+
+// Serialize `vk` into `bytes_out`.
+//
+// # Safety
+//
+// `vk` is a valid handle, and `bytes_out` is writable.
+enum CenoStatus ceno_vk_to_bytes(const struct CenoVerifyingKey *vk, struct CenoBytes *bytes_out);
+
+// Deserialize a verifying key of [`ceno_vk_to_bytes`] for the scheme `pcs`, a `CenoPcs`, into
+// `vk_out`.
+//
+// # Safety
+//
+// `data` points to `len` bytes, and `vk_out` is writable.
+enum CenoStatus ceno_vk_from_bytes(uint32_t pcs,
+                                   const uint8_t *data,
+                                   size_t len,
+                                   struct CenoVerifyingKey **vk_out);
+
+// Release a proof. Null is ignored.
+//
+// # Safety
+//
+// `proof` is null or a handle not yet released.
+void ceno_proof_free(struct CenoProof *proof);
+
+// Release a verifying key. Null is ignored.
+//
+// # Safety
+//
+// `vk` is null or a handle not yet released.
+void ceno_vk_free(struct CenoVerifyingKey *vk);
+
+// Release a buffer of this library.
+//
+// # Safety
+//
+// `bytes` is a buffer of this library not yet released, or has null `data`.
+void ceno_bytes_free(struct CenoBytes bytes);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CENO_H */
@@ -0,0 +1,607 @@
+//! A C ABI over `ceno_zkvm::sdk`, to embed the prover in a host written in another language,
+//! e.g. a sequencer in Go, Node or Python, rather than running `ceno-cli` as a subprocess.
+//!
+//! Keys and proofs are opaque handles, created by [`ceno_keygen`], [`ceno_prove`] and the
+//! `*_from_bytes` functions, and released by the matching `*_free`. A fallible function returns
+//! a [`CenoStatus`], and on failure [`ceno_last_error`] describes the error. Panics of the prover
+//! are caught at the boundary, and reported as [`CenoStatus::Panic`].
+//!
+//! A proof is serialized as a `.ceno` bundle, see `ProofBundle`, so it carries its polynomial
+//! commitment scheme and public values. A verifying key does not say its scheme, which is passed
+//! again to [`ceno_vk_from_bytes`].
+//!
+//! The C header is `include/ceno.h`, generated by cbindgen from this file:
+//! `cbindgen --config cbindgen.toml --output include/ceno.h`.
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    error::Error,
+    ffi::{CStr, CString, c_char},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    ptr, slice,
+};
+
+use ceno_zkvm::{
+    ProveOptions,
+    error::ZKVMError,
+    keygen, prove,
+    scheme::pcs::{PcsKind, PolynomialCommitmentScheme},
+    serialization::{ProofBundle, from_bytes, read_header, to_bytes},
+    structs::ZKVMVerifyingKey,
+    with_pcs,
+};
+use goldilocks::GoldilocksExt2;
+
+type E = GoldilocksExt2;
+
+/// The outcome of a call.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CenoStatus {
+    Ok = 0,
+    /// A null pointer where a value is required, or an argument which is not valid.
+    InvalidArgument,
+    /// A guest which cannot be loaded, panics, or does not halt within `max_steps`.
+    GuestError,
+    /// A proof which does not verify against the key.
+    Rejected,
+    /// Bytes which are not a key or proof of this version of the prover.
+    Serialization,
+    /// Any other error of the prover.
+    Error,
+    /// The prover panicked.
+    Panic,
+}
+
+impl From<&ZKVMError> for CenoStatus {
+    fn from(err: &ZKVMError) -> Self {
+        match err {
+            ZKVMError::GuestError(_) | ZKVMError::GuestPanic(_) => CenoStatus::GuestError,
+            ZKVMError::VerifyError(_)
+            | ZKVMError::VKDigestMismatch
+            | ZKVMError::VersionMismatch { .. } => CenoStatus::Rejected,
+            ZKVMError::SerializationError(_) | ZKVMError::UnsupportedFormatVersion(_) => {
+                CenoStatus::Serialization
+            }
+            _ => CenoStatus::Error,
+        }
+    }
+}
+
+/// The polynomial commitment scheme of the prover, see `PcsKind`. It is passed as a `uint32_t`,
+/// as a C caller may pass any value.
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CenoPcs {
+    BasefoldRs = 0,
+    BasefoldBasecode = 1,
+}
+
+impl From<CenoPcs> for PcsKind {
+    fn from(pcs: CenoPcs) -> Self {
+        match pcs {
+            CenoPcs::BasefoldRs => PcsKind::BasefoldRS,
+            CenoPcs::BasefoldBasecode => PcsKind::BasefoldBasecode,
+        }
+    }
+}
+
+/// The scheme of the `CenoPcs` value `pcs`.
+fn pcs_kind(pcs: u32) -> Result<PcsKind, Failure> {
+    match pcs {
+        pcs if pcs == CenoPcs::BasefoldRs as u32 => Ok(CenoPcs::BasefoldRs.into()),
+        pcs if pcs == CenoPcs::BasefoldBasecode as u32 => Ok(CenoPcs::BasefoldBasecode.into()),
+        pcs => Err(Failure::invalid(format!("unknown scheme {pcs}"))),
+    }
+}
+
+/// The parameters of [`ceno_keygen`] and [`ceno_prove`], see `ProveOptions`. Start from
+/// [`ceno_prove_options_default`] to set only some of them.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CenoProveOptions {
+    /// A `CenoPcs`.
+    pub pcs: u32,
+    /// Stack size in bytes.
+    pub stack_size: u32,
+    /// Heap size in bytes.
+    pub heap_size: u32,
+    /// The guest must halt within this many steps.
+    pub max_steps: u64,
+    /// Directory caching proving keys across runs, as a NUL-terminated UTF-8 path, or null.
+    pub key_cache: *const c_char,
+    /// Prove a guest which panics rather than fail with `CENO_STATUS_GUEST_ERROR`.
+    pub prove_panics: bool,
+}
+
+/// A heap buffer of bytes owned by the caller, to be released by [`ceno_bytes_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct CenoBytes {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl From<Vec<u8>> for CenoBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()).cast::<u8>();
+        CenoBytes { data, len }
+    }
+}
+
+/// The verifying key of a program, for the scheme `pcs`.
+pub struct CenoVerifyingKey {
+    pcs: PcsKind,
+    /// A `ZKVMVerifyingKey<E, PCS>`, with `PCS` the scheme of `pcs`.
+    vk: Box<dyn Any>,
+}
+
+impl CenoVerifyingKey {
+    fn new<PCS: PolynomialCommitmentScheme<E> + 'static>(
+        pcs: PcsKind,
+        vk: ZKVMVerifyingKey<E, PCS>,
+    ) -> Self {
+        CenoVerifyingKey {
+            pcs,
+            vk: Box::new(vk),
+        }
+    }
+
+    fn get<PCS: PolynomialCommitmentScheme<E> + 'static>(&self) -> &ZKVMVerifyingKey<E, PCS> {
+        self.vk.downcast_ref().expect("a key of its scheme")
+    }
+}
+
+/// A proof with its public values, see `ProofBundle`.
+pub struct CenoProof {
+    pcs: PcsKind,
+    /// A `ProofBundle<E, PCS>`, with `PCS` the scheme of `pcs`.
+    bundle: Box<dyn Any>,
+}
+
+impl CenoProof {
+    fn new<PCS: PolynomialCommitmentScheme<E> + 'static>(bundle: ProofBundle<E, PCS>) -> Self {
+        CenoProof {
+            pcs: bundle.pcs,
+            bundle: Box::new(bundle),
+        }
+    }
+
+    fn get<PCS: PolynomialCommitmentScheme<E> + 'static>(&self) -> &ProofBundle<E, PCS> {
+        self.bundle.downcast_ref().expect("a proof of its scheme")
+    }
+}
+
+/// The error of a call, reported by its status and by [`ceno_last_error`].
+struct Failure {
+    status: CenoStatus,
+    message: String,
+}
+
+impl Failure {
+    fn invalid(message: impl Into<String>) -> Self {
+        Failure {
+            status: CenoStatus::InvalidArgument,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ZKVMError> for Failure {
+    fn from(err: ZKVMError) -> Self {
+        let mut message = err.to_string();
+        let mut source = err.source();
+        while let Some(err) = source {
+            message.push_str(&format!(": {err}"));
+            source = err.source();
+        }
+        Failure {
+            status: (&err).into(),
+            message,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run the body of an exported function, recording its error for [`ceno_last_error`].
+fn ffi_call(body: impl FnOnce() -> Result<(), Failure>) -> CenoStatus {
+    let result = panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        Err(Failure {
+            status: CenoStatus::Panic,
+            message: format!("panicked: {message}"),
+        })
+    });
+    match result {
+        Ok(()) => CenoStatus::Ok,
+        Err(failure) => {
+            let message = CString::new(failure.message.replace('\0', " ")).unwrap();
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+            failure.status
+        }
+    }
+}
+
+/// The `len` bytes at `data`, which may be null if `len` is 0.
+unsafe fn bytes<'a>(data: *const u8, len: usize, name: &str) -> Result<&'a [u8], Failure> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(Failure::invalid(format!("{name} is null"))),
+        (false, _) => Ok(unsafe { slice::from_raw_parts(data, len) }),
+    }
+}
+
+unsafe fn get<'a, T>(value: *const T, name: &str) -> Result<&'a T, Failure> {
+    unsafe { value.as_ref() }.ok_or_else(|| Failure::invalid(format!("{name} is null")))
+}
+
+unsafe fn get_mut<'a, T>(value: *mut T, name: &str) -> Result<&'a mut T, Failure> {
+    unsafe { value.as_mut() }.ok_or_else(|| Failure::invalid(format!("{name} is null")))
+}
+
+/// The options of `options`, which default if null.
+unsafe fn prove_options(
+    options: *const CenoProveOptions,
+) -> Result<(PcsKind, ProveOptions), Failure> {
+    let options = match unsafe { options.as_ref() } {
+        Some(options) => *options,
+        None => ceno_prove_options_default(),
+    };
+    let key_cache = if options.key_cache.is_null() {
+        None
+    } else {
+        let path = unsafe { CStr::from_ptr(options.key_cache) }
+            .to_str()
+            .map_err(|_| Failure::invalid("key_cache is not UTF-8"))?;
+        Some(PathBuf::from(path))
+    };
+    Ok((pcs_kind(options.pcs)?, ProveOptions {
+        stack_size: options.stack_size,
+        heap_size: options.heap_size,
+        max_steps: options.max_steps.try_into().unwrap_or(usize::MAX),
+        key_cache,
+        prove_panics: options.prove_panics,
+        ..ProveOptions::default()
+    }))
+}
+
+/// The message of the last error of the calling thread, or null if no call failed. The string
+/// is owned by the library, and valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn ceno_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// The default options, as those of `ProveOptions::default` with the default scheme.
+#[no_mangle]
+pub extern "C" fn ceno_prove_options_default() -> CenoProveOptions {
+    let options = ProveOptions::default();
+    CenoProveOptions {
+        pcs: CenoPcs::BasefoldRs as u32,
+        stack_size: options.stack_size,
+        heap_size: options.heap_size,
+        max_steps: u64::MAX,
+        key_cache: ptr::null(),
+        prove_panics: options.prove_panics,
+    }
+}
+
+/// Generate the verifying key of the guest `elf` into `vk_out`, see `sdk::keygen`.
+///
+/// # Safety
+///
+/// `elf` points to `elf_len` bytes, `options` is null or valid, and `vk_out` is writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_keygen(
+    elf: *const u8,
+    elf_len: usize,
+    options: *const CenoProveOptions,
+    vk_out: *mut *mut CenoVerifyingKey,
+) -> CenoStatus {
+    ffi_call(|| {
+        let elf = unsafe { bytes(elf, elf_len, "elf") }?;
+        let (pcs, options) = unsafe { prove_options(options) }?;
+        let vk_out = unsafe { get_mut(vk_out, "vk_out") }?;
+        let vk = with_pcs!(pcs, E, Pcs => {
+            CenoVerifyingKey::new(pcs, keygen::<E, Pcs>(elf, &options)?)
+        });
+        *vk_out = Box::into_raw(Box::new(vk));
+        Ok(())
+    })
+}
+
+/// Run the guest `elf` with the hints `stdin` until it halts, and prove its execution into
+/// `proof_out`, see `sdk::prove`. The verifying key of the program is written to `vk_out`, unless
+/// null; a verifier obtains it from [`ceno_keygen`] rather than from the prover.
+///
+/// # Safety
+///
+/// `elf` and `stdin` point to `elf_len` and `stdin_len` bytes, `options` is null or valid,
+/// `proof_out` is writable, and `vk_out` is null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_prove(
+    elf: *const u8,
+    elf_len: usize,
+    stdin: *const u8,
+    stdin_len: usize,
+    options: *const CenoProveOptions,
+    proof_out: *mut *mut CenoProof,
+    vk_out: *mut *mut CenoVerifyingKey,
+) -> CenoStatus {
+    ffi_call(|| {
+        let elf = unsafe { bytes(elf, elf_len, "elf") }?;
+        let stdin = unsafe { bytes(stdin, stdin_len, "stdin") }?;
+        let (pcs, options) = unsafe { prove_options(options) }?;
+        let proof_out = unsafe { get_mut(proof_out, "proof_out") }?;
+        let vk_out = unsafe { vk_out.as_mut() };
+        let (proof, vk) = with_pcs!(pcs, E, Pcs => {
+            let (proof, vk, public_values) = prove::<E, Pcs>(elf, stdin, &options)?;
+            let bundle = ProofBundle::new(proof, &vk, public_values, elf, pcs)?;
+            (CenoProof::new(bundle), CenoVerifyingKey::new(pcs, vk))
+        });
+        *proof_out = Box::into_raw(Box::new(proof));
+        if let Some(vk_out) = vk_out {
+            *vk_out = Box::into_raw(Box::new(vk));
+        }
+        Ok(())
+    })
+}
+
+/// Verify `proof` against the verifying key `vk` of its program. Returns `CENO_STATUS_OK` if the
+/// proof is valid, and `CENO_STATUS_REJECTED` if not. That the proof is of the expected program
+/// is up to the caller, see [`ceno_proof_program_digest`].
+///
+/// # Safety
+///
+/// `proof` and `vk` are valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_verify(
+    proof: *const CenoProof,
+    vk: *const CenoVerifyingKey,
+) -> CenoStatus {
+    ffi_call(|| {
+        let proof = unsafe { get(proof, "proof") }?;
+        let vk = unsafe { get(vk, "vk") }?;
+        if proof.pcs != vk.pcs {
+            return Err(Failure {
+                status: CenoStatus::Rejected,
+                message: format!("a proof of {} against a key of {}", proof.pcs, vk.pcs),
+            });
+        }
+        with_pcs!(proof.pcs, E, Pcs => proof.get::<Pcs>().clone().verify(vk.get::<Pcs>()))?;
+        Ok(())
+    })
+}
+
+/// Write the 32-byte digest of the program of `proof`, see `program_digest`, to `digest_out`.
+///
+/// # Safety
+///
+/// `proof` is a valid handle, and `digest_out` points to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_proof_program_digest(
+    proof: *const CenoProof,
+    digest_out: *mut u8,
+) -> CenoStatus {
+    ffi_call(|| {
+        let proof = unsafe { get(proof, "proof") }?;
+        if digest_out.is_null() {
+            return Err(Failure::invalid("digest_out is null"));
+        }
+        let digest = with_pcs!(proof.pcs, E, Pcs => proof.get::<Pcs>().program_digest);
+        unsafe { ptr::copy_nonoverlapping(digest.as_ptr(), digest_out, digest.len()) };
+        Ok(())
+    })
+}
+
+/// Write to `has_output_out` whether the output committed by the guest of `proof` is the
+/// `output_len` bytes at `output`, according to its public values. To be trusted after
+/// [`ceno_verify`] only. A guest which does not attest the digest of its output, as `ceno_rt`
+/// does when it halts, has no output.
+///
+/// # Safety
+///
+/// `proof` is a valid handle, `output` points to `output_len` bytes, and `has_output_out` is
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_proof_has_output(
+    proof: *const CenoProof,
+    output: *const u8,
+    output_len: usize,
+    has_output_out: *mut bool,
+) -> CenoStatus {
+    ffi_call(|| {
+        let proof = unsafe { get(proof, "proof") }?;
+        let output = unsafe { bytes(output, output_len, "output") }?;
+        let has_output_out = unsafe { get_mut(has_output_out, "has_output_out") }?;
+        *has_output_out =
+            with_pcs!(proof.pcs, E, Pcs => proof.get::<Pcs>().proof.has_output(output));
+        Ok(())
+    })
+}
+
+/// Serialize `proof` as a `.ceno` bundle into `bytes_out`.
+///
+/// # Safety
+///
+/// `proof` is a valid handle, and `bytes_out` is writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_proof_to_bytes(
+    proof: *const CenoProof,
+    bytes_out: *mut CenoBytes,
+) -> CenoStatus {
+    ffi_call(|| {
+        let proof = unsafe { get(proof, "proof") }?;
+        let bytes_out = unsafe { get_mut(bytes_out, "bytes_out") }?;
+        *bytes_out = with_pcs!(proof.pcs, E, Pcs => proof.get::<Pcs>().to_bytes())?.into();
+        Ok(())
+    })
+}
+
+/// Deserialize a proof of [`ceno_proof_to_bytes`] into `proof_out`.
+///
+/// # Safety
+///
+/// `data` points to `len` bytes, and `proof_out` is writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_proof_from_bytes(
+    data: *const u8,
+    len: usize,
+    proof_out: *mut *mut CenoProof,
+) -> CenoStatus {
+    ffi_call(|| {
+        let data = unsafe { bytes(data, len, "data") }?;
+        let proof_out = unsafe { get_mut(proof_out, "proof_out") }?;
+        let pcs = read_header(data)?.pcs;
+        let proof =
+            with_pcs!(pcs, E, Pcs => CenoProof::new(ProofBundle::<E, Pcs>::from_bytes(data)?));
+        *proof_out = Box::into_raw(Box::new(proof));
+        Ok(())
+    })
+}
+
+/// Serialize `vk` into `bytes_out`.
+///
+/// # Safety
+///
+/// `vk` is a valid handle, and `bytes_out` is writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_vk_to_bytes(
+    vk: *const CenoVerifyingKey,
+    bytes_out: *mut CenoBytes,
+) -> CenoStatus {
+    ffi_call(|| {
+        let vk = unsafe { get(vk, "vk") }?;
+        let bytes_out = unsafe { get_mut(bytes_out, "bytes_out") }?;
+        *bytes_out = with_pcs!(vk.pcs, E, Pcs => to_bytes(vk.get::<Pcs>()))?.into();
+        Ok(())
+    })
+}
+
+/// Deserialize a verifying key of [`ceno_vk_to_bytes`] for the scheme `pcs`, a `CenoPcs`, into
+/// `vk_out`.
+///
+/// # Safety
+///
+/// `data` points to `len` bytes, and `vk_out` is writable.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_vk_from_bytes(
+    pcs: u32,
+    data: *const u8,
+    len: usize,
+    vk_out: *mut *mut CenoVerifyingKey,
+) -> CenoStatus {
+    ffi_call(|| {
+        let pcs = pcs_kind(pcs)?;
+        let data = unsafe { bytes(data, len, "data") }?;
+        let vk_out = unsafe { get_mut(vk_out, "vk_out") }?;
+        let vk = with_pcs!(pcs, E, Pcs => {
+            CenoVerifyingKey::new(pcs, from_bytes::<ZKVMVerifyingKey<E, Pcs>>(data)?)
+        });
+        *vk_out = Box::into_raw(Box::new(vk));
+        Ok(())
+    })
+}
+
+/// Release a proof. Null is ignored.
+///
+/// # Safety
+///
+/// `proof` is null or a handle not yet released.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_proof_free(proof: *mut CenoProof) {
+    if !proof.is_null() {
+        drop(unsafe { Box::from_raw(proof) });
+    }
+}
+
+/// Release a verifying key. Null is ignored.
+///
+/// # Safety
+///
+/// `vk` is null or a handle not yet released.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_vk_free(vk: *mut CenoVerifyingKey) {
+    if !vk.is_null() {
+        drop(unsafe { Box::from_raw(vk) });
+    }
+}
+
+/// Release a buffer of this library.
+///
+/// # Safety
+///
+/// `bytes` is a buffer of this library not yet released, or has null `data`.
+#[no_mangle]
+pub unsafe extern "C" fn ceno_bytes_free(bytes: CenoBytes) {
+    if !bytes.data.is_null() {
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(bytes.data, bytes.len)) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_arguments() {
+        let mut proof = ptr::null_mut();
+        let status = unsafe { ceno_proof_from_bytes(ptr::null(), 4, &mut proof) };
+        assert_eq!(status, CenoStatus::InvalidArgument);
+        assert!(proof.is_null());
+        let message = unsafe { CStr::from_ptr(ceno_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "data is null");
+
+        assert_eq!(
+            unsafe { ceno_verify(ptr::null(), ptr::null()) },
+            CenoStatus::InvalidArgument
+        );
+
+        let mut has_output = true;
+        let status = unsafe { ceno_proof_has_output(ptr::null(), ptr::null(), 0, &mut has_output) };
+        assert_eq!(status, CenoStatus::InvalidArgument);
+    }
+
+    #[test]
+    fn test_unknown_pcs() {
+        let mut vk = ptr::null_mut();
+        let status = unsafe { ceno_vk_from_bytes(7, b"{}".as_ptr(), 2, &mut vk) };
+        assert_eq!(status, CenoStatus::InvalidArgument);
+        assert!(vk.is_null());
+        let message = unsafe { CStr::from_ptr(ceno_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "unknown scheme 7");
+
+        let options = CenoProveOptions {
+            pcs: 7,
+            ..ceno_prove_options_default()
+        };
+        let status = unsafe { ceno_keygen(b"elf".as_ptr(), 3, &options, &mut vk) };
+        assert_eq!(status, CenoStatus::InvalidArgument);
+    }
+
+    #[test]
+    fn test_proof_from_bytes_not_a_bundle() {
+        let mut proof = ptr::null_mut();
+        let status = unsafe { ceno_proof_from_bytes(b"not a proof".as_ptr(), 11, &mut proof) };
+        assert_eq!(status, CenoStatus::Serialization);
+        assert!(proof.is_null());
+        let message = unsafe { CStr::from_ptr(ceno_last_error()) };
+        assert!(message.to_str().unwrap().contains("wrong magic bytes"));
+    }
+}
@@ -0,0 +1,20 @@
+use std::{fs, path::Path};
+
+#[test]
+fn test_header_is_current() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(dir.join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(dir.join("src/lib.rs"))
+        .generate()
+        .unwrap()
+        .write(&mut header);
+    assert!(
+        String::from_utf8(header).unwrap()
+            == fs::read_to_string(dir.join("include/ceno.h")).unwrap(),
+        "include/ceno.h is out of date, regenerate it with \
+         `cbindgen --config cbindgen.toml --output include/ceno.h`"
+    );
+}
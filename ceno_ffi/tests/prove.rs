@@ -0,0 +1,97 @@
+use std::{ffi::CStr, ptr, slice};
+
+use ceno_ffi::*;
+use ceno_zkvm::serialization::program_digest;
+
+fn bytes(bytes: &CenoBytes) -> &[u8] {
+    unsafe { slice::from_raw_parts(bytes.data, bytes.len) }
+}
+
+#[test]
+fn test_prove_verify() {
+    let elf = ceno_examples::ceno_rt_mini;
+    let options = CenoProveOptions {
+        max_steps: 1 << 20,
+        ..ceno_prove_options_default()
+    };
+    let (mut proof, mut vk) = (ptr::null_mut(), ptr::null_mut());
+    let status = unsafe {
+        ceno_prove(
+            elf.as_ptr(),
+            elf.len(),
+            ptr::null(),
+            0,
+            &options,
+            &mut proof,
+            &mut vk,
+        )
+    };
+    assert_eq!(status, CenoStatus::Ok, "{:?}", unsafe {
+        CStr::from_ptr(ceno_last_error())
+    });
+    assert_eq!(unsafe { ceno_verify(proof, vk) }, CenoStatus::Ok);
+
+    let mut digest = [0; 32];
+    assert_eq!(
+        unsafe { ceno_proof_program_digest(proof, digest.as_mut_ptr()) },
+        CenoStatus::Ok
+    );
+    assert_eq!(digest, program_digest(elf));
+
+    // the runtime attests the digest of the output, none here
+    for (output, expected) in [(&b""[..], true), (b"ceno", false)] {
+        let mut has_output = !expected;
+        let status =
+            unsafe { ceno_proof_has_output(proof, output.as_ptr(), output.len(), &mut has_output) };
+        assert_eq!(status, CenoStatus::Ok);
+        assert_eq!(has_output, expected);
+    }
+
+    // the proof and key survive a round trip through bytes
+    let mut proof_bytes = CenoBytes {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+    let mut vk_bytes = CenoBytes {
+        data: ptr::null_mut(),
+        len: 0,
+    };
+    unsafe {
+        assert_eq!(ceno_proof_to_bytes(proof, &mut proof_bytes), CenoStatus::Ok);
+        assert_eq!(ceno_vk_to_bytes(vk, &mut vk_bytes), CenoStatus::Ok);
+        ceno_proof_free(proof);
+        ceno_vk_free(vk);
+    }
+    let (mut proof, mut vk) = (ptr::null_mut(), ptr::null_mut());
+    let (proof_data, vk_data) = (bytes(&proof_bytes), bytes(&vk_bytes));
+    unsafe {
+        assert_eq!(
+            ceno_proof_from_bytes(proof_data.as_ptr(), proof_data.len(), &mut proof),
+            CenoStatus::Ok
+        );
+        assert_eq!(
+            ceno_vk_from_bytes(options.pcs, vk_data.as_ptr(), vk_data.len(), &mut vk),
+            CenoStatus::Ok
+        );
+        assert_eq!(ceno_verify(proof, vk), CenoStatus::Ok);
+        ceno_vk_free(vk);
+    }
+
+    // a key of another scheme
+    let mut other_vk = ptr::null_mut();
+    let other = CenoProveOptions {
+        pcs: CenoPcs::BasefoldBasecode as u32,
+        ..options
+    };
+    unsafe {
+        assert_eq!(
+            ceno_keygen(elf.as_ptr(), elf.len(), &other, &mut other_vk),
+            CenoStatus::Ok
+        );
+        assert_eq!(ceno_verify(proof, other_vk), CenoStatus::Rejected);
+        ceno_vk_free(other_vk);
+        ceno_proof_free(proof);
+        ceno_bytes_free(proof_bytes);
+        ceno_bytes_free(vk_bytes);
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+use serde::{Serialize, de::DeserializeOwned};
+
+pub use postcard::Error;
+
+pub(crate) const WORD_SIZE: usize = 4;
+
+/// Encode `value` for the hint tape, to read with `read`: its length in bytes as a word, followed
+/// by its encoding, zero-padded to a word. The frames of several values are concatenated.
+/// See `CenoStdin` to build the tape of several values.
+pub fn to_hint_frame<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    Ok(frame(&postcard::to_allocvec(value)?))
+}
+
+/// The frame of `bytes`: their length as a word, followed by them, zero-padded to a word.
+pub(crate) fn frame(bytes: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(WORD_SIZE + bytes.len().next_multiple_of(WORD_SIZE));
+    frame.extend((bytes.len() as u32).to_le_bytes());
+    frame.extend(bytes);
+    frame.resize(frame.len().next_multiple_of(WORD_SIZE), 0);
+    frame
+}
+
+/// Encode `value` as the public input, to read with `read_public`.
+pub fn to_public_input<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    postcard::to_allocvec(value)
+}
+
+/// Decode the next value committed with `commit` from the public output, and advance `output`
+/// past it.
+pub fn take_committed<T: DeserializeOwned>(output: &mut &[u8]) -> Result<T, Error> {
+    let (value, rest) = postcard::take_from_bytes(output)?;
+    *output = rest;
+    Ok(value)
+}
+
+/// Decode a value from the payload of a hint frame, or from the public input.
+#[cfg_attr(not(target_arch = "riscv32"), allow(dead_code))]
+pub(crate) fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    postcard::from_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec};
+
+    use super::*;
+
+    #[test]
+    fn test_hint_frame() {
+        let frame = to_hint_frame(&(7u8, String::from("ceno"))).unwrap();
+        assert_eq!(frame.len() % WORD_SIZE, 0);
+        let len = u32::from_le_bytes(frame[..WORD_SIZE].try_into().unwrap()) as usize;
+        assert_eq!(len, 6);
+        let value: (u8, String) = from_bytes(&frame[WORD_SIZE..WORD_SIZE + len]).unwrap();
+        assert_eq!(value, (7, String::from("ceno")));
+    }
+
+    #[test]
+    fn test_take_committed() {
+        let mut output = postcard::to_allocvec(&42u32).unwrap();
+        output.extend(postcard::to_allocvec(&vec![1u64, 2, 3]).unwrap());
+
+        let mut output = output.as_slice();
+        assert_eq!(take_committed::<u32>(&mut output).unwrap(), 42);
+        assert_eq!(take_committed::<Vec<u64>>(&mut output).unwrap(), [1, 2, 3]);
+        assert!(output.is_empty());
+        assert!(take_committed::<u32>(&mut output).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use alloc::vec::Vec;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::frame::{WORD_SIZE, from_bytes};
+
+/// Read the next value of the hint tape, written by the host with `CenoStdin::write` or
+/// `to_hint_frame`.
+///
+/// Hints are advice from the prover and are not part of the proof: the guest must check whatever
+/// it relies on.
+pub fn read<T: DeserializeOwned>() -> T {
+    from_bytes(&read_bytes()).expect("malformed hint")
+}
+
+/// Read the next bytes of the hint tape, written by the host with `CenoStdin::write_bytes`, or
+/// the encoding of a value written with `CenoStdin::write`. As for `read`, they are advice.
+pub fn read_bytes() -> Vec<u8> {
+    let len = ceno_rt::read_hint() as usize;
+    (0..len.div_ceil(WORD_SIZE))
+        .flat_map(|_| ceno_rt::read_hint().to_le_bytes())
+        .take(len)
+        .collect()
+}
+
+/// Read the public input, written by the host with `to_public_input`.
+pub fn read_public<T: DeserializeOwned>() -> T {
+    from_bytes(ceno_rt::read_public_input()).expect("malformed public input")
+}
+
+/// Commit `value` to the public output, to read on the host with `take_committed`.
+pub fn commit<T: Serialize + ?Sized>(value: &T) {
+    ceno_rt::commit(&postcard::to_allocvec(value).expect("unserializable output"));
+}
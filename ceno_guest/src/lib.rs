@@ -0,0 +1,33 @@
+//! The guest SDK: read typed values from the host, and commit typed values to the proof.
+//!
+//! On the guest, see [`read`], [`read_bytes`], [`read_public`] and [`commit`]. On the host, the
+//! same encoding is provided by [`CenoStdin`], [`to_public_input`] and [`take_committed`].
+//!
+//! Hashing with `sha256` runs on the SHA-256 precompile of the VM, see `sha256_compress`, and
+//! with `keccak256` on its Keccak precompile, see `keccak_permute`. Modular arithmetic on 256-bit
+//! numbers, e.g. in the fields of secp256k1 or BN254, runs on `uint256_addmod` and
+//! `uint256_mulmod`, the group law of secp256k1 on `secp256k1_add` and `secp256k1_double`, and
+//! that of ed25519 on `ed25519_add`. Merkle trees for recursion hash with `poseidon2_compress`, on
+//! the precompile of the Poseidon2 permutation, see `poseidon2_permute`.
+#![deny(clippy::cargo)]
+#![no_std]
+
+extern crate alloc;
+
+mod frame;
+pub use frame::{Error, take_committed, to_hint_frame, to_public_input};
+
+mod stdin;
+pub use stdin::CenoStdin;
+
+#[cfg(target_arch = "riscv32")]
+mod io;
+#[cfg(target_arch = "riscv32")]
+pub use io::{commit, read, read_bytes, read_public};
+
+#[cfg(target_arch = "riscv32")]
+pub use ceno_rt::{
+    ed25519_add, entry, halt, keccak_permute, keccak256, poseidon2_compress, poseidon2_permute,
+    print, println, secp256k1_add, secp256k1_double, sha256, sha256_compress, uint256_addmod,
+    uint256_mulmod,
+};
@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+use serde::Serialize;
+
+use crate::frame::{Error, WORD_SIZE, frame};
+
+/// The hint tape of a guest, built on the host from typed values, which the guest reads back in
+/// the same order with `read` and `read_bytes`.
+///
+/// Every value is a frame: its length in bytes as a word, followed by its bytes, zero-padded to a
+/// word, so that each value starts on a word of the tape.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CenoStdin {
+    tape: Vec<u8>,
+}
+
+impl CenoStdin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `value`, for the guest to read with `read::<T>()`.
+    pub fn write<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<&mut Self, Error> {
+        self.tape.extend(frame(&postcard::to_allocvec(value)?));
+        Ok(self)
+    }
+
+    /// Append `bytes` as they are, for the guest to read with `read_bytes()`, without the cost of
+    /// decoding them.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.tape.extend(frame(bytes));
+        self
+    }
+
+    /// The bytes of the tape, e.g. for `VMState::write_hints` or `ceno_zkvm::prove`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.tape
+    }
+
+    /// The words of the tape, as stored in the hints region of the guest memory.
+    pub fn to_words(&self) -> Vec<u32> {
+        self.tape
+            .chunks_exact(WORD_SIZE)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+}
+
+impl AsRef<[u8]> for CenoStdin {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec};
+
+    use super::*;
+    use crate::frame::{from_bytes, to_hint_frame};
+
+    #[test]
+    fn test_stdin() {
+        let mut stdin = CenoStdin::new();
+        stdin
+            .write(&(7u8, String::from("ceno")))
+            .unwrap()
+            .write_bytes(&[1, 2, 3, 4, 5]);
+        stdin.write(&vec![1u64, 2, 3]).unwrap();
+
+        let mut tape = stdin.as_bytes();
+        assert_eq!(
+            &tape[..12],
+            to_hint_frame(&(7u8, String::from("ceno"))).unwrap()
+        );
+        let mut next_frame = || {
+            let len = u32::from_le_bytes(tape[..WORD_SIZE].try_into().unwrap()) as usize;
+            let (frame, rest) = tape.split_at(WORD_SIZE + len.next_multiple_of(WORD_SIZE));
+            tape = rest;
+            &frame[WORD_SIZE..WORD_SIZE + len]
+        };
+        assert_eq!(
+            from_bytes::<(u8, String)>(next_frame()).unwrap(),
+            (7, String::from("ceno"))
+        );
+        assert_eq!(next_frame(), [1, 2, 3, 4, 5]);
+        assert_eq!(from_bytes::<Vec<u64>>(next_frame()).unwrap(), [1, 2, 3]);
+        assert!(tape.is_empty());
+        assert_eq!(stdin.to_words().len() * WORD_SIZE, stdin.as_bytes().len());
+    }
+}
@@ -0,0 +1,548 @@
+//! Python bindings to the zkVM, as the `ceno` module: execute a guest and profile it, prove and
+//! verify its execution, and inspect the statistics of the circuits, e.g. from a notebook.
+//!
+//! The module is built and installed into the current Python environment by maturin, see the
+//! README. The long calls, to execute, prove and verify, release the GIL.
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use ceno_emul::{
+    CENO_PLATFORM, CostModel, CostTable, InsnKind, IterAddresses, Platform, Symbols, VMState,
+    WORD_SIZE, Word,
+};
+use ceno_zkvm::{
+    error::ZKVMError,
+    instructions::riscv::{DummyExtraConfig, Rv32imConfig},
+    scheme::pcs::{PcsKind, PolynomialCommitmentScheme},
+    sdk,
+    serialization::{ProofBundle, from_bytes, read_header, to_bytes},
+    structs::{ZKVMConstraintSystem, ZKVMVerifyingKey},
+    with_pcs,
+};
+use goldilocks::GoldilocksExt2;
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+use strum::IntoEnumIterator;
+use transcript::TranscriptHash;
+
+type E = GoldilocksExt2;
+
+create_exception!(
+    ceno,
+    CenoError,
+    PyException,
+    "An error of the zkVM or of the guest."
+);
+
+/// A `CenoError` with the message of `err` and of its sources.
+fn ceno_error(err: &dyn Error) -> PyErr {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(&format!(": {err}"));
+        source = err.source();
+    }
+    CenoError::new_err(message)
+}
+
+fn zkvm<T>(result: Result<T, ZKVMError>) -> PyResult<T> {
+    result.map_err(|err| ceno_error(&err))
+}
+
+/// The instruction kind named `name`, as displayed in profiles, e.g. `ADD`.
+fn insn_kind(name: &str) -> PyResult<InsnKind> {
+    InsnKind::iter()
+        .find(|kind| kind.to_string() == name)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown instruction kind {name}")))
+}
+
+/// The costs of the instruction kinds as measured from the circuits, see
+/// `ZKVMConstraintSystem::cost_table`.
+fn circuit_costs() -> PyResult<&'static CostTable> {
+    static COSTS: OnceLock<CostTable> = OnceLock::new();
+    if let Some(costs) = COSTS.get() {
+        return Ok(costs);
+    }
+    let costs = zkvm(constraint_system())?.cost_table();
+    Ok(COSTS.get_or_init(|| costs))
+}
+
+/// The constraint systems of the instruction circuits.
+fn constraint_system() -> Result<ZKVMConstraintSystem<E>, ZKVMError> {
+    let mut cs = ZKVMConstraintSystem::default();
+    Rv32imConfig::<E>::construct_circuits(&mut cs)?;
+    DummyExtraConfig::<E>::construct_circuits(&mut cs)?;
+    Ok(cs)
+}
+
+/// The hints `stdin` as words, as they are laid out in memory.
+fn hint_words(platform: &Platform, stdin: &[u8]) -> PyResult<Vec<Word>> {
+    let mut hints = stdin.to_vec();
+    hints.resize(hints.len().next_multiple_of(WORD_SIZE), 0);
+    let hints = hints
+        .chunks_exact(WORD_SIZE)
+        .map(|word| Word::from_le_bytes(word.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    if hints.len() > platform.hints.iter_addresses().len() {
+        return Err(PyValueError::new_err(format!(
+            "stdin must fit in {} bytes",
+            platform.hints.len()
+        )));
+    }
+    Ok(hints)
+}
+
+/// The parameters of `keygen` and `prove`, see `sdk::ProveOptions`. An unset parameter takes
+/// its default value.
+#[pyclass(frozen, module = "ceno")]
+#[derive(Clone, Default)]
+struct ProveOptions {
+    pcs: PcsKind,
+    options: sdk::ProveOptions,
+}
+
+#[pymethods]
+impl ProveOptions {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        pcs = None,
+        max_steps = None,
+        stack_size = None,
+        heap_size = None,
+        key_cache = None,
+        transcript_hash = None,
+        prove_panics = false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        pcs: Option<&str>,
+        max_steps: Option<usize>,
+        stack_size: Option<u32>,
+        heap_size: Option<u32>,
+        key_cache: Option<PathBuf>,
+        transcript_hash: Option<&str>,
+        prove_panics: bool,
+    ) -> PyResult<Self> {
+        let defaults = sdk::ProveOptions::default();
+        let pcs = pcs
+            .map(str::parse)
+            .transpose()
+            .map_err(PyValueError::new_err)?
+            .unwrap_or_default();
+        let transcript_hash = transcript_hash
+            .map(str::parse::<TranscriptHash>)
+            .transpose()
+            .map_err(PyValueError::new_err)?
+            .unwrap_or(defaults.transcript_hash);
+        Ok(ProveOptions {
+            pcs,
+            options: sdk::ProveOptions {
+                max_steps: max_steps.unwrap_or(defaults.max_steps),
+                stack_size: stack_size.unwrap_or(defaults.stack_size),
+                heap_size: heap_size.unwrap_or(defaults.heap_size),
+                key_cache,
+                transcript_hash,
+                prove_panics,
+                ..defaults
+            },
+        })
+    }
+
+    #[getter]
+    fn pcs(&self) -> String {
+        self.pcs.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        let options = &self.options;
+        let key_cache = options
+            .key_cache
+            .as_ref()
+            .map_or("None".to_string(), |path| {
+                format!("{:?}", path.display().to_string())
+            });
+        format!(
+            "ProveOptions(pcs={:?}, max_steps={}, stack_size={}, heap_size={}, key_cache={}, \
+             transcript_hash={:?}, prove_panics={})",
+            self.pcs.to_string(),
+            options.max_steps,
+            options.stack_size,
+            options.heap_size,
+            key_cache,
+            format!("{:?}", options.transcript_hash).to_lowercase(),
+            if options.prove_panics {
+                "True"
+            } else {
+                "False"
+            },
+        )
+    }
+}
+
+/// Counts of the executed instructions, by kind and by pc, see `ceno_emul::Profile`.
+#[pyclass(frozen, module = "ceno")]
+struct Profile(ceno_emul::Profile);
+
+#[pymethods]
+impl Profile {
+    /// The instruction counts by kind.
+    #[getter]
+    fn by_kind(&self) -> BTreeMap<String, u64> {
+        self.0
+            .by_kind
+            .iter()
+            .map(|(kind, &count)| (kind.to_string(), count))
+            .collect()
+    }
+
+    /// The instruction counts by pc.
+    #[getter]
+    fn by_pc(&self) -> HashMap<Word, u64> {
+        self.0.by_pc.clone()
+    }
+
+    /// The number of executed instructions.
+    #[getter]
+    fn total(&self) -> u64 {
+        self.0.total()
+    }
+
+    /// The proving cost of the executed instructions, with `costs` the cost of each kind, or
+    /// else the costs measured from the circuits, see `cost_table`. The kinds missing from
+    /// `costs` cost 1.
+    #[pyo3(signature = (costs = None))]
+    fn prover_cycles(&self, costs: Option<HashMap<String, u64>>) -> PyResult<u64> {
+        let Some(costs) = costs else {
+            return Ok(self.0.prover_cycles(circuit_costs()?));
+        };
+        let mut table = CostTable::default();
+        for (name, cost) in costs {
+            table.set_cost(insn_kind(&name)?, cost);
+        }
+        Ok(self.0.prover_cycles(&table))
+    }
+
+    /// The instruction counts by function of the guest `elf`, most executed first.
+    fn hotspots(&self, elf: &[u8]) -> PyResult<Vec<(String, u64)>> {
+        let symbols =
+            Symbols::from_elf(elf).map_err(|err| CenoError::new_err(format!("{err:#}")))?;
+        Ok(self.0.hotspots(&symbols))
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// The outcome of `execute`.
+#[pyclass(frozen, module = "ceno")]
+struct Execution {
+    /// The exit code of the guest, if it halted.
+    #[pyo3(get)]
+    exit_code: Option<u32>,
+    #[pyo3(get)]
+    cycles: u64,
+    /// The panic message of the guest, if it panicked.
+    #[pyo3(get)]
+    panic: Option<String>,
+    #[pyo3(get)]
+    profile: Py<Profile>,
+    output: Vec<u8>,
+}
+
+#[pymethods]
+impl Execution {
+    /// The output committed by the guest.
+    #[getter]
+    fn output<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.output)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Execution(exit_code={:?}, cycles={}, panic={:?})",
+            self.exit_code, self.cycles, self.panic
+        )
+    }
+}
+
+/// The verifying key of a program.
+#[pyclass(frozen, module = "ceno")]
+struct VerifyingKey {
+    pcs: PcsKind,
+    /// A `ZKVMVerifyingKey<E, PCS>`, with `PCS` the scheme of `pcs`.
+    vk: Box<dyn Any + Send + Sync>,
+}
+
+impl VerifyingKey {
+    fn new<PCS: PolynomialCommitmentScheme<E> + 'static>(
+        pcs: PcsKind,
+        vk: ZKVMVerifyingKey<E, PCS>,
+    ) -> Self
+    where
+        ZKVMVerifyingKey<E, PCS>: Send + Sync,
+    {
+        VerifyingKey {
+            pcs,
+            vk: Box::new(vk),
+        }
+    }
+
+    fn get<PCS: PolynomialCommitmentScheme<E> + 'static>(&self) -> &ZKVMVerifyingKey<E, PCS> {
+        self.vk.downcast_ref().expect("a key of its scheme")
+    }
+}
+
+#[pymethods]
+impl VerifyingKey {
+    /// The polynomial commitment scheme of the key.
+    #[getter]
+    fn pcs(&self) -> String {
+        self.pcs.to_string()
+    }
+
+    /// The digest of the key, see `ZKVMVerifyingKey::digest`.
+    #[getter]
+    fn digest<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let digest = zkvm(with_pcs!(self.pcs, E, Pcs => self.get::<Pcs>().digest()))?;
+        Ok(PyBytes::new(py, &digest))
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = zkvm(with_pcs!(self.pcs, E, Pcs => to_bytes(self.get::<Pcs>())))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Deserialize a key of `to_bytes`, for the scheme `pcs`, which the bytes do not record.
+    #[staticmethod]
+    #[pyo3(signature = (data, pcs = "basefold-rs"))]
+    fn from_bytes(data: &[u8], pcs: &str) -> PyResult<Self> {
+        let pcs: PcsKind = pcs.parse().map_err(PyValueError::new_err)?;
+        zkvm(with_pcs!(pcs, E, Pcs => {
+            from_bytes::<ZKVMVerifyingKey<E, Pcs>>(data).map(|vk| VerifyingKey::new(pcs, vk))
+        }))
+    }
+}
+
+/// A proof with its public values, see `ProofBundle`.
+#[pyclass(frozen, module = "ceno")]
+struct Proof {
+    pcs: PcsKind,
+    /// A `ProofBundle<E, PCS>`, with `PCS` the scheme of `pcs`.
+    bundle: Box<dyn Any + Send + Sync>,
+}
+
+impl Proof {
+    fn new<PCS: PolynomialCommitmentScheme<E> + 'static>(bundle: ProofBundle<E, PCS>) -> Self
+    where
+        ProofBundle<E, PCS>: Send + Sync,
+    {
+        Proof {
+            pcs: bundle.pcs,
+            bundle: Box::new(bundle),
+        }
+    }
+
+    fn get<PCS: PolynomialCommitmentScheme<E> + 'static>(&self) -> &ProofBundle<E, PCS> {
+        self.bundle.downcast_ref().expect("a proof of its scheme")
+    }
+}
+
+#[pymethods]
+impl Proof {
+    /// The polynomial commitment scheme of the proof.
+    #[getter]
+    fn pcs(&self) -> String {
+        self.pcs.to_string()
+    }
+
+    /// The digest of the program of the proof, see `program_digest`.
+    #[getter]
+    fn program_digest<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let digest = with_pcs!(self.pcs, E, Pcs => self.get::<Pcs>().program_digest);
+        PyBytes::new(py, &digest)
+    }
+
+    /// Whether the output committed by the guest is `output`, according to the public values.
+    /// To be trusted after `verify` only. A guest which does not attest the digest of its output,
+    /// as `ceno_rt` does when it halts, has no output.
+    fn has_output(&self, output: &[u8]) -> bool {
+        with_pcs!(self.pcs, E, Pcs => self.get::<Pcs>().proof.has_output(output))
+    }
+
+    /// The number of proven instances of each circuit.
+    fn num_instances(&self) -> BTreeMap<String, usize> {
+        with_pcs!(self.pcs, E, Pcs => {
+            let proof = &self.get::<Pcs>().proof;
+            proof
+                .circuit_names()
+                .map(|name| (name.to_string(), proof.num_instances(name)))
+                .collect()
+        })
+    }
+
+    /// Serialize the proof as a `.ceno` bundle.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = zkvm(with_pcs!(self.pcs, E, Pcs => self.get::<Pcs>().to_bytes()))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Deserialize a `.ceno` bundle.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let pcs = zkvm(read_header(data))?.pcs;
+        zkvm(with_pcs!(pcs, E, Pcs => ProofBundle::<E, Pcs>::from_bytes(data).map(Proof::new)))
+    }
+}
+
+/// Run the guest `elf` with the hints `stdin` until it halts, or for at most `max_steps` steps,
+/// and profile its instructions.
+#[pyfunction]
+#[pyo3(signature = (elf, stdin = None, max_steps = None))]
+fn execute(
+    py: Python<'_>,
+    elf: &[u8],
+    stdin: Option<&[u8]>,
+    max_steps: Option<usize>,
+) -> PyResult<Execution> {
+    let stdin = stdin.unwrap_or_default();
+    let hints = hint_words(&CENO_PLATFORM, stdin)?;
+    let (profile, exit_code, cycles, panic, output) = py.allow_threads(|| {
+        let mut vm = VMState::new_from_elf(CENO_PLATFORM, elf)
+            .map_err(|err| CenoError::new_err(format!("invalid ELF: {err:#}")))?;
+        vm.write_hints(stdin);
+        for (addr, value) in CENO_PLATFORM.hints.iter_addresses().zip(hints) {
+            vm.init_memory(addr.into(), value);
+        }
+
+        let mut profile = ceno_emul::Profile::default();
+        let mut exit_code = None;
+        for step in vm.iter_until_halt().take(max_steps.unwrap_or(usize::MAX)) {
+            let step = step.map_err(|err| CenoError::new_err(format!("{err:#}")))?;
+            profile.record(&step);
+            if step.insn().codes().kind == InsnKind::EANY
+                && step.rs1().unwrap().value == Platform::ecall_halt()
+            {
+                exit_code = step.rs2().map(|rs2| rs2.value);
+            }
+        }
+        let panic = vm.guest_panic().map(ToString::to_string);
+        PyResult::Ok((
+            profile,
+            exit_code,
+            vm.tracer().cycle(),
+            panic,
+            vm.committed_output().to_vec(),
+        ))
+    })?;
+    Ok(Execution {
+        exit_code,
+        cycles,
+        panic,
+        profile: Py::new(py, Profile(profile))?,
+        output,
+    })
+}
+
+/// Generate the verifying key of the guest `elf`, see `sdk::keygen`.
+#[pyfunction]
+#[pyo3(signature = (elf, options = None))]
+fn keygen(py: Python<'_>, elf: &[u8], options: Option<ProveOptions>) -> PyResult<VerifyingKey> {
+    let ProveOptions { pcs, options } = options.unwrap_or_default();
+    zkvm(py.allow_threads(|| {
+        with_pcs!(pcs, E, Pcs => {
+            sdk::keygen::<E, Pcs>(elf, &options).map(|vk| VerifyingKey::new(pcs, vk))
+        })
+    }))
+}
+
+/// Run the guest `elf` with the hints `stdin` until it halts, and prove its execution, see
+/// `sdk::prove`. Return the proof and the verifying key of the program; a verifier obtains the
+/// key from `keygen` rather than from the prover.
+#[pyfunction]
+#[pyo3(signature = (elf, stdin = None, options = None))]
+fn prove(
+    py: Python<'_>,
+    elf: &[u8],
+    stdin: Option<&[u8]>,
+    options: Option<ProveOptions>,
+) -> PyResult<(Proof, VerifyingKey)> {
+    let stdin = stdin.unwrap_or_default();
+    let ProveOptions { pcs, options } = options.unwrap_or_default();
+    zkvm(py.allow_threads(|| {
+        with_pcs!(pcs, E, Pcs => {
+            let (proof, vk, public_values) = sdk::prove::<E, Pcs>(elf, stdin, &options)?;
+            let bundle = ProofBundle::new(proof, &vk, public_values, elf, pcs)?;
+            Ok((Proof::new(bundle), VerifyingKey::new(pcs, vk)))
+        })
+    }))
+}
+
+/// Verify `proof` against the verifying key `vk` of its program, and raise a `CenoError` if it
+/// is not valid. That the proof is of the expected program is up to the caller, who compares
+/// `proof.program_digest` with that of the ELF it trusts.
+#[pyfunction]
+fn verify(py: Python<'_>, proof: &Proof, vk: &VerifyingKey) -> PyResult<()> {
+    if proof.pcs != vk.pcs {
+        return Err(CenoError::new_err(format!(
+            "a proof of {} against a key of {}",
+            proof.pcs, vk.pcs
+        )));
+    }
+    zkvm(py.allow_threads(
+        || with_pcs!(proof.pcs, E, Pcs => proof.get::<Pcs>().clone().verify(vk.get::<Pcs>())),
+    ))
+}
+
+/// The proving cost of an instruction of each kind, as measured from its circuit, see
+/// `ZKVMConstraintSystem::cost_table`.
+#[pyfunction]
+fn cost_table() -> PyResult<BTreeMap<String, u64>> {
+    let costs = circuit_costs()?;
+    Ok(InsnKind::iter()
+        .map(|kind| (kind.to_string(), costs.cost(kind)))
+        .collect())
+}
+
+/// The statistics of the constraint system of each instruction circuit, see
+/// `ConstraintSystem::stats`: the numbers of witnesses, reads, writes, constraints by degree
+/// and lookups by table, in total and by namespace.
+#[pyfunction]
+fn circuit_stats(py: Python<'_>) -> PyResult<PyObject> {
+    let cs = zkvm(constraint_system())?;
+    let stats = cs
+        .get_css()
+        .iter()
+        .map(|(name, cs)| (name, cs.stats()))
+        .collect::<BTreeMap<_, _>>();
+    let json = serde_json::to_string(&stats).map_err(|err| CenoError::new_err(err.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+#[pymodule]
+fn ceno(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("CenoError", m.py().get_type::<CenoError>())?;
+    m.add_class::<ProveOptions>()?;
+    m.add_class::<Profile>()?;
+    m.add_class::<Execution>()?;
+    m.add_class::<VerifyingKey>()?;
+    m.add_class::<Proof>()?;
+    m.add_function(wrap_pyfunction!(execute, m)?)?;
+    m.add_function(wrap_pyfunction!(keygen, m)?)?;
+    m.add_function(wrap_pyfunction!(prove, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(cost_table, m)?)?;
+    m.add_function(wrap_pyfunction!(circuit_stats, m)?)?;
+    Ok(())
+}
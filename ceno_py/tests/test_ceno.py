@@ -0,0 +1,68 @@
+"""Tests of the `ceno` module, run by pytest after `maturin develop`.
+
+The tests which run a guest take its ELF from the path in `CENO_TEST_ELF`, e.g. the `ceno_rt_mini`
+example, which halts with exit code 0, and are skipped if it is not set.
+"""
+
+import os
+
+import pytest
+
+import ceno
+
+
+@pytest.fixture
+def elf():
+    path = os.environ.get("CENO_TEST_ELF")
+    if path is None:
+        pytest.skip("CENO_TEST_ELF is not set")
+    with open(path, "rb") as file:
+        return file.read()
+
+
+def test_prove_options():
+    options = ceno.ProveOptions(pcs="basefold-basecode", max_steps=1000)
+    assert options.pcs == "basefold-basecode"
+    with pytest.raises(ValueError, match="unknown polynomial commitment scheme"):
+        ceno.ProveOptions(pcs="kzg")
+
+
+def test_invalid_inputs():
+    with pytest.raises(ceno.CenoError, match="invalid ELF"):
+        ceno.execute(b"not an elf")
+    with pytest.raises(ceno.CenoError, match="wrong magic bytes"):
+        ceno.Proof.from_bytes(b"not a proof")
+
+
+def test_circuit_stats():
+    stats = ceno.circuit_stats()
+    assert stats["ADD"]["witnesses"] > 0
+    costs = ceno.cost_table()
+    assert costs["DIVU"] > costs["ADD"]
+
+
+def test_execute(elf):
+    execution = ceno.execute(elf)
+    assert execution.exit_code == 0
+    assert execution.panic is None
+    profile = execution.profile
+    assert profile.total == sum(profile.by_kind.values())
+    assert profile.prover_cycles({}) == profile.total
+    assert profile.prover_cycles() >= profile.total
+    assert sum(count for _, count in profile.hotspots(elf)) == profile.total
+
+
+def test_prove_verify(elf):
+    options = ceno.ProveOptions(max_steps=1 << 20)
+    proof, vk = ceno.prove(elf, options=options)
+    ceno.verify(proof, vk)
+    assert proof.num_instances()
+
+    # the verifier generates the key itself, and receives the proof as bytes
+    vk = ceno.VerifyingKey.from_bytes(ceno.keygen(elf, options).to_bytes())
+    proof = ceno.Proof.from_bytes(proof.to_bytes())
+    ceno.verify(proof, vk)
+
+    other = ceno.keygen(elf, ceno.ProveOptions(pcs="basefold-basecode"))
+    with pytest.raises(ceno.CenoError):
+        ceno.verify(proof, other)
@@ -0,0 +1,22 @@
+use core::arch::asm;
+
+/// The code of ecall ED25519_ADD.
+const ECALL_ED25519_ADD: u32 = 12;
+
+/// `p = p + q` for points of the twisted Edwards curve of ed25519, with the precompile of the
+/// VM. A point is `x` then `y`, each as 8 little-endian words, and the coordinates must be
+/// reduced. The addition law is complete: `p + p` doubles, and `(0, 1)` is the neutral point.
+pub fn ed25519_add(p: &mut [u32; 16], q: &[u32; 16]) {
+    // The ecall takes `p` then `q`, and replaces `p` by the sum.
+    let mut words = [0u32; 32];
+    words[..16].copy_from_slice(p);
+    words[16..].copy_from_slice(q);
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_ED25519_ADD,
+            in("a0") words.as_mut_ptr(),
+        );
+    }
+    p.copy_from_slice(&words[..16]);
+}
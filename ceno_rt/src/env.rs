@@ -0,0 +1,44 @@
+use core::arch::asm;
+
+/// The code of ecall READ_HINT.
+const ECALL_READ_HINT: u32 = 2;
+/// The code of ecall CLOCK.
+const ECALL_CLOCK: u32 = 3;
+/// The code of ecall RANDOM.
+const ECALL_RANDOM: u32 = 5;
+
+/// Run the ecall `function`, which stores a word at `a0`.
+fn ecall_word(function: u32) -> u32 {
+    let mut word = 0u32;
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") function,
+            in("a0") &mut word as *mut u32,
+        );
+    }
+    word
+}
+
+/// The next word of the hint tape written by the host, zero-padded at its end.
+pub fn read_hint() -> u32 {
+    ecall_word(ECALL_READ_HINT)
+}
+
+/// The time set by the host, never earlier than the previous one. By default, the cycle.
+pub fn clock() -> u32 {
+    ecall_word(ECALL_CLOCK)
+}
+
+/// A word of the pseudo-random stream seeded by the host. It is the same on every execution
+/// with the same seed, and is not secret.
+pub fn random_word() -> u32 {
+    ecall_word(ECALL_RANDOM)
+}
+
+/// Fill `buf` with bytes of the pseudo-random stream, see [`random_word`].
+pub fn fill_random(buf: &mut [u8]) {
+    for chunk in buf.chunks_mut(size_of::<u32>()) {
+        chunk.copy_from_slice(&random_word().to_le_bytes()[..chunk.len()]);
+    }
+}
@@ -1,5 +1,24 @@
-use crate::{INFO_OUT_ADDR, WORD_SIZE};
-use core::{cell::Cell, fmt, mem::size_of, slice};
+use crate::{INFO_OUT_ADDR, PUBLIC_IO_ADDR, WORD_SIZE, sha256::sha256_words};
+use alloc::{vec, vec::Vec};
+use core::{
+    arch::asm,
+    cell::{Cell, UnsafeCell},
+    fmt,
+    mem::size_of,
+    slice,
+};
+
+/// The code of ecall WRITE.
+const ECALL_WRITE: u32 = 1;
+/// The code of ecall COMMIT.
+const ECALL_COMMIT: u32 = 4;
+/// The code of ecall COMMIT_DIGEST.
+const ECALL_COMMIT_DIGEST: u32 = 15;
+
+/// The file descriptor of the standard output of the host.
+pub const FD_STDOUT: u32 = 1;
+/// The file descriptor of the standard error of the host.
+pub const FD_STDERR: u32 = 2;
 
 static INFO_OUT: IOWriter = IOWriter::new(INFO_OUT_ADDR);
 
@@ -60,6 +79,81 @@ impl fmt::Write for &IOWriter {
     }
 }
 
+/// The public input written by the host: a length in bytes, followed by the bytes.
+pub fn read_public_input() -> &'static [u8] {
+    let len = unsafe { *(PUBLIC_IO_ADDR as *const u32) } as usize;
+    unsafe { slice::from_raw_parts((PUBLIC_IO_ADDR as usize + WORD_SIZE) as *const u8, len) }
+}
+
+/// Run the ecall `function` on a frame of `bytes`: their length, followed by the bytes.
+fn ecall_frame(function: u32, arg1: u32, bytes: &[u8]) {
+    let mut frame = vec![0u32; 1 + bytes.len().div_ceil(WORD_SIZE)];
+    frame[0] = bytes.len() as u32;
+    let frame_bytes =
+        unsafe { slice::from_raw_parts_mut(frame[1..].as_mut_ptr() as *mut u8, bytes.len()) };
+    frame_bytes.copy_from_slice(bytes);
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") function,
+            in("a0") frame.as_ptr(),
+            in("a1") arg1,
+        );
+    }
+}
+
+/// The bytes committed so far, hashed when the guest halts.
+struct Output(UnsafeCell<Vec<u8>>);
+
+// Safety: Only single-threaded programs are supported.
+unsafe impl Sync for Output {}
+
+static OUTPUT: Output = Output(UnsafeCell::new(Vec::new()));
+
+/// Commit `output` as public output. The SHA-256 digest of all the committed bytes is a public
+/// value of the proof, attested when the guest halts, see [`halt`](crate::halt).
+pub fn commit(output: &[u8]) {
+    ecall_frame(ECALL_COMMIT, 0, output);
+    unsafe { &mut *OUTPUT.0.get() }.extend_from_slice(output);
+}
+
+/// Attest the SHA-256 digest of the committed output, which the circuits bind to the public
+/// values of the proof. It is hashed with the precompile, so that the digest is proven.
+pub(crate) fn commit_digest() {
+    let digest = sha256_words(unsafe { &*OUTPUT.0.get() });
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_COMMIT_DIGEST,
+            in("a0") digest.as_ptr(),
+        );
+    }
+}
+
+/// Write `bytes` to the file descriptor `fd` of the host, e.g. [`FD_STDOUT`]. The bytes are not
+/// part of the proof.
+pub fn write(fd: u32, bytes: &[u8]) {
+    ecall_frame(ECALL_WRITE, fd, bytes);
+}
+
+/// A writer to a file descriptor of the host, see [`write`].
+pub struct FdWriter(u32);
+
+pub fn stdout() -> FdWriter {
+    FdWriter(FD_STDOUT)
+}
+
+pub fn stderr() -> FdWriter {
+    FdWriter(FD_STDERR)
+}
+
+impl fmt::Write for FdWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write(self.0, s.as_bytes());
+        Ok(())
+    }
+}
+
 mod macros {
     #[macro_export]
     macro_rules! print {
@@ -0,0 +1,52 @@
+use core::arch::asm;
+
+/// The code of ecall KECCAK_PERMUTE.
+const ECALL_KECCAK_PERMUTE: u32 = 7;
+
+/// The rate of Keccak-256 in bytes.
+const RATE: usize = 136;
+
+/// Apply Keccak-f[1600] to a state of 25 lanes, with the precompile of the VM.
+pub fn keccak_permute(state: &mut [u64; 25]) {
+    // The ecall takes each lane as its low word then its high word, which is the little-endian
+    // layout of the lanes in memory.
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_KECCAK_PERMUTE,
+            in("a0") state.as_mut_ptr(),
+        );
+    }
+}
+
+/// The Keccak-256 digest of `data`, as used by Ethereum.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut blocks = data.chunks_exact(RATE);
+    for block in &mut blocks {
+        absorb(&mut state, block);
+        keccak_permute(&mut state);
+    }
+
+    // The padding: a one bit, zeros, and a final one bit, in one block.
+    let tail = blocks.remainder();
+    let mut padded = [0u8; RATE];
+    padded[..tail.len()].copy_from_slice(tail);
+    padded[tail.len()] |= 0x01;
+    padded[RATE - 1] |= 0x80;
+    absorb(&mut state, &padded);
+    keccak_permute(&mut state);
+
+    let mut digest = [0u8; 32];
+    for (bytes, lane) in digest.chunks_exact_mut(8).zip(state) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+/// Xor a block of `RATE` bytes into the first lanes of the state.
+fn absorb(state: &mut [u64; 25], block: &[u8]) {
+    for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+        *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+}
@@ -4,27 +4,98 @@
 
 use core::arch::{asm, global_asm};
 
+extern crate alloc;
+
 mod allocator;
 
+mod env;
+pub use env::{clock, fill_random, random_word, read_hint};
+
+mod ed25519;
+pub use ed25519::ed25519_add;
+
 mod io;
-pub use io::info_out;
+pub use io::{
+    FD_STDERR, FD_STDOUT, FdWriter, commit, info_out, read_public_input, stderr, stdout, write,
+};
+
+mod keccak;
+pub use keccak::{keccak_permute, keccak256};
 
 mod params;
 pub use params::*;
 
+mod poseidon2;
+pub use poseidon2::{poseidon2_compress, poseidon2_permute};
+
+mod secp256k1;
+pub use secp256k1::{secp256k1_add, secp256k1_double};
+
+mod sha256;
+pub use sha256::{sha256, sha256_compress};
+
+mod uint256;
+pub use uint256::{uint256_addmod, uint256_mulmod};
+
 #[cfg(not(test))]
 mod panic_handler {
-    use core::panic::PanicInfo;
+    use core::{
+        arch::asm,
+        fmt::{self, Write},
+        panic::PanicInfo,
+        slice,
+    };
+
+    use crate::WORD_SIZE;
+
+    /// The code of ecall PANIC.
+    const ECALL_PANIC: u32 = 14;
+    /// The exit code of a guest which panicked.
+    const EXIT_PANIC: u32 = 101;
+    /// The longest message reported to the host, in words.
+    const MESSAGE_WORDS: usize = 128;
+
+    /// The frame of ecall PANIC: the length of the message, followed by the message, truncated.
+    /// It is on the stack, as the panic may come from the allocator.
+    struct Frame([u32; 1 + MESSAGE_WORDS]);
 
+    impl Write for Frame {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let bytes = unsafe {
+                slice::from_raw_parts_mut(
+                    self.0[1..].as_mut_ptr() as *mut u8,
+                    MESSAGE_WORDS * WORD_SIZE,
+                )
+            };
+            let len = self.0[0] as usize;
+            let n = s.len().min(bytes.len() - len);
+            bytes[len..len + n].copy_from_slice(&s.as_bytes()[..n]);
+            self.0[0] += n as u32;
+            Ok(())
+        }
+    }
+
+    /// Report the panic to the host, then halt with exit code 101.
     #[panic_handler]
     #[inline(never)]
-    fn panic_handler(_panic: &PanicInfo<'_>) -> ! {
-        super::halt(1)
+    fn panic_handler(panic: &PanicInfo<'_>) -> ! {
+        let mut frame = Frame([0; 1 + MESSAGE_WORDS]);
+        let _ = write!(frame, "{panic}");
+        unsafe {
+            asm!(
+                "ecall",
+                in("t0") ECALL_PANIC,
+                in("a0") frame.0.as_ptr(),
+            );
+        }
+        super::halt(EXIT_PANIC)
     }
 }
 
+/// Attest the digest of the committed output, then halt with `exit_code`.
 #[allow(asm_sub_register)]
 pub fn halt(exit_code: u32) -> ! {
+    io::commit_digest();
     unsafe {
         asm!(
             // Set the first argument.
@@ -1,3 +1,6 @@
 pub const WORD_SIZE: usize = 4;
 
 pub const INFO_OUT_ADDR: u32 = 0xC000_0000;
+
+/// Start of the public input, as written by the host with `VMState::write_public_input`.
+pub const PUBLIC_IO_ADDR: u32 = 0x3000_1000;
@@ -0,0 +1,32 @@
+use core::arch::asm;
+
+/// The code of ecall POSEIDON2_PERMUTE.
+const ECALL_POSEIDON2_PERMUTE: u32 = 13;
+
+/// Apply the Poseidon2 permutation to a state of 12 elements of the Goldilocks field, with the
+/// precompile of the VM. The elements must be canonical, that is less than the
+/// modulus `2^64 - 2^32 + 1`.
+pub fn poseidon2_permute(state: &mut [u64; 12]) {
+    // The ecall takes each element as its low word then its high word, which is the
+    // little-endian layout of the elements in memory.
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_POSEIDON2_PERMUTE,
+            in("a0") state.as_mut_ptr(),
+        );
+    }
+}
+
+/// The Poseidon2 compression of two digests of 4 elements into one, as the nodes of a Merkle
+/// tree: `left` and `right` fill the state, padded with zeros, and the digest is the first 4
+/// elements of its permutation.
+pub fn poseidon2_compress(left: &[u64; 4], right: &[u64; 4]) -> [u64; 4] {
+    let mut state = [0u64; 12];
+    state[..4].copy_from_slice(left);
+    state[4..8].copy_from_slice(right);
+    poseidon2_permute(&mut state);
+    let mut digest = [0u64; 4];
+    digest.copy_from_slice(&state[..4]);
+    digest
+}
@@ -0,0 +1,36 @@
+use core::arch::asm;
+
+/// The code of ecall SECP256K1_ADD.
+const ECALL_SECP256K1_ADD: u32 = 10;
+/// The code of ecall SECP256K1_DOUBLE.
+const ECALL_SECP256K1_DOUBLE: u32 = 11;
+
+/// `p = p + q` for points of secp256k1, with the precompile of the VM. A point is `x` then `y`,
+/// each as 8 little-endian words. The coordinates must be reduced and the abscissas distinct:
+/// the sum of a point and itself is `secp256k1_double`.
+pub fn secp256k1_add(p: &mut [u32; 16], q: &[u32; 16]) {
+    // The ecall takes `p` then `q`, and replaces `p` by the sum.
+    let mut words = [0u32; 32];
+    words[..16].copy_from_slice(p);
+    words[16..].copy_from_slice(q);
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_SECP256K1_ADD,
+            in("a0") words.as_mut_ptr(),
+        );
+    }
+    p.copy_from_slice(&words[..16]);
+}
+
+/// `p = 2p` for a point of secp256k1, as in `secp256k1_add`. The coordinates must be reduced and
+/// the ordinate non-zero.
+pub fn secp256k1_double(p: &mut [u32; 16]) {
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_SECP256K1_DOUBLE,
+            in("a0") p.as_mut_ptr(),
+        );
+    }
+}
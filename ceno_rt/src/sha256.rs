@@ -0,0 +1,64 @@
+use core::arch::asm;
+
+/// The code of ecall SHA256.
+const ECALL_SHA256: u32 = 6;
+
+/// The initial state of SHA-256.
+const INIT: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Compress one block into a SHA-256 state, with the precompile of the VM.
+pub fn sha256_compress(state: &mut [u32; 8], block: &[u32; 16]) {
+    // The ecall takes the state followed by the block, and updates the state in place.
+    let mut words = [0u32; 24];
+    words[..8].copy_from_slice(state);
+    words[8..].copy_from_slice(block);
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ECALL_SHA256,
+            in("a0") words.as_mut_ptr(),
+        );
+    }
+    state.copy_from_slice(&words[..8]);
+}
+
+/// The SHA-256 digest of `data`.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(sha256_words(data)) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// The SHA-256 digest of `data`, as the words of the final state.
+pub(crate) fn sha256_words(data: &[u8]) -> [u32; 8] {
+    let mut state = INIT;
+    let mut blocks = data.chunks_exact(64);
+    for block in &mut blocks {
+        sha256_compress(&mut state, &to_block(block));
+    }
+
+    // The padding: a one bit, zeros, and the length in bits, in one or two blocks.
+    let tail = blocks.remainder();
+    let mut padded = [0u8; 128];
+    padded[..tail.len()].copy_from_slice(tail);
+    padded[tail.len()] = 0x80;
+    let len = if tail.len() < 56 { 64 } else { 128 };
+    padded[len - 8..len].copy_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for block in padded[..len].chunks_exact(64) {
+        sha256_compress(&mut state, &to_block(block));
+    }
+    state
+}
+
+/// The big-endian words of a block of 64 bytes.
+fn to_block(bytes: &[u8]) -> [u32; 16] {
+    let mut block = [0u32; 16];
+    for (word, bytes) in block.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    block
+}
@@ -0,0 +1,34 @@
+use core::arch::asm;
+
+/// The code of ecall UINT256_ADDMOD.
+const ECALL_UINT256_ADDMOD: u32 = 8;
+/// The code of ecall UINT256_MULMOD.
+const ECALL_UINT256_MULMOD: u32 = 9;
+
+/// `(x + y) mod m` for 256-bit numbers of little-endian words, with the precompile of the VM.
+/// The operands must be reduced, `x < m` and `y < m`.
+pub fn uint256_addmod(x: &[u32; 8], y: &[u32; 8], m: &[u32; 8]) -> [u32; 8] {
+    modop(ECALL_UINT256_ADDMOD, x, y, m)
+}
+
+/// `(x * y) mod m` for 256-bit numbers of little-endian words, with the precompile of the VM.
+/// The operands must be reduced, `x < m` and `y < m`.
+pub fn uint256_mulmod(x: &[u32; 8], y: &[u32; 8], m: &[u32; 8]) -> [u32; 8] {
+    modop(ECALL_UINT256_MULMOD, x, y, m)
+}
+
+fn modop(ecall: u32, x: &[u32; 8], y: &[u32; 8], m: &[u32; 8]) -> [u32; 8] {
+    // The ecall takes `x`, `y` and `m`, and replaces `x` by the result.
+    let mut words = [0u32; 24];
+    words[..8].copy_from_slice(x);
+    words[8..16].copy_from_slice(y);
+    words[16..].copy_from_slice(m);
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") ecall,
+            in("a0") words.as_mut_ptr(),
+        );
+    }
+    words[..8].try_into().unwrap()
+}
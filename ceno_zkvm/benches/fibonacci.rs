@@ -13,6 +13,7 @@ use criterion::*;
 
 use goldilocks::GoldilocksExt2;
 use mpcs::BasefoldDefault;
+use transcript::TranscriptHash;
 
 criterion_group! {
   name = fibonacci;
@@ -65,11 +66,14 @@ fn bench_e2e(c: &mut Criterion) {
                             heap_size,
                             vec![],
                             max_steps,
+                            None,
+                            TranscriptHash::default(),
                         )
+                        .expect("witness generation failed")
                     },
-                    |(prover, _, zkvm_witness, pi, _, _, _)| {
+                    |(prover, _, zkvm_witness, pi, _, _, _, _)| {
                         let timer = Instant::now();
-                        let _ = run_e2e_proof(prover, zkvm_witness, pi);
+                        run_e2e_proof(prover, zkvm_witness, pi).expect("create_proof failed");
                         println!(
                             "Fibonacci::create_proof, max_steps = {}, time = {}",
                             max_steps,
@@ -1,8 +1,9 @@
 use std::time::{Duration, Instant};
 
 use ark_std::test_rng;
+use ceno_emul::{ByteAddr, CENO_PLATFORM, Change, InsnKind, StepRecord, encode_rv32};
 use ceno_zkvm::{
-    self,
+    self, WitnessPool,
     instructions::{Instruction, riscv::arith::AddInstruction},
     scheme::prover::ZKVMProver,
     structs::{ZKVMConstraintSystem, ZKVMFixedTraces},
@@ -22,13 +23,13 @@ cfg_if::cfg_if! {
     criterion_group! {
       name = op_add;
       config = Criterion::default().warm_up_time(Duration::from_millis(3000)).with_profiler(pprof2::criterion::PProfProfiler::new(100, pprof2::criterion::Output::Flamegraph(None)));
-      targets = bench_add
+      targets = bench_add, bench_assign_add
     }
   } else {
     criterion_group! {
       name = op_add;
       config = Criterion::default().warm_up_time(Duration::from_millis(3000));
-      targets = bench_add
+      targets = bench_add, bench_assign_add
     }
   }
 }
@@ -40,9 +41,13 @@ const NUM_SAMPLES: usize = 10;
 fn bench_add(c: &mut Criterion) {
     type Pcs = BasefoldDefault<E>;
     let mut zkvm_cs = ZKVMConstraintSystem::default();
-    let _ = zkvm_cs.register_opcode_circuit::<AddInstruction<E>>();
+    zkvm_cs
+        .register_opcode_circuit::<AddInstruction<E>>()
+        .unwrap();
     let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
-    zkvm_fixed_traces.register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs);
+    zkvm_fixed_traces
+        .register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs)
+        .unwrap();
 
     let param = Pcs::setup(1 << MAX_NUM_VARIABLES).unwrap();
     let (pp, vp) = Pcs::trim(param, 1 << MAX_NUM_VARIABLES).unwrap();
@@ -99,10 +104,9 @@ fn bench_add(c: &mut Criterion) {
                         let _ = prover
                             .create_opcode_proof(
                                 "ADD",
-                                &prover.pk.pp,
                                 &circuit_pk,
                                 wits_in.into_iter().map(|mle| mle.into()).collect_vec(),
-                                commit,
+                                &commit,
                                 &[],
                                 num_instances,
                                 &mut transcript,
@@ -124,3 +128,69 @@ fn bench_add(c: &mut Criterion) {
 
     type E = GoldilocksExt2;
 }
+
+fn bench_assign_add(c: &mut Criterion) {
+    let mut zkvm_cs = ZKVMConstraintSystem::<E>::default();
+    let config = zkvm_cs
+        .register_opcode_circuit::<AddInstruction<E>>()
+        .unwrap();
+    let num_witin = zkvm_cs
+        .get_cs(&AddInstruction::<E>::name())
+        .unwrap()
+        .num_witin as usize;
+    let pool = WitnessPool::default();
+
+    for instance_num_vars in 20..22 {
+        let mut group = c.benchmark_group(format!("assign_add_{}", instance_num_vars));
+        group.sample_size(NUM_SAMPLES);
+
+        let insn_code = encode_rv32(InsnKind::ADD, 2, 3, 4, 0);
+        let steps = (0..1u32 << instance_num_vars)
+            .map(|i| {
+                StepRecord::new_r_instruction(
+                    4 * (i as u64 + 1),
+                    ByteAddr(CENO_PLATFORM.pc_base()),
+                    insn_code,
+                    i,
+                    1,
+                    Change::new(0, i + 1),
+                    0,
+                )
+            })
+            .collect_vec();
+
+        // a new witness matrix for every assignment
+        group.bench_function(
+            BenchmarkId::new(
+                "assign_add",
+                format!("assign_add_fresh_log2_{}", instance_num_vars),
+            ),
+            |b| {
+                b.iter_with_setup(
+                    || steps.clone(),
+                    |steps| AddInstruction::<E>::assign_instances(&config, num_witin, steps),
+                );
+            },
+        );
+
+        // the buffer of the matrix of the previous assignment, dropped between iterations
+        group.bench_function(
+            BenchmarkId::new(
+                "assign_add",
+                format!("assign_add_pooled_log2_{}", instance_num_vars),
+            ),
+            |b| {
+                b.iter_with_setup(
+                    || steps.clone(),
+                    |steps| {
+                        AddInstruction::<E>::assign_instances_in(&config, num_witin, steps, &pool)
+                    },
+                );
+            },
+        );
+
+        group.finish();
+    }
+
+    type E = GoldilocksExt2;
+}
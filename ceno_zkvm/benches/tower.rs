@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use ark_std::test_rng;
+use ceno_zkvm::{
+    scheme::constants::{NUM_FANIN, NUM_FANIN_LOGUP},
+    structs::{TowerProver, TowerProverSpec},
+};
+use criterion::*;
+use ff_ext::ff::Field;
+use goldilocks::GoldilocksExt2;
+use itertools::Itertools;
+use multilinear_extensions::{mle::IntoMLE, virtual_poly_v2::ArcMultilinearExtension};
+use transcript::BasicTranscript;
+
+cfg_if::cfg_if! {
+  if #[cfg(feature = "flamegraph")] {
+    criterion_group! {
+      name = tower;
+      config = Criterion::default().warm_up_time(Duration::from_millis(3000)).with_profiler(pprof2::criterion::PProfProfiler::new(100, pprof2::criterion::Output::Flamegraph(None)));
+      targets = bench_tower
+    }
+  } else {
+    criterion_group! {
+      name = tower;
+      config = Criterion::default().warm_up_time(Duration::from_millis(3000));
+      targets = bench_tower
+    }
+  }
+}
+
+criterion_main!(tower);
+
+const NUM_SAMPLES: usize = 10;
+
+type E = GoldilocksExt2;
+
+/// `num_specs` product and logup towers over `num_vars` variables, as in a lookup-heavy table.
+fn tower_specs(
+    num_vars: usize,
+    num_specs: usize,
+    streamed: bool,
+) -> (
+    Vec<TowerProverSpec<'static, E>>,
+    Vec<TowerProverSpec<'static, E>>,
+) {
+    let mut rng = test_rng();
+    let mut last_layer = || -> Vec<ArcMultilinearExtension<'static, E>> {
+        (0..2)
+            .map(|_| {
+                (0..1 << (num_vars - 1))
+                    .map(|_| E::random(&mut rng))
+                    .collect_vec()
+                    .into_mle()
+                    .into()
+            })
+            .collect()
+    };
+    let prod_specs = (0..num_specs)
+        .map(|_| TowerProverSpec::product(num_vars, last_layer(), NUM_FANIN, streamed))
+        .collect();
+    let logup_specs = (0..num_specs)
+        .map(|_| TowerProverSpec::logup(Some(last_layer()), last_layer(), streamed))
+        .collect();
+    (prod_specs, logup_specs)
+}
+
+fn bench_tower(c: &mut Criterion) {
+    for num_vars in [16, 20] {
+        let mut group = c.benchmark_group(format!("tower_log2_{}", num_vars));
+        group.sample_size(NUM_SAMPLES);
+
+        for num_specs in [1, 8] {
+            for streamed in [false, true] {
+                let id = format!(
+                    "{}_specs{}",
+                    num_specs,
+                    if streamed { "_streamed" } else { "" }
+                );
+                group.bench_function(BenchmarkId::new("prove_tower", id), |b| {
+                    b.iter_with_setup(
+                        || tower_specs(num_vars, num_specs, streamed),
+                        |(prod_specs, logup_specs)| {
+                            let mut transcript = BasicTranscript::new(b"tower");
+                            TowerProver::create_proof(
+                                prod_specs,
+                                logup_specs,
+                                NUM_FANIN_LOGUP,
+                                &mut transcript,
+                            )
+                        },
+                    );
+                });
+            }
+        }
+
+        group.finish();
+    }
+}
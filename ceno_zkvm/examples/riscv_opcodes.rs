@@ -122,18 +122,18 @@ fn main() {
     };
     let mut zkvm_cs = ZKVMConstraintSystem::new_with_platform(program_params);
 
-    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs);
-    let mmu_config = MmuConfig::<E>::construct_circuits(&mut zkvm_cs);
-    let prog_config = zkvm_cs.register_table_circuit::<ExampleProgramTableCircuit<E>>();
-    zkvm_cs.register_global_state::<GlobalState>();
+    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+    let mmu_config = MmuConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+    let prog_config = zkvm_cs
+        .register_table_circuit::<ExampleProgramTableCircuit<E>>()
+        .unwrap();
+    zkvm_cs.register_global_state::<GlobalState>().unwrap();
 
     let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
 
-    zkvm_fixed_traces.register_table_circuit::<ExampleProgramTableCircuit<E>>(
-        &zkvm_cs,
-        &prog_config,
-        &program,
-    );
+    zkvm_fixed_traces
+        .register_table_circuit::<ExampleProgramTableCircuit<E>>(&zkvm_cs, &prog_config, &program)
+        .unwrap();
 
     let static_report = StaticReport::new(&zkvm_cs);
 
@@ -148,14 +148,18 @@ fn main() {
 
     let io_addrs = init_public_io(&[]).iter().map(|v| v.addr).collect_vec();
 
-    config.generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces);
-    mmu_config.generate_fixed_traces(
-        &zkvm_cs,
-        &mut zkvm_fixed_traces,
-        &reg_init,
-        &mem_init,
-        &io_addrs,
-    );
+    config
+        .generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces)
+        .unwrap();
+    mmu_config
+        .generate_fixed_traces(
+            &zkvm_cs,
+            &mut zkvm_fixed_traces,
+            &reg_init,
+            &mem_init,
+            &io_addrs,
+        )
+        .unwrap();
 
     let pk = zkvm_cs
         .clone()
@@ -216,7 +220,7 @@ fn main() {
         config
             .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records)
             .unwrap();
-        zkvm_witness.finalize_lk_multiplicities();
+        zkvm_witness.finalize_lk_multiplicities().unwrap();
 
         // Find the final register values and cycles.
         let reg_final = reg_init
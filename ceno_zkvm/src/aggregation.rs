@@ -0,0 +1,213 @@
+//! Aggregation of the proofs of the segments of an execution, see [`run_e2e_segments`], into one
+//! proof of the whole execution.
+//!
+//! Only [`NativeAggregator`] exists so far: its proof and its verification still grow with the
+//! number of segments. A succinct aggregator needs a verifier circuit of the segment proofs, or a
+//! folding scheme over them, which remain to be written.
+//!
+//! [`run_e2e_segments`]: crate::e2e::run_e2e_segments
+
+use ceno_emul::{Addr, Cycle, Platform, Program};
+use ff_ext::ExtensionField;
+use mpcs::PolynomialCommitmentScheme;
+use serde::{Serialize, de::DeserializeOwned};
+use transcript::TranscriptHash;
+
+use crate::{
+    e2e::{SegmentProof, verify_segments},
+    error::{VerifyError, ZKVMError},
+};
+
+/// What the segments of an execution prove together: the program ran from its entry point to
+/// `end_pc` at `end_cycle`, in `num_segments` segments, and halted with `exit_code`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionStatement {
+    pub end_pc: Addr,
+    pub end_cycle: Cycle,
+    pub exit_code: u32,
+    pub num_segments: usize,
+}
+
+/// Compress the proofs of the segments of an execution into one proof of its
+/// [`ExecutionStatement`].
+///
+/// A recursive aggregator verifies the segment proofs in a circuit, or folds them into an
+/// accumulator, so that its proof and its verification do not grow with the number of segments.
+/// [`NativeAggregator`] is the reference for the statement such an aggregator proves.
+pub trait SegmentAggregator<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    type Proof;
+
+    /// Aggregate the proofs of all the segments of an execution, in order. Fail if they do not
+    /// verify, or do not stitch together.
+    fn aggregate(&self, segments: Vec<SegmentProof<E, PCS>>) -> Result<Self::Proof, ZKVMError>;
+
+    /// Verify an aggregated proof, and return the statement it proves.
+    fn verify(&self, proof: &Self::Proof) -> Result<ExecutionStatement, ZKVMError>;
+}
+
+/// The aggregator which verifies the segments natively, with [`verify_segments`]. Its proof
+/// keeps the proofs of all the segments, so it is not succinct.
+pub struct NativeAggregator {
+    program: Program,
+    platform: Platform,
+    stack_size: u32,
+    heap_size: u32,
+    transcript_hash: TranscriptHash,
+}
+
+/// The proof of [`NativeAggregator`]: the statement, and the proofs of the segments.
+pub struct NativeAggregation<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub statement: ExecutionStatement,
+    pub segments: Vec<SegmentProof<E, PCS>>,
+}
+
+impl NativeAggregator {
+    /// The aggregator of the executions of `program`, with the memory layout and the transcript
+    /// they were proven with, see [`run_e2e_segments`](crate::e2e::run_e2e_segments).
+    pub fn new(
+        program: Program,
+        platform: Platform,
+        stack_size: u32,
+        heap_size: u32,
+        transcript_hash: TranscriptHash,
+    ) -> Self {
+        NativeAggregator {
+            program,
+            platform,
+            stack_size,
+            heap_size,
+            transcript_hash,
+        }
+    }
+
+    fn verify_segments<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+        &self,
+        segments: &[SegmentProof<E, PCS>],
+    ) -> Result<ExecutionStatement, ZKVMError>
+    where
+        E::BaseField: Serialize + DeserializeOwned,
+    {
+        let exit_code = verify_segments(
+            segments,
+            &self.program,
+            &self.platform,
+            self.stack_size,
+            self.heap_size,
+            self.transcript_hash,
+        )?;
+        let last = &segments[segments.len() - 1];
+        Ok(ExecutionStatement {
+            end_pc: last.end.pc,
+            end_cycle: last.end.cycle,
+            exit_code,
+            num_segments: segments.len(),
+        })
+    }
+}
+
+impl<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>
+    SegmentAggregator<E, PCS> for NativeAggregator
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    type Proof = NativeAggregation<E, PCS>;
+
+    fn aggregate(&self, segments: Vec<SegmentProof<E, PCS>>) -> Result<Self::Proof, ZKVMError> {
+        Ok(NativeAggregation {
+            statement: self.verify_segments(&segments)?,
+            segments,
+        })
+    }
+
+    fn verify(&self, proof: &Self::Proof) -> Result<ExecutionStatement, ZKVMError> {
+        let statement = self.verify_segments(&proof.segments)?;
+        if statement != proof.statement {
+            return Err(VerifyError::Unsatisfied(
+                "the statement is not the one of the segments".into(),
+            )
+            .into());
+        }
+        Ok(statement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ceno_emul::{
+        CENO_PLATFORM,
+        InsnKind::{ADDI, EANY},
+        PC_WORD_SIZE, Program, Tracer, encode_rv32,
+    };
+    use goldilocks::GoldilocksExt2;
+    use itertools::{Itertools, chain};
+    use mpcs::{Basefold, BasefoldRSParams};
+    use std::iter::repeat_n;
+    use transcript::TranscriptHash;
+
+    use super::{ExecutionStatement, NativeAggregator, SegmentAggregator};
+    use crate::e2e::run_e2e_segments;
+
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+
+    #[test]
+    fn test_native_aggregation() {
+        // six additions, then HALT with exit code 0, in segments of at most 3 steps
+        let code = chain!(repeat_n(encode_rv32(ADDI, 1, 0, 1, 1), 6), [encode_rv32(
+            EANY, 0, 0, 0, 0
+        )])
+        .collect_vec();
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(
+            pc_base,
+            pc_base,
+            code.clone(),
+            code.iter()
+                .enumerate()
+                .map(|(i, &insn)| (pc_base + (i * PC_WORD_SIZE) as u32, insn))
+                .collect(),
+        );
+        let (stack_size, heap_size) = (1 << 10, 0);
+        let segments = run_e2e_segments::<E, Pcs>(
+            program.clone(),
+            CENO_PLATFORM,
+            stack_size,
+            heap_size,
+            vec![],
+            usize::MAX,
+            3,
+            TranscriptHash::default(),
+        )
+        .unwrap();
+        assert_eq!(segments.len(), 3);
+
+        let aggregator = NativeAggregator::new(
+            program,
+            CENO_PLATFORM,
+            stack_size,
+            heap_size,
+            TranscriptHash::default(),
+        );
+        let mut proof = aggregator.aggregate(segments).unwrap();
+        assert_eq!(aggregator.verify(&proof).unwrap(), ExecutionStatement {
+            end_pc: 0,
+            end_cycle: Tracer::SUBCYCLES_PER_INSN * (code.len() as u64 + 1),
+            exit_code: 0,
+            num_segments: 3,
+        });
+
+        // a statement which the segments do not prove is rejected
+        proof.statement.exit_code = 1;
+        assert!(aggregator.verify(&proof).is_err());
+        proof.statement.exit_code = 0;
+
+        // so are truncated segments, which do not halt
+        let last = proof.segments.pop().unwrap();
+        assert!(aggregator.verify(&proof).is_err());
+        proof.segments.push(last);
+
+        // and segments which do not start at the entry point
+        proof.segments.remove(0);
+        assert!(aggregator.aggregate(proof.segments).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+//! Drive the zkVM without a host program: execute a guest, generate its keys, prove its
+//! execution to a file, and verify a proof file. The proving of long executions is spread over
+//! `worker` processes by `prove-segments`.
+
+use std::{fmt::Write, fs, net::TcpListener, path::PathBuf, process, time::Instant};
+
+use ceno_emul::{
+    CENO_PLATFORM, InsnKind, IterAddresses, Platform, Profile, Program, VMState, WORD_SIZE, Word,
+};
+use ceno_zkvm::{
+    ProveOptions,
+    e2e::verify_segments,
+    keygen, prove,
+    prover_service::{Coordinator, ProvingTask, serve},
+    scheme::{
+        ZKVMProof,
+        pcs::{PcsKind, PolynomialCommitmentScheme},
+        verifier::ZKVMVerifier,
+    },
+    serialization::{ProofBundle, from_bytes, read_header, to_bytes, to_calldata},
+    structs::ZKVMVerifyingKey,
+    with_pcs,
+};
+use clap::{Args, CommandFactory, Parser, Subcommand, error::ErrorKind};
+use goldilocks::GoldilocksExt2;
+use itertools::Itertools;
+use tracing_subscriber::EnvFilter;
+use transcript::TranscriptHash;
+
+type E = GoldilocksExt2;
+
+/// Execute, prove and verify RISC-V guests on the Ceno zkVM.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a guest, and report its cycles and exit code.
+    Execute {
+        #[command(flatten)]
+        guest: GuestArgs,
+    },
+    /// Generate the verifying key of a guest, and cache its proving key.
+    Keygen {
+        #[command(flatten)]
+        guest: GuestArgs,
+        #[command(flatten)]
+        proving: ProvingArgs,
+        /// Where to write the verifying key.
+        #[arg(long)]
+        vk: PathBuf,
+    },
+    /// Prove the execution of a guest until it halts.
+    Prove {
+        #[command(flatten)]
+        guest: GuestArgs,
+        #[command(flatten)]
+        proving: ProvingArgs,
+        /// Where to write the proof.
+        #[arg(long, required_unless_present_any = ["bundle", "calldata"])]
+        proof: Option<PathBuf>,
+        /// Where to write the proof as a `.ceno` bundle, with its public values and the digests
+        /// of its verifying key and program.
+        #[arg(long)]
+        bundle: Option<PathBuf>,
+        /// Where to write the proof as EVM calldata. Requires `--transcript-hash keccak`.
+        #[arg(long)]
+        calldata: Option<PathBuf>,
+        /// Where to write the verifying key, if anywhere.
+        #[arg(long)]
+        vk: Option<PathBuf>,
+    },
+    /// Verify a proof file or a `.ceno` bundle against a verifying key file.
+    Verify {
+        #[arg(long, required_unless_present = "bundle", conflicts_with = "bundle")]
+        proof: Option<PathBuf>,
+        /// The bundle to verify, whose public values are checked too.
+        #[arg(long)]
+        bundle: Option<PathBuf>,
+        #[arg(long)]
+        vk: PathBuf,
+        /// The polynomial commitment scheme of the proof, which a bundle records itself.
+        #[arg(long, default_value = "basefold-rs")]
+        pcs: PcsKind,
+    },
+    /// Print the metadata of a `.ceno` bundle.
+    Inspect { bundle: PathBuf },
+    /// Prove the execution of a guest by segments, on workers, and check the segments chain.
+    ProveSegments {
+        #[command(flatten)]
+        guest: GuestArgs,
+        #[command(flatten)]
+        proving: ProvingArgs,
+        /// The number of steps of a segment.
+        #[arg(long)]
+        segment_steps: usize,
+        /// The addresses of the workers, as `host:port`.
+        #[arg(long, value_delimiter = ',', required = true)]
+        workers: Vec<String>,
+        /// Where to write the segment proofs.
+        #[arg(long)]
+        proof: PathBuf,
+    },
+    /// Prove the segments which coordinators send to this worker.
+    Worker {
+        /// The address to listen on. Workers prove the jobs of any coordinator which connects, so
+        /// only listen beyond localhost on a trusted network.
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        listen: String,
+    },
+}
+
+#[derive(Args, Debug)]
+struct GuestArgs {
+    /// The path to the ELF file of the guest.
+    elf: PathBuf,
+
+    /// Hints: prover-private unconstrained input, read from this file.
+    #[arg(long)]
+    hints: Option<PathBuf>,
+
+    /// The maximum number of steps to execute the guest.
+    #[arg(long)]
+    max_steps: Option<usize>,
+
+    /// Stack size in bytes.
+    #[arg(long, default_value = "32768")]
+    stack_size: u32,
+
+    /// Heap size in bytes.
+    #[arg(long, default_value = "2097152")]
+    heap_size: u32,
+}
+
+#[derive(Args, Debug)]
+struct ProvingArgs {
+    /// Directory caching proving keys across runs of the same guest, and the fixed traces of the
+    /// tables which do not depend on it.
+    #[arg(long)]
+    key_cache: Option<PathBuf>,
+
+    /// The polynomial commitment scheme: basefold-rs or basefold-basecode.
+    #[arg(long, default_value = "basefold-rs")]
+    pcs: PcsKind,
+
+    /// The hash function of the Fiat–Shamir transcript: poseidon, blake3 or keccak.
+    #[arg(long, default_value = "poseidon")]
+    transcript_hash: TranscriptHash,
+
+    /// Prove the execution of a guest which panics, which then exits with code 101.
+    #[arg(long)]
+    prove_panics: bool,
+}
+
+impl GuestArgs {
+    fn elf(&self) -> Vec<u8> {
+        fs::read(&self.elf).expect("read elf file")
+    }
+
+    fn hints(&self) -> Vec<u8> {
+        self.hints
+            .as_ref()
+            .map(|path| fs::read(path).expect("read hints file"))
+            .unwrap_or_default()
+    }
+
+    /// The hints as words, as they are laid out in memory.
+    fn hint_words(&self) -> Vec<Word> {
+        let mut hints = self.hints();
+        hints.resize(hints.len().next_multiple_of(WORD_SIZE), 0);
+        let hints = hints
+            .chunks_exact(WORD_SIZE)
+            .map(|word| Word::from_le_bytes(word.try_into().unwrap()))
+            .collect_vec();
+        assert!(
+            hints.len() <= CENO_PLATFORM.hints.iter_addresses().len(),
+            "hints must fit in {} bytes",
+            CENO_PLATFORM.hints.len()
+        );
+        hints
+    }
+
+    fn options(&self, proving: &ProvingArgs) -> ProveOptions {
+        ProveOptions {
+            stack_size: self.stack_size,
+            heap_size: self.heap_size,
+            max_steps: self.max_steps.unwrap_or(usize::MAX),
+            key_cache: proving.key_cache.clone(),
+            transcript_hash: proving.transcript_hash,
+            prove_panics: proving.prove_panics,
+            ..ProveOptions::default()
+        }
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt()
+        .compact()
+        .without_time()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    match Cli::parse().command {
+        Command::Execute { guest } => execute(&guest),
+        Command::Keygen { guest, proving, vk } => {
+            with_pcs!(proving.pcs, E, Pcs => {
+                let key = keygen::<E, Pcs>(&guest.elf(), &guest.options(&proving))
+                    .expect("keygen failed");
+                write_file(&vk, &to_bytes(&key).expect("serialize verifying key"));
+            })
+        }
+        Command::Prove {
+            guest,
+            proving,
+            proof,
+            bundle,
+            calldata,
+            vk,
+        } => {
+            if calldata.is_some() && proving.transcript_hash != TranscriptHash::Keccak {
+                Cli::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--calldata is meant for the EVM, prove with --transcript-hash keccak",
+                    )
+                    .exit();
+            }
+            with_pcs!(proving.pcs, E, Pcs => {
+                let elf = guest.elf();
+                let timer = Instant::now();
+                let (zkvm_proof, key, public_values) =
+                    prove::<E, Pcs>(&elf, &guest.hints(), &guest.options(&proving))
+                        .expect("proving failed");
+                println!("Proving finished in {:.3}s", timer.elapsed().as_secs_f64());
+                if let Some(proof) = proof {
+                    write_file(&proof, &to_bytes(&zkvm_proof).expect("serialize proof"));
+                }
+                if let Some(calldata) = calldata {
+                    write_file(&calldata, &to_calldata(&zkvm_proof).expect("encode calldata"));
+                }
+                if let Some(vk) = vk {
+                    write_file(&vk, &to_bytes(&key).expect("serialize verifying key"));
+                }
+                if let Some(bundle) = bundle {
+                    let zkvm_bundle =
+                        ProofBundle::new(zkvm_proof, &key, public_values, &elf, proving.pcs)
+                            .expect("bundle proof");
+                    write_file(&bundle, &zkvm_bundle.to_bytes().expect("serialize bundle"));
+                }
+            })
+        }
+        Command::Verify {
+            proof: Some(proof),
+            vk,
+            pcs,
+            ..
+        } => {
+            with_pcs!(pcs, E, Pcs => verify::<Pcs>(&proof, &vk))
+        }
+        Command::Verify {
+            bundle: Some(bundle),
+            vk,
+            ..
+        } => {
+            let bytes = read_file(&bundle);
+            let header = read_header(&bytes).expect("read bundle");
+            with_pcs!(header.pcs, E, Pcs => verify_bundle::<Pcs>(&bytes, &vk))
+        }
+        Command::Verify { .. } => unreachable!("clap requires a proof or a bundle"),
+        Command::Inspect { bundle } => {
+            let header = read_header(&read_file(&bundle)).expect("read bundle");
+            println!("version: {}", header.version);
+            println!("pcs: {}", header.pcs);
+            println!("vk digest: {}", hex(&header.vk_digest));
+            println!("program digest: {}", hex(&header.program_digest));
+            println!("public values: {:?}", header.public_values);
+        }
+        Command::ProveSegments {
+            guest,
+            proving,
+            segment_steps,
+            workers,
+            proof,
+        } => {
+            let elf = guest.elf();
+            let task = ProvingTask {
+                hints: guest.hint_words(),
+                stack_size: guest.stack_size,
+                heap_size: guest.heap_size,
+                max_steps: guest.max_steps.unwrap_or(usize::MAX),
+                segment_steps,
+                pcs: proving.pcs,
+                transcript_hash: proving.transcript_hash,
+                elf,
+            };
+            let program = Program::load_elf(&task.elf, u32::MAX).expect("load elf");
+            with_pcs!(proving.pcs, E, Pcs => {
+                let timer = Instant::now();
+                let segments = Coordinator::new(workers)
+                    .prove::<E, Pcs>(&task)
+                    .expect("proving failed");
+                println!(
+                    "Proved {} segments in {:.3}s",
+                    segments.len(),
+                    timer.elapsed().as_secs_f64()
+                );
+                let exit_code = verify_segments(
+                    &segments,
+                    &program,
+                    &CENO_PLATFORM,
+                    task.stack_size,
+                    task.heap_size,
+                    task.transcript_hash,
+                )
+                .expect("segments do not verify");
+                println!("Exit code: {exit_code}");
+                write_file(&proof, &to_bytes(&segments).expect("serialize proof"));
+            })
+        }
+        Command::Worker { listen } => {
+            let listener = TcpListener::bind(&listen).expect("bind listen address");
+            println!("Listening on {listen}");
+            serve(listener).expect("serve");
+        }
+    }
+}
+
+fn execute(guest: &GuestArgs) {
+    let mut vm = VMState::new_from_elf(CENO_PLATFORM, &guest.elf()).expect("load elf");
+    vm.write_hints(&guest.hints());
+    for (addr, value) in CENO_PLATFORM.hints.iter_addresses().zip(guest.hint_words()) {
+        vm.init_memory(addr.into(), value);
+    }
+
+    let timer = Instant::now();
+    let mut profile = Profile::default();
+    let mut exit_code = None;
+    for step in vm
+        .iter_until_halt()
+        .take(guest.max_steps.unwrap_or(usize::MAX))
+    {
+        let step = step.expect("vm exec failed");
+        profile.record(&step);
+        if step.insn().codes().kind == InsnKind::EANY
+            && step.rs1().unwrap().value == Platform::ecall_halt()
+        {
+            exit_code = step.rs2().map(|rs2| rs2.value);
+        }
+    }
+    println!("{profile}");
+    if let Some(panic) = vm.guest_panic() {
+        println!("{panic}");
+    }
+    println!(
+        "Executed {} instructions, {} cycles, in {:.3}s",
+        profile.total(),
+        vm.tracer().cycle(),
+        timer.elapsed().as_secs_f64()
+    );
+    match exit_code {
+        Some(0) => println!("exit code 0. Success."),
+        Some(code) => {
+            println!("exit code {code}. Failure.");
+            process::exit(1);
+        }
+        None => {
+            println!("Unfinished execution. max_steps={:?}.", guest.max_steps);
+            process::exit(1);
+        }
+    }
+}
+
+fn verify<Pcs: PolynomialCommitmentScheme<E>>(proof: &PathBuf, vk: &PathBuf) {
+    let proof: ZKVMProof<E, Pcs> = from_bytes(&read_file(proof)).expect("deserialize proof");
+    let vk: ZKVMVerifyingKey<E, Pcs> =
+        from_bytes(&read_file(vk)).expect("deserialize verifying key");
+    let verifier = ZKVMVerifier::new(vk);
+    match verifier.verify_proof(proof, verifier.new_transcript(b"riscv")) {
+        Ok(true) => println!("Proof verified."),
+        Ok(false) => {
+            println!("Proof rejected.");
+            process::exit(1);
+        }
+        Err(err) => {
+            println!("Proof rejected: {err:?}");
+            process::exit(1);
+        }
+    }
+}
+
+fn verify_bundle<Pcs: PolynomialCommitmentScheme<E>>(bytes: &[u8], vk: &PathBuf) {
+    let bundle = ProofBundle::<E, Pcs>::from_bytes(bytes).expect("deserialize bundle");
+    let vk: ZKVMVerifyingKey<E, Pcs> =
+        from_bytes(&read_file(vk)).expect("deserialize verifying key");
+    match bundle.verify(&vk) {
+        Ok(()) => println!("Proof verified."),
+        Err(err) => {
+            println!("Proof rejected: {err:?}");
+            process::exit(1);
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn read_file(path: &PathBuf) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|err| panic!("read {}: {err}", path.display()))
+}
+
+fn write_file(path: &PathBuf, bytes: &[u8]) {
+    fs::write(path, bytes).unwrap_or_else(|err| panic!("write {}: {err}", path.display()));
+    println!("Wrote {}", path.display());
+}
@@ -1,20 +1,25 @@
 use ceno_emul::{CENO_PLATFORM, IterAddresses, Platform, Program, WORD_SIZE, Word};
 use ceno_zkvm::{
-    e2e::{run_e2e_gen_witness, run_e2e_proof, run_e2e_verify},
-    with_panic_hook,
+    SpillAllocator,
+    e2e::{run_e2e_gen_witness, run_e2e_proof, run_e2e_segments, run_e2e_verify, verify_segments},
+    scheme::pcs::{PcsKind, PolynomialCommitmentScheme},
+    set_spill_threshold, with_panic_hook, with_pcs,
 };
 use clap::{Parser, ValueEnum};
 use ff_ext::ff::Field;
 use goldilocks::{Goldilocks, GoldilocksExt2};
 use itertools::Itertools;
-use mpcs::{Basefold, BasefoldRSParams};
-use std::{fs, panic, time::Instant};
+use std::{fs, panic, path::PathBuf, time::Instant};
 use tracing::level_filters::LevelFilter;
 use tracing_forest::ForestLayer;
 use tracing_subscriber::{
     EnvFilter, Registry, filter::filter_fn, fmt, layer::SubscriberExt, util::SubscriberInitExt,
 };
-use transcript::BasicTranscript as Transcript;
+use transcript::TranscriptHash;
+
+/// Spills the large allocations to disk under `--spill-threshold`, and else allocates as usual.
+#[global_allocator]
+static ALLOCATOR: SpillAllocator = SpillAllocator;
 
 /// Prove the execution of a fixed RISC-V program.
 #[derive(Parser, Debug)]
@@ -49,6 +54,28 @@ struct Args {
     /// Heap size in bytes.
     #[arg(long, default_value = "2097152")]
     heap_size: u32,
+
+    /// Directory caching proving keys across runs of the same program, and the fixed traces of
+    /// the tables which do not depend on it.
+    #[arg(long)]
+    key_cache: Option<PathBuf>,
+
+    /// The polynomial commitment scheme: basefold-rs or basefold-basecode.
+    #[arg(long, default_value = "basefold-rs")]
+    pcs: PcsKind,
+
+    /// The hash function of the Fiat–Shamir transcript: poseidon, blake3 or keccak.
+    #[arg(long, default_value = "poseidon")]
+    transcript_hash: TranscriptHash,
+
+    /// Split the execution into segments of this many steps, proven separately.
+    #[arg(long)]
+    segment_steps: Option<usize>,
+
+    /// Keep the witness matrices, and the other allocations, of at least this many bytes in
+    /// temporary files rather than in memory.
+    #[arg(long)]
+    spill_threshold: Option<usize>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -105,6 +132,7 @@ fn main() {
         args.heap_size = args.heap_size.next_multiple_of(WORD_SIZE as u32);
         args
     };
+    set_spill_threshold(args.spill_threshold);
 
     tracing::info!("Loading ELF file: {}", &args.elf);
     let elf_bytes = fs::read(&args.elf).expect("read elf file");
@@ -139,11 +167,64 @@ fn main() {
 
     let max_steps = args.max_steps.unwrap_or(usize::MAX);
 
-    type E = GoldilocksExt2;
-    type B = Goldilocks;
-    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+    with_pcs!(args.pcs, E, Pcs => {
+        tracing::info!("Proving with {}", args.pcs);
+        prove::<Pcs>(&args, program, platform, hints, max_steps)
+    });
+}
+
+type E = GoldilocksExt2;
+type B = Goldilocks;
+
+fn prove<Pcs: PolynomialCommitmentScheme<E>>(
+    args: &Args,
+    program: Program,
+    platform: Platform,
+    hints: Vec<u32>,
+    max_steps: usize,
+) {
+    if let Some(segment_steps) = args.segment_steps {
+        let timer = Instant::now();
+        let segments = run_e2e_segments::<E, Pcs>(
+            program.clone(),
+            platform.clone(),
+            args.stack_size,
+            args.heap_size,
+            hints,
+            max_steps,
+            segment_steps,
+            args.transcript_hash,
+        )
+        .expect("segments proving failed");
+        println!(
+            "Proving finished.\n\tSegments = {}, total time = {:.3}s",
+            segments.len(),
+            timer.elapsed().as_secs_f64()
+        );
+        if segments
+            .last()
+            .is_some_and(|segment| segment.exit_code.is_none())
+        {
+            tracing::error!("Unfinished execution. max_steps={:?}.", max_steps);
+            return;
+        }
+        match verify_segments(
+            &segments,
+            &program,
+            &platform,
+            args.stack_size,
+            args.heap_size,
+            args.transcript_hash,
+        )
+        .expect("segments verification failed")
+        {
+            0 => tracing::info!("exit code 0. Success."),
+            code => tracing::error!("exit code {}. Failure.", code),
+        }
+        return;
+    }
 
-    let (prover, verifier, zkvm_witness, pi, cycle_num, e2e_start, exit_code) =
+    let (prover, verifier, zkvm_witness, pi, cycle_num, e2e_start, exit_code, _) =
         run_e2e_gen_witness::<E, Pcs>(
             program,
             platform,
@@ -151,10 +232,13 @@ fn main() {
             args.heap_size,
             hints,
             max_steps,
-        );
+            args.key_cache.as_deref(),
+            args.transcript_hash,
+        )
+        .expect("witness generation failed");
 
     let timer = Instant::now();
-    let mut zkvm_proof = run_e2e_proof(prover, zkvm_witness, pi);
+    let mut zkvm_proof = run_e2e_proof(prover, zkvm_witness, pi).expect("create_proof failed");
     let proving_time = timer.elapsed().as_secs_f64();
     let e2e_time = e2e_start.elapsed().as_secs_f64();
     let witgen_time = e2e_time - proving_time;
@@ -176,14 +260,16 @@ fn main() {
     run_e2e_verify(&verifier, zkvm_proof.clone(), exit_code, max_steps);
 
     // do sanity check
-    let transcript = Transcript::new(b"riscv");
+    let transcript = verifier.new_transcript(b"riscv");
     // change public input maliciously should cause verifier to reject proof
     zkvm_proof.raw_pi[0] = vec![B::ONE];
     zkvm_proof.raw_pi[1] = vec![B::ONE];
 
     // capture panic message, if have
     let result = with_panic_hook(Box::new(|_info| ()), || {
-        panic::catch_unwind(|| verifier.verify_proof(zkvm_proof, transcript))
+        panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            verifier.verify_proof(zkvm_proof, transcript)
+        }))
     });
     match result {
         Ok(res) => {
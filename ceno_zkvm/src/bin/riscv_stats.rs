@@ -10,7 +10,7 @@ type E = GoldilocksExt2;
 fn main() {
     let mut zkvm_cs = ZKVMConstraintSystem::default();
 
-    let _ = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs);
+    Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs).expect("circuit construction failed");
     let static_report = StaticReport::new(&zkvm_cs);
     let report = TraceReport::new(&static_report, BTreeMap::new(), "no program");
     report.save_table("riscv_stats.txt");
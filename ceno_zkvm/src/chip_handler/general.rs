@@ -5,11 +5,11 @@ use crate::{
     error::ZKVMError,
     expression::{Expression, Fixed, Instance, ToExpr, WitIn},
     instructions::riscv::constants::{
-        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, INIT_CYCLE_IDX, INIT_PC_IDX, PUBLIC_IO_IDX,
-        UINT_LIMBS,
+        END_CYCLE_IDX, END_PC_IDX, EXIT_CODE_IDX, INIT_CYCLE_IDX, INIT_PC_IDX, OUTPUT_DIGEST_IDX,
+        OUTPUT_DIGEST_WORDS, PUBLIC_IO_IDX, UINT_LIMBS,
     },
     structs::{ProgramParams, RAMType, ROMType},
-    tables::InsnRecord,
+    tables::{BITS8_WIDTH, InsnRecord, range_lookups},
 };
 
 impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
@@ -36,6 +36,28 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.cs.create_fixed(name_fn)
     }
 
+    /// See `ConstraintSystem::create_fixed_with`.
+    pub fn create_fixed_with<NR, N>(
+        &mut self,
+        name_fn: N,
+        generator: fn(usize) -> u64,
+    ) -> Result<Fixed, ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs.create_fixed_with(name_fn, generator)
+    }
+
+    /// See `ConstraintSystem::create_instance`.
+    pub fn create_instance<NR, N>(&mut self, name_fn: N) -> Instance
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs.create_instance(name_fn)
+    }
+
     pub fn query_exit_code(&mut self) -> Result<[Instance; UINT_LIMBS], ZKVMError> {
         Ok([
             self.cs.query_instance(|| "exit_code_low", EXIT_CODE_IDX)?,
@@ -64,6 +86,37 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.cs.query_instance(|| "public_io", PUBLIC_IO_IDX)
     }
 
+    /// The values of a table at the start and at the end of a segment, by limb, from the public
+    /// values at `idx`, see `NonVolatileTable::STATE_IDX`.
+    pub fn query_segment_state(
+        &mut self,
+        idx: usize,
+        num_limbs: usize,
+    ) -> Result<[Vec<Instance>; 2], ZKVMError> {
+        let query = |cb: &mut Self, name: &str, offset: usize| {
+            (0..num_limbs)
+                .map(|l| {
+                    cb.cs
+                        .query_instance(|| format!("{name}_limb_{l}"), idx + offset + l)
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok([
+            query(self, "init_state", 0)?,
+            query(self, "end_state", num_limbs)?,
+        ])
+    }
+
+    pub fn query_output_digest(&mut self) -> Result<[Instance; OUTPUT_DIGEST_WORDS], ZKVMError> {
+        let mut digest = [Instance(OUTPUT_DIGEST_IDX); OUTPUT_DIGEST_WORDS];
+        for (i, word) in digest.iter_mut().enumerate() {
+            *word = self
+                .cs
+                .query_instance(|| format!("output_digest_{i}"), OUTPUT_DIGEST_IDX + i)?;
+        }
+        Ok(digest)
+    }
+
     pub fn lk_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -93,6 +146,22 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
             .lk_table_record(name_fn, table_len, rom_type, record, multiplicity)
     }
 
+    pub fn lk_batched_table_record<NR, N>(
+        &mut self,
+        name_fn: N,
+        table_len: usize,
+        rom_types: Vec<ROMType>,
+        record: Vec<Expression<E>>,
+        multiplicity: Expression<E>,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs
+            .lk_batched_table_record(name_fn, table_len, rom_types, record, multiplicity)
+    }
+
     pub fn r_table_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -250,6 +319,7 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         NR: Into<String>,
         N: FnOnce() -> NR,
     {
+        self.record_cond(&cond);
         // cond * (true_expr) + (1 - cond) * false_expr
         // => false_expr + cond * true_expr - cond * false_expr
         self.namespace(
@@ -261,15 +331,91 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         )
     }
 
+    /// See `ConstraintSystem::copy_witin`.
+    pub fn copy_witin<NR, N>(&mut self, name_fn: N, witin: WitIn) -> Result<WitIn, ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.cs.copy_witin(name_fn, witin)
+    }
+
+    pub fn require_copy<NR, N>(&mut self, name_fn: N, a: WitIn, b: WitIn) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespace(|| "require_copy", |cb| cb.cs.require_copy(name_fn, a, b))
+    }
+
+    /// Require `expr = 0` when `cond = 1`, and nothing when `cond = 0`.
+    /// `cond` must be boolean. The constraint has degree `deg(cond) + deg(expr)`.
+    pub fn require_zero_if<NR, N>(
+        &mut self,
+        name_fn: N,
+        cond: Expression<E>,
+        expr: Expression<E>,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.record_cond(&cond);
+        self.namespace(
+            || "require_zero_if",
+            |cb| cb.cs.require_zero(name_fn, cond * expr),
+        )
+    }
+
+    /// Require `lhs = rhs` when `cond = 1`, and nothing when `cond = 0`.
+    /// `cond` must be boolean. The constraint has degree `deg(cond) + max(deg(lhs), deg(rhs))`.
+    pub fn require_equal_if<NR, N>(
+        &mut self,
+        name_fn: N,
+        cond: Expression<E>,
+        lhs: Expression<E>,
+        rhs: Expression<E>,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.record_cond(&cond);
+        self.namespace(
+            || "require_equal_if",
+            |cb| cb.cs.require_zero(name_fn, cond * (lhs - rhs)),
+        )
+    }
+
     pub fn select(
         &mut self,
         cond: &Expression<E>,
         when_true: &Expression<E>,
         when_false: &Expression<E>,
     ) -> Expression<E> {
+        self.record_cond(cond);
         cond * when_true + (1 - cond) * when_false
     }
 
+    /// Remember a plain witness condition, for `lint_circuit` to check that it is boolean.
+    fn record_cond(&mut self, cond: &Expression<E>) {
+        if let Expression::WitIn(id) = cond {
+            self.cs.cond_witins.insert(*id);
+        }
+    }
+
+    /// Mark `expr`, if it is a plain witness, as bounded by a check which is not a range lookup.
+    pub(crate) fn record_range_checked(&mut self, expr: &Expression<E>) {
+        if let Expression::WitIn(id) = expr {
+            self.cs.range_checked_witins.insert(*id);
+        }
+    }
+
+    /// Mark UInt carries, for `lint_circuit` to check that they are range checked.
+    pub(crate) fn record_carries(&mut self, carries: &[WitIn]) {
+        self.cs.carry_witins.extend_from_slice(carries);
+    }
+
     pub(crate) fn assert_ux<NR, N, const C: usize>(
         &mut self,
         name_fn: N,
@@ -284,10 +430,40 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
             14 => self.assert_u14(name_fn, expr),
             8 => self.assert_byte(name_fn, expr),
             5 => self.assert_u5(name_fn, expr),
-            c => panic!("Unsupported bit range {c}"),
+            c => self.assert_bits(name_fn, expr, c),
         }
     }
 
+    /// Assert that `expr` fits in `bits <= 16` bits, see `range_lookups`.
+    /// Wider values are split into limbs by `RangeCheckConfig`.
+    pub fn assert_bits<NR, N>(
+        &mut self,
+        name_fn: N,
+        expr: Expression<E>,
+        bits: usize,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        self.namespace(
+            || format!("assert_u{bits}"),
+            |cb| {
+                for (rom_type, shift) in range_lookups(bits) {
+                    if shift == 0 {
+                        cb.lk_record(|| name.clone(), rom_type, vec![expr.clone()])?;
+                    } else {
+                        cb.lk_record(|| format!("{name}_shifted"), rom_type, vec![
+                            expr.clone() << shift,
+                        ])?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
     fn assert_u5<NR, N>(&mut self, name_fn: N, expr: Expression<E>) -> Result<(), ZKVMError>
     where
         NR: Into<String>,
@@ -352,12 +528,91 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         NR: Into<String>,
         N: FnOnce() -> NR,
     {
+        if let Expression::WitIn(id) = expr {
+            self.cs.bit_witins.insert(id);
+        }
         self.namespace(
             || "assert_bit",
             |cb| cb.cs.require_zero(name_fn, &expr * (1 - &expr)),
         )
     }
 
+    /// Assert that every expression of `bits` is boolean, with one `Bits8` lookup per 8 of them
+    /// instead of one constraint each.
+    pub fn assert_bit_batch<NR, N>(
+        &mut self,
+        name_fn: N,
+        bits: &[Expression<E>],
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        for expr in bits {
+            if let Expression::WitIn(id) = expr {
+                self.cs.bit_witins.insert(*id);
+            }
+        }
+        self.namespace(
+            || "assert_bit_batch",
+            |cb| {
+                for (i, chunk) in bits.chunks(BITS8_WIDTH).enumerate() {
+                    let mut record = chunk.to_vec();
+                    record.resize(BITS8_WIDTH, Expression::ZERO);
+                    cb.lk_record(|| format!("{name}_{i}"), ROMType::Bits8, record)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Constrain a bit operand, once per witness. Operands other than a plain witness are
+    /// expected to be boolean by construction, e.g. the output of another `b_*` helper.
+    fn constrain_bit_operand(&mut self, expr: &Expression<E>) -> Result<(), ZKVMError> {
+        match expr {
+            Expression::WitIn(id) if !self.cs.bit_witins.contains(id) => {
+                self.assert_bit(|| "bit_operand", expr.clone())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `!a`, of degree `deg(a)`.
+    pub fn b_not(&mut self, a: Expression<E>) -> Result<Expression<E>, ZKVMError> {
+        self.constrain_bit_operand(&a)?;
+        Ok(1 - a)
+    }
+
+    /// `a & b`, of degree `deg(a) + deg(b)`.
+    pub fn b_and(
+        &mut self,
+        a: Expression<E>,
+        b: Expression<E>,
+    ) -> Result<Expression<E>, ZKVMError> {
+        self.constrain_bit_operand(&a)?;
+        self.constrain_bit_operand(&b)?;
+        Ok(a * b)
+    }
+
+    /// `a | b`, of degree `deg(a) + deg(b)`.
+    pub fn b_or(&mut self, a: Expression<E>, b: Expression<E>) -> Result<Expression<E>, ZKVMError> {
+        self.constrain_bit_operand(&a)?;
+        self.constrain_bit_operand(&b)?;
+        Ok(a.clone() + b.clone() - a * b)
+    }
+
+    /// `a ^ b`, of degree `deg(a) + deg(b)`.
+    pub fn b_xor(
+        &mut self,
+        a: Expression<E>,
+        b: Expression<E>,
+    ) -> Result<Expression<E>, ZKVMError> {
+        self.constrain_bit_operand(&a)?;
+        self.constrain_bit_operand(&b)?;
+        Ok(a.clone() + b.clone() - 2 * a * b)
+    }
+
     /// Assert `rom_type(a, b) = c` and that `a, b, c` are all bytes.
     pub fn logic_u8(
         &mut self,
@@ -414,20 +669,61 @@ impl<'a, E: ExtensionField> CircuitBuilder<'a, E> {
         self.logic_u8(ROMType::Pow, 2.into(), b, c)
     }
 
-    pub(crate) fn is_equal(
+    /// Assert that `!a & b = c` and that `a, b, c` are all bytes.
+    pub fn lookup_keccak_chi(
+        &mut self,
+        a: Expression<E>,
+        b: Expression<E>,
+        c: Expression<E>,
+    ) -> Result<(), ZKVMError> {
+        self.logic_u8(ROMType::KeccakChi, a, b, c)
+    }
+
+    /// Assert that `rc_lo + rc_hi * 2^32` is the round constant of Keccak round `round`.
+    pub fn lookup_keccak_rc(
+        &mut self,
+        round: Expression<E>,
+        rc_lo: Expression<E>,
+        rc_hi: Expression<E>,
+    ) -> Result<(), ZKVMError> {
+        self.lk_record(|| "lookup_keccak_rc", ROMType::KeccakRc, vec![
+            round, rc_lo, rc_hi,
+        ])
+    }
+
+    /// Assert that `offset` is the rho rotation offset of Keccak lane `lane`.
+    pub fn lookup_keccak_rho(
+        &mut self,
+        lane: Expression<E>,
+        offset: Expression<E>,
+    ) -> Result<(), ZKVMError> {
+        self.lk_record(|| "lookup_keccak_rho", ROMType::KeccakRho, vec![
+            lane, offset,
+        ])
+    }
+
+    /// Allocate `(is_zero, inverse)` such that `is_zero` is 1 if `expr` is zero, 0 otherwise.
+    /// The witnesses are assigned with `gadgets::is_zero_witness`.
+    pub fn is_zero(&mut self, expr: Expression<E>) -> Result<(WitIn, WitIn), ZKVMError> {
+        let is_zero = self.create_witin(|| "is_zero");
+        let inverse = self.create_witin(|| "inv");
+        // boolean by the constraints below
+        self.cs.bit_witins.insert(is_zero.id);
+
+        // expr != 0 => is_zero = 0
+        self.require_zero_if(|| "is_zero_0", is_zero.expr(), expr.clone())?;
+        // expr == 0 => is_zero = 1
+        self.require_one(|| "is_zero_1", is_zero.expr() + expr * inverse.expr())?;
+
+        Ok((is_zero, inverse))
+    }
+
+    /// Allocate `(is_equal, diff_inverse)`, the `is_zero` pair of `lhs - rhs`.
+    pub fn is_equal(
         &mut self,
         lhs: Expression<E>,
         rhs: Expression<E>,
     ) -> Result<(WitIn, WitIn), ZKVMError> {
-        let is_eq = self.create_witin(|| "is_eq");
-        let diff_inverse = self.create_witin(|| "diff_inverse");
-
-        self.require_zero(|| "is equal", is_eq.expr() * &lhs - is_eq.expr() * &rhs)?;
-        self.require_zero(
-            || "is equal",
-            1 - is_eq.expr() - diff_inverse.expr() * lhs + diff_inverse.expr() * rhs,
-        )?;
-
-        Ok((is_eq, diff_inverse))
+        self.is_zero(lhs - rhs)
     }
 }
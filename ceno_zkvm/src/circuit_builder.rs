@@ -1,21 +1,34 @@
 use ceno_emul::Addr;
 use itertools::{Itertools, chain};
-use std::{collections::HashMap, iter::once, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    iter::once,
+    marker::PhantomData,
+    mem::MaybeUninit,
+};
 
+use ff::Field;
 use ff_ext::ExtensionField;
 use mpcs::PolynomialCommitmentScheme;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 
 use crate::{
     ROMType,
     chip_handler::utils::rlc_chip_record,
     error::ZKVMError,
-    expression::{Expression, Fixed, Instance, WitIn},
-    structs::{ProgramParams, ProvingKey, RAMType, VerifyingKey, WitnessId},
+    expression::{Expression, Fixed, Instance, ToExpr, WitIn},
+    instructions::riscv::constants::NUM_PUBLIC_VALUES,
+    scheme::constants::MIN_PAR_SIZE,
+    set_val,
+    structs::{
+        ChallengeId, ProgramParams, ProvingKey, RAMType, VerifyingKey, WitnessId, challenge_id,
+    },
     witness::RowMajorMatrix,
 };
 
 /// namespace used for annotation, preserve meta info during circuit construction
-#[derive(Clone, Debug, Default, serde::Serialize)]
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct NameSpace {
     namespace: Vec<String>,
 }
@@ -66,34 +79,116 @@ impl NameSpace {
     }
 }
 
-#[derive(Clone, Debug)]
+/// A namespace of a constructed circuit, with what was allocated directly in it.
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct NamespaceNode {
+    pub name: String,
+    pub path: String,
+    pub children: Vec<NamespaceNode>,
+    pub witins: Vec<WitnessId>,
+    /// names of the zero constraints, linear and sumcheck alike
+    pub constraints: Vec<String>,
+    pub lookups: Vec<String>,
+}
+
+impl NamespaceNode {
+    /// Find the node at `path`, relative to this node, e.g. `"add/rs1"`.
+    pub fn find(&self, path: &str) -> Option<&NamespaceNode> {
+        path.split('/').try_fold(self, |node, name| {
+            node.children.iter().find(|child| child.name == name)
+        })
+    }
+
+    /// This node and all its descendants, parents before children.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &NamespaceNode> + '_> {
+        Box::new(once(self).chain(self.children.iter().flat_map(|child| child.iter())))
+    }
+
+    /// Number of zero constraints in this node and its descendants.
+    pub fn num_constraints(&self) -> usize {
+        self.iter().map(|node| node.constraints.len()).sum()
+    }
+
+    /// The node at the absolute `path`, created along with its ancestors if missing.
+    fn node_mut(&mut self, path: &str) -> &mut NamespaceNode {
+        if path == self.path {
+            return self;
+        }
+        // the root name may itself contain '/'
+        let relative = path
+            .strip_prefix(self.path.as_str())
+            .and_then(|rest| rest.strip_prefix('/'))
+            .unwrap_or(path);
+        relative.split('/').fold(self, |node, name| {
+            let i = match node.children.iter().position(|child| child.name == name) {
+                Some(i) => i,
+                None => {
+                    node.children.push(NamespaceNode {
+                        name: name.to_string(),
+                        path: format!("{}/{}", node.path, name),
+                        ..Default::default()
+                    });
+                    node.children.len() - 1
+                }
+            };
+            &mut node.children[i]
+        })
+    }
+
+    /// The enclosing node of the item at the absolute `path`, and the item name.
+    fn parent_mut<'a>(&mut self, path: &'a str) -> (&mut NamespaceNode, &'a str) {
+        match path.rsplit_once('/') {
+            Some((ns, name)) => (self.node_mut(ns), name),
+            None => (self, path),
+        }
+    }
+}
+
+/// Names of the witness and fixed columns of a circuit, indexed by column.
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct ColumnNames {
+    pub witin: Vec<String>,
+    pub fixed: Vec<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: serde::Serialize",
+    deserialize = "E: serde::de::DeserializeOwned, E::BaseField: serde::de::DeserializeOwned"
+))]
 pub struct LogupTableExpression<E: ExtensionField> {
     pub multiplicity: Expression<E>,
     pub values: Expression<E>,
     pub table_len: usize,
+    /// ROM types served by this table, more than one for a batched table
+    pub rom_types: Vec<ROMType>,
 }
 
 // TODO encapsulate few information of table spec to SetTableAddrType value
 // once confirm syntax is friendly and parsed by recursive verifier
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum SetTableAddrType {
     FixedAddr,
     DynamicAddr(DynamicAddr),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct DynamicAddr {
     pub addr_witin_id: usize,
     pub offset: Addr,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SetTableSpec {
     pub addr_type: SetTableAddrType,
     pub len: usize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: serde::Serialize",
+    deserialize = "E: serde::de::DeserializeOwned, E::BaseField: serde::de::DeserializeOwned"
+))]
 pub struct SetTableExpression<E: ExtensionField> {
     pub expr: Expression<E>,
 
@@ -102,7 +197,14 @@ pub struct SetTableExpression<E: ExtensionField> {
     pub table_spec: SetTableSpec,
 }
 
-#[derive(Clone, Debug)]
+/// The value of a fixed column at a given row, see `create_fixed_with`.
+pub type FixedGenerator = fn(usize) -> u64;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: serde::Serialize",
+    deserialize = "E: serde::de::DeserializeOwned, E::BaseField: serde::de::DeserializeOwned"
+))]
 pub struct ConstraintSystem<E: ExtensionField> {
     pub(crate) ns: NameSpace,
 
@@ -112,8 +214,17 @@ pub struct ConstraintSystem<E: ExtensionField> {
 
     pub num_fixed: usize,
     pub fixed_namespace_map: Vec<String>,
+    /// the value of each fixed column at a given row, if created by `create_fixed_with`; not
+    /// serialized
+    #[serde(skip)]
+    pub fixed_generators: Vec<Option<FixedGenerator>>,
+
+    pub instance_name_map: BTreeMap<Instance, String>,
+    /// number of instance columns, see `create_instance`
+    pub num_instance_columns: usize,
 
-    pub instance_name_map: HashMap<Instance, String>,
+    /// paths of all namespaces entered during construction
+    pub namespace_paths: BTreeSet<String>,
 
     pub r_expressions: Vec<Expression<E>>,
     pub r_expressions_namespace_map: Vec<String>,
@@ -139,7 +250,8 @@ pub struct ConstraintSystem<E: ExtensionField> {
     pub lk_table_expressions: Vec<LogupTableExpression<E>>,
     pub lk_table_expressions_namespace_map: Vec<String>,
 
-    /// main constraints zero expression
+    /// main constraints zero expression of degree 1, checked by the verifier at the opening point
+    /// up to their constant terms on the padding rows
     pub assert_zero_expressions: Vec<Expression<E>>,
     pub assert_zero_expressions_namespace_map: Vec<String>,
 
@@ -153,10 +265,29 @@ pub struct ConstraintSystem<E: ExtensionField> {
     // alpha, beta challenge for chip record
     pub chip_record_alpha: Expression<E>,
     pub chip_record_beta: Expression<E>,
+    /// challenges requested by name
+    pub challenges: BTreeMap<String, ChallengeId>,
 
-    pub debug_map: HashMap<usize, Vec<Expression<E>>>,
+    pub debug_map: BTreeMap<usize, Vec<Expression<E>>>,
     pub lk_expressions_items_map: Vec<(ROMType, Vec<Expression<E>>)>,
 
+    /// witnesses already constrained to be boolean
+    pub(crate) bit_witins: BTreeSet<WitnessId>,
+    /// witnesses used as the condition of a conditional constraint, assumed to be boolean
+    pub(crate) cond_witins: BTreeSet<WitnessId>,
+    /// witnesses bounded by a dedicated check other than a range lookup, e.g. `AssertLTConfig`
+    pub(crate) range_checked_witins: BTreeSet<WitnessId>,
+    /// carries of UInt arithmetic, which must be range checked
+    pub(crate) carry_witins: Vec<WitIn>,
+
+    /// constraints above this degree are reduced as they are added
+    pub degree_reduction: Option<usize>,
+    /// witnesses introduced by degree reduction or copies, each equal to an expression of earlier
+    /// witnesses
+    pub derived_witins: Vec<(WitIn, Expression<E>)>,
+    /// pairs of witnesses, possibly from different namespaces, constrained to be equal
+    pub copy_constraints: Vec<(WitIn, WitIn)>,
+
     pub(crate) phantom: PhantomData<E>,
 }
 
@@ -168,8 +299,11 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             witin_namespace_map: vec![],
             num_fixed: 0,
             fixed_namespace_map: vec![],
+            fixed_generators: vec![],
             ns: NameSpace::new(root_name_fn),
-            instance_name_map: HashMap::new(),
+            instance_name_map: BTreeMap::new(),
+            num_instance_columns: 0,
+            namespace_paths: BTreeSet::new(),
             r_expressions: vec![],
             r_expressions_namespace_map: vec![],
             r_ram_types: vec![],
@@ -191,14 +325,30 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             max_non_lc_degree: 0,
             chip_record_alpha: Expression::Challenge(0, 1, E::ONE, E::ZERO),
             chip_record_beta: Expression::Challenge(1, 1, E::ONE, E::ZERO),
+            challenges: BTreeMap::new(),
 
-            debug_map: HashMap::new(),
+            debug_map: BTreeMap::new(),
             lk_expressions_items_map: vec![],
+            bit_witins: BTreeSet::new(),
+            cond_witins: BTreeSet::new(),
+            range_checked_witins: BTreeSet::new(),
+            carry_witins: vec![],
+            degree_reduction: None,
+            derived_witins: vec![],
+            copy_constraints: vec![],
 
             phantom: std::marker::PhantomData,
         }
     }
 
+    /// Whether this is an opcode circuit, as opposed to a table circuit, which has table
+    /// expressions.
+    pub fn is_opcode_circuit(&self) -> bool {
+        self.lk_table_expressions.is_empty()
+            && self.r_table_expressions.is_empty()
+            && self.w_table_expressions.is_empty()
+    }
+
     pub fn key_gen<PCS: PolynomialCommitmentScheme<E>>(
         self,
         pp: &PCS::ProverParam,
@@ -248,10 +398,59 @@ impl<E: ExtensionField> ConstraintSystem<E> {
 
         let path = self.ns.compute_path(n().into());
         self.fixed_namespace_map.push(path);
+        self.fixed_generators.push(None);
+
+        Ok(f)
+    }
+
+    /// A fixed column whose value at each row is given by `generator`, so that its trace comes
+    /// from `generate_fixed_traces` rather than being assigned out of band.
+    pub fn create_fixed_with<NR: Into<String>, N: FnOnce() -> NR>(
+        &mut self,
+        n: N,
+        generator: FixedGenerator,
+    ) -> Result<Fixed, ZKVMError> {
+        let f = self.create_fixed(n)?;
+        self.fixed_generators[f.0] = Some(generator);
 
         Ok(f)
     }
 
+    /// A column with one value per instance, which the verifier receives in the clear.
+    /// Unlike public values, see `query_instance`, it belongs to this circuit only. Its index
+    /// follows the public values. Instance columns are only supported in opcode circuits.
+    pub fn create_instance<NR: Into<String>, N: FnOnce() -> NR>(&mut self, n: N) -> Instance {
+        let i = Instance(NUM_PUBLIC_VALUES + self.num_instance_columns);
+        self.num_instance_columns += 1;
+
+        let path = self.ns.compute_path(n().into());
+        self.instance_name_map.insert(i, path);
+
+        i
+    }
+
+    /// The verifier evaluates instance columns at the opening point, as it does with the
+    /// witnesses of degree 1 constraints, so they cannot be multiplied by another column.
+    fn assert_instance_columns_linear(&self, expr: &Expression<E>) {
+        if self.num_instance_columns == 0 {
+            return;
+        }
+        let is_instance_column = |var: &Expression<E>| matches!(var, Expression::Instance(i) if i.0 >= NUM_PUBLIC_VALUES);
+        for (_, vars) in expr.monomials() {
+            let num_columns = vars
+                .iter()
+                .filter(|var| {
+                    matches!(var, Expression::WitIn(_) | Expression::Fixed(_))
+                        || is_instance_column(var)
+                })
+                .count();
+            assert!(
+                num_columns <= 1 || !vars.iter().any(is_instance_column),
+                "instance column multiplied by another column"
+            );
+        }
+    }
+
     pub fn query_instance<NR: Into<String>, N: FnOnce() -> NR>(
         &mut self,
         n: N,
@@ -265,7 +464,24 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         Ok(i)
     }
 
+    /// The challenge registered as `name` in `CHALLENGE_NAMES`.
+    pub fn challenge(&mut self, name: &str) -> Result<Expression<E>, ZKVMError> {
+        let id = match self.challenges.get(name) {
+            Some(id) => *id,
+            None => {
+                let id = challenge_id(name)
+                    .ok_or_else(|| ZKVMError::UnknownChallenge(name.to_string()))?;
+                self.challenges.insert(name.to_string(), id);
+                id
+            }
+        };
+        Ok(Expression::Challenge(id, 1, E::ONE, E::ZERO))
+    }
+
     pub fn rlc_chip_record(&self, items: Vec<Expression<E>>) -> Expression<E> {
+        items
+            .iter()
+            .for_each(|item| self.assert_instance_columns_linear(item));
         rlc_chip_record(
             items,
             self.chip_record_alpha.clone(),
@@ -327,6 +543,7 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             values: rlc_record,
             multiplicity,
             table_len,
+            rom_types: vec![rom_type],
         });
         let path = self.ns.compute_path(name_fn().into());
         self.lk_table_expressions_namespace_map.push(path);
@@ -337,6 +554,48 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         Ok(())
     }
 
+    /// Table record serving several ROM types at once.
+    /// The first item of `record` is the table id of the row, i.e. its `ROMType`,
+    /// which replaces the constant ROM type prefix of `lk_table_record`.
+    pub fn lk_batched_table_record<NR, N>(
+        &mut self,
+        name_fn: N,
+        table_len: usize,
+        rom_types: Vec<ROMType>,
+        record: Vec<Expression<E>>,
+        multiplicity: Expression<E>,
+    ) -> Result<(), ZKVMError>
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        assert!(
+            rom_types.len() > 1,
+            "batched table needs at least 2 rom types ({})",
+            name_fn().into()
+        );
+        let rlc_record = self.rlc_chip_record(record.clone());
+        assert_eq!(
+            rlc_record.degree(),
+            1,
+            "rlc lk_batched_table_record degree ({})",
+            name_fn().into()
+        );
+        let first_rom_type = rom_types[0];
+        self.lk_table_expressions.push(LogupTableExpression {
+            values: rlc_record,
+            multiplicity,
+            table_len,
+            rom_types,
+        });
+        let path = self.ns.compute_path(name_fn().into());
+        self.lk_table_expressions_namespace_map.push(path);
+        // record keeps the table id as first item
+        self.lk_expressions_items_map.push((first_rom_type, record));
+
+        Ok(())
+    }
+
     pub fn r_table_record<NR, N>(
         &mut self,
         name_fn: N,
@@ -446,6 +705,13 @@ impl<E: ExtensionField> ConstraintSystem<E> {
             assert_zero_expr.degree() > 0,
             "constant expression assert to zero ?"
         );
+        self.assert_instance_columns_linear(&assert_zero_expr);
+        let assert_zero_expr = match self.degree_reduction {
+            Some(max_degree) if assert_zero_expr.degree() > max_degree => {
+                self.reduce_degree(assert_zero_expr, max_degree)?
+            }
+            _ => assert_zero_expr,
+        };
         if assert_zero_expr.degree() == 1 {
             self.assert_zero_expressions.push(assert_zero_expr);
             let path = self.ns.compute_path(name_fn().into());
@@ -467,16 +733,295 @@ impl<E: ExtensionField> ConstraintSystem<E> {
         Ok(())
     }
 
+    /// Reduce every constraint added from now on to degree at most `max_degree`.
+    /// The witnesses this introduces are filled by `assign_derived_witins`.
+    pub fn enable_degree_reduction(&mut self, max_degree: usize) {
+        assert!(max_degree >= 2, "cannot reduce below degree 2");
+        self.degree_reduction = Some(max_degree);
+    }
+
+    /// Rewrite `expr` to degree at most `max_degree`, by replacing product operands with new
+    /// witnesses constrained to be equal to them.
+    pub fn reduce_degree(
+        &mut self,
+        expr: Expression<E>,
+        max_degree: usize,
+    ) -> Result<Expression<E>, ZKVMError> {
+        Ok(match expr {
+            Expression::Sum(a, b) => Expression::Sum(
+                Box::new(self.reduce_degree(*a, max_degree)?),
+                Box::new(self.reduce_degree(*b, max_degree)?),
+            ),
+            Expression::Product(a, b) => {
+                let mut a = self.reduce_degree(*a, max_degree)?;
+                let mut b = self.reduce_degree(*b, max_degree)?;
+                // replace the operand of higher degree first
+                if a.degree() < b.degree() {
+                    std::mem::swap(&mut a, &mut b);
+                }
+                if a.degree() + b.degree() > max_degree {
+                    a = self.derive_witin(a)?;
+                }
+                if a.degree() + b.degree() > max_degree {
+                    b = self.derive_witin(b)?;
+                }
+                Expression::Product(Box::new(a), Box::new(b))
+            }
+            Expression::ScaledSum(x, a, b) => {
+                let mut x = self.reduce_degree(*x, max_degree)?;
+                if x.degree() + a.degree() > max_degree {
+                    x = self.derive_witin(x)?;
+                }
+                Expression::ScaledSum(
+                    Box::new(x),
+                    a,
+                    Box::new(self.reduce_degree(*b, max_degree)?),
+                )
+            }
+            expr => expr,
+        })
+    }
+
+    /// A new witness constrained to be equal to `expr`, which must depend only on witnesses and
+    /// constants, as `assign_derived_witins` fills it from the row of the witness alone.
+    fn derive_witin(&mut self, expr: Expression<E>) -> Result<Expression<E>, ZKVMError> {
+        let id = self.derived_witins.len();
+        let derivable = expr.evaluate_with_instance(
+            &|_| false,
+            &|_| true,
+            &|_| false,
+            &|_| true,
+            &|_, _, _, _| false,
+            &|a, b| a && b,
+            &|a, b| a && b,
+            &|x, a, b| x && a && b,
+        );
+        if !derivable {
+            return Err(ZKVMError::NotDerivable {
+                path: self.ns.compute_path(format!("degree_reduction_{id}")),
+            });
+        }
+        let witin = self.create_witin(|| format!("degree_reduction_{id}"));
+        self.require_zero(
+            || format!("degree_reduction_{id}"),
+            witin.expr() - expr.clone(),
+        )?;
+        self.derived_witins.push((witin, expr));
+        Ok(witin.expr())
+    }
+
+    /// A new witness in the current namespace, constrained to be equal to `witin` and filled by
+    /// `assign_derived_witins`. Gadgets may also take `witin` itself to share its column.
+    pub fn copy_witin<NR: Into<String>, N: FnOnce() -> NR>(
+        &mut self,
+        name_fn: N,
+        witin: WitIn,
+    ) -> Result<WitIn, ZKVMError> {
+        let name = name_fn().into();
+        let copy = self.create_witin(|| name.clone());
+        self.require_copy(|| name, witin, copy)?;
+        self.derived_witins.push((copy, witin.expr()));
+        Ok(copy)
+    }
+
+    /// Constrain two witnesses allocated in different places to hold the same value.
+    pub fn require_copy<NR: Into<String>, N: FnOnce() -> NR>(
+        &mut self,
+        name_fn: N,
+        a: WitIn,
+        b: WitIn,
+    ) -> Result<(), ZKVMError> {
+        if a.id == b.id {
+            return Ok(());
+        }
+        self.require_zero(name_fn, a.expr() - b.expr())?;
+        self.copy_constraints.push((a, b));
+        Ok(())
+    }
+
+    /// Fill the witnesses introduced by degree reduction and copies, on the instances. They are
+    /// zero on the padding rows, like the other witnesses.
+    pub fn assign_derived_witins(&self, witness: &mut RowMajorMatrix<E::BaseField>) {
+        if self.derived_witins.is_empty() {
+            return;
+        }
+        witness
+            .par_iter_instances_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .for_each(|row| {
+                for (witin, expr) in &self.derived_witins {
+                    let value = expr.evaluate(
+                        &|_| unreachable!("fixed column in a derived witness"),
+                        &|id| unsafe { row[id as usize].assume_init() },
+                        &|c| c,
+                        &|_, _, _, _| unreachable!("challenge in a derived witness"),
+                        &|a, b| a + b,
+                        &|a, b| a * b,
+                        &|x, a, b| a * x + b,
+                    );
+                    set_val!(row, witin, value);
+                }
+            });
+    }
+
+    /// The zero constraints which the instances of `witness` do not satisfy, by row and then in
+    /// the order they were defined, e.g. to catch an assignment which drifted from its circuit
+    /// right after it rather than in the sumcheck.
+    ///
+    /// The witness must be complete, derived witnesses included. A constraint on the public
+    /// values, the instance columns, the fixed columns or the challenges is skipped, as those are
+    /// not known with the witness, and so are the lookups: `MockProver` checks them all.
+    pub fn unsatisfied_constraints(
+        &self,
+        witness: &RowMajorMatrix<E::BaseField>,
+    ) -> Vec<UnsatisfiedConstraint> {
+        let constraints = chain!(
+            &self.assert_zero_expressions,
+            &self.assert_zero_sumcheck_expressions
+        )
+        .zip_eq(chain!(
+            &self.assert_zero_expressions_namespace_map,
+            &self.assert_zero_sumcheck_expressions_namespace_map
+        ))
+        .collect_vec();
+
+        let mut unsatisfied = vec![];
+        for (row_index, row) in witness
+            .iter_rows()
+            .take(witness.num_instances())
+            .enumerate()
+        {
+            for (expr, name) in &constraints {
+                let value = expr.evaluate_with_instance(
+                    &|_| None,
+                    &|id| Some(unsafe { row[id as usize].assume_init() }),
+                    &|_| None,
+                    &|c| Some(c),
+                    &|_, _, _, _| None,
+                    &|a, b| Some(a? + b?),
+                    &|a, b| Some(a? * b?),
+                    &|x, a, b| Some(a? * x? + b?),
+                );
+                if value.is_some_and(|value| value != E::BaseField::ZERO) {
+                    unsatisfied.push(UnsatisfiedConstraint {
+                        name: name.to_string(),
+                        row: row_index,
+                    });
+                }
+            }
+        }
+        unsatisfied
+    }
+
+    /// Fail with the first of [`Self::unsatisfied_constraints`] of `witness`, the instances of
+    /// the circuit `circuit`, after logging them all.
+    pub fn check_assignment(
+        &self,
+        circuit: &str,
+        witness: &RowMajorMatrix<E::BaseField>,
+    ) -> Result<(), ZKVMError> {
+        let unsatisfied = self.unsatisfied_constraints(witness);
+        for constraint in &unsatisfied {
+            tracing::error!("{circuit}: {constraint}");
+        }
+        match unsatisfied.into_iter().next() {
+            None => Ok(()),
+            Some(UnsatisfiedConstraint { name, row }) => Err(ZKVMError::UnsatisfiedConstraint {
+                circuit: circuit.to_string(),
+                constraint: name,
+                row,
+            }),
+        }
+    }
+
+    /// The fixed traces of `num_rows` rows, from the generators of `create_fixed_with`. Padding
+    /// rows are left to the caller. Fails if a fixed column has no generator.
+    pub fn generate_fixed_traces(
+        &self,
+        num_rows: usize,
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let generators = self
+            .fixed_namespace_map
+            .iter()
+            .enumerate()
+            .map(|(i, path)| {
+                self.fixed_generators
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .ok_or(ZKVMError::FixedTraceNotFound(path.clone()))
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+        let mut fixed = RowMajorMatrix::<E::BaseField>::new(num_rows, self.num_fixed);
+        fixed
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip((0..num_rows).into_par_iter())
+            .for_each(|(row, i)| {
+                for (col, generator) in row.iter_mut().zip(&generators) {
+                    *col = MaybeUninit::new(E::BaseField::from(generator(i)));
+                }
+            });
+
+        Ok(fixed)
+    }
+
     pub fn namespace<NR: Into<String>, N: FnOnce() -> NR, T>(
         &mut self,
         name_fn: N,
         cb: impl FnOnce(&mut ConstraintSystem<E>) -> T,
     ) -> T {
         self.ns.push_namespace(name_fn().into());
+        self.namespace_paths
+            .insert(self.ns.get_namespaces().join("/"));
         let t = cb(self);
         self.ns.pop_namespace();
         t
     }
+
+    /// The full path of the witness column `witin`.
+    pub fn witin_name(&self, witin: WitIn) -> &str {
+        &self.witin_namespace_map[witin.id as usize]
+    }
+
+    /// Column index to name mapping, for witness debugging and trace viewers.
+    pub fn column_names(&self) -> ColumnNames {
+        ColumnNames {
+            witin: self.witin_namespace_map.clone(),
+            fixed: self.fixed_namespace_map.clone(),
+        }
+    }
+
+    /// The namespace tree of the circuit, rooted at the name given to `ConstraintSystem::new`.
+    pub fn namespace_tree(&self) -> NamespaceNode {
+        let root_name = &self.ns.get_namespaces()[0];
+        let mut root = NamespaceNode {
+            name: root_name.clone(),
+            path: root_name.clone(),
+            ..Default::default()
+        };
+
+        for path in &self.namespace_paths {
+            root.node_mut(path);
+        }
+        for (id, path) in self.witin_namespace_map.iter().enumerate() {
+            root.parent_mut(path).0.witins.push(id as WitnessId);
+        }
+        for path in chain!(
+            &self.assert_zero_expressions_namespace_map,
+            &self.assert_zero_sumcheck_expressions_namespace_map
+        ) {
+            let (node, name) = root.parent_mut(path);
+            node.constraints.push(name.to_string());
+        }
+        for path in &self.lk_expressions_namespace_map {
+            let (node, name) = root.parent_mut(path);
+            node.lookups.push(name.to_string());
+        }
+
+        root
+    }
 }
 
 #[cfg(test)]
@@ -495,8 +1040,251 @@ impl<E: ExtensionField> ConstraintSystem<E> {
     }
 }
 
+/// A zero constraint which a row of a witness does not satisfy, see
+/// [`ConstraintSystem::unsatisfied_constraints`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsatisfiedConstraint {
+    /// The path of the constraint.
+    pub name: String,
+    /// The index of the instance.
+    pub row: usize,
+}
+
+impl fmt::Display for UnsatisfiedConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} unsatisfied at row {}",
+            self.name, self.row
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct CircuitBuilder<'a, E: ExtensionField> {
     pub(crate) cs: &'a mut ConstraintSystem<E>,
     pub params: ProgramParams,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+    use goldilocks::{Goldilocks, GoldilocksExt2 as E};
+
+    #[test]
+    fn test_degree_reduction() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test_degree_reduction");
+        cs.enable_degree_reduction(2);
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(|name| cs.create_witin(|| name));
+        cs.require_zero(
+            || "a * b * c * d = 24",
+            a.expr() * b.expr() * c.expr() * d.expr() - 24,
+        )
+        .unwrap();
+        assert_eq!(cs.max_non_lc_degree, 2);
+        assert_eq!(cs.derived_witins.len(), 2);
+
+        let mut witness = RowMajorMatrix::<Goldilocks>::new(1, cs.num_witin as usize);
+        for (i, row) in witness.iter_mut().enumerate() {
+            let values = if i == 0 { [1, 2, 3, 4] } else { [0; 4] };
+            for (witin, v) in [a, b, c, d].iter().zip(values) {
+                set_val!(row, witin, Goldilocks::from(v));
+            }
+        }
+        cs.assign_derived_witins(&mut witness);
+
+        let row = &witness[0];
+        for expr in chain!(
+            &cs.assert_zero_expressions,
+            &cs.assert_zero_sumcheck_expressions
+        ) {
+            let value: Goldilocks = expr.evaluate(
+                &|_| unreachable!(),
+                &|id| unsafe { row[id as usize].assume_init() },
+                &|c| c,
+                &|_, _, _, _| unreachable!(),
+                &|a, b| a + b,
+                &|a, b| a * b,
+                &|x, a, b| a * x + b,
+            );
+            assert_eq!(value, Goldilocks::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_degree_reduction_not_derivable() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test_degree_reduction");
+        cs.enable_degree_reduction(2);
+        let a = cs.create_witin(|| "a");
+        let f = cs.create_fixed(|| "f").unwrap();
+        // f * f is derived first, but it is not a function of the witnesses
+        let result = cs.require_zero(
+            || "f * f * a = 0",
+            Expression::Fixed(f) * Expression::Fixed(f) * a.expr(),
+        );
+        assert!(matches!(
+            result,
+            Err(ZKVMError::NotDerivable { path }) if path == "test_degree_reduction/degree_reduction_0"
+        ));
+    }
+
+    #[test]
+    fn test_unsatisfied_constraints() {
+        let mut cs = ConstraintSystem::<E>::new(|| "check");
+        let [a, b] = ["a", "b"].map(|name| cs.create_witin(|| name));
+        cs.require_zero(|| "a = b + 1", a.expr() - b.expr() - 1)
+            .unwrap();
+        cs.require_zero(|| "a * b = 6", a.expr() * b.expr() - 6)
+            .unwrap();
+
+        let mut witness = RowMajorMatrix::<Goldilocks>::new(3, cs.num_witin as usize);
+        for (row, values) in witness.iter_mut().zip([[3, 2], [3, 3], [2, 1]]) {
+            for (witin, v) in [a, b].iter().zip(values) {
+                set_val!(row, witin, Goldilocks::from(v));
+            }
+        }
+        witness.padding_zero();
+
+        let unsatisfied = |name: &str, row| UnsatisfiedConstraint {
+            name: format!("check/{name}"),
+            row,
+        };
+        // the padding rows are not checked
+        assert_eq!(cs.unsatisfied_constraints(&witness), vec![
+            unsatisfied("a = b + 1", 1),
+            unsatisfied("a * b = 6", 1),
+            unsatisfied("a * b = 6", 2),
+        ]);
+        assert!(matches!(
+            cs.check_assignment("CHECK", &witness),
+            Err(ZKVMError::UnsatisfiedConstraint { circuit, constraint, row: 1 })
+                if circuit == "CHECK" && constraint == "check/a = b + 1"
+        ));
+    }
+
+    #[test]
+    fn test_copy_witin() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test_copy_witin");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let a = cb
+            .namespace(|| "load", |cb| Ok(cb.create_witin(|| "a")))
+            .unwrap();
+        let b = cb
+            .namespace(|| "bounds", |cb| cb.copy_witin(|| "a", a))
+            .unwrap();
+        cb.require_copy(|| "a = a", a, a).unwrap();
+        assert_eq!(cs.copy_constraints.len(), 1);
+        assert_eq!(cs.assert_zero_expressions.len(), 1);
+        assert_eq!(
+            cs.witin_namespace_map[b.id as usize],
+            "test_copy_witin/bounds/a"
+        );
+
+        let mut witness = RowMajorMatrix::<Goldilocks>::new(2, cs.num_witin as usize);
+        for (i, row) in witness.iter_mut().enumerate() {
+            set_val!(row, a, Goldilocks::from(i as u64 + 7));
+        }
+        cs.assign_derived_witins(&mut witness);
+        for (i, row) in witness.iter_mut().enumerate() {
+            assert_eq!(
+                unsafe { row[b.id as usize].assume_init() },
+                Goldilocks::from(i as u64 + 7)
+            );
+        }
+    }
+
+    #[test]
+    fn test_fixed_generators() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test_fixed_generators");
+        let x = cs.create_fixed_with(|| "x", |row| row as u64).unwrap();
+        let y = cs.create_fixed_with(|| "y", |row| 3 * row as u64).unwrap();
+
+        let fixed = cs.generate_fixed_traces(3).unwrap();
+        for (i, row) in fixed.iter_rows().take(3).enumerate() {
+            assert_eq!(
+                unsafe { row[x.0].assume_init() },
+                Goldilocks::from(i as u64)
+            );
+            assert_eq!(
+                unsafe { row[y.0].assume_init() },
+                Goldilocks::from(3 * i as u64)
+            );
+        }
+
+        // a column assigned out of band
+        cs.create_fixed(|| "z").unwrap();
+        assert!(matches!(
+            cs.generate_fixed_traces(3),
+            Err(ZKVMError::FixedTraceNotFound(path)) if path == "test_fixed_generators/z"
+        ));
+    }
+
+    #[test]
+    fn test_column_names() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test_column_names");
+        let a = cs.create_witin(|| "a");
+        let b = cs.namespace(|| "inner", |cs| cs.create_witin(|| "b"));
+        cs.create_fixed(|| "f").unwrap();
+
+        assert_eq!(cs.witin_name(b), "test_column_names/inner/b");
+        let names = cs.column_names();
+        assert_eq!(names.witin[a.id as usize], "test_column_names/a");
+        assert_eq!(names.fixed, vec!["test_column_names/f".to_string()]);
+        assert_eq!(
+            serde_json::to_string(&names).unwrap(),
+            r#"{"witin":["test_column_names/a","test_column_names/inner/b"],"fixed":["test_column_names/f"]}"#
+        );
+    }
+
+    #[test]
+    fn test_named_challenges() {
+        let mut cs = ConstraintSystem::<E>::new(|| "test_named_challenges");
+        let beta = cs.challenge("chip_record_beta").unwrap();
+        assert!(matches!(beta, Expression::Challenge(1, 1, _, _)));
+        assert_eq!(cs.challenge("chip_record_beta").unwrap(), beta);
+        assert_eq!(cs.challenges.len(), 1);
+        assert!(matches!(
+            cs.challenge("unknown"),
+            Err(ZKVMError::UnknownChallenge(_))
+        ));
+    }
+
+    #[test]
+    fn test_namespace_tree() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv/root");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let a = cb.create_witin(|| "a");
+        cb.namespace(
+            || "outer",
+            |cb| {
+                let b = cb.create_witin(|| "b");
+                cb.namespace(|| "empty", |_| Ok(()))?;
+                cb.namespace(
+                    || "inner",
+                    |cb| cb.require_equal(|| "a = b", a.expr(), b.expr()),
+                )
+            },
+        )
+        .unwrap();
+
+        let tree = cs.namespace_tree();
+        assert_eq!(tree.path, "riscv/root");
+        assert_eq!(tree.witins, vec![a.id]);
+        assert_eq!(tree.num_constraints(), 1);
+
+        let outer = tree.find("outer").unwrap();
+        assert_eq!(outer.path, "riscv/root/outer");
+        assert_eq!(outer.witins.len(), 1);
+        assert!(outer.constraints.is_empty());
+        assert!(tree.find("outer/empty").unwrap().children.is_empty());
+        assert_eq!(tree.find("outer/inner").unwrap().num_constraints(), 1);
+        assert!(tree.find("outer/missing").is_none());
+
+        let constrained = tree
+            .iter()
+            .find(|node| !node.constraints.is_empty())
+            .unwrap();
+        assert_eq!(constrained.constraints, vec!["a = b".to_string()]);
+    }
+}
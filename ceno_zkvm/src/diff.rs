@@ -0,0 +1,280 @@
+//! Structural comparison of constraint systems, see [`ConstraintSystem::diff`].
+
+use crate::{
+    circuit_builder::ConstraintSystem,
+    expression::{Expression, fmt},
+};
+use ff_ext::ExtensionField;
+use itertools::{Itertools, chain, izip};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter, Result},
+};
+
+/// Differences from one constraint system to another. Paths are relative to the root namespace.
+/// Expressions are compared in monomial form, with witnesses and fixed columns named by path,
+/// so the order in which they were allocated does not matter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstraintSystemDiff {
+    pub added_witins: Vec<String>,
+    pub removed_witins: Vec<String>,
+    /// (path, constraint)
+    pub added_constraints: Vec<(String, String)>,
+    pub removed_constraints: Vec<(String, String)>,
+    /// (path, old constraints, new constraints)
+    pub changed_constraints: Vec<(String, String, String)>,
+}
+
+impl ConstraintSystemDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for ConstraintSystemDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for path in &self.added_witins {
+            writeln!(f, "+ witin {path}")?;
+        }
+        for path in &self.removed_witins {
+            writeln!(f, "- witin {path}")?;
+        }
+        for (path, constraint) in &self.added_constraints {
+            writeln!(f, "+ {path}: {constraint}")?;
+        }
+        for (path, constraint) in &self.removed_constraints {
+            writeln!(f, "- {path}: {constraint}")?;
+        }
+        for (path, old, new) in &self.changed_constraints {
+            writeln!(f, "~ {path}: {old} -> {new}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the items of a constraint system independently of witness and fixed column ids.
+struct Renderer<'a, E: ExtensionField> {
+    cs: &'a ConstraintSystem<E>,
+    root: &'a str,
+}
+
+impl<E: ExtensionField> Renderer<'_, E> {
+    fn relative<'p>(&self, path: &'p str) -> &'p str {
+        path.strip_prefix(self.root)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .unwrap_or(path)
+    }
+
+    fn var(&self, var: &Expression<E>) -> String {
+        match var {
+            Expression::WitIn(id) => self
+                .relative(&self.cs.witin_namespace_map[*id as usize])
+                .to_string(),
+            Expression::Fixed(fixed) => self
+                .relative(&self.cs.fixed_namespace_map[fixed.0])
+                .to_string(),
+            Expression::Instance(instance) => self
+                .cs
+                .instance_name_map
+                .get(instance)
+                .cloned()
+                .unwrap_or_else(|| format!("{instance:?}")),
+            _ => fmt::expr(var, &mut vec![], true),
+        }
+    }
+
+    fn expr(&self, expr: &Expression<E>) -> String {
+        let terms = expr
+            .monomials()
+            .into_iter()
+            .map(|(coeff, vars)| {
+                let coeff =
+                    (coeff != Expression::ONE).then(|| fmt::expr(&coeff, &mut vec![], true));
+                let vars = vars.iter().map(|var| self.var(var)).sorted();
+                let term = chain!(coeff, vars).join("*");
+                if term.is_empty() {
+                    "1".to_string()
+                } else {
+                    term
+                }
+            })
+            .sorted()
+            .join(" + ");
+        if terms.is_empty() {
+            "0".to_string()
+        } else {
+            terms
+        }
+    }
+
+    fn record(&self, record: &[Expression<E>]) -> String {
+        format!("[{}]", record.iter().map(|e| self.expr(e)).join(", "))
+    }
+
+    /// Rendered constraints, lookups, reads and writes, by relative path.
+    fn items(&self) -> BTreeMap<String, Vec<String>> {
+        let cs = self.cs;
+        let mut items: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut push = |path: &str, item: String| {
+            items
+                .entry(self.relative(path).to_string())
+                .or_default()
+                .push(item)
+        };
+
+        for (expr, path) in chain!(
+            izip!(
+                &cs.assert_zero_expressions,
+                &cs.assert_zero_expressions_namespace_map
+            ),
+            izip!(
+                &cs.assert_zero_sumcheck_expressions,
+                &cs.assert_zero_sumcheck_expressions_namespace_map
+            ),
+        ) {
+            push(path, format!("{} = 0", self.expr(expr)));
+        }
+        for ((rom_type, record), path) in izip!(
+            &cs.lk_expressions_items_map,
+            &cs.lk_expressions_namespace_map
+        ) {
+            push(path, format!("lookup {rom_type:?} {}", self.record(record)));
+        }
+        for (table, path) in izip!(
+            &cs.lk_table_expressions,
+            &cs.lk_table_expressions_namespace_map
+        ) {
+            push(
+                path,
+                format!(
+                    "table {:?} {} with multiplicity {}",
+                    table.rom_types,
+                    self.expr(&table.values),
+                    self.expr(&table.multiplicity)
+                ),
+            );
+        }
+        for ((ram_type, record), path) in izip!(&cs.r_ram_types, &cs.r_expressions_namespace_map) {
+            push(path, format!("read {ram_type:?} {}", self.record(record)));
+        }
+        for ((ram_type, record), path) in izip!(&cs.w_ram_types, &cs.w_expressions_namespace_map) {
+            push(path, format!("write {ram_type:?} {}", self.record(record)));
+        }
+
+        for rendered in items.values_mut() {
+            rendered.sort();
+        }
+        items
+    }
+
+    fn witins(&self) -> BTreeSet<String> {
+        self.cs
+            .witin_namespace_map
+            .iter()
+            .map(|path| self.relative(path).to_string())
+            .collect()
+    }
+}
+
+impl<E: ExtensionField> ConstraintSystem<E> {
+    fn renderer(&self) -> Renderer<'_, E> {
+        Renderer {
+            cs: self,
+            root: &self.ns.get_namespaces()[0],
+        }
+    }
+
+    /// What changed from `self` to `other`: witnesses and constraints added, removed, or
+    /// changed, by namespace path. An empty diff means the circuits are the same up to
+    /// allocation order, so their keys need not be regenerated.
+    pub fn diff(&self, other: &ConstraintSystem<E>) -> ConstraintSystemDiff {
+        let (old, new) = (self.renderer(), other.renderer());
+        let (old_witins, new_witins) = (old.witins(), new.witins());
+        let (old_items, new_items) = (old.items(), new.items());
+
+        let mut diff = ConstraintSystemDiff {
+            added_witins: new_witins.difference(&old_witins).cloned().collect(),
+            removed_witins: old_witins.difference(&new_witins).cloned().collect(),
+            ..Default::default()
+        };
+        for path in chain!(old_items.keys(), new_items.keys()).collect::<BTreeSet<_>>() {
+            match (old_items.get(path), new_items.get(path)) {
+                (Some(old), None) => diff
+                    .removed_constraints
+                    .extend(old.iter().map(|item| (path.clone(), item.clone()))),
+                (None, Some(new)) => diff
+                    .added_constraints
+                    .extend(new.iter().map(|item| (path.clone(), item.clone()))),
+                (Some(old), Some(new)) if old != new => {
+                    diff.changed_constraints
+                        .push((path.clone(), old.join("; "), new.join("; ")))
+                }
+                _ => (),
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::ToExpr,
+    };
+    use goldilocks::GoldilocksExt2 as E;
+
+    fn circuit(swap: bool, sum: bool, extra: bool) -> ConstraintSystem<E> {
+        let mut cs = ConstraintSystem::<E>::new(|| format!("circuit_{swap}_{sum}_{extra}"));
+        let mut cb = CircuitBuilder::new(&mut cs);
+        cb.namespace(
+            || "inner",
+            |cb| {
+                let (a, b) = if swap {
+                    let b = cb.create_witin(|| "b");
+                    (cb.create_witin(|| "a"), b)
+                } else {
+                    (cb.create_witin(|| "a"), cb.create_witin(|| "b"))
+                };
+                let expr = if sum {
+                    a.expr() + b.expr()
+                } else if swap {
+                    a.expr() * 2 - (b.expr() + a.expr())
+                } else {
+                    a.expr() - b.expr()
+                };
+                cb.cs.require_zero(|| "eq", expr)?;
+                if extra {
+                    let c = cb.create_witin(|| "c");
+                    cb.assert_ux::<_, _, 16>(|| "c_u16", c.expr())?;
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+        cs
+    }
+
+    #[test]
+    fn test_diff() {
+        let base = circuit(false, false, false);
+        assert!(base.diff(&circuit(true, false, false)).is_empty());
+
+        let diff = base.diff(&circuit(false, true, true));
+        assert_eq!(diff.added_witins, vec!["inner/c".to_string()]);
+        assert!(diff.removed_witins.is_empty());
+        assert_eq!(diff.added_constraints, vec![(
+            "inner/c_u16".to_string(),
+            "lookup U16 [inner/c]".to_string()
+        )]);
+        assert_eq!(diff.changed_constraints, vec![(
+            "inner/eq".to_string(),
+            "(-1)*inner/b + inner/a = 0".to_string(),
+            "inner/a + inner/b = 0".to_string()
+        )]);
+
+        let diff = circuit(false, true, true).diff(&base);
+        assert_eq!(diff.removed_witins, vec!["inner/c".to_string()]);
+        assert_eq!(diff.removed_constraints.len(), 1);
+    }
+}
@@ -1,26 +1,47 @@
 use crate::{
-    instructions::riscv::{DummyExtraConfig, MemPadder, MmuConfig, Rv32imConfig},
+    error::{VerifyError, ZKVMError},
+    instructions::riscv::{
+        DummyExtraConfig, MemPadder, MmuConfig, Rv32imConfig,
+        constants::{MEM_STATE_IDX, NUM_PUBLIC_VALUES, PUBLIC_IO_IDX, REG_STATE_IDX, UINT_LIMBS},
+        find_unsupported_instructions,
+    },
     scheme::{
-        PublicValues, ZKVMProof, constants::MAX_NUM_VARIABLES, mock_prover::MockProver,
+        PublicValues, SegmentState, ZKVMProof, mock_prover::MockProver, pcs::setup_pcs,
         prover::ZKVMProver, verifier::ZKVMVerifier,
     },
     state::GlobalState,
-    structs::{ProgramParams, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
-    tables::{MemFinalRecord, MemInitRecord, ProgramTableCircuit},
+    structs::{
+        ProgramParams, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMProvingKey, ZKVMWitnesses,
+    },
+    tables::{
+        FixedTraceCache, MemFinalRecord, MemInitRecord, ProgramTableCircuit, RegTableCircuit,
+        StaticMemCircuit, TableCircuit,
+    },
+    witness::WitnessPool,
 };
 use ceno_emul::{
-    ByteAddr, EmuContext, InsnKind::EANY, IterAddresses, Platform, Program, StepRecord, Tracer,
+    ByteAddr, Cycle, EmuContext, GuestPanic, InsnKind::EANY, IterAddresses, MemoryLayout, Platform,
+    Profile, Program, Segment, SegmentBoundary, SegmentReport, Snapshot, StepRecord, Tracer,
     VMState, WORD_SIZE, WordAddr,
 };
 use ff_ext::ExtensionField;
 use itertools::{Itertools, MinMaxResult, chain, enumerate};
 use mpcs::PolynomialCommitmentScheme;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
     iter::zip,
+    path::Path,
+    sync::mpsc,
+    thread,
     time::Instant,
 };
-use transcript::BasicTranscript as Transcript;
+use transcript::TranscriptHash;
+
+/// Number of steps emulated at once, whose opcode circuits are assigned while the next ones are
+/// emulated.
+const PIPELINE_BATCH_SIZE: usize = 1 << 16;
 
 type E2EWitnessGen<E, PCS> = (
     ZKVMProver<E, PCS>,
@@ -30,92 +51,168 @@ type E2EWitnessGen<E, PCS> = (
     usize,   // number of cycles
     Instant, // e2e start, excluding key gen time
     Option<u32>,
+    Option<GuestPanic>,
 );
 
-pub fn run_e2e_gen_witness<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
-    program: Program,
-    platform: Platform,
-    stack_size: u32,
-    heap_size: u32,
-    hints: Vec<u32>,
-    max_steps: usize,
-) -> E2EWitnessGen<E, PCS> {
-    let stack_addrs = platform.stack_top - stack_size..platform.stack_top;
-
-    // Detect heap as starting after program data.
-    let heap_start = program.image.keys().max().unwrap() + WORD_SIZE as u32;
-    let heap_addrs = heap_start..heap_start + heap_size;
-
-    let mut mem_padder = MemPadder::new(heap_addrs.end..platform.ram.end);
-
-    let mem_init = {
-        let program_addrs = program.image.iter().map(|(addr, value)| MemInitRecord {
-            addr: *addr,
-            value: *value,
-        });
-
-        let stack = stack_addrs
-            .iter_addresses()
-            .map(|addr| MemInitRecord { addr, value: 0 });
-
-        let heap = heap_addrs
-            .iter_addresses()
-            .map(|addr| MemInitRecord { addr, value: 0 });
-
-        let mem_init = chain!(program_addrs, stack, heap).collect_vec();
-
-        mem_padder.padded_sorted(mem_init.len().next_power_of_two(), mem_init)
-    };
-
-    let mut vm = VMState::new(platform.clone(), program);
-
-    for (addr, value) in zip(platform.hints.iter_addresses(), &hints) {
-        vm.init_memory(addr.into(), *value);
-    }
+/// The circuits of a program and their keys, which depend on the program and its memory layout,
+/// but not on its execution.
+struct E2ECircuits<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    zkvm_cs: ZKVMConstraintSystem<E>,
+    config: Rv32imConfig<E>,
+    mmu_config: MmuConfig<E>,
+    dummy_config: DummyExtraConfig<E>,
+    prog_config: <ProgramTableCircuit<E> as TableCircuit<E>>::TableConfig,
+    fixed_traces: ZKVMFixedTraces<E>,
+    reg_init: Vec<MemInitRecord>,
+    io_init: Vec<MemInitRecord>,
+    pk: ZKVMProvingKey<E, PCS>,
+}
 
-    // keygen
-    let pcs_param = PCS::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
-    let (pp, vp) = PCS::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
+fn setup_circuits<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+    program: &Program,
+    platform: &Platform,
+    mem_init: &[MemInitRecord],
+    mem_padder: &mut MemPadder,
+    key_cache: Option<&Path>,
+    transcript_hash: TranscriptHash,
+    segmented: bool,
+) -> Result<E2ECircuits<E, PCS>, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    let (pp, vp) = setup_pcs::<E, PCS>()?;
     let program_params = ProgramParams {
         platform: platform.clone(),
-        program_size: vm.program().instructions.len(),
+        program_size: program.instructions.len(),
         static_memory_len: mem_init.len(),
+        segmented,
         ..ProgramParams::default()
     };
     let mut zkvm_cs = ZKVMConstraintSystem::new_with_platform(program_params);
 
-    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs);
-    let mmu_config = MmuConfig::<E>::construct_circuits(&mut zkvm_cs);
-    let dummy_config = DummyExtraConfig::<E>::construct_circuits(&mut zkvm_cs);
-    let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E>>();
-    zkvm_cs.register_global_state::<GlobalState>();
+    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs)?;
+    let mmu_config = MmuConfig::<E>::construct_circuits(&mut zkvm_cs)?;
+    let dummy_config = DummyExtraConfig::<E>::construct_circuits(&mut zkvm_cs)?;
+    let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E>>()?;
+    zkvm_cs.register_global_state::<GlobalState>()?;
 
-    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+    // the static tables are cached along with the proving keys
+    let mut fixed_traces =
+        ZKVMFixedTraces::with_cache(FixedTraceCache::new(key_cache.map(Path::to_path_buf)));
 
-    zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(
+    fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(
         &zkvm_cs,
         &prog_config,
-        vm.program(),
-    );
+        program,
+    )?;
 
     // IO is not used in this program, but it must have a particular size at the moment.
     let io_init = mem_padder.padded_sorted(mmu_config.public_io_len(), vec![]);
 
     let reg_init = mmu_config.initial_registers();
-    config.generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces);
+    config.generate_fixed_traces(&zkvm_cs, &mut fixed_traces)?;
     mmu_config.generate_fixed_traces(
         &zkvm_cs,
-        &mut zkvm_fixed_traces,
+        &mut fixed_traces,
         &reg_init,
-        &mem_init,
+        mem_init,
         &io_init.iter().map(|rec| rec.addr).collect_vec(),
-    );
-    dummy_config.generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces);
+    )?;
+    dummy_config.generate_fixed_traces(&zkvm_cs, &mut fixed_traces)?;
+
+    let mut pk =
+        ZKVMProvingKey::<E, PCS>::setup(zkvm_cs.clone(), pp, vp, fixed_traces.clone(), key_cache)?;
+    pk.transcript_hash = transcript_hash;
+
+    Ok(E2ECircuits {
+        zkvm_cs,
+        config,
+        mmu_config,
+        dummy_config,
+        prog_config,
+        fixed_traces,
+        reg_init,
+        io_init,
+        pk,
+    })
+}
+
+/// Generate the proving key of `program`, the same as [`run_e2e_gen_witness`] does, without
+/// running it.
+pub fn run_e2e_keygen<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+    program: &Program,
+    platform: &Platform,
+    stack_size: u32,
+    heap_size: u32,
+    key_cache: Option<&Path>,
+    transcript_hash: TranscriptHash,
+) -> Result<ZKVMProvingKey<E, PCS>, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    let (mem_init, mut mem_padder) = init_mem(program, platform, stack_size, heap_size);
+    Ok(setup_circuits::<E, PCS>(
+        program,
+        platform,
+        &mem_init,
+        &mut mem_padder,
+        key_cache,
+        transcript_hash,
+        false,
+    )?
+    .pk)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_e2e_gen_witness<
+    E: ExtensionField + DeserializeOwned,
+    PCS: PolynomialCommitmentScheme<E>,
+>(
+    program: Program,
+    platform: Platform,
+    stack_size: u32,
+    heap_size: u32,
+    hints: Vec<u32>,
+    max_steps: usize,
+    key_cache: Option<&Path>,
+    transcript_hash: TranscriptHash,
+) -> Result<E2EWitnessGen<E, PCS>, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    report_unsupported_instructions(&program);
+    let (mem_init, mut mem_padder) = init_mem(&program, &platform, stack_size, heap_size);
+
+    let mut vm = VMState::new(platform.clone(), program);
+    vm.set_memory_layout(MemoryLayout::from_addresses(
+        mem_init.iter().map(|rec| rec.addr),
+    ));
+
+    for (addr, value) in zip(platform.hints.iter_addresses(), &hints) {
+        vm.init_memory(addr.into(), *value);
+    }
+    vm.write_hints(&hints_bytes(&hints));
 
-    let pk = zkvm_cs
-        .clone()
-        .key_gen::<PCS>(pp.clone(), vp.clone(), zkvm_fixed_traces.clone())
-        .expect("keygen failed");
+    // keygen
+    let E2ECircuits {
+        zkvm_cs,
+        config,
+        mmu_config,
+        dummy_config,
+        prog_config,
+        fixed_traces: zkvm_fixed_traces,
+        reg_init,
+        io_init,
+        pk,
+    } = setup_circuits::<E, PCS>(
+        vm.program(),
+        &platform,
+        &mem_init,
+        &mut mem_padder,
+        key_cache,
+        transcript_hash,
+        false,
+    )?;
     let vk = pk.get_vk();
 
     // proving
@@ -123,29 +220,54 @@ pub fn run_e2e_gen_witness<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>
     let prover = ZKVMProver::new(pk);
     let verifier = ZKVMVerifier::new(vk);
 
-    let all_records = vm
-        .iter_until_halt()
-        .take(max_steps)
-        .collect::<Result<Vec<StepRecord>, _>>()
-        .expect("vm exec failed");
-
-    let cycle_num = all_records.len();
+    // The emulator runs on its own thread, and the opcode circuits of each batch of steps are
+    // assigned while the next batch is emulated. A trap of the emulator ends the batches with
+    // its error.
+    let mut zkvm_witness = ZKVMWitnesses::default();
+    let mut histogram = BTreeMap::<_, u64>::new();
+    let mut cycle_num = 0;
+    let mut exit_code = None;
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let vm = &mut vm;
+        let emulator = scope.spawn(move || {
+            for batch in vm.iter_batches(max_steps, PIPELINE_BATCH_SIZE) {
+                let batch = batch.map_err(vm_exec_error);
+                if sender.send(batch).is_err() {
+                    break;
+                }
+            }
+        });
+        // the receiver is dropped on an error, which stops the emulator at its next batch
+        let assigned = receiver.into_iter().try_for_each(|batch| {
+            let batch = batch?;
+            for (i, step) in enumerate(&batch).rev().take(5).rev() {
+                tracing::trace!(
+                    "Step {}: {:?} - {:?}\n",
+                    cycle_num + i,
+                    step.insn().codes().kind,
+                    step
+                );
+            }
+            cycle_num += batch.len();
+            exit_code = find_exit_code(&batch).or(exit_code);
+            for (kind, count) in Profile::kind_histogram(&batch) {
+                *histogram.entry(kind).or_default() += count;
+            }
+            let dummy_records = config.assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, batch)?;
+            dummy_config.assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, dummy_records)?;
+            Ok::<_, ZKVMError>(())
+        });
+        join_emulator(emulator)?;
+        assigned
+    })?;
+    Rv32imConfig::check_instance_counts(&zkvm_witness, &histogram)?;
     tracing::info!("Proving {} execution steps", cycle_num);
-    for (i, step) in enumerate(&all_records).rev().take(5).rev() {
-        tracing::trace!("Step {i}: {:?} - {:?}\n", step.insn().codes().kind, step);
+    let guest_panic = vm.guest_panic().cloned();
+    if let Some(panic) = &guest_panic {
+        tracing::error!("{panic}");
     }
 
-    // Find the exit code from the HALT step, if halting at all.
-    let exit_code = all_records
-        .iter()
-        .rev()
-        .find(|record| {
-            record.insn().codes().kind == EANY
-                && record.rs1().unwrap().value == Platform::ecall_halt()
-        })
-        .and_then(|halt_record| halt_record.rs2())
-        .map(|rs2| rs2.value);
-
     let final_access = vm.tracer().final_accesses();
     let end_cycle: u32 = vm.tracer().cycle().try_into().unwrap();
 
@@ -156,93 +278,37 @@ pub fn run_e2e_gen_witness<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>
         vm.get_pc().into(),
         end_cycle,
         io_init.iter().map(|rec| rec.value).collect_vec(),
-    );
-
-    let mut zkvm_witness = ZKVMWitnesses::default();
-    // assign opcode circuits
-    let dummy_records = config
-        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, all_records)
-        .unwrap();
-    dummy_config
-        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, dummy_records)
-        .unwrap();
-    zkvm_witness.finalize_lk_multiplicities();
-
-    // Find the final register values and cycles.
-    let reg_final = reg_init
-        .iter()
-        .map(|rec| {
-            let index = rec.addr as usize;
-            if index < VMState::REG_COUNT {
-                let vma: WordAddr = Platform::register_vma(index).into();
-                MemFinalRecord {
-                    addr: rec.addr,
-                    value: vm.peek_register(index),
-                    cycle: *final_access.get(&vma).unwrap_or(&0),
-                }
-            } else {
-                // The table is padded beyond the number of registers.
-                MemFinalRecord {
-                    addr: rec.addr,
-                    value: 0,
-                    cycle: 0,
-                }
-            }
-        })
-        .collect_vec();
-
-    // Find the final memory values and cycles.
-    let mem_final = mem_init
-        .iter()
-        .map(|rec| {
-            let vma: WordAddr = rec.addr.into();
-            MemFinalRecord {
-                addr: rec.addr,
-                value: vm.peek_memory(vma),
-                cycle: *final_access.get(&vma).unwrap_or(&0),
-            }
-        })
-        .collect_vec();
-    debug_memory_ranges(&vm, &mem_final);
+    )
+    .with_output(vm.committed_output());
 
-    // Find the final public IO cycles.
-    let io_final = io_init
-        .iter()
-        .map(|rec| *final_access.get(&rec.addr.into()).unwrap_or(&0))
-        .collect_vec();
+    zkvm_witness.finalize_lk_multiplicities()?;
 
-    let priv_io_final = zip(platform.hints.iter_addresses(), &hints)
-        .map(|(addr, &value)| MemFinalRecord {
-            addr,
-            value,
-            cycle: *final_access.get(&addr.into()).unwrap_or(&0),
-        })
-        .collect_vec();
+    let final_records =
+        FinalRecords::new(&vm, final_access, &reg_init, &mem_init, &io_init, &hints);
+    debug_memory_ranges(&vm, &final_records.mem);
 
     // assign table circuits
-    config
-        .assign_table_circuit(&zkvm_cs, &mut zkvm_witness)
-        .unwrap();
-    mmu_config
-        .assign_table_circuit(
-            &zkvm_cs,
-            &mut zkvm_witness,
-            &reg_final,
-            &mem_final,
-            &io_final,
-            &priv_io_final,
-        )
-        .unwrap();
+    config.assign_table_circuit(&zkvm_cs, &mut zkvm_witness)?;
+    mmu_config.assign_table_circuit(
+        &zkvm_cs,
+        &mut zkvm_witness,
+        &final_records.reg,
+        &final_records.mem,
+        &final_records.io,
+        &final_records.hints,
+    )?;
     // assign program circuit
-    zkvm_witness
-        .assign_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, vm.program())
-        .unwrap();
+    zkvm_witness.assign_table_circuit::<ProgramTableCircuit<E>>(
+        &zkvm_cs,
+        &prog_config,
+        vm.program(),
+    )?;
 
     if std::env::var("MOCK_PROVING").is_ok() {
         MockProver::assert_satisfied_full(zkvm_cs, zkvm_fixed_traces, &zkvm_witness, &pi);
         tracing::info!("Mock proving passed");
     }
-    (
+    Ok((
         prover,
         verifier,
         zkvm_witness,
@@ -250,18 +316,17 @@ pub fn run_e2e_gen_witness<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>
         cycle_num,
         e2e_start,
         exit_code,
-    )
+        guest_panic,
+    ))
 }
 
 pub fn run_e2e_proof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
     prover: ZKVMProver<E, PCS>,
     zkvm_witness: ZKVMWitnesses<E>,
     pi: PublicValues<u32>,
-) -> ZKVMProof<E, PCS> {
-    let transcript = Transcript::new(b"riscv");
-    prover
-        .create_proof(zkvm_witness, pi, transcript)
-        .expect("create_proof failed")
+) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
+    let transcript = prover.pk.new_transcript(b"riscv");
+    prover.create_proof(zkvm_witness, pi, transcript)
 }
 
 pub fn run_e2e_verify<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
@@ -270,7 +335,7 @@ pub fn run_e2e_verify<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
     exit_code: Option<u32>,
     max_steps: usize,
 ) {
-    let transcript = Transcript::new(b"riscv");
+    let transcript = verifier.new_transcript(b"riscv");
     assert!(
         verifier
             .verify_proof_halt(zkvm_proof, transcript, exit_code.is_some())
@@ -283,6 +348,704 @@ pub fn run_e2e_verify<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
     }
 }
 
+/// The proof of one segment of a chunked execution, see [`run_e2e_segments`].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub struct SegmentProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub start: SegmentBoundary,
+    pub end: SegmentBoundary,
+    pub exit_code: Option<u32>,
+    pub report: SegmentReport,
+    pub proof: ZKVMProof<E, PCS>,
+}
+
+/// Split the execution into segments of at most `segment_steps` steps, and prove each of them on
+/// its own. A segment starts from the registers and memory left by the previous one, and exports
+/// its boundaries as the init and end pc and cycle of its public values, and the registers and
+/// static memory at both ends as its segment state, see `ProgramParams::segmented`.
+#[allow(clippy::too_many_arguments)]
+pub fn run_e2e_segments<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+    program: Program,
+    platform: Platform,
+    stack_size: u32,
+    heap_size: u32,
+    hints: Vec<u32>,
+    max_steps: usize,
+    segment_steps: usize,
+    transcript_hash: TranscriptHash,
+) -> Result<Vec<SegmentProof<E, PCS>>, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    let prover = SegmentProver::<E, PCS>::new(
+        &program,
+        platform.clone(),
+        stack_size,
+        heap_size,
+        transcript_hash,
+    )?;
+    let mut vm = init_segments_vm(program.clone(), platform, &prover.mem_init, &hints);
+    let pool = WitnessPool::default();
+
+    // The emulator runs on its own thread, one segment ahead: a segment is assigned, committed
+    // and proven while the next one is emulated. A trap of the emulator ends the segments with
+    // its error.
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let (reg_init, mem_init, io_init) = (&prover.reg_init, &prover.mem_init, &prover.io_init);
+        let hints = &hints;
+        let emulator = scope.spawn(move || {
+            let mut num_steps = 0;
+            while num_steps < max_steps {
+                let segment = match next_segment(&mut vm, segment_steps.min(max_steps - num_steps))
+                {
+                    Ok(Some(segment)) => segment,
+                    Ok(None) => break,
+                    Err(err) => {
+                        let _ = sender.send(Err(err));
+                        break;
+                    }
+                };
+                num_steps += segment.steps.len();
+                let emulated =
+                    EmulatedSegment::new(&vm, segment, reg_init, mem_init, io_init, hints);
+                if sender.send(Ok(emulated)).is_err() {
+                    break;
+                }
+            }
+        });
+        // the receiver is dropped on an error, which stops the emulator after its segment
+        let mut segments = vec![];
+        let proven = receiver.into_iter().try_for_each(|emulated| {
+            segments.push(prover.prove(segments.len(), emulated?, &program, &pool)?);
+            Ok::<_, ZKVMError>(())
+        });
+        join_emulator(emulator)?;
+        proven.map(|()| segments)
+    })
+}
+
+/// The segments which [`run_e2e_segments`] splits the execution into, emulated one after the
+/// other without proving. Each is the machine state at its start and its number of steps, from
+/// which [`SegmentProver::prove_from`] proves it on its own, e.g. in another process.
+pub struct SegmentSnapshots {
+    vm: VMState,
+    max_steps: usize,
+    segment_steps: usize,
+    num_steps: usize,
+}
+
+impl SegmentSnapshots {
+    pub fn new(
+        program: Program,
+        platform: Platform,
+        stack_size: u32,
+        heap_size: u32,
+        hints: &[u32],
+        max_steps: usize,
+        segment_steps: usize,
+    ) -> Self {
+        let (mem_init, _) = init_mem(&program, &platform, stack_size, heap_size);
+        SegmentSnapshots {
+            vm: init_segments_vm(program, platform, &mem_init, hints),
+            max_steps,
+            segment_steps,
+            num_steps: 0,
+        }
+    }
+}
+
+impl Iterator for SegmentSnapshots {
+    type Item = Result<(Snapshot, usize), ZKVMError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.num_steps >= self.max_steps {
+            return None;
+        }
+        let start = self.vm.snapshot();
+        let max_steps = self.segment_steps.min(self.max_steps - self.num_steps);
+        match next_segment(&mut self.vm, max_steps) {
+            Ok(Some(segment)) => {
+                self.num_steps += segment.steps.len();
+                Some(Ok((start, segment.steps.len())))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                self.num_steps = self.max_steps;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// The machine at the start of a chunked execution.
+fn init_segments_vm(
+    program: Program,
+    platform: Platform,
+    mem_init: &[MemInitRecord],
+    hints: &[u32],
+) -> VMState {
+    let mut vm = VMState::new(platform.clone(), program);
+    vm.set_memory_layout(MemoryLayout::from_addresses(
+        mem_init.iter().map(|rec| rec.addr),
+    ));
+
+    for (addr, value) in zip(platform.hints.iter_addresses(), hints) {
+        vm.init_memory(addr.into(), *value);
+    }
+    vm.write_hints(&hints_bytes(hints));
+    vm
+}
+
+/// The circuits and the proving key of the segments of a program, which depend on the program
+/// and its memory layout only, so that they prove any number of segments of its executions.
+pub struct SegmentProver<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    platform: Platform,
+    mem_init: Vec<MemInitRecord>,
+    zkvm_cs: ZKVMConstraintSystem<E>,
+    config: Rv32imConfig<E>,
+    mmu_config: MmuConfig<E>,
+    dummy_config: DummyExtraConfig<E>,
+    prog_config: <ProgramTableCircuit<E> as TableCircuit<E>>::TableConfig,
+    fixed_traces: ZKVMFixedTraces<E>,
+    reg_init: Vec<MemInitRecord>,
+    io_init: Vec<MemInitRecord>,
+    prover: ZKVMProver<E, PCS>,
+}
+
+impl<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>> SegmentProver<E, PCS>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    /// Generate the circuits and the key of the segments of `program`. The initial registers and
+    /// memory are public values, so the key is the one of the program.
+    pub fn new(
+        program: &Program,
+        platform: Platform,
+        stack_size: u32,
+        heap_size: u32,
+        transcript_hash: TranscriptHash,
+    ) -> Result<Self, ZKVMError> {
+        report_unsupported_instructions(program);
+        let (mem_init, mut mem_padder) = init_mem(program, &platform, stack_size, heap_size);
+        let E2ECircuits {
+            zkvm_cs,
+            config,
+            mmu_config,
+            dummy_config,
+            prog_config,
+            fixed_traces,
+            reg_init,
+            io_init,
+            pk,
+        } = setup_circuits::<E, PCS>(
+            program,
+            &platform,
+            &mem_init,
+            &mut mem_padder,
+            None,
+            transcript_hash,
+            true,
+        )?;
+        Ok(SegmentProver {
+            platform,
+            mem_init,
+            zkvm_cs,
+            config,
+            mmu_config,
+            dummy_config,
+            prog_config,
+            fixed_traces,
+            reg_init,
+            io_init,
+            prover: ZKVMProver::new(pk),
+        })
+    }
+
+    /// Prove the segment of index `index` of the execution of `program` with `hints`, which runs
+    /// `steps` steps from `start`, see [`SegmentSnapshots`]. The witness matrices are taken from
+    /// `pool`, and return to it once proven.
+    pub fn prove_from(
+        &self,
+        program: Program,
+        index: usize,
+        start: Snapshot,
+        steps: usize,
+        hints: &[u32],
+        pool: &WitnessPool<E::BaseField>,
+    ) -> Result<SegmentProof<E, PCS>, ZKVMError> {
+        let mut vm = init_segments_vm(program, self.platform.clone(), &self.mem_init, hints);
+        vm.restore(start);
+        let Some(segment) = next_segment(&mut vm, steps)? else {
+            return Err(ZKVMError::GuestError(format!(
+                "the execution ends before segment {index}"
+            )));
+        };
+        let emulated = EmulatedSegment::new(
+            &vm,
+            segment,
+            &self.reg_init,
+            &self.mem_init,
+            &self.io_init,
+            hints,
+        );
+        self.prove(index, emulated, vm.program(), pool)
+    }
+
+    /// Prove the segment of index `index` of the execution of `program`.
+    fn prove(
+        &self,
+        index: usize,
+        emulated: EmulatedSegment,
+        program: &Program,
+        pool: &WitnessPool<E::BaseField>,
+    ) -> Result<SegmentProof<E, PCS>, ZKVMError> {
+        let EmulatedSegment {
+            segment,
+            final_records,
+            output,
+        } = emulated;
+        tracing::info!(
+            "Proving segment {} with {} execution steps, touching {} memory pages",
+            index,
+            segment.steps.len(),
+            segment.report.touched_pages.len()
+        );
+
+        // The state left by the previous segment is the initial state of this one.
+        let segment_reg_init = self
+            .reg_init
+            .iter()
+            .map(|rec| MemInitRecord {
+                addr: rec.addr,
+                value: segment
+                    .init_registers
+                    .get(rec.addr as usize)
+                    .copied()
+                    .unwrap_or(0),
+            })
+            .collect_vec();
+        let segment_mem_init = self
+            .mem_init
+            .iter()
+            .map(|rec| MemInitRecord {
+                addr: rec.addr,
+                value: *segment.init_memory.get(&rec.addr.into()).unwrap_or(&0),
+            })
+            .collect_vec();
+
+        let exit_code = find_exit_code(&segment.steps);
+        let mut zkvm_witness = ZKVMWitnesses::with_pool(pool.clone());
+        let histogram = Profile::kind_histogram(&segment.steps);
+        let dummy_records =
+            self.config
+                .assign_opcode_circuit(&self.zkvm_cs, &mut zkvm_witness, segment.steps)?;
+        self.dummy_config
+            .assign_opcode_circuit(&self.zkvm_cs, &mut zkvm_witness, dummy_records)?;
+        Rv32imConfig::check_instance_counts(&zkvm_witness, &histogram)?;
+        zkvm_witness.finalize_lk_multiplicities()?;
+
+        let pi = PublicValues::new(
+            exit_code.unwrap_or(0),
+            segment.start.pc,
+            cycle_value(segment.start.cycle)?,
+            segment.end.pc,
+            cycle_value(segment.end.cycle)?,
+            self.io_init.iter().map(|rec| rec.value).collect_vec(),
+        )
+        .with_segment_state(
+            SegmentState {
+                registers: segment_reg_init.iter().map(|rec| rec.value).collect(),
+                memory: segment_mem_init.iter().map(|rec| rec.value).collect(),
+            },
+            SegmentState {
+                registers: final_records.reg.iter().map(|rec| rec.value).collect(),
+                memory: final_records.mem.iter().map(|rec| rec.value).collect(),
+            },
+        )
+        // the output committed so far, so that the last segment carries the whole output
+        .with_output(&output);
+
+        self.config
+            .assign_table_circuit(&self.zkvm_cs, &mut zkvm_witness)?;
+        self.mmu_config.assign_table_circuit(
+            &self.zkvm_cs,
+            &mut zkvm_witness,
+            &final_records.reg,
+            &final_records.mem,
+            &final_records.io,
+            &final_records.hints,
+        )?;
+        zkvm_witness.assign_table_circuit::<ProgramTableCircuit<E>>(
+            &self.zkvm_cs,
+            &self.prog_config,
+            program,
+        )?;
+
+        if std::env::var("MOCK_PROVING").is_ok() {
+            MockProver::assert_satisfied_full(
+                self.zkvm_cs.clone(),
+                self.fixed_traces.clone(),
+                &zkvm_witness,
+                &pi,
+            );
+        }
+
+        let proof =
+            self.prover
+                .create_proof(zkvm_witness, pi, self.prover.pk.new_transcript(b"riscv"))?;
+        Ok(SegmentProof {
+            start: segment.start,
+            end: segment.end,
+            exit_code,
+            report: segment.report,
+            proof,
+        })
+    }
+}
+
+/// A segment which the emulator has run, with the state at its end.
+struct EmulatedSegment {
+    segment: Segment,
+    final_records: FinalRecords,
+    output: Vec<u8>,
+}
+
+impl EmulatedSegment {
+    /// Take from `vm`, which has just run `segment`, what the proof of the segment needs of the
+    /// state at its end, so that `vm` goes on with the next segment meanwhile. Only the addresses
+    /// of the initial records matter here.
+    fn new(
+        vm: &VMState,
+        segment: Segment,
+        reg_init: &[MemInitRecord],
+        mem_init: &[MemInitRecord],
+        io_init: &[MemInitRecord],
+        hints: &[u32],
+    ) -> Self {
+        let final_records = FinalRecords::new(
+            vm,
+            &segment.final_accesses,
+            reg_init,
+            mem_init,
+            io_init,
+            hints,
+        );
+        EmulatedSegment {
+            segment,
+            final_records,
+            output: vm.committed_output().to_vec(),
+        }
+    }
+}
+
+/// Emulate the next segment of at most `max_steps` steps, if the execution goes on.
+fn next_segment(vm: &mut VMState, max_steps: usize) -> Result<Option<Segment>, ZKVMError> {
+    vm.next_segment(max_steps).map_err(vm_exec_error)
+}
+
+/// A trap of the emulator, with its crash report.
+fn vm_exec_error(err: impl Display) -> ZKVMError {
+    ZKVMError::GuestError(format!("vm exec failed: {err:#}"))
+}
+
+/// Wait for the emulator thread of a pipeline, which does not panic but on a bug.
+fn join_emulator(emulator: thread::ScopedJoinHandle<'_, ()>) -> Result<(), ZKVMError> {
+    emulator
+        .join()
+        .map_err(|_| ZKVMError::GuestError("the emulator thread panicked".into()))
+}
+
+/// A cycle of a segment boundary as a public value.
+fn cycle_value(cycle: Cycle) -> Result<u32, ZKVMError> {
+    cycle
+        .try_into()
+        .map_err(|_| ZKVMError::GuestError(format!("cycle {cycle} does not fit in 32 bits")))
+}
+
+/// The public values of the registers and static memory at the start, then at the end of a
+/// segment.
+fn segment_state<F: Clone>(raw_pi: &[Vec<F>]) -> [Vec<Vec<F>>; 2] {
+    [0, 1].map(|end| {
+        chain!(&raw_pi[REG_STATE_IDX + end * UINT_LIMBS..][..UINT_LIMBS], [
+            &raw_pi[MEM_STATE_IDX + end]
+        ],)
+        .cloned()
+        .collect()
+    })
+}
+
+/// Verify the proofs of a chunked execution of `program`, and check that they stitch together:
+/// the first segment starts at the entry point of the program with its initial registers and
+/// memory, each segment starts with the pc, cycle, registers and static memory at the end of the
+/// previous one, and only the last one halts. Return its exit code.
+///
+/// The key is derived here from the program, as [`run_e2e_segments`] does. The states of the
+/// boundaries are public values, which the register and static memory tables of each segment
+/// constrain. The segment reports are claims of the prover, only checked to agree across
+/// boundaries.
+pub fn verify_segments<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+    segments: &[SegmentProof<E, PCS>],
+    program: &Program,
+    platform: &Platform,
+    stack_size: u32,
+    heap_size: u32,
+    transcript_hash: TranscriptHash,
+) -> Result<u32, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    let (mem_init, mut mem_padder) = init_mem(program, platform, stack_size, heap_size);
+    let circuits = setup_circuits::<E, PCS>(
+        program,
+        platform,
+        &mem_init,
+        &mut mem_padder,
+        None,
+        transcript_hash,
+        true,
+    )?;
+    let verifier = ZKVMVerifier::new(circuits.pk.get_vk());
+
+    let Some(last) = segments.last() else {
+        return Err(VerifyError::Unsatisfied("no segments".into()).into());
+    };
+    let Some(exit_code) = last.exit_code else {
+        return Err(VerifyError::Unsatisfied("the last segment does not halt".into()).into());
+    };
+    let initial_state = SegmentState {
+        registers: circuits.reg_init.iter().map(|rec| rec.value).collect(),
+        memory: mem_init.iter().map(|rec| rec.value).collect(),
+    };
+    let [mut expected_state, _] = segment_state(
+        &PublicValues::default()
+            .with_segment_state(initial_state, SegmentState::default())
+            .to_vec::<E>(),
+    );
+    let mut expected_start = (program.entry, Tracer::SUBCYCLES_PER_INSN);
+
+    for (i, segment) in enumerate(segments) {
+        if (segment.start.pc, segment.start.cycle) != expected_start {
+            return Err(VerifyError::Unsatisfied(format!(
+                "segment {i} does not start where the previous one ends"
+            ))
+            .into());
+        }
+        if i > 0 && segments[i - 1].report.registers_out != segment.report.registers_in {
+            return Err(VerifyError::Unsatisfied(format!(
+                "segment {i} does not start with the registers left by the previous one"
+            ))
+            .into());
+        }
+        if segment.exit_code.is_some() && i + 1 != segments.len() {
+            return Err(VerifyError::Unsatisfied(format!(
+                "segment {i} halts before the last segment"
+            ))
+            .into());
+        }
+
+        // The public values must match the boundaries and the public IO, then the state left by
+        // the previous segment. Only the tables of the registers and of the static memory
+        // constrain the state.
+        let raw_pi = &segment.proof.raw_pi;
+        let expected = PublicValues::new(
+            segment.exit_code.unwrap_or(0),
+            segment.start.pc,
+            cycle_value(segment.start.cycle)?,
+            segment.end.pc,
+            cycle_value(segment.end.cycle)?,
+            circuits.io_init.iter().map(|rec| rec.value).collect_vec(),
+        )
+        .to_vec::<E>();
+        if raw_pi.len() != NUM_PUBLIC_VALUES
+            || raw_pi[..=PUBLIC_IO_IDX] != expected[..=PUBLIC_IO_IDX]
+        {
+            return Err(VerifyError::Unsatisfied(format!(
+                "public values of segment {i} do not match its boundaries"
+            ))
+            .into());
+        }
+        let [init_state, end_state] = segment_state(raw_pi);
+        if init_state != expected_state
+            || end_state
+                .iter()
+                .map(Vec::len)
+                .ne(init_state.iter().map(Vec::len))
+        {
+            return Err(VerifyError::Unsatisfied(format!(
+                "segment {i} does not start with the state left by the previous one"
+            ))
+            .into());
+        }
+        for name in [RegTableCircuit::<E>::name(), StaticMemCircuit::<E>::name()] {
+            if segment.proof.table_proof(&name).is_none() {
+                return Err(VerifyError::MissingProof(format!("{name} of segment {i}")).into());
+            }
+        }
+
+        if !verifier.verify_proof_halt(
+            segment.proof.clone(),
+            verifier.new_transcript(b"riscv"),
+            segment.exit_code.is_some(),
+        )? {
+            return Err(
+                VerifyError::Unsatisfied(format!("proof of segment {i} is invalid")).into(),
+            );
+        }
+        expected_state = end_state;
+        expected_start = (segment.end.pc, segment.end.cycle);
+    }
+    Ok(exit_code)
+}
+
+/// The program image, stack and heap as initial static memory, padded to a power of two, and the
+/// padder used to place more memory after them.
+fn init_mem(
+    program: &Program,
+    platform: &Platform,
+    stack_size: u32,
+    heap_size: u32,
+) -> (Vec<MemInitRecord>, MemPadder) {
+    let stack_addrs = platform.stack_top - stack_size..platform.stack_top;
+
+    // Detect heap as starting after program data.
+    let heap_start = program.image.keys().max().unwrap() + WORD_SIZE as u32;
+    let heap_addrs = heap_start..heap_start + heap_size;
+
+    let mut mem_padder = MemPadder::new(heap_addrs.end..platform.ram.end);
+
+    let program_addrs = program.image.iter().map(|(addr, value)| MemInitRecord {
+        addr: *addr,
+        value: *value,
+    });
+
+    let stack = stack_addrs
+        .iter_addresses()
+        .map(|addr| MemInitRecord { addr, value: 0 });
+
+    let heap = heap_addrs
+        .iter_addresses()
+        .map(|addr| MemInitRecord { addr, value: 0 });
+
+    let mem_init = chain!(program_addrs, stack, heap).collect_vec();
+
+    let mem_init = mem_padder.padded_sorted(mem_init.len().next_power_of_two(), mem_init);
+    (mem_init, mem_padder)
+}
+
+/// The bytes of the hint tape served by ecall READ_HINT, the same as the hints in memory.
+fn hints_bytes(hints: &[u32]) -> Vec<u8> {
+    hints.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// Log the instructions of `program` which have no circuit, before running it.
+fn report_unsupported_instructions(program: &Program) {
+    let unsupported = find_unsupported_instructions(program);
+    if unsupported.is_empty() {
+        return;
+    }
+    tracing::warn!(
+        "{} instructions of the program have no circuit, they fail or are not constrained if executed:",
+        unsupported.len()
+    );
+    for insn in &unsupported {
+        tracing::warn!("  {insn}");
+    }
+}
+
+/// Find the exit code from the HALT step, if halting at all.
+pub(crate) fn find_exit_code(records: &[StepRecord]) -> Option<u32> {
+    records
+        .iter()
+        .rev()
+        .find(|record| {
+            record.insn().codes().kind == EANY
+                && record.rs1().unwrap().value == Platform::ecall_halt()
+        })
+        .and_then(|halt_record| halt_record.rs2())
+        .map(|rs2| rs2.value)
+}
+
+/// The final values and cycles of the register, static memory, public IO and hints tables.
+pub(crate) struct FinalRecords {
+    pub(crate) reg: Vec<MemFinalRecord>,
+    pub(crate) mem: Vec<MemFinalRecord>,
+    pub(crate) io: Vec<Cycle>,
+    pub(crate) hints: Vec<MemFinalRecord>,
+}
+
+impl FinalRecords {
+    /// Read the final values from `vm`, and the cycles of the last accesses from `final_access`.
+    pub(crate) fn new(
+        vm: &VMState,
+        final_access: &HashMap<WordAddr, Cycle>,
+        reg_init: &[MemInitRecord],
+        mem_init: &[MemInitRecord],
+        io_init: &[MemInitRecord],
+        hints: &[u32],
+    ) -> Self {
+        // Find the final register values and cycles.
+        let reg = reg_init
+            .iter()
+            .map(|rec| {
+                let index = rec.addr as usize;
+                if index < VMState::REG_COUNT {
+                    let vma: WordAddr = Platform::register_vma(index).into();
+                    MemFinalRecord {
+                        addr: rec.addr,
+                        value: vm.peek_register(index),
+                        cycle: *final_access.get(&vma).unwrap_or(&0),
+                    }
+                } else {
+                    // The table is padded beyond the number of registers.
+                    MemFinalRecord {
+                        addr: rec.addr,
+                        value: 0,
+                        cycle: 0,
+                    }
+                }
+            })
+            .collect_vec();
+
+        // Find the final memory values and cycles.
+        let mem = mem_init
+            .iter()
+            .map(|rec| {
+                let vma: WordAddr = rec.addr.into();
+                MemFinalRecord {
+                    addr: rec.addr,
+                    value: vm.peek_memory(vma),
+                    cycle: *final_access.get(&vma).unwrap_or(&0),
+                }
+            })
+            .collect_vec();
+
+        // Find the final public IO cycles.
+        let io = io_init
+            .iter()
+            .map(|rec| *final_access.get(&rec.addr.into()).unwrap_or(&0))
+            .collect_vec();
+
+        let hints = zip(vm.platform().hints.iter_addresses(), hints)
+            .map(|(addr, &value)| MemFinalRecord {
+                addr,
+                value,
+                cycle: *final_access.get(&addr.into()).unwrap_or(&0),
+            })
+            .collect_vec();
+
+        FinalRecords {
+            reg,
+            mem,
+            io,
+            hints,
+        }
+    }
+}
+
 fn debug_memory_ranges(vm: &VMState, mem_final: &[MemFinalRecord]) {
     let accessed_addrs = vm
         .tracer()
@@ -330,3 +1093,63 @@ fn format_segment(platform: &Platform, addr: u32) -> String {
         if platform.can_execute(addr) { "X" } else { "-" },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use ceno_emul::{CENO_PLATFORM, InsnKind::ADDI, PC_WORD_SIZE, Program, encode_rv32};
+    use goldilocks::GoldilocksExt2;
+    use mpcs::{Basefold, BasefoldRSParams};
+    use transcript::TranscriptHash;
+
+    use super::{run_e2e_gen_witness, run_e2e_segments};
+    use crate::error::ZKVMError;
+
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+
+    #[test]
+    fn test_emulator_trap() {
+        // two additions, then the execution runs off the end of the program
+        let code = vec![encode_rv32(ADDI, 1, 0, 1, 1); 2];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(
+            pc_base,
+            pc_base,
+            code.clone(),
+            code.iter()
+                .enumerate()
+                .map(|(i, &insn)| (pc_base + (i * PC_WORD_SIZE) as u32, insn))
+                .collect(),
+        );
+        let is_trap = |err: &ZKVMError| matches!(err, ZKVMError::GuestError(msg) if msg.starts_with("vm exec failed"));
+
+        let Err(err) = run_e2e_gen_witness::<E, Pcs>(
+            program.clone(),
+            CENO_PLATFORM,
+            1 << 10,
+            0,
+            vec![],
+            usize::MAX,
+            None,
+            TranscriptHash::default(),
+        ) else {
+            panic!("the trap is not reported");
+        };
+        assert!(is_trap(&err), "{err}");
+
+        // the segments before the trap are proven while it is emulated
+        let Err(err) = run_e2e_segments::<E, Pcs>(
+            program,
+            CENO_PLATFORM,
+            1 << 10,
+            0,
+            vec![],
+            usize::MAX,
+            1,
+            TranscriptHash::default(),
+        ) else {
+            panic!("the trap is not reported");
+        };
+        assert!(is_trap(&err), "{err}");
+    }
+}
@@ -1,24 +1,260 @@
+//! The errors of circuit construction, witness assignment, proving and verification.
+//!
+//! Each error carries the circuit, namespace path or witness it is about, and the error it stems
+//! from, if any, as its `std::error::Error::source`.
+
+use ceno_emul::{GuestPanic, InsnKind};
 use mpcs::Error;
+use thiserror::Error;
+
+use crate::structs::{ProtocolVersion, ROMType};
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum UtilError {
-    UIntError(String),
+    /// a `UIntLimbs<max_bits, limb_bits>` built from another number of cells than its limbs
+    #[error(
+        "cannot construct UIntLimbs<{max_bits}, {limb_bits}> from {got} cells, requires {expected} cells"
+    )]
+    UIntLimbCount {
+        max_bits: usize,
+        limb_bits: usize,
+        expected: usize,
+        got: usize,
+    },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum ZKVMError {
-    CircuitError,
-    UtilError(UtilError),
-    WitnessNotFound(String),
-    InvalidWitness(String),
-    VKNotFound(String),
+    /// a config, by its namespace path, which the circuit did not construct
+    #[error("{path} was not constructed")]
+    MissingConfig { path: String },
+    /// an expression, by its namespace path, which must be a constant but is not
+    #[error("{path} is not a constant")]
+    NotConstant { path: String },
+    /// an operand of degree reduction, by the namespace path of the witness it would derive,
+    /// which depends on a fixed column, an instance or a challenge rather than only on witnesses
+    #[error("{path} cannot be derived from the witnesses")]
+    NotDerivable { path: String },
+    /// a circuit registered twice in a `ZKVMConstraintSystem` or `ZKVMFixedTraces`
+    #[error("circuit {0} is registered twice")]
+    DuplicateCircuit(String),
+    #[error("circuit {0} is not registered")]
+    CircuitNotFound(String),
+    #[error(transparent)]
+    UtilError(#[from] UtilError),
+    #[error("no witness for circuit {circuit}")]
+    WitnessNotFound { circuit: String },
+    /// a number of instance columns other than the constraint system of the circuit declares
+    #[error("circuit {circuit} expects {expected} instance columns, got {got}")]
+    InstanceColumnCount {
+        circuit: String,
+        expected: usize,
+        got: usize,
+    },
+    /// an instance column with another number of values than the instances of the circuit
+    #[error("instance column {column} of circuit {circuit} has {got} values, expects {expected}")]
+    InstanceColumnLength {
+        circuit: String,
+        column: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// an instruction kind assigned to `Selectors` which do not select it
+    #[error("{0} is not among the selected kinds")]
+    UnselectedKind(InsnKind),
+    /// challenge name missing from `CHALLENGE_NAMES`
+    #[error("unknown challenge {0}")]
+    UnknownChallenge(String),
+    /// the fixed traces of a circuit, or of a fixed column by its namespace path, which were not
+    /// generated
+    #[error("no fixed traces for {0}")]
     FixedTraceNotFound(String),
-    VerifyError(String),
-    PCSError(Error),
+    #[error("verification failed")]
+    VerifyError(#[from] VerifyError),
+    #[error("polynomial commitment scheme failed")]
+    PCSError(#[source] Error),
+    /// lookup multiplicity of (table, key) exceeds what the logup argument can encode
+    #[error("lookup multiplicity of {1:#x} in table {0:?} overflows")]
+    LkMultiplicityOverflow(ROMType, u64),
+    /// lookup of (table, key) beyond the rows of the table
+    #[error("lookup of {1:#x} beyond the rows of table {0:?}")]
+    LkKeyOutOfRange(ROMType, u64),
+    /// a structured table, by its ROM type, without columns or with two columns of one name
+    #[error("invalid columns of table {0:?}: {1}")]
+    InvalidTableColumns(ROMType, String),
+    /// a ROM table, by its ROM type, which cannot be folded into a batched table, see
+    /// `BatchedTables::ROM_TYPES`
+    #[error("table {0:?} cannot be batched: {1}")]
+    UnbatchableTable(ROMType, String),
+    #[error("serialization: {0}")]
+    SerializationError(String),
+    #[error("I/O error")]
+    IOError(#[from] std::io::Error),
+    /// serialized data of a format version other than `FORMAT_VERSION`
+    #[error("unsupported format version {0}")]
+    UnsupportedFormatVersion(u32),
+    /// base field of the given bit width, narrower than a circuit requires, see
+    /// `Instruction::min_field_bits`, or other than the field a circuit is specific to
+    #[error("unsupported base field of {0} bits")]
+    UnsupportedField(u32),
+    /// verifying key whose digest is not the expected one
+    #[error("verifying key digest mismatch")]
+    VKDigestMismatch,
+    /// proof of a version which the verifying key cannot verify, see
+    /// `ProtocolVersion::is_compatible`
+    #[error("proof of version {proof} cannot be verified by a key of version {vk}")]
+    VersionMismatch {
+        proof: ProtocolVersion,
+        vk: ProtocolVersion,
+    },
+    /// a guest which cannot be loaded, or which does not halt
+    #[error("guest error: {0}")]
+    GuestError(String),
+    /// a guest which panicked, see `ProveOptions::prove_panics`
+    #[error("guest panicked")]
+    GuestPanic(#[source] GuestPanic),
+    /// a job of the prover service which no worker could prove, see `prover_service`
+    #[error("worker error: {0}")]
+    WorkerError(String),
+    /// the circuits of a kind of instruction with another number of instances than the steps of
+    /// this kind, see `Rv32imConfig::check_instance_counts`
+    #[error("circuit {circuit} has {actual} instances, expects {expected}")]
+    InstanceCountMismatch {
+        circuit: String,
+        expected: usize,
+        actual: usize,
+    },
+    /// an instance of a circuit which does not satisfy one of its constraints, see
+    /// `ConstraintSystem::check_assignment`
+    #[error("circuit {circuit}: constraint {constraint} unsatisfied at row {row}")]
+    UnsatisfiedConstraint {
+        circuit: String,
+        constraint: String,
+        row: usize,
+    },
+    /// an error in the proving of the named circuit, see [`ZKVMError::in_circuit`]
+    #[error("in circuit {circuit}")]
+    Circuit {
+        circuit: String,
+        #[source]
+        source: Box<ZKVMError>,
+    },
+}
+
+impl ZKVMError {
+    /// Attribute this error to the circuit `name`, unless it already names its circuit.
+    pub fn in_circuit(self, name: &str) -> Self {
+        match self {
+            error @ (ZKVMError::Circuit { .. }
+            | ZKVMError::DuplicateCircuit(_)
+            | ZKVMError::CircuitNotFound(_)
+            | ZKVMError::WitnessNotFound { .. }
+            | ZKVMError::InstanceColumnCount { .. }
+            | ZKVMError::InstanceColumnLength { .. }
+            | ZKVMError::InstanceCountMismatch { .. }
+            | ZKVMError::UnsatisfiedConstraint { .. }) => error,
+            error => ZKVMError::Circuit {
+                circuit: name.to_string(),
+                source: Box::new(error),
+            },
+        }
+    }
+}
+
+/// Why the verifier rejected a proof.
+///
+/// A proof which does not have the shape the verifying key expects is malformed, see
+/// [`VerifyError::is_malformed`]; otherwise it is well-formed but invalid.
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    /// a proof of a circuit which is not in the verifying key
+    #[error("circuit {0} is not in the verifying key")]
+    CircuitNotInVK(String),
+    /// a circuit proven on a transcript fork which does not exist
+    #[error("circuit {0} proven on the nonexistent transcript fork {1}")]
+    InvalidForkIndex(String, usize),
+    /// a tower proof with a number of layers other than expected
+    #[error("tower proof of {got} layers, expects {expected}")]
+    TowerLayerCount { expected: usize, got: usize },
+    /// a tower proof with a number of product or logup specs other than expected
+    #[error("tower proof of {got} specs, expects {expected}")]
+    TowerSpecCount { expected: usize, got: usize },
+    /// a sumcheck proof with a number of rounds other than expected
+    #[error("sumcheck proof of {got} rounds, expects {expected}")]
+    SumcheckRoundCount { expected: usize, got: usize },
+    /// a number of evaluations other than expected
+    #[error("{got} evaluations, expects {expected}")]
+    EvaluationCount { expected: usize, got: usize },
+    /// a sub-proof required by the verifying key which is missing
+    #[error("missing proof: {0}")]
+    MissingProof(String),
+    /// a proof of an opcode circuit without instances, which should be left out of the proof
+    #[error("opcode proof without instances")]
+    NoInstances,
+    /// an opcode proof of a table circuit, or the other way around
+    #[error("proof of the wrong kind for circuit {0}")]
+    CircuitKind(String),
+    /// a PCS opening which does not verify
+    #[error("PCS opening does not verify")]
+    PCSOpening(#[source] Error),
+    /// a relation checked by the verifier which does not hold
+    #[error("{0}")]
+    Unsatisfied(String),
+    /// an error in the proof of the named circuit
+    #[error("in circuit {0}")]
+    Circuit(String, #[source] Box<VerifyError>),
 }
 
-impl From<UtilError> for ZKVMError {
-    fn from(error: UtilError) -> Self {
-        Self::UtilError(error)
+impl VerifyError {
+    /// Whether the proof does not have the expected shape, rather than being invalid.
+    pub fn is_malformed(&self) -> bool {
+        match self {
+            VerifyError::CircuitNotInVK(_)
+            | VerifyError::InvalidForkIndex(..)
+            | VerifyError::TowerLayerCount { .. }
+            | VerifyError::TowerSpecCount { .. }
+            | VerifyError::SumcheckRoundCount { .. }
+            | VerifyError::EvaluationCount { .. }
+            | VerifyError::MissingProof(_)
+            | VerifyError::NoInstances
+            | VerifyError::CircuitKind(_) => true,
+            VerifyError::PCSOpening(_) | VerifyError::Unsatisfied(_) => false,
+            VerifyError::Circuit(_, error) => error.is_malformed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn test_error_chain() {
+        let error: ZKVMError = VerifyError::Circuit(
+            "ADD".into(),
+            Box::new(VerifyError::PCSOpening(Error::MerkleRootMismatch)),
+        )
+        .into();
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+        assert_eq!(chain, [
+            "verification failed",
+            "in circuit ADD",
+            "PCS opening does not verify",
+            "Merkle root mismatch",
+        ]);
+
+        // an error which names its circuit is not attributed again
+        let error = ZKVMError::UnknownChallenge("gamma".into()).in_circuit("ADD");
+        assert!(matches!(&error, ZKVMError::Circuit { circuit, .. } if circuit == "ADD"));
+        assert!(
+            matches!(error.in_circuit("SUB"), ZKVMError::Circuit { circuit, .. } if circuit == "ADD")
+        );
     }
 }
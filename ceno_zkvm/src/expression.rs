@@ -1,7 +1,9 @@
 mod monomial;
+mod simplify;
 
 use std::{
     cmp::max,
+    collections::BTreeSet,
     fmt::Display,
     iter::{Product, Sum},
     mem::MaybeUninit,
@@ -14,6 +16,10 @@ use ff_ext::ExtensionField;
 use goldilocks::SmallField;
 
 use multilinear_extensions::virtual_poly_v2::ArcMultilinearExtension;
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{DeserializeOwned, Error as _},
+};
 
 use crate::{
     circuit_builder::CircuitBuilder,
@@ -21,6 +27,7 @@ use crate::{
     structs::{ChallengeId, RAMType, WitnessId},
 };
 
+/// Expressions are serialized as their nodes in postfix order, see [`Node`].
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Expression<E: ExtensionField> {
     /// WitIn(Id)
@@ -42,6 +49,106 @@ pub enum Expression<E: ExtensionField> {
     Challenge(ChallengeId, usize, E, E),
 }
 
+/// A node of a serialized [`Expression`], whose operands are the nodes before it. A sum of many
+/// terms nests as deep as its number of terms, deeper than a recursive encoding allows.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+enum Node<E: ExtensionField> {
+    WitIn(WitnessId),
+    Fixed(Fixed),
+    Instance(Instance),
+    Constant(E::BaseField),
+    Sum,
+    Product,
+    ScaledSum,
+    Challenge(ChallengeId, usize, E, E),
+}
+
+impl<E: ExtensionField> Expression<E> {
+    fn postfix(&self, nodes: &mut Vec<Node<E>>) {
+        match self {
+            Expression::WitIn(id) => nodes.push(Node::WitIn(*id)),
+            Expression::Fixed(fixed) => nodes.push(Node::Fixed(*fixed)),
+            Expression::Instance(instance) => nodes.push(Node::Instance(*instance)),
+            Expression::Constant(c) => nodes.push(Node::Constant(*c)),
+            Expression::Sum(a, b) => {
+                a.postfix(nodes);
+                b.postfix(nodes);
+                nodes.push(Node::Sum);
+            }
+            Expression::Product(a, b) => {
+                a.postfix(nodes);
+                b.postfix(nodes);
+                nodes.push(Node::Product);
+            }
+            Expression::ScaledSum(x, a, b) => {
+                x.postfix(nodes);
+                a.postfix(nodes);
+                b.postfix(nodes);
+                nodes.push(Node::ScaledSum);
+            }
+            Expression::Challenge(id, pow, scalar, offset) => {
+                nodes.push(Node::Challenge(*id, *pow, *scalar, *offset))
+            }
+        }
+    }
+
+    fn from_postfix(nodes: Vec<Node<E>>) -> Option<Self> {
+        let mut stack: Vec<Expression<E>> = vec![];
+        for node in nodes {
+            let expr = match node {
+                Node::WitIn(id) => Expression::WitIn(id),
+                Node::Fixed(fixed) => Expression::Fixed(fixed),
+                Node::Instance(instance) => Expression::Instance(instance),
+                Node::Constant(c) => Expression::Constant(c),
+                Node::Sum => {
+                    let b = stack.pop()?;
+                    Expression::Sum(Box::new(stack.pop()?), Box::new(b))
+                }
+                Node::Product => {
+                    let b = stack.pop()?;
+                    Expression::Product(Box::new(stack.pop()?), Box::new(b))
+                }
+                Node::ScaledSum => {
+                    let b = stack.pop()?;
+                    let a = stack.pop()?;
+                    Expression::ScaledSum(Box::new(stack.pop()?), Box::new(a), Box::new(b))
+                }
+                Node::Challenge(id, pow, scalar, offset) => {
+                    Expression::Challenge(id, pow, scalar, offset)
+                }
+            };
+            stack.push(expr);
+        }
+        let expr = stack.pop()?;
+        stack.is_empty().then_some(expr)
+    }
+}
+
+impl<E: ExtensionField> Serialize for Expression<E>
+where
+    E::BaseField: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut nodes = vec![];
+        self.postfix(&mut nodes);
+        nodes.serialize(serializer)
+    }
+}
+
+impl<'de, E: ExtensionField + DeserializeOwned> Deserialize<'de> for Expression<E>
+where
+    E::BaseField: DeserializeOwned,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let nodes = Vec::<Node<E>>::deserialize(deserializer)?;
+        Expression::from_postfix(nodes).ok_or_else(|| D::Error::custom("malformed expression"))
+    }
+}
+
 /// this is used as finite state machine state
 /// for differentiate an expression is in monomial form or not
 enum MonomialState {
@@ -66,6 +173,24 @@ impl<E: ExtensionField> Expression<E> {
         }
     }
 
+    /// Ids of the witnesses the expression depends on.
+    pub fn witins_used(&self) -> BTreeSet<WitnessId> {
+        let union = |mut a: BTreeSet<WitnessId>, mut b: BTreeSet<WitnessId>| {
+            a.append(&mut b);
+            a
+        };
+        self.evaluate_with_instance(
+            &|_| BTreeSet::new(),
+            &|witness_id| BTreeSet::from([witness_id]),
+            &|_| BTreeSet::new(),
+            &|_| BTreeSet::new(),
+            &|_, _, _, _| BTreeSet::new(),
+            &union,
+            &union,
+            &|x, a, b| union(union(x, a), b),
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn evaluate<T>(
         &self,
@@ -721,15 +846,15 @@ impl<E: ExtensionField> Mul for Expression<E> {
     }
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
 pub struct WitIn {
     pub id: WitnessId,
 }
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Fixed(pub usize);
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Instance(pub usize);
 
 impl WitIn {
@@ -1075,6 +1200,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_introspection() {
+        type E = GoldilocksExt2;
+        let mut cs = ConstraintSystem::new(|| "test_root");
+        let mut cb = CircuitBuilder::<E>::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        let y = cb.create_witin(|| "y");
+        let z = cb.create_witin(|| "z");
+
+        // (x + y) * (x + 2) = x^2 + x * y + 2 * x + 2 * y
+        let expr: Expression<E> = (x.expr() + y.expr()) * (x.expr() + 2);
+        assert_eq!(expr.degree(), 2);
+        assert_eq!(expr.num_monomials(), 4);
+        assert_eq!(expr.witins_used().into_iter().collect::<Vec<_>>(), vec![
+            x.id, y.id
+        ]);
+
+        // x * y + y * x merges into a single monomial
+        let expr: Expression<E> = x.expr() * y.expr() + y.expr() * x.expr() + 3 * z.expr();
+        assert_eq!(expr.num_monomials(), 2);
+        assert_eq!(expr.witins_used().len(), 3);
+
+        let expr: Expression<E> = Expression::Challenge(0, 1, E::ONE, E::ONE) + 5;
+        assert_eq!(expr.degree(), 0);
+        assert!(expr.witins_used().is_empty());
+    }
+
     #[test]
     fn test_is_monomial_form() {
         type E = GoldilocksExt2;
@@ -10,6 +10,26 @@ impl<E: ExtensionField> Expression<E> {
         Self::combine(self.distribute()).into_iter().sum()
     }
 
+    /// Number of monomials once expanded, repeated monomials counted once, and the ones whose
+    /// coefficient cancels out left out.
+    pub fn num_monomials(&self) -> usize {
+        Self::combine(self.distribute())
+            .into_iter()
+            .filter(|term| term.coeff.simplify() != Expression::ZERO)
+            .count()
+    }
+
+    /// The monomials as `(coefficient, variables)`, merged, without the ones whose coefficient
+    /// cancels out, and sorted by variables.
+    pub fn monomials(&self) -> Vec<(Expression<E>, Vec<Expression<E>>)> {
+        Self::combine(self.distribute())
+            .into_iter()
+            .map(|Term { coeff, vars }| (coeff.simplify(), vars))
+            .filter(|(coeff, _)| *coeff != Expression::ZERO)
+            .sorted_by(|(_, a), (_, b)| a.cmp(b))
+            .collect()
+    }
+
     fn distribute(&self) -> Vec<Term<E>> {
         match self {
             Constant(_) => {
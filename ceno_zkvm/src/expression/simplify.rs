@@ -0,0 +1,119 @@
+use ff::Field;
+use ff_ext::ExtensionField;
+
+use super::Expression;
+use Expression::*;
+
+impl<E: ExtensionField> Expression<E> {
+    /// Fold constant subtrees and drop neutral elements: `x + 0`, `x * 1`, `x * 0`,
+    /// and `1 * x + 0` scaled sums. Nodes are rebuilt through the arithmetic operators,
+    /// which merge constants into scaled sums and challenges.
+    pub fn simplify(&self) -> Self {
+        match self {
+            Sum(a, b) => {
+                let (a, b) = (a.simplify(), b.simplify());
+                if a.is_constant(E::BaseField::ZERO) {
+                    b
+                } else if b.is_constant(E::BaseField::ZERO) {
+                    a
+                } else {
+                    a + b
+                }
+            }
+            Product(a, b) => {
+                let (a, b) = (a.simplify(), b.simplify());
+                if a.is_constant(E::BaseField::ZERO) || b.is_constant(E::BaseField::ZERO) {
+                    Expression::ZERO
+                } else if a.is_constant(E::BaseField::ONE) {
+                    b
+                } else if b.is_constant(E::BaseField::ONE) {
+                    a
+                } else {
+                    a * b
+                }
+            }
+            ScaledSum(x, a, b) => {
+                let (x, a, b) = (x.simplify(), a.simplify(), b.simplify());
+                if x.is_constant(E::BaseField::ZERO) || a.is_constant(E::BaseField::ZERO) {
+                    b
+                } else if a.is_constant(E::BaseField::ONE) && b.is_constant(E::BaseField::ZERO) {
+                    x
+                } else if let (Constant(x), Constant(a), Constant(b)) = (&x, &a, &b) {
+                    Constant(*a * x + b)
+                } else {
+                    ScaledSum(Box::new(x), Box::new(a), Box::new(b))
+                }
+            }
+            Challenge(_, _, scalar, offset) if *scalar == E::ZERO => match offset.as_bases() {
+                [offset, rest @ ..] if rest.iter().all(|c| *c == E::BaseField::ZERO) => {
+                    Constant(*offset)
+                }
+                _ => self.clone(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Put the expression in monomial form, merging repeated monomials and dropping those whose
+    /// coefficient cancels out, then simplify it.
+    pub fn normalize(&self) -> Self {
+        self.to_monomial_form().simplify()
+    }
+
+    fn is_constant(&self, c: E::BaseField) -> bool {
+        matches!(self, Constant(value) if *value == c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{expression::Fixed as FixedS, scheme::utils::eval_by_expr_with_fixed};
+
+    use super::*;
+    use goldilocks::{Goldilocks as F, GoldilocksExt2 as E};
+
+    #[test]
+    fn test_simplify() {
+        let x = || WitIn::<E>(0);
+        let y = || WitIn::<E>(1);
+        let a = || Fixed::<E>(FixedS(0));
+        let zero = || Expression::<E>::ZERO;
+        let one = || Expression::<E>::ONE;
+        let sum = |a: Expression<E>, b: Expression<E>| Sum(Box::new(a), Box::new(b));
+        let product = |a: Expression<E>, b: Expression<E>| Product(Box::new(a), Box::new(b));
+
+        assert_eq!(sum(x(), zero()).simplify(), x());
+        assert_eq!(product(one(), x()).simplify(), x());
+        assert_eq!(product(product(x(), y()), zero()).simplify(), zero());
+        assert_eq!(
+            product(sum(Constant(F::from(2)), Constant(F::from(3))), one()).simplify(),
+            Constant(F::from(5))
+        );
+        assert_eq!((one() * x() + zero()).simplify(), x());
+        assert_eq!(
+            sum(product(zero(), a()), product(x(), one())).simplify(),
+            x()
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let x = || WitIn::<E>(0);
+        let y = || WitIn::<E>(1);
+
+        // x * y + y * x - 2 * x * y cancels out
+        let expr: Expression<E> = x() * y() + y() * x() - x() * y() * 2;
+        assert_eq!(expr.normalize(), Expression::ZERO);
+
+        // (x + y) * (x - y) - x * x = -y * y
+        let expr: Expression<E> = (x() + y()) * (x() - y()) - x() * x();
+        let normalized = expr.normalize();
+        assert!(normalized.is_monomial_form());
+        for (wx, wy) in [(3, 5), (7, 11)] {
+            let witnesses = [E::from(wx), E::from(wy)];
+            let eval = |e: &Expression<E>| eval_by_expr_with_fixed(&[], &witnesses, &[], e);
+            assert_eq!(eval(&normalized), eval(&expr));
+            assert_eq!(eval(&normalized), -E::from(wy * wy));
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! Entry point to fuzz the circuits against the emulator: a random instruction sequence is
+//! executed by the emulator, and its trace must satisfy the circuits.
+//!
+//! A [`FuzzProgram`] is decoded from raw bytes, or drawn as an `arbitrary::Arbitrary` value, so
+//! that any fuzzer input is a valid program, e.g. with `cargo fuzz`:
+//!
+//! ```ignore
+//! fuzz_target!(|program: FuzzProgram| {
+//!     ceno_zkvm::fuzz::mock_prove::<GoldilocksExt2>(&program);
+//! });
+//! ```
+//!
+//! The programs only branch and jump forward, and only access a scratch range of the stack, so
+//! that they always halt without a trap. A divergence between the emulator and the circuits is
+//! reported as a panic of the mock prover.
+
+use arbitrary::{Arbitrary, Unstructured};
+use ceno_emul::{
+    CENO_PLATFORM, EmuContext,
+    InsnKind::{self, *},
+    IterAddresses, MemoryLayout, PC_WORD_SIZE, Platform, Program, StepRecord, Tracer, VMState,
+    encode_rv32,
+};
+use ff_ext::ExtensionField;
+use itertools::{Itertools, enumerate};
+use strum::IntoEnumIterator;
+
+use crate::{
+    e2e::{FinalRecords, find_exit_code},
+    instructions::riscv::{DummyExtraConfig, MemPadder, MmuConfig, Rv32imConfig},
+    scheme::{PublicValues, mock_prover::MockProver},
+    state::GlobalState,
+    structs::{ProgramParams, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
+    tables::{MemInitRecord, ProgramTableCircuit},
+};
+
+/// Number of input bytes decoded into one generated instruction.
+pub const BYTES_PER_INSN: usize = 8;
+
+/// Size of the scratch range at the bottom of the stack, accessed by the loads and stores.
+const SCRATCH_SIZE: u32 = 1 << 12;
+
+/// Holds the base address of the loads and stores.
+const MEM_BASE_REG: u32 = 31;
+/// Holds the return address computed for JALR.
+const JALR_BASE_REG: u32 = 30;
+
+/// A straight-line rv32im program, generated from fuzzer bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzProgram {
+    instructions: Vec<u32>,
+}
+
+impl FuzzProgram {
+    /// Decode one instruction from each [`BYTES_PER_INSN`] bytes of `data`: its kind, the
+    /// destination and source registers, and a 32-bit immediate, adjusted to the kind. The
+    /// trailing bytes are ignored.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let kinds = FuzzInsn::kinds();
+        let insns = data
+            .chunks_exact(BYTES_PER_INSN)
+            .map(|chunk| FuzzInsn {
+                kind: kinds[chunk[0] as usize % kinds.len()],
+                // The reserved registers are never written.
+                rd: chunk[1] as u32 % JALR_BASE_REG,
+                rs1: chunk[2] as u32 % 32,
+                rs2: chunk[3] as u32 % 32,
+                imm: u32::from_le_bytes(chunk[4..8].try_into().unwrap()),
+            })
+            .collect_vec();
+        Self::from_insns(&insns)
+    }
+
+    fn from_insns(insns: &[FuzzInsn]) -> Self {
+        let mut instructions = vec![encode_rv32(
+            LUI,
+            0,
+            0,
+            MEM_BASE_REG,
+            CENO_PLATFORM.stack_top - SCRATCH_SIZE,
+        )];
+        for (i, insn) in enumerate(insns) {
+            // A branch or jump skips the whole next instruction, or the target below.
+            let next_len = insns.get(i + 1).map_or(1, FuzzInsn::len);
+            instructions.extend(insn.encode(next_len));
+        }
+        instructions.extend([
+            // The target of a branch or jump from the last generated instruction.
+            encode_rv32(ADDI, 0, 0, 0, 0),
+            encode_rv32(
+                ADDI,
+                0,
+                0,
+                Platform::reg_ecall() as u32,
+                Platform::ecall_halt(),
+            ),
+            encode_rv32(ADDI, 0, 0, Platform::reg_arg0() as u32, 0),
+            encode_rv32(EANY, 0, 0, 0, 0),
+        ]);
+        Self { instructions }
+    }
+
+    pub fn instructions(&self) -> &[u32] {
+        &self.instructions
+    }
+
+    pub fn program(&self) -> Program {
+        let pc_base = CENO_PLATFORM.pc_base();
+        let image = enumerate(&self.instructions)
+            .map(|(i, &insn)| (pc_base + (i * PC_WORD_SIZE) as u32, insn))
+            .collect();
+        Program::new(pc_base, pc_base, self.instructions.clone(), image)
+    }
+
+    /// Execute the program, returning its trace.
+    pub fn execute(&self) -> Vec<StepRecord> {
+        let mut vm = VMState::new(CENO_PLATFORM, self.program());
+        vm.set_memory_layout(MemoryLayout::from_addresses(scratch_addresses()));
+        // Every instruction runs at most once.
+        vm.iter_until_halt()
+            .take(self.instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("vm exec failed")
+    }
+}
+
+/// Draw the instructions from the fuzzer input, as [`FuzzProgram::from_bytes`] decodes them.
+impl<'a> Arbitrary<'a> for FuzzProgram {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let insns = u.arbitrary_iter()?.collect::<arbitrary::Result<Vec<_>>>()?;
+        Ok(Self::from_insns(&insns))
+    }
+}
+
+/// A generated instruction, before its operands are adjusted to its kind.
+#[derive(Clone, Copy, Debug)]
+struct FuzzInsn {
+    kind: InsnKind,
+    rd: u32,
+    rs1: u32,
+    rs2: u32,
+    imm: u32,
+}
+
+impl FuzzInsn {
+    /// The kinds generated, every one but the ecalls.
+    fn kinds() -> Vec<InsnKind> {
+        InsnKind::iter()
+            .filter(|kind| !matches!(kind, INVALID | EANY))
+            .collect()
+    }
+
+    /// The number of words of the encoding.
+    fn len(&self) -> usize {
+        self.encode(1).len()
+    }
+
+    /// Encode the instruction, with branches and jumps skipping the `next_len` words which follow
+    /// it.
+    fn encode(&self, next_len: usize) -> Vec<u32> {
+        let FuzzInsn {
+            kind,
+            rd,
+            rs1,
+            rs2,
+            imm,
+        } = *self;
+        let skip = ((1 + next_len) * PC_WORD_SIZE) as u32;
+        match kind {
+            SLLI | SRLI | SRAI => vec![encode_rv32(kind, rs1, 0, rd, imm & 0x1f)],
+            // Within the scratch range, aligned to the access size.
+            LB | LBU => vec![encode_rv32(kind, MEM_BASE_REG, 0, rd, imm & 0x7ff)],
+            LH | LHU => vec![encode_rv32(kind, MEM_BASE_REG, 0, rd, imm & 0x7fe)],
+            LW => vec![encode_rv32(kind, MEM_BASE_REG, 0, rd, imm & 0x7fc)],
+            SB => vec![encode_rv32(kind, MEM_BASE_REG, rs2, 0, imm & 0x7ff)],
+            SH => vec![encode_rv32(kind, MEM_BASE_REG, rs2, 0, imm & 0x7fe)],
+            SW => vec![encode_rv32(kind, MEM_BASE_REG, rs2, 0, imm & 0x7fc)],
+            // Forward over the next instruction when taken.
+            BEQ | BNE | BLT | BGE | BLTU | BGEU => vec![encode_rv32(kind, rs1, rs2, 0, skip)],
+            JAL => vec![encode_rv32(kind, 0, 0, rd, skip)],
+            // Jump to the next instruction, through the pc held by the reserved register.
+            JALR => vec![
+                encode_rv32(AUIPC, 0, 0, JALR_BASE_REG, 0),
+                encode_rv32(kind, JALR_BASE_REG, 0, rd, 8),
+            ],
+            _ => vec![encode_rv32(kind, rs1, rs2, rd, imm)],
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for FuzzInsn {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(FuzzInsn {
+            kind: *u.choose(&Self::kinds())?,
+            // The reserved registers are never written.
+            rd: u.int_in_range(0..=JALR_BASE_REG - 1)?,
+            rs1: u.int_in_range(0..=31)?,
+            rs2: u.int_in_range(0..=31)?,
+            imm: u.arbitrary()?,
+        })
+    }
+}
+
+fn scratch_addresses() -> impl Iterator<Item = u32> {
+    (CENO_PLATFORM.stack_top - SCRATCH_SIZE..CENO_PLATFORM.stack_top).iter_addresses()
+}
+
+/// Execute `program` and mock prove all the circuits with its trace, panicking on any
+/// unsatisfied constraint or lookup.
+pub fn mock_prove<E: ExtensionField>(program: &FuzzProgram) {
+    let platform = CENO_PLATFORM;
+    let program = program.program();
+    let mut mem_padder = MemPadder::new(platform.stack_top..platform.ram.end);
+    let mem_init = mem_padder.padded_sorted(
+        (SCRATCH_SIZE as usize / 4).next_power_of_two(),
+        scratch_addresses()
+            .map(|addr| MemInitRecord { addr, value: 0 })
+            .collect(),
+    );
+
+    let mut vm = VMState::new(platform.clone(), program);
+    vm.set_memory_layout(MemoryLayout::from_addresses(
+        mem_init.iter().map(|rec| rec.addr),
+    ));
+
+    let program_params = ProgramParams {
+        platform,
+        program_size: vm.program().instructions.len(),
+        static_memory_len: mem_init.len(),
+        ..ProgramParams::default()
+    };
+    let mut zkvm_cs = ZKVMConstraintSystem::new_with_platform(program_params);
+    let config = Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+    let mmu_config = MmuConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+    let dummy_config = DummyExtraConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+    let prog_config = zkvm_cs
+        .register_table_circuit::<ProgramTableCircuit<E>>()
+        .unwrap();
+    zkvm_cs.register_global_state::<GlobalState>().unwrap();
+
+    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+    zkvm_fixed_traces
+        .register_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, vm.program())
+        .unwrap();
+    let io_init = mem_padder.padded_sorted(mmu_config.public_io_len(), vec![]);
+    let reg_init = mmu_config.initial_registers();
+    config
+        .generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces)
+        .unwrap();
+    mmu_config
+        .generate_fixed_traces(
+            &zkvm_cs,
+            &mut zkvm_fixed_traces,
+            &reg_init,
+            &mem_init,
+            &io_init.iter().map(|rec| rec.addr).collect_vec(),
+        )
+        .unwrap();
+    dummy_config
+        .generate_fixed_traces(&zkvm_cs, &mut zkvm_fixed_traces)
+        .unwrap();
+
+    let max_steps = vm.program().instructions.len();
+    let steps = vm
+        .iter_until_halt()
+        .take(max_steps)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("vm exec failed");
+    assert!(vm.halted(), "generated program did not halt");
+    let exit_code = find_exit_code(&steps);
+
+    let mut zkvm_witness = ZKVMWitnesses::default();
+    let dummy_records = config
+        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, steps)
+        .unwrap();
+    dummy_config
+        .assign_opcode_circuit(&zkvm_cs, &mut zkvm_witness, dummy_records)
+        .unwrap();
+    zkvm_witness.finalize_lk_multiplicities().unwrap();
+
+    let final_access = vm.tracer().final_accesses();
+    let final_records = FinalRecords::new(&vm, final_access, &reg_init, &mem_init, &io_init, &[]);
+    config
+        .assign_table_circuit(&zkvm_cs, &mut zkvm_witness)
+        .unwrap();
+    mmu_config
+        .assign_table_circuit(
+            &zkvm_cs,
+            &mut zkvm_witness,
+            &final_records.reg,
+            &final_records.mem,
+            &final_records.io,
+            &final_records.hints,
+        )
+        .unwrap();
+    zkvm_witness
+        .assign_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, vm.program())
+        .unwrap();
+
+    let pi = PublicValues::new(
+        exit_code.unwrap_or(0),
+        vm.program().entry,
+        Tracer::SUBCYCLES_PER_INSN as u32,
+        vm.get_pc().into(),
+        vm.tracer().cycle().try_into().unwrap(),
+        io_init.iter().map(|rec| rec.value).collect_vec(),
+    );
+    MockProver::assert_satisfied_full(zkvm_cs, zkvm_fixed_traces, &zkvm_witness, &pi);
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+
+    use super::*;
+
+    fn fuzz_bytes(len: usize) -> Vec<u8> {
+        // A fixed xorshift stream, to cover most kinds and registers.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzz_program_halts() {
+        for len in [0, 7, 64, 1024] {
+            let program = FuzzProgram::from_bytes(&fuzz_bytes(len));
+            let steps = program.execute();
+            assert_eq!(
+                steps.last().unwrap().insn().codes().kind,
+                EANY,
+                "input of {len} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fuzz_program_skips_jalr() {
+        // the branch is taken over both words of the JALR
+        let beq = FuzzInsn {
+            kind: BEQ,
+            rd: 0,
+            rs1: 0,
+            rs2: 0,
+            imm: 0,
+        };
+        let jalr = FuzzInsn { kind: JALR, ..beq };
+        let steps = FuzzProgram::from_insns(&[beq, jalr]).execute();
+        assert_eq!(steps.last().unwrap().insn().codes().kind, EANY);
+        assert!(steps.iter().all(|step| step.insn().codes().kind != JALR));
+    }
+
+    #[test]
+    fn test_fuzz_program_arbitrary() {
+        let data = fuzz_bytes(1024);
+        let program = FuzzProgram::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        let steps = program.execute();
+        assert_eq!(steps.last().unwrap().insn().codes().kind, EANY);
+    }
+
+    #[test]
+    fn test_fuzz_mock_prove() {
+        mock_prove::<GoldilocksExt2>(&FuzzProgram::from_bytes(&fuzz_bytes(64 * BYTES_PER_INSN)));
+    }
+}
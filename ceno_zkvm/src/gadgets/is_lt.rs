@@ -13,10 +13,10 @@ use crate::{
     expression::{Expression, ToExpr, WitIn},
     instructions::riscv::constants::{UINT_LIMBS, UInt},
     set_val,
-    witness::LkMultiplicity,
+    witness::{AssignmentCtx, LkMultiplicity},
 };
 
-use super::SignedExtendConfig;
+use super::{Gadget, SignedExtendConfig};
 
 #[derive(Debug, Clone)]
 pub struct AssertLTConfig(InnerLtConfig);
@@ -37,6 +37,7 @@ impl AssertLTConfig {
             || "assert_lt",
             |cb| {
                 let name = name_fn();
+                cb.record_range_checked(&lhs);
                 let config = InnerLtConfig::construct_circuit(
                     cb,
                     name,
@@ -374,3 +375,37 @@ impl<E: ExtensionField> InnerSignedLtConfig<E> {
         Ok(())
     }
 }
+
+impl<E: ExtensionField> Gadget<E> for AssertLTConfig {
+    /// (lhs, rhs, max_num_u16_limbs)
+    type Input = (Expression<E>, Expression<E>, usize);
+    type Value = (u64, u64);
+
+    fn construct(
+        cb: &mut CircuitBuilder<E>,
+        (lhs, rhs, max_num_u16_limbs): Self::Input,
+    ) -> Result<Self, ZKVMError> {
+        Self::construct_circuit(cb, || "lhs < rhs", lhs, rhs, max_num_u16_limbs)
+    }
+
+    fn assign(&self, ctx: &mut AssignmentCtx<E>, (lhs, rhs): (u64, u64)) -> Result<(), ZKVMError> {
+        self.assign_instance(ctx.instance, ctx.lk_multiplicity, lhs, rhs)
+    }
+}
+
+impl<E: ExtensionField> Gadget<E> for IsLtConfig {
+    /// (lhs, rhs, max_num_u16_limbs)
+    type Input = (Expression<E>, Expression<E>, usize);
+    type Value = (u64, u64);
+
+    fn construct(
+        cb: &mut CircuitBuilder<E>,
+        (lhs, rhs, max_num_u16_limbs): Self::Input,
+    ) -> Result<Self, ZKVMError> {
+        Self::construct_circuit(cb, || "lhs < rhs", lhs, rhs, max_num_u16_limbs)
+    }
+
+    fn assign(&self, ctx: &mut AssignmentCtx<E>, (lhs, rhs): (u64, u64)) -> Result<(), ZKVMError> {
+        self.assign_instance(ctx.instance, ctx.lk_multiplicity, lhs, rhs)
+    }
+}
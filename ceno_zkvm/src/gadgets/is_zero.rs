@@ -8,8 +8,20 @@ use crate::{
     error::ZKVMError,
     expression::{Expression, ToExpr, WitIn},
     set_val,
+    witness::AssignmentCtx,
 };
 
+use super::Gadget;
+
+/// The `(is_zero, inverse)` witness pair of `x`, as constrained by `CircuitBuilder::is_zero`.
+pub fn is_zero_witness<F: SmallField>(x: F) -> (F, F) {
+    if x.is_zero_vartime() {
+        (F::ONE, F::ZERO)
+    } else {
+        (F::ZERO, x.invert().expect("not zero"))
+    }
+}
+
 pub struct IsZeroConfig {
     is_zero: Option<WitIn>,
     inverse: WitIn,
@@ -25,7 +37,7 @@ impl IsZeroConfig {
         name_fn: N,
         x: Expression<E>,
     ) -> Result<Self, ZKVMError> {
-        Self::construct(cb, name_fn, x, false)
+        Self::construct_inner(cb, name_fn, x, false)
     }
 
     pub fn construct_non_zero<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
@@ -33,32 +45,32 @@ impl IsZeroConfig {
         name_fn: N,
         x: Expression<E>,
     ) -> Result<Self, ZKVMError> {
-        Self::construct(cb, name_fn, x, true)
+        Self::construct_inner(cb, name_fn, x, true)
     }
 
-    fn construct<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
+    fn construct_inner<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
         cb: &mut CircuitBuilder<E>,
         name_fn: N,
         x: Expression<E>,
         assert_non_zero: bool,
     ) -> Result<Self, ZKVMError> {
         cb.namespace(name_fn, |cb| {
-            let (is_zero, is_zero_expr) = if assert_non_zero {
-                (None, 0.into())
+            if assert_non_zero {
+                let inverse = cb.create_witin(|| "inv");
+                cb.require_one(|| "is_non_zero", x * inverse.expr())?;
+
+                Ok(IsZeroConfig {
+                    is_zero: None,
+                    inverse,
+                })
             } else {
-                let is_zero = cb.create_witin(|| "is_zero");
-
-                // x!=0 => is_zero=0
-                cb.require_zero(|| "is_zero_0", is_zero.expr() * x.clone())?;
-
-                (Some(is_zero), is_zero.expr())
-            };
-            let inverse = cb.create_witin(|| "inv");
+                let (is_zero, inverse) = cb.is_zero(x)?;
 
-            // x==0 => is_zero=1
-            cb.require_one(|| "is_zero_1", is_zero_expr + x.clone() * inverse.expr())?;
-
-            Ok(IsZeroConfig { is_zero, inverse })
+                Ok(IsZeroConfig {
+                    is_zero: Some(is_zero),
+                    inverse,
+                })
+            }
         })
     }
 
@@ -67,11 +79,7 @@ impl IsZeroConfig {
         instance: &mut [MaybeUninit<F>],
         x: F,
     ) -> Result<(), ZKVMError> {
-        let (is_zero, inverse) = if x.is_zero_vartime() {
-            (F::ONE, F::ZERO)
-        } else {
-            (F::ZERO, x.invert().expect("not zero"))
-        };
+        let (is_zero, inverse) = is_zero_witness(x);
 
         if let Some(wit) = self.is_zero {
             set_val!(instance, wit, is_zero);
@@ -124,3 +132,36 @@ impl IsEqualConfig {
         self.0.assign_instance(instance, a - b)
     }
 }
+
+impl<E: ExtensionField> Gadget<E> for IsZeroConfig {
+    type Input = Expression<E>;
+    type Value = E::BaseField;
+
+    fn construct(cb: &mut CircuitBuilder<E>, x: Expression<E>) -> Result<Self, ZKVMError> {
+        Self::construct_circuit(cb, || "is_zero", x)
+    }
+
+    fn assign(&self, ctx: &mut AssignmentCtx<E>, x: E::BaseField) -> Result<(), ZKVMError> {
+        self.assign_instance(ctx.instance, x)
+    }
+}
+
+impl<E: ExtensionField> Gadget<E> for IsEqualConfig {
+    type Input = (Expression<E>, Expression<E>);
+    type Value = (E::BaseField, E::BaseField);
+
+    fn construct(
+        cb: &mut CircuitBuilder<E>,
+        (a, b): (Expression<E>, Expression<E>),
+    ) -> Result<Self, ZKVMError> {
+        Self::construct_circuit(cb, || "is_equal", a, b)
+    }
+
+    fn assign(
+        &self,
+        ctx: &mut AssignmentCtx<E>,
+        (a, b): (E::BaseField, E::BaseField),
+    ) -> Result<(), ZKVMError> {
+        self.assign_instance(ctx.instance, a, b)
+    }
+}
@@ -1,11 +1,34 @@
+use ff_ext::ExtensionField;
+
+use crate::{circuit_builder::CircuitBuilder, error::ZKVMError, witness::AssignmentCtx};
+
 mod div;
 mod is_lt;
 mod is_zero;
+mod range_check;
+mod selectors;
 mod signed_ext;
+mod uint256;
 
 pub use div::DivConfig;
 pub use is_lt::{
     AssertLTConfig, AssertSignedLtConfig, InnerLtConfig, IsLtConfig, SignedLtConfig, cal_lt_diff,
 };
-pub use is_zero::{IsEqualConfig, IsZeroConfig};
+pub use is_zero::{IsEqualConfig, IsZeroConfig, is_zero_witness};
+pub use range_check::RangeCheckConfig;
+pub use selectors::Selectors;
 pub use signed_ext::SignedExtendConfig;
+pub use uint256::{AssertUint256LtConfig, MulAddModConfig, assign_uint256};
+
+/// A reusable config whose construction and assignment are defined side by side, so that a
+/// circuit composing gadgets only passes their inputs around.
+pub trait Gadget<E: ExtensionField>: Sized {
+    /// Expressions the gadget is constructed on.
+    type Input;
+    /// Values of the inputs on one row.
+    type Value;
+
+    fn construct(cb: &mut CircuitBuilder<E>, input: Self::Input) -> Result<Self, ZKVMError>;
+
+    fn assign(&self, ctx: &mut AssignmentCtx<E>, value: Self::Value) -> Result<(), ZKVMError>;
+}
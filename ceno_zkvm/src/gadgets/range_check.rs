@@ -0,0 +1,107 @@
+use std::mem::MaybeUninit;
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+    tables::range_limbs,
+    witness::{AssignmentCtx, LkMultiplicity},
+};
+
+use super::Gadget;
+
+/// Assert that a value fits in an arbitrary number of bits, below the field size.
+/// The value is split into the limbs of `range_limbs`, or checked as is if one limb suffices.
+#[derive(Debug)]
+pub struct RangeCheckConfig {
+    bits: usize,
+    /// (limb, width), least significant first
+    limbs: Vec<(WitIn, usize)>,
+}
+
+impl RangeCheckConfig {
+    pub fn construct_circuit<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
+        cb: &mut CircuitBuilder<E>,
+        name_fn: N,
+        expr: Expression<E>,
+        bits: usize,
+    ) -> Result<Self, ZKVMError> {
+        assert!(bits < 64, "{bits} bits do not fit in the field");
+        cb.namespace(name_fn, |cb| {
+            let widths = range_limbs(bits);
+            if widths.len() == 1 {
+                cb.assert_bits(|| "value", expr, bits)?;
+                return Ok(RangeCheckConfig {
+                    bits,
+                    limbs: vec![],
+                });
+            }
+
+            let limbs = widths
+                .into_iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let limb = cb.create_witin(|| format!("limb_{i}"));
+                    cb.assert_bits(|| format!("limb_{i}"), limb.expr(), width)?;
+                    Ok((limb, width))
+                })
+                .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+            let mut offset = 0;
+            let recomposed = limbs
+                .iter()
+                .map(|(limb, width)| {
+                    let term = limb.expr() << offset;
+                    offset += width;
+                    term
+                })
+                .sum();
+            cb.require_equal(|| "recompose", expr, recomposed)?;
+
+            Ok(RangeCheckConfig { bits, limbs })
+        })
+    }
+
+    pub fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        lk_multiplicity: &mut LkMultiplicity,
+        value: u64,
+    ) -> Result<(), ZKVMError> {
+        if self.limbs.is_empty() {
+            lk_multiplicity.assert_bits(value, self.bits);
+            return Ok(());
+        }
+
+        let mut offset = 0;
+        for (limb, width) in &self.limbs {
+            let limb_value = (value >> offset) & ((1 << width) - 1);
+            lk_multiplicity.assert_bits(limb_value, *width);
+            set_val!(instance, limb, limb_value);
+            offset += width;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: ExtensionField> Gadget<E> for RangeCheckConfig {
+    /// (value, bits)
+    type Input = (Expression<E>, usize);
+    type Value = u64;
+
+    fn construct(
+        cb: &mut CircuitBuilder<E>,
+        (expr, bits): (Expression<E>, usize),
+    ) -> Result<Self, ZKVMError> {
+        Self::construct_circuit(cb, || "range_check", expr, bits)
+    }
+
+    fn assign(&self, ctx: &mut AssignmentCtx<E>, value: u64) -> Result<(), ZKVMError> {
+        self.assign_instance(ctx.instance, ctx.lk_multiplicity, value)
+    }
+}
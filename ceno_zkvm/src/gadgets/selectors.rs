@@ -0,0 +1,146 @@
+use std::mem::MaybeUninit;
+
+use ceno_emul::InsnKind;
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+    witness::AssignmentCtx,
+};
+
+use super::Gadget;
+
+/// One-hot flags over a set of instruction kinds, so that several opcodes can share the rows
+/// of a single circuit. Exactly one flag is set on each row.
+#[derive(Debug)]
+pub struct Selectors {
+    flags: Vec<(InsnKind, WitIn)>,
+}
+
+impl Selectors {
+    pub fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        kinds: &[InsnKind],
+    ) -> Result<Self, ZKVMError> {
+        cb.namespace(
+            || "selectors",
+            |cb| {
+                let flags = kinds
+                    .iter()
+                    .map(|&kind| {
+                        let flag = cb.create_witin(|| format!("is_{kind}"));
+                        cb.assert_bit(|| format!("is_{kind}_bit"), flag.expr())?;
+                        Ok((kind, flag))
+                    })
+                    .collect::<Result<Vec<_>, ZKVMError>>()?;
+
+                let sum = flags.iter().map(|(_, flag)| flag.expr()).sum();
+                cb.require_one(|| "exactly_one", sum)?;
+
+                Ok(Selectors { flags })
+            },
+        )
+    }
+
+    /// The flag of `kind`, if it is part of the set.
+    pub fn flag<E: ExtensionField>(&self, kind: InsnKind) -> Option<Expression<E>> {
+        self.flags
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, flag)| flag.expr())
+    }
+
+    /// The selected instruction kind, as it appears in the decode lookup.
+    pub fn kind<E: ExtensionField>(&self) -> Expression<E> {
+        self.flags
+            .iter()
+            .map(|(kind, flag)| flag.expr() * Expression::from(*kind))
+            .sum()
+    }
+
+    pub fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        kind: InsnKind,
+    ) -> Result<(), ZKVMError> {
+        if self.flags.iter().all(|(k, _)| *k != kind) {
+            return Err(ZKVMError::UnselectedKind(kind));
+        }
+        for (k, flag) in &self.flags {
+            set_val!(instance, flag, (*k == kind) as u64);
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: ExtensionField> Gadget<E> for Selectors {
+    type Input = Vec<InsnKind>;
+    type Value = InsnKind;
+
+    fn construct(cb: &mut CircuitBuilder<E>, kinds: Vec<InsnKind>) -> Result<Self, ZKVMError> {
+        Self::construct_circuit(cb, &kinds)
+    }
+
+    fn assign(&self, ctx: &mut AssignmentCtx<E>, kind: InsnKind) -> Result<(), ZKVMError> {
+        self.assign_instance(ctx.instance, kind)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::InsnKind::{ADD, SUB, XOR};
+    use goldilocks::{Goldilocks as F, GoldilocksExt2 as E};
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        error::ZKVMError,
+        scheme::mock_prover::MockProver,
+        set_val,
+        witness::RowMajorMatrix,
+    };
+
+    use super::Selectors;
+
+    #[test]
+    fn test_selectors() -> Result<(), ZKVMError> {
+        impl_test_selectors(None, &[])?;
+        impl_test_selectors(Some(&[0, 0, 0]), &["exactly_one"])?;
+        impl_test_selectors(Some(&[1, 0, 1]), &["exactly_one"])?;
+        Ok(())
+    }
+
+    /// Assign SUB, or overwrite the flags with `flags`.
+    fn impl_test_selectors(
+        flags: Option<&[u64]>,
+        expected_errors: &[&str],
+    ) -> Result<(), ZKVMError> {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let selectors = Selectors::construct_circuit(&mut cb, &[ADD, SUB, XOR])?;
+
+        let mut raw_witin = RowMajorMatrix::<F>::new(2, cb.cs.num_witin as usize);
+        for instance in raw_witin.iter_mut() {
+            selectors.assign_instance(instance, SUB)?;
+            if let Some(flags) = flags {
+                for ((_, flag), &value) in selectors.flags.iter().zip(flags) {
+                    set_val!(instance, flag, value);
+                }
+            }
+        }
+
+        MockProver::assert_with_expected_errors_raw(
+            &cb,
+            raw_witin,
+            &[],
+            expected_errors,
+            None,
+            None,
+        );
+        Ok(())
+    }
+}
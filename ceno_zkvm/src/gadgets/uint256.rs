@@ -0,0 +1,244 @@
+use std::mem::MaybeUninit;
+
+use ceno_emul::{UINT256_WORDS, Word, uint256_divrem};
+use ff_ext::ExtensionField;
+use itertools::izip;
+
+use crate::{
+    Value,
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr},
+    instructions::riscv::constants::{LIMB_BITS, UInt256, UInt512},
+    witness::LkMultiplicity,
+};
+
+/// The number of limbs of a 256-bit number.
+const UINT256_LIMBS: usize = 2 * UINT256_WORDS;
+
+/// modular multiply-add gadget
+///
+/// Prove `u * v + w = q * m + z` on 512 bits, for a 256-bit quotient `q`, that is `z` congruent
+/// to `u * v + w` modulo `m`. `z` is not reduced by this gadget, see `AssertUint256LtConfig`.
+/// Without `v` the product is `u`, and without `w` the sum is the product, so that additions,
+/// multiplications, subtractions as `z + w = u`, and divisions as `z * v = u`, are all the same
+/// gadget.
+#[derive(Debug, Clone)]
+pub struct MulAddModConfig<E: ExtensionField> {
+    /// `u * v`.
+    mul: Option<UInt512<E>>,
+    /// `u * v + w`.
+    add: Option<UInt512<E>>,
+    q: UInt256<E>,
+    q_mul_m: UInt512<E>,
+    q_mul_m_add_z: UInt512<E>,
+}
+
+impl<E: ExtensionField> MulAddModConfig<E> {
+    pub fn construct_circuit<NR: Into<String>, N: FnOnce() -> NR>(
+        circuit_builder: &mut CircuitBuilder<E>,
+        name_fn: N,
+        u: &mut UInt256<E>,
+        v: Option<&mut UInt256<E>>,
+        w: Option<&UInt256<E>>,
+        m: &mut UInt256<E>,
+        z: &UInt256<E>,
+    ) -> Result<Self, ZKVMError> {
+        circuit_builder.namespace(name_fn, |cb| {
+            let mul: Option<UInt512<E>> =
+                v.map(|v| u.mul(|| "u_mul_v", cb, v, false)).transpose()?;
+            let lhs = UInt512::from_exprs_unchecked(match &mul {
+                Some(mul) => mul.expr(),
+                None => u.expr(),
+            });
+            let add = w
+                .map(|w| {
+                    lhs.add(
+                        || "add_w",
+                        cb,
+                        &UInt512::from_exprs_unchecked(w.expr()),
+                        false,
+                    )
+                })
+                .transpose()?;
+            let lhs = add.as_ref().map_or_else(|| lhs.expr(), |add| add.expr());
+
+            let mut q = UInt256::new(|| "q", cb)?;
+            let q_mul_m = q.mul(|| "q_mul_m", cb, m, false)?;
+            let q_mul_m_add_z = q_mul_m.add(
+                || "q_mul_m_add_z",
+                cb,
+                &UInt512::from_exprs_unchecked(z.expr()),
+                false,
+            )?;
+            for (i, (lhs, rhs)) in izip!(lhs, q_mul_m_add_z.expr()).enumerate() {
+                cb.require_equal(|| format!("limb_{i}"), lhs, rhs)?;
+            }
+
+            Ok(Self {
+                mul,
+                add,
+                q,
+                q_mul_m,
+                q_mul_m_add_z,
+            })
+        })
+    }
+
+    /// Assign the quotient for operands of the shape of the circuit, with `z` congruent to
+    /// `u * v + w` modulo `m`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lkm: &mut LkMultiplicity,
+        u: &[Word; UINT256_WORDS],
+        v: Option<&[Word; UINT256_WORDS]>,
+        w: Option<&[Word; UINT256_WORDS]>,
+        m: &[Word; UINT256_WORDS],
+        z: &[Word; UINT256_WORDS],
+    ) -> Result<(), ZKVMError> {
+        let mut lhs = to_limbs(u);
+        if let (Some(mul), Some(v)) = (&self.mul, v) {
+            let u_mul_v =
+                value(&lhs, UINT256_LIMBS).mul_hi(&value(&to_limbs(v), UINT256_LIMBS), lkm, false);
+            mul.assign_mul_outcome(instance, lkm, &u_mul_v)?;
+            lhs = u_mul_v.limbs;
+        }
+        if let (Some(add), Some(w)) = (&self.add, w) {
+            let add_w = value(&lhs, 2 * UINT256_LIMBS).add(
+                &value(&to_limbs(w), 2 * UINT256_LIMBS),
+                lkm,
+                false,
+            );
+            add.assign_add_outcome(instance, &add_w);
+            lhs = add_w.limbs;
+        }
+
+        // q = (lhs - z) / m, which is exact
+        let mut diff = [0; 2 * UINT256_WORDS];
+        let mut borrow = 0;
+        for (i, word) in diff.iter_mut().enumerate() {
+            let limb = |j: usize| lhs.get(j).copied().unwrap_or(0) as i64;
+            let z = z.get(i).copied().unwrap_or(0) as i64;
+            let d = limb(2 * i) + (limb(2 * i + 1) << LIMB_BITS) - z - borrow;
+            *word = d as Word;
+            borrow = (d < 0) as i64;
+        }
+        let (q, rem) = uint256_divrem(&diff, m);
+        debug_assert!(
+            borrow == 0 && rem == [0; UINT256_WORDS],
+            "z is not congruent to u * v + w modulo m"
+        );
+        assign_uint256(&self.q, instance, lkm, &q);
+
+        let q_mul_m = value(&to_limbs(&q), UINT256_LIMBS).mul_hi(
+            &value(&to_limbs(m), UINT256_LIMBS),
+            lkm,
+            false,
+        );
+        self.q_mul_m.assign_mul_outcome(instance, lkm, &q_mul_m)?;
+        let q_mul_m_add_z = value(&q_mul_m.limbs, 2 * UINT256_LIMBS).add(
+            &value(&to_limbs(z), 2 * UINT256_LIMBS),
+            lkm,
+            false,
+        );
+        self.q_mul_m_add_z
+            .assign_add_outcome(instance, &q_mul_m_add_z);
+        Ok(())
+    }
+}
+
+/// assert less than gadget of 256-bit numbers
+///
+/// Prove `z < m` as `z + d + 1 = m`, for a 256-bit `d`.
+#[derive(Debug, Clone)]
+pub struct AssertUint256LtConfig<E: ExtensionField> {
+    /// `m - 1 - z`.
+    d: UInt256<E>,
+    z_add_d: UInt256<E>,
+    z_add_d_add_1: UInt256<E>,
+}
+
+impl<E: ExtensionField> AssertUint256LtConfig<E> {
+    pub fn construct_circuit<NR: Into<String>, N: FnOnce() -> NR>(
+        circuit_builder: &mut CircuitBuilder<E>,
+        name_fn: N,
+        z: &UInt256<E>,
+        m: &UInt256<E>,
+    ) -> Result<Self, ZKVMError> {
+        circuit_builder.namespace(name_fn, |cb| {
+            let d = UInt256::new(|| "d", cb)?;
+            let z_add_d = z.add(|| "z_add_d", cb, &d, false)?;
+            let z_add_d_add_1 =
+                z_add_d.add_const(|| "z_add_d_add_1", cb, Expression::ONE, false)?;
+            for (i, (sum, m)) in izip!(z_add_d_add_1.expr(), m.expr()).enumerate() {
+                cb.require_equal(|| format!("limb_{i}"), sum, m)?;
+            }
+            Ok(Self {
+                d,
+                z_add_d,
+                z_add_d_add_1,
+            })
+        })
+    }
+
+    pub fn assign_instance(
+        &self,
+        instance: &mut [MaybeUninit<E::BaseField>],
+        lkm: &mut LkMultiplicity,
+        z: &[Word; UINT256_WORDS],
+        m: &[Word; UINT256_WORDS],
+    ) {
+        // m - 1 - z, which does not underflow as z < m
+        let mut d = [0; UINT256_WORDS];
+        let mut borrow = 1;
+        for i in 0..UINT256_WORDS {
+            let diff = m[i] as i64 - z[i] as i64 - borrow;
+            d[i] = diff as Word;
+            borrow = (diff < 0) as i64;
+        }
+        debug_assert_eq!(borrow, 0, "z is not less than m");
+        assign_uint256(&self.d, instance, lkm, &d);
+
+        let z_add_d = value(&to_limbs(z), UINT256_LIMBS).add(
+            &value(&to_limbs(&d), UINT256_LIMBS),
+            lkm,
+            false,
+        );
+        self.z_add_d.assign_add_outcome(instance, &z_add_d);
+        let z_add_d_add_1 =
+            value(&z_add_d.limbs, UINT256_LIMBS).add(&value(&[1], UINT256_LIMBS), lkm, false);
+        self.z_add_d_add_1
+            .assign_add_outcome(instance, &z_add_d_add_1);
+    }
+}
+
+/// Assign the limbs of a range-checked `UInt256` from little-endian words.
+pub fn assign_uint256<E: ExtensionField>(
+    uint: &UInt256<E>,
+    instance: &mut [MaybeUninit<E::BaseField>],
+    lkm: &mut LkMultiplicity,
+    words: &[Word; UINT256_WORDS],
+) {
+    let limbs = to_limbs(words);
+    uint.assign_limbs(instance, &limbs);
+    for limb in limbs {
+        lkm.assert_ux::<LIMB_BITS>(limb as u64);
+    }
+}
+
+/// The 16-bit limbs of a number of words.
+fn to_limbs(words: &[Word]) -> Vec<u16> {
+    words
+        .iter()
+        .flat_map(|&word| [word as u16, (word >> LIMB_BITS) as u16])
+        .collect()
+}
+
+/// The value of `limbs`, padded with zeros to `width` limbs.
+fn value(limbs: &[u16], width: usize) -> Value<'static, u32> {
+    let mut limbs = limbs.to_vec();
+    limbs.resize(width, 0);
+    Value::from_limb_unchecked(limbs)
+}
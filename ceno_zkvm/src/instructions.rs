@@ -1,5 +1,3 @@
-use std::mem::MaybeUninit;
-
 use ceno_emul::StepRecord;
 use ff_ext::ExtensionField;
 use multilinear_extensions::util::max_usable_threads;
@@ -11,26 +9,22 @@ use rayon::{
 use crate::{
     circuit_builder::CircuitBuilder,
     error::ZKVMError,
-    scheme::constants::MIN_PAR_SIZE,
-    witness::{LkMultiplicity, RowMajorMatrix},
+    scheme::constants::MIN_BASE_FIELD_BITS,
+    witness::{AssignmentCtx, LkMultiplicity, RowMajorMatrix, WitnessPool},
 };
-use ff::Field;
 
 pub mod riscv;
 
-pub enum InstancePaddingStrategy {
-    Zero,
-    RepeatLast,
-}
-
 pub trait Instruction<E: ExtensionField> {
     type InstructionConfig: Send + Sync;
 
-    fn padding_strategy() -> InstancePaddingStrategy {
-        InstancePaddingStrategy::RepeatLast
+    fn name() -> String;
+
+    /// The bit width the base field must reach for the circuit to be sound.
+    fn min_field_bits() -> u32 {
+        MIN_BASE_FIELD_BITS
     }
 
-    fn name() -> String;
     fn construct_circuit(
         circuit_builder: &mut CircuitBuilder<E>,
     ) -> Result<Self::InstructionConfig, ZKVMError>;
@@ -38,15 +32,27 @@ pub trait Instruction<E: ExtensionField> {
     // assign single instance giving step from trace
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError>;
 
+    /// Assign the instances of `steps`, padded with zeros up to a power of two, see
+    /// `RowMajorMatrix::padding_zero`.
     fn assign_instances(
         config: &Self::InstructionConfig,
         num_witin: usize,
         steps: Vec<StepRecord>,
+    ) -> Result<(RowMajorMatrix<E::BaseField>, LkMultiplicity), ZKVMError> {
+        Self::assign_instances_in(config, num_witin, steps, &WitnessPool::default())
+    }
+
+    /// Like [`Self::assign_instances`], with the witness matrix taken from `pool`, see
+    /// `RowMajorMatrix::new_in`.
+    fn assign_instances_in(
+        config: &Self::InstructionConfig,
+        num_witin: usize,
+        steps: Vec<StepRecord>,
+        pool: &WitnessPool<E::BaseField>,
     ) -> Result<(RowMajorMatrix<E::BaseField>, LkMultiplicity), ZKVMError> {
         let nthreads = max_usable_threads();
         let num_instance_per_batch = if steps.len() > 256 {
@@ -56,51 +62,28 @@ pub trait Instruction<E: ExtensionField> {
         }
         .max(1);
         let lk_multiplicity = LkMultiplicity::default();
-        let mut raw_witin = RowMajorMatrix::<E::BaseField>::new(steps.len(), num_witin);
+        let mut raw_witin = RowMajorMatrix::<E::BaseField>::new_in(steps.len(), num_witin, pool);
         let raw_witin_iter = raw_witin.par_batch_iter_mut(num_instance_per_batch);
 
         raw_witin_iter
             .zip(steps.par_chunks(num_instance_per_batch))
-            .flat_map(|(instances, steps)| {
+            .enumerate()
+            .flat_map(|(batch, (instances, steps))| {
                 let mut lk_multiplicity = lk_multiplicity.clone();
                 instances
                     .chunks_mut(num_witin)
                     .zip(steps)
-                    .map(|(instance, step)| {
-                        Self::assign_instance(config, instance, &mut lk_multiplicity, step)
+                    .enumerate()
+                    .map(|(i, (instance, step))| {
+                        let row = batch * num_instance_per_batch + i;
+                        let mut ctx = AssignmentCtx::new(instance, &mut lk_multiplicity, row);
+                        Self::assign_instance(config, &mut ctx, step)
                     })
                     .collect::<Vec<_>>()
             })
             .collect::<Result<(), ZKVMError>>()?;
 
-        let num_padding_instances = raw_witin.num_padding_instances();
-        if num_padding_instances > 0 {
-            // Fill the padding based on strategy
-
-            let padding_instance = match Self::padding_strategy() {
-                InstancePaddingStrategy::Zero => {
-                    vec![MaybeUninit::new(E::BaseField::ZERO); num_witin]
-                }
-                InstancePaddingStrategy::RepeatLast if steps.is_empty() => {
-                    tracing::debug!("No {} steps to repeat, using zero padding", Self::name());
-                    vec![MaybeUninit::new(E::BaseField::ZERO); num_witin]
-                }
-                InstancePaddingStrategy::RepeatLast => raw_witin[steps.len() - 1].to_vec(),
-            };
-
-            let num_padding_instance_per_batch = if num_padding_instances > 256 {
-                num_padding_instances.div_ceil(nthreads)
-            } else {
-                num_padding_instances
-            };
-            raw_witin
-                .par_batch_iter_padding_mut(num_padding_instance_per_batch)
-                .with_min_len(MIN_PAR_SIZE)
-                .for_each(|row| {
-                    row.chunks_mut(num_witin)
-                        .for_each(|instance| instance.copy_from_slice(padding_instance.as_slice()));
-                });
-        }
+        raw_witin.padding_zero();
 
         Ok((raw_witin, lk_multiplicity))
     }
@@ -2,7 +2,8 @@ use ceno_emul::InsnKind;
 
 mod rv32im;
 pub use rv32im::{
-    DummyExtraConfig, Rv32imConfig,
+    DummyExtraConfig, Rv32imConfig, UNSUPPORTED_INSN_KINDS, UnsupportedInsn,
+    find_unsupported_instructions,
     mmu::{MemPadder, MmuConfig},
 };
 
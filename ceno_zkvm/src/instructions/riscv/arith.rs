@@ -6,9 +6,8 @@ use ff_ext::ExtensionField;
 use super::{RIVInstruction, constants::UInt, r_insn::RInstructionConfig};
 use crate::{
     circuit_builder::CircuitBuilder, error::ZKVMError, instructions::Instruction, uint::Value,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
-use core::mem::MaybeUninit;
 
 /// This config handles R-Instructions that represent registers values as 2 * u16.
 #[derive(Debug)]
@@ -88,18 +87,17 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ArithInstruction<E
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         config
             .r_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         let rs2_read = Value::new_unchecked(step.rs2().unwrap().value);
         config
             .rs2_read
-            .assign_limbs(instance, rs2_read.as_u16_limbs());
+            .assign_limbs(ctx.instance, rs2_read.as_u16_limbs());
 
         match I::INST_KIND {
             InsnKind::ADD => {
@@ -107,19 +105,23 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ArithInstruction<E
                 let rs1_read = Value::new_unchecked(step.rs1().unwrap().value);
                 config
                     .rs1_read
-                    .assign_limbs(instance, rs1_read.as_u16_limbs());
-                let result = rs1_read.add(&rs2_read, lk_multiplicity, true);
-                config.rd_written.assign_carries(instance, &result.carries);
+                    .assign_limbs(ctx.instance, rs1_read.as_u16_limbs());
+                let result = rs1_read.add(&rs2_read, ctx.lk_multiplicity, true);
+                config
+                    .rd_written
+                    .assign_carries(ctx.instance, &result.carries);
             }
 
             InsnKind::SUB => {
                 // rs1_read = rd_written + rs2_read
-                let rd_written = Value::new(step.rd().unwrap().value.after, lk_multiplicity);
+                let rd_written = Value::new(step.rd().unwrap().value.after, ctx.lk_multiplicity);
                 config
                     .rd_written
-                    .assign_limbs(instance, rd_written.as_u16_limbs());
-                let result = rs2_read.add(&rd_written, lk_multiplicity, true);
-                config.rs1_read.assign_carries(instance, &result.carries);
+                    .assign_limbs(ctx.instance, rd_written.as_u16_limbs());
+                let result = rs2_read.add(&rd_written, ctx.lk_multiplicity, true);
+                config
+                    .rs1_read
+                    .assign_carries(ctx.instance, &result.carries);
             }
 
             _ => unreachable!("Unsupported instruction kind"),
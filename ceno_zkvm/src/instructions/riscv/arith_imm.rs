@@ -1,11 +1,11 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::marker::PhantomData;
 
 use ceno_emul::StepRecord;
 use ff_ext::ExtensionField;
 
 use crate::{
     Value, circuit_builder::CircuitBuilder, error::ZKVMError, instructions::Instruction,
-    tables::InsnRecord, witness::LkMultiplicity,
+    tables::InsnRecord, witness::AssignmentCtx,
 };
 
 use super::{RIVInstruction, constants::UInt, i_insn::IInstructionConfig};
@@ -57,26 +57,25 @@ impl<E: ExtensionField> Instruction<E> for AddiInstruction<E> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         let rs1_read = Value::new_unchecked(step.rs1().unwrap().value);
         let imm = Value::new(
             InsnRecord::imm_internal(&step.insn()) as u32,
-            lk_multiplicity,
+            ctx.lk_multiplicity,
         );
 
-        let result = rs1_read.add(&imm, lk_multiplicity, true);
+        let result = rs1_read.add(&imm, ctx.lk_multiplicity, true);
 
-        config.rs1_read.assign_value(instance, rs1_read);
-        config.imm.assign_value(instance, imm);
+        config.rs1_read.assign_value(ctx.instance, rs1_read);
+        config.imm.assign_value(ctx.instance, imm);
 
-        config.rd_written.assign_add_outcome(instance, &result);
+        config.rd_written.assign_add_outcome(ctx.instance, &result);
 
         config
             .i_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
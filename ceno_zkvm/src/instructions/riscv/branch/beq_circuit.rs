@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::marker::PhantomData;
 
 use ceno_emul::{InsnKind, StepRecord};
 use ff_ext::ExtensionField;
@@ -8,12 +8,12 @@ use crate::{
     circuit_builder::CircuitBuilder,
     error::ZKVMError,
     expression::Expression,
-    gadgets::IsEqualConfig,
+    gadgets::{Gadget, IsEqualConfig},
     instructions::{
         Instruction,
         riscv::{RIVInstruction, b_insn::BInstructionConfig, constants::UInt},
     },
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 
 pub struct BeqConfig<E: ExtensionField> {
@@ -72,28 +72,29 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for BeqCircuit<E, I> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         config
             .b_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         let rs1_read = step.rs1().unwrap().value;
         config
             .rs1_read
-            .assign_limbs(instance, Value::new_unchecked(rs1_read).as_u16_limbs());
+            .assign_limbs(ctx.instance, Value::new_unchecked(rs1_read).as_u16_limbs());
 
         let rs2_read = step.rs2().unwrap().value;
         config
             .rs2_read
-            .assign_limbs(instance, Value::new_unchecked(rs2_read).as_u16_limbs());
-
-        config.equal.assign_instance(
-            instance,
-            E::BaseField::from(rs2_read as u64),
-            E::BaseField::from(rs1_read as u64),
+            .assign_limbs(ctx.instance, Value::new_unchecked(rs2_read).as_u16_limbs());
+
+        config.equal.assign(
+            ctx,
+            (
+                E::BaseField::from(rs2_read as u64),
+                E::BaseField::from(rs1_read as u64),
+            ),
         )?;
 
         Ok(())
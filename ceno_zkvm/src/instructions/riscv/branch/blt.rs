@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::marker::PhantomData;
 
 use ff_ext::ExtensionField;
 
@@ -12,7 +12,7 @@ use crate::{
         Instruction,
         riscv::{RIVInstruction, b_insn::BInstructionConfig, constants::UInt},
     },
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{InsnKind, SWord};
 
@@ -66,24 +66,27 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for BltCircuit<E, I> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &ceno_emul::StepRecord,
     ) -> Result<(), ZKVMError> {
         let rs1 = Value::new_unchecked(step.rs1().unwrap().value);
         let rs2 = Value::new_unchecked(step.rs2().unwrap().value);
-        config.read_rs1.assign_limbs(instance, rs1.as_u16_limbs());
-        config.read_rs2.assign_limbs(instance, rs2.as_u16_limbs());
+        config
+            .read_rs1
+            .assign_limbs(ctx.instance, rs1.as_u16_limbs());
+        config
+            .read_rs2
+            .assign_limbs(ctx.instance, rs2.as_u16_limbs());
         config.signed_lt.assign_instance(
-            instance,
-            lk_multiplicity,
+            ctx.instance,
+            ctx.lk_multiplicity,
             step.rs1().unwrap().value as SWord,
             step.rs2().unwrap().value as SWord,
         )?;
 
         config
             .b_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
@@ -2,6 +2,15 @@ use crate::uint::UIntLimbs;
 pub use ceno_emul::PC_STEP_SIZE;
 
 pub const ECALL_HALT_OPCODE: [usize; 2] = [0x00_00, 0x00_00];
+pub const ECALL_SHA256_OPCODE: [usize; 2] = [0x00_06, 0x00_00];
+pub const ECALL_KECCAK_OPCODE: [usize; 2] = [0x00_07, 0x00_00];
+pub const ECALL_UINT256_ADDMOD_OPCODE: [usize; 2] = [0x00_08, 0x00_00];
+pub const ECALL_UINT256_MULMOD_OPCODE: [usize; 2] = [0x00_09, 0x00_00];
+pub const ECALL_SECP256K1_ADD_OPCODE: [usize; 2] = [0x00_0A, 0x00_00];
+pub const ECALL_SECP256K1_DOUBLE_OPCODE: [usize; 2] = [0x00_0B, 0x00_00];
+pub const ECALL_ED25519_ADD_OPCODE: [usize; 2] = [0x00_0C, 0x00_00];
+pub const ECALL_POSEIDON2_OPCODE: [usize; 2] = [0x00_0D, 0x00_00];
+pub const ECALL_COMMIT_DIGEST_OPCODE: [usize; 2] = [0x00_0F, 0x00_00];
 pub const EXIT_PC: usize = 0;
 pub const EXIT_CODE_IDX: usize = 0;
 
@@ -10,6 +19,17 @@ pub const INIT_CYCLE_IDX: usize = 3;
 pub const END_PC_IDX: usize = 4;
 pub const END_CYCLE_IDX: usize = 5;
 pub const PUBLIC_IO_IDX: usize = 6;
+/// The registers at the start, then at the end of a segment, one public value per limb, see
+/// `NonVolatileTable::STATE_IDX`. They are empty outside of segments.
+pub const REG_STATE_IDX: usize = PUBLIC_IO_IDX + 1;
+/// The static memory at the start, then at the end of a segment.
+pub const MEM_STATE_IDX: usize = REG_STATE_IDX + 2 * UINT_LIMBS;
+/// The digest of the committed output takes `OUTPUT_DIGEST_WORDS` public values from there, as
+/// read by `CommitDigestInstruction`.
+pub const OUTPUT_DIGEST_IDX: usize = MEM_STATE_IDX + 2;
+pub const OUTPUT_DIGEST_WORDS: usize = 8;
+/// Instance columns of a circuit are indexed after the public values.
+pub const NUM_PUBLIC_VALUES: usize = OUTPUT_DIGEST_IDX + OUTPUT_DIGEST_WORDS;
 
 pub const LIMB_BITS: usize = 16;
 pub const LIMB_MASK: u32 = 0xFFFF;
@@ -22,4 +42,7 @@ pub type UInt<E> = UIntLimbs<BIT_WIDTH, LIMB_BITS, E>;
 pub type UIntMul<E> = UIntLimbs<{ 2 * BIT_WIDTH }, LIMB_BITS, E>;
 /// use UInt<x> for x bits limb size
 pub type UInt8<E> = UIntLimbs<BIT_WIDTH, 8, E>;
+/// The operands of the 256-bit modular arithmetic, and their products.
+pub type UInt256<E> = UIntLimbs<256, LIMB_BITS, E>;
+pub type UInt512<E> = UIntLimbs<512, LIMB_BITS, E>;
 pub const UINT_LIMBS: usize = BIT_WIDTH.div_ceil(LIMB_BITS);
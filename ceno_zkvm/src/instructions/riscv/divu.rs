@@ -14,9 +14,8 @@ use crate::{
     gadgets::{IsLtConfig, IsZeroConfig},
     instructions::Instruction,
     uint::Value,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
-use core::mem::MaybeUninit;
 use std::marker::PhantomData;
 
 pub struct ArithConfig<E: ExtensionField> {
@@ -124,42 +123,50 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ArithInstruction<E
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lkm: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
-        let rs1 = step.rs1().unwrap().value;
         let rs2 = step.rs2().unwrap().value;
         let rd = step.rd().unwrap().value.after;
 
         // dividend = divisor * outcome + r
         let divisor = Value::new_unchecked(rs2);
-        let outcome = Value::new(rd, lkm);
+        let outcome = Value::new(rd, ctx.lk_multiplicity);
 
-        let r = Value::new(if rs2 == 0 { 0 } else { rs1 % rs2 }, lkm);
+        // the remainder of a zero divisor is the dividend, as the quotient is all ones
+        let r = Value::new(step.div_rem().unwrap().remainder, ctx.lk_multiplicity);
 
         // assignment
-        config.r_insn.assign_instance(instance, lkm, step)?;
+        config
+            .r_insn
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
         config
             .divisor
-            .assign_limbs(instance, divisor.as_u16_limbs());
+            .assign_limbs(ctx.instance, divisor.as_u16_limbs());
         config
             .outcome
-            .assign_limbs(instance, outcome.as_u16_limbs());
+            .assign_limbs(ctx.instance, outcome.as_u16_limbs());
 
-        let (dividend, inter_mul_value) = divisor.mul_add(&outcome, &r, lkm, true);
-        config
-            .inter_mul_value
-            .assign_mul_outcome(instance, lkm, &inter_mul_value)?;
+        let (dividend, inter_mul_value) = divisor.mul_add(&outcome, &r, ctx.lk_multiplicity, true);
+        config.inter_mul_value.assign_mul_outcome(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            &inter_mul_value,
+        )?;
 
-        config.dividend.assign_add_outcome(instance, &dividend);
-        config.remainder.assign_limbs(instance, r.as_u16_limbs());
+        config.dividend.assign_add_outcome(ctx.instance, &dividend);
         config
-            .is_zero
-            .assign_instance(instance, divisor.as_u64().into())?;
+            .remainder
+            .assign_limbs(ctx.instance, r.as_u16_limbs());
         config
-            .remainder_lt
-            .assign_instance(instance, lkm, r.as_u64(), divisor.as_u64())?;
+            .is_zero
+            .assign_instance(ctx.instance, divisor.as_u64().into())?;
+        config.remainder_lt.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            r.as_u64(),
+            divisor.as_u64(),
+        )?;
 
         Ok(())
     }
@@ -172,8 +179,6 @@ mod test {
 
         use ceno_emul::{Change, InsnKind, StepRecord, Word, encode_rv32};
         use goldilocks::GoldilocksExt2;
-        use itertools::Itertools;
-        use multilinear_extensions::mle::IntoMLEs;
         use rand::Rng;
 
         use crate::{
@@ -235,14 +240,9 @@ mod test {
                 .unwrap();
 
             let expected_errors: &[_] = if is_ok { &[] } else { &[name] };
-            MockProver::assert_with_expected_errors(
+            MockProver::assert_with_expected_errors_raw(
                 &cb,
-                &raw_witin
-                    .de_interleaving()
-                    .into_mles()
-                    .into_iter()
-                    .map(|v| v.into())
-                    .collect_vec(),
+                raw_witin,
                 &[insn_code],
                 expected_errors,
                 None,
@@ -17,7 +17,7 @@ use crate::{
     tables::InsnRecord,
     uint::Value,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::{AssignmentCtx, LkMultiplicity},
 };
 use core::mem::MaybeUninit;
 
@@ -69,11 +69,10 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for DummyInstruction<E
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
-        config.assign_instance(instance, lk_multiplicity, step)
+        config.assign_instance(ctx.instance, ctx.lk_multiplicity, step)
     }
 }
 
@@ -1,7 +1,23 @@
+mod bits;
+mod commit_digest;
+mod curve;
+mod ed25519;
 mod halt;
+mod keccak;
+mod poseidon2;
+mod secp256k1;
+mod sha256;
+mod uint256;
 
 use ceno_emul::InsnKind;
+pub use commit_digest::CommitDigestInstruction;
+pub use ed25519::Ed25519AddInstruction;
 pub use halt::HaltInstruction;
+pub use keccak::KeccakInstruction;
+pub use poseidon2::Poseidon2Instruction;
+pub use secp256k1::{Secp256k1AddInstruction, Secp256k1DoubleInstruction};
+pub use sha256::Sha256Instruction;
+pub use uint256::{Uint256AddModInstruction, Uint256MulModInstruction};
 
 use super::{RIVInstruction, dummy::DummyInstruction};
 
@@ -0,0 +1,60 @@
+//! Bitwise helpers of the precompiles, whose words are decomposed into boolean witnesses.
+
+use std::{array, mem::MaybeUninit};
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    set_val,
+    witness::LkMultiplicity,
+};
+
+/// Boolean witnesses, checked by `Bits8` lookups.
+pub(super) fn bit_witins<E: ExtensionField, const N: usize>(
+    cb: &mut CircuitBuilder<E>,
+    name: String,
+) -> Result<[WitIn; N], ZKVMError> {
+    let bits = array::from_fn(|i| cb.create_witin(|| format!("{name}_{i}")));
+    cb.assert_bit_batch(|| name, &exprs(&bits))?;
+    Ok(bits)
+}
+
+/// `a ^ b` for bits `a` and `b`.
+pub(super) fn xor<E: ExtensionField>(a: &Expression<E>, b: &Expression<E>) -> Expression<E> {
+    a + b - 2 * a * b
+}
+
+/// The number of which `bits` are the binary digits, least significant first.
+pub(super) fn value<E: ExtensionField>(bits: &[Expression<E>]) -> Expression<E> {
+    bits.iter().enumerate().map(|(i, bit)| bit << i).sum()
+}
+
+pub(super) fn exprs<E: ExtensionField>(witins: &[WitIn]) -> Vec<Expression<E>> {
+    witins.iter().map(|witin| witin.expr()).collect()
+}
+
+/// Assign the binary digits of `value` to `bits`.
+pub(super) fn set_bits<F: SmallField>(instance: &mut [MaybeUninit<F>], bits: &[WitIn], value: u64) {
+    for (i, bit) in bits.iter().enumerate() {
+        set_val!(instance, bit, (value >> i) & 1);
+    }
+}
+
+/// Assign the binary digits of `value` to `bits` of [`bit_witins`], and track their lookups.
+pub(super) fn assign_bits<F: SmallField>(
+    instance: &mut [MaybeUninit<F>],
+    lk_multiplicity: &mut LkMultiplicity,
+    bits: &[WitIn],
+    value: u64,
+) {
+    set_bits(instance, bits, value);
+    lk_multiplicity.assert_bit_batch(
+        &(0..bits.len())
+            .map(|i| (value >> i) & 1)
+            .collect::<Vec<_>>(),
+    );
+}
@@ -0,0 +1,196 @@
+//! The ecall COMMIT_DIGEST: one row reads the digest of the committed output, see
+//! `Platform::ecall_commit_digest`.
+//!
+//! The words read from memory are the public values of the digest, so that the proof binds the
+//! digest which the guest computed to its public values. The bytes committed with the ecall
+//! COMMIT are not constrained: the guest hashes them itself, see `ceno_rt::halt`.
+
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{Platform, StepRecord, Tracer, WORD_SIZE};
+use ff_ext::ExtensionField;
+
+use crate::{
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{ToExpr, WitIn},
+    gadgets::AssertLTConfig,
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_COMMIT_DIGEST_OPCODE, OUTPUT_DIGEST_WORDS, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    witness::AssignmentCtx,
+};
+
+pub struct CommitDigestConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    /// The accesses to the words of the digest.
+    mem_ops: Vec<(WitIn, AssertLTConfig)>,
+}
+
+pub struct CommitDigestInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for CommitDigestInstruction<E> {
+    type InstructionConfig = CommitDigestConfig<E>;
+
+    fn name() -> String {
+        "ECALL_COMMIT_DIGEST".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let digest = cb.query_output_digest()?;
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [
+                ECALL_COMMIT_DIGEST_OPCODE[0].into(),
+                ECALL_COMMIT_DIGEST_OPCODE[1].into(),
+            ],
+            None,
+            None,
+        )?;
+
+        // read the address of the digest from arg0 (X10 register)
+        let ptr = UInt::new_unchecked(|| "ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ptr.register_expr(),
+        )?;
+
+        // read the digest, which must be the public one
+        let mem_ts = ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM;
+        let mut mem_ops = Vec::with_capacity(OUTPUT_DIGEST_WORDS);
+        for (i, word) in digest.iter().enumerate() {
+            let prev_ts = cb.create_witin(|| format!("prev_ts_digest_{i}"));
+            let (_, lt_cfg) = cb.memory_read(
+                || format!("read digest_{i}"),
+                &(ptr.address_expr() + i * WORD_SIZE),
+                prev_ts.expr(),
+                mem_ts.clone(),
+                word.expr(),
+            )?;
+            mem_ops.push((prev_ts, lt_cfg));
+        }
+
+        Ok(CommitDigestConfig {
+            ecall_cfg,
+            ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            mem_ops,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let ops = step.syscall_ops();
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_COMMIT_DIGEST_OPCODE[0] + (ECALL_COMMIT_DIGEST_OPCODE[1] << 16)) as u32
+        );
+        assert_eq!(ops.len(), config.mem_ops.len());
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x10 = step.rs2().unwrap();
+        config
+            .ptr
+            .assign_value(ctx.instance, Value::new_unchecked(x10.value));
+        set_val!(ctx.instance, config.prev_x10_ts, x10.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            x10.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        for ((prev_ts, lt_cfg), op) in config.mem_ops.iter().zip(ops) {
+            set_val!(ctx.instance, prev_ts, op.previous_cycle);
+            lt_cfg.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::{
+        CENO_PLATFORM, InsnKind, Platform, Program, SHA256_STATE_WORDS, VMState, encode_rv32,
+        sha256_digest,
+    };
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::Instruction,
+        scheme::mock_prover::MockProver,
+    };
+
+    use super::CommitDigestInstruction;
+
+    #[test]
+    fn test_ecall_commit_digest() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "commit_digest",
+                CommitDigestInstruction::<GoldilocksExt2>::construct_circuit,
+            )
+            .unwrap();
+
+        // Attest the digest of no output, at the start of RAM.
+        let ptr = CENO_PLATFORM.ram.start;
+        let instructions = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 5, Platform::ecall_commit_digest()),
+            encode_rv32(InsnKind::LUI, 0, 0, 10, ptr),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        for (i, word) in sha256_digest(&[]).into_iter().enumerate() {
+            vm.init_memory((ptr + 4 * i as u32).into(), word);
+        }
+        let steps = vm
+            .iter_until_halt()
+            .take(instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let step = steps.last().unwrap();
+        assert_eq!(step.syscall_ops().len(), SHA256_STATE_WORDS);
+
+        let (raw_witin, lkm) = CommitDigestInstruction::<GoldilocksExt2>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            vec![step.clone()],
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &instructions, None, Some(lkm));
+    }
+}
@@ -0,0 +1,394 @@
+//! The precompiles of elliptic curve operations: one row computes a point from points in affine
+//! coordinates, e.g. `p + q` on secp256k1, see `Platform::ecall_secp256k1_add`.
+//!
+//! The row runs a short program of operations modulo the prime `p` of the field, see `FieldOp`,
+//! each proven by a `MulAddModConfig` on 256-bit `UInt`s. The intermediate values need not be
+//! reduced, only the coordinates of the result are, so that they are those written by the VM.
+//! Every divisor is proven invertible, so that the formulas hold only where the VM computes
+//! them. The operands being reduced is checked by the VM only, and neither checks that they are
+//! on the curve.
+
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{
+    Platform, StepRecord, Tracer, UINT256_WORDS, WORD_SIZE, Word, uint256_addmod, uint256_invmod,
+    uint256_mulmod, uint256_submod,
+};
+use ff_ext::ExtensionField;
+use itertools::izip;
+
+use super::uint256::word;
+use crate::{
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{AssertLTConfig, AssertUint256LtConfig, MulAddModConfig, assign_uint256},
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{LIMB_BITS, UInt, UInt256},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    witness::AssignmentCtx,
+};
+
+/// An operation modulo `p` on the values of a row, by index: the coordinates of the points
+/// first, then the constants, then the result of each operation of the program in order.
+#[derive(Clone, Copy, Debug)]
+pub enum FieldOp {
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Div(usize, usize),
+}
+
+impl FieldOp {
+    /// The operands `(u, v, w, z)` of the `MulAddModConfig`s which prove the operation, for its
+    /// result `r`, and for a division the inverse `inv` of the divisor.
+    #[allow(clippy::type_complexity)]
+    fn mul_add_mods<T: Clone>(
+        self,
+        values: &[T],
+        r: &T,
+        inv: Option<&T>,
+        one: &T,
+    ) -> Vec<(T, Option<T>, Option<T>, T)> {
+        let value = |i: usize| values[i].clone();
+        match self {
+            FieldOp::Add(a, b) => vec![(value(a), None, Some(value(b)), r.clone())],
+            FieldOp::Sub(a, b) => vec![(r.clone(), None, Some(value(b)), value(a))],
+            FieldOp::Mul(a, b) => vec![(value(a), Some(value(b)), None, r.clone())],
+            // r * b = a, and inv * b = 1 so that b is not 0
+            FieldOp::Div(a, b) => vec![
+                (r.clone(), Some(value(b)), None, value(a)),
+                (inv.unwrap().clone(), Some(value(b)), None, one.clone()),
+            ],
+        }
+    }
+}
+
+pub trait CurveOp {
+    const NAME: &'static str;
+    const OPCODE: [usize; 2];
+    /// The prime of the field.
+    const MODULUS: [Word; UINT256_WORDS];
+    /// The number of points read, the first one being overwritten by the result.
+    const POINTS: usize;
+    /// The constants of the program, e.g. the coefficients of the curve.
+    const CONSTANTS: &'static [[Word; UINT256_WORDS]];
+    const PROGRAM: &'static [FieldOp];
+    /// The values of the coordinates of the result.
+    const RESULT: [usize; 2];
+}
+
+pub struct CurveConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    /// The accesses to the words of the points.
+    mem_ops: Vec<(WitIn, AssertLTConfig)>,
+    /// The prime `p`, as witnesses for the multiplications.
+    p: UInt256<E>,
+    /// The coordinates of the points, the constants, then the result of each operation.
+    values: Vec<UInt256<E>>,
+    /// The inverses of the divisors of the divisions.
+    inverses: Vec<UInt256<E>>,
+    mul_add_mods: Vec<MulAddModConfig<E>>,
+    result_lt_p: Vec<AssertUint256LtConfig<E>>,
+}
+
+pub struct CurveInstruction<E, I>(PhantomData<(E, I)>);
+
+impl<E: ExtensionField, I: CurveOp> Instruction<E> for CurveInstruction<E, I> {
+    type InstructionConfig = CurveConfig<E>;
+
+    fn name() -> String {
+        I::NAME.into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [I::OPCODE[0].into(), I::OPCODE[1].into()],
+            None,
+            None,
+        )?;
+
+        // read the address of the points from arg0 (X10 register)
+        let ptr = UInt::new_unchecked(|| "ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ptr.register_expr(),
+        )?;
+
+        let mut p = constant(cb, || "p", &I::MODULUS)?;
+
+        // run the program
+        let mut values = (0..2 * I::POINTS)
+            .map(|i| UInt256::new(|| format!("value_{i}"), cb))
+            .collect::<Result<Vec<_>, _>>()?;
+        for words in I::CONSTANTS {
+            let i = values.len();
+            values.push(constant(cb, || format!("value_{i}"), words)?);
+        }
+        let one = UInt256::from_exprs_unchecked(vec![Expression::ONE]);
+        let mut inverses = Vec::new();
+        let mut mul_add_mods = Vec::new();
+        for &op in I::PROGRAM {
+            let k = values.len();
+            let r = UInt256::new(|| format!("value_{k}"), cb)?;
+            let inv = match op {
+                FieldOp::Div(..) => Some(UInt256::new(|| format!("inv_{k}"), cb)?),
+                _ => None,
+            };
+            for (j, (mut u, mut v, w, z)) in op
+                .mul_add_mods(&values, &r, inv.as_ref(), &one)
+                .into_iter()
+                .enumerate()
+            {
+                mul_add_mods.push(MulAddModConfig::construct_circuit(
+                    cb,
+                    || format!("op_{k}_{j}"),
+                    &mut u,
+                    v.as_mut(),
+                    w.as_ref(),
+                    &mut p,
+                    &z,
+                )?);
+            }
+            inverses.extend(inv);
+            values.push(r);
+        }
+        let result_lt_p = I::RESULT
+            .iter()
+            .map(|&i| {
+                AssertUint256LtConfig::construct_circuit(
+                    cb,
+                    || format!("value_{i}_lt_p"),
+                    &values[i],
+                    &p,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // write the result over the first point, and read the others
+        let mem_ts = ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM;
+        let mut mem_ops = Vec::with_capacity(2 * I::POINTS * UINT256_WORDS);
+        let limbs = values.iter().map(|value| value.expr()).collect::<Vec<_>>();
+        for (j, coordinate) in limbs[..2 * I::POINTS].iter().enumerate() {
+            for i in 0..UINT256_WORDS {
+                let word_idx = j * UINT256_WORDS + i;
+                let prev_ts = cb.create_witin(|| format!("prev_ts_{word_idx}"));
+                let addr = ptr.address_expr() + word_idx * WORD_SIZE;
+                let (_, lt_cfg) = if j < 2 {
+                    cb.memory_write(
+                        || format!("write {word_idx}"),
+                        &addr,
+                        prev_ts.expr(),
+                        mem_ts.clone(),
+                        word(coordinate, i),
+                        word(&limbs[I::RESULT[j]], i),
+                    )?
+                } else {
+                    cb.memory_read(
+                        || format!("read {word_idx}"),
+                        &addr,
+                        prev_ts.expr(),
+                        mem_ts.clone(),
+                        word(coordinate, i),
+                    )?
+                };
+                mem_ops.push((prev_ts, lt_cfg));
+            }
+        }
+
+        Ok(CurveConfig {
+            ecall_cfg,
+            ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            mem_ops,
+            p,
+            values,
+            inverses,
+            mul_add_mods,
+            result_lt_p,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let ops = step.syscall_ops();
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (I::OPCODE[0] + (I::OPCODE[1] << 16)) as u32
+        );
+        assert_eq!(ops.len(), config.mem_ops.len());
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x10 = step.rs2().unwrap();
+        config
+            .ptr
+            .assign_value(ctx.instance, Value::new_unchecked(x10.value));
+        set_val!(ctx.instance, config.prev_x10_ts, x10.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            x10.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        for ((prev_ts, lt_cfg), op) in config.mem_ops.iter().zip(ops) {
+            set_val!(ctx.instance, prev_ts, op.previous_cycle);
+            lt_cfg.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        // run the program
+        let p = &I::MODULUS;
+        let mut values = (0..2 * I::POINTS)
+            .map(|j| std::array::from_fn(|i| ops[j * UINT256_WORDS + i].value.before))
+            .chain(I::CONSTANTS.iter().copied())
+            .collect::<Vec<[Word; UINT256_WORDS]>>();
+        let mut one = [0; UINT256_WORDS];
+        one[0] = 1;
+        let mut inverses = Vec::new();
+        let mut mul_add_mods = config.mul_add_mods.iter();
+        for &op in I::PROGRAM {
+            let (r, inv) = match op {
+                FieldOp::Add(a, b) => (uint256_addmod(&values[a], &values[b], p), None),
+                FieldOp::Sub(a, b) => (uint256_submod(&values[a], &values[b], p), None),
+                FieldOp::Mul(a, b) => (uint256_mulmod(&values[a], &values[b], p), None),
+                FieldOp::Div(a, b) => {
+                    let inv = uint256_invmod(&values[b], p);
+                    (uint256_mulmod(&values[a], &inv, p), Some(inv))
+                }
+            };
+            for (u, v, w, z) in op.mul_add_mods(&values, &r, inv.as_ref(), &one) {
+                mul_add_mods.next().unwrap().assign_instance(
+                    ctx.instance,
+                    ctx.lk_multiplicity,
+                    &u,
+                    v.as_ref(),
+                    w.as_ref(),
+                    p,
+                    &z,
+                )?;
+            }
+            inverses.extend(inv);
+            values.push(r);
+        }
+        debug_assert!(
+            I::RESULT
+                .iter()
+                .flat_map(|&i| values[i])
+                .eq(ops[..2 * UINT256_WORDS].iter().map(|op| op.value.after))
+        );
+
+        assign_uint256(&config.p, ctx.instance, ctx.lk_multiplicity, p);
+        for (uint, words) in
+            izip!(&config.values, &values).chain(izip!(&config.inverses, &inverses))
+        {
+            assign_uint256(uint, ctx.instance, ctx.lk_multiplicity, words);
+        }
+        for (lt_p, &i) in izip!(&config.result_lt_p, &I::RESULT) {
+            lt_p.assign_instance(ctx.instance, ctx.lk_multiplicity, &values[i], p);
+        }
+
+        Ok(())
+    }
+}
+
+/// A `UInt256` constrained to the constant `words`, as witnesses for the multiplications.
+fn constant<E: ExtensionField, NR: Into<String>, N: FnOnce() -> NR>(
+    cb: &mut CircuitBuilder<E>,
+    name_fn: N,
+    words: &[Word; UINT256_WORDS],
+) -> Result<UInt256<E>, ZKVMError> {
+    cb.namespace(name_fn, |cb| {
+        let uint = UInt256::new(|| "uint", cb)?;
+        let limbs = words
+            .iter()
+            .flat_map(|&word| [word & 0xFFFF, word >> LIMB_BITS]);
+        for (i, (limb, value)) in izip!(uint.expr(), limbs).enumerate() {
+            cb.require_equal(|| format!("limb_{i}"), limb, Expression::from(value))?;
+        }
+        Ok(uint)
+    })
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use ceno_emul::{CENO_PLATFORM, InsnKind, Program, StepRecord, VMState, encode_rv32};
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::Instruction,
+        scheme::mock_prover::MockProver,
+    };
+
+    /// Run the ecall on the words of `points`, at the start of RAM.
+    fn run_ecall(ecall: u32, points: &[u32]) -> (Vec<u32>, StepRecord) {
+        let ptr = CENO_PLATFORM.ram.start;
+        let instructions = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 5, ecall),
+            encode_rv32(InsnKind::LUI, 0, 0, 10, ptr),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        for (i, &word) in points.iter().enumerate() {
+            vm.init_memory((ptr + 4 * i as u32).into(), word);
+        }
+        let steps = vm
+            .iter_until_halt()
+            .take(instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        (instructions, steps.last().unwrap().clone())
+    }
+
+    pub(crate) fn test_ecall<I: Instruction<GoldilocksExt2>>(
+        ecall: u32,
+        points: &[u32],
+        expected: [u32; 16],
+    ) {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb.namespace(|| "curve", I::construct_circuit).unwrap();
+
+        let (instructions, step) = run_ecall(ecall, points);
+        let result = step.syscall_ops()[..16]
+            .iter()
+            .map(|op| op.value.after)
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        let (raw_witin, lkm) =
+            I::assign_instances(&config, cb.cs.num_witin as usize, vec![step]).unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &instructions, None, Some(lkm));
+    }
+}
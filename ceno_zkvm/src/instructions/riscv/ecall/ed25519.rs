@@ -0,0 +1,119 @@
+//! The precompile of the group law of ed25519: one row computes `p + q` for points of its twisted
+//! Edwards curve in affine coordinates, see `Platform::ecall_ed25519_add`.
+//!
+//! With `t = d x1 x2 y1 y2`, the result is `x3 = (x1 y2 + y1 x2) / (1 + t)` and
+//! `y3 = (y1 y2 + x1 x2) / (1 - t)`. The law is complete, so that the same row doubles.
+
+use ceno_emul::{ED25519_D, ED25519_P, UINT256_WORDS, Word};
+
+use super::curve::{CurveInstruction, CurveOp, FieldOp};
+use crate::instructions::riscv::constants::ECALL_ED25519_ADD_OPCODE;
+
+pub struct AddOp;
+impl CurveOp for AddOp {
+    const NAME: &'static str = "ECALL_ED25519_ADD";
+    const OPCODE: [usize; 2] = ECALL_ED25519_ADD_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = ED25519_P;
+    const POINTS: usize = 2;
+    const CONSTANTS: &'static [[Word; UINT256_WORDS]] = &[[1, 0, 0, 0, 0, 0, 0, 0], ED25519_D];
+    // From (x1, y1, x2, y2, 1, d).
+    const PROGRAM: &'static [FieldOp] = &[
+        FieldOp::Mul(0, 3),   // 6: x1 y2
+        FieldOp::Mul(1, 2),   // 7: y1 x2
+        FieldOp::Mul(1, 3),   // 8: y1 y2
+        FieldOp::Mul(0, 2),   // 9: x1 x2
+        FieldOp::Mul(6, 7),   // 10: x1 x2 y1 y2
+        FieldOp::Mul(5, 10),  // 11: t
+        FieldOp::Add(6, 7),   // 12: x1 y2 + y1 x2
+        FieldOp::Add(8, 9),   // 13: y1 y2 + x1 x2
+        FieldOp::Add(4, 11),  // 14: 1 + t
+        FieldOp::Sub(4, 11),  // 15: 1 - t
+        FieldOp::Div(12, 14), // 16: x3
+        FieldOp::Div(13, 15), // 17: y3
+    ];
+    const RESULT: [usize; 2] = [16, 17];
+}
+pub type Ed25519AddInstruction<E> = CurveInstruction<E, AddOp>;
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::Platform;
+    use goldilocks::GoldilocksExt2;
+
+    use super::Ed25519AddInstruction;
+    use crate::instructions::riscv::ecall::curve::test::test_ecall;
+
+    /// The base point B, and its multiples 2B and 3B.
+    const B: [u32; 16] = [
+        0x8F25_D51A,
+        0xC956_2D60,
+        0x9525_A7B2,
+        0x692C_C760,
+        0xFDD6_DC5C,
+        0xC0A4_E231,
+        0xCD6E_53FE,
+        0x2169_36D3,
+        0x6666_6658,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+        0x6666_6666,
+    ];
+    const B2: [u32; 16] = [
+        0x2843_CE0E,
+        0x83C5_A14E,
+        0x15D7_A45F,
+        0x080D_8E45,
+        0x1833_E7AC,
+        0x3D04_3B7D,
+        0x9F5A_046C,
+        0x36AB_384C,
+        0x6AF8_A3C9,
+        0x0E5F_46AE,
+        0x6438_5156,
+        0x9739_0F51,
+        0xC9A2_1F56,
+        0x1DA2_5EE8,
+        0x0923_29C2,
+        0x2260_CDF3,
+    ];
+    const B3: [u32; 16] = [
+        0xD3F8_E25C,
+        0xAC62_485F,
+        0x8162_4886,
+        0x6343_9819,
+        0x3EDA_C83A,
+        0x1FF4_AE74,
+        0x2292_8F49,
+        0x67AE_9C4A,
+        0x78F5_B4D4,
+        0x02C3_6848,
+        0x6724_0304,
+        0x9F16_EC17,
+        0x6026_9EF7,
+        0xA126_A18E,
+        0x77EE_69AB,
+        0x1267_B1D1,
+    ];
+
+    #[test]
+    fn test_ecall_ed25519_add() {
+        test_ecall::<Ed25519AddInstruction<GoldilocksExt2>>(
+            Platform::ecall_ed25519_add(),
+            &[B2, B].concat(),
+            B3,
+        );
+    }
+
+    #[test]
+    fn test_ecall_ed25519_double() {
+        test_ecall::<Ed25519AddInstruction<GoldilocksExt2>>(
+            Platform::ecall_ed25519_add(),
+            &[B, B].concat(),
+            B2,
+        );
+    }
+}
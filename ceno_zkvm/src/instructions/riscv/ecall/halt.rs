@@ -12,7 +12,7 @@ use crate::{
         },
     },
     set_val,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{StepRecord, Tracer};
 use ff_ext::ExtensionField;
@@ -65,8 +65,7 @@ impl<E: ExtensionField> Instruction<E> for HaltInstruction<E> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         assert_eq!(
@@ -82,21 +81,21 @@ impl<E: ExtensionField> Instruction<E> for HaltInstruction<E> {
 
         // the access of X10 register is stored in rs2()
         set_val!(
-            instance,
+            ctx.instance,
             config.prev_x10_ts,
             step.rs2().unwrap().previous_cycle
         );
 
         config.lt_x10_cfg.assign_instance(
-            instance,
-            lk_multiplicity,
+            ctx.instance,
+            ctx.lk_multiplicity,
             step.rs2().unwrap().previous_cycle,
             step.cycle() + Tracer::SUBCYCLE_RS2,
         )?;
 
         config
             .ecall_cfg
-            .assign_instance::<E>(instance, lk_multiplicity, step)?;
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
@@ -0,0 +1,390 @@
+//! The Keccak precompile: one row applies Keccak-f[1600] to a state, see
+//! `Platform::ecall_keccak_permute`.
+//!
+//! The lanes of the state are decomposed into bits, so that rho and pi only reorder bits, and
+//! theta, chi and iota are polynomials of bits. The input bits are checked by lookups, and every
+//! other witness is a bit by construction. Every XOR of two bits is a witness, so that every
+//! constraint has degree 2: the parities of theta go through the parities of the first rows of
+//! each column, and chi through the AND of its lanes. A row has about 155k witnesses.
+
+use std::{array, marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{
+    KECCAK_LANES, KECCAK_RHO_OFFSETS, KECCAK_ROUND_CONSTANTS, KECCAK_ROUNDS, KECCAK_STATE_WORDS,
+    Platform, StepRecord, Tracer, WORD_SIZE, keccak_lane, keccak_pi,
+};
+use ff_ext::ExtensionField;
+
+use super::bits::{assign_bits, bit_witins, exprs, set_bits, value, xor};
+use crate::{
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::AssertLTConfig,
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_KECCAK_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    witness::AssignmentCtx,
+};
+
+const LANE_BITS: usize = 64;
+const WORD_BITS: usize = 32;
+
+/// The configuration of a round, indexed by column `x` or by lane `x + 5 * y`.
+struct RoundConfig {
+    /// The parities of the rows 0 to `y` of each column, for `y` from 1 to 3.
+    parity_partial: Vec<Vec<[WitIn; LANE_BITS]>>,
+    /// The parities of the columns.
+    parity: Vec<[WitIn; LANE_BITS]>,
+    /// The parities which theta XORs into each column.
+    d: Vec<[WitIn; LANE_BITS]>,
+    /// The state after theta.
+    theta: Vec<[WitIn; LANE_BITS]>,
+    /// The `!b[x + 1] & b[x + 2]` of chi.
+    and: Vec<[WitIn; LANE_BITS]>,
+    /// The state after the round.
+    state: Vec<[WitIn; LANE_BITS]>,
+}
+
+pub struct KeccakConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    /// The accesses to the words of the state, the low word of each lane first.
+    mem_ops: Vec<(WitIn, AssertLTConfig)>,
+    state_in: Vec<[WitIn; LANE_BITS]>,
+    rounds: Vec<RoundConfig>,
+}
+
+pub struct KeccakInstruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for KeccakInstruction<E> {
+    type InstructionConfig = KeccakConfig<E>;
+
+    fn name() -> String {
+        "ECALL_KECCAK".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [ECALL_KECCAK_OPCODE[0].into(), ECALL_KECCAK_OPCODE[1].into()],
+            None,
+            None,
+        )?;
+
+        // read the address of the state from arg0 (X10 register)
+        let ptr = UInt::new_unchecked(|| "ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ptr.register_expr(),
+        )?;
+
+        let state_in = (0..KECCAK_LANES)
+            .map(|i| bit_witins(cb, format!("a_in_{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut a = state_in.iter().map(|lane| exprs(lane)).collect::<Vec<_>>();
+        let mut rounds = Vec::with_capacity(KECCAK_ROUNDS);
+        for (r, rc) in KECCAK_ROUND_CONSTANTS.into_iter().enumerate() {
+            // theta
+            let mut parity_partial = Vec::with_capacity(5);
+            let mut parity = Vec::with_capacity(5);
+            for x in 0..5 {
+                let mut partial: Vec<[WitIn; LANE_BITS]> = Vec::with_capacity(4);
+                for y in 1..5 {
+                    let bits = (0..LANE_BITS)
+                        .map(|z| {
+                            let c = partial
+                                .last()
+                                .map_or_else(|| a[keccak_lane(x, 0)][z].clone(), |c| c[z].expr());
+                            xor(&c, &a[keccak_lane(x, y)][z])
+                        })
+                        .collect();
+                    let name = if y < 4 {
+                        format!("round_{r}_c_partial_{x}_{y}")
+                    } else {
+                        format!("round_{r}_c_{x}")
+                    };
+                    partial.push(bit_witins_of(cb, name, bits)?);
+                }
+                parity.push(partial.pop().unwrap());
+                parity_partial.push(partial);
+            }
+            let d = (0..5)
+                .map(|x| {
+                    let bits = (0..LANE_BITS)
+                        .map(|z| {
+                            xor(
+                                &parity[(x + 4) % 5][z].expr(),
+                                &parity[(x + 1) % 5][(z + LANE_BITS - 1) % LANE_BITS].expr(),
+                            )
+                        })
+                        .collect();
+                    bit_witins_of(cb, format!("round_{r}_d_{x}"), bits)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let theta = (0..KECCAK_LANES)
+                .map(|lane| {
+                    let bits = (0..LANE_BITS)
+                        .map(|z| xor(&a[lane][z], &d[lane % 5][z].expr()))
+                        .collect();
+                    bit_witins_of(cb, format!("round_{r}_theta_{lane}"), bits)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // rho and pi
+            let mut b = vec![vec![]; KECCAK_LANES];
+            for (lane, offset) in KECCAK_RHO_OFFSETS.into_iter().enumerate() {
+                b[keccak_pi(lane % 5, lane / 5)] = rotl(&exprs(&theta[lane]), offset as usize);
+            }
+
+            // chi and iota
+            let and = (0..KECCAK_LANES)
+                .map(|lane| {
+                    let (x, y) = (lane % 5, lane / 5);
+                    let bits = (0..LANE_BITS)
+                        .map(|z| (1 - &b[keccak_lane(x + 1, y)][z]) * &b[keccak_lane(x + 2, y)][z])
+                        .collect();
+                    bit_witins_of(cb, format!("round_{r}_and_{lane}"), bits)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let state = (0..KECCAK_LANES)
+                .map(|lane| {
+                    let bits = (0..LANE_BITS)
+                        .map(|z| {
+                            let chi = xor(&b[lane][z], &and[lane][z].expr());
+                            if lane == 0 && (rc >> z) & 1 == 1 {
+                                1 - chi
+                            } else {
+                                chi
+                            }
+                        })
+                        .collect();
+                    bit_witins_of(cb, format!("round_{r}_a_{lane}"), bits)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            a = state.iter().map(|lane| exprs(lane)).collect();
+            rounds.push(RoundConfig {
+                parity_partial,
+                parity,
+                d,
+                theta,
+                and,
+                state,
+            });
+        }
+
+        // write the state
+        let mem_ts = ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM;
+        let mut mem_ops = Vec::with_capacity(KECCAK_STATE_WORDS);
+        for (i, (lane_in, lane_out)) in state_in.iter().zip(&a).enumerate() {
+            let lane_in = exprs(lane_in);
+            for (half, range) in [(0, 0..WORD_BITS), (1, WORD_BITS..LANE_BITS)] {
+                let word = 2 * i + half;
+                let prev_ts = cb.create_witin(|| format!("prev_ts_a_{word}"));
+                let (_, lt_cfg) = cb.memory_write(
+                    || format!("write a_{word}"),
+                    &(ptr.address_expr() + word * WORD_SIZE),
+                    prev_ts.expr(),
+                    mem_ts.clone(),
+                    value(&lane_in[range.clone()]),
+                    value(&lane_out[range]),
+                )?;
+                mem_ops.push((prev_ts, lt_cfg));
+            }
+        }
+
+        Ok(KeccakConfig {
+            ecall_cfg,
+            ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            mem_ops,
+            state_in,
+            rounds,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let ops = step.syscall_ops();
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_KECCAK_OPCODE[0] + (ECALL_KECCAK_OPCODE[1] << 16)) as u32
+        );
+        assert_eq!(ops.len(), config.mem_ops.len());
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x10 = step.rs2().unwrap();
+        config
+            .ptr
+            .assign_value(ctx.instance, Value::new_unchecked(x10.value));
+        set_val!(ctx.instance, config.prev_x10_ts, x10.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            x10.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        for ((prev_ts, lt_cfg), op) in config.mem_ops.iter().zip(ops) {
+            set_val!(ctx.instance, prev_ts, op.previous_cycle);
+            lt_cfg.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        let mut a: [u64; KECCAK_LANES] = array::from_fn(|i| {
+            ops[2 * i].value.before as u64 | (ops[2 * i + 1].value.before as u64) << WORD_BITS
+        });
+        for (bits, lane) in config.state_in.iter().zip(a) {
+            assign_bits(ctx.instance, ctx.lk_multiplicity, bits, lane);
+        }
+
+        for (round, rc) in config.rounds.iter().zip(KECCAK_ROUND_CONSTANTS) {
+            let parity_partial: [[u64; 3]; 5] = array::from_fn(|x| {
+                array::from_fn(|y| (0..y + 2).fold(0, |c, y| c ^ a[keccak_lane(x, y)]))
+            });
+            let parity: [u64; 5] = array::from_fn(|x| parity_partial[x][2] ^ a[keccak_lane(x, 4)]);
+            let d: [u64; 5] =
+                array::from_fn(|x| parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1));
+            let theta: [u64; KECCAK_LANES] = array::from_fn(|lane| a[lane] ^ d[lane % 5]);
+            let mut b = [0; KECCAK_LANES];
+            for (lane, offset) in KECCAK_RHO_OFFSETS.into_iter().enumerate() {
+                b[keccak_pi(lane % 5, lane / 5)] = theta[lane].rotate_left(offset as u32);
+            }
+            let and: [u64; KECCAK_LANES] = array::from_fn(|lane| {
+                let (x, y) = (lane % 5, lane / 5);
+                !b[keccak_lane(x + 1, y)] & b[keccak_lane(x + 2, y)]
+            });
+            a = array::from_fn(|lane| b[lane] ^ and[lane]);
+            a[0] ^= rc;
+
+            for (partial, lanes) in round.parity_partial.iter().zip(parity_partial) {
+                for (bits, lane) in partial.iter().zip(lanes) {
+                    set_bits(ctx.instance, bits, lane);
+                }
+            }
+            for (bits, lane) in round.parity.iter().zip(parity) {
+                set_bits(ctx.instance, bits, lane);
+            }
+            for (bits, lane) in round.d.iter().zip(d) {
+                set_bits(ctx.instance, bits, lane);
+            }
+            for (bits, lane) in round.theta.iter().zip(theta) {
+                set_bits(ctx.instance, bits, lane);
+            }
+            for (bits, lane) in round.and.iter().zip(and) {
+                set_bits(ctx.instance, bits, lane);
+            }
+            for (bits, lane) in round.state.iter().zip(a) {
+                set_bits(ctx.instance, bits, lane);
+            }
+        }
+
+        for (i, lane) in a.into_iter().enumerate() {
+            debug_assert_eq!(lane as u32, ops[2 * i].value.after);
+            debug_assert_eq!((lane >> WORD_BITS) as u32, ops[2 * i + 1].value.after);
+        }
+
+        Ok(())
+    }
+}
+
+/// Witnesses equal to `bits`, which are boolean if `bits` are.
+fn bit_witins_of<E: ExtensionField>(
+    cb: &mut CircuitBuilder<E>,
+    name: String,
+    bits: Vec<Expression<E>>,
+) -> Result<[WitIn; LANE_BITS], ZKVMError> {
+    let witins: [WitIn; LANE_BITS] = array::from_fn(|z| cb.create_witin(|| format!("{name}_{z}")));
+    for (z, (witin, bit)) in witins.iter().zip(bits).enumerate() {
+        cb.require_equal(|| format!("{name}_{z}"), witin.expr(), bit)?;
+    }
+    Ok(witins)
+}
+
+/// The bits of `x` rotated left by `n`.
+fn rotl<E: ExtensionField>(x: &[Expression<E>], n: usize) -> Vec<Expression<E>> {
+    (0..LANE_BITS)
+        .map(|z| x[(z + LANE_BITS - n) % LANE_BITS].clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::{CENO_PLATFORM, InsnKind, Platform, Program, VMState, encode_rv32};
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::Instruction,
+        scheme::mock_prover::MockProver,
+    };
+
+    use super::KeccakInstruction;
+
+    #[test]
+    fn test_ecall_keccak() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "keccak",
+                KeccakInstruction::<GoldilocksExt2>::construct_circuit,
+            )
+            .unwrap();
+
+        // Permute the zero state, at the start of RAM.
+        let ptr = CENO_PLATFORM.ram.start;
+        let instructions = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 5, Platform::ecall_keccak_permute()),
+            encode_rv32(InsnKind::LUI, 0, 0, 10, ptr),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        let steps = vm
+            .iter_until_halt()
+            .take(instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let step = steps.last().unwrap();
+        assert_eq!(step.syscall_ops()[0].value.after, 0x40E1DDE7);
+        assert_eq!(step.syscall_ops()[1].value.after, 0xF1258F79);
+
+        let (raw_witin, lkm) = KeccakInstruction::<GoldilocksExt2>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            vec![step.clone()],
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &instructions, None, Some(lkm));
+    }
+}
@@ -0,0 +1,395 @@
+//! The Poseidon2 precompile: one row applies the Poseidon2 permutation to a state of 12
+//! Goldilocks elements, see `Platform::ecall_poseidon2_permute`.
+//!
+//! The circuit is over the same field, so that the rounds are native arithmetic: the state after
+//! every round is a witness, and the s-box `x^7` goes through witnesses of `x^2`, `x^3` and `x^6`,
+//! so that every constraint has degree 2. The linear layers are sums and scalings of the state,
+//! `circ(2 M4, M4, M4)` in the full rounds and `1 + diag` in the partial rounds, and the initial
+//! external layer is folded into the first round, with no witness. The elements in memory are
+//! decomposed into 16-bit limbs and checked canonical, so that their words are those of the field
+//! elements. A row has about 900 witnesses.
+
+use std::{array, marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{POSEIDON2_STATE_WORDS, POSEIDON2_WIDTH, Platform, StepRecord, Tracer, WORD_SIZE};
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use poseidon::poseidon2::{
+    EXTERNAL_ROUND_CONSTANTS, HALF_N_FULL_ROUNDS, INTERNAL_ROUND_CONSTANTS, N_ROUNDS,
+    external_linear_layer, full_round_index, internal_linear_layer, is_full_round,
+};
+
+use crate::{
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{AssertLTConfig, IsEqualConfig},
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_POSEIDON2_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    utils::field_bits,
+    witness::{AssignmentCtx, LkMultiplicity},
+};
+
+const LIMB_BITS: usize = 16;
+const WORD_BITS: usize = 32;
+
+/// An element of the state in memory, as 16-bit limbs, the limbs of its low word first.
+struct ElementConfig {
+    limbs: [WitIn; 4],
+    /// Whether the high word is `2^32 - 1`, in which case the low word must be 0.
+    hi_is_max: IsEqualConfig,
+}
+
+impl ElementConfig {
+    fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        name: String,
+    ) -> Result<Self, ZKVMError> {
+        let limbs = array::from_fn(|i| cb.create_witin(|| format!("{name}_limb_{i}")));
+        for (i, limb) in limbs.iter().enumerate() {
+            cb.assert_ux::<_, _, LIMB_BITS>(|| format!("{name}_limb_{i}"), limb.expr())?;
+        }
+        let hi_is_max = IsEqualConfig::construct_circuit(
+            cb,
+            || format!("{name}_hi_is_max"),
+            word(&limbs, 1),
+            u32::MAX.into(),
+        )?;
+        // Below the modulus 2^64 - 2^32 + 1.
+        cb.require_zero(
+            || format!("{name}_canonical"),
+            hi_is_max.expr() * word(&limbs, 0),
+        )?;
+        Ok(Self { limbs, hi_is_max })
+    }
+
+    /// The field element.
+    fn expr<E: ExtensionField>(&self) -> Expression<E> {
+        word(&self.limbs, 0) + (word(&self.limbs, 1) << WORD_BITS)
+    }
+
+    fn assign_instance<F: SmallField>(
+        &self,
+        instance: &mut [MaybeUninit<F>],
+        lk_multiplicity: &mut LkMultiplicity,
+        value: u64,
+    ) -> Result<(), ZKVMError> {
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let limb_value = (value >> (i * LIMB_BITS)) & 0xFFFF;
+            set_val!(instance, limb, limb_value);
+            lk_multiplicity.assert_ux::<LIMB_BITS>(limb_value);
+        }
+        self.hi_is_max.assign_instance(
+            instance,
+            F::from(value >> WORD_BITS),
+            F::from(u32::MAX as u64),
+        )
+    }
+}
+
+/// The word `i` of an element of `limbs`.
+fn word<E: ExtensionField>(limbs: &[WitIn; 4], i: usize) -> Expression<E> {
+    limbs[2 * i].expr() + (limbs[2 * i + 1].expr() << LIMB_BITS)
+}
+
+/// The configuration of a round.
+struct RoundConfig {
+    /// The witnesses `x^2`, `x^3` and `x^6` of the elements through the s-box, that is all of them
+    /// in a full round, and the first one in a partial round.
+    sbox: Vec<[WitIn; 3]>,
+    /// The state after the round.
+    state: [WitIn; POSEIDON2_WIDTH],
+}
+
+pub struct Poseidon2Config<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    /// The accesses to the words of the state, the low word of each element first.
+    mem_ops: Vec<(WitIn, AssertLTConfig)>,
+    state_in: Vec<ElementConfig>,
+    state_out: Vec<ElementConfig>,
+    rounds: Vec<RoundConfig>,
+}
+
+pub struct Poseidon2Instruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Poseidon2Instruction<E> {
+    type InstructionConfig = Poseidon2Config<E>;
+
+    fn name() -> String {
+        "ECALL_POSEIDON2".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        // The round constants and the canonical check are those of the Goldilocks field.
+        if E::BaseField::MODULUS_U64 != goldilocks::MODULUS {
+            return Err(ZKVMError::UnsupportedField(field_bits::<E::BaseField>()));
+        }
+
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [
+                ECALL_POSEIDON2_OPCODE[0].into(),
+                ECALL_POSEIDON2_OPCODE[1].into(),
+            ],
+            None,
+            None,
+        )?;
+
+        // read the address of the state from arg0 (X10 register)
+        let ptr = UInt::new_unchecked(|| "ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ptr.register_expr(),
+        )?;
+
+        let state_in = (0..POSEIDON2_WIDTH)
+            .map(|i| ElementConfig::construct_circuit(cb, format!("state_in_{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let scale = |x: &Expression<E>, c: u64| x * c;
+        let mut state = external_linear_layer(&array::from_fn(|i| state_in[i].expr()), scale);
+        let mut rounds = Vec::with_capacity(N_ROUNDS);
+        for r in 0..N_ROUNDS {
+            let mut sbox = vec![];
+            let sboxed = array::from_fn(|i| {
+                let x = match round_constant(r, i) {
+                    Some(c) => state[i].clone() + c,
+                    None => state[i].clone(),
+                };
+                if i == 0 || is_full_round(r) {
+                    let x2 = cb.create_witin(|| format!("round_{r}_x2_{i}"));
+                    let x3 = cb.create_witin(|| format!("round_{r}_x3_{i}"));
+                    let x6 = cb.create_witin(|| format!("round_{r}_x6_{i}"));
+                    sbox.push((i, [x2, x3, x6], x.clone()));
+                    x6.expr() * x
+                } else {
+                    x
+                }
+            });
+            for (i, [x2, x3, x6], x) in &sbox {
+                cb.require_equal(|| format!("round_{r}_x2_{i}"), x2.expr(), x * x)?;
+                cb.require_equal(|| format!("round_{r}_x3_{i}"), x3.expr(), x2.expr() * x)?;
+                cb.require_equal(
+                    || format!("round_{r}_x6_{i}"),
+                    x6.expr(),
+                    x3.expr() * x3.expr(),
+                )?;
+            }
+            let mixed = if is_full_round(r) {
+                external_linear_layer(&sboxed, scale)
+            } else {
+                internal_linear_layer(&sboxed, scale)
+            };
+
+            let round_state =
+                array::from_fn(|i| cb.create_witin(|| format!("round_{r}_state_{i}")));
+            for (i, (witin, x)) in round_state.iter().zip(mixed).enumerate() {
+                cb.require_equal(|| format!("round_{r}_state_{i}"), witin.expr(), x)?;
+            }
+            state = round_state.map(|witin| witin.expr());
+            rounds.push(RoundConfig {
+                sbox: sbox.into_iter().map(|(_, witins, _)| witins).collect(),
+                state: round_state,
+            });
+        }
+
+        let state_out = (0..POSEIDON2_WIDTH)
+            .map(|i| ElementConfig::construct_circuit(cb, format!("state_out_{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (i, (element, x)) in state_out.iter().zip(state).enumerate() {
+            cb.require_equal(|| format!("state_out_{i}"), element.expr(), x)?;
+        }
+
+        // write the state
+        let mem_ts = ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM;
+        let mut mem_ops = Vec::with_capacity(POSEIDON2_STATE_WORDS);
+        for (i, (element_in, element_out)) in state_in.iter().zip(&state_out).enumerate() {
+            for half in 0..2 {
+                let w = 2 * i + half;
+                let prev_ts = cb.create_witin(|| format!("prev_ts_state_{w}"));
+                let (_, lt_cfg) = cb.memory_write(
+                    || format!("write state_{w}"),
+                    &(ptr.address_expr() + w * WORD_SIZE),
+                    prev_ts.expr(),
+                    mem_ts.clone(),
+                    word(&element_in.limbs, half),
+                    word(&element_out.limbs, half),
+                )?;
+                mem_ops.push((prev_ts, lt_cfg));
+            }
+        }
+
+        Ok(Poseidon2Config {
+            ecall_cfg,
+            ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            mem_ops,
+            state_in,
+            state_out,
+            rounds,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let ops = step.syscall_ops();
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_POSEIDON2_OPCODE[0] + (ECALL_POSEIDON2_OPCODE[1] << 16)) as u32
+        );
+        assert_eq!(ops.len(), config.mem_ops.len());
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x10 = step.rs2().unwrap();
+        config
+            .ptr
+            .assign_value(ctx.instance, Value::new_unchecked(x10.value));
+        set_val!(ctx.instance, config.prev_x10_ts, x10.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            x10.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        for ((prev_ts, lt_cfg), op) in config.mem_ops.iter().zip(ops) {
+            set_val!(ctx.instance, prev_ts, op.previous_cycle);
+            lt_cfg.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        let state_in: [u64; POSEIDON2_WIDTH] = array::from_fn(|i| {
+            ops[2 * i].value.before as u64 | (ops[2 * i + 1].value.before as u64) << WORD_BITS
+        });
+        let state_out: [u64; POSEIDON2_WIDTH] = array::from_fn(|i| {
+            ops[2 * i].value.after as u64 | (ops[2 * i + 1].value.after as u64) << WORD_BITS
+        });
+        for (element, value) in config.state_in.iter().zip(state_in) {
+            element.assign_instance(ctx.instance, ctx.lk_multiplicity, value)?;
+        }
+        for (element, value) in config.state_out.iter().zip(state_out) {
+            element.assign_instance(ctx.instance, ctx.lk_multiplicity, value)?;
+        }
+
+        let scale = |x: &E::BaseField, c: u64| *x * E::BaseField::from(c);
+        let mut state = external_linear_layer(&state_in.map(E::BaseField::from), scale);
+        for (r, round) in config.rounds.iter().enumerate() {
+            for (i, x) in state.iter_mut().enumerate() {
+                if let Some(c) = round_constant(r, i) {
+                    *x += E::BaseField::from(c);
+                }
+            }
+            for ([x2, x3, x6], x) in round.sbox.iter().zip(state.iter_mut()) {
+                let square = *x * *x;
+                let cube = square * *x;
+                let sixth = cube * cube;
+                set_val!(ctx.instance, x2, square);
+                set_val!(ctx.instance, x3, cube);
+                set_val!(ctx.instance, x6, sixth);
+                *x = sixth * *x;
+            }
+            state = if is_full_round(r) {
+                external_linear_layer(&state, scale)
+            } else {
+                internal_linear_layer(&state, scale)
+            };
+            for (witin, x) in round.state.iter().zip(&state) {
+                set_val!(ctx.instance, witin, *x);
+            }
+        }
+        debug_assert_eq!(state.map(|x| x.to_canonical_u64()), state_out);
+
+        Ok(())
+    }
+}
+
+/// The constant added to the element `i` in the round `r`, none for the elements after the first
+/// in a partial round.
+fn round_constant(r: usize, i: usize) -> Option<u64> {
+    if is_full_round(r) {
+        Some(EXTERNAL_ROUND_CONSTANTS[full_round_index(r)][i])
+    } else {
+        (i == 0).then(|| INTERNAL_ROUND_CONSTANTS[r - HALF_N_FULL_ROUNDS])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::{CENO_PLATFORM, InsnKind, Platform, Program, VMState, encode_rv32};
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::Instruction,
+        scheme::mock_prover::MockProver,
+    };
+
+    use super::Poseidon2Instruction;
+
+    #[test]
+    fn test_ecall_poseidon2() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "poseidon2",
+                Poseidon2Instruction::<GoldilocksExt2>::construct_circuit,
+            )
+            .unwrap();
+
+        // Permute the zero state, at the start of RAM.
+        let ptr = CENO_PLATFORM.ram.start;
+        let instructions = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 5, Platform::ecall_poseidon2_permute()),
+            encode_rv32(InsnKind::LUI, 0, 0, 10, ptr),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        let steps = vm
+            .iter_until_halt()
+            .take(instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let step = steps.last().unwrap();
+        assert_eq!(step.syscall_ops()[0].value.after, 0x8217461C);
+        assert_eq!(step.syscall_ops()[1].value.after, 0x5953AFEE);
+
+        let (raw_witin, lkm) = Poseidon2Instruction::<GoldilocksExt2>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            vec![step.clone()],
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &instructions, None, Some(lkm));
+    }
+}
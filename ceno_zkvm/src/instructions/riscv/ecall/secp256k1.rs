@@ -0,0 +1,143 @@
+//! The precompiles of the group law of secp256k1: one row computes `p + q` or `2p` for points in
+//! affine coordinates, see `Platform::ecall_secp256k1_add`.
+//!
+//! With the slope `λ`, the result is `x3 = λ^2 - x1 - x2` and `y3 = λ(x1 - x3) - y1`. The
+//! divisor of the slope is proven invertible, so that a doubling cannot pass for an addition.
+
+use ceno_emul::{SECP256K1_P, UINT256_WORDS, Word};
+
+use super::curve::{CurveInstruction, CurveOp, FieldOp};
+use crate::instructions::riscv::constants::{
+    ECALL_SECP256K1_ADD_OPCODE, ECALL_SECP256K1_DOUBLE_OPCODE,
+};
+
+pub struct AddOp;
+impl CurveOp for AddOp {
+    const NAME: &'static str = "ECALL_SECP256K1_ADD";
+    const OPCODE: [usize; 2] = ECALL_SECP256K1_ADD_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = SECP256K1_P;
+    const POINTS: usize = 2;
+    const CONSTANTS: &'static [[Word; UINT256_WORDS]] = &[];
+    // From (x1, y1, x2, y2).
+    const PROGRAM: &'static [FieldOp] = &[
+        FieldOp::Sub(2, 0),  // 4: x2 - x1
+        FieldOp::Sub(3, 1),  // 5: y2 - y1
+        FieldOp::Div(5, 4),  // 6: λ
+        FieldOp::Mul(6, 6),  // 7: λ^2
+        FieldOp::Add(0, 2),  // 8: x1 + x2
+        FieldOp::Sub(7, 8),  // 9: x3
+        FieldOp::Sub(0, 9),  // 10: x1 - x3
+        FieldOp::Mul(6, 10), // 11: λ(x1 - x3)
+        FieldOp::Sub(11, 1), // 12: y3
+    ];
+    const RESULT: [usize; 2] = [9, 12];
+}
+pub type Secp256k1AddInstruction<E> = CurveInstruction<E, AddOp>;
+
+pub struct DoubleOp;
+impl CurveOp for DoubleOp {
+    const NAME: &'static str = "ECALL_SECP256K1_DOUBLE";
+    const OPCODE: [usize; 2] = ECALL_SECP256K1_DOUBLE_OPCODE;
+    const MODULUS: [Word; UINT256_WORDS] = SECP256K1_P;
+    const POINTS: usize = 1;
+    const CONSTANTS: &'static [[Word; UINT256_WORDS]] = &[];
+    // From (x1, y1).
+    const PROGRAM: &'static [FieldOp] = &[
+        FieldOp::Mul(0, 0),  // 2: x1^2
+        FieldOp::Add(2, 2),  // 3: 2 x1^2
+        FieldOp::Add(3, 2),  // 4: 3 x1^2
+        FieldOp::Add(1, 1),  // 5: 2 y1
+        FieldOp::Div(4, 5),  // 6: λ
+        FieldOp::Mul(6, 6),  // 7: λ^2
+        FieldOp::Add(0, 0),  // 8: x1 + x1
+        FieldOp::Sub(7, 8),  // 9: x3
+        FieldOp::Sub(0, 9),  // 10: x1 - x3
+        FieldOp::Mul(6, 10), // 11: λ(x1 - x3)
+        FieldOp::Sub(11, 1), // 12: y3
+    ];
+    const RESULT: [usize; 2] = [9, 12];
+}
+pub type Secp256k1DoubleInstruction<E> = CurveInstruction<E, DoubleOp>;
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::Platform;
+    use goldilocks::GoldilocksExt2;
+
+    use super::{Secp256k1AddInstruction, Secp256k1DoubleInstruction};
+    use crate::instructions::riscv::ecall::curve::test::test_ecall;
+
+    /// The generator G, and its multiples 2G and 3G.
+    const G: [u32; 16] = [
+        0x16F8_1798,
+        0x59F2_815B,
+        0x2DCE_28D9,
+        0x029B_FCDB,
+        0xCE87_0B07,
+        0x55A0_6295,
+        0xF9DC_BBAC,
+        0x79BE_667E,
+        0xFB10_D4B8,
+        0x9C47_D08F,
+        0xA685_5419,
+        0xFD17_B448,
+        0x0E11_08A8,
+        0x5DA4_FBFC,
+        0x26A3_C465,
+        0x483A_DA77,
+    ];
+    const G2: [u32; 16] = [
+        0x5C70_9EE5,
+        0xABAC_09B9,
+        0x8CEF_3CA7,
+        0x5C77_8E4B,
+        0x95C0_7CD8,
+        0x3045_406E,
+        0x41ED_7D6D,
+        0xC604_7F94,
+        0x50CF_E52A,
+        0x2364_31A9,
+        0x3266_D0E1,
+        0xF7F6_3265,
+        0x466C_EAEE,
+        0xA3C5_8419,
+        0xA63D_C339,
+        0x1AE1_68FE,
+    ];
+    const G3: [u32; 16] = [
+        0xBCE0_36F9,
+        0x8601_F113,
+        0x836F_99B0,
+        0xB531_C845,
+        0xF89D_5229,
+        0x4934_4F85,
+        0x9258_C310,
+        0xF930_8A01,
+        0x84B8_E672,
+        0x6CB9_FD75,
+        0x34C2_231B,
+        0x6500_A999,
+        0x2A37_F356,
+        0x0FE3_37E6,
+        0x632D_E814,
+        0x388F_7B0F,
+    ];
+
+    #[test]
+    fn test_ecall_secp256k1_add() {
+        test_ecall::<Secp256k1AddInstruction<GoldilocksExt2>>(
+            Platform::ecall_secp256k1_add(),
+            &[G2, G].concat(),
+            G3,
+        );
+    }
+
+    #[test]
+    fn test_ecall_secp256k1_double() {
+        test_ecall::<Secp256k1DoubleInstruction<GoldilocksExt2>>(
+            Platform::ecall_secp256k1_double(),
+            &G,
+            G2,
+        );
+    }
+}
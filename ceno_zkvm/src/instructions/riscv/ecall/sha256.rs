@@ -0,0 +1,491 @@
+//! The SHA-256 precompile: one row compresses one block, see `Platform::ecall_sha256`.
+//!
+//! The words of the message schedule and the working variables `a` and `e` of every round are
+//! decomposed into bits, so that rotations and shifts only reorder bits, and the bitwise
+//! functions are products of bits. A 3-way XOR goes through a witness of its first 2-way XOR,
+//! so that every constraint has degree 2. The additions modulo 2^32 subtract a carry.
+
+use std::{array, marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{
+    Platform, SHA256_BLOCK_WORDS, SHA256_K, SHA256_ROUNDS, SHA256_STATE_WORDS, StepRecord, Tracer,
+    WORD_SIZE, sha256_schedule,
+};
+use ff_ext::ExtensionField;
+
+use super::bits::{assign_bits, bit_witins, exprs, set_bits, value, xor};
+use crate::{
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::AssertLTConfig,
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{ECALL_SHA256_OPCODE, UInt},
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    witness::AssignmentCtx,
+};
+
+const WORD_BITS: usize = 32;
+
+/// The configuration of a word of the message schedule after the block.
+struct ScheduleConfig {
+    word: [WitIn; WORD_BITS],
+    /// `rotr(w[t-15], 7) ^ rotr(w[t-15], 18)`.
+    sigma0_partial: [WitIn; WORD_BITS],
+    /// `rotr(w[t-2], 17) ^ rotr(w[t-2], 19)`.
+    sigma1_partial: [WitIn; WORD_BITS],
+    carry: [WitIn; 2],
+}
+
+/// The configuration of a round, which computes the next `a` and `e`.
+struct RoundConfig {
+    a: [WitIn; WORD_BITS],
+    e: [WitIn; WORD_BITS],
+    /// `rotr(a, 2) ^ rotr(a, 13)`.
+    sigma0_partial: [WitIn; WORD_BITS],
+    /// `rotr(e, 6) ^ rotr(e, 11)`.
+    sigma1_partial: [WitIn; WORD_BITS],
+    a_xor_b: [WitIn; WORD_BITS],
+    /// The carry of `e` in the 3 low bits, then the carry of `a`.
+    carry: [WitIn; 6],
+}
+
+pub struct Sha256Config<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    /// The accesses to the words of the state, then to the words of the block.
+    mem_ops: Vec<(WitIn, AssertLTConfig)>,
+    state_in: Vec<[WitIn; WORD_BITS]>,
+    block: Vec<[WitIn; WORD_BITS]>,
+    schedule: Vec<ScheduleConfig>,
+    rounds: Vec<RoundConfig>,
+    state_out: Vec<UInt<E>>,
+    state_carry: [WitIn; SHA256_STATE_WORDS],
+}
+
+pub struct Sha256Instruction<E>(PhantomData<E>);
+
+impl<E: ExtensionField> Instruction<E> for Sha256Instruction<E> {
+    type InstructionConfig = Sha256Config<E>;
+
+    fn name() -> String {
+        "ECALL_SHA256".into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [ECALL_SHA256_OPCODE[0].into(), ECALL_SHA256_OPCODE[1].into()],
+            None,
+            None,
+        )?;
+
+        // read the address of the state and the block from arg0 (X10 register)
+        let ptr = UInt::new_unchecked(|| "ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ptr.register_expr(),
+        )?;
+
+        let state_in = (0..SHA256_STATE_WORDS)
+            .map(|i| bit_witins(cb, format!("h_in_{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let block = (0..SHA256_BLOCK_WORDS)
+            .map(|t| bit_witins(cb, format!("w_{t}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // message schedule
+        let mut w = block.iter().map(|word| exprs(word)).collect::<Vec<_>>();
+        let mut schedule = Vec::with_capacity(SHA256_ROUNDS - SHA256_BLOCK_WORDS);
+        for t in SHA256_BLOCK_WORDS..SHA256_ROUNDS {
+            let (sigma0_partial, sigma0) = xor3(
+                cb,
+                format!("sigma0_{t}"),
+                [rotr(&w[t - 15], 7), rotr(&w[t - 15], 18)],
+                shr(&w[t - 15], 3),
+            )?;
+            let (sigma1_partial, sigma1) = xor3(
+                cb,
+                format!("sigma1_{t}"),
+                [rotr(&w[t - 2], 17), rotr(&w[t - 2], 19)],
+                shr(&w[t - 2], 10),
+            )?;
+            let word = bit_witins(cb, format!("w_{t}"))?;
+            let carry = bit_witins(cb, format!("w_{t}_carry"))?;
+            cb.require_zero(
+                || format!("w_{t}"),
+                value(&sigma1) + value(&w[t - 7]) + value(&sigma0) + value(&w[t - 16])
+                    - value(&exprs(&word))
+                    - (value(&exprs(&carry)) << WORD_BITS),
+            )?;
+            w.push(exprs(&word));
+            schedule.push(ScheduleConfig {
+                word,
+                sigma0_partial,
+                sigma1_partial,
+                carry,
+            });
+        }
+
+        // rounds: a[t..t + 4] is (d, c, b, a) and e[t..t + 4] is (h, g, f, e) at round t
+        let mut a = [3, 2, 1, 0].map(|i| exprs(&state_in[i])).to_vec();
+        let mut e = [7, 6, 5, 4].map(|i| exprs(&state_in[i])).to_vec();
+        let mut rounds = Vec::with_capacity(SHA256_ROUNDS);
+        for t in 0..SHA256_ROUNDS {
+            let (a_t, b_t, c_t, d_t) = (&a[t + 3], &a[t + 2], &a[t + 1], &a[t]);
+            let (e_t, f_t, g_t, h_t) = (&e[t + 3], &e[t + 2], &e[t + 1], &e[t]);
+
+            let (sigma1_partial, sigma1) = xor3(
+                cb,
+                format!("round_{t}_sigma1"),
+                [rotr(e_t, 6), rotr(e_t, 11)],
+                rotr(e_t, 25).into_iter().map(Some).collect(),
+            )?;
+            let ch = (0..WORD_BITS)
+                .map(|j| &e_t[j] * (&f_t[j] - &g_t[j]) + &g_t[j])
+                .collect::<Vec<_>>();
+            let (sigma0_partial, sigma0) = xor3(
+                cb,
+                format!("round_{t}_sigma0"),
+                [rotr(a_t, 2), rotr(a_t, 13)],
+                rotr(a_t, 22).into_iter().map(Some).collect(),
+            )?;
+            let a_xor_b = xor_witins(cb, format!("round_{t}_a_xor_b"), a_t, b_t)?;
+            let maj = (0..WORD_BITS)
+                .map(|j| &a_t[j] * &b_t[j] + &c_t[j] * a_xor_b[j].expr())
+                .collect::<Vec<_>>();
+
+            let t1 = value(h_t) + value(&sigma1) + value(&ch) + SHA256_K[t] + value(&w[t]);
+            let t2 = value(&sigma0) + value(&maj);
+            let next_a = bit_witins(cb, format!("round_{t}_a"))?;
+            let next_e = bit_witins(cb, format!("round_{t}_e"))?;
+            let carry = bit_witins(cb, format!("round_{t}_carry"))?;
+            cb.require_zero(
+                || format!("round_{t}_e"),
+                value(d_t) + t1.clone()
+                    - value(&exprs(&next_e))
+                    - (value(&exprs(&carry[..3])) << WORD_BITS),
+            )?;
+            cb.require_zero(
+                || format!("round_{t}_a"),
+                t1 + t2 - value(&exprs(&next_a)) - (value(&exprs(&carry[3..])) << WORD_BITS),
+            )?;
+
+            a.push(exprs(&next_a));
+            e.push(exprs(&next_e));
+            rounds.push(RoundConfig {
+                a: next_a,
+                e: next_e,
+                sigma0_partial,
+                sigma1_partial,
+                a_xor_b,
+                carry,
+            });
+        }
+
+        // add the working variables to the state
+        let working = [3, 2, 1, 0]
+            .map(|i| &a[SHA256_ROUNDS + i])
+            .into_iter()
+            .chain([3, 2, 1, 0].map(|i| &e[SHA256_ROUNDS + i]))
+            .collect::<Vec<_>>();
+        let state_out = (0..SHA256_STATE_WORDS)
+            .map(|i| UInt::new(|| format!("h_out_{i}"), cb))
+            .collect::<Result<Vec<_>, _>>()?;
+        let state_carry = bit_witins(cb, "h_carry".to_string())?;
+        for i in 0..SHA256_STATE_WORDS {
+            cb.require_zero(
+                || format!("h_out_{i}"),
+                value(&exprs(&state_in[i])) + value(working[i])
+                    - state_out[i].value()
+                    - (state_carry[i].expr() << WORD_BITS),
+            )?;
+        }
+
+        // write the state and read the block
+        let mem_ts = ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM;
+        let mut mem_ops = Vec::with_capacity(SHA256_STATE_WORDS + SHA256_BLOCK_WORDS);
+        for (i, (h_in, h_out)) in state_in.iter().zip(&state_out).enumerate() {
+            let prev_ts = cb.create_witin(|| format!("prev_ts_h_{i}"));
+            let (_, lt_cfg) = cb.memory_write(
+                || format!("write h_{i}"),
+                &(ptr.address_expr() + i * WORD_SIZE),
+                prev_ts.expr(),
+                mem_ts.clone(),
+                value(&exprs(h_in)),
+                h_out.memory_expr(),
+            )?;
+            mem_ops.push((prev_ts, lt_cfg));
+        }
+        for (t, word) in block.iter().enumerate() {
+            let prev_ts = cb.create_witin(|| format!("prev_ts_w_{t}"));
+            let (_, lt_cfg) = cb.memory_read(
+                || format!("read w_{t}"),
+                &(ptr.address_expr() + (SHA256_STATE_WORDS + t) * WORD_SIZE),
+                prev_ts.expr(),
+                mem_ts.clone(),
+                value(&exprs(word)),
+            )?;
+            mem_ops.push((prev_ts, lt_cfg));
+        }
+
+        Ok(Sha256Config {
+            ecall_cfg,
+            ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            mem_ops,
+            state_in,
+            block,
+            schedule,
+            rounds,
+            state_out,
+            state_carry,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let ops = step.syscall_ops();
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (ECALL_SHA256_OPCODE[0] + (ECALL_SHA256_OPCODE[1] << 16)) as u32
+        );
+        assert_eq!(ops.len(), config.mem_ops.len());
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x10 = step.rs2().unwrap();
+        config
+            .ptr
+            .assign_value(ctx.instance, Value::new_unchecked(x10.value));
+        set_val!(ctx.instance, config.prev_x10_ts, x10.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            x10.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        for ((prev_ts, lt_cfg), op) in config.mem_ops.iter().zip(ops) {
+            set_val!(ctx.instance, prev_ts, op.previous_cycle);
+            lt_cfg.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        let state: [u32; SHA256_STATE_WORDS] = array::from_fn(|i| ops[i].value.before);
+        let block: [u32; SHA256_BLOCK_WORDS] =
+            array::from_fn(|t| ops[SHA256_STATE_WORDS + t].value.before);
+        for (bits, word) in config.state_in.iter().zip(state) {
+            assign_bits(ctx.instance, ctx.lk_multiplicity, bits, word as u64);
+        }
+        for (bits, word) in config.block.iter().zip(block) {
+            assign_bits(ctx.instance, ctx.lk_multiplicity, bits, word as u64);
+        }
+
+        let w = sha256_schedule(&block);
+        for (t, sched) in (SHA256_BLOCK_WORDS..).zip(&config.schedule) {
+            let (x, y) = (w[t - 15], w[t - 2]);
+            let sigma0_partial = x.rotate_right(7) ^ x.rotate_right(18);
+            let sigma1_partial = y.rotate_right(17) ^ y.rotate_right(19);
+            set_bits(ctx.instance, &sched.sigma0_partial, sigma0_partial as u64);
+            set_bits(ctx.instance, &sched.sigma1_partial, sigma1_partial as u64);
+            let sum = (sigma1_partial ^ (y >> 10)) as u64
+                + w[t - 7] as u64
+                + (sigma0_partial ^ (x >> 3)) as u64
+                + w[t - 16] as u64;
+            assign_bits(ctx.instance, ctx.lk_multiplicity, &sched.word, w[t] as u64);
+            assign_bits(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                &sched.carry,
+                sum >> WORD_BITS,
+            );
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for (t, round) in config.rounds.iter().enumerate() {
+            let sigma1_partial = e.rotate_right(6) ^ e.rotate_right(11);
+            let sigma0_partial = a.rotate_right(2) ^ a.rotate_right(13);
+            set_bits(ctx.instance, &round.sigma1_partial, sigma1_partial as u64);
+            set_bits(ctx.instance, &round.sigma0_partial, sigma0_partial as u64);
+            set_bits(ctx.instance, &round.a_xor_b, (a ^ b) as u64);
+
+            let sigma1 = sigma1_partial ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let sigma0 = sigma0_partial ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t1 = h as u64 + sigma1 as u64 + ch as u64 + SHA256_K[t] as u64 + w[t] as u64;
+            let t2 = sigma0 as u64 + maj as u64;
+            let (next_e, next_a) = (d as u64 + t1, t1 + t2);
+            assign_bits(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                &round.e,
+                next_e & 0xffff_ffff,
+            );
+            assign_bits(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                &round.a,
+                next_a & 0xffff_ffff,
+            );
+            assign_bits(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                &round.carry,
+                (next_e >> WORD_BITS) | ((next_a >> WORD_BITS) << 3),
+            );
+
+            (h, g, f, e) = (g, f, e, next_e as u32);
+            (d, c, b, a) = (c, b, a, next_a as u32);
+        }
+
+        let mut state_carry = 0;
+        for (i, working) in [a, b, c, d, e, f, g, h].into_iter().enumerate() {
+            let sum = state[i] as u64 + working as u64;
+            debug_assert_eq!(sum as u32, ops[i].value.after);
+            config.state_out[i]
+                .assign_value(ctx.instance, Value::new(sum as u32, ctx.lk_multiplicity));
+            state_carry |= (sum >> WORD_BITS) << i;
+        }
+        assign_bits(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            &config.state_carry,
+            state_carry,
+        );
+
+        Ok(())
+    }
+}
+
+/// Witnesses of the bitwise `x ^ y`, which are boolean if `x` and `y` are.
+fn xor_witins<E: ExtensionField>(
+    cb: &mut CircuitBuilder<E>,
+    name: String,
+    x: &[Expression<E>],
+    y: &[Expression<E>],
+) -> Result<[WitIn; WORD_BITS], ZKVMError> {
+    let bits: [WitIn; WORD_BITS] = array::from_fn(|i| cb.create_witin(|| format!("{name}_{i}")));
+    for (i, bit) in bits.iter().enumerate() {
+        cb.require_equal(|| format!("{name}_{i}"), bit.expr(), xor(&x[i], &y[i]))?;
+    }
+    Ok(bits)
+}
+
+/// The bitwise `x ^ y ^ z`, with `z` missing for the bits shifted out by a right shift. Return
+/// the witnesses of `x ^ y`, and the bits of the result.
+fn xor3<E: ExtensionField>(
+    cb: &mut CircuitBuilder<E>,
+    name: String,
+    [x, y]: [Vec<Expression<E>>; 2],
+    z: Vec<Option<Expression<E>>>,
+) -> Result<([WitIn; WORD_BITS], Vec<Expression<E>>), ZKVMError> {
+    let partial = xor_witins(cb, name, &x, &y)?;
+    let bits = partial
+        .iter()
+        .zip(z)
+        .map(|(p, z)| match z {
+            Some(z) => xor(&p.expr(), &z),
+            None => p.expr(),
+        })
+        .collect();
+    Ok((partial, bits))
+}
+
+/// The bits of `x` rotated right by `n`.
+fn rotr<E: ExtensionField>(x: &[Expression<E>], n: usize) -> Vec<Expression<E>> {
+    (0..WORD_BITS)
+        .map(|i| x[(i + n) % WORD_BITS].clone())
+        .collect()
+}
+
+/// The bits of `x` shifted right by `n`, missing for the high bits.
+fn shr<E: ExtensionField>(x: &[Expression<E>], n: usize) -> Vec<Option<Expression<E>>> {
+    (0..WORD_BITS).map(|i| x.get(i + n).cloned()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::{
+        CENO_PLATFORM, InsnKind, Platform, Program, SHA256_INIT, VMState, encode_rv32,
+    };
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::Instruction,
+        scheme::mock_prover::MockProver,
+    };
+
+    use super::Sha256Instruction;
+
+    #[test]
+    fn test_ecall_sha256() {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb
+            .namespace(
+                || "sha256",
+                Sha256Instruction::<GoldilocksExt2>::construct_circuit,
+            )
+            .unwrap();
+
+        // Compress the padded block of "abc" from the initial state, at the start of RAM.
+        let ptr = CENO_PLATFORM.ram.start;
+        let instructions = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 5, Platform::ecall_sha256()),
+            encode_rv32(InsnKind::LUI, 0, 0, 10, ptr),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        let mut block = [0; 16];
+        block[0] = 0x61626380;
+        block[15] = 24;
+        for (i, word) in SHA256_INIT.into_iter().chain(block).enumerate() {
+            vm.init_memory((ptr + 4 * i as u32).into(), word);
+        }
+        let steps = vm
+            .iter_until_halt()
+            .take(instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let step = steps.last().unwrap();
+        assert_eq!(step.syscall_ops()[0].value.after, 0xba7816bf);
+
+        let (raw_witin, lkm) = Sha256Instruction::<GoldilocksExt2>::assign_instances(
+            &config,
+            cb.cs.num_witin as usize,
+            vec![step.clone()],
+        )
+        .unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &instructions, None, Some(lkm));
+    }
+}
@@ -0,0 +1,334 @@
+//! The precompiles of 256-bit modular arithmetic: one row computes `(x + y) mod m` or
+//! `(x * y) mod m`, see `Platform::ecall_uint256_addmod`.
+//!
+//! The operands are 256-bit `UInt`s of 16-bit limbs. The row proves `x op y = q * m + r` on 512
+//! bits, see `MulAddModConfig`, and `r < m`, which determine the remainder `r`. The operands
+//! being reduced is checked by the VM only: the circuit proves the result of any operands, as
+//! long as the quotient holds in 256 bits.
+
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+use ceno_emul::{Platform, StepRecord, Tracer, UINT256_WORDS, WORD_SIZE, Word};
+use ff_ext::ExtensionField;
+
+use crate::{
+    chip_handler::{MemoryChipOperations, RegisterChipOperations},
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, ToExpr, WitIn},
+    gadgets::{AssertLTConfig, AssertUint256LtConfig, MulAddModConfig, assign_uint256},
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{
+                ECALL_UINT256_ADDMOD_OPCODE, ECALL_UINT256_MULMOD_OPCODE, LIMB_BITS, UInt, UInt256,
+            },
+            ecall_insn::EcallInstructionConfig,
+        },
+    },
+    set_val,
+    uint::Value,
+    witness::AssignmentCtx,
+};
+
+pub trait ModArithOp {
+    const NAME: &'static str;
+    const OPCODE: [usize; 2];
+    /// Whether the operation is a multiplication, or an addition.
+    const IS_MUL: bool;
+}
+
+pub struct AddModOp;
+impl ModArithOp for AddModOp {
+    const NAME: &'static str = "ECALL_UINT256_ADDMOD";
+    const OPCODE: [usize; 2] = ECALL_UINT256_ADDMOD_OPCODE;
+    const IS_MUL: bool = false;
+}
+pub type Uint256AddModInstruction<E> = Uint256ModInstruction<E, AddModOp>;
+
+pub struct MulModOp;
+impl ModArithOp for MulModOp {
+    const NAME: &'static str = "ECALL_UINT256_MULMOD";
+    const OPCODE: [usize; 2] = ECALL_UINT256_MULMOD_OPCODE;
+    const IS_MUL: bool = true;
+}
+pub type Uint256MulModInstruction<E> = Uint256ModInstruction<E, MulModOp>;
+
+pub struct Uint256ModConfig<E: ExtensionField> {
+    ecall_cfg: EcallInstructionConfig,
+    ptr: UInt<E>,
+    prev_x10_ts: WitIn,
+    lt_x10_cfg: AssertLTConfig,
+    /// The accesses to the words of `x`, `y` and `m`.
+    mem_ops: Vec<(WitIn, AssertLTConfig)>,
+    x: UInt256<E>,
+    y: UInt256<E>,
+    m: UInt256<E>,
+    r: UInt256<E>,
+    x_op_y: MulAddModConfig<E>,
+    r_lt_m: AssertUint256LtConfig<E>,
+}
+
+pub struct Uint256ModInstruction<E, I>(PhantomData<(E, I)>);
+
+impl<E: ExtensionField, I: ModArithOp> Instruction<E> for Uint256ModInstruction<E, I> {
+    type InstructionConfig = Uint256ModConfig<E>;
+
+    fn name() -> String {
+        I::NAME.into()
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let ecall_cfg = EcallInstructionConfig::construct_circuit(
+            cb,
+            [I::OPCODE[0].into(), I::OPCODE[1].into()],
+            None,
+            None,
+        )?;
+
+        // read the address of the operands from arg0 (X10 register)
+        let ptr = UInt::new_unchecked(|| "ptr", cb)?;
+        let prev_x10_ts = cb.create_witin(|| "prev_x10_ts");
+        let (_, lt_x10_cfg) = cb.register_read(
+            || "read x10",
+            E::BaseField::from(Platform::reg_arg0() as u64),
+            prev_x10_ts.expr(),
+            ecall_cfg.ts.expr() + Tracer::SUBCYCLE_RS2,
+            ptr.register_expr(),
+        )?;
+
+        let mut x = UInt256::new(|| "x", cb)?;
+        let mut y = UInt256::new(|| "y", cb)?;
+        let mut m = UInt256::new(|| "m", cb)?;
+        let r = UInt256::new(|| "r", cb)?;
+
+        // x op y = q * m + r, and r < m
+        let x_op_y = if I::IS_MUL {
+            MulAddModConfig::construct_circuit(
+                cb,
+                || "x_mul_y",
+                &mut x,
+                Some(&mut y),
+                None,
+                &mut m,
+                &r,
+            )?
+        } else {
+            MulAddModConfig::construct_circuit(
+                cb,
+                || "x_add_y",
+                &mut x,
+                None,
+                Some(&y),
+                &mut m,
+                &r,
+            )?
+        };
+        let r_lt_m = AssertUint256LtConfig::construct_circuit(cb, || "r_lt_m", &r, &m)?;
+
+        // write the result over x, and read y and m
+        let mem_ts = ecall_cfg.ts.expr() + Tracer::SUBCYCLE_MEM;
+        let mut mem_ops = Vec::with_capacity(3 * UINT256_WORDS);
+        let [x_limbs, y_limbs, m_limbs, r_limbs] = [&x, &y, &m, &r].map(|uint| uint.expr());
+        for i in 0..UINT256_WORDS {
+            let prev_ts = cb.create_witin(|| format!("prev_ts_x_{i}"));
+            let (_, lt_cfg) = cb.memory_write(
+                || format!("write x_{i}"),
+                &(ptr.address_expr() + i * WORD_SIZE),
+                prev_ts.expr(),
+                mem_ts.clone(),
+                word(&x_limbs, i),
+                word(&r_limbs, i),
+            )?;
+            mem_ops.push((prev_ts, lt_cfg));
+        }
+        for (j, operand) in [(1, &y_limbs), (2, &m_limbs)] {
+            for i in 0..UINT256_WORDS {
+                let word_idx = j * UINT256_WORDS + i;
+                let prev_ts = cb.create_witin(|| format!("prev_ts_{word_idx}"));
+                let (_, lt_cfg) = cb.memory_read(
+                    || format!("read {word_idx}"),
+                    &(ptr.address_expr() + word_idx * WORD_SIZE),
+                    prev_ts.expr(),
+                    mem_ts.clone(),
+                    word(operand, i),
+                )?;
+                mem_ops.push((prev_ts, lt_cfg));
+            }
+        }
+
+        Ok(Uint256ModConfig {
+            ecall_cfg,
+            ptr,
+            prev_x10_ts,
+            lt_x10_cfg,
+            mem_ops,
+            x,
+            y,
+            m,
+            r,
+            x_op_y,
+            r_lt_m,
+        })
+    }
+
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        let ops = step.syscall_ops();
+        assert_eq!(
+            step.rs1().unwrap().value,
+            (I::OPCODE[0] + (I::OPCODE[1] << 16)) as u32
+        );
+        assert_eq!(ops.len(), config.mem_ops.len());
+
+        config
+            .ecall_cfg
+            .assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step)?;
+
+        // the access of X10 register is stored in rs2()
+        let x10 = step.rs2().unwrap();
+        config
+            .ptr
+            .assign_value(ctx.instance, Value::new_unchecked(x10.value));
+        set_val!(ctx.instance, config.prev_x10_ts, x10.previous_cycle);
+        config.lt_x10_cfg.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            x10.previous_cycle,
+            step.cycle() + Tracer::SUBCYCLE_RS2,
+        )?;
+
+        for ((prev_ts, lt_cfg), op) in config.mem_ops.iter().zip(ops) {
+            set_val!(ctx.instance, prev_ts, op.previous_cycle);
+            lt_cfg.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                op.previous_cycle,
+                step.cycle() + Tracer::SUBCYCLE_MEM,
+            )?;
+        }
+
+        let [x, y, m]: [[Word; UINT256_WORDS]; 3] = std::array::from_fn(|j| {
+            std::array::from_fn(|i| ops[j * UINT256_WORDS + i].value.before)
+        });
+        let r: [Word; UINT256_WORDS] = std::array::from_fn(|i| ops[i].value.after);
+
+        for (uint, words) in [
+            (&config.x, &x),
+            (&config.y, &y),
+            (&config.m, &m),
+            (&config.r, &r),
+        ] {
+            assign_uint256(uint, ctx.instance, ctx.lk_multiplicity, words);
+        }
+        let (v, w) = if I::IS_MUL {
+            (Some(&y), None)
+        } else {
+            (None, Some(&y))
+        };
+        config
+            .x_op_y
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, &x, v, w, &m, &r)?;
+        config
+            .r_lt_m
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, &r, &m);
+
+        Ok(())
+    }
+}
+
+/// The word `i` of a number of 16-bit limbs.
+pub(super) fn word<E: ExtensionField>(limbs: &[Expression<E>], i: usize) -> Expression<E> {
+    &limbs[2 * i] + (&limbs[2 * i + 1] << LIMB_BITS)
+}
+
+#[cfg(test)]
+mod test {
+    use ceno_emul::{CENO_PLATFORM, InsnKind, Platform, Program, StepRecord, VMState, encode_rv32};
+    use goldilocks::GoldilocksExt2;
+
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        instructions::Instruction,
+        scheme::mock_prover::MockProver,
+    };
+
+    use super::{Uint256AddModInstruction, Uint256MulModInstruction};
+
+    /// The prime of the field of secp256k1, 2^256 - 2^32 - 977.
+    const P: [u32; 8] = [0xFFFF_FC2F, 0xFFFF_FFFE, !0, !0, !0, !0, !0, !0];
+
+    /// Run the ecall on `x`, `y` and `P`, at the start of RAM.
+    fn run_ecall(ecall: u32, x: [u32; 8], y: [u32; 8]) -> (Vec<u32>, StepRecord) {
+        let ptr = CENO_PLATFORM.ram.start;
+        let instructions = vec![
+            encode_rv32(InsnKind::ADDI, 0, 0, 5, ecall),
+            encode_rv32(InsnKind::LUI, 0, 0, 10, ptr),
+            encode_rv32(InsnKind::EANY, 0, 0, 0, 0),
+        ];
+        let pc_base = CENO_PLATFORM.pc_base();
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+        let mut vm = VMState::new(CENO_PLATFORM, program);
+        for (i, word) in x.into_iter().chain(y).chain(P).enumerate() {
+            vm.init_memory((ptr + 4 * i as u32).into(), word);
+        }
+        let steps = vm
+            .iter_until_halt()
+            .take(instructions.len())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        (instructions, steps.last().unwrap().clone())
+    }
+
+    fn test_ecall<I: Instruction<GoldilocksExt2>>(
+        ecall: u32,
+        x: [u32; 8],
+        y: [u32; 8],
+        expected: [u32; 8],
+    ) {
+        let mut cs = ConstraintSystem::<GoldilocksExt2>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = cb.namespace(|| "uint256", I::construct_circuit).unwrap();
+
+        let (instructions, step) = run_ecall(ecall, x, y);
+        let result = step.syscall_ops()[..8]
+            .iter()
+            .map(|op| op.value.after)
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        let (raw_witin, lkm) =
+            I::assign_instances(&config, cb.cs.num_witin as usize, vec![step]).unwrap();
+
+        MockProver::assert_satisfied_raw(&cb, raw_witin, &instructions, None, Some(lkm));
+    }
+
+    #[test]
+    fn test_ecall_uint256_addmod() {
+        // (p - 1) + (p - 1) = p - 2
+        let mut minus_one = P;
+        minus_one[0] -= 1;
+        let mut minus_two = P;
+        minus_two[0] -= 2;
+        test_ecall::<Uint256AddModInstruction<GoldilocksExt2>>(
+            Platform::ecall_uint256_addmod(),
+            minus_one,
+            minus_one,
+            minus_two,
+        );
+    }
+
+    #[test]
+    fn test_ecall_uint256_mulmod() {
+        // 2^255 * 2 = 2^256 = 2^32 + 977
+        test_ecall::<Uint256MulModInstruction<GoldilocksExt2>>(
+            Platform::ecall_uint256_mulmod(),
+            [0, 0, 0, 0, 0, 0, 0, 1 << 31],
+            [2, 0, 0, 0, 0, 0, 0, 0],
+            [977, 1, 0, 0, 0, 0, 0, 0],
+        );
+    }
+}
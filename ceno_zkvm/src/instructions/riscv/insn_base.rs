@@ -470,8 +470,6 @@ impl<E: ExtensionField> MemAddr<E> {
 #[cfg(test)]
 mod test {
     use goldilocks::{Goldilocks as F, GoldilocksExt2 as E};
-    use itertools::Itertools;
-    use multilinear_extensions::mle::IntoMLEs;
 
     use crate::{
         ROMType,
@@ -539,14 +537,9 @@ mod test {
             cb.require_equal(|| "", mem_addr.expr_align2(), (addr & !1).into())?;
             cb.require_equal(|| "", mem_addr.expr_align4(), (addr & !3).into())?;
         }
-        MockProver::assert_with_expected_errors(
+        MockProver::assert_with_expected_errors_raw(
             &cb,
-            &raw_witin
-                .de_interleaving()
-                .into_mles()
-                .into_iter()
-                .map(|v| v.into())
-                .collect_vec(),
+            raw_witin,
             &[],
             if is_ok { &[] } else { &["mid_u14"] },
             None,
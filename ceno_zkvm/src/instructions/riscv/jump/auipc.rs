@@ -15,7 +15,7 @@ use crate::{
     set_val,
     tables::InsnRecord,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 
 pub struct AuipcConfig<E: ExtensionField> {
@@ -71,23 +71,22 @@ impl<E: ExtensionField> Instruction<E> for AuipcInstruction<E> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &ceno_emul::StepRecord,
     ) -> Result<(), ZKVMError> {
         let pc: u32 = step.pc().before.0;
         let imm = InsnRecord::imm_internal(&step.insn());
         let (sum, overflow) = pc.overflowing_add(imm as u32);
 
-        set_val!(instance, config.imm, i64_to_base::<E::BaseField>(imm));
-        set_val!(instance, config.overflow_bit, overflow as u64);
+        set_val!(ctx.instance, config.imm, i64_to_base::<E::BaseField>(imm));
+        set_val!(ctx.instance, config.overflow_bit, overflow as u64);
 
-        let sum_limbs = Value::new(sum, lk_multiplicity);
-        config.rd_written.assign_value(instance, sum_limbs);
+        let sum_limbs = Value::new(sum, ctx.lk_multiplicity);
+        config.rd_written.assign_value(ctx.instance, sum_limbs);
 
         config
             .u_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::marker::PhantomData;
 
 use ff_ext::ExtensionField;
 
@@ -11,7 +11,7 @@ use crate::{
         Instruction,
         riscv::{constants::UInt, j_insn::JInstructionConfig},
     },
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{InsnKind, PC_STEP_SIZE};
 
@@ -62,16 +62,15 @@ impl<E: ExtensionField> Instruction<E> for JalInstruction<E> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &ceno_emul::StepRecord,
     ) -> Result<(), ZKVMError> {
         config
             .j_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
-        let rd_written = Value::new(step.rd().unwrap().value.after, lk_multiplicity);
-        config.rd_written.assign_value(instance, rd_written);
+        let rd_written = Value::new(step.rd().unwrap().value.after, ctx.lk_multiplicity);
+        config.rd_written.assign_value(ctx.instance, rd_written);
 
         Ok(())
     }
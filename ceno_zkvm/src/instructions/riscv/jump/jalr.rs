@@ -15,7 +15,7 @@ use crate::{
     set_val,
     tables::InsnRecord,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{InsnKind, PC_STEP_SIZE};
 
@@ -110,8 +110,7 @@ impl<E: ExtensionField> Instruction<E> for JalrInstruction<E> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &ceno_emul::StepRecord,
     ) -> Result<(), ZKVMError> {
         let insn = step.insn();
@@ -124,16 +123,16 @@ impl<E: ExtensionField> Instruction<E> for JalrInstruction<E> {
 
         config
             .rs1_read
-            .assign_value(instance, Value::new_unchecked(rs1));
+            .assign_value(ctx.instance, Value::new_unchecked(rs1));
         config
             .rd_written
-            .assign_value(instance, Value::new(rd, lk_multiplicity));
+            .assign_value(ctx.instance, Value::new(rd, ctx.lk_multiplicity));
 
-        set_val!(instance, config.imm, i64_to_base::<E::BaseField>(imm));
+        set_val!(ctx.instance, config.imm, i64_to_base::<E::BaseField>(imm));
 
         config
             .next_pc_addr
-            .assign_instance(instance, lk_multiplicity, sum)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, sum)?;
 
         if let Some((overflow_cfg, tmp_cfg)) = &config.overflow {
             let (overflow, tmp) = match (overflowing, imm < 0) {
@@ -141,15 +140,15 @@ impl<E: ExtensionField> Instruction<E> for JalrInstruction<E> {
                 (true, false) => (E::BaseField::ONE, E::BaseField::ZERO),
                 (true, true) => (-E::BaseField::ONE, E::BaseField::ZERO),
             };
-            set_val!(instance, overflow_cfg, overflow);
-            set_val!(instance, tmp_cfg, tmp);
+            set_val!(ctx.instance, overflow_cfg, overflow);
+            set_val!(ctx.instance, tmp_cfg, tmp);
         } else {
             assert!(!overflowing, "overflow not allowed in JALR");
         }
 
         config
             .i_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::marker::PhantomData;
 
 use ceno_emul::InsnKind;
 use ff_ext::ExtensionField;
@@ -11,7 +11,7 @@ use crate::{
         Instruction,
         riscv::{constants::UInt, u_insn::UInstructionConfig},
     },
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 
 pub struct LuiConfig<E: ExtensionField> {
@@ -48,16 +48,17 @@ impl<E: ExtensionField> Instruction<E> for LuiInstruction<E> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &ceno_emul::StepRecord,
     ) -> Result<(), ZKVMError> {
         config
             .u_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
-        let rd = Value::new(step.rd().unwrap().value.after, lk_multiplicity);
-        config.rd_written.assign_limbs(instance, rd.as_u16_limbs());
+        let rd = Value::new(step.rd().unwrap().value.after, ctx.lk_multiplicity);
+        config
+            .rd_written
+            .assign_limbs(ctx.instance, rd.as_u16_limbs());
 
         Ok(())
     }
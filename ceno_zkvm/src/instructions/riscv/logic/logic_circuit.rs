@@ -13,7 +13,7 @@ use crate::{
     },
     tables::OpsTable,
     utils::split_to_u8,
-    witness::LkMultiplicity,
+    witness::{AssignmentCtx, LkMultiplicity},
 };
 use ceno_emul::{InsnKind, StepRecord};
 
@@ -50,17 +50,16 @@ impl<E: ExtensionField, I: LogicOp> Instruction<E> for LogicInstruction<E, I> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         UInt8::<E>::logic_assign::<I::OpsTable>(
-            lk_multiplicity,
+            ctx.lk_multiplicity,
             step.rs1().unwrap().value as u64,
             step.rs2().unwrap().value as u64,
         );
 
-        config.assign_instance(instance, lk_multiplicity, step)
+        config.assign_instance(ctx.instance, ctx.lk_multiplicity, step)
     }
 }
 
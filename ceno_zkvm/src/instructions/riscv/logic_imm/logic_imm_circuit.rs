@@ -13,7 +13,7 @@ use crate::{
     },
     tables::{InsnRecord, OpsTable},
     utils::split_to_u8,
-    witness::LkMultiplicity,
+    witness::{AssignmentCtx, LkMultiplicity},
 };
 use ceno_emul::{InsnKind, StepRecord};
 
@@ -50,17 +50,16 @@ impl<E: ExtensionField, I: LogicOp> Instruction<E> for LogicInstruction<E, I> {
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lkm: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         UInt8::<E>::logic_assign::<I::OpsTable>(
-            lkm,
+            ctx.lk_multiplicity,
             step.rs1().unwrap().value.into(),
             InsnRecord::imm_internal(&step.insn()) as u64,
         );
 
-        config.assign_instance(instance, lkm, step)
+        config.assign_instance(ctx.instance, ctx.lk_multiplicity, step)
     }
 }
 
@@ -14,7 +14,7 @@ use crate::{
     set_val,
     tables::InsnRecord,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{ByteAddr, InsnKind, StepRecord};
 use ff_ext::ExtensionField;
@@ -199,13 +199,12 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         let rs1 = Value::new_unchecked(step.rs1().unwrap().value);
         let memory_value = step.memory_op().unwrap().value.before;
-        let memory_read = Value::new(memory_value, lk_multiplicity);
+        let memory_read = Value::new(memory_value, ctx.lk_multiplicity);
         // imm is signed 12-bit value
         let imm = InsnRecord::imm_internal(&step.insn());
         let unaligned_addr =
@@ -215,17 +214,19 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
         let target_limb = memory_read.as_u16_limbs()[addr_low_bits[1] as usize];
         let mut target_limb_bytes = target_limb.to_le_bytes();
 
-        set_val!(instance, config.imm, i64_to_base::<E::BaseField>(imm));
+        set_val!(ctx.instance, config.imm, i64_to_base::<E::BaseField>(imm));
         config
             .im_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
-        config.rs1_read.assign_value(instance, rs1);
-        config.memory_read.assign_value(instance, memory_read);
-        config
-            .memory_addr
-            .assign_instance(instance, lk_multiplicity, unaligned_addr.into())?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
+        config.rs1_read.assign_value(ctx.instance, rs1);
+        config.memory_read.assign_value(ctx.instance, memory_read);
+        config.memory_addr.assign_instance(
+            ctx.instance,
+            ctx.lk_multiplicity,
+            unaligned_addr.into(),
+        )?;
         if let Some(&limb) = config.target_limb.as_ref() {
-            set_val!(instance, limb, E::BaseField::from(target_limb as u64));
+            set_val!(ctx.instance, limb, E::BaseField::from(target_limb as u64));
         }
         if let Some(limb_bytes) = config.target_limb_bytes.as_ref() {
             if addr_low_bits[0] == 1 {
@@ -237,8 +238,8 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
                 limb_bytes.iter(),
                 target_limb_bytes.into_iter().map(|byte| byte as u64)
             ) {
-                lk_multiplicity.assert_ux::<8>(byte);
-                set_val!(instance, col, E::BaseField::from(byte));
+                ctx.lk_multiplicity.assert_ux::<8>(byte);
+                set_val!(ctx.instance, col, E::BaseField::from(byte));
             }
         }
         let val = match I::INST_KIND {
@@ -247,7 +248,7 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for LoadInstruction<E,
             _ => 0,
         };
         if let Some(signed_ext_config) = config.signed_extend_config.as_ref() {
-            signed_ext_config.assign_instance(instance, lk_multiplicity, val)?;
+            signed_ext_config.assign_instance(ctx.instance, ctx.lk_multiplicity, val)?;
         }
 
         Ok(())
@@ -13,7 +13,7 @@ use crate::{
     set_val,
     tables::InsnRecord,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{ByteAddr, InsnKind, StepRecord};
 use ff_ext::ExtensionField;
@@ -128,32 +128,31 @@ impl<E: ExtensionField, I: RIVInstruction, const N_ZEROS: usize> Instruction<E>
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         let rs1 = Value::new_unchecked(step.rs1().unwrap().value);
         let rs2 = Value::new_unchecked(step.rs2().unwrap().value);
         let memory_op = step.memory_op().unwrap();
         let imm = InsnRecord::imm_internal(&step.insn());
-        let prev_mem_value = Value::new(memory_op.value.before, lk_multiplicity);
+        let prev_mem_value = Value::new(memory_op.value.before, ctx.lk_multiplicity);
 
         let addr = ByteAddr::from(step.rs1().unwrap().value.wrapping_add_signed(imm as i32));
         config
             .s_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
-        config.rs1_read.assign_value(instance, rs1);
-        config.rs2_read.assign_value(instance, rs2);
-        set_val!(instance, config.imm, i64_to_base::<E::BaseField>(imm));
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
+        config.rs1_read.assign_value(ctx.instance, rs1);
+        config.rs2_read.assign_value(ctx.instance, rs2);
+        set_val!(ctx.instance, config.imm, i64_to_base::<E::BaseField>(imm));
         config
             .prev_memory_value
-            .assign_value(instance, prev_mem_value);
+            .assign_value(ctx.instance, prev_mem_value);
 
         config
             .memory_addr
-            .assign_instance(instance, lk_multiplicity, addr.into())?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, addr.into())?;
         if let Some(change) = config.word_change.as_ref() {
-            change.assign_instance::<E>(instance, lk_multiplicity, step, addr.shift())?;
+            change.assign_instance::<E>(ctx.instance, ctx.lk_multiplicity, step, addr.shift())?;
         }
 
         Ok(())
@@ -99,7 +99,7 @@ use crate::{
     },
     uint::Value,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::{AssignmentCtx, LkMultiplicity},
 };
 use core::mem::MaybeUninit;
 
@@ -309,8 +309,7 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for MulhInstructionBas
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         // Read registers from step
@@ -318,24 +317,24 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for MulhInstructionBas
         let rs1_val = Value::new_unchecked(rs1);
         config
             .rs1_read
-            .assign_limbs(instance, rs1_val.as_u16_limbs());
+            .assign_limbs(ctx.instance, rs1_val.as_u16_limbs());
 
         let rs2 = step.rs2().unwrap().value;
         let rs2_val = Value::new_unchecked(rs2);
         config
             .rs2_read
-            .assign_limbs(instance, rs2_val.as_u16_limbs());
+            .assign_limbs(ctx.instance, rs2_val.as_u16_limbs());
 
         let rd = step.rd().unwrap().value.after;
-        let rd_val = Value::new(rd, lk_multiplicity);
+        let rd_val = Value::new(rd, ctx.lk_multiplicity);
         config
             .rd_written
-            .assign_limbs(instance, rd_val.as_u16_limbs());
+            .assign_limbs(ctx.instance, rd_val.as_u16_limbs());
 
         // R-type instruction
         config
             .r_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         // Assign signed values, if any, and compute low 32-bit limb of product
         let prod_lo_hi = match &config.sign_deps {
@@ -345,9 +344,11 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for MulhInstructionBas
                 rd_signed,
             } => {
                 // Signed register values
-                let rs1_s = rs1_signed.assign_instance(instance, lk_multiplicity, &rs1_val)?;
-                let rs2_s = rs2_signed.assign_instance(instance, lk_multiplicity, &rs2_val)?;
-                rd_signed.assign_instance(instance, lk_multiplicity, &rd_val)?;
+                let rs1_s =
+                    rs1_signed.assign_instance(ctx.instance, ctx.lk_multiplicity, &rs1_val)?;
+                let rs2_s =
+                    rs2_signed.assign_instance(ctx.instance, ctx.lk_multiplicity, &rs2_val)?;
+                rd_signed.assign_instance(ctx.instance, ctx.lk_multiplicity, &rd_val)?;
 
                 // only take the low part of the product
                 rs1_s.wrapping_mul(rs2_s) as u32
@@ -356,7 +357,7 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for MulhInstructionBas
                 // assign nonzero value (u32::MAX - rd)
                 let rd_f = E::BaseField::from(rd as u64);
                 let avoid_f = E::BaseField::from(u32::MAX.into());
-                constrain_rd.assign_instance(instance, rd_f, avoid_f)?;
+                constrain_rd.assign_instance(ctx.instance, rd_f, avoid_f)?;
 
                 // only take the low part of the product
                 rs1.wrapping_mul(rs2)
@@ -368,7 +369,7 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for MulhInstructionBas
 
                 let prod_hi = prod >> BIT_WIDTH;
                 let avoid_f = E::BaseField::from(u32::MAX.into());
-                constrain_rd.assign_instance(instance, E::BaseField::from(prod_hi), avoid_f)?;
+                constrain_rd.assign_instance(ctx.instance, E::BaseField::from(prod_hi), avoid_f)?;
                 prod_hi as u32
             }
             MulhSignDependencies::SU {
@@ -377,23 +378,24 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for MulhInstructionBas
                 constrain_rd,
             } => {
                 // Signed register values
-                let rs1_s = rs1_signed.assign_instance(instance, lk_multiplicity, &rs1_val)?;
-                let rd_s = rd_signed.assign_instance(instance, lk_multiplicity, &rd_val)?;
+                let rs1_s =
+                    rs1_signed.assign_instance(ctx.instance, ctx.lk_multiplicity, &rs1_val)?;
+                let rd_s = rd_signed.assign_instance(ctx.instance, ctx.lk_multiplicity, &rd_val)?;
 
                 // assign nonzero value (i32::MAX - rd)
                 let rd_f = i64_to_base(rd_s as i64);
                 let avoid_f = i64_to_base(i32::MAX.into());
-                constrain_rd.assign_instance(instance, rd_f, avoid_f)?;
+                constrain_rd.assign_instance(ctx.instance, rd_f, avoid_f)?;
 
                 // only take the low part of the product
                 (rs2).wrapping_mul(rs1_s as u32)
             }
         };
 
-        let prod_lo_hi_val = Value::new(prod_lo_hi, lk_multiplicity);
+        let prod_lo_hi_val = Value::new(prod_lo_hi, ctx.lk_multiplicity);
         config
             .prod_lo_hi
-            .assign_limbs(instance, prod_lo_hi_val.as_u16_limbs());
+            .assign_limbs(ctx.instance, prod_lo_hi_val.as_u16_limbs());
 
         Ok(())
     }
@@ -17,15 +17,19 @@ use crate::{
             *,
         },
     },
-    structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
+    structs::{
+        OpcodeAssignment, TableFixedTraces, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses,
+    },
     tables::{
-        AndTableCircuit, LtuTableCircuit, OrTableCircuit, PowTableCircuit, TableCircuit,
-        U5TableCircuit, U8TableCircuit, U14TableCircuit, U16TableCircuit, XorTableCircuit,
+        AndTableCircuit, Bits8TableCircuit, LtuTableCircuit, OrTableCircuit, PowTableCircuit,
+        TableCircuit, U5TableCircuit, U8TableCircuit, U14TableCircuit, U16TableCircuit,
+        XorTableCircuit,
     },
 };
 use ceno_emul::{
+    DecodedInstruction,
     InsnKind::{self, *},
-    Platform, StepRecord,
+    PC_WORD_SIZE, Platform, Program, StepRecord,
 };
 use divu::{DivDummy, RemDummy, RemuDummy};
 use ecall::EcallDummy;
@@ -35,13 +39,20 @@ use mul::{MulInstruction, MulhInstruction, MulhsuInstruction};
 use shift::SraInstruction;
 use slt::{SltInstruction, SltuInstruction};
 use slti::SltiuInstruction;
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 use strum::IntoEnumIterator;
 
 use super::{
     arith::AddInstruction,
     branch::BltuInstruction,
-    ecall::HaltInstruction,
+    ecall::{
+        CommitDigestInstruction, Ed25519AddInstruction, HaltInstruction, KeccakInstruction,
+        Poseidon2Instruction, Secp256k1AddInstruction, Secp256k1DoubleInstruction,
+        Sha256Instruction, Uint256AddModInstruction, Uint256MulModInstruction,
+    },
     jump::{JalInstruction, LuiInstruction},
     memory::LwInstruction,
 };
@@ -103,6 +114,16 @@ pub struct Rv32imConfig<E: ExtensionField> {
 
     // Ecall Opcodes
     pub halt_config: <HaltInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub sha256_config: <Sha256Instruction<E> as Instruction<E>>::InstructionConfig,
+    pub keccak_config: <KeccakInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub uint256_addmod_config: <Uint256AddModInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub uint256_mulmod_config: <Uint256MulModInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub secp256k1_add_config: <Secp256k1AddInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub secp256k1_double_config:
+        <Secp256k1DoubleInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub ed25519_add_config: <Ed25519AddInstruction<E> as Instruction<E>>::InstructionConfig,
+    pub poseidon2_config: <Poseidon2Instruction<E> as Instruction<E>>::InstructionConfig,
+    pub commit_digest_config: <CommitDigestInstruction<E> as Instruction<E>>::InstructionConfig,
     // Tables.
     pub u16_range_config: <U16TableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub u14_range_config: <U14TableCircuit<E> as TableCircuit<E>>::TableConfig,
@@ -113,77 +134,89 @@ pub struct Rv32imConfig<E: ExtensionField> {
     pub xor_table_config: <XorTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub ltu_config: <LtuTableCircuit<E> as TableCircuit<E>>::TableConfig,
     pub pow_config: <PowTableCircuit<E> as TableCircuit<E>>::TableConfig,
+    pub bits8_config: <Bits8TableCircuit<E> as TableCircuit<E>>::TableConfig,
 }
 
 impl<E: ExtensionField> Rv32imConfig<E> {
-    pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Self {
+    pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Result<Self, ZKVMError> {
         // opcode circuits
         // alu opcodes
-        let add_config = cs.register_opcode_circuit::<AddInstruction<E>>();
-        let sub_config = cs.register_opcode_circuit::<SubInstruction<E>>();
-        let and_config = cs.register_opcode_circuit::<AndInstruction<E>>();
-        let or_config = cs.register_opcode_circuit::<OrInstruction<E>>();
-        let xor_config = cs.register_opcode_circuit::<XorInstruction<E>>();
-        let sll_config = cs.register_opcode_circuit::<SllInstruction<E>>();
-        let srl_config = cs.register_opcode_circuit::<SrlInstruction<E>>();
-        let sra_config = cs.register_opcode_circuit::<SraInstruction<E>>();
-        let slt_config = cs.register_opcode_circuit::<SltInstruction<E>>();
-        let sltu_config = cs.register_opcode_circuit::<SltuInstruction<E>>();
-        let mul_config = cs.register_opcode_circuit::<MulInstruction<E>>();
-        let mulh_config = cs.register_opcode_circuit::<MulhInstruction<E>>();
-        let mulhsu_config = cs.register_opcode_circuit::<MulhsuInstruction<E>>();
-        let mulhu_config = cs.register_opcode_circuit::<MulhuInstruction<E>>();
-        let divu_config = cs.register_opcode_circuit::<DivUInstruction<E>>();
+        let add_config = cs.register_opcode_circuit::<AddInstruction<E>>()?;
+        let sub_config = cs.register_opcode_circuit::<SubInstruction<E>>()?;
+        let and_config = cs.register_opcode_circuit::<AndInstruction<E>>()?;
+        let or_config = cs.register_opcode_circuit::<OrInstruction<E>>()?;
+        let xor_config = cs.register_opcode_circuit::<XorInstruction<E>>()?;
+        let sll_config = cs.register_opcode_circuit::<SllInstruction<E>>()?;
+        let srl_config = cs.register_opcode_circuit::<SrlInstruction<E>>()?;
+        let sra_config = cs.register_opcode_circuit::<SraInstruction<E>>()?;
+        let slt_config = cs.register_opcode_circuit::<SltInstruction<E>>()?;
+        let sltu_config = cs.register_opcode_circuit::<SltuInstruction<E>>()?;
+        let mul_config = cs.register_opcode_circuit::<MulInstruction<E>>()?;
+        let mulh_config = cs.register_opcode_circuit::<MulhInstruction<E>>()?;
+        let mulhsu_config = cs.register_opcode_circuit::<MulhsuInstruction<E>>()?;
+        let mulhu_config = cs.register_opcode_circuit::<MulhuInstruction<E>>()?;
+        let divu_config = cs.register_opcode_circuit::<DivUInstruction<E>>()?;
 
         // alu with imm opcodes
-        let addi_config = cs.register_opcode_circuit::<AddiInstruction<E>>();
-        let andi_config = cs.register_opcode_circuit::<AndiInstruction<E>>();
-        let ori_config = cs.register_opcode_circuit::<OriInstruction<E>>();
-        let xori_config = cs.register_opcode_circuit::<XoriInstruction<E>>();
-        let slli_config = cs.register_opcode_circuit::<SlliInstruction<E>>();
-        let srli_config = cs.register_opcode_circuit::<SrliInstruction<E>>();
-        let srai_config = cs.register_opcode_circuit::<SraiInstruction<E>>();
-        let slti_config = cs.register_opcode_circuit::<SltiInstruction<E>>();
-        let sltiu_config = cs.register_opcode_circuit::<SltiuInstruction<E>>();
+        let addi_config = cs.register_opcode_circuit::<AddiInstruction<E>>()?;
+        let andi_config = cs.register_opcode_circuit::<AndiInstruction<E>>()?;
+        let ori_config = cs.register_opcode_circuit::<OriInstruction<E>>()?;
+        let xori_config = cs.register_opcode_circuit::<XoriInstruction<E>>()?;
+        let slli_config = cs.register_opcode_circuit::<SlliInstruction<E>>()?;
+        let srli_config = cs.register_opcode_circuit::<SrliInstruction<E>>()?;
+        let srai_config = cs.register_opcode_circuit::<SraiInstruction<E>>()?;
+        let slti_config = cs.register_opcode_circuit::<SltiInstruction<E>>()?;
+        let sltiu_config = cs.register_opcode_circuit::<SltiuInstruction<E>>()?;
 
         // branching opcodes
-        let beq_config = cs.register_opcode_circuit::<BeqInstruction<E>>();
-        let bne_config = cs.register_opcode_circuit::<BneInstruction<E>>();
-        let blt_config = cs.register_opcode_circuit::<BltInstruction<E>>();
-        let bltu_config = cs.register_opcode_circuit::<BltuInstruction<E>>();
-        let bge_config = cs.register_opcode_circuit::<BgeInstruction<E>>();
-        let bgeu_config = cs.register_opcode_circuit::<BgeuInstruction<E>>();
+        let beq_config = cs.register_opcode_circuit::<BeqInstruction<E>>()?;
+        let bne_config = cs.register_opcode_circuit::<BneInstruction<E>>()?;
+        let blt_config = cs.register_opcode_circuit::<BltInstruction<E>>()?;
+        let bltu_config = cs.register_opcode_circuit::<BltuInstruction<E>>()?;
+        let bge_config = cs.register_opcode_circuit::<BgeInstruction<E>>()?;
+        let bgeu_config = cs.register_opcode_circuit::<BgeuInstruction<E>>()?;
 
         // jump opcodes
-        let lui_config = cs.register_opcode_circuit::<LuiInstruction<E>>();
-        let jal_config = cs.register_opcode_circuit::<JalInstruction<E>>();
-        let jalr_config = cs.register_opcode_circuit::<JalrInstruction<E>>();
-        let auipc_config = cs.register_opcode_circuit::<AuipcInstruction<E>>();
+        let lui_config = cs.register_opcode_circuit::<LuiInstruction<E>>()?;
+        let jal_config = cs.register_opcode_circuit::<JalInstruction<E>>()?;
+        let jalr_config = cs.register_opcode_circuit::<JalrInstruction<E>>()?;
+        let auipc_config = cs.register_opcode_circuit::<AuipcInstruction<E>>()?;
 
         // memory opcodes
-        let lw_config = cs.register_opcode_circuit::<LwInstruction<E>>();
-        let lhu_config = cs.register_opcode_circuit::<LhuInstruction<E>>();
-        let lh_config = cs.register_opcode_circuit::<LhInstruction<E>>();
-        let lbu_config = cs.register_opcode_circuit::<LbuInstruction<E>>();
-        let lb_config = cs.register_opcode_circuit::<LbInstruction<E>>();
-        let sw_config = cs.register_opcode_circuit::<SwInstruction<E>>();
-        let sh_config = cs.register_opcode_circuit::<ShInstruction<E>>();
-        let sb_config = cs.register_opcode_circuit::<SbInstruction<E>>();
+        let lw_config = cs.register_opcode_circuit::<LwInstruction<E>>()?;
+        let lhu_config = cs.register_opcode_circuit::<LhuInstruction<E>>()?;
+        let lh_config = cs.register_opcode_circuit::<LhInstruction<E>>()?;
+        let lbu_config = cs.register_opcode_circuit::<LbuInstruction<E>>()?;
+        let lb_config = cs.register_opcode_circuit::<LbInstruction<E>>()?;
+        let sw_config = cs.register_opcode_circuit::<SwInstruction<E>>()?;
+        let sh_config = cs.register_opcode_circuit::<ShInstruction<E>>()?;
+        let sb_config = cs.register_opcode_circuit::<SbInstruction<E>>()?;
 
         // ecall opcodes
-        let halt_config = cs.register_opcode_circuit::<HaltInstruction<E>>();
+        let halt_config = cs.register_opcode_circuit::<HaltInstruction<E>>()?;
+        let sha256_config = cs.register_opcode_circuit::<Sha256Instruction<E>>()?;
+        let keccak_config = cs.register_opcode_circuit::<KeccakInstruction<E>>()?;
+        let uint256_addmod_config = cs.register_opcode_circuit::<Uint256AddModInstruction<E>>()?;
+        let uint256_mulmod_config = cs.register_opcode_circuit::<Uint256MulModInstruction<E>>()?;
+        let secp256k1_add_config = cs.register_opcode_circuit::<Secp256k1AddInstruction<E>>()?;
+        let secp256k1_double_config =
+            cs.register_opcode_circuit::<Secp256k1DoubleInstruction<E>>()?;
+        let ed25519_add_config = cs.register_opcode_circuit::<Ed25519AddInstruction<E>>()?;
+        let poseidon2_config = cs.register_opcode_circuit::<Poseidon2Instruction<E>>()?;
+        let commit_digest_config = cs.register_opcode_circuit::<CommitDigestInstruction<E>>()?;
         // tables
-        let u16_range_config = cs.register_table_circuit::<U16TableCircuit<E>>();
-        let u14_range_config = cs.register_table_circuit::<U14TableCircuit<E>>();
-        let u8_range_config = cs.register_table_circuit::<U8TableCircuit<E>>();
-        let u5_range_config = cs.register_table_circuit::<U5TableCircuit<E>>();
-        let and_table_config = cs.register_table_circuit::<AndTableCircuit<E>>();
-        let or_table_config = cs.register_table_circuit::<OrTableCircuit<E>>();
-        let xor_table_config = cs.register_table_circuit::<XorTableCircuit<E>>();
-        let ltu_config = cs.register_table_circuit::<LtuTableCircuit<E>>();
-        let pow_config = cs.register_table_circuit::<PowTableCircuit<E>>();
-
-        Self {
+        let u16_range_config = cs.register_table_circuit::<U16TableCircuit<E>>()?;
+        let u14_range_config = cs.register_table_circuit::<U14TableCircuit<E>>()?;
+        let u8_range_config = cs.register_table_circuit::<U8TableCircuit<E>>()?;
+        let u5_range_config = cs.register_table_circuit::<U5TableCircuit<E>>()?;
+        let and_table_config = cs.register_table_circuit::<AndTableCircuit<E>>()?;
+        let or_table_config = cs.register_table_circuit::<OrTableCircuit<E>>()?;
+        let xor_table_config = cs.register_table_circuit::<XorTableCircuit<E>>()?;
+        let ltu_config = cs.register_table_circuit::<LtuTableCircuit<E>>()?;
+        let pow_config = cs.register_table_circuit::<PowTableCircuit<E>>()?;
+        let bits8_config = cs.register_table_circuit::<Bits8TableCircuit<E>>()?;
+
+        Ok(Self {
             // alu opcodes
             add_config,
             sub_config,
@@ -233,6 +266,15 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             lb_config,
             // ecall opcodes
             halt_config,
+            sha256_config,
+            keccak_config,
+            uint256_addmod_config,
+            uint256_mulmod_config,
+            secp256k1_add_config,
+            secp256k1_double_config,
+            ed25519_add_config,
+            poseidon2_config,
+            commit_digest_config,
             // tables
             u16_range_config,
             u14_range_config,
@@ -243,73 +285,100 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             xor_table_config,
             ltu_config,
             pow_config,
-        }
+            bits8_config,
+        })
     }
 
     pub fn generate_fixed_traces(
         &self,
         cs: &ZKVMConstraintSystem<E>,
         fixed: &mut ZKVMFixedTraces<E>,
-    ) {
+    ) -> Result<(), ZKVMError> {
         // alu
-        fixed.register_opcode_circuit::<AddInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SubInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<AndInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<OrInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<XorInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SllInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SrlInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SraInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SltInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SltuInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<MulInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<MulhInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<MulhsuInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<MulhuInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<DivUInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<AddInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SubInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<AndInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<OrInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<XorInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SllInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SrlInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SraInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SltInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SltuInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<MulInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<MulhInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<MulhsuInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<MulhuInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<DivUInstruction<E>>(cs)?;
         // alu with imm
-        fixed.register_opcode_circuit::<AddiInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<AndiInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<OriInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<XoriInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SlliInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SrliInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SraiInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SltiInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SltiuInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<AddiInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<AndiInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<OriInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<XoriInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SlliInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SrliInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SraiInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SltiInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SltiuInstruction<E>>(cs)?;
         // branching
-        fixed.register_opcode_circuit::<BeqInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<BneInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<BltInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<BltuInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<BgeInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<BgeuInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<BeqInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<BneInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<BltInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<BltuInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<BgeInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<BgeuInstruction<E>>(cs)?;
         // jump
-        fixed.register_opcode_circuit::<JalInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<JalrInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<AuipcInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<LuiInstruction<E>>(cs);
+        fixed.register_opcode_circuit::<JalInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<JalrInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<AuipcInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<LuiInstruction<E>>(cs)?;
         // memory
-        fixed.register_opcode_circuit::<SwInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<ShInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<SbInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<LwInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<LhuInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<LhInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<LbuInstruction<E>>(cs);
-        fixed.register_opcode_circuit::<LbInstruction<E>>(cs);
-
-        fixed.register_opcode_circuit::<HaltInstruction<E>>(cs);
-
-        fixed.register_table_circuit::<U16TableCircuit<E>>(cs, &self.u16_range_config, &());
-        fixed.register_table_circuit::<U14TableCircuit<E>>(cs, &self.u14_range_config, &());
-        fixed.register_table_circuit::<U8TableCircuit<E>>(cs, &self.u8_range_config, &());
-        fixed.register_table_circuit::<U5TableCircuit<E>>(cs, &self.u5_range_config, &());
-        fixed.register_table_circuit::<AndTableCircuit<E>>(cs, &self.and_table_config, &());
-        fixed.register_table_circuit::<OrTableCircuit<E>>(cs, &self.or_table_config, &());
-        fixed.register_table_circuit::<XorTableCircuit<E>>(cs, &self.xor_table_config, &());
-        fixed.register_table_circuit::<LtuTableCircuit<E>>(cs, &self.ltu_config, &());
-        fixed.register_table_circuit::<PowTableCircuit<E>>(cs, &self.pow_config, &());
+        fixed.register_opcode_circuit::<SwInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<ShInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<SbInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<LwInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<LhuInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<LhInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<LbuInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<LbInstruction<E>>(cs)?;
+
+        fixed.register_opcode_circuit::<HaltInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Sha256Instruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<KeccakInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Uint256AddModInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Uint256MulModInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Secp256k1AddInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Secp256k1DoubleInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Ed25519AddInstruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<Poseidon2Instruction<E>>(cs)?;
+        fixed.register_opcode_circuit::<CommitDigestInstruction<E>>(cs)?;
+
+        // the tables are generated in parallel, see `ZKVMFixedTraces::register_table_circuits`
+        let mut generations: Vec<TableFixedTraces<E>> = vec![];
+        macro_rules! generate_table {
+            ($table:ty, $config:ident) => {
+                generations.push(Box::new(move |cache| {
+                    ZKVMFixedTraces::generate_table_fixed_traces::<$table>(
+                        cs,
+                        &self.$config,
+                        &(),
+                        cache,
+                    )
+                }));
+            };
+        }
+
+        generate_table!(U16TableCircuit<E>, u16_range_config);
+        generate_table!(U14TableCircuit<E>, u14_range_config);
+        generate_table!(U8TableCircuit<E>, u8_range_config);
+        generate_table!(U5TableCircuit<E>, u5_range_config);
+        generate_table!(AndTableCircuit<E>, and_table_config);
+        generate_table!(OrTableCircuit<E>, or_table_config);
+        generate_table!(XorTableCircuit<E>, xor_table_config);
+        generate_table!(LtuTableCircuit<E>, ltu_config);
+        generate_table!(PowTableCircuit<E>, pow_config);
+        generate_table!(Bits8TableCircuit<E>, bits8_config);
+        fixed.register_table_circuits(generations)
     }
 
     pub fn assign_opcode_circuit(
@@ -322,6 +391,15 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             .map(|insn_kind| ((insn_kind as usize), Vec::new()))
             .collect();
         let mut halt_records = Vec::new();
+        let mut sha256_records = Vec::new();
+        let mut keccak_records = Vec::new();
+        let mut uint256_addmod_records = Vec::new();
+        let mut uint256_mulmod_records = Vec::new();
+        let mut secp256k1_add_records = Vec::new();
+        let mut secp256k1_double_records = Vec::new();
+        let mut ed25519_add_records = Vec::new();
+        let mut poseidon2_records = Vec::new();
+        let mut commit_digest_records = Vec::new();
         steps.into_iter().for_each(|record| {
             let insn_kind = record.insn().codes().kind;
             match insn_kind {
@@ -329,6 +407,33 @@ impl<E: ExtensionField> Rv32imConfig<E> {
                 EANY if record.rs1().unwrap().value == Platform::ecall_halt() => {
                     halt_records.push(record);
                 }
+                EANY if record.rs1().unwrap().value == Platform::ecall_sha256() => {
+                    sha256_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_keccak_permute() => {
+                    keccak_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_uint256_addmod() => {
+                    uint256_addmod_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_uint256_mulmod() => {
+                    uint256_mulmod_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_secp256k1_add() => {
+                    secp256k1_add_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_secp256k1_double() => {
+                    secp256k1_double_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_ed25519_add() => {
+                    ed25519_add_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_poseidon2_permute() => {
+                    poseidon2_records.push(record);
+                }
+                EANY if record.rs1().unwrap().value == Platform::ecall_commit_digest() => {
+                    commit_digest_records.push(record);
+                }
                 // other type of ecalls are handled by dummy ecall instruction
                 _ => {
                     let insn_kind = insn_kind as usize;
@@ -348,13 +453,28 @@ impl<E: ExtensionField> Rv32imConfig<E> {
             }
         }
 
+        // the circuits are assigned in parallel, see `ZKVMWitnesses::assign_opcode_circuits`
+        let mut assignments: Vec<OpcodeAssignment<E>> = vec![];
+        macro_rules! assign {
+            ($instruction:ty, $config:ident, $records:expr) => {
+                let records = $records;
+                assignments.push(Box::new(move |pool| {
+                    ZKVMWitnesses::assign_opcode_instances::<$instruction>(
+                        cs,
+                        &self.$config,
+                        records,
+                        pool,
+                    )
+                }));
+            };
+        }
         macro_rules! assign_opcode {
             ($insn_kind:ident,$instruction:ty,$config:ident) => {
-                witness.assign_opcode_circuit::<$instruction>(
-                    cs,
-                    &self.$config,
-                    all_records.remove(&($insn_kind as usize)).unwrap(),
-                )?;
+                assign!(
+                    $instruction,
+                    $config,
+                    all_records.remove(&($insn_kind as usize)).unwrap()
+                );
             };
         }
         // alu
@@ -406,19 +526,98 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         assign_opcode!(SB, SbInstruction<E>, sb_config);
 
         // ecall / halt
-        witness.assign_opcode_circuit::<HaltInstruction<E>>(cs, &self.halt_config, halt_records)?;
+        assign!(HaltInstruction<E>, halt_config, halt_records);
+        assign!(Sha256Instruction<E>, sha256_config, sha256_records);
+        assign!(KeccakInstruction<E>, keccak_config, keccak_records);
+        assign!(
+            Uint256AddModInstruction<E>,
+            uint256_addmod_config,
+            uint256_addmod_records
+        );
+        assign!(
+            Uint256MulModInstruction<E>,
+            uint256_mulmod_config,
+            uint256_mulmod_records
+        );
+        assign!(
+            Secp256k1AddInstruction<E>,
+            secp256k1_add_config,
+            secp256k1_add_records
+        );
+        assign!(
+            Secp256k1DoubleInstruction<E>,
+            secp256k1_double_config,
+            secp256k1_double_records
+        );
+        assign!(
+            Ed25519AddInstruction<E>,
+            ed25519_add_config,
+            ed25519_add_records
+        );
+        assign!(Poseidon2Instruction<E>, poseidon2_config, poseidon2_records);
+        assign!(
+            CommitDigestInstruction<E>,
+            commit_digest_config,
+            commit_digest_records
+        );
+        witness.assign_opcode_circuits(assignments)?;
 
         assert_eq!(
             all_records.keys().cloned().collect::<BTreeSet<_>>(),
             // these are opcodes that haven't been implemented
-            [INVALID, DIV, REM, REMU, EANY]
+            UNSUPPORTED_INSN_KINDS
                 .into_iter()
+                .chain([EANY])
                 .map(|insn_kind| insn_kind as usize)
                 .collect::<BTreeSet<_>>(),
         );
         Ok(GroupedSteps(all_records))
     }
 
+    /// Check that the opcode circuits of `witness` have as many instances as the steps of each
+    /// kind in `histogram`, e.g. from `Profile::kind_histogram`, to catch the steps assigned to
+    /// the circuit of another kind, or to none.
+    ///
+    /// The steps of a kind are the instances of its circuit or of its dummy circuit, both named
+    /// after the kind, and the ecalls those of the ecall circuits together.
+    pub fn check_instance_counts(
+        witness: &ZKVMWitnesses<E>,
+        histogram: &BTreeMap<InsnKind, u64>,
+    ) -> Result<(), ZKVMError> {
+        let num_instances = witness.num_instances();
+        let count = |name: &String| num_instances.get(name).copied().unwrap_or(0);
+        let ecall_circuits = [
+            HaltInstruction::<E>::name(),
+            Sha256Instruction::<E>::name(),
+            KeccakInstruction::<E>::name(),
+            Uint256AddModInstruction::<E>::name(),
+            Uint256MulModInstruction::<E>::name(),
+            Secp256k1AddInstruction::<E>::name(),
+            Secp256k1DoubleInstruction::<E>::name(),
+            Ed25519AddInstruction::<E>::name(),
+            Poseidon2Instruction::<E>::name(),
+            CommitDigestInstruction::<E>::name(),
+            EcallDummy::<E>::name(),
+        ];
+
+        for kind in InsnKind::iter() {
+            let actual = if kind == EANY {
+                ecall_circuits.iter().map(count).sum()
+            } else {
+                count(&format!("{kind:?}")) + count(&format!("{kind:?}_DUMMY"))
+            };
+            let expected = histogram.get(&kind).copied().unwrap_or(0) as usize;
+            if actual != expected {
+                return Err(ZKVMError::InstanceCountMismatch {
+                    circuit: format!("{kind:?}"),
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+
     pub fn assign_table_circuit(
         &self,
         cs: &ZKVMConstraintSystem<E>,
@@ -433,6 +632,7 @@ impl<E: ExtensionField> Rv32imConfig<E> {
         witness.assign_table_circuit::<XorTableCircuit<E>>(cs, &self.xor_table_config, &())?;
         witness.assign_table_circuit::<LtuTableCircuit<E>>(cs, &self.ltu_config, &())?;
         witness.assign_table_circuit::<PowTableCircuit<E>>(cs, &self.pow_config, &())?;
+        witness.assign_table_circuit::<Bits8TableCircuit<E>>(cs, &self.bits8_config, &())?;
 
         Ok(())
     }
@@ -450,28 +650,28 @@ pub struct DummyExtraConfig<E: ExtensionField> {
 }
 
 impl<E: ExtensionField> DummyExtraConfig<E> {
-    pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Self {
-        let div_config = cs.register_opcode_circuit::<DivDummy<E>>();
-        let rem_config = cs.register_opcode_circuit::<RemDummy<E>>();
-        let remu_config = cs.register_opcode_circuit::<RemuDummy<E>>();
-        let ecall_config = cs.register_opcode_circuit::<EcallDummy<E>>();
-        Self {
+    pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Result<Self, ZKVMError> {
+        let div_config = cs.register_opcode_circuit::<DivDummy<E>>()?;
+        let rem_config = cs.register_opcode_circuit::<RemDummy<E>>()?;
+        let remu_config = cs.register_opcode_circuit::<RemuDummy<E>>()?;
+        let ecall_config = cs.register_opcode_circuit::<EcallDummy<E>>()?;
+        Ok(Self {
             div_config,
             rem_config,
             remu_config,
             ecall_config,
-        }
+        })
     }
 
     pub fn generate_fixed_traces(
         &self,
         cs: &ZKVMConstraintSystem<E>,
         fixed: &mut ZKVMFixedTraces<E>,
-    ) {
-        fixed.register_opcode_circuit::<DivDummy<E>>(cs);
-        fixed.register_opcode_circuit::<RemDummy<E>>(cs);
-        fixed.register_opcode_circuit::<RemuDummy<E>>(cs);
-        fixed.register_opcode_circuit::<EcallDummy<E>>(cs);
+    ) -> Result<(), ZKVMError> {
+        fixed.register_opcode_circuit::<DivDummy<E>>(cs)?;
+        fixed.register_opcode_circuit::<RemDummy<E>>(cs)?;
+        fixed.register_opcode_circuit::<RemuDummy<E>>(cs)?;
+        fixed.register_opcode_circuit::<EcallDummy<E>>(cs)
     }
 
     pub fn assign_opcode_circuit(
@@ -482,13 +682,18 @@ impl<E: ExtensionField> DummyExtraConfig<E> {
     ) -> Result<(), ZKVMError> {
         let mut steps = steps.0;
 
+        let mut assignments: Vec<OpcodeAssignment<E>> = vec![];
         macro_rules! assign_opcode {
             ($insn_kind:ident,$instruction:ty,$config:ident) => {
-                witness.assign_opcode_circuit::<$instruction>(
-                    cs,
-                    &self.$config,
-                    steps.remove(&($insn_kind as usize)).unwrap(),
-                )?;
+                let records = steps.remove(&($insn_kind as usize)).unwrap();
+                assignments.push(Box::new(move |pool| {
+                    ZKVMWitnesses::assign_opcode_instances::<$instruction>(
+                        cs,
+                        &self.$config,
+                        records,
+                        pool,
+                    )
+                }));
             };
         }
 
@@ -496,9 +701,154 @@ impl<E: ExtensionField> DummyExtraConfig<E> {
         assign_opcode!(REM, RemDummy<E>, rem_config);
         assign_opcode!(REMU, RemuDummy<E>, remu_config);
         assign_opcode!(EANY, EcallDummy<E>, ecall_config);
+        witness.assign_opcode_circuits(assignments)?;
 
         let _ = steps.remove(&(INVALID as usize));
         assert!(steps.is_empty());
         Ok(())
     }
 }
+
+/// The instruction kinds without a circuit in [`Rv32imConfig`]: DIV, REM and REMU are only
+/// assigned to the unconstrained circuits of [`DummyExtraConfig`], and INVALID to none.
+pub const UNSUPPORTED_INSN_KINDS: [InsnKind; 4] = [INVALID, DIV, REM, REMU];
+
+/// An instruction of a program without a circuit, see [`find_unsupported_instructions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsupportedInsn {
+    pub pc: u32,
+    pub insn_code: u32,
+    pub kind: InsnKind,
+}
+
+impl fmt::Display for UnsupportedInsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = match self.kind {
+            INVALID => "unknown".to_string(),
+            kind => kind.to_string().to_lowercase(),
+        };
+        write!(
+            f,
+            "{:#010x}: {mnemonic} ({:#010x})",
+            self.pc, self.insn_code
+        )
+    }
+}
+
+/// Disassemble the code of `program`, and list its instructions of [`UNSUPPORTED_INSN_KINDS`],
+/// before they fail or are proven unsoundly in the middle of an execution.
+///
+/// The scan is static: it also lists the words of the code which are never executed, e.g. data
+/// or padding, and it does not tell which ecalls are handled, as the ecall code is only known at
+/// runtime.
+pub fn find_unsupported_instructions(program: &Program) -> Vec<UnsupportedInsn> {
+    program
+        .instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &insn_code)| {
+            let kind = DecodedInstruction::new(insn_code).codes().kind;
+            UNSUPPORTED_INSN_KINDS
+                .contains(&kind)
+                .then_some(UnsupportedInsn {
+                    pc: program.base_address + (i * PC_WORD_SIZE) as u32,
+                    insn_code,
+                    kind,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ceno_emul::{ByteAddr, CENO_PLATFORM, Change, Profile, encode_rv32};
+    use goldilocks::GoldilocksExt2 as E;
+
+    use super::*;
+
+    #[test]
+    fn test_check_instance_counts() {
+        let mut cs = ZKVMConstraintSystem::default();
+        let add_config = cs.register_opcode_circuit::<AddInstruction<E>>().unwrap();
+        let steps = (0..3)
+            .map(|i| {
+                StepRecord::new_r_instruction(
+                    4 * (i + 1),
+                    ByteAddr(CENO_PLATFORM.pc_base()),
+                    encode_rv32(ADD, 2, 3, 4, 0),
+                    1,
+                    2,
+                    Change::new(0, 3),
+                    0,
+                )
+            })
+            .collect_vec();
+        let histogram = Profile::kind_histogram(&steps);
+
+        let mut witness = ZKVMWitnesses::default();
+        witness
+            .assign_opcode_circuit::<AddInstruction<E>>(&cs, &add_config, steps[..2].to_vec())
+            .unwrap();
+        // a step left out
+        assert!(matches!(
+            Rv32imConfig::check_instance_counts(&witness, &histogram),
+            Err(ZKVMError::InstanceCountMismatch {
+                circuit,
+                expected: 3,
+                actual: 2,
+            }) if circuit == "ADD"
+        ));
+
+        witness
+            .assign_opcode_circuit::<AddInstruction<E>>(&cs, &add_config, steps[2..].to_vec())
+            .unwrap();
+        Rv32imConfig::check_instance_counts(&witness, &histogram).unwrap();
+
+        // the steps of SUB assigned to the circuit of ADD
+        let sub_histogram = BTreeMap::from([(SUB, 3)]);
+        assert!(matches!(
+            Rv32imConfig::check_instance_counts(&witness, &sub_histogram),
+            Err(ZKVMError::InstanceCountMismatch {
+                circuit,
+                expected: 0,
+                actual: 3,
+            }) if circuit == "ADD"
+        ));
+    }
+
+    #[test]
+    fn test_find_unsupported_instructions() {
+        let pc_base = CENO_PLATFORM.pc_base();
+        let instructions = vec![
+            encode_rv32(ADD, 1, 2, 3, 0),
+            encode_rv32(DIV, 1, 2, 3, 0),
+            encode_rv32(DIVU, 1, 2, 3, 0),
+            // The custom-0 opcode.
+            0x0000_000b,
+            encode_rv32(EANY, 0, 0, 0, 0),
+        ];
+        let program = Program::new(pc_base, pc_base, instructions.clone(), Default::default());
+
+        let unsupported = find_unsupported_instructions(&program);
+        assert_eq!(unsupported, vec![
+            UnsupportedInsn {
+                pc: pc_base + 4,
+                insn_code: instructions[1],
+                kind: DIV,
+            },
+            UnsupportedInsn {
+                pc: pc_base + 12,
+                insn_code: 0x0000_000b,
+                kind: INVALID,
+            },
+        ]);
+        assert_eq!(
+            unsupported[0].to_string(),
+            format!("{:#010x}: div ({:#010x})", pc_base + 4, instructions[1])
+        );
+        assert_eq!(
+            unsupported[1].to_string(),
+            format!("{:#010x}: unknown (0x0000000b)", pc_base + 12)
+        );
+    }
+}
@@ -26,21 +26,21 @@ pub struct MmuConfig<E: ExtensionField> {
 }
 
 impl<E: ExtensionField> MmuConfig<E> {
-    pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Self {
-        let reg_config = cs.register_table_circuit::<RegTableCircuit<E>>();
+    pub fn construct_circuits(cs: &mut ZKVMConstraintSystem<E>) -> Result<Self, ZKVMError> {
+        let reg_config = cs.register_table_circuit::<RegTableCircuit<E>>()?;
 
-        let static_mem_config = cs.register_table_circuit::<StaticMemCircuit<E>>();
+        let static_mem_config = cs.register_table_circuit::<StaticMemCircuit<E>>()?;
 
-        let public_io_config = cs.register_table_circuit::<PubIOCircuit<E>>();
-        let hints_config = cs.register_table_circuit::<HintsCircuit<E>>();
+        let public_io_config = cs.register_table_circuit::<PubIOCircuit<E>>()?;
+        let hints_config = cs.register_table_circuit::<HintsCircuit<E>>()?;
 
-        Self {
+        Ok(Self {
             reg_config,
             static_mem_config,
             public_io_config,
             hints_config,
             params: cs.params.clone(),
-        }
+        })
     }
 
     pub fn generate_fixed_traces(
@@ -50,7 +50,7 @@ impl<E: ExtensionField> MmuConfig<E> {
         reg_init: &[MemInitRecord],
         static_mem_init: &[MemInitRecord],
         io_addrs: &[Addr],
-    ) {
+    ) -> Result<(), ZKVMError> {
         assert!(
             chain!(
                 static_mem_init.iter_addresses(),
@@ -62,16 +62,40 @@ impl<E: ExtensionField> MmuConfig<E> {
             "memory addresses must be unique"
         );
 
-        fixed.register_table_circuit::<RegTableCircuit<E>>(cs, &self.reg_config, reg_init);
-
-        fixed.register_table_circuit::<StaticMemCircuit<E>>(
-            cs,
-            &self.static_mem_config,
-            static_mem_init,
-        );
-
-        fixed.register_table_circuit::<PubIOCircuit<E>>(cs, &self.public_io_config, io_addrs);
-        fixed.register_table_circuit::<HintsCircuit<E>>(cs, &self.hints_config, &());
+        fixed.register_table_circuits(vec![
+            Box::new(move |cache| {
+                ZKVMFixedTraces::generate_table_fixed_traces::<RegTableCircuit<E>>(
+                    cs,
+                    &self.reg_config,
+                    reg_init,
+                    cache,
+                )
+            }),
+            Box::new(move |cache| {
+                ZKVMFixedTraces::generate_table_fixed_traces::<StaticMemCircuit<E>>(
+                    cs,
+                    &self.static_mem_config,
+                    static_mem_init,
+                    cache,
+                )
+            }),
+            Box::new(move |cache| {
+                ZKVMFixedTraces::generate_table_fixed_traces::<PubIOCircuit<E>>(
+                    cs,
+                    &self.public_io_config,
+                    io_addrs,
+                    cache,
+                )
+            }),
+            Box::new(move |cache| {
+                ZKVMFixedTraces::generate_table_fixed_traces::<HintsCircuit<E>>(
+                    cs,
+                    &self.hints_config,
+                    &(),
+                    cache,
+                )
+            }),
+        ])
     }
 
     pub fn assign_table_circuit(
@@ -10,6 +10,7 @@ use crate::{
     gadgets::{AssertLTConfig, SignedExtendConfig},
     instructions::Instruction,
     set_val,
+    witness::AssignmentCtx,
 };
 
 use super::{RIVInstruction, constants::UInt, r_insn::RInstructionConfig};
@@ -163,33 +164,32 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ShiftLogicalInstru
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut crate::witness::LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &ceno_emul::StepRecord,
     ) -> Result<(), crate::error::ZKVMError> {
         // rs2 & its derived values
         let rs2_read = Value::new_unchecked(step.rs2().unwrap().value);
         let rs2_low5 = rs2_read.as_u64() & 0b11111;
-        lk_multiplicity.assert_ux::<5>(rs2_low5);
-        lk_multiplicity.lookup_pow2(rs2_low5);
+        ctx.lk_multiplicity.assert_ux::<5>(rs2_low5);
+        ctx.lk_multiplicity.lookup_pow2(rs2_low5);
 
         let pow2_rs2_low5 = 1u64 << rs2_low5;
 
         let rs2_high = Value::new(
             ((rs2_read.as_u64() - rs2_low5) >> 5) as u32,
-            lk_multiplicity,
+            ctx.lk_multiplicity,
         );
-        config.rs2_high.assign_value(instance, rs2_high);
-        config.rs2_read.assign_value(instance, rs2_read);
+        config.rs2_high.assign_value(ctx.instance, rs2_high);
+        config.rs2_read.assign_value(ctx.instance, rs2_read);
 
-        set_val!(instance, config.pow2_rs2_low5, pow2_rs2_low5);
-        set_val!(instance, config.rs2_low5, rs2_low5);
+        set_val!(ctx.instance, config.pow2_rs2_low5, pow2_rs2_low5);
+        set_val!(ctx.instance, config.rs2_low5, rs2_low5);
 
         // rs1
         let rs1_read = Value::new_unchecked(step.rs1().unwrap().value);
 
         // rd
-        let rd_written = Value::new(step.rd().unwrap().value.after, lk_multiplicity);
+        let rd_written = Value::new(step.rd().unwrap().value.after, ctx.lk_multiplicity);
 
         // outflow
         let outflow = match I::INST_KIND {
@@ -197,11 +197,13 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ShiftLogicalInstru
             InsnKind::SRL => rs1_read.as_u64() & (pow2_rs2_low5 - 1),
             InsnKind::SRA => {
                 let Some(signed_ext_config) = config.signed_extend_config.as_ref() else {
-                    Err(ZKVMError::CircuitError)?
+                    Err(ZKVMError::MissingConfig {
+                        path: format!("{}/signed_extend_config", Self::name()),
+                    })?
                 };
                 signed_ext_config.assign_instance(
-                    instance,
-                    lk_multiplicity,
+                    ctx.instance,
+                    ctx.lk_multiplicity,
                     *rs1_read.as_u16_limbs().last().unwrap() as u64,
                 )?;
                 rs1_read.as_u64() & (pow2_rs2_low5 - 1)
@@ -209,21 +211,21 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ShiftLogicalInstru
             _ => unreachable!("Unsupported instruction kind {:?}", I::INST_KIND),
         };
 
-        set_val!(instance, config.outflow, outflow);
+        set_val!(ctx.instance, config.outflow, outflow);
 
-        config.rs1_read.assign_value(instance, rs1_read);
-        config.rd_written.assign_value(instance, rd_written);
+        config.rs1_read.assign_value(ctx.instance, rs1_read);
+        config.rd_written.assign_value(ctx.instance, rd_written);
 
         config.assert_lt_config.assign_instance(
-            instance,
-            lk_multiplicity,
+            ctx.instance,
+            ctx.lk_multiplicity,
             outflow,
             pow2_rs2_low5,
         )?;
 
         config
             .r_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
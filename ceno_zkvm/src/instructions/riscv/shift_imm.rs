@@ -11,7 +11,7 @@ use crate::{
     },
     set_val,
     tables::InsnRecord,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use ceno_emul::{InsnKind, StepRecord};
 use ff_ext::ExtensionField;
@@ -144,26 +144,25 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ShiftImmInstructio
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
         // imm_internal is a precomputed 2**shift.
         let imm = InsnRecord::imm_internal(&step.insn()) as u64;
         let rs1_read = Value::new_unchecked(step.rs1().unwrap().value);
-        let rd_written = Value::new(step.rd().unwrap().value.after, lk_multiplicity);
+        let rd_written = Value::new(step.rd().unwrap().value.after, ctx.lk_multiplicity);
 
-        set_val!(instance, config.imm, imm);
-        config.rs1_read.assign_value(instance, rs1_read.clone());
-        config.rd_written.assign_value(instance, rd_written);
+        set_val!(ctx.instance, config.imm, imm);
+        config.rs1_read.assign_value(ctx.instance, rs1_read.clone());
+        config.rd_written.assign_value(ctx.instance, rd_written);
 
         let outflow = match I::INST_KIND {
             InsnKind::SLLI => (rs1_read.as_u64() * imm) >> UInt::<E>::TOTAL_BITS,
             InsnKind::SRAI | InsnKind::SRLI => {
                 if I::INST_KIND == InsnKind::SRAI {
                     config.is_lt_config.as_ref().unwrap().assign_instance(
-                        instance,
-                        lk_multiplicity,
+                        ctx.instance,
+                        ctx.lk_multiplicity,
                         *rs1_read.as_u16_limbs().last().unwrap() as u64,
                     )?;
                 }
@@ -173,14 +172,14 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for ShiftImmInstructio
             _ => unreachable!("Unsupported instruction kind {:?}", I::INST_KIND),
         };
 
-        set_val!(instance, config.outflow, outflow);
+        set_val!(ctx.instance, config.outflow, outflow);
         config
             .assert_lt_config
-            .assign_instance(instance, lk_multiplicity, outflow, imm)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, outflow, imm)?;
 
         config
             .i_insn
-            .assign_instance(instance, lk_multiplicity, step)?;
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         Ok(())
     }
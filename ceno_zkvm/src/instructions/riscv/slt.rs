@@ -14,9 +14,8 @@ use crate::{
     gadgets::{IsLtConfig, SignedLtConfig},
     instructions::Instruction,
     uint::Value,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
-use core::mem::MaybeUninit;
 
 pub struct SetLessThanInstruction<E, I>(PhantomData<(E, I)>);
 
@@ -84,7 +83,7 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for SetLessThanInstruc
 
         let r_insn = RInstructionConfig::<E>::construct_circuit(
             cb,
-            InsnKind::SLT,
+            I::INST_KIND,
             rs1_read.register_expr(),
             rs2_read.register_expr(),
             rd_written.register_expr(),
@@ -101,11 +100,12 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for SetLessThanInstruc
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lkm: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
-        config.r_insn.assign_instance(instance, lkm, step)?;
+        config
+            .r_insn
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         let rs1 = step.rs1().unwrap().value;
         let rs2 = step.rs2().unwrap().value;
@@ -114,17 +114,20 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for SetLessThanInstruc
         let rs2_read = Value::new_unchecked(rs2);
         config
             .rs1_read
-            .assign_limbs(instance, rs1_read.as_u16_limbs());
+            .assign_limbs(ctx.instance, rs1_read.as_u16_limbs());
         config
             .rs2_read
-            .assign_limbs(instance, rs2_read.as_u16_limbs());
+            .assign_limbs(ctx.instance, rs2_read.as_u16_limbs());
 
         match &config.deps {
-            SetLessThanDependencies::Slt { signed_lt } => {
-                signed_lt.assign_instance(instance, lkm, rs1 as SWord, rs2 as SWord)?
-            }
+            SetLessThanDependencies::Slt { signed_lt } => signed_lt.assign_instance(
+                ctx.instance,
+                ctx.lk_multiplicity,
+                rs1 as SWord,
+                rs2 as SWord,
+            )?,
             SetLessThanDependencies::Sltu { is_lt } => {
-                is_lt.assign_instance(instance, lkm, rs1.into(), rs2.into())?
+                is_lt.assign_instance(ctx.instance, ctx.lk_multiplicity, rs1.into(), rs2.into())?
             }
         }
 
@@ -160,7 +163,7 @@ mod test {
             .unwrap()
             .unwrap();
 
-        let insn_code = encode_rv32(InsnKind::SLT, 2, 3, 4, 0);
+        let insn_code = encode_rv32(I::INST_KIND, 2, 3, 4, 0);
         let (raw_witin, lkm) = SetLessThanInstruction::<_, I>::assign_instances(
             &config,
             cb.cs.num_witin as usize,
@@ -18,7 +18,7 @@ use crate::{
     tables::InsnRecord,
     uint::Value,
     utils::i64_to_base,
-    witness::LkMultiplicity,
+    witness::AssignmentCtx,
 };
 use core::mem::MaybeUninit;
 
@@ -96,36 +96,43 @@ impl<E: ExtensionField, I: RIVInstruction> Instruction<E> for SetLessThanImmInst
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        lkm: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         step: &StepRecord,
     ) -> Result<(), ZKVMError> {
-        config.i_insn.assign_instance(instance, lkm, step)?;
+        config
+            .i_insn
+            .assign_instance(ctx.instance, ctx.lk_multiplicity, step)?;
 
         let rs1 = step.rs1().unwrap().value;
         let rs1_value = Value::new_unchecked(rs1 as Word);
         config
             .rs1_read
-            .assign_value(instance, Value::new_unchecked(rs1));
+            .assign_value(ctx.instance, Value::new_unchecked(rs1));
 
         let imm = InsnRecord::imm_internal(&step.insn());
-        set_val!(instance, config.imm, i64_to_base::<E::BaseField>(imm));
+        set_val!(ctx.instance, config.imm, i64_to_base::<E::BaseField>(imm));
 
         match I::INST_KIND {
             InsnKind::SLTIU => {
-                config
-                    .lt
-                    .assign_instance(instance, lkm, rs1 as u64, imm as u64)?;
+                config.lt.assign_instance(
+                    ctx.instance,
+                    ctx.lk_multiplicity,
+                    rs1 as u64,
+                    imm as u64,
+                )?;
             }
             InsnKind::SLTI => {
                 config.is_rs1_neg.as_ref().unwrap().assign_instance(
-                    instance,
-                    lkm,
+                    ctx.instance,
+                    ctx.lk_multiplicity,
                     *rs1_value.as_u16_limbs().last().unwrap() as u64,
                 )?;
-                config
-                    .lt
-                    .assign_instance_signed(instance, lkm, rs1 as SWord, imm as SWord)?;
+                config.lt.assign_instance_signed(
+                    ctx.instance,
+                    ctx.lk_multiplicity,
+                    rs1 as SWord,
+                    imm as SWord,
+                )?;
             }
             _ => unreachable!("Unsupported instruction kind {:?}", I::INST_KIND),
         }
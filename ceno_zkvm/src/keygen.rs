@@ -1,9 +1,14 @@
 use crate::{
     error::ZKVMError,
+    scheme::constants::CIRCUIT_VERSION,
+    serialization::{FORMAT_VERSION, from_bytes, to_bytes},
     structs::{ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMProvingKey},
 };
 use ff_ext::ExtensionField;
+use goldilocks::SmallField;
 use mpcs::PolynomialCommitmentScheme;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{any::type_name, fs, io, path::Path};
 
 impl<E: ExtensionField> ZKVMConstraintSystem<E> {
     pub fn key_gen<PCS: PolynomialCommitmentScheme<E>>(
@@ -35,4 +40,159 @@ impl<E: ExtensionField> ZKVMConstraintSystem<E> {
 
         Ok(vm_pk)
     }
+
+    /// The Blake3 digest of the circuits and their fixed traces, which together determine the
+    /// proving key for given PCS parameters. It keys the cache of `ZKVMProvingKey::setup`.
+    pub fn key_digest(&self, vm_fixed_traces: &ZKVMFixedTraces<E>) -> Result<[u8; 32], ZKVMError>
+    where
+        E::BaseField: Serialize,
+    {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&FORMAT_VERSION.to_le_bytes());
+        hasher.update(&CIRCUIT_VERSION.to_le_bytes());
+        for (name, cs) in &self.circuit_css {
+            hash_bytes(&mut hasher, name.as_bytes());
+            hash_bytes(&mut hasher, &to_bytes(cs)?);
+        }
+        hash_bytes(
+            &mut hasher,
+            &to_bytes(&(
+                &self.initial_global_state_expr,
+                &self.finalize_global_state_expr,
+            ))?,
+        );
+        for (name, fixed_traces) in &vm_fixed_traces.circuit_fixed_traces {
+            hash_bytes(&mut hasher, name.as_bytes());
+            for row in fixed_traces.iter().flat_map(|traces| traces.iter_rows()) {
+                for v in row {
+                    hasher.update(&unsafe { v.assume_init() }.to_canonical_u64().to_le_bytes());
+                }
+            }
+        }
+        Ok(*hasher.finalize().as_bytes())
+    }
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PCS> {
+    /// Key generation, which loads the key from `cache_dir` instead if an earlier run cached it
+    /// for the same circuits, fixed traces and PCS parameters. A generated key is written to the
+    /// cache, and an unreadable cache entry is regenerated.
+    pub fn setup(
+        cs: ZKVMConstraintSystem<E>,
+        pp: PCS::ProverParam,
+        vp: PCS::VerifierParam,
+        vm_fixed_traces: ZKVMFixedTraces<E>,
+        cache_dir: Option<&Path>,
+    ) -> Result<Self, ZKVMError>
+    where
+        E: DeserializeOwned,
+        E::BaseField: Serialize + DeserializeOwned,
+    {
+        let Some(cache_dir) = cache_dir else {
+            return cs.key_gen(pp, vp, vm_fixed_traces);
+        };
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&cs.key_digest(&vm_fixed_traces)?);
+        hash_bytes(&mut hasher, type_name::<PCS>().as_bytes());
+        hash_bytes(&mut hasher, &to_bytes(&vp)?);
+        let path = cache_dir.join(format!("pk_{}.json", hasher.finalize().to_hex()));
+
+        match fs::read(&path) {
+            Ok(bytes) => match from_bytes(&bytes) {
+                Ok(pk) => {
+                    tracing::info!("loaded proving key from {}", path.display());
+                    return Ok(pk);
+                }
+                Err(e) => tracing::warn!("regenerating proving key {}: {e:?}", path.display()),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(ZKVMError::IOError(e)),
+        }
+
+        let pk = cs.key_gen(pp, vp, vm_fixed_traces)?;
+        fs::create_dir_all(cache_dir).map_err(ZKVMError::IOError)?;
+        fs::write(&path, to_bytes(&pk)?).map_err(ZKVMError::IOError)?;
+        tracing::info!("cached proving key at {}", path.display());
+        Ok(pk)
+    }
+}
+
+/// Hash `bytes` prefixed by their length, so that consecutive fields cannot run into each other.
+fn hash_bytes(hasher: &mut blake3::Hasher, bytes: &[u8]) {
+    hasher.update(&(bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{Instruction, riscv::arith::AddInstruction},
+        scheme::constants::MIN_BASE_FIELD_BITS,
+        tables::{AndTableCircuit, PowTableCircuit, TableCircuit, U8TableCircuit, U16TableCircuit},
+        utils::field_bits,
+    };
+    use goldilocks::{Goldilocks, GoldilocksExt2 as E};
+    use mpcs::BasefoldDefault;
+
+    #[test]
+    fn test_field_bits() {
+        assert_eq!(field_bits::<Goldilocks>(), 64);
+        assert!(field_bits::<Goldilocks>() >= MIN_BASE_FIELD_BITS);
+
+        // a 31-bit field such as BabyBear or Mersenne31 holds the range and byte tables, but not
+        // the tables of words nor the instruction circuits
+        let narrow = 31;
+        assert!(U16TableCircuit::<E>::min_field_bits() <= narrow);
+        assert!(AndTableCircuit::<E>::min_field_bits() <= narrow);
+        assert_eq!(PowTableCircuit::<E>::min_field_bits(), 33);
+        assert!(AddInstruction::<E>::min_field_bits() > narrow);
+    }
+
+    #[test]
+    fn test_setup_key_cache() {
+        type Pcs = BasefoldDefault<E>;
+        let param = Pcs::setup(1 << 13).unwrap();
+        let (pp, vp) = Pcs::trim(param, 1 << 13).unwrap();
+
+        let mut zkvm_cs = ZKVMConstraintSystem::default();
+        let config = zkvm_cs
+            .register_table_circuit::<U8TableCircuit<E>>()
+            .unwrap();
+        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+        // the digest covers the fixed traces
+        let digest = zkvm_cs.key_digest(&zkvm_fixed_traces).unwrap();
+        zkvm_fixed_traces
+            .register_table_circuit::<U8TableCircuit<E>>(&zkvm_cs, &config, &())
+            .unwrap();
+        assert_ne!(zkvm_cs.key_digest(&zkvm_fixed_traces).unwrap(), digest);
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let setup = || {
+            ZKVMProvingKey::<E, Pcs>::setup(
+                zkvm_cs.clone(),
+                pp.clone(),
+                vp.clone(),
+                zkvm_fixed_traces.clone(),
+                Some(cache_dir.path()),
+            )
+            .unwrap()
+        };
+        let vk_bytes = |pk: &ZKVMProvingKey<E, Pcs>| to_bytes(&pk.get_vk()).unwrap();
+
+        // generated, then loaded
+        let pk = setup();
+        let cached = fs::read_dir(cache_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(vk_bytes(&setup()), vk_bytes(&pk));
+
+        // an unreadable entry is regenerated
+        fs::write(&cached[0], b"stale").unwrap();
+        assert_eq!(vk_bytes(&setup()), vk_bytes(&pk));
+        assert!(from_bytes::<ZKVMProvingKey<E, Pcs>>(&fs::read(&cached[0]).unwrap()).is_ok());
+    }
 }
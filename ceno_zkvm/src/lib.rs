@@ -7,14 +7,21 @@
 pub mod error;
 pub mod instructions;
 pub mod scheme;
+pub mod sdk;
 pub mod tables;
 pub use utils::u64vec;
+pub mod aggregation;
 mod chip_handler;
 pub mod circuit_builder;
+pub mod diff;
 pub mod e2e;
 pub mod expression;
+pub mod fuzz;
 pub mod gadgets;
 mod keygen;
+pub mod lint;
+pub mod prover_service;
+pub mod serialization;
 pub mod state;
 pub mod stats;
 pub mod structs;
@@ -23,6 +30,8 @@ mod utils;
 mod virtual_polys;
 mod witness;
 
+pub use sdk::{ProveOptions, keygen, prove, verify};
 pub use structs::ROMType;
 pub use uint::Value;
 pub use utils::with_panic_hook;
+pub use witness::{SpillAllocator, WitnessPool, set_spill_threshold};
@@ -0,0 +1,233 @@
+//! Static checks for common unsoundness patterns, see [`lint_circuit`].
+
+use crate::{
+    circuit_builder::{ConstraintSystem, SetTableAddrType},
+    expression::Expression,
+    structs::{ROMType, WitnessId, ZKVMConstraintSystem},
+};
+use ff_ext::ExtensionField;
+use itertools::{Itertools, chain};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter, Result},
+};
+
+/// A suspicious pattern in a constraint system. Witnesses are named by path.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintWarning {
+    /// A witness which appears in no constraint, lookup, read, write or table record.
+    UnconstrainedWitin(String),
+    /// A witness used as the condition of a conditional constraint, but never asserted boolean.
+    UncheckedCondition(String),
+    /// A UInt carry which is neither asserted boolean nor range checked.
+    UncheckedCarry(String),
+    /// A lookup into a ROM type which no registered table circuit serves.
+    UnregisteredLookup(String, ROMType),
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            LintWarning::UnconstrainedWitin(path) => write!(f, "unconstrained witness {path}"),
+            LintWarning::UncheckedCondition(path) => {
+                write!(f, "condition {path} is not asserted boolean")
+            }
+            LintWarning::UncheckedCarry(path) => write!(f, "carry {path} is not range checked"),
+            LintWarning::UnregisteredLookup(path, rom_type) => {
+                write!(f, "lookup {path} into unregistered table {rom_type:?}")
+            }
+        }
+    }
+}
+
+/// Tables whose rows bound each item of a lookup on its own.
+fn is_range_table(rom_type: ROMType) -> bool {
+    matches!(
+        rom_type,
+        ROMType::U5 | ROMType::U8 | ROMType::U14 | ROMType::U16 | ROMType::Bits8
+    )
+}
+
+/// Check a single circuit for witnesses which are never constrained, conditions which are never
+/// asserted boolean, and UInt carries which are never range checked.
+///
+/// Conditions and carries are those recorded by the `CircuitBuilder` helpers, so constraints
+/// written by hand are only covered by the first check. Lookups into unregistered tables can only
+/// be found across circuits, by [`ZKVMConstraintSystem::lint`].
+pub fn lint_circuit<E: ExtensionField>(cs: &ConstraintSystem<E>) -> Vec<LintWarning> {
+    let mut used: BTreeSet<WitnessId> = chain!(
+        &cs.assert_zero_expressions,
+        &cs.assert_zero_sumcheck_expressions,
+        &cs.r_expressions,
+        &cs.w_expressions,
+        &cs.lk_expressions,
+        cs.lk_table_expressions
+            .iter()
+            .flat_map(|table| [&table.multiplicity, &table.values]),
+        cs.r_table_expressions.iter().map(|table| &table.expr),
+        cs.w_table_expressions.iter().map(|table| &table.expr),
+    )
+    .flat_map(Expression::witins_used)
+    .collect();
+    used.extend(
+        chain!(&cs.r_table_expressions, &cs.w_table_expressions).filter_map(|table| {
+            match &table.table_spec.addr_type {
+                SetTableAddrType::DynamicAddr(addr) => Some(addr.addr_witin_id as WitnessId),
+                SetTableAddrType::FixedAddr => None,
+            }
+        }),
+    );
+
+    let mut range_checked: BTreeSet<WitnessId> = chain!(&cs.bit_witins, &cs.range_checked_witins)
+        .copied()
+        .collect();
+    for (_, (_, record)) in cs
+        .lk_expressions_namespace_map
+        .iter()
+        .zip(&cs.lk_expressions_items_map)
+        .filter(|(_, (rom_type, _))| is_range_table(*rom_type))
+    {
+        range_checked.extend(record.iter().filter_map(|item| match item {
+            Expression::WitIn(id) => Some(*id),
+            _ => None,
+        }));
+    }
+
+    let path = |id: WitnessId| cs.witin_namespace_map[id as usize].clone();
+    chain!(
+        (0..cs.num_witin)
+            .filter(|id| !used.contains(id))
+            .map(|id| LintWarning::UnconstrainedWitin(path(id))),
+        cs.cond_witins
+            .iter()
+            .filter(|id| !cs.bit_witins.contains(id))
+            .map(|id| LintWarning::UncheckedCondition(path(*id))),
+        cs.carry_witins
+            .iter()
+            .filter(|carry| !range_checked.contains(&carry.id))
+            .map(|carry| LintWarning::UncheckedCarry(path(carry.id))),
+    )
+    .sorted()
+    .collect()
+}
+
+impl<E: ExtensionField> ZKVMConstraintSystem<E> {
+    /// [`lint_circuit`] on every registered circuit, plus lookups into ROM types which no
+    /// registered table circuit serves. Circuits without warnings are left out.
+    pub fn lint(&self) -> BTreeMap<String, Vec<LintWarning>> {
+        let served: BTreeSet<ROMType> = self
+            .get_css()
+            .values()
+            .flat_map(|cs| &cs.lk_table_expressions)
+            .flat_map(|table| table.rom_types.iter().copied())
+            .collect();
+
+        self.get_css()
+            .iter()
+            .map(|(name, cs)| {
+                let unregistered = cs
+                    .lk_expressions_namespace_map
+                    .iter()
+                    .zip(&cs.lk_expressions_items_map)
+                    .filter(|(_, (rom_type, _))| !served.contains(rom_type))
+                    .map(|(path, (rom_type, _))| {
+                        LintWarning::UnregisteredLookup(path.clone(), *rom_type)
+                    });
+                let warnings = chain!(lint_circuit(cs), unregistered)
+                    .sorted()
+                    .collect_vec();
+                (name.clone(), warnings)
+            })
+            .filter(|(_, warnings)| !warnings.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::CircuitBuilder,
+        expression::ToExpr,
+        instructions::riscv::{DummyExtraConfig, MmuConfig, Rv32imConfig},
+        structs::ProgramParams,
+        tables::{ProgramTableCircuit, U8TableCircuit},
+        uint::UIntLimbs,
+    };
+    use goldilocks::GoldilocksExt2 as E;
+
+    #[test]
+    fn test_lint_circuit() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+
+        let _unused = cb.create_witin(|| "unused");
+        let cond = cb.create_witin(|| "cond");
+        let bit = cb.create_witin(|| "bit");
+        let x = cb.create_witin(|| "x");
+        cb.assert_bit(|| "bit", bit.expr()).unwrap();
+        cb.require_zero_if(|| "x = 0 if cond", cond.expr(), x.expr())
+            .unwrap();
+        cb.require_zero_if(|| "x = 0 if bit", bit.expr(), x.expr())
+            .unwrap();
+
+        // carries of an addition are asserted boolean
+        let a = UIntLimbs::<32, 16, E>::new(|| "a", &mut cb).unwrap();
+        let b = UIntLimbs::<32, 16, E>::new(|| "b", &mut cb).unwrap();
+        a.add(|| "a + b", &mut cb, &b, true).unwrap();
+        // an unchecked carry
+        let carry = cb.create_witin(|| "carry");
+        cb.record_carries(&[carry]);
+        cb.require_zero(|| "carry = x", carry.expr() - x.expr())
+            .unwrap();
+
+        assert_eq!(lint_circuit(&cs), vec![
+            LintWarning::UnconstrainedWitin("riscv/unused".to_string()),
+            LintWarning::UncheckedCondition("riscv/cond".to_string()),
+            LintWarning::UncheckedCarry("riscv/carry".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_lint_unregistered_lookup() {
+        let mut zkvm_cs = ZKVMConstraintSystem::<E>::default();
+        let mut cs = ConstraintSystem::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        cb.assert_ux::<_, _, 8>(|| "x_u8", x.expr()).unwrap();
+        cb.assert_ux::<_, _, 16>(|| "x_u16", x.expr()).unwrap();
+        zkvm_cs.circuit_css.insert("test".to_string(), cs);
+        zkvm_cs
+            .register_table_circuit::<U8TableCircuit<E>>()
+            .unwrap();
+
+        assert_eq!(
+            zkvm_cs.lint(),
+            BTreeMap::from([("test".to_string(), vec![LintWarning::UnregisteredLookup(
+                "riscv/x_u16".to_string(),
+                ROMType::U16
+            )])])
+        );
+    }
+
+    #[test]
+    fn test_lint_registered_circuits() {
+        let mut zkvm_cs = ZKVMConstraintSystem::<E>::new_with_platform(ProgramParams::default());
+        Rv32imConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+        MmuConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+        DummyExtraConfig::<E>::construct_circuits(&mut zkvm_cs).unwrap();
+        zkvm_cs
+            .register_table_circuit::<ProgramTableCircuit<E>>()
+            .unwrap();
+
+        let warnings = zkvm_cs.lint();
+        assert!(
+            warnings.is_empty(),
+            "{}",
+            warnings
+                .iter()
+                .flat_map(|(name, warnings)| warnings.iter().map(move |w| format!("{name}: {w}")))
+                .join("\n")
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! Proving an execution across processes: a [`Coordinator`] splits it into segments, see
+//! `e2e::run_e2e_segments`, and dispatches the proving of each segment, a job, to workers which
+//! [`serve`] them over TCP.
+//!
+//! The coordinator emulates the execution once, as it dispatches the jobs. A job carries the
+//! guest, its input, and the snapshot of the machine at the start of its segment, from which the
+//! worker runs the segment alone. A worker keeps the proving key of the task of its last job, so
+//! that the jobs of one task generate it once. The coordinator collects the segment proofs in
+//! order, for `e2e::verify_segments`.
+//!
+//! Requests and responses are JSON messages, each prefixed by its length as 4 little-endian
+//! bytes, of at most [`MAX_MESSAGE_LEN`] bytes. The connection to a worker carries one job at a
+//! time. Workers do not authenticate coordinators, and prove whatever they are sent: bind them to
+//! localhost, or to an interface of a trusted network only.
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    panic::{self, AssertUnwindSafe},
+    sync::Mutex,
+    thread,
+};
+
+use ceno_emul::{CENO_PLATFORM, Program, Snapshot};
+use ff_ext::ExtensionField;
+use goldilocks::{Goldilocks, GoldilocksExt2};
+use mpcs::PolynomialCommitmentScheme;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use transcript::TranscriptHash;
+
+use crate::{
+    e2e::{SegmentProof, SegmentProver, SegmentSnapshots},
+    error::ZKVMError,
+    scheme::pcs::PcsKind,
+    serialization::{from_bytes, to_bytes},
+    with_pcs,
+    witness::WitnessPool,
+};
+
+/// An execution to prove by segments, on `CENO_PLATFORM`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProvingTask {
+    pub elf: Vec<u8>,
+    /// The hints, as words.
+    pub hints: Vec<u32>,
+    pub stack_size: u32,
+    pub heap_size: u32,
+    pub max_steps: usize,
+    /// The number of steps of a segment, but the last.
+    pub segment_steps: usize,
+    pub pcs: PcsKind,
+    pub transcript_hash: TranscriptHash,
+}
+
+impl ProvingTask {
+    fn program(&self) -> Result<Program, ZKVMError> {
+        Program::load_elf(&self.elf, u32::MAX)
+            .map_err(|err| ZKVMError::GuestError(format!("invalid ELF: {err}")))
+    }
+
+    /// Whether the proving key of `self` is the one of `other`.
+    fn same_key(&self, other: &ProvingTask) -> bool {
+        self.elf == other.elf
+            && self.stack_size == other.stack_size
+            && self.heap_size == other.heap_size
+            && self.pcs == other.pcs
+            && self.transcript_hash == other.transcript_hash
+    }
+}
+
+/// The proving of one segment.
+#[derive(Serialize, Deserialize)]
+struct Job {
+    segment: usize,
+    /// The machine at the start of the segment.
+    start: Snapshot,
+    /// The number of steps of the segment.
+    steps: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+enum Request {
+    /// Prove `job` of `task`.
+    Prove { task: ProvingTask, job: Job },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Response {
+    /// The `SegmentProof`, as serialized by `to_bytes`.
+    Proved(Vec<u8>),
+    /// The job failed, e.g. because the guest does not run, and would fail on any worker.
+    Failed(String),
+}
+
+/// The longest message, in bytes, which a worker or coordinator reads, so that a peer cannot make
+/// it allocate more.
+pub const MAX_MESSAGE_LEN: usize = 1 << 28;
+
+fn write_message<T: Serialize>(stream: &mut TcpStream, message: &T) -> io::Result<()> {
+    let bytes = serde_json::to_vec(message)?;
+    if bytes.len() > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "message too long",
+        ));
+    }
+    let len = bytes.len() as u32;
+    stream.write_all(&len.to_le_bytes())?;
+    stream.write_all(&bytes)?;
+    stream.flush()
+}
+
+fn read_message<T: DeserializeOwned>(stream: &mut TcpStream) -> io::Result<T> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {len} bytes, longer than {MAX_MESSAGE_LEN}"),
+        ));
+    }
+    let mut bytes = vec![0; len];
+    stream.read_exact(&mut bytes)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// The task of the last job of a worker, and its `SegmentProver`.
+type KeyCache = Option<(ProvingTask, Box<dyn Any>)>;
+
+/// Serve the jobs of coordinators connecting to `listener`, one connection at a time, until the
+/// listener fails. The jobs reuse the witness buffers of the previous ones, and the proving key
+/// of the previous one of the same task.
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let pool = WitnessPool::default();
+    let mut cache = None;
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        // A coordinator which goes away only ends its connection.
+        while let Ok(Request::Prove { task, job }) = read_message(&mut stream) {
+            tracing::info!("Proving segment {}", job.segment);
+            let response = match prove_segment(&task, job, &pool, &mut cache) {
+                Ok(proof) => Response::Proved(proof),
+                Err(err) => Response::Failed(format!("{err:?}")),
+            };
+            if write_message(&mut stream, &response).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The serialized proof of `job` of `task`, with the proving key in `cache` if it is the one of
+/// `task`, which is left there for the next job.
+fn prove_segment(
+    task: &ProvingTask,
+    job: Job,
+    pool: &WitnessPool<Goldilocks>,
+    cache: &mut KeyCache,
+) -> Result<Vec<u8>, ZKVMError> {
+    let program = task.program()?;
+    // The proving may still panic on a guest which fails to run, e.g. on a bad instruction.
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        with_pcs!(task.pcs, GoldilocksExt2, Pcs => {
+            if !cache.as_ref().is_some_and(|(cached, _)| cached.same_key(task)) {
+                // Drop the key of the previous task before generating the next one.
+                *cache = None;
+                let prover = SegmentProver::<GoldilocksExt2, Pcs>::new(
+                    &program,
+                    CENO_PLATFORM,
+                    task.stack_size,
+                    task.heap_size,
+                    task.transcript_hash,
+                )?;
+                *cache = Some((task.clone(), Box::new(prover)));
+            }
+            let (_, prover) = cache.as_ref().unwrap();
+            let prover = prover
+                .downcast_ref::<SegmentProver<GoldilocksExt2, Pcs>>()
+                .unwrap();
+            let proof =
+                prover.prove_from(program, job.segment, job.start, job.steps, &task.hints, pool)?;
+            to_bytes(&proof)
+        })
+    }))
+    .unwrap_or_else(|_| Err(ZKVMError::GuestError("proving panicked".into())))
+}
+
+/// Dispatches the segments of an execution to workers, and reassembles their proofs.
+pub struct Coordinator {
+    workers: Vec<String>,
+}
+
+impl Coordinator {
+    /// A coordinator of the workers at `workers`, as `host:port`.
+    pub fn new(workers: Vec<String>) -> Self {
+        Self { workers }
+    }
+
+    /// Prove `task` on the workers, with `PCS` the scheme of `task.pcs`, and return the proofs of
+    /// its segments in order. The coordinator emulates the execution once, a segment ahead of the
+    /// workers which are free.
+    ///
+    /// A worker which cannot be reached, or whose connection fails, is dropped and its job given
+    /// to another one. A job which fails on a worker fails the task.
+    pub fn prove<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+        &self,
+        task: &ProvingTask,
+    ) -> Result<Vec<SegmentProof<E, PCS>>, ZKVMError>
+    where
+        E::BaseField: DeserializeOwned,
+    {
+        let jobs = Mutex::new(Jobs {
+            retries: VecDeque::new(),
+            segments: SegmentSnapshots::new(
+                task.program()?,
+                CENO_PLATFORM,
+                task.stack_size,
+                task.heap_size,
+                &task.hints,
+                task.max_steps,
+                task.segment_steps,
+            ),
+            num_segments: 0,
+        });
+        let proofs = Mutex::new(Vec::<Option<SegmentProof<E, PCS>>>::new());
+        let failure = Mutex::new(None);
+
+        thread::scope(|scope| {
+            let (jobs, proofs, failure) = (&jobs, &proofs, &failure);
+            for worker in &self.workers {
+                scope.spawn(move || {
+                    let Ok(mut stream) = connect(worker) else {
+                        tracing::warn!("Cannot reach worker {worker}");
+                        return;
+                    };
+                    loop {
+                        if failure.lock().unwrap().is_some() {
+                            return;
+                        }
+                        let job = match jobs.lock().unwrap().next() {
+                            Some(Ok(job)) => job,
+                            Some(Err(err)) => {
+                                *failure.lock().unwrap() = Some(err);
+                                return;
+                            }
+                            None => return,
+                        };
+                        let segment = job.segment;
+                        let request = Request::Prove {
+                            task: task.clone(),
+                            job,
+                        };
+                        let response = write_message(&mut stream, &request)
+                            .and_then(|()| read_message(&mut stream));
+                        match response {
+                            Ok(Response::Proved(bytes)) => match from_bytes(&bytes) {
+                                Ok(proof) => {
+                                    let mut proofs = proofs.lock().unwrap();
+                                    if proofs.len() <= segment {
+                                        proofs.resize_with(segment + 1, || None);
+                                    }
+                                    proofs[segment] = Some(proof);
+                                }
+                                Err(err) => *failure.lock().unwrap() = Some(err),
+                            },
+                            Ok(Response::Failed(err)) => {
+                                *failure.lock().unwrap() = Some(ZKVMError::WorkerError(format!(
+                                    "segment {segment} failed on {worker}: {err}"
+                                )));
+                            }
+                            Err(err) => {
+                                tracing::warn!("Dropping worker {worker}: {err}");
+                                let Request::Prove { job, .. } = request;
+                                jobs.lock().unwrap().retries.push_back(job);
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(err) = failure.into_inner().unwrap() {
+            return Err(err);
+        }
+        let mut jobs = jobs.into_inner().unwrap();
+        if let Some(job) = jobs.next() {
+            return Err(ZKVMError::WorkerError(format!(
+                "no worker left to prove segment {}",
+                job?.segment
+            )));
+        }
+        let mut proofs = proofs.into_inner().unwrap();
+        proofs.resize_with(jobs.num_segments, || None);
+        proofs
+            .into_iter()
+            .enumerate()
+            .map(|(segment, proof)| {
+                proof.ok_or_else(|| {
+                    ZKVMError::WorkerError(format!("no worker left to prove segment {segment}"))
+                })
+            })
+            .collect()
+    }
+}
+
+/// The jobs of a task which are left: those of the workers which were dropped, then the next
+/// segments of the execution, emulated as they are dispatched.
+struct Jobs {
+    retries: VecDeque<Job>,
+    segments: SegmentSnapshots,
+    /// The number of segments emulated so far.
+    num_segments: usize,
+}
+
+impl Iterator for Jobs {
+    type Item = Result<Job, ZKVMError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(job) = self.retries.pop_front() {
+            return Some(Ok(job));
+        }
+        let item = self.segments.next()?.map(|(start, steps)| Job {
+            segment: self.num_segments,
+            start,
+            steps,
+        });
+        self.num_segments += 1;
+        Some(item)
+    }
+}
+
+fn connect(worker: &str) -> io::Result<TcpStream> {
+    let addr = worker
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address"))?;
+    TcpStream::connect(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::e2e::verify_segments;
+
+    #[test]
+    fn test_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write_message(&mut stream, &Response::Failed("no guest".into())).unwrap();
+            read_message::<Response>(&mut stream).unwrap()
+        });
+        let (mut stream, _) = listener.accept().unwrap();
+        let Response::Failed(message) = read_message(&mut stream).unwrap() else {
+            panic!("expected a failure");
+        };
+        assert_eq!(message, "no guest");
+        write_message(&mut stream, &Response::Proved(vec![1, 2, 3])).unwrap();
+        assert!(matches!(client.join().unwrap(), Response::Proved(bytes) if bytes == [1, 2, 3]));
+    }
+
+    #[test]
+    fn test_message_too_long() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        });
+        let (mut stream, _) = listener.accept().unwrap();
+        let err = read_message::<Response>(&mut stream).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn test_prove_on_worker() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let worker = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(listener));
+
+        let task = ProvingTask {
+            elf: ceno_examples::ceno_rt_mini.to_vec(),
+            hints: vec![],
+            stack_size: 32 << 10,
+            heap_size: 2 << 20,
+            max_steps: 1 << 20,
+            segment_steps: 1 << 10,
+            pcs: PcsKind::default(),
+            transcript_hash: TranscriptHash::default(),
+        };
+        // a worker which cannot be reached is skipped
+        let coordinator = Coordinator::new(vec!["127.0.0.1:1".into(), worker]);
+        with_pcs!(task.pcs, GoldilocksExt2, Pcs => {
+            let segments = coordinator.prove::<GoldilocksExt2, Pcs>(&task).unwrap();
+            let exit_code = verify_segments(
+                &segments,
+                &task.program().unwrap(),
+                &CENO_PLATFORM,
+                task.stack_size,
+                task.heap_size,
+                task.transcript_hash,
+            )
+            .unwrap();
+            assert_eq!(exit_code, 0);
+        });
+    }
+}
@@ -1,14 +1,25 @@
 use ff_ext::ExtensionField;
-use itertools::Itertools;
+use itertools::{Itertools, chain};
 use mpcs::PolynomialCommitmentScheme;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{collections::BTreeMap, fmt::Debug};
 use sumcheck::structs::IOPProverMessage;
 
-use crate::structs::TowerProofs;
+use crate::{
+    instructions::{
+        Instruction,
+        riscv::{
+            constants::{LIMB_BITS, LIMB_MASK, OUTPUT_DIGEST_IDX, OUTPUT_DIGEST_WORDS, UINT_LIMBS},
+            ecall::CommitDigestInstruction,
+        },
+    },
+    structs::{ProtocolVersion, TowerProofs},
+};
 
 pub mod constants;
+pub mod pcs;
 pub mod prover;
+pub mod report;
 pub mod utils;
 pub mod verifier;
 
@@ -16,7 +27,11 @@ pub mod mock_prover;
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMOpcodeProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // TODO support >1 opcodes
     pub num_instances: usize,
@@ -40,8 +55,10 @@ pub struct ZKVMOpcodeProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>
     pub lk_records_in_evals: Vec<E>,
 
     pub wits_commit: PCS::Commitment,
-    pub wits_opening_proof: PCS::Proof,
     pub wits_in_evals: Vec<E>,
+
+    // instance columns, sent in the clear and evaluated by the verifier
+    pub instance_columns: Vec<Vec<E::BaseField>>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -61,14 +78,12 @@ pub struct ZKVMTableProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>
     pub rw_hints_num_vars: Vec<usize>,
 
     pub fixed_in_evals: Vec<E>,
-    pub fixed_opening_proof: Option<PCS::Proof>,
     pub wits_commit: PCS::Commitment,
     pub wits_in_evals: Vec<E>,
-    pub wits_opening_proof: PCS::Proof,
 }
 
 /// each field will be interpret to (constant) polynomial
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PublicValues<T: Default + Clone + Debug> {
     exit_code: T,
     init_pc: T,
@@ -76,6 +91,38 @@ pub struct PublicValues<T: Default + Clone + Debug> {
     end_pc: T,
     end_cycle: T,
     public_io: Vec<T>,
+    /// The machine at the start and at the end of a segment, or empty outside of segments.
+    init_state: SegmentState,
+    end_state: SegmentState,
+    output_digest: [T; OUTPUT_DIGEST_WORDS],
+}
+
+/// The registers and the static memory between two segments of an execution, in the order of
+/// the register and static memory tables, see `ProgramParams::segmented`.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentState {
+    pub registers: Vec<u32>,
+    pub memory: Vec<u32>,
+}
+
+impl SegmentState {
+    /// The public values of the registers, one per limb, see `REG_STATE_IDX`.
+    fn register_limbs<E: ExtensionField>(&self) -> Vec<Vec<E::BaseField>> {
+        (0..UINT_LIMBS)
+            .map(|l| {
+                self.registers
+                    .iter()
+                    .map(|reg| E::BaseField::from(((reg >> (l * LIMB_BITS)) & LIMB_MASK) as u64))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The digest of the output committed by the guest, see `VMState::committed_output`: its
+/// SHA-256 hash, as the guest attests it with the ecall COMMIT_DIGEST.
+pub fn output_digest(output: &[u8]) -> [u32; OUTPUT_DIGEST_WORDS] {
+    ceno_emul::sha256_digest(output)
 }
 
 impl PublicValues<u32> {
@@ -94,8 +141,25 @@ impl PublicValues<u32> {
             end_pc,
             end_cycle,
             public_io,
+            init_state: SegmentState::default(),
+            end_state: SegmentState::default(),
+            output_digest: output_digest(&[]),
         }
     }
+
+    /// Set the machine at the start and at the end of a segment.
+    pub fn with_segment_state(mut self, init_state: SegmentState, end_state: SegmentState) -> Self {
+        self.init_state = init_state;
+        self.end_state = end_state;
+        self
+    }
+
+    /// Set the digest of the output committed by the guest.
+    pub fn with_output(mut self, output: &[u8]) -> Self {
+        self.output_digest = output_digest(output);
+        self
+    }
+
     pub fn to_vec<E: ExtensionField>(&self) -> Vec<Vec<E::BaseField>> {
         vec![
             vec![E::BaseField::from((self.exit_code & 0xffff) as u64)],
@@ -109,13 +173,33 @@ impl PublicValues<u32> {
                 .map(|e| E::BaseField::from(*e as u64))
                 .collect(),
         ]
+        .into_iter()
+        .chain(self.init_state.register_limbs::<E>())
+        .chain(self.end_state.register_limbs::<E>())
+        .chain([&self.init_state, &self.end_state].map(|state| {
+            state
+                .memory
+                .iter()
+                .map(|e| E::BaseField::from(*e as u64))
+                .collect()
+        }))
+        .chain(
+            self.output_digest
+                .iter()
+                .map(|e| vec![E::BaseField::from(*e as u64)]),
+        )
+        .collect()
     }
 }
 
 /// Map circuit names to
 /// - an opcode or table proof,
 /// - an index unique across both types.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     // TODO preserve in serde only for auxiliary public input
     // other raw value can be construct by verifier directly.
@@ -124,6 +208,10 @@ pub struct ZKVMProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub pi_evals: Vec<E>,
     opcode_proofs: BTreeMap<String, (usize, ZKVMOpcodeProof<E, PCS>)>,
     table_proofs: BTreeMap<String, (usize, ZKVMTableProof<E, PCS>)>,
+    /// The opening of the witnesses of all the circuits, and of the fixed traces of the tables,
+    /// at the points of their proofs. None when no circuit has instances.
+    opening_proof: Option<PCS::Proof>,
+    version: ProtocolVersion,
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
@@ -147,6 +235,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
             pi_evals,
             opcode_proofs: BTreeMap::new(),
             table_proofs: BTreeMap::new(),
+            opening_proof: None,
+            version: ProtocolVersion::current(),
         }
     }
 
@@ -159,4 +249,55 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProof<E, PCS> {
     pub fn num_circuits(&self) -> usize {
         self.opcode_proofs.len() + self.table_proofs.len()
     }
+
+    /// Whether the output committed by the guest is `output`, according to the public values.
+    /// The digest of the public values is constrained by the circuit of the ecall COMMIT_DIGEST
+    /// only, so that a guest which did not attest it, e.g. without `ceno_rt`, has no output.
+    pub fn has_output(&self, output: &[u8]) -> bool {
+        let digest = output_digest(output).map(|word| vec![E::BaseField::from(word as u64)]);
+        self.num_instances(&CommitDigestInstruction::<E>::name()) > 0
+            && self
+                .raw_pi
+                .get(OUTPUT_DIGEST_IDX..OUTPUT_DIGEST_IDX + OUTPUT_DIGEST_WORDS)
+                == Some(&digest[..])
+    }
+
+    /// The version of the prover which produced the proof.
+    pub fn version(&self) -> &ProtocolVersion {
+        &self.version
+    }
+
+    /// The names of the proven circuits, opcode circuits first, each sorted by name. Circuits
+    /// without instances are left out.
+    pub fn circuit_names(&self) -> impl Iterator<Item = &str> {
+        chain(self.opcode_proofs.keys(), self.table_proofs.keys()).map(String::as_str)
+    }
+
+    pub fn opcode_proof(&self, name: &str) -> Option<&ZKVMOpcodeProof<E, PCS>> {
+        self.opcode_proofs.get(name).map(|(_, proof)| proof)
+    }
+
+    pub fn table_proof(&self, name: &str) -> Option<&ZKVMTableProof<E, PCS>> {
+        self.table_proofs.get(name).map(|(_, proof)| proof)
+    }
+
+    /// The opcode proofs, sorted by circuit name.
+    pub fn iter_opcode_proofs(&self) -> impl Iterator<Item = (&str, &ZKVMOpcodeProof<E, PCS>)> {
+        self.opcode_proofs
+            .iter()
+            .map(|(name, (_, proof))| (name.as_str(), proof))
+    }
+
+    /// The table proofs, sorted by circuit name.
+    pub fn iter_table_proofs(&self) -> impl Iterator<Item = (&str, &ZKVMTableProof<E, PCS>)> {
+        self.table_proofs
+            .iter()
+            .map(|(name, (_, proof))| (name.as_str(), proof))
+    }
+
+    /// The number of instances of an opcode circuit, 0 when it is left out of the proof.
+    pub fn num_instances(&self, name: &str) -> usize {
+        self.opcode_proof(name)
+            .map_or(0, |proof| proof.num_instances)
+    }
 }
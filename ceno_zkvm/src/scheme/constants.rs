@@ -6,3 +6,17 @@ pub const NUM_FANIN: usize = 2;
 pub const NUM_FANIN_LOGUP: usize = 2;
 
 pub const MAX_NUM_VARIABLES: usize = 24;
+
+/// Bit width the base field must reach for the RISC-V instruction circuits, and by default for
+/// the tables. They combine the limbs of a 32-bit word into a single field element, with some
+/// headroom, e.g. for the carry of AUIPC, and the MUL circuits rely on the 64-bit Goldilocks
+/// prime, see `instructions::riscv::mul`.
+///
+/// The circuits which only handle small values, such as the range and byte tables, run on
+/// 31-bit fields such as BabyBear or Mersenne31, see `TableCircuit::min_field_bits`.
+pub const MIN_BASE_FIELD_BITS: u32 = 64;
+
+/// Version of the circuits and of the proving protocol, embedded in proofs and verifying keys.
+/// Bump it whenever a change to either makes the proofs of the previous version fail to verify,
+/// or verify with a different meaning.
+pub const CIRCUIT_VERSION: u32 = 2;
@@ -1,6 +1,6 @@
 use super::{
     PublicValues,
-    utils::{eval_by_expr, wit_infer_by_expr},
+    utils::{eval_by_expr, instance_column_mles, wit_infer_by_expr},
 };
 use crate::{
     ROMType,
@@ -10,8 +10,9 @@ use crate::{
     state::{GlobalState, StateCircuit},
     structs::{ProgramParams, RAMType, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMWitnesses},
     tables::{
-        AndTable, LtuTable, OpsTable, OrTable, PowTable, ProgramTableCircuit, RangeTable,
-        TableCircuit, U5Table, U8Table, U14Table, U16Table, XorTable,
+        AndTable, Bits8Table, KeccakChiTable, KeccakRcTable, KeccakRhoTable, LtuTable, OpsTable,
+        OrTable, PowTable, ProgramTableCircuit, RangeTable, StructuredTable, TableCircuit, U5Table,
+        U8Table, U14Table, U16Table, XorTable,
     },
     witness::{LkMultiplicity, RowMajorMatrix},
 };
@@ -30,6 +31,7 @@ use std::{
     fs::File,
     hash::Hash,
     io::{BufReader, ErrorKind},
+    iter,
     marker::PhantomData,
     ops::Neg,
     sync::OnceLock,
@@ -150,6 +152,16 @@ impl<E: ExtensionField> PartialEq for MockProverError<E> {
 
 impl<E: ExtensionField> MockProverError<E> {
     pub fn print(&self, wits_in: &[ArcMultilinearExtension<E>], wits_in_name: &[String]) {
+        println!("{}", self.report(wits_in, wits_in_name));
+    }
+
+    /// Describe the error: the namespace path of the failing constraint, and the values of the
+    /// witnesses it refers to at the failing instance.
+    pub fn report(
+        &self,
+        wits_in: &[ArcMultilinearExtension<E>],
+        wits_in_name: &[String],
+    ) -> String {
         let mut wtns = vec![];
 
         match self {
@@ -162,12 +174,12 @@ impl<E: ExtensionField> MockProverError<E> {
                 let expression_fmt = fmt::expr(expression, &mut wtns, false);
                 let wtns_fmt = fmt::wtns(&wtns, wits_in, *inst_id, wits_in_name);
                 let eval_fmt = fmt::base_field(evaluated, false);
-                println!(
+                format!(
                     "\nAssertZeroError {name:?}: Evaluated expression is not zero\n\
                     Expression: {expression_fmt}\n\
                     Evaluation: {eval_fmt} != 0\n\
                     Inst[{inst_id}]:\n{wtns_fmt}\n",
-                );
+                )
             }
             Self::AssertEqualError {
                 left_expression,
@@ -182,13 +194,13 @@ impl<E: ExtensionField> MockProverError<E> {
                 let wtns_fmt = fmt::wtns(&wtns, wits_in, *inst_id, wits_in_name);
                 let left_eval_fmt = fmt::base_field(left, false);
                 let right_eval_fmt = fmt::base_field(right, false);
-                println!(
+                format!(
                     "\nAssertEqualError {name:?}\n\
                     Left: {left_eval_fmt} != Right: {right_eval_fmt}\n\
                     Left Expression: {left_expression_fmt}\n\
                     Right Expression: {right_expression_fmt}\n\
                     Inst[{inst_id}]:\n{wtns_fmt}\n",
-                );
+                )
             }
             Self::DegreeTooHigh {
                 expression,
@@ -196,11 +208,11 @@ impl<E: ExtensionField> MockProverError<E> {
                 name,
             } => {
                 let expression_fmt = fmt::expr(expression, &mut wtns, false);
-                println!(
+                format!(
                     "\nDegreeTooHigh {name:?}: Expression degree is too high\n\
                     Expression: {expression_fmt}\n\
                     Degree: {degree} > {MAX_CONSTRAINT_DEGREE}\n",
-                );
+                )
             }
             Self::LookupError {
                 expression,
@@ -211,12 +223,12 @@ impl<E: ExtensionField> MockProverError<E> {
                 let expression_fmt = fmt::expr(expression, &mut wtns, false);
                 let wtns_fmt = fmt::wtns(&wtns, wits_in, *inst_id, wits_in_name);
                 let eval_fmt = fmt::field(evaluated);
-                println!(
+                format!(
                     "\nLookupError {name:#?}: Evaluated expression does not exist in T vector\n\
                     Expression: {expression_fmt}\n\
                     Evaluation: {eval_fmt}\n\
                     Inst[{inst_id}]:\n{wtns_fmt}\n",
-                );
+                )
             }
             Self::LkMultiplicityError {
                 rom_type,
@@ -259,24 +271,36 @@ impl<E: ExtensionField> MockProverError<E> {
                         format!("Element: {a} ** {b}")
                     }
                     ROMType::Instruction => format!("PC: {key}"),
+                    ROMType::KeccakRc => format!("Round: {key}"),
+                    ROMType::KeccakRho => format!("Lane: {key}"),
+                    ROMType::KeccakChi => {
+                        let (a, b) = KeccakChiTable::unpack(*key);
+                        format!("Element: !{a} & {b}")
+                    }
+                    ROMType::Bits8 => format!("Bits: {key:08b}"),
                 };
-                println!(
+                format!(
                     "\nLkMultiplicityError:\n\
                     {lookups} of {rom_type:?} missing in {location}\n\
                     {element}\n"
-                );
+                )
             }
         }
     }
 
     #[cfg(test)]
     fn inst_id(&self) -> usize {
+        self.instance().unwrap()
+    }
+
+    /// The failing instance, if the error is tied to one.
+    pub fn instance(&self) -> Option<usize> {
         match self {
             Self::AssertZeroError { inst_id, .. }
             | Self::AssertEqualError { inst_id, .. }
             | Self::LookupError { inst_id, .. }
-            | Self::LkMultiplicityError { inst_id, .. } => *inst_id,
-            Self::DegreeTooHigh { .. } => unreachable!(),
+            | Self::LkMultiplicityError { inst_id, .. } => Some(*inst_id),
+            Self::DegreeTooHigh { .. } => None,
         }
     }
 
@@ -320,6 +344,23 @@ fn load_tables<E: ExtensionField>(cb: &CircuitBuilder<E>, challenge: [E; 2]) ->
         }
     }
 
+    fn load_structured_table<T: StructuredTable, E: ExtensionField>(
+        t_vec: &mut Vec<Vec<u64>>,
+        cb: &CircuitBuilder<E>,
+        challenge: [E; 2],
+    ) {
+        let columns = T::columns();
+        for row in 0..T::len() {
+            let rlc_record = cb.rlc_chip_record(
+                iter::once((T::ROM_TYPE as usize).into())
+                    .chain(columns.iter().map(|c| ((c.generator)(row) as usize).into()))
+                    .collect(),
+            );
+            let rlc_record = eval_by_expr(&[], &challenge, &rlc_record);
+            t_vec.push(rlc_record.to_canonical_u64_vec());
+        }
+    }
+
     let mut table_vec = vec![];
     load_range_table::<U5Table, _>(&mut table_vec, cb, challenge);
     load_range_table::<U8Table, _>(&mut table_vec, cb, challenge);
@@ -330,6 +371,10 @@ fn load_tables<E: ExtensionField>(cb: &CircuitBuilder<E>, challenge: [E; 2]) ->
     load_op_table::<XorTable, _>(&mut table_vec, cb, challenge);
     load_op_table::<LtuTable, _>(&mut table_vec, cb, challenge);
     load_op_table::<PowTable, _>(&mut table_vec, cb, challenge);
+    load_op_table::<KeccakChiTable, _>(&mut table_vec, cb, challenge);
+    load_structured_table::<KeccakRcTable, _>(&mut table_vec, cb, challenge);
+    load_structured_table::<KeccakRhoTable, _>(&mut table_vec, cb, challenge);
+    load_structured_table::<Bits8Table, _>(&mut table_vec, cb, challenge);
 
     HashSet::from_iter(table_vec)
 }
@@ -406,6 +451,25 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         Self::run_maybe_challenge(cb, wits_in, programs, &[], None, lkm)
     }
 
+    /// Check the constraints row by row and report the first failure: the lowest failing
+    /// instance, then the constraint defined first.
+    pub fn run_first_error(
+        cb: &CircuitBuilder<E>,
+        wits_in: &[ArcMultilinearExtension<'a, E>],
+        programs: &[u32],
+        lkm: Option<LkMultiplicity>,
+    ) -> Result<(), String> {
+        let errors = match Self::run(cb, wits_in, programs, lkm) {
+            Ok(()) => return Ok(()),
+            Err(errors) => errors,
+        };
+        let first = errors
+            .iter()
+            .min_by_key(|error| error.instance())
+            .expect("failed run without errors");
+        Err(first.report(wits_in, &cb.cs.witin_namespace_map))
+    }
+
     fn run_maybe_challenge(
         cb: &CircuitBuilder<E>,
         wits_in: &[ArcMultilinearExtension<'a, E>],
@@ -577,6 +641,10 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
                             lkm.lookup_pow2(args[1])
                         }
                         ROMType::Instruction => lkm.fetch(args[0] as u32),
+                        ROMType::KeccakRc => lkm.lookup_keccak_rc(args[0]),
+                        ROMType::KeccakRho => lkm.lookup_keccak_rho(args[0]),
+                        ROMType::KeccakChi => lkm.lookup_keccak_chi(args[0], args[1]),
+                        ROMType::Bits8 => lkm.assert_bit_batch(&args),
                     };
 
                     lkm
@@ -649,7 +717,7 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
             ..ProgramParams::default()
         });
         let config = ProgramTableCircuit::<_>::construct_circuit(&mut cb).unwrap();
-        let fixed = ProgramTableCircuit::<E>::generate_fixed_traces(&config, cs.num_fixed, program);
+        let fixed = ProgramTableCircuit::<E>::generate_fixed_traces(&config, &cs, program);
         for table_expr in &cs.lk_table_expressions {
             for row in fixed.iter_rows() {
                 // TODO: Find a better way to obtain the row content.
@@ -675,15 +743,60 @@ impl<'a, E: ExtensionField + Hash> MockProver<E> {
         challenge: Option<[E; 2]>,
         lkm: Option<LkMultiplicity>,
     ) {
-        let error_groups = if let Some(challenge) = challenge {
+        let errors = if let Some(challenge) = challenge {
             Self::run_with_challenge(cb, wits_in, challenge, lkm)
         } else {
             Self::run(cb, wits_in, programs, lkm)
         }
         .err()
+        .unwrap_or_default();
+        Self::assert_errors(cb, wits_in, errors, constraint_names);
+    }
+
+    /// Like `assert_with_expected_errors`, on the instances of `raw_witin` only, as the prover
+    /// excludes its padding rows from the constraints.
+    pub fn assert_with_expected_errors_raw(
+        cb: &CircuitBuilder<E>,
+        raw_witin: RowMajorMatrix<E::BaseField>,
+        programs: &[u32],
+        constraint_names: &[&str],
+        challenge: Option<[E; 2]>,
+        lkm: Option<LkMultiplicity>,
+    ) {
+        let num_instances = raw_witin.num_instances();
+        let wits_in = raw_witin
+            .de_interleaving()
+            .into_mles()
+            .into_iter()
+            .map(|v| v.into())
+            .collect_vec();
+        let errors = if let Some(challenge) = challenge {
+            Self::run_with_challenge(cb, &wits_in, challenge, lkm)
+        } else {
+            Self::run(cb, &wits_in, programs, lkm)
+        }
+        .err()
+        .unwrap_or_default()
         .into_iter()
-        .flatten()
-        .into_group_map_by(|error| constraint_names.iter().find(|&name| error.contains(name)));
+        .filter(|error| {
+            error
+                .instance()
+                .is_none_or(|inst_id| inst_id < num_instances)
+        })
+        .collect();
+        Self::assert_errors(cb, &wits_in, errors, constraint_names);
+    }
+
+    /// Panic, unless `errors` are exactly the errors of `constraint_names`.
+    fn assert_errors(
+        cb: &CircuitBuilder<E>,
+        wits_in: &[ArcMultilinearExtension<'a, E>],
+        errors: Vec<MockProverError<E>>,
+        constraint_names: &[&str],
+    ) {
+        let error_groups = errors
+            .into_iter()
+            .into_group_map_by(|error| constraint_names.iter().find(|&name| error.contains(name)));
         // Unexpected errors
         if let Some(errors) = error_groups.get(&None) {
             println!("======================================================");
@@ -726,13 +839,7 @@ Hints:
         challenge: Option<[E; 2]>,
         lkm: Option<LkMultiplicity>,
     ) {
-        let wits_in = raw_witin
-            .de_interleaving()
-            .into_mles()
-            .into_iter()
-            .map(|v| v.into())
-            .collect_vec();
-        Self::assert_satisfied(cb, &wits_in, programs, challenge, lkm);
+        Self::assert_with_expected_errors_raw(cb, raw_witin, programs, &[], challenge, lkm);
     }
 
     pub fn assert_satisfied(
@@ -769,6 +876,7 @@ Hints:
         let mut wit_mles = HashMap::new();
         let mut fixed_mles = HashMap::new();
         let mut num_instances = HashMap::new();
+        let mut circuit_pis = HashMap::new();
         // Lookup errors
         let mut rom_inputs =
             HashMap::<ROMType, Vec<(Vec<E>, String, String, Vec<Expression<E>>)>>::new();
@@ -799,6 +907,16 @@ Hints:
                 .into_iter()
                 .map(|w| w.into())
                 .collect_vec();
+            // public values, then the instance columns of this circuit
+            let circuit_pi: Vec<ArcMultilinearExtension<E>> = pi_mles
+                .iter()
+                .cloned()
+                .chain(
+                    instance_column_mles(witnesses.get_instance_columns(circuit_name), num_rows)
+                        .into_iter()
+                        .map(|mle| mle.into()),
+                )
+                .collect();
             let fixed: Vec<_> = fixed_trace
                 .circuit_fixed_traces
                 .remove(circuit_name)
@@ -825,7 +943,7 @@ Hints:
                     .zip(cs.lk_expressions_items_map.clone().into_iter())
                 {
                     let lk_input =
-                        (wit_infer_by_expr(&fixed, &witness, &pi_mles, &challenges, expr)
+                        (wit_infer_by_expr(&fixed, &witness, &circuit_pi, &challenges, expr)
                             .get_ext_field_vec())[..num_rows]
                             .to_vec();
                     rom_inputs.entry(rom_type).or_default().push((
@@ -842,26 +960,52 @@ Hints:
                     num_rows
                 );
                 // gather lookup tables
-                for (expr, (rom_type, _)) in cs
+                for (expr, (rom_type, values)) in cs
                     .lk_table_expressions
                     .iter()
                     .zip(cs.lk_expressions_items_map.clone().into_iter())
                 {
                     let lk_table =
-                        wit_infer_by_expr(&fixed, &witness, &pi_mles, &challenges, &expr.values)
+                        wit_infer_by_expr(&fixed, &witness, &circuit_pi, &challenges, &expr.values)
                             .get_ext_field_vec()
                             .to_vec();
 
                     let multiplicity = wit_infer_by_expr(
                         &fixed,
                         &witness,
-                        &pi_mles,
+                        &circuit_pi,
                         &challenges,
                         &expr.multiplicity,
                     )
                     .get_base_field_vec()
                     .to_vec();
 
+                    if expr.rom_types.len() > 1 {
+                        // batched table: split rows by the table id in the first record item
+                        let table_ids = wit_infer_by_expr(
+                            &fixed,
+                            &witness,
+                            &circuit_pi,
+                            &challenges,
+                            &values[0],
+                        )
+                        .get_base_field_vec()
+                        .to_vec();
+                        for (value, multiplicity, table_id) in
+                            izip!(lk_table, multiplicity, table_ids).take(num_rows)
+                        {
+                            let rom_type = ROMType::iter()
+                                .nth(table_id.to_canonical_u64() as usize)
+                                .expect("invalid table id");
+                            assert!(expr.rom_types.contains(&rom_type));
+                            rom_tables
+                                .entry(rom_type)
+                                .or_default()
+                                .insert(value, multiplicity);
+                        }
+                        continue;
+                    }
+
                     assert!(
                         rom_tables
                             .insert(
@@ -869,6 +1013,7 @@ Hints:
                                 lk_table
                                     .into_iter()
                                     .zip(multiplicity.into_iter())
+                                    .take(num_rows)
                                     .collect::<HashMap<_, _>>(),
                             )
                             .is_none(),
@@ -880,6 +1025,7 @@ Hints:
             wit_mles.insert(circuit_name.clone(), witness);
             fixed_mles.insert(circuit_name.clone(), fixed);
             num_instances.insert(circuit_name.clone(), num_rows);
+            circuit_pis.insert(circuit_name.clone(), circuit_pi);
         }
 
         for (rom_type, inputs) in rom_inputs {
@@ -913,6 +1059,16 @@ Hints:
                                 .collect_vec()
                         })
                         .unwrap();
+                    let instance = instance
+                        .iter()
+                        .copied()
+                        .chain(
+                            witnesses
+                                .get_instance_columns(&circuit_name)
+                                .iter()
+                                .map(|column| E::from(column[row as usize])),
+                        )
+                        .collect_vec();
                     let values = input_value_exprs
                         .iter()
                         .map(|expr| {
@@ -977,10 +1133,15 @@ Hints:
                     .zip_eq(cs.w_ram_types.iter())
                     .filter(|((_, _), (ram_type, _))| *ram_type == $ram_type)
                     {
-                        let write_rlc_records =
-                            (wit_infer_by_expr(fixed, witness, &pi_mles, &challenges, w_rlc_expr)
-                                .get_ext_field_vec())[..*num_rows]
-                                .to_vec();
+                        let write_rlc_records = (wit_infer_by_expr(
+                            fixed,
+                            witness,
+                            &circuit_pis[circuit_name],
+                            &challenges,
+                            w_rlc_expr,
+                        )
+                        .get_ext_field_vec())[..*num_rows]
+                            .to_vec();
 
                         if $ram_type == RAMType::GlobalState {
                             // w_exprs = [GlobalState, pc, timestamp]
@@ -992,7 +1153,7 @@ Hints:
                                     let v = wit_infer_by_expr(
                                         fixed,
                                         witness,
-                                        &pi_mles,
+                                        &circuit_pis[circuit_name],
                                         &challenges,
                                         expr,
                                     );
@@ -1039,10 +1200,15 @@ Hints:
                     .zip_eq(cs.r_ram_types.iter())
                     .filter(|((_, _), (ram_type, _))| *ram_type == $ram_type)
                     {
-                        let read_records =
-                            wit_infer_by_expr(fixed, witness, &pi_mles, &challenges, r_expr)
-                                .get_ext_field_vec()[..*num_rows]
-                                .to_vec();
+                        let read_records = wit_infer_by_expr(
+                            fixed,
+                            witness,
+                            &circuit_pis[circuit_name],
+                            &challenges,
+                            r_expr,
+                        )
+                        .get_ext_field_vec()[..*num_rows]
+                            .to_vec();
                         let mut records = vec![];
                         for (row, record) in enumerate(read_records) {
                             // TODO: return error
@@ -1212,10 +1378,11 @@ mod tests {
     use crate::{
         ROMType::U5,
         error::ZKVMError,
-        expression::{ToExpr, WitIn},
-        gadgets::{AssertLTConfig, IsLtConfig},
+        expression::{Instance, ToExpr, WitIn},
+        gadgets::{AssertLTConfig, Gadget, IsLtConfig, RangeCheckConfig, is_zero_witness},
+        instructions::riscv::constants::NUM_PUBLIC_VALUES,
         set_val,
-        witness::{LkMultiplicity, RowMajorMatrix},
+        witness::{AssignmentCtx, LkMultiplicity, RowMajorMatrix},
     };
     use ff::Field;
     use goldilocks::{Goldilocks, GoldilocksExt2};
@@ -1279,6 +1446,234 @@ mod tests {
         MockProver::assert_satisfied(&builder, &wits_in, &[], None, None);
     }
 
+    #[test]
+    fn test_require_equal_if() {
+        let mut cs = ConstraintSystem::new(|| "test_require_equal_if");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let cond = builder.create_witin(|| "cond");
+        let a = builder.create_witin(|| "a");
+        let b = builder.create_witin(|| "b");
+        builder
+            .require_equal_if(|| "a == b if cond", cond.expr(), a.expr(), b.expr())
+            .unwrap();
+        builder
+            .require_zero_if(|| "a == 5 if cond", cond.expr(), a.expr() - 5)
+            .unwrap();
+        assert_eq!(builder.cs.max_non_lc_degree, 2);
+
+        fn wits_in(
+            cond: u64,
+            a: u64,
+            b: u64,
+        ) -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+            vec![
+                vec![Goldilocks::from(1), Goldilocks::from(cond)]
+                    .into_mle()
+                    .into(),
+                vec![Goldilocks::from(5), Goldilocks::from(a)]
+                    .into_mle()
+                    .into(),
+                vec![Goldilocks::from(5), Goldilocks::from(b)]
+                    .into_mle()
+                    .into(),
+            ]
+        }
+
+        MockProver::assert_satisfied(&builder, &wits_in(0, 3, 7), &[], None, None);
+        let err = MockProver::run(&builder, &wits_in(1, 3, 7), &[], None).unwrap_err();
+        assert_eq!(err.len(), 2);
+    }
+
+    #[test]
+    fn test_is_zero() {
+        let mut cs = ConstraintSystem::new(|| "test_is_zero");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let x = builder.create_witin(|| "x");
+        let (is_zero, inverse) = builder.is_zero(x.expr()).unwrap();
+        assert_eq!((is_zero.id, inverse.id), (1, 2));
+
+        fn wits_in(
+            rows: &[(Goldilocks, Goldilocks, Goldilocks)],
+        ) -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+            let (x, is_zero, inverse): (Vec<_>, Vec<_>, Vec<_>) = rows.iter().copied().multiunzip();
+            vec![
+                x.into_mle().into(),
+                is_zero.into_mle().into(),
+                inverse.into_mle().into(),
+            ]
+        }
+
+        let [zero, seven] = [0, 7].map(|x| {
+            let x = Goldilocks::from(x);
+            let (is_zero, inverse) = is_zero_witness(x);
+            (x, is_zero, inverse)
+        });
+        assert_eq!(zero.1, Goldilocks::ONE);
+        assert_eq!(seven.1, Goldilocks::ZERO);
+        MockProver::assert_satisfied(&builder, &wits_in(&[zero, seven]), &[], None, None);
+
+        // claim 7 is zero
+        let wrong = (seven.0, Goldilocks::ONE, Goldilocks::ZERO);
+        let err = MockProver::run(&builder, &wits_in(&[zero, wrong]), &[], None).unwrap_err();
+        assert_eq!(err.len(), 1);
+    }
+
+    #[test]
+    fn test_instance_column() {
+        let mut cs = ConstraintSystem::new(|| "test_instance_column");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let x = builder.create_witin(|| "x");
+        let y = builder.create_instance(|| "y");
+        assert_eq!(y, Instance(NUM_PUBLIC_VALUES));
+        builder
+            .require_equal(|| "x = y + 1", x.expr(), y.expr() + 1)
+            .unwrap();
+
+        // public values, then the instance column
+        let pi = |y: Vec<u64>| -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+            PublicValues::<u32>::default()
+                .to_vec::<GoldilocksExt2>()
+                .into_mles()
+                .into_iter()
+                .map(|mle| mle.into())
+                .chain(
+                    instance_column_mles::<GoldilocksExt2>(
+                        &[y.into_iter().map(Goldilocks::from).collect()],
+                        2,
+                    )
+                    .into_iter()
+                    .map(|mle| mle.into()),
+                )
+                .collect()
+        };
+        let wits_in: Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> = vec![
+            vec![Goldilocks::from(4), Goldilocks::from(8)]
+                .into_mle()
+                .into(),
+        ];
+
+        MockProver::run_maybe_challenge(&builder, &wits_in, &[], &pi(vec![3, 7]), None, None)
+            .unwrap();
+        let err =
+            MockProver::run_maybe_challenge(&builder, &wits_in, &[], &pi(vec![3, 8]), None, None)
+                .unwrap_err();
+        assert_eq!(err.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "instance column multiplied by another column")]
+    fn test_instance_column_nonlinear() {
+        let mut cs = ConstraintSystem::new(|| "test_instance_column_nonlinear");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let x = builder.create_witin(|| "x");
+        let y = builder.create_instance(|| "y");
+        let _ = builder.require_zero(|| "x * y = 0", x.expr() * y.expr());
+    }
+
+    #[test]
+    fn test_assert_bit_batch() {
+        let mut cs = ConstraintSystem::new(|| "test_assert_bit_batch");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let bits = (0..10)
+            .map(|i| builder.create_witin(|| format!("b{i}")).expr())
+            .collect_vec();
+        builder.assert_bit_batch(|| "bits", &bits).unwrap();
+        assert_eq!(builder.cs.lk_expressions.len(), 2);
+        assert!(builder.cs.assert_zero_sumcheck_expressions.is_empty());
+
+        fn wits_in(bits: [u64; 10]) -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+            bits.iter()
+                .map(|b| vec![Goldilocks::from(*b)].into_mle().into())
+                .collect()
+        }
+
+        let challenge = [2.into(), 1000.into()];
+        let bits = [1, 0, 1, 1, 0, 0, 1, 0, 1, 1];
+        MockProver::assert_satisfied(&builder, &wits_in(bits), &[], Some(challenge), None);
+        let not_bits = [1, 2, 1, 1, 0, 0, 1, 0, 1, 1];
+        let err = MockProver::run_with_challenge(&builder, &wits_in(not_bits), challenge, None)
+            .unwrap_err();
+        assert_eq!(err.len(), 1);
+    }
+
+    #[test]
+    fn test_boolean_helpers() {
+        let mut cs = ConstraintSystem::new(|| "test_boolean_helpers");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let a = builder.create_witin(|| "a");
+        let b = builder.create_witin(|| "b");
+        builder.assert_bit(|| "a is bit", a.expr()).unwrap();
+        let outputs = [
+            builder.b_and(a.expr(), b.expr()).unwrap(),
+            builder.b_or(a.expr(), b.expr()).unwrap(),
+            builder.b_xor(a.expr(), b.expr()).unwrap(),
+            builder.b_not(a.expr()).unwrap(),
+        ];
+        // a and b are each constrained to be boolean only once
+        assert_eq!(builder.cs.assert_zero_sumcheck_expressions.len(), 2);
+
+        for (i, out) in outputs.into_iter().enumerate() {
+            let w = builder.create_witin(|| format!("out_{i}"));
+            builder
+                .require_equal(|| format!("out_{i}"), w.expr(), out)
+                .unwrap();
+        }
+
+        // one instance per (a, b) combination
+        fn column(f: fn(u64, u64) -> u64) -> ArcMultilinearExtension<'static, GoldilocksExt2> {
+            izip!([0, 0, 1, 1], [0, 1, 0, 1])
+                .map(|(a, b)| Goldilocks::from(f(a, b)))
+                .collect_vec()
+                .into_mle()
+                .into()
+        }
+        let wits_in = vec![
+            column(|a, _| a),
+            column(|_, b| b),
+            column(|a, b| a & b),
+            column(|a, b| a | b),
+            column(|a, b| a ^ b),
+            column(|a, _| 1 - a),
+        ];
+        MockProver::assert_satisfied(&builder, &wits_in, &[], None, None);
+    }
+
+    #[test]
+    fn test_run_first_error() {
+        let mut cs = ConstraintSystem::new(|| "test_run_first_error");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let _ = AssertZeroCircuit::construct_circuit(&mut builder).unwrap();
+
+        // instance 0 satisfies everything, instance 1 breaks "c - 2 == 0" and then "d*d - ..."
+        let wits_in = vec![
+            vec![Goldilocks::from(3), Goldilocks::from(500)]
+                .into_mle()
+                .into(),
+            vec![Goldilocks::from(4), Goldilocks::from(501)]
+                .into_mle()
+                .into(),
+            vec![Goldilocks::from(2), Goldilocks::from(7)]
+                .into_mle()
+                .into(),
+            vec![Goldilocks::from(3), Goldilocks::from(4)]
+                .into_mle()
+                .into(),
+        ];
+
+        let report = MockProver::run_first_error(&builder, &wits_in, &[], None).unwrap_err();
+        assert!(report.contains("test_run_first_error/require_zero/c - 2 == 0"));
+        assert!(report.contains("Inst[1]"));
+        assert!(report.contains("test_run_first_error/c"));
+        assert!(report.contains('7'));
+    }
+
     #[derive(Debug)]
     struct RangeCheckCircuit {
         #[allow(dead_code)]
@@ -1312,6 +1707,25 @@ mod tests {
         MockProver::assert_satisfied(&builder, &wits_in, &[], Some(challenge), None);
     }
 
+    #[test]
+    fn test_assert_bits() {
+        let mut cs = ConstraintSystem::new(|| "test_assert_bits");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let a = builder.create_witin(|| "a");
+        builder.assert_bits(|| "assert u12", a.expr(), 12).unwrap();
+        assert_eq!(builder.cs.lk_expressions.len(), 2);
+
+        let wits_in = |a: u64| -> Vec<ArcMultilinearExtension<'static, GoldilocksExt2>> {
+            vec![vec![Goldilocks::from(a)].into_mle().into()]
+        };
+        let challenge = [2.into(), 1000.into()];
+        MockProver::assert_satisfied(&builder, &wits_in(4095), &[], Some(challenge), None);
+        let err =
+            MockProver::run_with_challenge(&builder, &wits_in(4096), challenge, None).unwrap_err();
+        assert_eq!(err.len(), 1);
+    }
+
     #[test]
     // TODO: add it back after the support of missing lookup
     fn test_lookup_error() {
@@ -1548,6 +1962,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gadget_range_check() {
+        let mut cs = ConstraintSystem::new(|| "test_gadget_range_check");
+        let mut builder = CircuitBuilder::<GoldilocksExt2>::new(&mut cs);
+
+        let a = builder.create_witin(|| "a");
+        let config = RangeCheckConfig::construct(&mut builder, (a.expr(), 20)).unwrap();
+
+        let mut lk_multiplicity = LkMultiplicity::default();
+        let mut raw_witin = RowMajorMatrix::<Goldilocks>::new(2, builder.cs.num_witin as usize);
+        for (row, (instance, value)) in raw_witin.iter_mut().zip([0xabcde, 0xfffff]).enumerate() {
+            set_val!(instance, a, value);
+            let mut ctx = AssignmentCtx::new(instance, &mut lk_multiplicity, row);
+            Gadget::<GoldilocksExt2>::assign(&config, &mut ctx, value).unwrap();
+        }
+
+        MockProver::assert_satisfied_raw(
+            &builder,
+            raw_witin,
+            &[],
+            Some([1.into(), 1000.into()]),
+            None,
+        );
+    }
+
     #[test]
     fn test_lt_u32() {
         let mut cs = ConstraintSystem::new(|| "test_lt_u32");
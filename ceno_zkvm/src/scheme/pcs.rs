@@ -0,0 +1,86 @@
+//! The polynomial commitment schemes behind the prover.
+//!
+//! Circuits never see the PCS: the keys, prover and verifier are generic over
+//! [`PolynomialCommitmentScheme`], so a backend is picked per deployment with [`PcsKind`] and
+//! [`setup_pcs`] without touching any circuit.
+
+use std::{fmt, str::FromStr};
+
+use ff_ext::ExtensionField;
+pub use mpcs::PolynomialCommitmentScheme;
+use mpcs::{Basefold, BasefoldBasecodeParams, BasefoldRSParams};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ZKVMError, scheme::constants::MAX_NUM_VARIABLES};
+
+/// Basefold over Reed-Solomon codes, the default backend.
+pub type BasefoldRS<E> = Basefold<E, BasefoldRSParams>;
+
+/// Basefold over random foldable codes.
+pub type BasefoldBasecode<E> = Basefold<E, BasefoldBasecodeParams>;
+
+/// The available backends, see [`with_pcs`](crate::with_pcs) to run code generic over the PCS.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PcsKind {
+    #[default]
+    BasefoldRS,
+    BasefoldBasecode,
+}
+
+impl fmt::Display for PcsKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PcsKind::BasefoldRS => write!(f, "basefold-rs"),
+            PcsKind::BasefoldBasecode => write!(f, "basefold-basecode"),
+        }
+    }
+}
+
+impl FromStr for PcsKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "basefold-rs" => Ok(PcsKind::BasefoldRS),
+            "basefold-basecode" => Ok(PcsKind::BasefoldBasecode),
+            _ => Err(format!("unknown polynomial commitment scheme {s}")),
+        }
+    }
+}
+
+/// Run `$body` with the type alias `$pcs` bound to the backend of `$kind`, a [`PcsKind`].
+#[macro_export]
+macro_rules! with_pcs {
+    ($kind:expr, $e:ty, $pcs:ident => $body:expr) => {
+        match $kind {
+            $crate::scheme::pcs::PcsKind::BasefoldRS => {
+                type $pcs = $crate::scheme::pcs::BasefoldRS<$e>;
+                $body
+            }
+            $crate::scheme::pcs::PcsKind::BasefoldBasecode => {
+                type $pcs = $crate::scheme::pcs::BasefoldBasecode<$e>;
+                $body
+            }
+        }
+    };
+}
+
+/// Set up the parameters of a PCS for polynomials of up to `MAX_NUM_VARIABLES` variables.
+pub fn setup_pcs<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>()
+-> Result<(PCS::ProverParam, PCS::VerifierParam), ZKVMError> {
+    let param = PCS::setup(1 << MAX_NUM_VARIABLES).map_err(ZKVMError::PCSError)?;
+    PCS::trim(param, 1 << MAX_NUM_VARIABLES).map_err(ZKVMError::PCSError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pcs_kind_from_str() {
+        for kind in [PcsKind::BasefoldRS, PcsKind::BasefoldBasecode] {
+            assert_eq!(kind.to_string().parse::<PcsKind>(), Ok(kind));
+        }
+        assert!("kzg".parse::<PcsKind>().is_err());
+    }
+}
@@ -1,19 +1,25 @@
 use ff_ext::ExtensionField;
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashMap},
+    mem::size_of,
     sync::Arc,
+    time::Instant,
 };
 
 use ff::Field;
 use itertools::{Itertools, enumerate, izip};
 use mpcs::PolynomialCommitmentScheme;
 use multilinear_extensions::{
-    mle::{IntoMLE, MultilinearExtension},
+    device::{Cpu, Device, Evals},
+    mle::{DenseMultilinearExtension, IntoMLE, MultilinearExtension},
     util::ceil_log2,
     virtual_poly::build_eq_x_r_vec,
     virtual_poly_v2::ArcMultilinearExtension,
 };
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 use sumcheck::{
     macros::{entered_span, exit_span},
     structs::{IOPProverMessage, IOPProverStateV2},
@@ -21,16 +27,19 @@ use sumcheck::{
 use transcript::{ForkableTranscript, Transcript};
 
 use crate::{
-    circuit_builder::SetTableAddrType,
+    circuit_builder::{ConstraintSystem, SetTableAddrType},
     error::ZKVMError,
     expression::Instance,
     scheme::{
         constants::{MAINCONSTRAIN_SUMCHECK_BATCH_SIZE, NUM_FANIN, NUM_FANIN_LOGUP},
+        report::{CircuitReport, ProveReport, serialized_size},
         utils::{
-            infer_tower_logup_witness, infer_tower_product_witness, interleaving_mles_to_mles,
-            wit_infer_by_expr,
+            draw_challenges, infer_tower_logup_layer, infer_tower_logup_witness,
+            infer_tower_product_layer, infer_tower_product_witness, instance_column_mles,
+            interleaving_mles_to_mles, wit_infer_by_expr, wit_infer_by_expr_on,
         },
     },
+    serialization::to_bytes,
     structs::{
         Point, ProvingKey, TowerProofs, TowerProver, TowerProverSpec, ZKVMProvingKey, ZKVMWitnesses,
     },
@@ -40,30 +49,118 @@ use crate::{
 
 use super::{PublicValues, ZKVMOpcodeProof, ZKVMProof, ZKVMTableProof};
 
-type ResultCreateTableProof<E, PCS> = (ZKVMTableProof<E, PCS>, HashMap<usize, E>);
+type ResultCreateOpcodeProof<E, PCS> = (ZKVMOpcodeProof<E, PCS>, Point<E>);
+type ResultCreateTableProof<E, PCS> = (ZKVMTableProof<E, PCS>, HashMap<usize, E>, Point<E>);
+type ResultProve<E, PCS> = (ZKVMProof<E, PCS>, ProveReport);
 
+/// The prover is deterministic: the same proving key, witness and public values give a
+/// byte-identical proof, whatever the thread count or the options below. Field arithmetic is
+/// exact, so parallel reductions do not depend on their order, and nothing is blinded.
 pub struct ZKVMProver<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub pk: ZKVMProvingKey<E, PCS>,
+    /// prove the circuits in parallel rather than one after the other
+    parallel_circuits: bool,
+    /// bound on the memory of the circuits proven at once, in bytes
+    memory_budget: Option<usize>,
+    /// the device of the witness inference, sumcheck and evaluation kernels
+    device: Arc<dyn Device<E>>,
+}
+
+/// The proof of a single circuit, see `ZKVMProver::prove_circuit`, with the point its
+/// committed polynomials are to be opened at.
+enum CircuitProof<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    Opcode(ZKVMOpcodeProof<E, PCS>, Point<E>),
+    /// with the evaluations of the public values opened by the table
+    Table(ZKVMTableProof<E, PCS>, HashMap<usize, E>, Point<E>),
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
     pub fn new(pk: ZKVMProvingKey<E, PCS>) -> Self {
-        ZKVMProver { pk }
+        ZKVMProver {
+            pk,
+            parallel_circuits: false,
+            memory_budget: None,
+            device: Arc::new(Cpu),
+        }
+    }
+
+    /// Prove the opcode and table circuits in parallel. Every circuit already has its own fork
+    /// of the transcript, so the proof is the same as the sequential one; only peak memory grows
+    /// as the circuits are proven at once.
+    pub fn enable_parallel_circuits(&mut self) {
+        self.parallel_circuits = true;
+    }
+
+    /// Trade proving time for a bounded peak memory.
+    ///
+    /// The prover drops its own copy of the committed witness of a circuit, and recovers it from
+    /// the commitment while the circuit is proven. The PCS commitment data still holds the
+    /// witness polynomials until the final opening, so this avoids a second copy of them rather
+    /// than bounding the witness memory. Only the input layers of the towers are kept, and each
+    /// layer above them is recomputed when its sumcheck is proven, which costs a logarithmic
+    /// factor on tower witness inference. With parallel circuits, the circuits proven at once are
+    /// grouped so that their estimated memory stays within `bytes`; a circuit larger than the
+    /// budget is proven on its own.
+    ///
+    /// The proof is the same as without a budget.
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        self.memory_budget = Some(bytes);
+    }
+
+    /// Run the hot kernels of the prover on `device` rather than on [`Cpu`]. The proof is the
+    /// same, as a device agrees with [`Cpu`] bit for bit.
+    pub fn set_device(&mut self, device: Arc<dyn Device<E>>) {
+        self.device = device;
     }
 
     /// create proof for zkvm execution
+    pub fn create_proof(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+        transcript: impl ForkableTranscript<E> + Send,
+    ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
+        self.prove(witnesses, pi, transcript)
+            .map(|(vm_proof, _)| vm_proof)
+    }
+
+    /// Create a proof, along with the sizes and timings of each circuit. The proof is the same
+    /// as the one of `create_proof`.
+    pub fn create_proof_with_report(
+        &self,
+        witnesses: ZKVMWitnesses<E>,
+        pi: PublicValues<u32>,
+        transcript: impl ForkableTranscript<E> + Send,
+    ) -> Result<(ZKVMProof<E, PCS>, ProveReport), ZKVMError> {
+        let (vm_proof, mut report) = self.prove(witnesses, pi, transcript)?;
+        report.proof_bytes = to_bytes(&vm_proof)?.len();
+        report.opening_proof_bytes = serialized_size(&vm_proof.opening_proof)?;
+        for (circuit_name, (_, proof)) in &vm_proof.opcode_proofs {
+            let circuit = report.circuits.get_mut(circuit_name).unwrap();
+            circuit.tower_proof_bytes = serialized_size(&proof.tower_proof)?;
+            circuit.proof_bytes = serialized_size(proof)?;
+        }
+        for (circuit_name, (_, proof)) in &vm_proof.table_proofs {
+            let circuit = report.circuits.get_mut(circuit_name).unwrap();
+            circuit.tower_proof_bytes = serialized_size(&proof.tower_proof)?;
+            circuit.proof_bytes = serialized_size(proof)?;
+        }
+        Ok((vm_proof, report))
+    }
+
+    /// Create a proof, along with the timings of each circuit with instances.
     #[tracing::instrument(
         skip_all,
         name = "ZKVM_create_proof",
         fields(profiling_1),
         level = "trace"
     )]
-    pub fn create_proof(
+    fn prove(
         &self,
-        witnesses: ZKVMWitnesses<E>,
+        mut witnesses: ZKVMWitnesses<E>,
         pi: PublicValues<u32>,
-        mut transcript: impl ForkableTranscript<E>,
-    ) -> Result<ZKVMProof<E, PCS>, ZKVMError> {
+        mut transcript: impl ForkableTranscript<E> + Send,
+    ) -> Result<ResultProve<E, PCS>, ZKVMError> {
         let span = entered_span!("commit_to_fixed_commit", profiling_1 = true);
         let mut vm_proof = ZKVMProof::empty(pi);
 
@@ -93,6 +190,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         // commit to main traces
         let mut commitments = BTreeMap::new();
         let mut wits = BTreeMap::new();
+        let mut reports = BTreeMap::new();
+        let mut instance_columns = witnesses.take_instance_columns();
 
         let commit_to_traces_span = entered_span!("commit_to_traces", profiling_1 = true);
         // commit to opcode circuits first and then commit to table circuits, sorted by name
@@ -107,12 +206,37 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 0 => vec![],
                 _ => {
                     let witness = witness.into_mles();
+                    let commit_time = Instant::now();
                     commitments.insert(
                         circuit_name.clone(),
                         PCS::batch_commit_and_write(&self.pk.pp, &witness, &mut transcript)
                             .map_err(ZKVMError::PCSError)?,
                     );
-                    witness
+                    reports.insert(circuit_name.clone(), CircuitReport {
+                        num_instances,
+                        witness_bytes: witness
+                            .iter()
+                            .map(|mle| mle.evaluations().len())
+                            .sum::<usize>()
+                            * size_of::<E::BaseField>(),
+                        commit_time: commit_time.elapsed(),
+                        ..Default::default()
+                    });
+                    // instance columns are sent in the clear
+                    for v in instance_columns
+                        .get(&circuit_name)
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                    {
+                        transcript.append_field_element(v);
+                    }
+                    // under a budget, the commitment keeps the only copy of the witness
+                    if self.memory_budget.is_some() {
+                        vec![]
+                    } else {
+                        witness
+                    }
                 }
             };
             exit_span!(span);
@@ -120,108 +244,302 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         }
         exit_span!(commit_to_traces_span);
 
-        // squeeze two challenges from transcript
-        let challenges = [
-            transcript.read_challenge().elements,
-            transcript.read_challenge().elements,
-        ];
+        // squeeze the challenges from transcript
+        let challenges = draw_challenges(&mut transcript);
         tracing::debug!("challenges in prover: {:?}", challenges);
 
         let main_proofs_span = entered_span!("main_proofs", profiling_1 = true);
-        let mut transcripts = transcript.fork(self.pk.circuit_pks.len());
+        // each circuit is proven on its own fork of the transcript, indexed by its position in
+        // the sorted circuits, so the proofs do not depend on the order they are generated in.
+        // The last fork opens the commitments of all the circuits.
+        let mut transcripts = transcript.fork(self.pk.circuit_pks.len() + 1);
+        let mut opening_transcript = transcripts.pop().unwrap();
+        let mut circuits = vec![];
         for ((circuit_name, pk), (i, transcript)) in self
             .pk
             .circuit_pks
             .iter() // Sorted by key.
-            .zip_eq(transcripts.iter_mut().enumerate())
+            .zip_eq(transcripts.into_iter().enumerate())
         {
-            let (witness, num_instances) = wits
-                .remove(circuit_name)
-                .ok_or(ZKVMError::WitnessNotFound(circuit_name.clone()))?;
-            if witness.is_empty() {
+            let (witness, num_instances) =
+                wits.remove(circuit_name)
+                    .ok_or_else(|| ZKVMError::WitnessNotFound {
+                        circuit: circuit_name.clone(),
+                    })?;
+            if num_instances == 0 {
                 continue;
             }
-            let wits_commit = commitments.remove(circuit_name).unwrap();
-            // TODO: add an enum for circuit type either in constraint_system or vk
-            let cs = pk.get_cs();
-            let is_opcode_circuit = cs.lk_table_expressions.is_empty()
-                && cs.r_table_expressions.is_empty()
-                && cs.w_table_expressions.is_empty();
-
-            if is_opcode_circuit {
-                tracing::debug!(
-                    "opcode circuit {} has {} witnesses, {} reads, {} writes, {} lookups",
-                    circuit_name,
-                    cs.num_witin,
-                    cs.r_expressions.len(),
-                    cs.w_expressions.len(),
-                    cs.lk_expressions.len(),
-                );
-                let opcode_proof = self.create_opcode_proof(
-                    circuit_name,
-                    &self.pk.pp,
-                    pk,
-                    witness.into_iter().map(|w| w.into()).collect_vec(),
-                    wits_commit,
-                    &pi,
-                    num_instances,
-                    transcript,
-                    &challenges,
-                )?;
-                tracing::info!(
-                    "generated proof for opcode {} with num_instances={}",
-                    circuit_name,
-                    num_instances
-                );
-                vm_proof
-                    .opcode_proofs
-                    .insert(circuit_name.clone(), (i, opcode_proof));
-            } else {
-                let (table_proof, pi_in_evals) = self.create_table_proof(
-                    circuit_name,
-                    &self.pk.pp,
-                    pk,
-                    witness.into_iter().map(|v| v.into()).collect_vec(),
-                    wits_commit,
-                    &pi,
-                    transcript,
-                    &challenges,
-                )?;
-                tracing::info!(
-                    "generated proof for table {} with num_instances={}",
-                    circuit_name,
-                    num_instances
+            let wits_commit = &commitments[circuit_name];
+            let columns = instance_columns.remove(circuit_name).unwrap_or_default();
+            let report = reports.remove(circuit_name).unwrap();
+            circuits.push((
+                i,
+                circuit_name,
+                pk,
+                witness,
+                num_instances,
+                wits_commit,
+                columns,
+                transcript,
+                report,
+            ));
+        }
+        let prove = |circuit: (usize, &String, _, _, _, _, _, _, CircuitReport)| {
+            let (
+                i,
+                circuit_name,
+                pk,
+                witness,
+                num_instances,
+                wits_commit,
+                columns,
+                mut transcript,
+                mut report,
+            ) = circuit;
+            let witness = match self.memory_budget {
+                Some(_) => PCS::get_committed_polys(wits_commit),
+                None => witness,
+            };
+            self.prove_circuit(
+                circuit_name,
+                pk,
+                witness,
+                num_instances,
+                wits_commit,
+                columns,
+                &pi,
+                &mut transcript,
+                &challenges,
+                &mut report,
+            )
+            .map(|proof| (i, circuit_name.clone(), proof, report))
+            .map_err(|err| err.in_circuit(circuit_name))
+        };
+        let circuit_proofs = if self.parallel_circuits {
+            let batches = match self.memory_budget {
+                Some(budget) => batch_by_budget(circuits, budget, |circuit| {
+                    estimated_proving_memory(circuit.2.get_cs(), circuit.4)
+                }),
+                None => vec![circuits],
+            };
+            let mut circuit_proofs = vec![];
+            for batch in batches {
+                circuit_proofs.extend(
+                    batch
+                        .into_par_iter()
+                        .map(prove)
+                        .collect::<Result<Vec<_>, ZKVMError>>()?,
                 );
-                vm_proof
-                    .table_proofs
-                    .insert(circuit_name.clone(), (i, table_proof));
-                for (idx, eval) in pi_in_evals {
-                    vm_proof.update_pi_eval(idx, eval);
+            }
+            circuit_proofs
+        } else {
+            circuits
+                .into_iter()
+                .map(prove)
+                .collect::<Result<Vec<_>, ZKVMError>>()?
+        };
+        let mut opening_points = BTreeMap::new();
+        for (i, circuit_name, proof, report) in circuit_proofs {
+            reports.insert(circuit_name.clone(), report);
+            match proof {
+                CircuitProof::Opcode(opcode_proof, point) => {
+                    vm_proof
+                        .opcode_proofs
+                        .insert(circuit_name.clone(), (i, opcode_proof));
+                    opening_points.insert(circuit_name, point);
+                }
+                CircuitProof::Table(table_proof, pi_in_evals, point) => {
+                    vm_proof
+                        .table_proofs
+                        .insert(circuit_name.clone(), (i, table_proof));
+                    for (idx, eval) in pi_in_evals {
+                        vm_proof.update_pi_eval(idx, eval);
+                    }
+                    opening_points.insert(circuit_name, point);
                 }
             }
         }
         exit_span!(main_proofs_span);
 
-        Ok(vm_proof)
+        // open the witnesses of all the circuits, and the fixed traces of the tables, at once.
+        // The points and evaluations are sorted by circuit, the fixed traces of a table first.
+        let pcs_opening = entered_span!("pcs_opening", profiling_1 = true);
+        let opening_time = Instant::now();
+        let mut comms = vec![];
+        let mut points = vec![];
+        let mut evals = vec![];
+        for (circuit_name, pk) in &self.pk.circuit_pks {
+            let Some(point) = opening_points.remove(circuit_name) else {
+                continue;
+            };
+            if let Some((_, proof)) = vm_proof.opcode_proofs.get(circuit_name) {
+                evals.push(proof.wits_in_evals.clone());
+            } else {
+                let (_, proof) = &vm_proof.table_proofs[circuit_name];
+                if let Some(fixed_commit_wd) = &pk.fixed_commit_wd {
+                    comms.push(fixed_commit_wd);
+                    points.push(point.clone());
+                    evals.push(proof.fixed_in_evals.clone());
+                }
+                evals.push(proof.wits_in_evals.clone());
+            }
+            comms.push(&commitments[circuit_name]);
+            points.push(point);
+        }
+        if !comms.is_empty() {
+            for (point, evals) in points.iter().zip_eq(&evals) {
+                opening_transcript.append_field_element_exts(point);
+                opening_transcript.append_field_element_exts(evals);
+            }
+            tracing::debug!("build opening proof for {} commitments", comms.len());
+            vm_proof.opening_proof = Some(
+                PCS::multi_batch_open(
+                    &self.pk.pp,
+                    &comms,
+                    &points,
+                    &evals,
+                    &mut opening_transcript,
+                )
+                .map_err(ZKVMError::PCSError)?,
+            );
+        }
+        let opening_time = opening_time.elapsed();
+        exit_span!(pcs_opening);
+
+        let report = ProveReport {
+            circuits: reports,
+            opening_time,
+            ..Default::default()
+        };
+        Ok((vm_proof, report))
+    }
+    /// Prove a single circuit, on its own fork of the transcript.
+    #[allow(clippy::too_many_arguments)]
+    fn prove_circuit(
+        &self,
+        circuit_name: &str,
+        pk: &ProvingKey<E, PCS>,
+        witness: Vec<DenseMultilinearExtension<E>>,
+        num_instances: usize,
+        wits_commit: &PCS::CommitmentWithData,
+        columns: Vec<Vec<E::BaseField>>,
+        pi: &[ArcMultilinearExtension<'_, E>],
+        transcript: &mut impl Transcript<E>,
+        challenges: &[E; 2],
+        report: &mut CircuitReport,
+    ) -> Result<CircuitProof<E, PCS>, ZKVMError> {
+        let cs = pk.get_cs();
+        if cs.is_opcode_circuit() {
+            tracing::debug!(
+                "opcode circuit {} has {} witnesses, {} reads, {} writes, {} lookups",
+                circuit_name,
+                cs.num_witin,
+                cs.r_expressions.len(),
+                cs.w_expressions.len(),
+                cs.lk_expressions.len(),
+            );
+            if columns.len() != cs.num_instance_columns {
+                return Err(ZKVMError::InstanceColumnCount {
+                    circuit: circuit_name.to_string(),
+                    expected: cs.num_instance_columns,
+                    got: columns.len(),
+                });
+            }
+            // public values, then the instance columns of this circuit
+            let circuit_pi = pi
+                .iter()
+                .cloned()
+                .chain(
+                    instance_column_mles(&columns, num_instances)
+                        .into_iter()
+                        .map(|mle| -> ArcMultilinearExtension<E> { Arc::new(mle) }),
+                )
+                .collect_vec();
+            let (mut opcode_proof, point) = self.prove_opcode(
+                circuit_name,
+                pk,
+                witness.into_iter().map(|w| w.into()).collect_vec(),
+                wits_commit,
+                &circuit_pi,
+                num_instances,
+                transcript,
+                challenges,
+                report,
+            )?;
+            opcode_proof.instance_columns = columns;
+            tracing::info!(
+                "generated proof for opcode {} with num_instances={}",
+                circuit_name,
+                num_instances
+            );
+            Ok(CircuitProof::Opcode(opcode_proof, point))
+        } else {
+            let (table_proof, pi_in_evals, point) = self.prove_table(
+                circuit_name,
+                pk,
+                witness.into_iter().map(|v| v.into()).collect_vec(),
+                wits_commit,
+                pi,
+                transcript,
+                challenges,
+                report,
+            )?;
+            tracing::info!(
+                "generated proof for table {} with num_instances={}",
+                circuit_name,
+                num_instances
+            );
+            Ok(CircuitProof::Table(table_proof, pi_in_evals, point))
+        }
     }
+
     /// create proof giving witness and num_instances
     /// major flow break down into
     /// 1: witness layer inferring from input -> output
     /// 2: proof (sumcheck reduce) from output to input
+    ///
+    /// The witness is not opened: the proof comes with the point to open `wits_commit` at.
     #[allow(clippy::too_many_arguments)]
-    #[tracing::instrument(skip_all, name = "create_opcode_proof", fields(circuit_name=name,profiling_2), level="trace")]
     pub fn create_opcode_proof(
         &self,
         name: &str,
-        pp: &PCS::ProverParam,
         circuit_pk: &ProvingKey<E, PCS>,
         witnesses: Vec<ArcMultilinearExtension<'_, E>>,
-        wits_commit: PCS::CommitmentWithData,
+        wits_commit: &PCS::CommitmentWithData,
+        pi: &[ArcMultilinearExtension<'_, E>],
+        num_instances: usize,
+        transcript: &mut impl Transcript<E>,
+        challenges: &[E; 2],
+    ) -> Result<ResultCreateOpcodeProof<E, PCS>, ZKVMError> {
+        self.prove_opcode(
+            name,
+            circuit_pk,
+            witnesses,
+            wits_commit,
+            pi,
+            num_instances,
+            transcript,
+            challenges,
+            &mut CircuitReport::default(),
+        )
+    }
+
+    /// `create_opcode_proof`, recording its timings in `report`.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all, name = "create_opcode_proof", fields(circuit_name=name,profiling_2), level="trace")]
+    fn prove_opcode(
+        &self,
+        name: &str,
+        circuit_pk: &ProvingKey<E, PCS>,
+        witnesses: Vec<ArcMultilinearExtension<'_, E>>,
+        wits_commit: &PCS::CommitmentWithData,
         pi: &[ArcMultilinearExtension<'_, E>],
         num_instances: usize,
         transcript: &mut impl Transcript<E>,
         challenges: &[E; 2],
-    ) -> Result<ZKVMOpcodeProof<E, PCS>, ZKVMError> {
+        report: &mut CircuitReport,
+    ) -> Result<ResultCreateOpcodeProof<E, PCS>, ZKVMError> {
+        let sumcheck_time = Instant::now();
         let cs = circuit_pk.get_cs();
         let next_pow2_instances = next_pow2_instance_padding(num_instances);
         let log2_num_instances = ceil_log2(next_pow2_instances);
@@ -245,7 +563,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .chain(cs.lk_expressions.par_iter())
             .map(|expr| {
                 assert_eq!(expr.degree(), 1);
-                wit_infer_by_expr(&[], &witnesses, pi, challenges, expr)
+                wit_infer_by_expr_on(&*self.device, &[], &witnesses, pi, challenges, expr)
             })
             .collect();
         let (r_records_wit, w_lk_records_wit) = records_wit.split_at(cs.r_expressions.len());
@@ -272,15 +590,6 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         assert_eq!(r_records_last_layer.len(), NUM_FANIN);
         exit_span!(span);
 
-        // infer all tower witness after last layer
-        let span = entered_span!("tower_witness_r_layers");
-        let r_wit_layers = infer_tower_product_witness(
-            log2_num_instances + log2_r_count,
-            r_records_last_layer,
-            NUM_FANIN,
-        );
-        exit_span!(span);
-
         let span = entered_span!("tower_witness_w_last_layer");
         // TODO optimize last layer to avoid alloc new vector to save memory
         let w_records_last_layer =
@@ -288,14 +597,6 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         assert_eq!(w_records_last_layer.len(), NUM_FANIN);
         exit_span!(span);
 
-        let span = entered_span!("tower_witness_w_layers");
-        let w_wit_layers = infer_tower_product_witness(
-            log2_num_instances + log2_w_count,
-            w_records_last_layer,
-            NUM_FANIN,
-        );
-        exit_span!(span);
-
         let span = entered_span!("tower_witness_lk_last_layer");
         // TODO optimize last layer to avoid alloc new vector to save memory
         let lk_records_last_layer =
@@ -303,17 +604,45 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         assert_eq!(lk_records_last_layer.len(), 2);
         exit_span!(span);
 
-        let span = entered_span!("tower_witness_lk_layers");
-        let lk_wit_layers = infer_tower_logup_witness(None, lk_records_last_layer);
+        // infer all tower witness after last layer, the towers being independent
+        let span = entered_span!("tower_witness_layers");
+        let streamed = self.memory_budget.is_some();
+        let (r_wit_layers, (w_wit_layers, lk_wit_layers)) = rayon::join(
+            || {
+                TowerProverSpec::product(
+                    log2_num_instances + log2_r_count,
+                    r_records_last_layer,
+                    NUM_FANIN,
+                    streamed,
+                )
+            },
+            || {
+                rayon::join(
+                    || {
+                        TowerProverSpec::product(
+                            log2_num_instances + log2_w_count,
+                            w_records_last_layer,
+                            NUM_FANIN,
+                            streamed,
+                        )
+                    },
+                    || TowerProverSpec::logup(None, lk_records_last_layer, streamed),
+                )
+            },
+        );
         exit_span!(span);
         exit_span!(wit_inference_span);
 
         if cfg!(test) {
             // sanity check
-            assert_eq!(lk_wit_layers.len(), log2_num_instances + log2_lk_count);
-            assert_eq!(r_wit_layers.len(), log2_num_instances + log2_r_count);
-            assert_eq!(w_wit_layers.len(), log2_num_instances + log2_w_count);
-            assert!(lk_wit_layers.iter().enumerate().all(|(i, w)| {
+            assert_eq!(
+                lk_wit_layers.num_layers(),
+                log2_num_instances + log2_lk_count
+            );
+            assert_eq!(r_wit_layers.num_layers(), log2_num_instances + log2_r_count);
+            assert_eq!(w_wit_layers.num_layers(), log2_num_instances + log2_w_count);
+            assert!((0..lk_wit_layers.num_layers()).all(|i| {
+                let w = lk_wit_layers.layer(i);
                 let expected_size = 1 << i;
                 let (p1, p2, q1, q2) = (&w[0], &w[1], &w[2], &w[3]);
                 p1.evaluations().len() == expected_size
@@ -321,14 +650,16 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                     && q1.evaluations().len() == expected_size
                     && q2.evaluations().len() == expected_size
             }));
-            assert!(r_wit_layers.iter().enumerate().all(|(i, r_wit_layer)| {
+            assert!((0..r_wit_layers.num_layers()).all(|i| {
+                let r_wit_layer = r_wit_layers.layer(i);
                 let expected_size = 1 << (ceil_log2(NUM_FANIN) * i);
                 r_wit_layer.len() == NUM_FANIN
                     && r_wit_layer
                         .iter()
                         .all(|f| f.evaluations().len() == expected_size)
             }));
-            assert!(w_wit_layers.iter().enumerate().all(|(i, w_wit_layer)| {
+            assert!((0..w_wit_layers.num_layers()).all(|i| {
+                let w_wit_layer = w_wit_layers.layer(i);
                 let expected_size = 1 << (ceil_log2(NUM_FANIN) * i);
                 w_wit_layer.len() == NUM_FANIN
                     && w_wit_layer
@@ -341,31 +672,20 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         // product constraint tower sumcheck
         let tower_span = entered_span!("tower");
         // final evals for verifier
-        let record_r_out_evals: Vec<E> = r_wit_layers[0]
-            .iter()
-            .map(|w| w.get_ext_field_vec()[0])
-            .collect();
-        let record_w_out_evals: Vec<E> = w_wit_layers[0]
-            .iter()
-            .map(|w| w.get_ext_field_vec()[0])
-            .collect();
-        let lk_p1_out_eval = lk_wit_layers[0][0].get_ext_field_vec()[0];
-        let lk_p2_out_eval = lk_wit_layers[0][1].get_ext_field_vec()[0];
-        let lk_q1_out_eval = lk_wit_layers[0][2].get_ext_field_vec()[0];
-        let lk_q2_out_eval = lk_wit_layers[0][3].get_ext_field_vec()[0];
+        let record_r_out_evals = r_wit_layers.out_evals();
+        let record_w_out_evals = w_wit_layers.out_evals();
+        let lk_out_evals = lk_wit_layers.out_evals();
+        let (lk_p1_out_eval, lk_p2_out_eval, lk_q1_out_eval, lk_q2_out_eval) = (
+            lk_out_evals[0],
+            lk_out_evals[1],
+            lk_out_evals[2],
+            lk_out_evals[3],
+        );
         assert!(record_r_out_evals.len() == NUM_FANIN && record_w_out_evals.len() == NUM_FANIN);
-        let (rt_tower, tower_proof) = TowerProver::create_proof(
-            vec![
-                TowerProverSpec {
-                    witness: r_wit_layers,
-                },
-                TowerProverSpec {
-                    witness: w_wit_layers,
-                },
-            ],
-            vec![TowerProverSpec {
-                witness: lk_wit_layers,
-            }],
+        let (rt_tower, tower_proof) = TowerProver::create_proof_on(
+            &*self.device,
+            vec![r_wit_layers, w_wit_layers],
+            vec![lk_wit_layers],
             NUM_FANIN,
             transcript,
         );
@@ -516,7 +836,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             assert!(sel_non_lc_zero_sumcheck.is_some());
 
             // \sum_t (sel(rt, t) * (\sum_j alpha_{j} * all_monomial_terms(t) ))
-            for ((expr, name), alpha) in cs
+            for ((expr, constraint), alpha) in cs
                 .assert_zero_sumcheck_expressions
                 .iter()
                 .zip_eq(cs.assert_zero_sumcheck_expressions_namespace_map.iter())
@@ -526,18 +846,16 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 if cfg!(debug_assertions) {
                     let expected_zero_poly =
                         wit_infer_by_expr(&[], &witnesses, pi, challenges, expr);
-                    let top_100_errors = expected_zero_poly
-                        .get_base_field_vec()
+                    // the selector excludes the padding rows
+                    let first_error = expected_zero_poly.get_base_field_vec()[..num_instances]
                         .iter()
-                        .enumerate()
-                        .filter(|(_, v)| **v != E::BaseField::ZERO)
-                        .take(100)
-                        .collect_vec();
-                    if !top_100_errors.is_empty() {
-                        return Err(ZKVMError::InvalidWitness(format!(
-                            "degree > 1 zero check virtual poly: expr {name} != 0 on instance indexes: {}...",
-                            top_100_errors.into_iter().map(|(i, _)| i).join(",")
-                        )));
+                        .position(|v| *v != E::BaseField::ZERO);
+                    if let Some(row) = first_error {
+                        return Err(ZKVMError::UnsatisfiedConstraint {
+                            circuit: name.to_string(),
+                            constraint: constraint.clone(),
+                            row,
+                        });
                     }
                 }
 
@@ -552,7 +870,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         }
 
         tracing::debug!("main sel sumcheck start");
-        let (main_sel_sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys(
+        let (main_sel_sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys_on(
+            &*self.device,
             num_threads,
             virtual_polys.get_batched_polys(),
             transcript,
@@ -603,69 +922,83 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let span = entered_span!("witin::evals", profiling_3 = true);
         let wits_in_evals: Vec<E> = witnesses
             .par_iter()
-            .map(|poly| poly.evaluate(&input_open_point))
+            .map(|poly| {
+                self.device
+                    .evaluate(Evals::of(poly.as_ref()), &input_open_point)
+            })
             .collect();
         exit_span!(span);
 
-        let pcs_open_span = entered_span!("pcs_open", profiling_3 = true);
-        let opening_dur = std::time::Instant::now();
-        tracing::debug!(
-            "[opcode {}]: build opening proof for {} polys",
-            name,
-            witnesses.len()
-        );
-        let wits_opening_proof = PCS::simple_batch_open(
-            pp,
-            &witnesses,
-            &wits_commit,
-            &input_open_point,
-            wits_in_evals.as_slice(),
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
-        tracing::info!(
-            "[opcode {}] build opening proof took {:?}",
-            name,
-            opening_dur.elapsed(),
-        );
-        exit_span!(pcs_open_span);
-        let wits_commit = PCS::get_pure_commitment(&wits_commit);
+        report.sumcheck_time = sumcheck_time.elapsed();
+        // the witness is opened with those of the other circuits, see `prove`
+        let wits_commit = PCS::get_pure_commitment(wits_commit);
 
-        Ok(ZKVMOpcodeProof {
-            num_instances,
-            record_r_out_evals,
-            record_w_out_evals,
-            lk_p1_out_eval,
-            lk_p2_out_eval,
-            lk_q1_out_eval,
-            lk_q2_out_eval,
-            tower_proof,
-            main_sel_sumcheck_proofs: main_sel_sumcheck_proofs.proofs,
-            r_records_in_evals,
-            w_records_in_evals,
-            lk_records_in_evals,
-            wits_commit,
-            wits_opening_proof,
-            wits_in_evals,
-        })
+        Ok((
+            ZKVMOpcodeProof {
+                num_instances,
+                record_r_out_evals,
+                record_w_out_evals,
+                lk_p1_out_eval,
+                lk_p2_out_eval,
+                lk_q1_out_eval,
+                lk_q2_out_eval,
+                tower_proof,
+                main_sel_sumcheck_proofs: main_sel_sumcheck_proofs.proofs,
+                r_records_in_evals,
+                w_records_in_evals,
+                lk_records_in_evals,
+                wits_commit,
+                wits_in_evals,
+                instance_columns: vec![],
+            },
+            input_open_point,
+        ))
     }
 
     #[allow(clippy::too_many_arguments)]
     /// support batch prove for logup + product arguments each with different num_vars()
     /// side effect: concurrency will be determine based on min(thread, num_vars()),
     /// so suggest dont batch too small table (size < threads) with large table together
-    #[tracing::instrument(skip_all, name = "create_table_proof", fields(table_name=name, profiling_2), level="trace")]
+    ///
+    /// Neither the witness nor the fixed traces are opened: the proof comes with the point to
+    /// open them at.
     pub fn create_table_proof(
         &self,
         name: &str,
-        pp: &PCS::ProverParam,
         circuit_pk: &ProvingKey<E, PCS>,
         witnesses: Vec<ArcMultilinearExtension<'_, E>>,
-        wits_commit: PCS::CommitmentWithData,
+        wits_commit: &PCS::CommitmentWithData,
         pi: &[ArcMultilinearExtension<'_, E>],
         transcript: &mut impl Transcript<E>,
         challenges: &[E; 2],
     ) -> Result<ResultCreateTableProof<E, PCS>, ZKVMError> {
+        self.prove_table(
+            name,
+            circuit_pk,
+            witnesses,
+            wits_commit,
+            pi,
+            transcript,
+            challenges,
+            &mut CircuitReport::default(),
+        )
+    }
+
+    /// `create_table_proof`, recording its timings in `report`.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all, name = "create_table_proof", fields(table_name=name, profiling_2), level="trace")]
+    fn prove_table(
+        &self,
+        name: &str,
+        circuit_pk: &ProvingKey<E, PCS>,
+        witnesses: Vec<ArcMultilinearExtension<'_, E>>,
+        wits_commit: &PCS::CommitmentWithData,
+        pi: &[ArcMultilinearExtension<'_, E>],
+        transcript: &mut impl Transcript<E>,
+        challenges: &[E; 2],
+        report: &mut CircuitReport,
+    ) -> Result<ResultCreateTableProof<E, PCS>, ZKVMError> {
+        let sumcheck_time = Instant::now();
         let cs = circuit_pk.get_cs();
         let fixed = circuit_pk
             .fixed_traces
@@ -714,7 +1047,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .chain(cs.lk_table_expressions.par_iter().map(|lk| &lk.values))
             .map(|expr| {
                 assert_eq!(expr.degree(), 1);
-                wit_infer_by_expr(&fixed, &witnesses, pi, challenges, expr)
+                wit_infer_by_expr_on(&*self.device, &fixed, &witnesses, pi, challenges, expr)
             })
             .collect();
         let max_log2_num_instance = records_wit.iter().map(|mle| mle.num_vars()).max().unwrap();
@@ -777,25 +1110,27 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         exit_span!(span);
 
         let span = entered_span!("tower_witness_lk_layers");
+        let streamed = self.memory_budget.is_some();
+        // the towers are independent, and rayon balances their inference across threads
         let r_wit_layers = r_set_last_layer
-            .into_iter()
-            .zip(r_set_wit.iter())
+            .into_par_iter()
+            .zip(r_set_wit.par_iter())
             .map(|(last_layer, origin_mle)| {
-                infer_tower_product_witness(origin_mle.num_vars(), last_layer, NUM_FANIN)
+                TowerProverSpec::product(origin_mle.num_vars(), last_layer, NUM_FANIN, streamed)
             })
-            .collect_vec();
+            .collect::<Vec<_>>();
         let w_wit_layers = w_set_last_layer
-            .into_iter()
-            .zip(w_set_wit.iter())
+            .into_par_iter()
+            .zip(w_set_wit.par_iter())
             .map(|(last_layer, origin_mle)| {
-                infer_tower_product_witness(origin_mle.num_vars(), last_layer, NUM_FANIN)
+                TowerProverSpec::product(origin_mle.num_vars(), last_layer, NUM_FANIN, streamed)
             })
-            .collect_vec();
+            .collect::<Vec<_>>();
         let lk_wit_layers = lk_numerator_last_layer
-            .into_iter()
+            .into_par_iter()
             .zip(lk_denominator_last_layer)
-            .map(|(lk_n, lk_d)| infer_tower_logup_witness(Some(lk_n), lk_d))
-            .collect_vec();
+            .map(|(lk_n, lk_d)| TowerProverSpec::logup(Some(lk_n), lk_d, streamed))
+            .collect::<Vec<_>>();
         exit_span!(span);
         exit_span!(wit_inference_span);
 
@@ -806,10 +1141,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 r_wit_layers
                     .iter()
                     .zip(r_set_wit.iter()) // depth equals to num_vars
-                    .all(|(layers, origin_mle)| layers.len() == origin_mle.num_vars())
+                    .all(|(layers, origin_mle)| layers.num_layers() == origin_mle.num_vars())
             );
             assert!(r_wit_layers.iter().all(|layers| {
-                layers.iter().enumerate().all(|(i, w)| {
+                (0..layers.num_layers()).all(|i| {
+                    let w = layers.layer(i);
                     let expected_size = 1 << i;
                     w[0].evaluations().len() == expected_size
                         && w[1].evaluations().len() == expected_size
@@ -821,10 +1157,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 w_wit_layers
                     .iter()
                     .zip(w_set_wit.iter()) // depth equals to num_vars
-                    .all(|(layers, origin_mle)| layers.len() == origin_mle.num_vars())
+                    .all(|(layers, origin_mle)| layers.num_layers() == origin_mle.num_vars())
             );
             assert!(w_wit_layers.iter().all(|layers| {
-                layers.iter().enumerate().all(|(i, w)| {
+                (0..layers.num_layers()).all(|i| {
+                    let w = layers.layer(i);
                     let expected_size = 1 << i;
                     w[0].evaluations().len() == expected_size
                         && w[1].evaluations().len() == expected_size
@@ -836,10 +1173,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 lk_wit_layers
                     .iter()
                     .zip(lk_n_wit.iter()) // depth equals to num_vars
-                    .all(|(layers, origin_mle)| layers.len() == origin_mle.num_vars())
+                    .all(|(layers, origin_mle)| layers.num_layers() == origin_mle.num_vars())
             );
             assert!(lk_wit_layers.iter().all(|layers| {
-                layers.iter().enumerate().all(|(i, w)| {
+                (0..layers.num_layers()).all(|i| {
+                    let w = layers.layer(i);
                     let expected_size = 1 << i;
                     let (p1, p2, q1, q2) = (&w[0], &w[1], &w[2], &w[3]);
                     p1.evaluations().len() == expected_size
@@ -854,54 +1192,29 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         // product constraint tower sumcheck
         let tower_span = entered_span!("tower");
         // final evals for verifier
-        let r_out_evals = r_wit_layers
+        let r_out_evals: Vec<[E; 2]> = r_wit_layers
             .iter()
-            .map(|r_wit_layers| {
-                [
-                    r_wit_layers[0][0].get_ext_field_vec()[0],
-                    r_wit_layers[0][1].get_ext_field_vec()[0],
-                ]
-            })
+            .map(|r_wit_layers| r_wit_layers.out_evals().try_into().unwrap())
             .collect_vec();
-        let w_out_evals = w_wit_layers
+        let w_out_evals: Vec<[E; 2]> = w_wit_layers
             .iter()
-            .map(|w_wit_layers| {
-                [
-                    w_wit_layers[0][0].get_ext_field_vec()[0],
-                    w_wit_layers[0][1].get_ext_field_vec()[0],
-                ]
-            })
+            .map(|w_wit_layers| w_wit_layers.out_evals().try_into().unwrap())
             .collect_vec();
-        let lk_out_evals = lk_wit_layers
+        // p1, p2, q1, q2
+        let lk_out_evals: Vec<[E; 4]> = lk_wit_layers
             .iter()
-            .map(|lk_wit_layers| {
-                [
-                    // p1, p2, q1, q2
-                    lk_wit_layers[0][0].get_ext_field_vec()[0],
-                    lk_wit_layers[0][1].get_ext_field_vec()[0],
-                    lk_wit_layers[0][2].get_ext_field_vec()[0],
-                    lk_wit_layers[0][3].get_ext_field_vec()[0],
-                ]
-            })
+            .map(|lk_wit_layers| lk_wit_layers.out_evals().try_into().unwrap())
             .collect_vec();
 
-        let (rt_tower, tower_proof) = TowerProver::create_proof(
+        let (rt_tower, tower_proof) = TowerProver::create_proof_on(
+            &*self.device,
             // pattern [r1, w1, r2, w2, ...] same pair are chain together
             r_wit_layers
                 .into_iter()
                 .zip(w_wit_layers)
-                .flat_map(|(r, w)| {
-                    vec![TowerProverSpec { witness: r }, TowerProverSpec {
-                        witness: w,
-                    }]
-                })
-                .collect_vec(),
-            lk_wit_layers
-                .into_iter()
-                .map(|lk_wit_layers| TowerProverSpec {
-                    witness: lk_wit_layers,
-                })
+                .flat_map(|(r, w)| [r, w])
                 .collect_vec(),
+            lk_wit_layers,
             NUM_FANIN_LOGUP,
             transcript,
         );
@@ -975,7 +1288,8 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                     virtual_polys.add_mle_list(vec![eq, lk_d_wit], *alpha);
                 }
 
-                let (same_r_sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys(
+                let (same_r_sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys_on(
+                    &*self.device,
                     num_threads,
                     virtual_polys.get_batched_polys(),
                     transcript,
@@ -1018,7 +1332,12 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
         let mut evals = witnesses
             .par_iter()
             .chain(fixed.par_iter())
-            .map(|poly| poly.evaluate(&input_open_point[..poly.num_vars()]))
+            .map(|poly| {
+                self.device.evaluate(
+                    Evals::of(poly.as_ref()),
+                    &input_open_point[..poly.num_vars()],
+                )
+            })
             .collect::<Vec<_>>();
         let fixed_in_evals = evals.split_off(witnesses.len());
         let wits_in_evals = evals;
@@ -1029,7 +1348,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             let span = entered_span!("pi::evals");
             for &Instance(idx) in cs.instance_name_map.keys() {
                 let poly = &pi[idx];
-                pi_in_evals.insert(idx, poly.evaluate(&input_open_point[..poly.num_vars()]));
+                let eval = self.device.evaluate(
+                    Evals::of(poly.as_ref()),
+                    &input_open_point[..poly.num_vars()],
+                );
+                pi_in_evals.insert(idx, eval);
             }
             exit_span!(span);
         }
@@ -1046,49 +1369,10 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
             .collect_vec();
         // TODO implement mechanism to skip commitment
 
-        let pcs_opening = entered_span!("pcs_opening");
-        let (fixed_opening_proof, _fixed_commit) = if !fixed.is_empty() {
-            (
-                Some(
-                    PCS::simple_batch_open(
-                        pp,
-                        &fixed,
-                        circuit_pk.fixed_commit_wd.as_ref().unwrap(),
-                        &input_open_point,
-                        fixed_in_evals.as_slice(),
-                        transcript,
-                    )
-                    .map_err(ZKVMError::PCSError)?,
-                ),
-                Some(PCS::get_pure_commitment(
-                    circuit_pk.fixed_commit_wd.as_ref().unwrap(),
-                )),
-            )
-        } else {
-            (None, None)
-        };
-
-        tracing::debug!(
-            "[table {}] build opening proof for {} fixed polys",
-            name,
-            fixed.len()
-        );
-        let wits_opening_proof = PCS::simple_batch_open(
-            pp,
-            &witnesses,
-            &wits_commit,
-            &input_open_point,
-            wits_in_evals.as_slice(),
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
-        exit_span!(pcs_opening);
-        let wits_commit = PCS::get_pure_commitment(&wits_commit);
-        tracing::debug!(
-            "[table {}] build opening proof for {} polys",
-            name,
-            witnesses.len(),
-        );
+        report.sumcheck_time = sumcheck_time.elapsed();
+        // the witness and the fixed traces are opened with those of the other circuits, see
+        // `prove`
+        let wits_commit = PCS::get_pure_commitment(wits_commit);
 
         Ok((
             ZKVMTableProof {
@@ -1100,17 +1384,53 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProver<E, PCS> {
                 lk_in_evals,
                 tower_proof,
                 fixed_in_evals,
-                fixed_opening_proof,
                 rw_hints_num_vars,
                 wits_in_evals,
                 wits_commit,
-                wits_opening_proof,
             },
             pi_in_evals,
+            input_open_point,
         ))
     }
 }
 
+/// A rough estimate of the memory needed to prove a circuit, in bytes: its witnesses, and the
+/// input layers of its towers, which are in the extension field.
+fn estimated_proving_memory<E: ExtensionField>(
+    cs: &ConstraintSystem<E>,
+    num_instances: usize,
+) -> usize {
+    let num_rows = next_pow2_instance_padding(num_instances);
+    let num_records = cs.r_expressions.len()
+        + cs.w_expressions.len()
+        + cs.lk_expressions.len()
+        + cs.r_table_expressions.len()
+        + cs.w_table_expressions.len()
+        + 2 * cs.lk_table_expressions.len();
+    num_rows * (cs.num_witin as usize * size_of::<E::BaseField>() + num_records * size_of::<E>())
+}
+
+/// Split `items` in consecutive batches whose total size fits `budget`.
+/// An item larger than the budget gets a batch of its own.
+fn batch_by_budget<T>(items: Vec<T>, budget: usize, size: impl Fn(&T) -> usize) -> Vec<Vec<T>> {
+    let mut batches: Vec<Vec<T>> = vec![];
+    let mut batch_size = 0;
+    for item in items {
+        let item_size = size(&item);
+        match batches.last_mut() {
+            Some(batch) if batch_size + item_size <= budget => {
+                batch.push(item);
+                batch_size += item_size;
+            }
+            _ => {
+                batches.push(vec![item]);
+                batch_size = item_size;
+            }
+        }
+    }
+    batches
+}
+
 /// TowerProofs
 impl<E: ExtensionField> TowerProofs<E> {
     pub fn new(prod_spec_size: usize, logup_spec_size: usize) -> Self {
@@ -1145,14 +1465,90 @@ impl<E: ExtensionField> TowerProofs<E> {
     }
 }
 
+impl<'a, E: ExtensionField> TowerProverSpec<'a, E> {
+    /// A product tower over the `num_vars` variables of `last_layer`, see
+    /// `infer_tower_product_witness`. Only the last layer is kept when `streamed`.
+    pub fn product(
+        num_vars: usize,
+        last_layer: Vec<ArcMultilinearExtension<'a, E>>,
+        num_product_fanin: usize,
+        streamed: bool,
+    ) -> Self {
+        if streamed {
+            TowerProverSpec::StreamedProduct(num_vars, last_layer)
+        } else {
+            TowerProverSpec::Layers(infer_tower_product_witness(
+                num_vars,
+                last_layer,
+                num_product_fanin,
+            ))
+        }
+    }
+
+    /// A logup tower over the input layer `(p, q)`, see `infer_tower_logup_witness`.
+    /// Only the input layer is kept when `streamed`.
+    pub fn logup(
+        p: Option<Vec<ArcMultilinearExtension<'a, E>>>,
+        q: Vec<ArcMultilinearExtension<'a, E>>,
+        streamed: bool,
+    ) -> Self {
+        if streamed {
+            TowerProverSpec::StreamedLogup(p, q)
+        } else {
+            TowerProverSpec::Layers(infer_tower_logup_witness(p, q))
+        }
+    }
+
+    pub fn num_layers(&self) -> usize {
+        match self {
+            TowerProverSpec::Layers(layers) => layers.len(),
+            // fanin 2
+            TowerProverSpec::StreamedProduct(num_vars, _) => *num_vars,
+            TowerProverSpec::StreamedLogup(_, q) => q[0].num_vars() + 1,
+        }
+    }
+
+    /// The layer at `round`, recomputed from the input layer when streamed.
+    pub fn layer(&self, round: usize) -> Cow<'_, [ArcMultilinearExtension<'a, E>]> {
+        match self {
+            TowerProverSpec::Layers(layers) => Cow::Borrowed(&layers[round]),
+            TowerProverSpec::StreamedProduct(num_vars, last_layer) => Cow::Owned(
+                infer_tower_product_layer(*num_vars, last_layer, NUM_FANIN, round),
+            ),
+            TowerProverSpec::StreamedLogup(p, q) => {
+                Cow::Owned(infer_tower_logup_layer(p.as_deref(), q, round))
+            }
+        }
+    }
+
+    /// The evaluations of the output layer.
+    pub fn out_evals(&self) -> Vec<E> {
+        self.layer(0)
+            .iter()
+            .map(|w| w.get_ext_field_vec()[0])
+            .collect()
+    }
+}
+
 /// Tower Prover
 impl TowerProver {
-    #[tracing::instrument(skip_all, name = "tower_prover_create_proof", level = "trace")]
     pub fn create_proof<'a, E: ExtensionField>(
         prod_specs: Vec<TowerProverSpec<'a, E>>,
         logup_specs: Vec<TowerProverSpec<'a, E>>,
         num_fanin: usize,
         transcript: &mut impl Transcript<E>,
+    ) -> (Point<E>, TowerProofs<E>) {
+        Self::create_proof_on(&Cpu, prod_specs, logup_specs, num_fanin, transcript)
+    }
+
+    /// [`TowerProver::create_proof`] with the sumchecks on `device`.
+    #[tracing::instrument(skip_all, name = "tower_prover_create_proof", level = "trace")]
+    pub fn create_proof_on<'a, E: ExtensionField>(
+        device: &dyn Device<E>,
+        prod_specs: Vec<TowerProverSpec<'a, E>>,
+        logup_specs: Vec<TowerProverSpec<'a, E>>,
+        num_fanin: usize,
+        transcript: &mut impl Transcript<E>,
     ) -> (Point<E>, TowerProofs<E>) {
         // XXX to sumcheck batched product argument with logup, we limit num_product_fanin to 2
         // TODO mayber give a better naming?
@@ -1164,7 +1560,7 @@ impl TowerProver {
         let max_round_index = prod_specs
             .iter()
             .chain(logup_specs.iter())
-            .map(|m| m.witness.len())
+            .map(|m| m.num_layers())
             .max()
             .unwrap()
             - 1; // index start from 0
@@ -1186,11 +1582,19 @@ impl TowerProver {
                 let num_threads = optimal_sumcheck_threads(out_rt.len());
 
                 let eq: ArcMultilinearExtension<E> = build_eq_x_r_vec(&out_rt).into_mle().into();
+                // streamed layers are recomputed for each spec in parallel
+                let prod_layers = prod_specs
+                    .par_iter()
+                    .map(|s| (round < s.num_layers()).then(|| s.layer(round)))
+                    .collect::<Vec<_>>();
+                let logup_layers = logup_specs
+                    .par_iter()
+                    .map(|s| (round < s.num_layers()).then(|| s.layer(round)))
+                    .collect::<Vec<_>>();
                 let mut virtual_polys = VirtualPolynomials::<E>::new(num_threads, out_rt.len());
 
-                for (s, alpha) in izip!(&prod_specs, &alpha_pows) {
-                    if round < s.witness.len() {
-                        let layer_polys = &s.witness[round];
+                for (layer_polys, alpha) in izip!(&prod_layers, &alpha_pows) {
+                    if let Some(layer_polys) = layer_polys {
 
                         // sanity check
                         assert_eq!(layer_polys.len(), num_fanin);
@@ -1210,10 +1614,10 @@ impl TowerProver {
                     }
                 }
 
-                for (s, alpha) in izip!(&logup_specs, alpha_pows[prod_specs.len()..].chunks(2))
+                for (layer_polys, alpha) in
+                    izip!(&logup_layers, alpha_pows[prod_specs.len()..].chunks(2))
                 {
-                    if round < s.witness.len() {
-                        let layer_polys = &s.witness[round];
+                    if let Some(layer_polys) = layer_polys {
                         // sanity check
                         assert_eq!(layer_polys.len(), 4); // p1, q1, p2, q2
                         assert!(
@@ -1244,7 +1648,8 @@ impl TowerProver {
                 // NOTE: at the time of adding this span, visualizing it with the flamegraph layer
                 // shows it to be (inexplicably) much more time-consuming than the call to `prove_batch_polys`
                 // This is likely a bug in the tracing-flame crate.
-                let (sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys(
+                let (sumcheck_proofs, state) = IOPProverStateV2::prove_batch_polys_on(
+                    device,
                     num_threads,
                     virtual_polys.get_batched_polys(),
                     transcript,
@@ -1268,7 +1673,7 @@ impl TowerProver {
                 let mut evals_iter = evals.iter();
                 evals_iter.next(); // skip first eq
                 for (i, s) in enumerate(&prod_specs) {
-                    if round < s.witness.len() {
+                    if round < s.num_layers() {
                         // collect evals belong to current spec
                         proofs.push_prod_evals_and_point(
                             i,
@@ -1280,7 +1685,7 @@ impl TowerProver {
                     }
                 }
                 for (i, s) in enumerate(&logup_specs) {
-                    if round < s.witness.len() {
+                    if round < s.num_layers() {
                         // collect evals belong to current spec
                         // p1, q2, p2, q1
                         let p1 = *evals_iter.next().expect("insufficient evals length");
@@ -0,0 +1,94 @@
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use serde::Serialize;
+
+use crate::error::ZKVMError;
+
+/// Sizes and timings of one circuit, see `ZKVMProver::create_proof_with_report`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CircuitReport {
+    pub num_instances: usize,
+    /// size of the witness polynomials, padded to a power of two, in bytes
+    pub witness_bytes: usize,
+    /// time to commit to the witness
+    pub commit_time: Duration,
+    /// time from the witness to the opening point: tower witness inference, tower sumchecks,
+    /// and the main constraint (or same point) sumcheck
+    pub sumcheck_time: Duration,
+    /// serialized size of the tower proof
+    pub tower_proof_bytes: usize,
+    /// serialized size of the whole circuit proof
+    pub proof_bytes: usize,
+}
+
+/// Sizes and timings of a proof, per circuit, for benchmarking and regression tracking.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProveReport {
+    /// by circuit name, for the circuits with instances
+    pub circuits: BTreeMap<String, CircuitReport>,
+    /// time to open the witnesses, and the fixed traces of the tables, of all the circuits
+    pub opening_time: Duration,
+    /// serialized size of the opening proof of all the circuits
+    pub opening_proof_bytes: usize,
+    /// serialized size of the whole proof, as written by `serialization::to_bytes`
+    pub proof_bytes: usize,
+}
+
+impl ProveReport {
+    pub fn total_commit_time(&self) -> Duration {
+        self.circuits.values().map(|c| c.commit_time).sum()
+    }
+
+    pub fn total_sumcheck_time(&self) -> Duration {
+        self.circuits.values().map(|c| c.sumcheck_time).sum()
+    }
+}
+
+impl fmt::Display for ProveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<32} {:>10} {:>12} {:>10} {:>10} {:>10} {:>12}",
+            "circuit", "instances", "witness (B)", "commit", "sumcheck", "opening", "proof (B)"
+        )?;
+        for (name, c) in &self.circuits {
+            writeln!(
+                f,
+                "{:<32} {:>10} {:>12} {:>10.2?} {:>10.2?} {:>10} {:>12}",
+                name,
+                c.num_instances,
+                c.witness_bytes,
+                c.commit_time,
+                c.sumcheck_time,
+                "",
+                c.proof_bytes
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<32} {:>10} {:>12} {:>10} {:>10} {:>10.2?} {:>12}",
+            "opening", "", "", "", "", self.opening_time, self.opening_proof_bytes
+        )?;
+        write!(
+            f,
+            "{:<32} {:>10} {:>12} {:>10.2?} {:>10.2?} {:>10.2?} {:>12}",
+            "total",
+            "",
+            self.circuits
+                .values()
+                .map(|c| c.witness_bytes)
+                .sum::<usize>(),
+            self.total_commit_time(),
+            self.total_sumcheck_time(),
+            self.opening_time,
+            self.proof_bytes
+        )
+    }
+}
+
+/// The size of `value` once serialized, in the encoding of `serialization::to_bytes`.
+pub(crate) fn serialized_size<T: Serialize>(value: &T) -> Result<usize, ZKVMError> {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .map_err(|e| ZKVMError::SerializationError(e.to_string()))
+}
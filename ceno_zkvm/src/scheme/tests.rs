@@ -1,10 +1,14 @@
-use std::{marker::PhantomData, mem::MaybeUninit};
+use std::{
+    marker::PhantomData,
+    mem::MaybeUninit,
+    panic::{self, AssertUnwindSafe},
+};
 
 use ark_std::test_rng;
 use ceno_emul::{
-    CENO_PLATFORM,
-    InsnKind::{ADD, EANY},
-    PC_WORD_SIZE, Platform, Program, StepRecord, VMState,
+    ByteAddr, CENO_PLATFORM, Change,
+    InsnKind::{self, ADD, EANY, SUB},
+    PC_WORD_SIZE, Platform, Program, StepRecord, VMState, encode_rv32,
 };
 use ff::Field;
 use ff_ext::ExtensionField;
@@ -14,31 +18,35 @@ use mpcs::{Basefold, BasefoldDefault, BasefoldRSParams, PolynomialCommitmentSche
 use multilinear_extensions::{
     mle::IntoMLE, util::ceil_log2, virtual_poly_v2::ArcMultilinearExtension,
 };
-use transcript::{BasicTranscript, Transcript};
+use transcript::{BasicTranscript, ForkableTranscript, TranscriptHash, TranscriptLog};
 
 use crate::{
     circuit_builder::CircuitBuilder,
     declare_program,
-    error::ZKVMError,
-    expression::{ToExpr, WitIn},
+    error::{VerifyError, ZKVMError},
+    expression::{Expression, ToExpr, WitIn},
     instructions::{
         Instruction,
-        riscv::{arith::AddInstruction, ecall::HaltInstruction},
+        riscv::{
+            arith::{AddInstruction, SubInstruction},
+            ecall::HaltInstruction,
+        },
     },
+    serialization::{from_bytes, to_bytes},
     set_val,
     structs::{
-        PointAndEval, RAMType::Register, TowerProver, TowerProverSpec, ZKVMConstraintSystem,
-        ZKVMFixedTraces, ZKVMWitnesses,
+        OpcodeAssignment, PointAndEval, ProtocolVersion, RAMType::Register, TowerProver,
+        TowerProverSpec, ZKVMConstraintSystem, ZKVMFixedTraces, ZKVMVerifyingKey, ZKVMWitnesses,
     },
-    tables::{ProgramTableCircuit, U16TableCircuit},
-    witness::LkMultiplicity,
+    tables::{FixedTraceCache, ProgramTableCircuit, TableCircuit, U16TableCircuit},
+    witness::AssignmentCtx,
 };
 
 use super::{
-    PublicValues,
-    constants::{MAX_NUM_VARIABLES, NUM_FANIN},
+    PublicValues, ZKVMOpcodeProof, ZKVMProof,
+    constants::NUM_FANIN,
     prover::ZKVMProver,
-    utils::infer_tower_product_witness,
+    utils::{draw_challenges, infer_tower_product_witness},
     verifier::{TowerVerify, ZKVMVerifier},
 };
 
@@ -77,114 +85,195 @@ impl<E: ExtensionField, const L: usize, const RW: usize> Instruction<E> for Test
 
     fn assign_instance(
         config: &Self::InstructionConfig,
-        instance: &mut [MaybeUninit<E::BaseField>],
-        _lk_multiplicity: &mut LkMultiplicity,
+        ctx: &mut AssignmentCtx<E>,
         _step: &StepRecord,
     ) -> Result<(), ZKVMError> {
-        set_val!(instance, config.reg_id, E::BaseField::ONE);
+        set_val!(ctx.instance, config.reg_id, E::BaseField::ONE);
 
         Ok(())
     }
 }
 
-#[test]
-fn test_rw_lk_expression_combination() {
-    fn test_rw_lk_expression_combination_inner<const L: usize, const RW: usize>() {
-        type E = GoldilocksExt2;
-        type Pcs = BasefoldDefault<E>;
+/// A circuit whose constraints have constant terms, which its zero padding rows do not satisfy.
+struct TestPaddingCircuit<E: ExtensionField> {
+    phantom: PhantomData<E>,
+}
 
-        // pcs setup
-        let param = Pcs::setup(1 << 13).unwrap();
-        let (pp, vp) = Pcs::trim(param, 1 << 13).unwrap();
+impl<E: ExtensionField> Instruction<E> for TestPaddingCircuit<E> {
+    type InstructionConfig = TestConfig;
 
-        // configure
-        let name = TestCircuit::<E, RW, L>::name();
-        let mut zkvm_cs = ZKVMConstraintSystem::default();
-        let config = zkvm_cs.register_opcode_circuit::<TestCircuit<E, RW, L>>();
+    fn name() -> String {
+        "TEST_PADDING".into()
+    }
 
-        // generate fixed traces
-        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
-        zkvm_fixed_traces.register_opcode_circuit::<TestCircuit<E, RW, L>>(&zkvm_cs);
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<Self::InstructionConfig, ZKVMError> {
+        let reg_id = cb.create_witin(|| "reg_id");
+        let record = vec![1.into(), reg_id.expr()];
+        cb.read_record(|| "read", Register, record.clone())?;
+        cb.write_record(|| "write", Register, record)?;
+        cb.assert_ux::<_, _, 16>(|| "regid_in_range", reg_id.expr())?;
+        cb.require_equal(|| "reg_id = 1", reg_id.expr(), Expression::ONE)?;
+        cb.require_zero(
+            || "reg_id * reg_id = 1",
+            reg_id.expr() * reg_id.expr() - Expression::ONE,
+        )?;
+        assert_eq!(cb.cs.assert_zero_expressions.len(), 1);
+        assert_eq!(cb.cs.assert_zero_sumcheck_expressions.len(), 1);
 
-        // keygen
-        let pk = zkvm_cs
-            .clone()
-            .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
-            .unwrap();
-        let vk = pk.get_vk();
+        Ok(TestConfig { reg_id })
+    }
 
-        // generate mock witness
-        let num_instances = 1 << 8;
-        let mut zkvm_witness = ZKVMWitnesses::default();
-        zkvm_witness
-            .assign_opcode_circuit::<TestCircuit<E, RW, L>>(
-                &zkvm_cs,
-                &config,
-                vec![StepRecord::default(); num_instances],
-            )
-            .unwrap();
+    fn assign_instance(
+        config: &Self::InstructionConfig,
+        ctx: &mut AssignmentCtx<E>,
+        _step: &StepRecord,
+    ) -> Result<(), ZKVMError> {
+        set_val!(ctx.instance, config.reg_id, E::BaseField::ONE);
 
-        // get proof
-        let prover = ZKVMProver::new(pk);
-        let mut transcript = BasicTranscript::new(b"test");
-        let wits_in = zkvm_witness
-            .into_iter_sorted()
-            .next()
-            .unwrap()
-            .1
-            .into_mles();
-        // commit to main traces
-        let commit = Pcs::batch_commit_and_write(&prover.pk.pp, &wits_in, &mut transcript).unwrap();
-        let wits_in = wits_in.into_iter().map(|v| v.into()).collect_vec();
-        let prover_challenges = [
-            transcript.read_challenge().elements,
-            transcript.read_challenge().elements,
-        ];
+        Ok(())
+    }
+}
 
-        let proof = prover
-            .create_opcode_proof(
-                name.as_str(),
-                &prover.pk.pp,
-                prover.pk.circuit_pks.get(&name).unwrap(),
-                wits_in,
-                commit,
-                &[],
-                num_instances,
-                &mut transcript,
-                &prover_challenges,
-            )
-            .expect("create_proof failed");
-
-        // verify proof
-        let verifier = ZKVMVerifier::new(vk.clone());
-        let mut v_transcript = BasicTranscript::new(b"test");
-        // write commitment into transcript and derive challenges from it
-        Pcs::write_commitment(&proof.wits_commit, &mut v_transcript).unwrap();
-        let verifier_challenges = [
-            v_transcript.read_challenge().elements,
-            v_transcript.read_challenge().elements,
-        ];
+/// Prove `num_instances` instances of the opcode circuit `OC` from default records, and verify
+/// the proof.
+fn prove_and_verify_opcode_circuit<OC: Instruction<GoldilocksExt2>>(
+    num_instances: usize,
+    transcript_hash: TranscriptHash,
+) {
+    type E = GoldilocksExt2;
+    type Pcs = BasefoldDefault<E>;
 
-        assert_eq!(prover_challenges, verifier_challenges);
-        let _rt_input = verifier
-            .verify_opcode_proof(
-                name.as_str(),
-                &vk.vp,
-                verifier.vk.circuit_vks.get(&name).unwrap(),
-                &proof,
-                &[],
-                &mut v_transcript,
-                NUM_FANIN,
-                &PointAndEval::default(),
-                &verifier_challenges,
-            )
-            .expect("verifier failed");
+    // pcs setup
+    let param = Pcs::setup(1 << 13).unwrap();
+    let (pp, vp) = Pcs::trim(param, 1 << 13).unwrap();
+
+    // configure
+    let name = OC::name();
+    let mut zkvm_cs = ZKVMConstraintSystem::default();
+    let config = zkvm_cs.register_opcode_circuit::<OC>().unwrap();
+
+    // generate fixed traces
+    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+    zkvm_fixed_traces
+        .register_opcode_circuit::<OC>(&zkvm_cs)
+        .unwrap();
+
+    // keygen
+    let mut pk = zkvm_cs
+        .clone()
+        .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
+        .unwrap();
+    pk.transcript_hash = transcript_hash;
+    let vk = pk.get_vk();
+
+    // generate mock witness
+    let mut zkvm_witness = ZKVMWitnesses::default();
+    zkvm_witness
+        .assign_opcode_circuit::<OC>(&zkvm_cs, &config, vec![
+            StepRecord::default();
+            num_instances
+        ])
+        .unwrap();
+
+    // get proof
+    let prover = ZKVMProver::new(pk);
+    let mut transcript = prover.pk.new_transcript(b"test");
+    let wits_in = zkvm_witness
+        .into_iter_sorted()
+        .next()
+        .unwrap()
+        .1
+        .into_mles();
+    // commit to main traces
+    let commit = Pcs::batch_commit_and_write(&prover.pk.pp, &wits_in, &mut transcript).unwrap();
+    let wits_in = wits_in.into_iter().map(|v| v.into()).collect_vec();
+    let prover_challenges = draw_challenges(&mut transcript);
+
+    let (proof, point) = prover
+        .create_opcode_proof(
+            name.as_str(),
+            prover.pk.circuit_pks.get(&name).unwrap(),
+            wits_in.clone(),
+            &commit,
+            &[],
+            num_instances,
+            &mut transcript,
+            &prover_challenges,
+        )
+        .expect("create_proof failed");
+    // open the witness, which the proof leaves to the caller
+    let opening_proof = Pcs::simple_batch_open(
+        &prover.pk.pp,
+        &wits_in,
+        &commit,
+        &point,
+        &proof.wits_in_evals,
+        &mut transcript,
+    )
+    .unwrap();
+
+    // the verifier works on a proof and key shipped in serialized form
+    let proof: ZKVMOpcodeProof<E, Pcs> = from_bytes(&to_bytes(&proof).unwrap()).unwrap();
+    let vk: ZKVMVerifyingKey<E, Pcs> = from_bytes(&to_bytes(&vk).unwrap()).unwrap();
+
+    // verify proof
+    let verifier = ZKVMVerifier::new(vk.clone());
+    let mut v_transcript = verifier.new_transcript(b"test");
+    // write commitment into transcript and derive challenges from it
+    Pcs::write_commitment(&proof.wits_commit, &mut v_transcript).unwrap();
+    let verifier_challenges = draw_challenges(&mut v_transcript);
+
+    assert_eq!(prover_challenges, verifier_challenges);
+    let rt_input = verifier
+        .verify_opcode_proof(
+            name.as_str(),
+            verifier.vk.circuit_vks.get(&name).unwrap(),
+            &proof,
+            &[],
+            &mut v_transcript,
+            NUM_FANIN,
+            &PointAndEval::default(),
+            &verifier_challenges,
+        )
+        .expect("verifier failed");
+    Pcs::simple_batch_verify(
+        &vk.vp,
+        &proof.wits_commit,
+        &rt_input,
+        &proof.wits_in_evals,
+        &opening_proof,
+        &mut v_transcript,
+    )
+    .expect("opening failed");
+}
+
+#[test]
+fn test_rw_lk_expression_combination() {
+    fn test_rw_lk_expression_combination_inner<const L: usize, const RW: usize>(
+        transcript_hash: TranscriptHash,
+    ) {
+        prove_and_verify_opcode_circuit::<TestCircuit<GoldilocksExt2, RW, L>>(
+            1 << 8,
+            transcript_hash,
+        );
     }
 
     // <lookup count, rw count>
-    test_rw_lk_expression_combination_inner::<19, 17>();
-    test_rw_lk_expression_combination_inner::<61, 17>();
-    test_rw_lk_expression_combination_inner::<17, 61>();
+    test_rw_lk_expression_combination_inner::<19, 17>(TranscriptHash::Poseidon);
+    test_rw_lk_expression_combination_inner::<61, 17>(TranscriptHash::Poseidon);
+    test_rw_lk_expression_combination_inner::<17, 61>(TranscriptHash::Poseidon);
+    // the other transcript hashes
+    test_rw_lk_expression_combination_inner::<19, 17>(TranscriptHash::Blake3);
+    test_rw_lk_expression_combination_inner::<19, 17>(TranscriptHash::Keccak);
+}
+
+#[test]
+fn test_zero_padding() {
+    // 5 instances and 3 rows of zero padding
+    prove_and_verify_opcode_circuit::<TestPaddingCircuit<GoldilocksExt2>>(
+        5,
+        TranscriptHash::Poseidon,
+    );
 }
 
 const PROGRAM_SIZE: usize = 4;
@@ -205,117 +294,596 @@ const PROGRAM_CODE: [u32; PROGRAM_SIZE] = {
     program
 };
 
-#[ignore = "this case is already tested in riscv_example as ecall_halt has only one instance"]
+type E2EPcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+
+/// `PROGRAM_CODE` with the circuits it runs through, keyed and assigned, to be proven on a
+/// transcript of label `riscv`. SUB is registered but never executed.
+struct AddProgram {
+    prover: ZKVMProver<GoldilocksExt2, E2EPcs>,
+    verifier: ZKVMVerifier<GoldilocksExt2, E2EPcs>,
+    witness: ZKVMWitnesses<GoldilocksExt2>,
+    pi: PublicValues<u32>,
+}
+
+impl AddProgram {
+    fn new() -> Self {
+        type E = GoldilocksExt2;
+        type Pcs = E2EPcs;
+
+        // set up program
+        let program = Program::new(
+            CENO_PLATFORM.pc_base(),
+            CENO_PLATFORM.pc_base(),
+            PROGRAM_CODE.to_vec(),
+            PROGRAM_CODE
+                .iter()
+                .enumerate()
+                .map(|(insn_idx, &insn)| {
+                    (
+                        (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
+                        insn,
+                    )
+                })
+                .collect(),
+        );
+
+        // the largest circuit is the U16 range table
+        let pcs_param = Pcs::setup(1 << 16).expect("Basefold PCS setup");
+        let (pp, vp) = Pcs::trim(pcs_param, 1 << 16).expect("Basefold trim");
+
+        let mut zkvm_cs = ZKVMConstraintSystem::default();
+        // opcode circuits
+        let add_config = zkvm_cs
+            .register_opcode_circuit::<AddInstruction<E>>()
+            .unwrap();
+        let halt_config = zkvm_cs
+            .register_opcode_circuit::<HaltInstruction<E>>()
+            .unwrap();
+        // never executed
+        let sub_config = zkvm_cs
+            .register_opcode_circuit::<SubInstruction<E>>()
+            .unwrap();
+        let u16_range_config = zkvm_cs
+            .register_table_circuit::<U16TableCircuit<E>>()
+            .unwrap();
+
+        let prog_config = zkvm_cs
+            .register_table_circuit::<ProgramTableCircuit<E>>()
+            .unwrap();
+
+        let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+        zkvm_fixed_traces
+            .register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs)
+            .unwrap();
+        zkvm_fixed_traces
+            .register_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs)
+            .unwrap();
+        zkvm_fixed_traces
+            .register_opcode_circuit::<SubInstruction<E>>(&zkvm_cs)
+            .unwrap();
+
+        zkvm_fixed_traces
+            .register_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &u16_range_config, &())
+            .unwrap();
+
+        zkvm_fixed_traces
+            .register_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, &program)
+            .unwrap();
+
+        let pk = zkvm_cs
+            .clone()
+            .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
+            .expect("keygen failed");
+        let vk = pk.get_vk();
+
+        // single instance
+        let mut vm = VMState::new(CENO_PLATFORM, program.clone());
+        let all_records = vm
+            .iter_until_halt()
+            .collect::<Result<Vec<StepRecord>, _>>()
+            .expect("vm exec failed")
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut add_records = vec![];
+        let mut halt_records = vec![];
+        all_records.into_iter().for_each(|record| {
+            let kind = record.insn().codes().kind;
+            match kind {
+                ADD => add_records.push(record),
+                EANY => {
+                    if record.rs1().unwrap().value == Platform::ecall_halt() {
+                        halt_records.push(record);
+                    }
+                }
+                _ => {}
+            }
+        });
+        assert_eq!(add_records.len(), 1);
+        assert_eq!(halt_records.len(), 1);
+
+        let mut zkvm_witness = ZKVMWitnesses::default();
+        // assign opcode circuits
+        zkvm_witness
+            .assign_opcode_circuit::<AddInstruction<E>>(&zkvm_cs, &add_config, add_records)
+            .unwrap();
+        zkvm_witness
+            .assign_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs, &halt_config, halt_records)
+            .unwrap();
+        zkvm_witness
+            .assign_opcode_circuit::<SubInstruction<E>>(&zkvm_cs, &sub_config, vec![])
+            .unwrap();
+        zkvm_witness.finalize_lk_multiplicities().unwrap();
+        zkvm_witness
+            .assign_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &u16_range_config, &())
+            .unwrap();
+        zkvm_witness
+            .assign_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, &program)
+            .unwrap();
+
+        Self {
+            prover: ZKVMProver::new(pk),
+            verifier: ZKVMVerifier::new(vk),
+            witness: zkvm_witness,
+            pi: PublicValues::new(0, 0, 0, 0, 0, vec![0]),
+        }
+    }
+
+    fn prove_on(
+        &self,
+        transcript: impl ForkableTranscript<GoldilocksExt2> + Send,
+    ) -> ZKVMProof<GoldilocksExt2, E2EPcs> {
+        self.prover
+            .create_proof(self.witness.clone(), self.pi.clone(), transcript)
+            .expect("create_proof failed")
+    }
+
+    fn prove(&self) -> ZKVMProof<GoldilocksExt2, E2EPcs> {
+        self.prove_on(BasicTranscript::new(b"riscv"))
+    }
+
+    fn verify(&self, proof: ZKVMProof<GoldilocksExt2, E2EPcs>) -> Result<bool, ZKVMError> {
+        self.verifier
+            .verify_proof(proof, BasicTranscript::new(b"riscv"))
+    }
+}
+
+/// `proof` with its ADD proof changed by `tamper`.
+fn tamper_add_proof(
+    mut proof: ZKVMProof<GoldilocksExt2, E2EPcs>,
+    tamper: impl FnOnce(&mut ZKVMOpcodeProof<GoldilocksExt2, E2EPcs>),
+) -> ZKVMProof<GoldilocksExt2, E2EPcs> {
+    let (_, add_proof) = proof
+        .opcode_proofs
+        .get_mut(&AddInstruction::<GoldilocksExt2>::name())
+        .unwrap();
+    tamper(add_proof);
+    proof
+}
+
 #[test]
 fn test_single_add_instance_e2e() {
+    let e2e = AddProgram::new();
+    let zkvm_proof = e2e.prove();
+    assert!(
+        e2e.verify(zkvm_proof)
+            .expect("verify proof return with error"),
+    );
+}
+
+#[test]
+fn test_circuit_without_instances_left_out() {
     type E = GoldilocksExt2;
-    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
-
-    // set up program
-    let program = Program::new(
-        CENO_PLATFORM.pc_base(),
-        CENO_PLATFORM.pc_base(),
-        PROGRAM_CODE.to_vec(),
-        PROGRAM_CODE
-            .iter()
-            .enumerate()
-            .map(|(insn_idx, &insn)| {
-                (
-                    (insn_idx * PC_WORD_SIZE) as u32 + CENO_PLATFORM.pc_base(),
-                    insn,
-                )
-            })
-            .collect(),
+
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // SUB is registered but never executed
+    assert!(
+        !proof
+            .opcode_proofs
+            .contains_key(&SubInstruction::<E>::name())
     );
+    assert!(e2e.verify(proof).unwrap());
+}
 
-    let pcs_param = Pcs::setup(1 << MAX_NUM_VARIABLES).expect("Basefold PCS setup");
-    let (pp, vp) = Pcs::trim(pcs_param, 1 << MAX_NUM_VARIABLES).expect("Basefold trim");
-    let mut zkvm_cs = ZKVMConstraintSystem::default();
-    // opcode circuits
-    let add_config = zkvm_cs.register_opcode_circuit::<AddInstruction<E>>();
-    let halt_config = zkvm_cs.register_opcode_circuit::<HaltInstruction<E>>();
-    let u16_range_config = zkvm_cs.register_table_circuit::<U16TableCircuit<E>>();
+#[test]
+fn test_has_output_without_digest() {
+    let proof = AddProgram::new().prove();
+    // the program attests no digest, so that the public one is not constrained
+    assert!(!proof.has_output(&[]));
+    assert!(!proof.has_output(b"ceno"));
+}
 
-    let prog_config = zkvm_cs.register_table_circuit::<ProgramTableCircuit<E>>();
+#[test]
+fn test_prove_deterministic() {
+    let e2e = AddProgram::new();
+    let proof = to_bytes(&e2e.prove()).unwrap();
+    // the same inputs give a byte-identical proof
+    assert_eq!(to_bytes(&e2e.prove()).unwrap(), proof);
+    // whatever the thread count
+    for num_threads in [1, 4] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        assert_eq!(to_bytes(&pool.install(|| e2e.prove())).unwrap(), proof);
+    }
+}
 
-    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
-    zkvm_fixed_traces.register_opcode_circuit::<AddInstruction<E>>(&zkvm_cs);
-    zkvm_fixed_traces.register_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs);
+#[test]
+fn test_prove_with_report() {
+    type E = GoldilocksExt2;
 
-    zkvm_fixed_traces.register_table_circuit::<U16TableCircuit<E>>(
-        &zkvm_cs,
-        &u16_range_config,
-        &(),
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // the report covers every circuit with instances, and leaves the proof unchanged
+    let (reported_proof, report) = e2e
+        .prover
+        .create_proof_with_report(
+            e2e.witness.clone(),
+            e2e.pi.clone(),
+            BasicTranscript::new(b"riscv"),
+        )
+        .expect("create_proof failed");
+    let proof_bytes = to_bytes(&reported_proof).unwrap();
+    assert_eq!(proof_bytes, to_bytes(&proof).unwrap());
+    assert_eq!(report.proof_bytes, proof_bytes.len());
+    assert_eq!(report.circuits.len(), proof.num_circuits());
+    let add_report = &report.circuits[&AddInstruction::<E>::name()];
+    assert_eq!(add_report.num_instances, 1);
+    assert!(add_report.witness_bytes > 0);
+    assert!(add_report.tower_proof_bytes < add_report.proof_bytes);
+    assert!(report.opening_proof_bytes > 0);
+    assert!(report.opening_proof_bytes < report.proof_bytes);
+}
+
+#[test]
+fn test_verify_malformed_proof() {
+    type E = GoldilocksExt2;
+
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // a proof missing a tower layer or without instances is malformed, a proof with a wrong
+    // evaluation is invalid
+    let malformed_proof = tamper_add_proof(proof.clone(), |add_proof| {
+        add_proof.tower_proof.proofs.pop();
+    });
+    let empty_proof = tamper_add_proof(proof.clone(), |add_proof| add_proof.num_instances = 0);
+    let invalid_proof = tamper_add_proof(proof, |add_proof| {
+        add_proof.wits_in_evals[0] += E::ONE;
+    });
+    for (proof, malformed) in [
+        (malformed_proof, true),
+        (empty_proof, true),
+        (invalid_proof, false),
+    ] {
+        match e2e.verify(proof) {
+            Err(ZKVMError::VerifyError(VerifyError::Circuit(name, error))) => {
+                assert_eq!(name, AddInstruction::<E>::name());
+                assert_eq!(error.is_malformed(), malformed, "{error:?}");
+            }
+            _ => panic!("expected a verification error"),
+        }
+    }
+}
+
+#[test]
+fn test_transcript_divergence() {
+    let e2e = AddProgram::new();
+    let prover_log = TranscriptLog::new();
+    let proof = e2e.prove_on(prover_log.record(BasicTranscript::new(b"riscv")));
+
+    // the recorded transcripts of a proof with swapped sumcheck messages diverge in the fork of
+    // the tampered circuit; the evaluations are only absorbed by the opening fork
+    let add_index = proof.opcode_proofs[&AddInstruction::<GoldilocksExt2>::name()].0;
+    let tampered_proof = tamper_add_proof(proof, |add_proof| {
+        add_proof.tower_proof.proofs.last_mut().unwrap().swap(0, 1);
+    });
+    let verifier_log = TranscriptLog::new();
+    let transcript = verifier_log.record(BasicTranscript::new(b"riscv"));
+    // the sumcheck verifier panics on an inconsistent message
+    let verified = panic::catch_unwind(AssertUnwindSafe(|| {
+        e2e.verifier.verify_proof(tampered_proof, transcript)
+    }));
+    assert!(!matches!(verified, Ok(Ok(true))));
+    let divergence = prover_log.first_divergence(&verifier_log).unwrap();
+    assert_eq!(divergence.fork, vec![add_index]);
+}
+
+#[test]
+fn test_verify_with_expected_digest() {
+    type E = GoldilocksExt2;
+
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // a verifier pinning the digest of the key accepts it, and rejects a modified key
+    let vk = e2e.verifier.vk;
+    let digest = vk.digest().unwrap();
+    assert!(
+        ZKVMVerifier::verify_with_expected_digest(
+            &to_bytes(&vk).unwrap(),
+            &digest,
+            proof.clone(),
+            b"riscv"
+        )
+        .expect("verify proof return with error"),
     );
+    let mut modified_vk = vk;
+    modified_vk
+        .circuit_vks
+        .remove(&U16TableCircuit::<E>::name());
+    assert!(matches!(
+        ZKVMVerifier::verify_with_expected_digest(
+            &to_bytes(&modified_vk).unwrap(),
+            &digest,
+            proof,
+            b"riscv"
+        ),
+        Err(ZKVMError::VKDigestMismatch)
+    ));
+}
 
-    zkvm_fixed_traces.register_table_circuit::<ProgramTableCircuit<E>>(
-        &zkvm_cs,
-        &prog_config,
-        &program,
+#[test]
+fn test_inspect_and_verify_circuit() {
+    type E = GoldilocksExt2;
+
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    assert_eq!(proof.circuit_names().collect_vec(), vec![
+        "ADD",
+        "ECALL_HALT",
+        "PROGRAM",
+        "RANGE_U16"
+    ]);
+    assert_eq!(proof.num_instances(&AddInstruction::<E>::name()), 1);
+    assert_eq!(proof.num_instances(&SubInstruction::<E>::name()), 0);
+    assert!(
+        proof
+            .table_proof(&ProgramTableCircuit::<E>::name())
+            .is_some()
     );
 
-    let pk = zkvm_cs
-        .clone()
-        .key_gen::<Pcs>(pp, vp, zkvm_fixed_traces)
-        .expect("keygen failed");
-    let vk = pk.get_vk();
+    // a wrong evaluation of ADD only fails the verification of ADD
+    let invalid_proof = tamper_add_proof(proof.clone(), |add_proof| {
+        add_proof.wits_in_evals[0] += E::ONE;
+    });
+    let verify_circuit = |proof, name: String| {
+        e2e.verifier
+            .verify_circuit(proof, &name, BasicTranscript::new(b"riscv"))
+    };
+    assert!(verify_circuit(&proof, AddInstruction::<E>::name()).is_ok());
+    assert!(verify_circuit(&invalid_proof, AddInstruction::<E>::name()).is_err());
+    assert!(verify_circuit(&invalid_proof, HaltInstruction::<E>::name()).is_ok());
+    assert!(verify_circuit(&invalid_proof, U16TableCircuit::<E>::name()).is_ok());
+}
 
-    // single instance
-    let mut vm = VMState::new(CENO_PLATFORM, program.clone());
-    let all_records = vm
-        .iter_until_halt()
-        .collect::<Result<Vec<StepRecord>, _>>()
-        .expect("vm exec failed")
-        .into_iter()
-        .collect::<Vec<_>>();
-    let mut add_records = vec![];
-    let mut halt_records = vec![];
-    all_records.into_iter().for_each(|record| {
-        let kind = record.insn().codes().kind;
-        match kind {
-            ADD => add_records.push(record),
-            EANY => {
-                if record.rs1().unwrap().value == Platform::ecall_halt() {
-                    halt_records.push(record);
-                }
-            }
-            _ => {}
+#[test]
+fn test_verify_protocol_version() {
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // a proof of another crate version verifies, one of another circuit version is rejected
+    let mut other_release = proof.clone();
+    other_release.version.crate_version = "0.0.0".to_string();
+    assert!(matches!(e2e.verify(other_release), Ok(true)));
+    let mut other_circuits = proof;
+    other_circuits.version.circuit_version += 1;
+    match e2e.verify(other_circuits) {
+        Err(ZKVMError::VersionMismatch { proof, vk }) => {
+            assert_eq!(proof.circuit_version, vk.circuit_version + 1);
+            assert_eq!(vk, ProtocolVersion::current());
         }
+        _ => panic!("expected a version mismatch"),
+    }
+}
+
+#[test]
+fn test_batch_verify() {
+    type E = GoldilocksExt2;
+
+    let e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // a wrong evaluation fails the circuit, an opening of another proof fails the opening only
+    let invalid_proof = tamper_add_proof(proof.clone(), |add_proof| {
+        add_proof.wits_in_evals[0] += E::ONE;
     });
-    assert_eq!(add_records.len(), 1);
-    assert_eq!(halt_records.len(), 1);
+    let mut unopened_proof = proof.clone();
+    unopened_proof.opening_proof = e2e
+        .prover
+        .create_proof(
+            e2e.witness.clone(),
+            e2e.pi.clone(),
+            BasicTranscript::new(b"other"),
+        )
+        .unwrap()
+        .opening_proof;
 
-    // proving
-    let prover = ZKVMProver::new(pk);
-    let verifier = ZKVMVerifier::new(vk);
-    let mut zkvm_witness = ZKVMWitnesses::default();
-    // assign opcode circuits
-    zkvm_witness
-        .assign_opcode_circuit::<AddInstruction<E>>(&zkvm_cs, &add_config, add_records)
+    let results = ZKVMVerifier::batch_verify(
+        &[proof.clone(), invalid_proof, unopened_proof, proof],
+        &e2e.verifier.vk,
+        b"riscv",
+    );
+    assert_eq!(results.len(), 4);
+    assert!(matches!(results[0], Ok(true)));
+    assert!(matches!(
+        results[1],
+        Err(ZKVMError::VerifyError(VerifyError::Circuit(..)))
+    ));
+    assert!(matches!(
+        results[2],
+        Err(ZKVMError::VerifyError(VerifyError::PCSOpening(_)))
+    ));
+    assert!(matches!(results[3], Ok(true)));
+}
+
+#[test]
+fn test_prove_parallel_circuits() {
+    let mut e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // every circuit has its own fork of the transcript, so the proof is the same
+    e2e.prover.enable_parallel_circuits();
+    assert_eq!(to_bytes(&e2e.prove()).unwrap(), to_bytes(&proof).unwrap());
+    assert!(e2e.verify(proof).unwrap());
+}
+
+#[test]
+fn test_prove_under_memory_budget() {
+    let mut e2e = AddProgram::new();
+    let proof = e2e.prove();
+    // the witness is recovered from the commitments, and the tower layers are recomputed
+    e2e.prover.set_memory_budget(1 << 10);
+    assert_eq!(to_bytes(&e2e.prove()).unwrap(), to_bytes(&proof).unwrap());
+    // the budget fits no two circuits, so that each parallel batch holds a single circuit
+    e2e.prover.enable_parallel_circuits();
+    assert_eq!(to_bytes(&e2e.prove()).unwrap(), to_bytes(&proof).unwrap());
+    assert!(e2e.verify(proof).unwrap());
+}
+
+#[test]
+fn test_assign_opcode_circuits() {
+    type E = GoldilocksExt2;
+    let mut zkvm_cs = ZKVMConstraintSystem::default();
+    let add_config = zkvm_cs
+        .register_opcode_circuit::<AddInstruction<E>>()
+        .unwrap();
+    let sub_config = zkvm_cs
+        .register_opcode_circuit::<SubInstruction<E>>()
         .unwrap();
+    let records = |kind: InsnKind| {
+        (0..300u32)
+            .map(|i| {
+                StepRecord::new_r_instruction(
+                    4 * (i as u64 + 1),
+                    ByteAddr(CENO_PLATFORM.pc_base()),
+                    encode_rv32(kind, 2, 3, 4, 0),
+                    i,
+                    7 * i,
+                    Change::new(
+                        0,
+                        if kind == ADD {
+                            8 * i
+                        } else {
+                            i.wrapping_sub(7 * i)
+                        },
+                    ),
+                    0,
+                )
+            })
+            .collect_vec()
+    };
+
+    let mut sequential = ZKVMWitnesses::default();
+    sequential
+        .assign_opcode_circuit::<AddInstruction<E>>(&zkvm_cs, &add_config, records(ADD))
+        .unwrap();
+    sequential
+        .assign_opcode_circuit::<SubInstruction<E>>(&zkvm_cs, &sub_config, records(SUB))
+        .unwrap();
+
+    let mut parallel = ZKVMWitnesses::default();
+    let assignments: Vec<OpcodeAssignment<E>> = vec![
+        Box::new(|pool| {
+            ZKVMWitnesses::assign_opcode_instances::<AddInstruction<E>>(
+                &zkvm_cs,
+                &add_config,
+                records(ADD),
+                pool,
+            )
+        }),
+        Box::new(|pool| {
+            ZKVMWitnesses::assign_opcode_instances::<SubInstruction<E>>(
+                &zkvm_cs,
+                &sub_config,
+                records(SUB),
+                pool,
+            )
+        }),
+    ];
+    parallel.assign_opcode_circuits(assignments).unwrap();
+
+    // the same witnesses as one circuit after the other
+    for name in [AddInstruction::<E>::name(), SubInstruction::<E>::name()] {
+        assert_eq!(
+            parallel
+                .get_opcode_witness(&name)
+                .unwrap()
+                .de_interleaving(),
+            sequential
+                .get_opcode_witness(&name)
+                .unwrap()
+                .de_interleaving(),
+        );
+    }
+    assert_eq!(parallel.num_instances(), sequential.num_instances());
+}
+
+#[test]
+fn test_register_circuit_twice() {
+    type E = GoldilocksExt2;
+    let mut zkvm_cs = ZKVMConstraintSystem::<E>::default();
+    let add_config = zkvm_cs
+        .register_opcode_circuit::<AddInstruction<E>>()
+        .unwrap();
+    assert!(matches!(
+        zkvm_cs.register_opcode_circuit::<AddInstruction<E>>(),
+        Err(ZKVMError::DuplicateCircuit(name)) if name == "ADD"
+    ));
+    let config = zkvm_cs
+        .register_table_circuit::<U16TableCircuit<E>>()
+        .unwrap();
+    assert!(matches!(
+        zkvm_cs.register_table_circuit::<U16TableCircuit<E>>(),
+        Err(ZKVMError::DuplicateCircuit(_))
+    ));
+
+    let mut zkvm_fixed_traces = ZKVMFixedTraces::default();
+    zkvm_fixed_traces
+        .register_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &config, &())
+        .unwrap();
+    assert!(matches!(
+        zkvm_fixed_traces.register_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &config, &()),
+        Err(ZKVMError::DuplicateCircuit(_))
+    ));
+    // a table which is not in the constraint systems
+    assert!(matches!(
+        ZKVMFixedTraces::generate_table_fixed_traces::<U16TableCircuit<E>>(
+            &ZKVMConstraintSystem::default(),
+            &config,
+            &(),
+            &FixedTraceCache::default(),
+        ),
+        Err(ZKVMError::CircuitNotFound(_))
+    ));
+
+    let mut zkvm_witness = ZKVMWitnesses::default();
+    assert!(matches!(
+        zkvm_witness.assign_opcode_circuit::<AddInstruction<E>>(
+            &ZKVMConstraintSystem::default(),
+            &add_config,
+            vec![],
+        ),
+        Err(ZKVMError::CircuitNotFound(_))
+    ));
+    assert!(matches!(
+        zkvm_witness.assign_instance_columns::<AddInstruction<E>>(&zkvm_cs, vec![]),
+        Err(ZKVMError::WitnessNotFound { .. })
+    ));
     zkvm_witness
-        .assign_opcode_circuit::<HaltInstruction<E>>(&zkvm_cs, &halt_config, halt_records)
+        .assign_opcode_circuit::<AddInstruction<E>>(&zkvm_cs, &add_config, vec![])
         .unwrap();
-    zkvm_witness.finalize_lk_multiplicities();
     zkvm_witness
-        .assign_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &u16_range_config, &())
+        .assign_instance_columns::<AddInstruction<E>>(&zkvm_cs, vec![])
         .unwrap();
+    assert!(matches!(
+        zkvm_witness.assign_instance_columns::<AddInstruction<E>>(&zkvm_cs, vec![]),
+        Err(ZKVMError::DuplicateCircuit(_))
+    ));
+    zkvm_witness.finalize_lk_multiplicities().unwrap();
     zkvm_witness
-        .assign_table_circuit::<ProgramTableCircuit<E>>(&zkvm_cs, &prog_config, &program)
+        .assign_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &config, &())
         .unwrap();
-
-    let pi = PublicValues::new(0, 0, 0, 0, 0, vec![0]);
-    let transcript = BasicTranscript::new(b"riscv");
-    let zkvm_proof = prover
-        .create_proof(zkvm_witness, pi, transcript)
-        .expect("create_proof failed");
-
-    let transcript = BasicTranscript::new(b"riscv");
-    assert!(
-        verifier
-            .verify_proof(zkvm_proof, transcript)
-            .expect("verify proof return with error"),
-    );
+    assert!(matches!(
+        zkvm_witness.assign_table_circuit::<U16TableCircuit<E>>(&zkvm_cs, &config, &()),
+        Err(ZKVMError::DuplicateCircuit(_))
+    ));
 }
 
 /// test various product argument size, starting from minimal leaf size 2
@@ -338,16 +906,31 @@ fn test_tower_proof_various_prod_size() {
             first.to_vec().into_mle().into(),
             second.to_vec().into_mle().into(),
         ];
-        let layers = infer_tower_product_witness(num_vars, last_layer_splitted_fanin, 2);
+        let layers = infer_tower_product_witness(num_vars, last_layer_splitted_fanin.clone(), 2);
         let (rt_tower_p, tower_proof) = TowerProver::create_proof(
-            vec![TowerProverSpec {
-                witness: layers.clone(),
-            }],
+            vec![TowerProverSpec::Layers(layers.clone())],
             vec![],
             2,
             &mut transcript,
         );
 
+        // recomputing the layers gives the same proof
+        let mut transcript = BasicTranscript::new(b"test_tower_proof");
+        let (rt_tower_streamed, tower_proof_streamed) = TowerProver::create_proof(
+            vec![TowerProverSpec::StreamedProduct(
+                num_vars,
+                last_layer_splitted_fanin,
+            )],
+            vec![],
+            2,
+            &mut transcript,
+        );
+        assert_eq!(rt_tower_p, rt_tower_streamed);
+        assert_eq!(
+            serde_json::to_string(&tower_proof).unwrap(),
+            serde_json::to_string(&tower_proof_streamed).unwrap()
+        );
+
         let mut transcript = BasicTranscript::new(b"test_tower_proof");
         let (rt_tower_v, prod_point_and_eval, _, _) = TowerVerify::verify(
             vec![
@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
-use ark_std::iterable::Iterable;
+use ff::Field;
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use multilinear_extensions::{
     commutative_op_mle_pair,
+    device::{Cpu, Device, Evals},
     mle::{DenseMultilinearExtension, FieldType, IntoMLE},
     op_mle_xa_b, op_mle3_range,
     util::ceil_log2,
@@ -18,10 +19,43 @@ use rayon::{
     prelude::ParallelSliceMut,
 };
 
+use transcript::Transcript;
+
 use crate::{
-    expression::Expression, scheme::constants::MIN_PAR_SIZE, utils::next_pow2_instance_padding,
+    expression::Expression,
+    scheme::constants::MIN_PAR_SIZE,
+    structs::{CHALLENGE_NAMES, NUM_CHALLENGES},
+    utils::next_pow2_instance_padding,
 };
 
+/// MLEs of the instance columns of a circuit, padded with zeros like the witness to
+/// `next_pow2_instance_padding(num_instances)` rows.
+pub fn instance_column_mles<E: ExtensionField>(
+    columns: &[Vec<E::BaseField>],
+    num_instances: usize,
+) -> Vec<DenseMultilinearExtension<E>> {
+    let num_rows = next_pow2_instance_padding(num_instances);
+    columns
+        .iter()
+        .map(|column| {
+            let mut column = column.clone();
+            column.resize(num_rows, E::BaseField::ZERO);
+            column.into_mle()
+        })
+        .collect()
+}
+
+/// Draw the challenges of `CHALLENGE_NAMES` from the transcript, in order.
+pub fn draw_challenges<E: ExtensionField>(
+    transcript: &mut impl Transcript<E>,
+) -> [E; NUM_CHALLENGES] {
+    CHALLENGE_NAMES.map(|name| {
+        transcript
+            .get_and_append_challenge(name.as_bytes())
+            .elements
+    })
+}
+
 /// interleaving multiple mles into mles, and num_limbs indicate number of final limbs vector
 /// e.g input [[1,2],[3,4],[5,6],[7,8]], num_limbs=2,log2_per_instance_size=3
 /// output [[1,3,5,7,0,0,0,0],[2,4,6,8,0,0,0,0]]
@@ -104,29 +138,24 @@ macro_rules! tower_mle_4 {
     };
 }
 
-/// infer logup witness from last layer
-/// return is the ([p1,p2], [q1,q2]) for each layer
-pub(crate) fn infer_tower_logup_witness<'a, E: ExtensionField>(
-    p_mles: Option<Vec<ArcMultilinearExtension<'a, E>>>,
-    q_mles: Vec<ArcMultilinearExtension<'a, E>>,
-) -> Vec<Vec<ArcMultilinearExtension<'a, E>>> {
-    if cfg!(test) {
-        assert_eq!(q_mles.len(), 2);
-        assert!(q_mles.iter().map(|q| q.evaluations().len()).all_equal());
-    }
-    let num_vars = ceil_log2(q_mles[0].evaluations().len());
-    let mut wit_layers = (0..num_vars).fold(vec![(p_mles, q_mles)], |mut acc, _| {
-        let (p, q): &(
-            Option<Vec<ArcMultilinearExtension<E>>>,
-            Vec<ArcMultilinearExtension<E>>,
-        ) = acc.last().unwrap();
-        let (q1, q2) = (&q[0], &q[1]);
-        let cur_len = q1.evaluations().len() / 2;
-        let (next_p, next_q): (
-            Vec<ArcMultilinearExtension<E>>,
-            Vec<ArcMultilinearExtension<E>>,
-        ) = (0..2)
-            .map(|index| {
+/// The layer of a logup tower above the layer `(p, q)`, as `([p1, p2], [q1, q2])`.
+/// The numerators `p` are all 1 when `None`.
+fn tower_logup_layer<'a, E: ExtensionField>(
+    p: Option<&[ArcMultilinearExtension<'a, E>]>,
+    q: &[ArcMultilinearExtension<'a, E>],
+) -> (
+    Vec<ArcMultilinearExtension<'a, E>>,
+    Vec<ArcMultilinearExtension<'a, E>>,
+) {
+    let (q1, q2) = (&q[0], &q[1]);
+    let cur_len = q1.evaluations().len() / 2;
+    (0..2)
+        .into_par_iter()
+        .map(
+            |index| -> (
+                ArcMultilinearExtension<'a, E>,
+                ArcMultilinearExtension<'a, E>,
+            ) {
                 let mut p_evals = vec![E::ZERO; cur_len];
                 let mut q_evals = vec![E::ZERO; cur_len];
                 let start_index = cur_len * index;
@@ -170,32 +199,101 @@ pub(crate) fn infer_tower_logup_witness<'a, E: ExtensionField>(
                     };
                 }
                 (p_evals.into_mle().into(), q_evals.into_mle().into())
-            })
-            .unzip(); // vec[vec[p1, p2], vec[q1, q2]]
+            },
+        )
+        .unzip() // vec[vec[p1, p2], vec[q1, q2]]
+}
+
+/// The polynomials `[p1, p2, q1, q2]` of a logup tower layer.
+fn logup_layer_polys<'a, E: ExtensionField>(
+    p: Option<Vec<ArcMultilinearExtension<'a, E>>>,
+    q: Vec<ArcMultilinearExtension<'a, E>>,
+) -> Vec<ArcMultilinearExtension<'a, E>> {
+    // input layer p are all 1
+    if let Some(p) = p {
+        [p, q].concat()
+    } else {
+        let len = q[0].evaluations().len();
+        vec![
+            vec![E::ONE; len].into_mle().into(),
+            vec![E::ONE; len].into_mle().into(),
+        ]
+        .into_iter()
+        .chain(q)
+        .collect()
+    }
+}
+
+/// infer logup witness from last layer
+/// return is the ([p1,p2], [q1,q2]) for each layer
+pub(crate) fn infer_tower_logup_witness<'a, E: ExtensionField>(
+    p_mles: Option<Vec<ArcMultilinearExtension<'a, E>>>,
+    q_mles: Vec<ArcMultilinearExtension<'a, E>>,
+) -> Vec<Vec<ArcMultilinearExtension<'a, E>>> {
+    if cfg!(test) {
+        assert_eq!(q_mles.len(), 2);
+        assert!(q_mles.iter().map(|q| q.evaluations().len()).all_equal());
+    }
+    let num_vars = ceil_log2(q_mles[0].evaluations().len());
+    let mut wit_layers = (0..num_vars).fold(vec![(p_mles, q_mles)], |mut acc, _| {
+        let (p, q) = acc.last().unwrap();
+        let (next_p, next_q) = tower_logup_layer(p.as_deref(), q);
         acc.push((Some(next_p), next_q));
         acc
     });
     wit_layers.reverse();
     wit_layers
         .into_iter()
-        .map(|(p, q)| {
-            // input layer p are all 1
-            if let Some(p) = p {
-                [p, q].concat()
-            } else {
-                let len = q[0].evaluations().len();
-                vec![
-                    vec![E::ONE; len].into_mle().into(),
-                    vec![E::ONE; len].into_mle().into(),
-                ]
-                .into_iter()
-                .chain(q)
-                .collect()
-            }
-        })
+        .map(|(p, q)| logup_layer_polys(p, q))
         .collect_vec()
 }
 
+/// The layer at `round` of a logup tower, as in [`infer_tower_logup_witness`], recomputed from
+/// the input layer. Only one layer is kept at a time, at the cost of recomputing the layers
+/// below `round` on every call.
+pub(crate) fn infer_tower_logup_layer<'a, E: ExtensionField>(
+    p_mles: Option<&[ArcMultilinearExtension<'a, E>]>,
+    q_mles: &[ArcMultilinearExtension<'a, E>],
+    round: usize,
+) -> Vec<ArcMultilinearExtension<'a, E>> {
+    let num_vars = ceil_log2(q_mles[0].evaluations().len());
+    let (p, q) = (round..num_vars).fold(
+        (p_mles.map(|p| p.to_vec()), q_mles.to_vec()),
+        |(p, q), _| {
+            let (next_p, next_q) = tower_logup_layer(p.as_deref(), &q);
+            (Some(next_p), next_q)
+        },
+    );
+    logup_layer_polys(p, q)
+}
+
+/// The layer of a product tower above `next_layer`.
+fn tower_product_layer<'a, E: ExtensionField>(
+    next_layer: &[ArcMultilinearExtension<'a, E>],
+    num_product_fanin: usize,
+) -> Vec<ArcMultilinearExtension<'a, E>> {
+    let cur_len = next_layer[0].evaluations().len() / num_product_fanin;
+    (0..num_product_fanin)
+        .into_par_iter()
+        .map(|index| -> ArcMultilinearExtension<'a, E> {
+            let mut evaluations = vec![E::ONE; cur_len];
+            next_layer.iter().for_each(|f| match f.evaluations() {
+                FieldType::Ext(f) => {
+                    let start: usize = index * cur_len;
+                    f[start..][..cur_len]
+                        .par_iter()
+                        .zip(evaluations.par_iter_mut())
+                        .with_min_len(MIN_PAR_SIZE)
+                        .map(|(v, evaluations)| *evaluations *= *v)
+                        .collect()
+                }
+                _ => unreachable!("must be extension field"),
+            });
+            evaluations.into_mle().into()
+        })
+        .collect()
+}
+
 /// infer tower witness from last layer
 pub(crate) fn infer_tower_product_witness<E: ExtensionField>(
     num_vars: usize,
@@ -206,26 +304,7 @@ pub(crate) fn infer_tower_product_witness<E: ExtensionField>(
     let log2_num_product_fanin = ceil_log2(num_product_fanin);
     let mut wit_layers =
         (0..(num_vars / log2_num_product_fanin) - 1).fold(vec![last_layer], |mut acc, _| {
-            let next_layer = acc.last().unwrap();
-            let cur_len = next_layer[0].evaluations().len() / num_product_fanin;
-            let cur_layer: Vec<ArcMultilinearExtension<E>> = (0..num_product_fanin)
-                .map(|index| {
-                    let mut evaluations = vec![E::ONE; cur_len];
-                    next_layer.iter().for_each(|f| match f.evaluations() {
-                        FieldType::Ext(f) => {
-                            let start: usize = index * cur_len;
-                            f[start..][..cur_len]
-                                .par_iter()
-                                .zip(evaluations.par_iter_mut())
-                                .with_min_len(MIN_PAR_SIZE)
-                                .map(|(v, evaluations)| *evaluations *= *v)
-                                .collect()
-                        }
-                        _ => unreachable!("must be extension field"),
-                    });
-                    evaluations.into_mle().into()
-                })
-                .collect_vec();
+            let cur_layer = tower_product_layer(acc.last().unwrap(), num_product_fanin);
             acc.push(cur_layer);
             acc
         });
@@ -233,12 +312,40 @@ pub(crate) fn infer_tower_product_witness<E: ExtensionField>(
     wit_layers
 }
 
+/// The layer at `round` of a product tower, as in [`infer_tower_product_witness`], recomputed
+/// from the last layer. Only one layer is kept at a time, at the cost of recomputing the layers
+/// below `round` on every call.
+pub(crate) fn infer_tower_product_layer<'a, E: ExtensionField>(
+    num_vars: usize,
+    last_layer: &[ArcMultilinearExtension<'a, E>],
+    num_product_fanin: usize,
+    round: usize,
+) -> Vec<ArcMultilinearExtension<'a, E>> {
+    assert!(last_layer.len() == num_product_fanin);
+    let num_layers = num_vars / ceil_log2(num_product_fanin);
+    (round + 1..num_layers).fold(last_layer.to_vec(), |layer, _| {
+        tower_product_layer(&layer, num_product_fanin)
+    })
+}
+
 pub(crate) fn wit_infer_by_expr<'a, E: ExtensionField, const N: usize>(
     fixed: &[ArcMultilinearExtension<'a, E>],
     witnesses: &[ArcMultilinearExtension<'a, E>],
     instance: &[ArcMultilinearExtension<'a, E>],
     challenges: &[E; N],
     expr: &Expression<E>,
+) -> ArcMultilinearExtension<'a, E> {
+    wit_infer_by_expr_on(&Cpu, fixed, witnesses, instance, challenges, expr)
+}
+
+/// [`wit_infer_by_expr`] with the products of base and extension field MLEs on `device`.
+pub(crate) fn wit_infer_by_expr_on<'a, E: ExtensionField, const N: usize>(
+    device: &dyn Device<E>,
+    fixed: &[ArcMultilinearExtension<'a, E>],
+    witnesses: &[ArcMultilinearExtension<'a, E>],
+    instance: &[ArcMultilinearExtension<'a, E>],
+    challenges: &[E; N],
+    expr: &Expression<E>,
 ) -> ArcMultilinearExtension<'a, E> {
     expr.evaluate_with_instance::<ArcMultilinearExtension<'_, E>>(
         &|f| fixed[f.0].clone(),
@@ -294,6 +401,17 @@ pub(crate) fn wit_infer_by_expr<'a, E: ExtensionField, const N: usize>(
             })
         },
         &|a, b| {
+            if let (Evals::Base(base), Evals::Ext(ext)) | (Evals::Ext(ext), Evals::Base(base)) =
+                (Evals::of(a.as_ref()), Evals::of(b.as_ref()))
+            {
+                if base.len() > 1 && ext.len() > 1 {
+                    assert_eq!(base.len(), ext.len());
+                    return Arc::new(DenseMultilinearExtension::from_evaluations_ext_vec(
+                        ceil_log2(base.len()),
+                        device.mul_base_ext(base, ext),
+                    ));
+                }
+            }
             commutative_op_mle_pair!(|a, b| {
                 match (a.len(), b.len()) {
                     (1, 1) => Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
@@ -415,7 +533,8 @@ mod tests {
         circuit_builder::{CircuitBuilder, ConstraintSystem},
         expression::{Expression, ToExpr},
         scheme::utils::{
-            infer_tower_logup_witness, infer_tower_product_witness, interleaving_mles_to_mles,
+            infer_tower_logup_layer, infer_tower_logup_witness, infer_tower_product_layer,
+            infer_tower_product_witness, interleaving_mles_to_mles,
         },
     };
 
@@ -660,6 +779,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_infer_tower_layer() {
+        type E = GoldilocksExt2;
+        let mle = |offset: u64| -> ArcMultilinearExtension<E> {
+            (0..8)
+                .map(|i| E::from(i + offset))
+                .collect_vec()
+                .into_mle()
+                .into()
+        };
+        let evals = |layer: &[ArcMultilinearExtension<E>]| {
+            layer
+                .iter()
+                .map(|mle| mle.get_ext_field_vec().to_vec())
+                .collect_vec()
+        };
+
+        let last_layer = vec![mle(1), mle(9)];
+        let layers = infer_tower_product_witness(4, last_layer.clone(), 2);
+        for (round, layer) in layers.iter().enumerate() {
+            assert_eq!(
+                evals(&infer_tower_product_layer(4, &last_layer, 2, round)),
+                evals(layer)
+            );
+        }
+
+        let (p, q) = (vec![mle(1), mle(2)], vec![mle(3), mle(4)]);
+        let layers = infer_tower_logup_witness(Some(p.clone()), q.clone());
+        for (round, layer) in layers.iter().enumerate() {
+            assert_eq!(
+                evals(&infer_tower_logup_layer(Some(&p), &q, round)),
+                evals(layer)
+            );
+        }
+    }
+
     #[test]
     fn test_wit_infer_by_expr_base_field() {
         type E = goldilocks::GoldilocksExt2;
@@ -1,32 +1,35 @@
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, iter, marker::PhantomData};
 
 use ark_std::iterable::Iterable;
 use ceno_emul::WORD_SIZE;
 use ff_ext::ExtensionField;
 
-use itertools::{Itertools, interleave, izip};
-use mpcs::PolynomialCommitmentScheme;
+use itertools::{Itertools, chain, enumerate, interleave, izip};
+use mpcs::{MultiBatchOpening, PolynomialCommitmentScheme};
 use multilinear_extensions::{
     mle::{IntoMLE, MultilinearExtension},
     util::ceil_log2,
     virtual_poly::{VPAuxInfo, build_eq_x_r_vec_sequential, eq_eval},
 };
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use serde::{Serialize, de::DeserializeOwned};
 use sumcheck::structs::{IOPProof, IOPVerifierState};
-use transcript::{ForkableTranscript, Transcript};
+use transcript::{AnyTranscript, ForkableTranscript, Transcript};
 
 use crate::{
     circuit_builder::SetTableAddrType,
-    error::ZKVMError,
+    error::{VerifyError, ZKVMError},
     expression::Instance,
     instructions::{Instruction, riscv::ecall::HaltInstruction},
     scheme::{
         constants::{NUM_FANIN, NUM_FANIN_LOGUP, SEL_DEGREE},
-        utils::eval_by_expr_with_instance,
+        utils::{draw_challenges, eval_by_expr_with_instance, instance_column_mles},
     },
-    structs::{Point, PointAndEval, TowerProofs, VerifyingKey, ZKVMVerifyingKey},
+    serialization::from_bytes,
+    structs::{Point, PointAndEval, TowerProofs, VKDigest, VerifyingKey, ZKVMVerifyingKey},
     utils::{
         eq_eval_less_or_equal_than, eval_wellform_address_vec, get_challenge_pows,
-        next_pow2_instance_padding,
+        next_pow2_instance_padding, sel_eval,
     },
 };
 
@@ -43,6 +46,34 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         ZKVMVerifier { vk }
     }
 
+    /// Verify a trace from start to halt, against a verifying key from an untrusted source as
+    /// serialized by `serialization::to_bytes`. The key is rejected unless its digest is
+    /// `expected_digest`, which pins the circuits the proof must be generated for. The
+    /// transcript, labelled `label`, uses the hash function of the key.
+    pub fn verify_with_expected_digest(
+        vk_bytes: &[u8],
+        expected_digest: &VKDigest,
+        vm_proof: ZKVMProof<E, PCS>,
+        label: &'static [u8],
+    ) -> Result<bool, ZKVMError>
+    where
+        E: DeserializeOwned,
+        E::BaseField: Serialize + DeserializeOwned,
+    {
+        let vk: ZKVMVerifyingKey<E, PCS> = from_bytes(vk_bytes)?;
+        if vk.digest()? != *expected_digest {
+            return Err(ZKVMError::VKDigestMismatch);
+        }
+        let verifier = Self::new(vk);
+        let transcript = verifier.new_transcript(label);
+        verifier.verify_proof(vm_proof, transcript)
+    }
+
+    /// Create a new transcript over the hash function of the verifying key.
+    pub fn new_transcript(&self, label: &'static [u8]) -> AnyTranscript<E> {
+        self.vk.new_transcript(label)
+    }
+
     /// Verify a trace from start to halt.
     #[tracing::instrument(skip_all, name = "verify_proof")]
     pub fn verify_proof(
@@ -53,6 +84,49 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         self.verify_proof_halt(vm_proof, transcript, true)
     }
 
+    /// Verify many traces from start to halt against `vk`, each on its own transcript labelled
+    /// `label`. Returns the result of each proof, in order.
+    ///
+    /// A single verifier over `vk` serves all the proofs. The circuits of the proofs are verified
+    /// in parallel, then the openings of the commitments of the proofs whose circuits verify are
+    /// checked together, see `PolynomialCommitmentScheme::multi_batch_verify_all`.
+    pub fn batch_verify(
+        vm_proofs: &[ZKVMProof<E, PCS>],
+        vk: &ZKVMVerifyingKey<E, PCS>,
+        label: &'static [u8],
+    ) -> Vec<Result<bool, ZKVMError>> {
+        let verifier = Self::new(vk.clone());
+        let verified = vm_proofs
+            .par_iter()
+            .map(|vm_proof| {
+                verifier.check_version(vm_proof)?;
+                verifier.check_halt(vm_proof, true)?;
+                verifier.verify_circuits(vm_proof, verifier.new_transcript(label))
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = Vec::with_capacity(vm_proofs.len());
+        let mut openings = vec![];
+        let mut opened = vec![];
+        for (i, verified) in verified.into_iter().enumerate() {
+            match verified {
+                Ok(Some(opening)) => {
+                    openings.push(opening);
+                    opened.push(i);
+                    results.push(Ok(true));
+                }
+                Ok(None) => results.push(Ok(true)),
+                Err(error) => results.push(Err(error)),
+            }
+        }
+        for (i, result) in izip!(opened, PCS::multi_batch_verify_all(&vk.vp, openings)) {
+            if let Err(error) = result {
+                results[i] = Err(VerifyError::PCSOpening(error).into());
+            }
+        }
+        results
+    }
+
     /// Verify a trace from start to optional halt.
     pub fn verify_proof_halt(
         &self,
@@ -60,34 +134,105 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         transcript: impl ForkableTranscript<E>,
         does_halt: bool,
     ) -> Result<bool, ZKVMError> {
-        // require ecall/halt proof to exist, depending whether we expect a halt.
+        self.check_version(&vm_proof)?;
+        self.check_halt(&vm_proof, does_halt)?;
+        self.verify_proof_validity(&vm_proof, transcript)
+    }
+
+    /// Require the ecall/halt proof to exist, depending whether we expect a halt.
+    fn check_halt(&self, vm_proof: &ZKVMProof<E, PCS>, does_halt: bool) -> Result<(), ZKVMError> {
         let num_instances = vm_proof
             .opcode_proofs
             .get(&HaltInstruction::<E>::name())
             .map(|(_, p)| p.num_instances)
             .unwrap_or(0);
         if num_instances != (does_halt as usize) {
-            return Err(ZKVMError::VerifyError(format!(
+            return Err(VerifyError::Unsatisfied(format!(
                 "ecall/halt num_instances={}, expected={}",
                 num_instances, does_halt as usize
-            )));
+            ))
+            .into());
         }
-
-        self.verify_proof_validity(vm_proof, transcript)
+        Ok(())
     }
 
-    fn verify_proof_validity(
+    /// Verify the proof of a single circuit of `vm_proof`, e.g. to check part of a proof. This
+    /// does not check the relations across circuits, nor the public values shared with other
+    /// circuits, nor the opening of the commitments, which is batched across circuits, so it
+    /// does not verify the execution.
+    pub fn verify_circuit(
         &self,
-        vm_proof: ZKVMProof<E, PCS>,
-        mut transcript: impl ForkableTranscript<E>,
-    ) -> Result<bool, ZKVMError> {
-        // main invariant between opcode circuits and table circuits
-        let mut prod_r = E::ONE;
-        let mut prod_w = E::ONE;
-        let mut logup_sum = E::ZERO;
+        vm_proof: &ZKVMProof<E, PCS>,
+        name: &str,
+        transcript: impl ForkableTranscript<E>,
+    ) -> Result<(), ZKVMError> {
+        self.check_version(vm_proof)?;
+        let (mut transcripts, challenges) = self.fork_transcripts(vm_proof, transcript)?;
+        let circuit_vk = self
+            .vk
+            .circuit_vks
+            .get(name)
+            .ok_or_else(|| VerifyError::CircuitNotInVK(name.to_string()))?;
+        let point_eval = PointAndEval::default();
+        if let Some((i, opcode_proof)) = vm_proof.opcode_proofs.get(name) {
+            let transcript = transcripts
+                .get_mut(*i)
+                .ok_or_else(|| VerifyError::InvalidForkIndex(name.to_string(), *i))?;
+            if opcode_proof.num_instances == 0 {
+                return Err(in_circuit(name, VerifyError::NoInstances.into()));
+            }
+            self.verify_opcode_proof(
+                name,
+                circuit_vk,
+                opcode_proof,
+                &vm_proof.pi_evals,
+                transcript,
+                NUM_FANIN,
+                &point_eval,
+                &challenges,
+            )
+            .map_err(|e| in_circuit(name, e))?;
+        } else if let Some((i, table_proof)) = vm_proof.table_proofs.get(name) {
+            let transcript = transcripts
+                .get_mut(*i)
+                .ok_or_else(|| VerifyError::InvalidForkIndex(name.to_string(), *i))?;
+            self.verify_table_proof(
+                name,
+                circuit_vk,
+                table_proof,
+                &vm_proof.raw_pi,
+                &vm_proof.pi_evals,
+                transcript,
+                NUM_FANIN_LOGUP,
+                &point_eval,
+                &challenges,
+            )
+            .map_err(|e| in_circuit(name, e))?;
+        } else {
+            return Err(VerifyError::MissingProof(name.to_string()).into());
+        }
+        Ok(())
+    }
 
-        let pi_evals = &vm_proof.pi_evals;
+    /// Reject a proof of a version the key cannot verify, before reading it any further.
+    fn check_version(&self, vm_proof: &ZKVMProof<E, PCS>) -> Result<(), ZKVMError> {
+        if vm_proof.version.is_compatible(&self.vk.version) {
+            Ok(())
+        } else {
+            Err(ZKVMError::VersionMismatch {
+                proof: vm_proof.version.clone(),
+                vk: self.vk.version.clone(),
+            })
+        }
+    }
 
+    /// Replay the transcript up to the challenges, and fork it into one transcript per circuit,
+    /// then one for the opening of the commitments of all the circuits.
+    fn fork_transcripts<T: ForkableTranscript<E>>(
+        &self,
+        vm_proof: &ZKVMProof<E, PCS>,
+        mut transcript: T,
+    ) -> Result<(Vec<T>, [E; 2]), ZKVMError> {
         // TODO fix soundness: construct raw public input by ourself and trustless from proof
         // including raw public input to transcript
         vm_proof
@@ -95,20 +240,6 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             .iter()
             .for_each(|v| v.iter().for_each(|v| transcript.append_field_element(v)));
 
-        // verify constant poly(s) evaluation result match
-        // we can evaluate at this moment because constant always evaluate to same value
-        // non-constant poly(s) will be verified in respective (table) proof accordingly
-        izip!(&vm_proof.raw_pi, pi_evals)
-            .enumerate()
-            .try_for_each(|(i, (raw, eval))| {
-                if raw.len() == 1 && E::from(raw[0]) != *eval {
-                    Err(ZKVMError::VerifyError(format!(
-                        "pub input on index {i} mismatch  {raw:?} != {eval:?}"
-                    )))
-                } else {
-                    Ok(())
-                }
-            })?;
         // write fixed commitment to transcript
         for (_, vk) in self.vk.circuit_vks.iter() {
             if let Some(fixed_commit) = vk.fixed_commit.as_ref() {
@@ -121,6 +252,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             tracing::debug!("read {}'s commit", name);
             PCS::write_commitment(&proof.wits_commit, &mut transcript)
                 .map_err(ZKVMError::PCSError)?;
+            for v in proof.instance_columns.iter().flatten() {
+                transcript.append_field_element(v);
+            }
         }
         for (name, (_, proof)) in vm_proof.table_proofs.iter() {
             tracing::debug!("read {}'s commit", name);
@@ -129,36 +263,110 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         }
 
         // alpha, beta
-        let challenges = [
-            transcript.read_challenge().elements,
-            transcript.read_challenge().elements,
-        ];
+        let challenges = draw_challenges(&mut transcript);
         tracing::debug!("challenges in verifier: {:?}", challenges);
 
+        Ok((transcript.fork(self.vk.circuit_vks.len() + 1), challenges))
+    }
+
+    fn verify_proof_validity(
+        &self,
+        vm_proof: &ZKVMProof<E, PCS>,
+        transcript: impl ForkableTranscript<E>,
+    ) -> Result<bool, ZKVMError> {
+        if let Some(mut opening) = self.verify_circuits(vm_proof, transcript)? {
+            PCS::multi_batch_verify(
+                &self.vk.vp,
+                &opening.comms,
+                &opening.points,
+                &opening.evals,
+                opening.proof,
+                &mut opening.transcript,
+            )
+            .map_err(VerifyError::PCSOpening)?;
+            tracing::info!(
+                "verified opening proof for {} commitments",
+                opening.comms.len()
+            );
+        }
+        Ok(true)
+    }
+
+    /// Verify the proofs of the circuits and the relations across them, and return the
+    /// opening of their commitments, which is left to check, or `None` if nothing is committed.
+    fn verify_circuits<'a, T: ForkableTranscript<E>>(
+        &self,
+        vm_proof: &'a ZKVMProof<E, PCS>,
+        transcript: T,
+    ) -> Result<Option<MultiBatchOpening<'a, E, PCS, T>>, ZKVMError> {
+        // main invariant between opcode circuits and table circuits
+        let mut prod_r = E::ONE;
+        let mut prod_w = E::ONE;
+        let mut logup_sum = E::ZERO;
+
+        let pi_evals = &vm_proof.pi_evals;
+
+        // verify constant poly(s) evaluation result match
+        // we can evaluate at this moment because constant always evaluate to same value
+        // non-constant poly(s) will be verified in respective (table) proof accordingly
+        izip!(&vm_proof.raw_pi, pi_evals)
+            .enumerate()
+            .try_for_each(|(i, (raw, eval))| {
+                if raw.len() == 1 && E::from(raw[0]) != *eval {
+                    Err(VerifyError::Unsatisfied(format!(
+                        "pub input on index {i} mismatch  {raw:?} != {eval:?}"
+                    )))
+                } else {
+                    Ok(())
+                }
+            })?;
+        let (mut transcripts, challenges) = self.fork_transcripts(vm_proof, transcript)?;
+
         let dummy_table_item = challenges[0];
         let mut dummy_table_item_multiplicity = 0;
         let point_eval = PointAndEval::default();
-        let mut transcripts = transcript.fork(self.vk.circuit_vks.len());
+        // the last fork opens the commitments of all the circuits
+        let mut opening_transcript = transcripts.pop().unwrap();
+
+        // the commitments to open, with their points and evaluations, by circuit
+        let mut opening_claims = BTreeMap::new();
+
+        // circuits without instances are left out of the proof, the others must be proven as
+        // the kind of circuit they are
+        for (name, circuit_vk) in &self.vk.circuit_vks {
+            let is_opcode_circuit = circuit_vk.get_cs().is_opcode_circuit();
+            if (vm_proof.opcode_proofs.contains_key(name) && !is_opcode_circuit)
+                || (vm_proof.table_proofs.contains_key(name) && is_opcode_circuit)
+            {
+                return Err(VerifyError::CircuitKind(name.clone()).into());
+            }
+        }
 
-        for (name, (i, opcode_proof)) in vm_proof.opcode_proofs {
-            let transcript = &mut transcripts[i];
+        for (name, (i, opcode_proof)) in &vm_proof.opcode_proofs {
+            let transcript = transcripts
+                .get_mut(*i)
+                .ok_or_else(|| VerifyError::InvalidForkIndex(name.clone(), *i))?;
+            if opcode_proof.num_instances == 0 {
+                return Err(in_circuit(name, VerifyError::NoInstances.into()));
+            }
 
             let circuit_vk = self
                 .vk
                 .circuit_vks
-                .get(&name)
-                .ok_or(ZKVMError::VKNotFound(name.clone()))?;
-            let _rand_point = self.verify_opcode_proof(
-                &name,
-                &self.vk.vp,
-                circuit_vk,
-                &opcode_proof,
-                pi_evals,
-                transcript,
-                NUM_FANIN,
-                &point_eval,
-                &challenges,
-            )?;
+                .get(name)
+                .ok_or_else(|| VerifyError::CircuitNotInVK(name.clone()))?;
+            let input_opening_point = self
+                .verify_opcode_proof(
+                    name,
+                    circuit_vk,
+                    opcode_proof,
+                    pi_evals,
+                    transcript,
+                    NUM_FANIN,
+                    &point_eval,
+                    &challenges,
+                )
+                .map_err(|e| in_circuit(name, e))?;
             tracing::info!("verified proof for opcode {}", name);
 
             // getting the number of dummy padding item that we used in this opcode circuit
@@ -177,28 +385,37 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                 opcode_proof.lk_p1_out_eval * opcode_proof.lk_q1_out_eval.invert().unwrap();
             logup_sum +=
                 opcode_proof.lk_p2_out_eval * opcode_proof.lk_q2_out_eval.invert().unwrap();
+
+            opening_claims.insert(name, vec![(
+                opcode_proof.wits_commit.clone(),
+                input_opening_point,
+                opcode_proof.wits_in_evals.clone(),
+            )]);
         }
 
-        for (name, (i, table_proof)) in vm_proof.table_proofs {
-            let transcript = &mut transcripts[i];
+        for (name, (i, table_proof)) in &vm_proof.table_proofs {
+            let transcript = transcripts
+                .get_mut(*i)
+                .ok_or_else(|| VerifyError::InvalidForkIndex(name.clone(), *i))?;
 
             let circuit_vk = self
                 .vk
                 .circuit_vks
-                .get(&name)
-                .ok_or(ZKVMError::VKNotFound(name.clone()))?;
-            let _rand_point = self.verify_table_proof(
-                &name,
-                &self.vk.vp,
-                circuit_vk,
-                &table_proof,
-                &vm_proof.raw_pi,
-                &vm_proof.pi_evals,
-                transcript,
-                NUM_FANIN_LOGUP,
-                &point_eval,
-                &challenges,
-            )?;
+                .get(name)
+                .ok_or_else(|| VerifyError::CircuitNotInVK(name.clone()))?;
+            let input_opening_point = self
+                .verify_table_proof(
+                    name,
+                    circuit_vk,
+                    table_proof,
+                    &vm_proof.raw_pi,
+                    &vm_proof.pi_evals,
+                    transcript,
+                    NUM_FANIN_LOGUP,
+                    &point_eval,
+                    &challenges,
+                )
+                .map_err(|e| in_circuit(name, e))?;
             tracing::info!("verified proof for table {}", name);
 
             logup_sum = table_proof
@@ -210,16 +427,32 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
 
             prod_w *= table_proof.w_out_evals.iter().flatten().product::<E>();
             prod_r *= table_proof.r_out_evals.iter().flatten().product::<E>();
+
+            // the fixed traces first, as the prover opens them
+            let mut claims = vec![];
+            if let Some(fixed_commit) = &circuit_vk.fixed_commit {
+                claims.push((
+                    fixed_commit.clone(),
+                    input_opening_point.clone(),
+                    table_proof.fixed_in_evals.clone(),
+                ));
+            }
+            claims.push((
+                table_proof.wits_commit.clone(),
+                input_opening_point,
+                table_proof.wits_in_evals.clone(),
+            ));
+            opening_claims.insert(name, claims);
         }
+
         logup_sum -=
             E::from(dummy_table_item_multiplicity as u64) * dummy_table_item.invert().unwrap();
 
         // check logup relation across all proofs
         if logup_sum != E::ZERO {
-            return Err(ZKVMError::VerifyError(format!(
-                "logup_sum({:?}) != 0",
-                logup_sum
-            )));
+            return Err(
+                VerifyError::Unsatisfied(format!("logup_sum({:?}) != 0", logup_sum)).into(),
+            );
         }
 
         let initial_global_state = eval_by_expr_with_instance(
@@ -240,18 +473,40 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         prod_r *= finalize_global_state;
         // check rw_set equality across all proofs
         if prod_r != prod_w {
-            return Err(ZKVMError::VerifyError("prod_r != prod_w".into()));
+            return Err(VerifyError::Unsatisfied("prod_r != prod_w".into()).into());
         }
 
-        Ok(true)
+        // the commitments of all the circuits are opened at once, in the order of the circuits
+        let mut comms = vec![];
+        let mut points = vec![];
+        let mut evals = vec![];
+        for (comm, point, circuit_evals) in opening_claims.into_values().flatten() {
+            opening_transcript.append_field_element_exts(&point);
+            opening_transcript.append_field_element_exts(&circuit_evals);
+            comms.push(comm);
+            points.push(point);
+            evals.push(circuit_evals);
+        }
+        if comms.is_empty() {
+            return Ok(None);
+        }
+        let Some(opening_proof) = &vm_proof.opening_proof else {
+            return Err(VerifyError::MissingProof("opening proof".into()).into());
+        };
+        Ok(Some(MultiBatchOpening {
+            comms,
+            points,
+            evals,
+            proof: opening_proof,
+            transcript: opening_transcript,
+        }))
     }
 
-    /// verify proof and return input opening point
+    /// verify proof and return input opening point, at which the witness is left to open
     #[allow(clippy::too_many_arguments)]
     pub fn verify_opcode_proof(
         &self,
         name: &str,
-        vp: &PCS::VerifierParam,
         circuit_vk: &VerifyingKey<E, PCS>,
         proof: &ZKVMOpcodeProof<E, PCS>,
         pi: &[E],
@@ -272,6 +527,13 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             ceil_log2(lk_counts_per_instance),
         );
         let (chip_record_alpha, _) = (challenges[0], challenges[1]);
+        if proof.wits_in_evals.len() != cs.num_witin as usize {
+            return Err(VerifyError::EvaluationCount {
+                expected: cs.num_witin as usize,
+                got: proof.wits_in_evals.len(),
+            }
+            .into());
+        }
 
         let num_instances = proof.num_instances;
         let next_pow2_instance = next_pow2_instance_padding(num_instances);
@@ -307,9 +569,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         // verify LogUp witness nominator p(x) ?= constant vector 1
         // index 0 is LogUp witness for Fixed Lookup table
         if logup_p_evals[0].eval != E::ONE {
-            return Err(ZKVMError::VerifyError(
-                "Lookup table witness p(x) != constant 1".into(),
-            ));
+            return Err(
+                VerifyError::Unsatisfied("Lookup table witness p(x) != constant 1".into()).into(),
+            );
         }
 
         // verify zero statement (degree > 1) + sel sumcheck
@@ -335,6 +597,13 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             + *alpha_write * (record_evals[1].eval - E::ONE)
             + *alpha_lk * (logup_q_evals[0].eval - chip_record_alpha);
 
+        if proof.main_sel_sumcheck_proofs.len() != log2_num_instances {
+            return Err(VerifyError::SumcheckRoundCount {
+                expected: log2_num_instances,
+                got: proof.main_sel_sumcheck_proofs.len(),
+            }
+            .into());
+        }
         let main_sel_subclaim = IOPVerifierState::verify(
             claim_sum,
             &IOPProof {
@@ -357,6 +626,31 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                 .collect_vec(),
             main_sel_subclaim.expected_evaluation,
         );
+
+        // public values, then the instance columns of this circuit evaluated at the opening point
+        if proof.instance_columns.len() != cs.num_instance_columns
+            || proof
+                .instance_columns
+                .iter()
+                .any(|column| column.len() != num_instances)
+        {
+            return Err(VerifyError::Unsatisfied(format!(
+                "{name} expects {} instance columns of {num_instances} values",
+                cs.num_instance_columns
+            ))
+            .into());
+        }
+        let pi = pi
+            .iter()
+            .copied()
+            .chain(
+                instance_column_mles::<E>(&proof.instance_columns, num_instances)
+                    .iter()
+                    .map(|mle| mle.evaluate(&input_opening_point)),
+            )
+            .collect_vec();
+        let pi = pi.as_slice();
+
         let eq_r = build_eq_x_r_vec_sequential(&rt_r[..log2_r_count]);
         let eq_w = build_eq_x_r_vec_sequential(&rt_w[..log2_w_count]);
         let eq_lk = build_eq_x_r_vec_sequential(&rt_lk[..log2_lk_count]);
@@ -444,9 +738,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
         .iter()
         .sum::<E>();
         if computed_evals != expected_evaluation {
-            return Err(ZKVMError::VerifyError(
-                "main + sel evaluation verify failed".into(),
-            ));
+            return Err(
+                VerifyError::Unsatisfied("main + sel evaluation verify failed".into()).into(),
+            );
         }
         // verify records (degree = 1) statement, thus no sumcheck
         if cs
@@ -465,41 +759,39 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                     != *expected_evals
             })
         {
-            return Err(ZKVMError::VerifyError(
-                "record evaluate != expected_evals".into(),
-            ));
+            return Err(
+                VerifyError::Unsatisfied("record evaluate != expected_evals".into()).into(),
+            );
         }
 
-        // verify zero expression (degree = 1) statement, thus no sumcheck
+        // verify zero expression (degree = 1) statement, thus no sumcheck. On the zero padding
+        // rows, an expression evaluates to its constant term, which the selector of the
+        // instances cancels.
+        let sel = sel_eval(num_instances, &input_opening_point);
+        let zero_row_wits = vec![E::ZERO; cs.num_witin as usize];
+        let zero_row_pi = pi[..pi.len() - cs.num_instance_columns]
+            .iter()
+            .copied()
+            .chain(iter::repeat_n(E::ZERO, cs.num_instance_columns))
+            .collect_vec();
         if cs.assert_zero_expressions.iter().any(|expr| {
-            eval_by_expr_with_instance(&[], &proof.wits_in_evals, pi, challenges, expr) != E::ZERO
+            let constant =
+                eval_by_expr_with_instance(&[], &zero_row_wits, &zero_row_pi, challenges, expr);
+            eval_by_expr_with_instance(&[], &proof.wits_in_evals, pi, challenges, expr)
+                != constant * (E::ONE - sel)
         }) {
-            return Err(ZKVMError::VerifyError("zero expression != 0".into()));
+            return Err(VerifyError::Unsatisfied("zero expression != 0".into()).into());
         }
 
-        tracing::debug!(
-            "[opcode {}] verify opening proof for {} polys",
-            name,
-            proof.wits_in_evals.len(),
-        );
-        PCS::simple_batch_verify(
-            vp,
-            &proof.wits_commit,
-            &input_opening_point,
-            &proof.wits_in_evals,
-            &proof.wits_opening_proof,
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
-
         Ok(input_opening_point)
     }
 
+    /// verify proof and return input opening point, at which the witness and the fixed traces
+    /// are left to open
     #[allow(clippy::too_many_arguments)]
     pub fn verify_table_proof(
         &self,
         name: &str,
-        vp: &PCS::VerifierParam,
         circuit_vk: &VerifyingKey<E, PCS>,
         proof: &ZKVMTableProof<E, PCS>,
         raw_pi: &[Vec<E::BaseField>],
@@ -524,6 +816,19 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             .chain(cs.lk_table_expressions.iter().map(|lk| lk.table_len))
             .all_equal();
 
+        for (expected, got) in [
+            (cs.r_table_expressions.len(), proof.rw_hints_num_vars.len()),
+            (cs.r_table_expressions.len(), proof.r_out_evals.len()),
+            (cs.w_table_expressions.len(), proof.w_out_evals.len()),
+            (cs.lk_table_expressions.len(), proof.lk_out_evals.len()),
+            (cs.num_witin as usize, proof.wits_in_evals.len()),
+            (cs.num_fixed, proof.fixed_in_evals.len()),
+        ] {
+            if expected != got {
+                return Err(VerifyError::EvaluationCount { expected, got }.into());
+            }
+        }
+
         // verify and reduce product tower sumcheck
         let tower_proofs = &proof.tower_proof;
 
@@ -627,11 +932,22 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                     .zip_eq(alpha_pow.iter().skip(prod_point_and_eval.len()))
                     .map(|(point_n_eval, alpha)| *alpha * point_n_eval.eval)
                     .sum::<E>();
+            let same_r_sumcheck_proofs = proof
+                .same_r_sumcheck_proofs
+                .clone()
+                .ok_or_else(|| VerifyError::MissingProof("same point sumcheck".into()))?;
+            if same_r_sumcheck_proofs.len() != expected_max_rounds {
+                return Err(VerifyError::SumcheckRoundCount {
+                    expected: expected_max_rounds,
+                    got: same_r_sumcheck_proofs.len(),
+                }
+                .into());
+            }
             let sel_subclaim = IOPVerifierState::verify(
                 claim_sum,
                 &IOPProof {
                     point: vec![], // final claimed point will be derived from sumcheck protocol
-                    proofs: proof.same_r_sumcheck_proofs.clone().unwrap(),
+                    proofs: same_r_sumcheck_proofs,
                 },
                 &VPAuxInfo {
                     max_degree: SEL_DEGREE,
@@ -680,9 +996,7 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             .iter()
             .sum::<E>();
             if computed_evals != expected_evaluation {
-                return Err(ZKVMError::VerifyError(
-                    "sel evaluation verify failed".into(),
-                ));
+                return Err(VerifyError::Unsatisfied("sel evaluation verify failed".into()).into());
             }
             (
                 input_opening_point,
@@ -710,9 +1024,9 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                 expr,
             ) != expected_evals
         }) {
-            return Err(ZKVMError::VerifyError(
-                "record evaluate != expected_evals".into(),
-            ));
+            return Err(
+                VerifyError::Unsatisfied("record evaluate != expected_evals".into()).into(),
+            );
         }
 
         // verify dynamic address evaluation succinctly
@@ -727,9 +1041,10 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
                         &input_opening_point,
                     );
                     if expected_eval != proof.wits_in_evals[spec.addr_witin_id] {
-                        return Err(ZKVMError::VerifyError(
+                        return Err(VerifyError::Unsatisfied(
                             "dynamic addr evaluate != expected_evals".into(),
-                        ));
+                        )
+                        .into());
                     }
                 }
             }
@@ -741,58 +1056,30 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifier<E, PCS>
             let expected_eval = poly.evaluate(&input_opening_point[..poly.num_vars()]);
             let eval = pi[idx];
             if expected_eval != eval {
-                return Err(ZKVMError::VerifyError(format!(
+                return Err(VerifyError::Unsatisfied(format!(
                     "pub input on index {idx} mismatch  {expected_eval:?} != {eval:?}"
-                )));
+                ))
+                .into());
             }
             tracing::debug!(
                 "[table {name}] verified public inputs on index {idx} with point {input_opening_point:?}",
             );
         }
 
-        // do optional check of fixed_commitment openings by vk
-        if circuit_vk.fixed_commit.is_some() {
-            let Some(fixed_opening_proof) = &proof.fixed_opening_proof else {
-                return Err(ZKVMError::VerifyError(
-                    "fixed openning proof shoudn't be none".into(),
-                ));
-            };
-            PCS::simple_batch_verify(
-                vp,
-                circuit_vk.fixed_commit.as_ref().unwrap(),
-                &input_opening_point,
-                &proof.fixed_in_evals,
-                fixed_opening_proof,
-                transcript,
-            )
-            .map_err(ZKVMError::PCSError)?;
-        }
-
-        tracing::debug!(
-            "[table {}] verified opening proof for {} fixed polys",
-            name,
-            proof.fixed_in_evals.len(),
-        );
-
-        PCS::simple_batch_verify(
-            vp,
-            &proof.wits_commit,
-            &input_opening_point,
-            &proof.wits_in_evals,
-            &proof.wits_opening_proof,
-            transcript,
-        )
-        .map_err(ZKVMError::PCSError)?;
-        tracing::debug!(
-            "[table {}] verified opening proof for {} polys",
-            name,
-            proof.wits_in_evals.len(),
-        );
-
         Ok(input_opening_point)
     }
 }
 
+/// Attribute a verification error to the circuit `name`.
+fn in_circuit(name: &str, error: ZKVMError) -> ZKVMError {
+    match error {
+        ZKVMError::VerifyError(error) => {
+            VerifyError::Circuit(name.to_string(), Box::new(error)).into()
+        }
+        error => error,
+    }
+}
+
 pub struct TowerVerify;
 
 pub type TowerVerifyResult<E> = Result<
@@ -821,14 +1108,62 @@ impl TowerVerify {
         let num_logup_spec = logup_out_evals.len();
 
         let log2_num_fanin = ceil_log2(num_fanin);
-        // sanity check
-        assert!(num_prod_spec == tower_proofs.prod_spec_size());
-        assert!(prod_out_evals.iter().all(|evals| evals.len() == num_fanin));
-        assert!(num_logup_spec == tower_proofs.logup_spec_size());
-        assert!(logup_out_evals.iter().all(|evals| {
-            evals.len() == 4 // [p1, p2, q1, q2]
-        }));
         assert_eq!(num_variables.len(), num_prod_spec + num_logup_spec);
+        // check the shape of the proof
+        for (expected, got) in [
+            (num_prod_spec, tower_proofs.prod_spec_size()),
+            (num_logup_spec, tower_proofs.logup_spec_size()),
+        ] {
+            if expected != got {
+                return Err(VerifyError::TowerSpecCount { expected, got }.into());
+            }
+        }
+        let max_num_variables = *num_variables.iter().max().unwrap();
+        if tower_proofs.proofs.len() != max_num_variables - 1 {
+            return Err(VerifyError::TowerLayerCount {
+                expected: max_num_variables - 1,
+                got: tower_proofs.proofs.len(),
+            }
+            .into());
+        }
+        for (round, proofs) in enumerate(&tower_proofs.proofs) {
+            let expected = (round + 1) * log2_num_fanin;
+            if proofs.len() != expected {
+                return Err(VerifyError::SumcheckRoundCount {
+                    expected,
+                    got: proofs.len(),
+                }
+                .into());
+            }
+        }
+        let specs = izip!(
+            chain!(&prod_out_evals, &logup_out_evals),
+            chain!(
+                &tower_proofs.prod_specs_eval,
+                &tower_proofs.logup_specs_eval
+            ),
+            &num_variables
+        );
+        for (i, (out_evals, layer_evals, num_vars)) in specs.enumerate() {
+            // [p1, p2, q1, q2] for logup
+            let num_evals = if i < num_prod_spec { num_fanin } else { 4 };
+            if layer_evals.len() != num_vars - 1 {
+                return Err(VerifyError::TowerLayerCount {
+                    expected: num_vars - 1,
+                    got: layer_evals.len(),
+                }
+                .into());
+            }
+            for evals in chain!([out_evals], layer_evals) {
+                if evals.len() != num_evals {
+                    return Err(VerifyError::EvaluationCount {
+                        expected: num_evals,
+                        got: evals.len(),
+                    }
+                    .into());
+                }
+            }
+        }
 
         let alpha_pows = get_challenge_pows(
             num_prod_spec + num_logup_spec * 2, /* logup occupy 2 sumcheck: numerator and denominator */
@@ -877,8 +1212,6 @@ impl TowerVerify {
             .map(|(point_n_eval, alpha)| point_n_eval.eval * alpha)
             .sum::<E>();
 
-        let max_num_variables = num_variables.iter().max().unwrap();
-
         let (next_rt, _) = (0..(max_num_variables-1)).try_fold(
             (
                 PointAndEval {
@@ -933,7 +1266,7 @@ impl TowerVerify {
                         })
                         .sum::<E>();
                 if expected_evaluation != sumcheck_claim.expected_evaluation {
-                    return Err(ZKVMError::VerifyError("mismatch tower evaluation".into()));
+                    return Err(VerifyError::Unsatisfied("mismatch tower evaluation".into()).into());
                 }
 
                 // derive single eval
@@ -1013,7 +1346,7 @@ impl TowerVerify {
                     .sum::<E>();
                 // sum evaluation from different specs
                 let next_eval = next_prod_spec_evals + next_logup_spec_evals;
-                Ok((PointAndEval {
+                Ok::<_, ZKVMError>((PointAndEval {
                     point: rt_prime,
                     eval: next_eval,
                 }, next_alpha_pows))
@@ -0,0 +1,203 @@
+//! Proving and verification of a guest in a single call each, see [`prove`] and [`verify`].
+//!
+//! They run the steps of `e2e` in the order they require: key generation from the constraint
+//! systems and fixed traces, emulation, witness assignment of the opcode circuits, then of the
+//! table circuits once the lookup multiplicities are final, and proving.
+
+use std::path::PathBuf;
+
+use ceno_emul::{CENO_PLATFORM, IterAddresses, Platform, Program, WORD_SIZE, Word};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use mpcs::PolynomialCommitmentScheme;
+use serde::{Serialize, de::DeserializeOwned};
+use transcript::TranscriptHash;
+
+use crate::{
+    e2e::{run_e2e_gen_witness, run_e2e_keygen, run_e2e_proof},
+    error::{VerifyError, ZKVMError},
+    scheme::{PublicValues, ZKVMProof, verifier::ZKVMVerifier},
+    structs::ZKVMVerifyingKey,
+};
+
+/// The execution and proving parameters of [`prove`].
+#[derive(Clone, Debug)]
+pub struct ProveOptions {
+    pub platform: Platform,
+    /// Stack size in bytes.
+    pub stack_size: u32,
+    /// Heap size in bytes.
+    pub heap_size: u32,
+    /// The guest must halt within this many steps.
+    pub max_steps: usize,
+    /// Directory caching proving keys across runs of the same program, and the fixed traces of
+    /// the tables which do not depend on it.
+    pub key_cache: Option<PathBuf>,
+    pub transcript_hash: TranscriptHash,
+    /// Prove the execution of a guest which panics, as one which halts with the exit code
+    /// `Platform::code_panic`, rather than fail with `ZKVMError::GuestPanic`.
+    pub prove_panics: bool,
+}
+
+impl Default for ProveOptions {
+    fn default() -> Self {
+        Self {
+            platform: CENO_PLATFORM,
+            stack_size: 32 << 10,
+            heap_size: 2 << 20,
+            max_steps: usize::MAX,
+            key_cache: None,
+            transcript_hash: TranscriptHash::default(),
+            prove_panics: false,
+        }
+    }
+}
+
+fn load_elf(elf: &[u8]) -> Result<Program, ZKVMError> {
+    Program::load_elf(elf, u32::MAX)
+        .map_err(|err| ZKVMError::GuestError(format!("invalid ELF: {err}")))
+}
+
+/// Generate the verifying key of the guest `elf`, without running it. The proving key is cached
+/// in `options.key_cache`, if set, for the next [`prove`] of the same guest.
+pub fn keygen<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+    elf: &[u8],
+    options: &ProveOptions,
+) -> Result<ZKVMVerifyingKey<E, PCS>, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    let pk = run_e2e_keygen::<E, PCS>(
+        &load_elf(elf)?,
+        &options.platform,
+        options.stack_size.next_multiple_of(WORD_SIZE as u32),
+        options.heap_size.next_multiple_of(WORD_SIZE as u32),
+        options.key_cache.as_deref(),
+        options.transcript_hash,
+    )?;
+    Ok(pk.get_vk())
+}
+
+/// The proof of an execution, the verifying key of its program, and its public values.
+pub type ProveOutput<E, PCS> = (
+    ZKVMProof<E, PCS>,
+    ZKVMVerifyingKey<E, PCS>,
+    PublicValues<u32>,
+);
+
+/// Execute the guest `elf` until it halts, with `stdin` as its hints, e.g. the bytes of a
+/// `ceno_guest::CenoStdin`, and prove the execution. Return the proof, the verifying key of the
+/// program, and the public values of the execution. A guest which panics fails with its panic
+/// message, unless `options.prove_panics`.
+///
+/// The verifying key depends only on the program and the options, and a verifier must obtain it
+/// from a trusted source rather than from the prover, e.g. by running this key generation itself.
+pub fn prove<E: ExtensionField + DeserializeOwned, PCS: PolynomialCommitmentScheme<E>>(
+    elf: &[u8],
+    stdin: &[u8],
+    options: &ProveOptions,
+) -> Result<ProveOutput<E, PCS>, ZKVMError>
+where
+    E::BaseField: Serialize + DeserializeOwned,
+{
+    let program = load_elf(elf)?;
+
+    let mut hints = stdin.to_vec();
+    hints.resize(hints.len().next_multiple_of(WORD_SIZE), 0);
+    let hints = hints
+        .chunks_exact(WORD_SIZE)
+        .map(|word| Word::from_le_bytes(word.try_into().unwrap()))
+        .collect_vec();
+    if hints.len() > options.platform.hints.iter_addresses().len() {
+        return Err(ZKVMError::GuestError(format!(
+            "stdin must fit in {} bytes",
+            options.platform.hints.len()
+        )));
+    }
+
+    let stack_size = options.stack_size.next_multiple_of(WORD_SIZE as u32);
+    let heap_size = options.heap_size.next_multiple_of(WORD_SIZE as u32);
+    let (prover, verifier, zkvm_witness, pi, _, _, exit_code, guest_panic) =
+        run_e2e_gen_witness::<E, PCS>(
+            program,
+            options.platform.clone(),
+            stack_size,
+            heap_size,
+            hints,
+            options.max_steps,
+            options.key_cache.as_deref(),
+            options.transcript_hash,
+        )?;
+    if exit_code.is_none() {
+        return Err(ZKVMError::GuestError(format!(
+            "the guest did not halt within {} steps",
+            options.max_steps
+        )));
+    }
+    if let Some(panic) = guest_panic.filter(|_| !options.prove_panics) {
+        return Err(ZKVMError::GuestPanic(panic));
+    }
+
+    let proof = run_e2e_proof(prover, zkvm_witness, pi.clone())?;
+    Ok((proof, verifier.vk, pi))
+}
+
+/// Verify a proof of [`prove`] against the verifying key of the program, and check that it
+/// proves the execution with the given public values.
+pub fn verify<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>(
+    proof: ZKVMProof<E, PCS>,
+    vk: &ZKVMVerifyingKey<E, PCS>,
+    public_values: &PublicValues<u32>,
+) -> Result<(), ZKVMError> {
+    if proof.raw_pi != public_values.to_vec::<E>() {
+        return Err(VerifyError::Unsatisfied(
+            "the public values of the proof are not the expected ones".into(),
+        )
+        .into());
+    }
+
+    let verifier = ZKVMVerifier::new(vk.clone());
+    if !verifier.verify_proof_halt(proof, verifier.new_transcript(b"riscv"), true)? {
+        return Err(VerifyError::Unsatisfied("the proof is invalid".into()).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2;
+    use mpcs::{Basefold, BasefoldRSParams};
+
+    use super::{ProveOptions, keygen, prove, verify};
+    use crate::scheme::PublicValues;
+
+    type E = GoldilocksExt2;
+    type Pcs = Basefold<GoldilocksExt2, BasefoldRSParams>;
+
+    #[test]
+    fn test_prove_verify() {
+        let elf = ceno_examples::ceno_rt_mini;
+        let options = ProveOptions {
+            max_steps: 1 << 20,
+            ..ProveOptions::default()
+        };
+        let (proof, vk, public_values) = prove::<E, Pcs>(elf, &[], &options).unwrap();
+        verify(proof.clone(), &vk, &public_values).unwrap();
+
+        // the key of the verifier, generated without running the guest
+        let vk = keygen::<E, Pcs>(elf, &options).unwrap();
+        verify(proof.clone(), &vk, &public_values).unwrap();
+
+        // a proof of other public values is rejected
+        assert!(verify(proof, &vk, &PublicValues::default()).is_err());
+    }
+
+    #[test]
+    fn test_prove_unfinished() {
+        let options = ProveOptions {
+            max_steps: 1,
+            ..ProveOptions::default()
+        };
+        assert!(prove::<E, Pcs>(ceno_examples::ceno_rt_mini, &[], &options).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Versioned (de)serialization of proofs and keys.
+//!
+//! The payload is prefixed with [`FORMAT_VERSION`], so that data written by an incompatible
+//! version is rejected up front rather than misread. A proof for another party is best sent as
+//! a `.ceno` file, which also says what it proves, see [`ProofBundle`].
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::error::ZKVMError;
+
+mod bundle;
+pub use bundle::{
+    BUNDLE_MAGIC, BundleHeader, ProgramDigest, ProofBundle, program_digest, read_header,
+};
+mod calldata;
+pub use calldata::to_calldata;
+
+/// Version of the serialized layout of proofs and keys. Bump it whenever a serialized struct
+/// changes.
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct VersionedRef<'a, T> {
+    version: u32,
+    payload: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct Versioned<T> {
+    payload: T,
+}
+
+/// Serialize `value`, e.g. a `ZKVMProof` or `ZKVMVerifyingKey`, with a version header.
+pub fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, ZKVMError> {
+    serde_json::to_vec(&VersionedRef {
+        version: FORMAT_VERSION,
+        payload: value,
+    })
+    .map_err(|e| ZKVMError::SerializationError(e.to_string()))
+}
+
+/// Deserialize a value written by [`to_bytes`], checking its version first.
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ZKVMError> {
+    let header: Header =
+        serde_json::from_slice(bytes).map_err(|e| ZKVMError::SerializationError(e.to_string()))?;
+    if header.version != FORMAT_VERSION {
+        return Err(ZKVMError::UnsupportedFormatVersion(header.version));
+    }
+    let versioned: Versioned<T> =
+        serde_json::from_slice(bytes).map_err(|e| ZKVMError::SerializationError(e.to_string()))?;
+    Ok(versioned.payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::{Expression, ToExpr, WitIn},
+    };
+    use goldilocks::GoldilocksExt2 as E;
+
+    #[test]
+    fn test_constraint_system_round_trip() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let x = cb.create_witin(|| "x");
+        let y = cb.create_witin(|| "y");
+        cb.require_equal(|| "x = y", x.expr(), y.expr()).unwrap();
+        cb.assert_ux::<_, _, 16>(|| "x_u16", x.expr()).unwrap();
+        cb.create_fixed_with(|| "f", |row| row as u64).unwrap();
+
+        let bytes = to_bytes(&cs).unwrap();
+        let decoded: ConstraintSystem<E> = from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.witin_namespace_map, cs.witin_namespace_map);
+        assert_eq!(decoded.assert_zero_expressions, cs.assert_zero_expressions);
+        assert_eq!(decoded.lk_expressions, cs.lk_expressions);
+        // generators are not serialized
+        assert!(matches!(
+            decoded.generate_fixed_traces(1),
+            Err(ZKVMError::FixedTraceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_deep_expression_round_trip() {
+        let x = WitIn { id: 0 };
+        let sum = (0..1000).fold(x.expr(), |sum, _| {
+            Expression::<E>::Sum(Box::new(sum), Box::new(x.expr()))
+        });
+        let decoded: Expression<E> = from_bytes(&to_bytes(&sum).unwrap()).unwrap();
+        assert_eq!(decoded, sum);
+    }
+
+    #[test]
+    fn test_format_version() {
+        let bytes = to_bytes(&vec![1u32, 2, 3]).unwrap();
+        assert_eq!(from_bytes::<Vec<u32>>(&bytes).unwrap(), vec![1, 2, 3]);
+
+        let stale = String::from_utf8(bytes)
+            .unwrap()
+            .replace(&format!("\"version\":{FORMAT_VERSION}"), "\"version\":0");
+        assert!(matches!(
+            from_bytes::<Vec<u32>>(stale.as_bytes()),
+            Err(ZKVMError::UnsupportedFormatVersion(0))
+        ));
+        assert!(matches!(
+            from_bytes::<Vec<u32>>(b"not a proof"),
+            Err(ZKVMError::SerializationError(_))
+        ));
+    }
+}
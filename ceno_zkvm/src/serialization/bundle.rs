@@ -0,0 +1,187 @@
+//! The `.ceno` file: a proof with what a verifier needs to know about it, so that a proof handed
+//! to another party describes itself.
+//!
+//! The file is [`BUNDLE_MAGIC`] followed by a [`ProofBundle`] as written by `to_bytes`. Besides
+//! the proof, a bundle holds the version of the prover and its polynomial commitment scheme, the
+//! digest of the verifying key and of the program, and the public values of the execution. They
+//! are all in [`BundleHeader`], which is read without decoding the proof, e.g. to pick the PCS
+//! to decode it with.
+
+use std::path::Path;
+
+use ff_ext::ExtensionField;
+use mpcs::PolynomialCommitmentScheme;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{from_bytes, to_bytes};
+use crate::{
+    error::{VerifyError, ZKVMError},
+    scheme::{PublicValues, ZKVMProof, pcs::PcsKind},
+    sdk,
+    structs::{ProtocolVersion, VKDigest, ZKVMVerifyingKey},
+};
+
+/// The first bytes of a `.ceno` file.
+pub const BUNDLE_MAGIC: &[u8; 8] = b"CENOPRF\0";
+
+/// Blake3 digest of the ELF of a program, see [`program_digest`].
+pub type ProgramDigest = [u8; 32];
+
+/// The digest identifying the program `elf` in a bundle.
+pub fn program_digest(elf: &[u8]) -> ProgramDigest {
+    *blake3::hash(elf).as_bytes()
+}
+
+/// The contents of a `.ceno` file.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
+pub struct ProofBundle<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
+    pub version: ProtocolVersion,
+    pub pcs: PcsKind,
+    /// The digest of the verifying key of the proof, see `ZKVMVerifyingKey::digest`.
+    pub vk_digest: VKDigest,
+    pub program_digest: ProgramDigest,
+    pub public_values: PublicValues<u32>,
+    pub proof: ZKVMProof<E, PCS>,
+}
+
+/// The metadata of a bundle, without its proof.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct BundleHeader {
+    pub version: ProtocolVersion,
+    pub pcs: PcsKind,
+    pub vk_digest: VKDigest,
+    pub program_digest: ProgramDigest,
+    pub public_values: PublicValues<u32>,
+}
+
+/// The payload of a `.ceno` file, after its magic bytes.
+fn payload(bytes: &[u8]) -> Result<&[u8], ZKVMError> {
+    bytes
+        .strip_prefix(BUNDLE_MAGIC)
+        .ok_or_else(|| ZKVMError::SerializationError("not a .ceno file: wrong magic bytes".into()))
+}
+
+/// Read the metadata of a `.ceno` file.
+pub fn read_header(bytes: &[u8]) -> Result<BundleHeader, ZKVMError> {
+    from_bytes(payload(bytes)?)
+}
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ProofBundle<E, PCS> {
+    /// Bundle a proof of [`sdk::prove`] of the program `elf`, with its verifying key and public
+    /// values. `pcs` is the scheme of `PCS`.
+    pub fn new(
+        proof: ZKVMProof<E, PCS>,
+        vk: &ZKVMVerifyingKey<E, PCS>,
+        public_values: PublicValues<u32>,
+        elf: &[u8],
+        pcs: PcsKind,
+    ) -> Result<Self, ZKVMError>
+    where
+        E::BaseField: Serialize,
+    {
+        Ok(Self {
+            version: proof.version().clone(),
+            pcs,
+            vk_digest: vk.digest()?,
+            program_digest: program_digest(elf),
+            public_values,
+            proof,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ZKVMError>
+    where
+        E::BaseField: Serialize,
+    {
+        let mut bytes = BUNDLE_MAGIC.to_vec();
+        bytes.extend(to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ZKVMError>
+    where
+        E: DeserializeOwned,
+        E::BaseField: DeserializeOwned,
+    {
+        from_bytes(payload(bytes)?)
+    }
+
+    /// Write the bundle to a `.ceno` file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ZKVMError>
+    where
+        E::BaseField: Serialize,
+    {
+        std::fs::write(path, self.to_bytes()?).map_err(ZKVMError::IOError)
+    }
+
+    /// Read a bundle from a `.ceno` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ZKVMError>
+    where
+        E: DeserializeOwned,
+        E::BaseField: DeserializeOwned,
+    {
+        Self::from_bytes(&std::fs::read(path).map_err(ZKVMError::IOError)?)
+    }
+
+    /// Verify the proof against `vk`, which must be the key of the bundle, and check that it
+    /// proves the public values of the bundle. That the bundle is of the expected program is
+    /// up to the caller, who compares `program_digest` with that of the ELF it trusts.
+    pub fn verify(self, vk: &ZKVMVerifyingKey<E, PCS>) -> Result<(), ZKVMError>
+    where
+        E::BaseField: Serialize,
+    {
+        if vk.digest()? != self.vk_digest {
+            return Err(ZKVMError::VKDigestMismatch);
+        }
+        if self.version != *self.proof.version() {
+            return Err(VerifyError::Unsatisfied(
+                "the version of the bundle is not that of its proof".into(),
+            )
+            .into());
+        }
+        sdk::verify(self.proof, vk, &self.public_values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::GoldilocksExt2 as E;
+
+    use super::*;
+    use crate::scheme::pcs::BasefoldRS;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let public_values = PublicValues::new(0, 0x2000_0000, 4, 0x2000_0010, 64, vec![7, 8]);
+        let bundle = ProofBundle::<E, BasefoldRS<E>> {
+            version: ProtocolVersion::current(),
+            pcs: PcsKind::BasefoldRS,
+            vk_digest: [1; 32],
+            program_digest: program_digest(b"elf"),
+            public_values: public_values.clone(),
+            proof: ZKVMProof::empty(public_values.clone()),
+        };
+        let bytes = bundle.to_bytes().unwrap();
+        assert!(bytes.starts_with(BUNDLE_MAGIC));
+
+        assert_eq!(read_header(&bytes).unwrap(), BundleHeader {
+            version: ProtocolVersion::current(),
+            pcs: PcsKind::BasefoldRS,
+            vk_digest: [1; 32],
+            program_digest: program_digest(b"elf"),
+            public_values,
+        });
+        let decoded = ProofBundle::<E, BasefoldRS<E>>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.proof.raw_pi, bundle.proof.raw_pi);
+
+        // a proof serialized on its own is not a bundle
+        assert!(matches!(
+            read_header(&to_bytes(&bundle.proof).unwrap()),
+            Err(ZKVMError::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+//! An encoding of proofs as EVM calldata: a sequence of 32-byte big-endian words, in the spirit
+//! of the Solidity ABI, so that a contract reads each value with a single `calldataload`.
+//!
+//! - integers and bools take one word, signed ones sign-extended;
+//! - options are a word 0 or 1, followed by the value;
+//! - sequences, maps, strings and bytes are prefixed by their length, bytes are right-padded to
+//!   a whole number of words;
+//! - enum variants are prefixed by their index;
+//! - structs and tuples are their fields, in order.
+//!
+//! The encoding follows the serde data model, so it is not self-describing: the layout of a
+//! proof is fixed by its type and its lengths.
+//!
+//! A proof meant for the EVM should be proven over the Keccak transcript, see
+//! [`transcript::TranscriptHash`]. The Solidity contract that verifies it is not generated yet,
+//! and the Merkle commitments of Basefold still hash with Poseidon.
+
+use std::fmt::{self, Display};
+
+use serde::{Serialize, Serializer, ser};
+
+use super::FORMAT_VERSION;
+use crate::error::ZKVMError;
+
+pub const WORD_SIZE: usize = 32;
+
+/// Encode `value`, e.g. a `ZKVMProof`, as calldata. The first word is [`FORMAT_VERSION`].
+pub fn to_calldata<T: Serialize>(value: &T) -> Result<Vec<u8>, ZKVMError> {
+    let mut serializer = CalldataSerializer { output: vec![] };
+    serializer.word(FORMAT_VERSION as u128);
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| ZKVMError::SerializationError(e.0))?;
+    Ok(serializer.output)
+}
+
+#[derive(Debug)]
+struct CalldataError(String);
+
+impl Display for CalldataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CalldataError {}
+
+impl ser::Error for CalldataError {
+    fn custom<T: Display>(msg: T) -> Self {
+        CalldataError(msg.to_string())
+    }
+}
+
+struct CalldataSerializer {
+    output: Vec<u8>,
+}
+
+impl CalldataSerializer {
+    fn word(&mut self, value: u128) {
+        self.output.extend([0; WORD_SIZE - 16]);
+        self.output.extend(value.to_be_bytes());
+    }
+
+    fn signed_word(&mut self, value: i128) {
+        let fill = if value < 0 { 0xff } else { 0 };
+        self.output.extend([fill; WORD_SIZE - 16]);
+        self.output.extend(value.to_be_bytes());
+    }
+
+    fn padded_bytes(&mut self, bytes: &[u8]) {
+        self.word(bytes.len() as u128);
+        self.output.extend(bytes);
+        let padding = bytes.len().next_multiple_of(WORD_SIZE) - bytes.len();
+        self.output.extend(std::iter::repeat_n(0, padding));
+    }
+
+    fn length(&mut self, len: Option<usize>) -> Result<(), CalldataError> {
+        let len = len.ok_or_else(|| CalldataError("sequences must have a known length".into()))?;
+        self.word(len as u128);
+        Ok(())
+    }
+}
+
+impl Serializer for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), CalldataError> {
+        self.word(v as u128);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), CalldataError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), CalldataError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), CalldataError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), CalldataError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), CalldataError> {
+        self.signed_word(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), CalldataError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), CalldataError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), CalldataError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), CalldataError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), CalldataError> {
+        self.word(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), CalldataError> {
+        Err(CalldataError("floats have no calldata encoding".into()))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), CalldataError> {
+        Err(CalldataError("floats have no calldata encoding".into()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), CalldataError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), CalldataError> {
+        self.padded_bytes(v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CalldataError> {
+        self.padded_bytes(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), CalldataError> {
+        self.word(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), CalldataError> {
+        self.word(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CalldataError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), CalldataError> {
+        self.serialize_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        self.word(variant_index as u128);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, CalldataError> {
+        self.length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, CalldataError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self, CalldataError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CalldataError> {
+        self.word(variant_index as u128);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, CalldataError> {
+        self.length(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, CalldataError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CalldataError> {
+        self.word(variant_index as u128);
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), CalldataError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut CalldataSerializer {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calldata_layout() {
+        #[derive(Serialize)]
+        struct Example {
+            a: u64,
+            b: Vec<u32>,
+            c: Option<bool>,
+            d: i8,
+            e: String,
+        }
+
+        let calldata = to_calldata(&Example {
+            a: 1,
+            b: vec![2, 3],
+            c: Some(true),
+            d: -1,
+            e: "ceno".into(),
+        })
+        .unwrap();
+
+        let words = calldata.chunks_exact(WORD_SIZE).collect::<Vec<_>>();
+        assert_eq!(calldata.len() % WORD_SIZE, 0);
+        let small = |word: &[u8]| {
+            assert!(word[..WORD_SIZE - 8].iter().all(|b| *b == 0));
+            u64::from_be_bytes(word[WORD_SIZE - 8..].try_into().unwrap())
+        };
+        let header_and_ints = [FORMAT_VERSION as u64, 1, 2, 2, 3, 1, 1];
+        for (word, expected) in words.iter().zip(header_and_ints) {
+            assert_eq!(small(word), expected);
+        }
+        assert_eq!(words[7], &[0xff; WORD_SIZE]);
+        assert_eq!(small(words[8]), 4);
+        assert_eq!(&words[9][..4], b"ceno");
+        assert!(words[9][4..].iter().all(|b| *b == 0));
+        assert_eq!(words.len(), 10);
+    }
+}
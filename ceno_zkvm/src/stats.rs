@@ -1,18 +1,170 @@
 use crate::{
     circuit_builder::{ConstraintSystem, NameSpace},
     expression::Expression,
-    structs::{ZKVMConstraintSystem, ZKVMWitnesses},
+    structs::{ROMType, ZKVMConstraintSystem, ZKVMWitnesses},
     utils,
 };
+use ceno_emul::{CostTable, InsnKind};
 use ff_ext::ExtensionField;
-use itertools::Itertools;
+use itertools::{Itertools, chain};
 use prettytable::{Table, row};
 use serde_json::json;
 use std::{
     collections::{BTreeMap, HashMap},
+    fmt::{self, Debug, Display},
     fs::File,
     io::Write,
 };
+use strum::IntoEnumIterator;
+/// Cost breakdown of a single constraint system, see [`ConstraintSystem::stats`].
+#[derive(Clone, Debug, serde::Serialize, Default)]
+pub struct ConstraintSystemStats {
+    pub witnesses: usize,
+    pub fixed: usize,
+    pub reads: usize,
+    pub writes: usize,
+    /// number of zero constraints, by degree
+    pub constraints: BTreeMap<usize, usize>,
+    /// number of lookups, by ROM type
+    pub lookups: BTreeMap<ROMType, usize>,
+    /// totals of every enclosing namespace, by namespace path
+    pub namespaces: BTreeMap<String, NamespaceStats>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, Default)]
+pub struct NamespaceStats {
+    pub witnesses: usize,
+    /// number of zero constraints, by degree
+    pub constraints: BTreeMap<usize, usize>,
+    pub lookups: usize,
+}
+
+/// The enclosing namespaces of a path, outermost first.
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/').map(move |(i, _)| &path[..i])
+}
+
+impl<E: ExtensionField> ConstraintSystem<E> {
+    pub fn stats(&self) -> ConstraintSystemStats {
+        let mut stats = ConstraintSystemStats {
+            witnesses: self.num_witin as usize,
+            fixed: self.num_fixed,
+            reads: self.r_expressions.len(),
+            writes: self.w_expressions.len(),
+            ..Default::default()
+        };
+
+        for path in &self.witin_namespace_map {
+            for ns in ancestors(path) {
+                stats
+                    .namespaces
+                    .entry(ns.to_string())
+                    .or_default()
+                    .witnesses += 1;
+            }
+        }
+
+        for (expr, path) in chain!(
+            self.assert_zero_expressions
+                .iter()
+                .zip_eq(&self.assert_zero_expressions_namespace_map),
+            self.assert_zero_sumcheck_expressions
+                .iter()
+                .zip_eq(&self.assert_zero_sumcheck_expressions_namespace_map),
+        ) {
+            *stats.constraints.entry(expr.degree()).or_default() += 1;
+            for ns in ancestors(path) {
+                let ns_stats = stats.namespaces.entry(ns.to_string()).or_default();
+                *ns_stats.constraints.entry(expr.degree()).or_default() += 1;
+            }
+        }
+
+        // the items map also holds table records, from table circuits which do no lookups
+        for (path, (rom_type, _)) in self
+            .lk_expressions_namespace_map
+            .iter()
+            .zip(&self.lk_expressions_items_map)
+        {
+            *stats.lookups.entry(*rom_type).or_default() += 1;
+            for ns in ancestors(path) {
+                stats.namespaces.entry(ns.to_string()).or_default().lookups += 1;
+            }
+        }
+
+        stats
+    }
+}
+
+impl<E: ExtensionField> ZKVMConstraintSystem<E> {
+    /// The cost model of the emulator profiles: the proving cost of an instruction is measured as
+    /// the number of its witnesses, reads, writes and lookups, which the prover commits to or
+    /// proves per instance of its opcode circuit. The kinds without a circuit cost as much as the
+    /// most costly one.
+    pub fn cost_table(&self) -> CostTable {
+        let costs = InsnKind::iter()
+            .filter_map(|kind| {
+                let name = match kind {
+                    InsnKind::EANY => "ECALL_HALT".to_string(),
+                    kind => format!("{kind:?}"),
+                };
+                let cs = self
+                    .circuit_css
+                    .get(&name)
+                    .or_else(|| self.circuit_css.get(&format!("{kind:?}_DUMMY")))?;
+                let stats = cs.stats();
+                let cost = stats.witnesses
+                    + stats.reads
+                    + stats.writes
+                    + stats.lookups.values().sum::<usize>();
+                Some((kind, cost as u64))
+            })
+            .collect_vec();
+        let mut table = CostTable::new(costs.iter().map(|(_, cost)| *cost).max().unwrap_or(1));
+        for (kind, cost) in costs {
+            table.set_cost(kind, cost);
+        }
+        table
+    }
+}
+
+fn fmt_counts<K: Debug>(counts: &BTreeMap<K, usize>) -> String {
+    format!(
+        "[{}]",
+        counts.iter().map(|(k, v)| format!("{k:?}: {v}")).join(", ")
+    )
+}
+
+impl Display for ConstraintSystemStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "witnesses: {}, fixed: {}, reads: {}, writes: {}",
+            self.witnesses, self.fixed, self.reads, self.writes
+        )?;
+        writeln!(
+            f,
+            "constraints by degree: {}",
+            fmt_counts(&self.constraints)
+        )?;
+        writeln!(f, "lookups: {}", fmt_counts(&self.lookups))?;
+        writeln!(f, "namespaces:")?;
+        for (path, ns) in &self.namespaces {
+            let depth = path.matches('/').count();
+            let name = path.rsplit('/').next().unwrap();
+            writeln!(
+                f,
+                "{:indent$}{name}: witnesses: {}, constraints: {}, lookups: {}",
+                "",
+                ns.witnesses,
+                fmt_counts(&ns.constraints),
+                ns.lookups,
+                indent = 2 * (depth + 1),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, Default)]
 pub struct OpCodeStats {
     namespace: NameSpace,
@@ -226,12 +378,7 @@ impl Report<CircuitStatsTrace> {
         zkvm_witnesses: &ZKVMWitnesses<E>,
         program_name: &str,
     ) -> Self {
-        let num_instances = zkvm_witnesses
-            .clone()
-            .into_iter_sorted()
-            .map(|(key, value)| (key, value.num_instances()))
-            .collect::<BTreeMap<_, _>>();
-        Self::new(static_report, num_instances, program_name)
+        Self::new(static_report, zkvm_witnesses.num_instances(), program_name)
     }
 
     pub fn save_table(&self, filename: &str) {
@@ -277,3 +424,65 @@ impl Report<CircuitStatsTrace> {
         _ = tables_table.print(&mut file);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        expression::ToExpr,
+        instructions::riscv::{DummyExtraConfig, Rv32imConfig},
+        structs::{ROMType, ZKVMConstraintSystem},
+    };
+    use ceno_emul::{CostModel, InsnKind};
+    use goldilocks::GoldilocksExt2 as E;
+
+    #[test]
+    fn test_constraint_system_stats() {
+        let mut cs = ConstraintSystem::<E>::new(|| "circuit");
+        let mut cb = CircuitBuilder::new(&mut cs);
+
+        let a = cb.create_witin(|| "a");
+        cb.namespace(
+            || "gadget",
+            |cb| {
+                let b = cb.create_witin(|| "b");
+                cb.assert_ux::<_, _, 16>(|| "b_u16", b.expr())?;
+                cb.require_zero(|| "a * b", a.expr() * b.expr())?;
+                cb.require_equal(|| "a = b", a.expr(), b.expr())
+            },
+        )
+        .unwrap();
+
+        let stats = cs.stats();
+        assert_eq!(stats.witnesses, 2);
+        assert_eq!(stats.constraints.into_iter().collect::<Vec<_>>(), vec![
+            (1, 1),
+            (2, 1)
+        ]);
+        assert_eq!(stats.lookups[&ROMType::U16], 1);
+        assert_eq!(stats.namespaces["circuit"].witnesses, 2);
+        assert_eq!(stats.namespaces["circuit/gadget"].witnesses, 1);
+        assert_eq!(stats.namespaces["circuit/gadget"].lookups, 1);
+        assert_eq!(
+            stats.namespaces["circuit/gadget/require_zero"].constraints[&2],
+            1
+        );
+    }
+
+    #[test]
+    fn test_cost_table() {
+        let mut cs = ZKVMConstraintSystem::<E>::default();
+        Rv32imConfig::<E>::construct_circuits(&mut cs).unwrap();
+        DummyExtraConfig::<E>::construct_circuits(&mut cs).unwrap();
+
+        let costs = cs.cost_table();
+        let add = costs.cost(InsnKind::ADD);
+        assert_eq!(add, {
+            let stats = cs.get_css()["ADD"].stats();
+            (stats.witnesses + stats.reads + stats.writes + stats.lookups.values().sum::<usize>())
+                as u64
+        });
+        assert!(costs.cost(InsnKind::DIVU) > add);
+        assert!(costs.cost(InsnKind::INVALID) >= costs.cost(InsnKind::DIVU));
+    }
+}
@@ -1,23 +1,32 @@
 use crate::{
-    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    circuit_builder::{CircuitBuilder, ColumnNames, ConstraintSystem},
     error::ZKVMError,
     expression::Expression,
     instructions::Instruction,
+    scheme::constants::{CIRCUIT_VERSION, MIN_BASE_FIELD_BITS},
+    serialization::to_bytes,
     state::StateCircuit,
-    tables::TableCircuit,
-    witness::{LkMultiplicity, RowMajorMatrix},
+    tables::{FixedTraceCache, TableCircuit},
+    utils::field_bits,
+    witness::{RowMajorMatrix, WitnessPool, check_lk_multiplicities, merge_lk_multiplicities},
 };
 use ceno_emul::{CENO_PLATFORM, Platform, StepRecord};
 use ff_ext::ExtensionField;
-use itertools::{Itertools, chain};
+use goldilocks::SmallField;
+use itertools::chain;
 use mpcs::PolynomialCommitmentScheme;
 use multilinear_extensions::{
     mle::DenseMultilinearExtension, virtual_poly_v2::ArcMultilinearExtension,
 };
-use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    collections::{BTreeMap, HashMap, btree_map::Entry},
+    fmt, mem,
+};
 use strum_macros::EnumIter;
 use sumcheck::structs::IOPProverMessage;
+use transcript::{AnyTranscript, TranscriptHash};
 
 pub struct TowerProver;
 
@@ -27,23 +36,46 @@ pub struct TowerProofs<E: ExtensionField> {
     // specs -> layers -> evals
     pub prod_specs_eval: Vec<Vec<Vec<E>>>,
     // specs -> layers -> point
-    #[serde(skip)] // verifier can derive points itself
     pub prod_specs_points: Vec<Vec<Point<E>>>,
     // specs -> layers -> evals
     pub logup_specs_eval: Vec<Vec<Vec<E>>>,
     // specs -> layers -> point
-    #[serde(skip)] // verifier can derive points itself
     pub logup_specs_points: Vec<Vec<Point<E>>>,
 }
 
-pub struct TowerProverSpec<'a, E: ExtensionField> {
-    pub witness: Vec<Vec<ArcMultilinearExtension<'a, E>>>,
+/// The witness of a tower, whose layers are indexed from the output layer to the input layer.
+pub enum TowerProverSpec<'a, E: ExtensionField> {
+    /// Every layer of the tower.
+    Layers(Vec<Vec<ArcMultilinearExtension<'a, E>>>),
+    /// Only the last layer of a product tower, with the number of variables of the tower.
+    /// The other layers are recomputed when proven.
+    StreamedProduct(usize, Vec<ArcMultilinearExtension<'a, E>>),
+    /// Only the input layer `(p, q)` of a logup tower, the numerators being all 1 when `None`.
+    /// The other layers are recomputed when proven.
+    StreamedLogup(
+        Option<Vec<ArcMultilinearExtension<'a, E>>>,
+        Vec<ArcMultilinearExtension<'a, E>>,
+    ),
 }
 
-pub type WitnessId = u16;
+pub type WitnessId = u32;
 pub type ChallengeId = u16;
 
-#[derive(Copy, Clone, Debug, EnumIter, PartialEq, Eq, Hash)]
+/// Challenges drawn from the transcript once all witnesses are committed, in drawing order.
+/// The position of a name is its `ChallengeId`; append new challenges at the end.
+pub const CHALLENGE_NAMES: [&str; 2] = ["chip_record_alpha", "chip_record_beta"];
+pub const NUM_CHALLENGES: usize = CHALLENGE_NAMES.len();
+
+pub fn challenge_id(name: &str) -> Option<ChallengeId> {
+    CHALLENGE_NAMES
+        .iter()
+        .position(|n| *n == name)
+        .map(|id| id as ChallengeId)
+}
+
+#[derive(
+    Copy, Clone, Debug, EnumIter, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 pub enum ROMType {
     U5 = 0,      // 2^5 = 32
     U8,          // 2^8 = 256
@@ -55,9 +87,13 @@ pub enum ROMType {
     Ltu,         // a <(usign) b where a, b are bytes and the result is 0/1.
     Pow,         // a ** b where a is 2 and b is 5-bit value
     Instruction, // Decoded instruction from the fixed program.
+    KeccakRc,    // Keccak round constants, keyed by round
+    KeccakRho,   // Keccak rotation offsets, keyed by lane
+    KeccakChi,   // !a & b where a, b are bytes
+    Bits8,       // 8 booleans, keyed by their packing into a byte
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RAMType {
     GlobalState,
     Register,
@@ -100,7 +136,11 @@ impl<F: Clone> PointAndEval<F> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ProvingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub fixed_traces: Option<Vec<DenseMultilinearExtension<E>>>,
     pub fixed_commit_wd: Option<PCS::CommitmentWithData>,
@@ -113,7 +153,11 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ProvingKey<E, PCS> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct VerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub(crate) cs: ConstraintSystem<E>,
     pub fixed_commit: Option<PCS::Commitment>,
@@ -131,6 +175,10 @@ pub struct ProgramParams {
     pub program_size: usize,
     pub pub_io_len: usize,
     pub static_memory_len: usize,
+    /// Whether the circuits prove a segment of a chunked execution, whose registers and static
+    /// memory start and end as public values, rather than as fixed traces and witnesses. The
+    /// key is then the same for all the segments of a program.
+    pub segmented: bool,
 }
 
 impl Default for ProgramParams {
@@ -140,6 +188,7 @@ impl Default for ProgramParams {
             program_size: (1 << 14),
             pub_io_len: (1 << 2),
             static_memory_len: (1 << 16),
+            segmented: false,
         }
     }
 }
@@ -163,6 +212,16 @@ impl<E: ExtensionField> Default for ZKVMConstraintSystem<E> {
     }
 }
 
+/// Fail with [`ZKVMError::UnsupportedField`] if the base field of `E` is narrower than
+/// `min_bits`, e.g. 31-bit fields for the circuits which combine the limbs of a word.
+fn check_field_bits<E: ExtensionField>(min_bits: u32) -> Result<(), ZKVMError> {
+    let bits = field_bits::<E::BaseField>();
+    if bits < min_bits {
+        return Err(ZKVMError::UnsupportedField(bits));
+    }
+    Ok(())
+}
+
 impl<E: ExtensionField> ZKVMConstraintSystem<E> {
     pub fn new_with_platform(params: ProgramParams) -> Self {
         ZKVMConstraintSystem {
@@ -170,34 +229,69 @@ impl<E: ExtensionField> ZKVMConstraintSystem<E> {
             ..Default::default()
         }
     }
-    pub fn register_opcode_circuit<OC: Instruction<E>>(&mut self) -> OC::InstructionConfig {
+    /// Construct the opcode circuit `OC` and register its constraint system. A circuit which is
+    /// registered twice is an error.
+    pub fn register_opcode_circuit<OC: Instruction<E>>(
+        &mut self,
+    ) -> Result<OC::InstructionConfig, ZKVMError> {
+        if self.circuit_css.contains_key(&OC::name()) {
+            return Err(ZKVMError::DuplicateCircuit(OC::name()));
+        }
+        check_field_bits::<E>(OC::min_field_bits()).map_err(|err| err.in_circuit(&OC::name()))?;
         let mut cs = ConstraintSystem::new(|| format!("riscv_opcode/{}", OC::name()));
         let mut circuit_builder =
             CircuitBuilder::<E>::new_with_params(&mut cs, self.params.clone());
-        let config = OC::construct_circuit(&mut circuit_builder).unwrap();
-        assert!(self.circuit_css.insert(OC::name(), cs).is_none());
+        let config = OC::construct_circuit(&mut circuit_builder)
+            .map_err(|err| err.in_circuit(&OC::name()))?;
+        self.circuit_css.insert(OC::name(), cs);
 
-        config
+        Ok(config)
     }
 
-    pub fn register_table_circuit<TC: TableCircuit<E>>(&mut self) -> TC::TableConfig {
+    /// Construct the table circuit `TC` and register its constraint system, see
+    /// [`Self::register_opcode_circuit`].
+    pub fn register_table_circuit<TC: TableCircuit<E>>(
+        &mut self,
+    ) -> Result<TC::TableConfig, ZKVMError> {
+        if self.circuit_css.contains_key(&TC::name()) {
+            return Err(ZKVMError::DuplicateCircuit(TC::name()));
+        }
+        check_field_bits::<E>(TC::min_field_bits()).map_err(|err| err.in_circuit(&TC::name()))?;
         let mut cs = ConstraintSystem::new(|| format!("riscv_table/{}", TC::name()));
         let mut circuit_builder =
             CircuitBuilder::<E>::new_with_params(&mut cs, self.params.clone());
-        let config = TC::construct_circuit(&mut circuit_builder).unwrap();
-        assert!(self.circuit_css.insert(TC::name(), cs).is_none());
+        let config = TC::construct_circuit(&mut circuit_builder)
+            .map_err(|err| err.in_circuit(&TC::name()))?;
+        // instance columns are only supported in opcode circuits
+        if cs.num_instance_columns != 0 {
+            return Err(ZKVMError::InstanceColumnCount {
+                circuit: TC::name(),
+                expected: 0,
+                got: cs.num_instance_columns,
+            });
+        }
+        self.circuit_css.insert(TC::name(), cs);
 
-        config
+        Ok(config)
     }
 
-    pub fn register_global_state<SC: StateCircuit<E>>(&mut self) {
+    pub fn register_global_state<SC: StateCircuit<E>>(&mut self) -> Result<(), ZKVMError> {
+        // the global state packs the pc and the cycle into field elements
+        check_field_bits::<E>(MIN_BASE_FIELD_BITS)?;
         let mut cs = ConstraintSystem::new(|| "riscv_state");
         let mut circuit_builder =
             CircuitBuilder::<E>::new_with_params(&mut cs, self.params.clone());
-        self.initial_global_state_expr =
-            SC::initial_global_state(&mut circuit_builder).expect("global_state_in failed");
-        self.finalize_global_state_expr =
-            SC::finalize_global_state(&mut circuit_builder).expect("global_state_out failed");
+        self.initial_global_state_expr = SC::initial_global_state(&mut circuit_builder)?;
+        self.finalize_global_state_expr = SC::finalize_global_state(&mut circuit_builder)?;
+        Ok(())
+    }
+
+    /// Column names of every registered circuit, by circuit name.
+    pub fn column_names(&self) -> BTreeMap<String, ColumnNames> {
+        self.circuit_css
+            .iter()
+            .map(|(name, cs)| (name.clone(), cs.column_names()))
+            .collect()
     }
 
     pub fn get_css(&self) -> &BTreeMap<String, ConstraintSystem<E>> {
@@ -209,14 +303,39 @@ impl<E: ExtensionField> ZKVMConstraintSystem<E> {
     }
 }
 
+/// The generation of the fixed traces of a table circuit, to run on any thread, with the cache
+/// of [`ZKVMFixedTraces::register_table_circuits`].
+pub type TableFixedTraces<'a, E> = Box<
+    dyn FnOnce(
+            &FixedTraceCache<<E as ExtensionField>::BaseField>,
+        )
+            -> Result<(String, RowMajorMatrix<<E as ExtensionField>::BaseField>), ZKVMError>
+        + Send
+        + 'a,
+>;
+
 #[derive(Default, Clone)]
 pub struct ZKVMFixedTraces<E: ExtensionField> {
     pub circuit_fixed_traces: BTreeMap<String, Option<RowMajorMatrix<E::BaseField>>>,
+    /// The cache of the fixed traces of the static tables.
+    cache: FixedTraceCache<E::BaseField>,
 }
 
 impl<E: ExtensionField> ZKVMFixedTraces<E> {
-    pub fn register_opcode_circuit<OC: Instruction<E>>(&mut self, _cs: &ZKVMConstraintSystem<E>) {
-        assert!(self.circuit_fixed_traces.insert(OC::name(), None).is_none());
+    /// Fixed traces whose static tables are taken from `cache`, e.g. one kept across the setups
+    /// of a service, or one on disk.
+    pub fn with_cache(cache: FixedTraceCache<E::BaseField>) -> Self {
+        Self {
+            cache,
+            ..Default::default()
+        }
+    }
+
+    pub fn register_opcode_circuit<OC: Instruction<E>>(
+        &mut self,
+        _cs: &ZKVMConstraintSystem<E>,
+    ) -> Result<(), ZKVMError> {
+        self.record_fixed_traces(OC::name(), None)
     }
 
     pub fn register_table_circuit<TC: TableCircuit<E>>(
@@ -224,28 +343,99 @@ impl<E: ExtensionField> ZKVMFixedTraces<E> {
         cs: &ZKVMConstraintSystem<E>,
         config: &TC::TableConfig,
         input: &TC::FixedInput,
-    ) {
-        let cs = cs.get_cs(&TC::name()).expect("cs not found");
-        assert!(
-            self.circuit_fixed_traces
-                .insert(
-                    TC::name(),
-                    Some(TC::generate_fixed_traces(config, cs.num_fixed, input)),
-                )
-                .is_none()
-        );
+    ) -> Result<(), ZKVMError> {
+        let (name, traces) =
+            Self::generate_table_fixed_traces::<TC>(cs, config, input, &self.cache)?;
+        self.record_fixed_traces(name, Some(traces))
+    }
+
+    /// Generate the fixed traces of several table circuits, in parallel with each other as well
+    /// as within each table, then record them.
+    pub fn register_table_circuits(
+        &mut self,
+        generations: Vec<TableFixedTraces<'_, E>>,
+    ) -> Result<(), ZKVMError> {
+        let generated = generations
+            .into_par_iter()
+            .map(|generate| generate(&self.cache))
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        for (name, traces) in generated {
+            self.record_fixed_traces(name, Some(traces))?;
+        }
+        Ok(())
+    }
+
+    /// The fixed traces of the table circuit `TC`, from `cache` if they are static, without
+    /// recording them.
+    pub fn generate_table_fixed_traces<TC: TableCircuit<E>>(
+        cs: &ZKVMConstraintSystem<E>,
+        config: &TC::TableConfig,
+        input: &TC::FixedInput,
+        cache: &FixedTraceCache<E::BaseField>,
+    ) -> Result<(String, RowMajorMatrix<E::BaseField>), ZKVMError> {
+        let cs = cs
+            .get_cs(&TC::name())
+            .ok_or_else(|| ZKVMError::CircuitNotFound(TC::name()))?;
+        let generate = || TC::generate_fixed_traces(config, cs, input);
+        let traces = match TC::static_fixed_len() {
+            Some(len) => cache.get_or_generate(&TC::name(), len, generate),
+            None => generate(),
+        };
+        Ok((TC::name(), traces))
+    }
+
+    fn record_fixed_traces(
+        &mut self,
+        name: String,
+        traces: Option<RowMajorMatrix<E::BaseField>>,
+    ) -> Result<(), ZKVMError> {
+        if self.circuit_fixed_traces.contains_key(&name) {
+            return Err(ZKVMError::DuplicateCircuit(name));
+        }
+        self.circuit_fixed_traces.insert(name, traces);
+        Ok(())
     }
 }
 
+/// The instances of an opcode circuit and their lookup multiplicities, assigned by
+/// [`ZKVMWitnesses::assign_opcode_instances`] but not yet recorded.
+pub struct AssignedOpcode<E: ExtensionField> {
+    name: String,
+    witness: RowMajorMatrix<E::BaseField>,
+    lk_mlt: Vec<HashMap<u64, usize>>,
+}
+
+/// The assignment of the instances of an opcode circuit, to run on any thread, with the witness
+/// pool of [`ZKVMWitnesses::assign_opcode_circuits`].
+pub type OpcodeAssignment<'a, E> = Box<
+    dyn FnOnce(
+            &WitnessPool<<E as ExtensionField>::BaseField>,
+        ) -> Result<AssignedOpcode<E>, ZKVMError>
+        + Send
+        + 'a,
+>;
+
 #[derive(Default, Clone)]
 pub struct ZKVMWitnesses<E: ExtensionField> {
     witnesses_opcodes: BTreeMap<String, RowMajorMatrix<E::BaseField>>,
     witnesses_tables: BTreeMap<String, RowMajorMatrix<E::BaseField>>,
-    lk_mlts: BTreeMap<String, LkMultiplicity>,
+    lk_mlts: BTreeMap<String, Vec<HashMap<u64, usize>>>,
     combined_lk_mlt: Option<Vec<HashMap<u64, usize>>>,
+    instance_columns: BTreeMap<String, Vec<Vec<E::BaseField>>>,
+    /// The pool of the opcode witness matrices.
+    pool: WitnessPool<E::BaseField>,
 }
 
 impl<E: ExtensionField> ZKVMWitnesses<E> {
+    /// Witnesses whose opcode matrices are taken from `pool`, e.g. one kept across the proving
+    /// runs of a service. They return to it once the prover has consumed them.
+    pub fn with_pool(pool: WitnessPool<E::BaseField>) -> Self {
+        Self {
+            pool,
+            ..Default::default()
+        }
+    }
+
     pub fn get_opcode_witness(&self, name: &String) -> Option<RowMajorMatrix<E::BaseField>> {
         self.witnesses_opcodes.get(name).cloned()
     }
@@ -254,52 +444,162 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
         self.witnesses_tables.get(name).cloned()
     }
 
+    /// Assign the instances of an opcode circuit from `records`. It may be called several times
+    /// for the same circuit, e.g. on successive batches of an execution, and the instances of
+    /// each call are appended to the previous ones.
     pub fn assign_opcode_circuit<OC: Instruction<E>>(
         &mut self,
         cs: &ZKVMConstraintSystem<E>,
         config: &OC::InstructionConfig,
         records: Vec<StepRecord>,
+    ) -> Result<(), ZKVMError> {
+        let assigned = Self::assign_opcode_instances::<OC>(cs, config, records, &self.pool)?;
+        self.record_opcode_instances(assigned)
+    }
+
+    /// Assign the instances of several opcode circuits, in parallel with each other as well as
+    /// within each circuit, then record them in the order of `assignments`, so that the witnesses
+    /// do not depend on the scheduling.
+    pub fn assign_opcode_circuits(
+        &mut self,
+        assignments: Vec<OpcodeAssignment<'_, E>>,
+    ) -> Result<(), ZKVMError> {
+        let assigned = assignments
+            .into_par_iter()
+            .map(|assign| assign(&self.pool))
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        for assigned in assigned {
+            self.record_opcode_instances(assigned)?;
+        }
+        Ok(())
+    }
+
+    /// Assign the instances of the opcode circuit `OC` from `records`, in a matrix of `pool`,
+    /// without recording them, see [`Self::record_opcode_instances`].
+    ///
+    /// With the feature `sanity_check`, the instances are checked against the constraints of
+    /// the circuit, see `ConstraintSystem::check_assignment`.
+    pub fn assign_opcode_instances<OC: Instruction<E>>(
+        cs: &ZKVMConstraintSystem<E>,
+        config: &OC::InstructionConfig,
+        records: Vec<StepRecord>,
+        pool: &WitnessPool<E::BaseField>,
+    ) -> Result<AssignedOpcode<E>, ZKVMError> {
+        let cs = cs
+            .get_cs(&OC::name())
+            .ok_or_else(|| ZKVMError::CircuitNotFound(OC::name()))?;
+        let (mut witness, logup_multiplicity) =
+            OC::assign_instances_in(config, cs.num_witin as usize, records, pool)?;
+        cs.assign_derived_witins(&mut witness);
+        #[cfg(feature = "sanity_check")]
+        cs.check_assignment(&OC::name(), &witness)?;
+        Ok(AssignedOpcode {
+            name: OC::name(),
+            witness,
+            lk_mlt: logup_multiplicity.into_finalize_result().into(),
+        })
+    }
+
+    /// Record the instances of an opcode circuit, after those of the previous calls for it.
+    pub fn record_opcode_instances(
+        &mut self,
+        assigned: AssignedOpcode<E>,
     ) -> Result<(), ZKVMError> {
         assert!(self.combined_lk_mlt.is_none());
 
-        let cs = cs.get_cs(&OC::name()).unwrap();
-        let (witness, logup_multiplicity) =
-            OC::assign_instances(config, cs.num_witin as usize, records)?;
-        assert!(self.witnesses_opcodes.insert(OC::name(), witness).is_none());
-        assert!(!self.witnesses_tables.contains_key(&OC::name()));
-        assert!(
-            self.lk_mlts
-                .insert(OC::name(), logup_multiplicity)
-                .is_none()
-        );
+        let AssignedOpcode {
+            name,
+            witness,
+            lk_mlt,
+        } = assigned;
+        if self.witnesses_tables.contains_key(&name) {
+            return Err(ZKVMError::DuplicateCircuit(name));
+        }
+        match self.witnesses_opcodes.entry(name.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(witness);
+                self.lk_mlts.insert(name, lk_mlt);
+            }
+            Entry::Occupied(mut entry) => {
+                let previous = entry.get_mut();
+                previous.append(witness);
+                merge_lk_multiplicities(self.lk_mlts.get_mut(&name).unwrap(), &lk_mlt)?;
+            }
+        }
 
         Ok(())
     }
 
+    /// Set the instance columns of an opcode circuit, one value per instance, after its witness.
+    /// See `ConstraintSystem::create_instance`.
+    pub fn assign_instance_columns<OC: Instruction<E>>(
+        &mut self,
+        cs: &ZKVMConstraintSystem<E>,
+        columns: Vec<Vec<E::BaseField>>,
+    ) -> Result<(), ZKVMError> {
+        let cs = cs
+            .get_cs(&OC::name())
+            .ok_or_else(|| ZKVMError::CircuitNotFound(OC::name()))?;
+        // the witness must be assigned before the instance columns
+        let num_instances = self
+            .witnesses_opcodes
+            .get(&OC::name())
+            .ok_or_else(|| ZKVMError::WitnessNotFound {
+                circuit: OC::name(),
+            })?
+            .num_instances();
+        if self.instance_columns.contains_key(&OC::name()) {
+            return Err(ZKVMError::DuplicateCircuit(OC::name()));
+        }
+        if columns.len() != cs.num_instance_columns {
+            return Err(ZKVMError::InstanceColumnCount {
+                circuit: OC::name(),
+                expected: cs.num_instance_columns,
+                got: columns.len(),
+            });
+        }
+        if let Some((column, values)) = columns
+            .iter()
+            .enumerate()
+            .find(|(_, values)| values.len() != num_instances)
+        {
+            return Err(ZKVMError::InstanceColumnLength {
+                circuit: OC::name(),
+                column,
+                expected: num_instances,
+                got: values.len(),
+            });
+        }
+        self.instance_columns.insert(OC::name(), columns);
+
+        Ok(())
+    }
+
+    pub fn get_instance_columns(&self, name: &String) -> &[Vec<E::BaseField>] {
+        self.instance_columns
+            .get(name)
+            .map_or(&[], |columns| columns)
+    }
+
+    /// Take out the instance columns, by circuit name.
+    pub fn take_instance_columns(&mut self) -> BTreeMap<String, Vec<Vec<E::BaseField>>> {
+        mem::take(&mut self.instance_columns)
+    }
+
     // merge the multiplicities in each opcode circuit into one
-    pub fn finalize_lk_multiplicities(&mut self) {
+    pub fn finalize_lk_multiplicities(&mut self) -> Result<(), ZKVMError> {
         assert!(self.combined_lk_mlt.is_none());
         assert!(!self.lk_mlts.is_empty());
 
-        let mut combined_lk_mlt = vec![];
-        let keys = self.lk_mlts.keys().cloned().collect_vec();
-        for name in keys {
-            let lk_mlt = self.lk_mlts.remove(&name).unwrap().into_finalize_result();
-            if combined_lk_mlt.is_empty() {
-                combined_lk_mlt = lk_mlt.to_vec();
-            } else {
-                combined_lk_mlt
-                    .iter_mut()
-                    .zip_eq(lk_mlt.iter())
-                    .for_each(|(m1, m2)| {
-                        for (key, value) in m2 {
-                            *m1.entry(*key).or_insert(0) += value;
-                        }
-                    });
-            }
+        let mut combined_lk_mlt = vec![HashMap::new(); mem::variant_count::<ROMType>()];
+        for lk_mlt in mem::take(&mut self.lk_mlts).into_values() {
+            merge_lk_multiplicities(&mut combined_lk_mlt, &lk_mlt)?;
         }
+        // multiplicities are committed as base field elements
+        check_lk_multiplicities(&combined_lk_mlt, E::BaseField::MODULUS_U64)?;
 
         self.combined_lk_mlt = Some(combined_lk_mlt);
+        Ok(())
     }
 
     pub fn assign_table_circuit<TC: TableCircuit<E>>(
@@ -310,26 +610,44 @@ impl<E: ExtensionField> ZKVMWitnesses<E> {
     ) -> Result<(), ZKVMError> {
         assert!(self.combined_lk_mlt.is_some());
 
-        let cs = cs.get_cs(&TC::name()).unwrap();
-        let witness = TC::assign_instances(
+        let cs = cs
+            .get_cs(&TC::name())
+            .ok_or_else(|| ZKVMError::CircuitNotFound(TC::name()))?;
+        if self.witnesses_tables.contains_key(&TC::name())
+            || self.witnesses_opcodes.contains_key(&TC::name())
+        {
+            return Err(ZKVMError::DuplicateCircuit(TC::name()));
+        }
+        let mut witness = TC::assign_instances(
             config,
             cs.num_witin as usize,
             self.combined_lk_mlt.as_ref().unwrap(),
             input,
         )?;
-        assert!(self.witnesses_tables.insert(TC::name(), witness).is_none());
-        assert!(!self.witnesses_opcodes.contains_key(&TC::name()));
+        cs.assign_derived_witins(&mut witness);
+        self.witnesses_tables.insert(TC::name(), witness);
 
         Ok(())
     }
 
+    /// The number of instances of every assigned circuit, by name.
+    pub fn num_instances(&self) -> BTreeMap<String, usize> {
+        chain(&self.witnesses_opcodes, &self.witnesses_tables)
+            .map(|(name, witness)| (name.clone(), witness.num_instances()))
+            .collect()
+    }
+
     /// Iterate opcode circuits, then table circuits, sorted by name.
     pub fn into_iter_sorted(self) -> impl Iterator<Item = (String, RowMajorMatrix<E::BaseField>)> {
         chain(self.witnesses_opcodes, self.witnesses_tables)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMProvingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub pp: PCS::ProverParam,
     pub vp: PCS::VerifierParam,
@@ -339,6 +657,10 @@ pub struct ZKVMProvingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>>
     // expression for global state in/out
     pub initial_global_state_expr: Expression<E>,
     pub finalize_global_state_expr: Expression<E>,
+
+    /// The hash function of the Fiat–Shamir transcript.
+    #[serde(default)]
+    pub transcript_hash: TranscriptHash,
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PCS> {
@@ -349,8 +671,14 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PC
             circuit_pks: BTreeMap::new(),
             initial_global_state_expr: Expression::ZERO,
             finalize_global_state_expr: Expression::ZERO,
+            transcript_hash: TranscriptHash::default(),
         }
     }
+
+    /// Create a new transcript over the hash function of this key.
+    pub fn new_transcript(&self, label: &'static [u8]) -> AnyTranscript<E> {
+        self.transcript_hash.new_transcript(label)
+    }
 }
 
 impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PCS> {
@@ -365,11 +693,55 @@ impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMProvingKey<E, PC
             // expression for global state in/out
             initial_global_state_expr: self.initial_global_state_expr.clone(),
             finalize_global_state_expr: self.finalize_global_state_expr.clone(),
+            transcript_hash: self.transcript_hash,
+            version: ProtocolVersion::current(),
         }
     }
 }
 
-#[derive(Clone)]
+/// Version of the prover which produced a proof or a verifying key.
+///
+/// A proof verifies only under a key of the same circuit version, see
+/// `ZKVMError::VersionMismatch`. The crate version is informative, so that releases which leave
+/// the circuits and the protocol unchanged stay compatible.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersion {
+    /// version of the `ceno_zkvm` crate
+    pub crate_version: String,
+    /// see `CIRCUIT_VERSION`
+    pub circuit_version: u32,
+}
+
+impl ProtocolVersion {
+    /// The version of this build.
+    pub fn current() -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            circuit_version: CIRCUIT_VERSION,
+        }
+    }
+
+    /// Whether the proofs of version `self` can be verified under keys of version `other`.
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.circuit_version == other.circuit_version
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ceno_zkvm {} (circuits v{})",
+            self.crate_version, self.circuit_version
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "E::BaseField: Serialize",
+    deserialize = "E: DeserializeOwned, E::BaseField: DeserializeOwned"
+))]
 pub struct ZKVMVerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> {
     pub vp: PCS::VerifierParam,
     // vk for opcode and table circuits
@@ -377,4 +749,31 @@ pub struct ZKVMVerifyingKey<E: ExtensionField, PCS: PolynomialCommitmentScheme<E
     // expression for global state in/out
     pub initial_global_state_expr: Expression<E>,
     pub finalize_global_state_expr: Expression<E>,
+
+    /// The hash function of the Fiat–Shamir transcript.
+    #[serde(default)]
+    pub transcript_hash: TranscriptHash,
+
+    /// The version of the prover the key was generated by.
+    pub version: ProtocolVersion,
+}
+
+/// Blake3 digest of a verifying key, see `ZKVMVerifyingKey::digest`.
+pub type VKDigest = [u8; 32];
+
+impl<E: ExtensionField, PCS: PolynomialCommitmentScheme<E>> ZKVMVerifyingKey<E, PCS> {
+    /// Create a new transcript over the hash function of this key.
+    pub fn new_transcript(&self, label: &'static [u8]) -> AnyTranscript<E> {
+        self.transcript_hash.new_transcript(label)
+    }
+
+    /// The Blake3 digest of the key, as serialized by `serialization::to_bytes`. It covers the
+    /// PCS parameters, the constraints of every circuit, their fixed commitments and the
+    /// transcript hash, so it changes whenever the constraint system does.
+    pub fn digest(&self) -> Result<VKDigest, ZKVMError>
+    where
+        E::BaseField: Serialize,
+    {
+        Ok(*blake3::hash(&to_bytes(self)?).as_bytes())
+    }
 }
@@ -0,0 +1,126 @@
+//! Definition of batched tables: several small ROM tables folded into a single logup table.
+//!
+//! Each row carries its `ROMType` in a table-id column, so a lookup `rom_type(a, b, c)` into
+//! the batched table has the same RLC as a lookup into the standalone table. Registering a
+//! batched table instead of its members saves one tower spec and one proof per member.
+
+mod batched_impl;
+
+mod batched_circuit;
+pub use batched_circuit::{BatchedTableCircuit, BatchedTables, rom_table_content};
+pub use batched_impl::BatchedTableConfig;
+
+use crate::structs::ROMType;
+
+/// U5 range check and the byte-wise logic tables.
+pub struct SmallTables;
+impl BatchedTables for SmallTables {
+    const ROM_TYPES: &'static [ROMType] = &[
+        ROMType::U5,
+        ROMType::And,
+        ROMType::Or,
+        ROMType::Xor,
+        ROMType::Ltu,
+    ];
+
+    fn name() -> String {
+        "SMALL_TABLES".into()
+    }
+}
+pub type SmallTablesCircuit<E> = BatchedTableCircuit<E, SmallTables>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        error::ZKVMError,
+        scheme::utils::eval_by_expr_with_fixed,
+        tables::{AndTable, OpsTable, TableCircuit},
+        witness::LkMultiplicity,
+    };
+    use goldilocks::{GoldilocksExt2 as E, SmallField};
+
+    #[test]
+    fn test_batched_table_rlc_matches_lookup() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = SmallTablesCircuit::<E>::construct_circuit(&mut cb).unwrap();
+        let fixed = SmallTablesCircuit::<E>::generate_fixed_traces(&config, cb.cs, &());
+
+        let challenges = [E::from(7), E::from(1000)];
+        let table_expr = &cb.cs.lk_table_expressions[0].values;
+        let eval_row = |row: usize| {
+            let row = fixed[row]
+                .iter()
+                .map(|v| unsafe { (*v).assume_init() }.into())
+                .collect::<Vec<E>>();
+            eval_by_expr_with_fixed(&row, &[], &challenges, table_expr)
+        };
+
+        // U5 segment comes first, with 32 rows
+        let lookup = cb.rlc_chip_record(vec![(ROMType::U5 as usize).into(), 17.into()]);
+        assert_eq!(
+            eval_row(17),
+            eval_by_expr_with_fixed(&[], &[], &challenges, &lookup)
+        );
+
+        // then the And segment
+        let (a, b) = (0x3c, 0xa5);
+        let lookup = cb.rlc_chip_record(vec![
+            (ROMType::And as usize).into(),
+            a.into(),
+            b.into(),
+            (a & b).into(),
+        ]);
+        assert_eq!(
+            eval_row(32 + AndTable::pack(a as u64, b as u64) as usize),
+            eval_by_expr_with_fixed(&[], &[], &challenges, &lookup)
+        );
+    }
+
+    #[test]
+    fn test_batched_table_multiplicity() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let config = SmallTablesCircuit::<E>::construct_circuit(&mut cb).unwrap();
+
+        let mut lkm = LkMultiplicity::default();
+        lkm.assert_ux::<5>(3);
+        lkm.assert_ux::<5>(3);
+        lkm.lookup_xor_byte(1, 2);
+        let lkm = lkm.into_finalize_result();
+
+        let witness =
+            SmallTablesCircuit::<E>::assign_instances(&config, cb.cs.num_witin as usize, &lkm, &())
+                .unwrap();
+        let mlt = |row: usize| unsafe { witness[row][0].assume_init() }.to_canonical_u64();
+        assert_eq!(mlt(3), 2);
+        // U5, And, Or segments precede Xor
+        assert_eq!(mlt(32 + 2 * (1 << 16) + 1 + (2 << 8)), 1);
+        assert_eq!(mlt(0), 0);
+    }
+
+    #[test]
+    fn test_batched_table_rejects_wide_tables() {
+        struct WithBits8;
+        impl BatchedTables for WithBits8 {
+            const ROM_TYPES: &'static [ROMType] = &[ROMType::U5, ROMType::Bits8];
+            fn name() -> String {
+                "WITH_BITS8".into()
+            }
+        }
+
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        assert!(matches!(
+            BatchedTableCircuit::<E, WithBits8>::construct_circuit(&mut cb),
+            Err(ZKVMError::UnbatchableTable(ROMType::Bits8, _))
+        ));
+        assert!(BatchedTableCircuit::<E, WithBits8>::static_fixed_len().is_none());
+        assert!(matches!(
+            rom_table_content(ROMType::Instruction),
+            Err(ZKVMError::UnbatchableTable(ROMType::Instruction, _))
+        ));
+    }
+}
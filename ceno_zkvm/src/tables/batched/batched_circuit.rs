@@ -0,0 +1,146 @@
+//! Batched tables as circuits with trait TableCircuit.
+
+use super::batched_impl::BatchedTableConfig;
+
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::{
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    error::ZKVMError,
+    structs::ROMType,
+    tables::{
+        AndTable, Bits8Table, KeccakChiTable, KeccakRcTable, KeccakRhoTable, LtuTable, OpsTable,
+        OrTable, PowTable, RangeTable, StructuredTable, TableCircuit, U5Table, U8Table, U14Table,
+        U16Table, XorTable,
+    },
+    witness::RowMajorMatrix,
+};
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+
+/// Use this trait as parameter to BatchedTableCircuit.
+pub trait BatchedTables {
+    /// The ROM types folded into the table, in row order.
+    const ROM_TYPES: &'static [ROMType];
+
+    fn name() -> String;
+}
+
+/// The records of a ROM table as `[a, b, c]`, in lookup key order.
+/// Records with fewer items are padded with zeros.
+///
+/// Fails for the tables a batched table cannot hold: the program table, whose content depends
+/// on the program, and tables of more than three columns.
+pub fn rom_table_content(rom_type: ROMType) -> Result<Vec<[u64; 3]>, ZKVMError> {
+    fn range_content<R: RangeTable>() -> Result<Vec<[u64; 3]>, ZKVMError> {
+        Ok(R::content().into_iter().map(|v| [v, 0, 0]).collect())
+    }
+    fn structured_content<T: StructuredTable>() -> Result<Vec<[u64; 3]>, ZKVMError> {
+        let columns = T::columns();
+        if columns.len() > 3 {
+            return Err(ZKVMError::UnbatchableTable(
+                T::ROM_TYPE,
+                format!("{} columns, at most 3 fit", columns.len()),
+            ));
+        }
+        Ok((0..T::len())
+            .map(|row| {
+                let mut record = [0; 3];
+                for (item, column) in record.iter_mut().zip(&columns) {
+                    *item = (column.generator)(row);
+                }
+                record
+            })
+            .collect())
+    }
+    match rom_type {
+        ROMType::U5 => range_content::<U5Table>(),
+        ROMType::U8 => range_content::<U8Table>(),
+        ROMType::U14 => range_content::<U14Table>(),
+        ROMType::U16 => range_content::<U16Table>(),
+        ROMType::And => Ok(AndTable::content()),
+        ROMType::Or => Ok(OrTable::content()),
+        ROMType::Xor => Ok(XorTable::content()),
+        ROMType::Ltu => Ok(LtuTable::content()),
+        ROMType::Pow => Ok(PowTable::content()),
+        ROMType::Instruction => Err(ZKVMError::UnbatchableTable(
+            rom_type,
+            "its content depends on the program".into(),
+        )),
+        ROMType::KeccakRc => structured_content::<KeccakRcTable>(),
+        ROMType::KeccakRho => structured_content::<KeccakRhoTable>(),
+        ROMType::KeccakChi => Ok(KeccakChiTable::content()),
+        ROMType::Bits8 => structured_content::<Bits8Table>(),
+    }
+}
+
+pub struct BatchedTableCircuit<E, B>(PhantomData<(E, B)>);
+
+impl<E: ExtensionField, B: BatchedTables> BatchedTableCircuit<E, B> {
+    fn content() -> Result<Vec<(ROMType, [u64; 3])>, ZKVMError> {
+        Ok(B::ROM_TYPES
+            .iter()
+            .map(|rom_type| rom_table_content(*rom_type))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .zip(B::ROM_TYPES)
+            .flat_map(|(content, rom_type)| content.into_iter().map(move |abc| (*rom_type, abc)))
+            .collect_vec())
+    }
+
+    fn segments() -> Result<Vec<(ROMType, usize)>, ZKVMError> {
+        B::ROM_TYPES
+            .iter()
+            .map(|rom_type| Ok((*rom_type, rom_table_content(*rom_type)?.len())))
+            .collect()
+    }
+
+    fn len() -> Result<usize, ZKVMError> {
+        Ok(Self::segments()?.iter().map(|(_, len)| len).sum())
+    }
+}
+
+impl<E: ExtensionField, B: BatchedTables> TableCircuit<E> for BatchedTableCircuit<E, B> {
+    type TableConfig = BatchedTableConfig;
+    type FixedInput = ();
+    type WitnessInput = ();
+
+    fn name() -> String {
+        format!("BATCHED_{}", B::name())
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<BatchedTableConfig, ZKVMError> {
+        // fails on the first ROM type the table cannot hold
+        let len = Self::len()?;
+        cb.namespace(
+            || Self::name(),
+            |cb| BatchedTableConfig::construct_circuit(cb, B::ROM_TYPES, len.next_power_of_two()),
+        )
+    }
+
+    fn static_fixed_len() -> Option<usize> {
+        Self::len().ok()
+    }
+
+    fn generate_fixed_traces(
+        config: &BatchedTableConfig,
+        cs: &ConstraintSystem<E>,
+        _input: &(),
+    ) -> RowMajorMatrix<E::BaseField> {
+        let content = Self::content().expect("ROM types are checked by construct_circuit");
+        let mut table = config.generate_fixed_traces(cs.num_fixed, content);
+        table.padding_zero();
+        table
+    }
+
+    fn assign_instances(
+        config: &Self::TableConfig,
+        num_witin: usize,
+        multiplicity: &[HashMap<u64, usize>],
+        _input: &(),
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let mut table = config.assign_instances(num_witin, multiplicity, &Self::segments()?)?;
+        table.padding_zero();
+        Ok(table)
+    }
+}
@@ -0,0 +1,115 @@
+//! The implementation of batched tables. No generics.
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::Itertools;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use std::{collections::HashMap, mem::MaybeUninit};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, Fixed, ToExpr, WitIn},
+    scheme::constants::MIN_PAR_SIZE,
+    set_fixed_val, set_val,
+    structs::ROMType,
+    witness::RowMajorMatrix,
+};
+
+#[derive(Clone, Debug)]
+pub struct BatchedTableConfig {
+    table_id: Fixed,
+    abc: [Fixed; 3],
+    mlt: WitIn,
+}
+
+impl BatchedTableConfig {
+    pub fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        rom_types: &[ROMType],
+        table_len: usize,
+    ) -> Result<Self, ZKVMError> {
+        let table_id = cb.create_fixed(|| "table_id")?;
+        let abc = [
+            cb.create_fixed(|| "a")?,
+            cb.create_fixed(|| "b")?,
+            cb.create_fixed(|| "c")?,
+        ];
+        let mlt = cb.create_witin(|| "mlt");
+
+        // a table with fewer items pads its record with zeros,
+        // which does not change the RLC of the record.
+        let record_exprs = std::iter::once(table_id)
+            .chain(abc)
+            .map(|f| Expression::Fixed(f))
+            .collect_vec();
+
+        cb.lk_batched_table_record(
+            || "record",
+            table_len,
+            rom_types.to_vec(),
+            record_exprs,
+            mlt.expr(),
+        )?;
+
+        Ok(Self { table_id, abc, mlt })
+    }
+
+    pub fn generate_fixed_traces<F: SmallField>(
+        &self,
+        num_fixed: usize,
+        content: Vec<(ROMType, [u64; 3])>,
+    ) -> RowMajorMatrix<F> {
+        let mut fixed = RowMajorMatrix::<F>::new(content.len(), num_fixed);
+
+        fixed
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(content.into_par_iter())
+            .for_each(|(row, (rom_type, abc))| {
+                set_fixed_val!(row, self.table_id, F::from(rom_type as u64));
+                for (col, val) in self.abc.iter().zip(abc.iter()) {
+                    set_fixed_val!(row, *col, F::from(*val));
+                }
+            });
+
+        fixed
+    }
+
+    /// `segments` lists the ROM types in row order with the length of their segment.
+    /// Within a segment, the lookup key of a record is its row offset.
+    pub fn assign_instances<F: SmallField>(
+        &self,
+        num_witin: usize,
+        multiplicity: &[HashMap<u64, usize>],
+        segments: &[(ROMType, usize)],
+    ) -> Result<RowMajorMatrix<F>, ZKVMError> {
+        let length = segments.iter().map(|(_, len)| len).sum();
+        let mut witness = RowMajorMatrix::<F>::new(length, num_witin);
+
+        let mut mlts = vec![0; length];
+        let mut offset = 0;
+        for (rom_type, len) in segments {
+            for (idx, mlt) in &multiplicity[*rom_type as usize] {
+                assert!(
+                    (*idx as usize) < *len,
+                    "{:?} lookup key {} out of table",
+                    rom_type,
+                    idx
+                );
+                mlts[offset + *idx as usize] = *mlt;
+            }
+            offset += len;
+        }
+
+        witness
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(mlts.into_par_iter())
+            .for_each(|(row, mlt)| {
+                set_val!(row, self.mlt, F::from(mlt as u64));
+            });
+
+        Ok(witness)
+    }
+}
@@ -0,0 +1,226 @@
+//! A cache of the fixed traces of the tables which depend on nothing but their type, e.g. the
+//! range tables, see `TableCircuit::static_fixed_len`.
+//!
+//! The traces are kept in memory by a cache shared across setups, and in a directory, if any,
+//! across processes. A file holds the number of instances and of columns of the matrix, then
+//! its values in row order, each as a little-endian `u64`.
+
+use std::{
+    any::type_name,
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    mem::{self, MaybeUninit},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use goldilocks::SmallField;
+use tempfile::NamedTempFile;
+
+use crate::witness::RowMajorMatrix;
+
+const WORD_BYTES: usize = mem::size_of::<u64>();
+
+/// A table by name and length.
+type TableKey = (String, usize);
+
+/// The fixed traces of the static tables by name and length. Clones share the traces.
+pub struct FixedTraceCache<F: SmallField> {
+    traces: Arc<Mutex<HashMap<TableKey, RowMajorMatrix<F>>>>,
+    dir: Option<PathBuf>,
+}
+
+impl<F: SmallField> Default for FixedTraceCache<F> {
+    fn default() -> Self {
+        Self {
+            traces: Arc::default(),
+            dir: None,
+        }
+    }
+}
+
+impl<F: SmallField> Clone for FixedTraceCache<F> {
+    fn clone(&self) -> Self {
+        Self {
+            traces: self.traces.clone(),
+            dir: self.dir.clone(),
+        }
+    }
+}
+
+impl<F: SmallField> FixedTraceCache<F> {
+    /// A cache in memory, and in `dir` too if set.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            ..Self::default()
+        }
+    }
+
+    /// The fixed traces of the table `name` of `len` rows, from the cache, or else from
+    /// `generate` and then cached.
+    ///
+    /// The cache is not locked while generating, so that tables are generated in parallel; a
+    /// table requested twice at once is generated twice, to the same traces.
+    pub fn get_or_generate(
+        &self,
+        name: &str,
+        len: usize,
+        generate: impl FnOnce() -> RowMajorMatrix<F>,
+    ) -> RowMajorMatrix<F> {
+        let key = (name.to_string(), len);
+        if let Some(traces) = self.traces.lock().unwrap().get(&key) {
+            return traces.clone();
+        }
+
+        let traces = self.load(name, len).unwrap_or_else(|| {
+            let traces = generate();
+            if let Err(err) = self.store(name, len, &traces) {
+                tracing::warn!("cannot cache the fixed traces of {name}: {err}");
+            }
+            traces
+        });
+        self.traces.lock().unwrap().insert(key, traces.clone());
+        traces
+    }
+
+    /// The number of traces in memory.
+    pub fn len(&self) -> usize {
+        self.traces.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn path(&self, name: &str, len: usize) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        // a digest of the field, stable across toolchains unlike `DefaultHasher`
+        let field = blake3::hash(type_name::<F>().as_bytes()).to_hex();
+        Some(dir.join(format!("fixed_{name}_{len}_{}.bin", &field[..16])))
+    }
+
+    fn load(&self, name: &str, len: usize) -> Option<RowMajorMatrix<F>> {
+        let path = self.path(name, len)?;
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                tracing::warn!("regenerating fixed traces {}: {err}", path.display());
+                return None;
+            }
+        };
+        let traces = Self::decode(&bytes);
+        match &traces {
+            Some(_) => tracing::debug!("loaded fixed traces from {}", path.display()),
+            None => tracing::warn!("regenerating corrupt fixed traces {}", path.display()),
+        }
+        traces
+    }
+
+    fn store(&self, name: &str, len: usize, traces: &RowMajorMatrix<F>) -> io::Result<()> {
+        let (Some(dir), Some(path)) = (&self.dir, self.path(name, len)) else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)?;
+        // a concurrent reader sees either no file or a whole one
+        let mut file = NamedTempFile::new_in(dir)?;
+        file.write_all(&Self::encode(traces))?;
+        file.persist(&path)?;
+        tracing::debug!("cached fixed traces at {}", path.display());
+        Ok(())
+    }
+
+    fn encode(traces: &RowMajorMatrix<F>) -> Vec<u8> {
+        [traces.num_instances() as u64, traces.num_col() as u64]
+            .into_iter()
+            // the fixed traces are assigned, padding included
+            .chain(
+                traces
+                    .iter_rows()
+                    .flatten()
+                    .map(|v| unsafe { v.assume_init() }.to_canonical_u64()),
+            )
+            .flat_map(u64::to_le_bytes)
+            .collect()
+    }
+
+    fn decode(bytes: &[u8]) -> Option<RowMajorMatrix<F>> {
+        let words = bytes.chunks_exact(WORD_BYTES);
+        if !words.remainder().is_empty() {
+            return None;
+        }
+        let mut words = words.map(|word| u64::from_le_bytes(word.try_into().unwrap()));
+        let num_instances = words.next()? as usize;
+        let num_col = words.next()? as usize;
+        if num_col == 0 {
+            return None;
+        }
+
+        let mut traces = RowMajorMatrix::<F>::new(num_instances, num_col);
+        let num_rows = num_instances + traces.num_padding_instances();
+        if words.len() != num_rows * num_col {
+            return None;
+        }
+        for (cell, word) in traces.iter_mut().flatten().zip(words) {
+            *cell = MaybeUninit::new(F::from(word));
+        }
+        Some(traces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goldilocks::Goldilocks;
+
+    use super::*;
+
+    fn traces(num_instances: usize) -> RowMajorMatrix<Goldilocks> {
+        let mut traces = RowMajorMatrix::new(num_instances, 2);
+        for (i, row) in traces.iter_mut().enumerate() {
+            row[0] = MaybeUninit::new(Goldilocks::from(i as u64));
+            row[1] = MaybeUninit::new(Goldilocks::from(u64::MAX - i as u64));
+        }
+        traces
+    }
+
+    #[test]
+    fn test_fixed_trace_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FixedTraceCache::new(Some(dir.path().to_path_buf()));
+
+        let generated = cache.get_or_generate("TABLE", 5, || traces(5));
+        assert_eq!(cache.len(), 1);
+        let cached = cache.get_or_generate("TABLE", 5, || unreachable!("cached in memory"));
+        assert_eq!(
+            cached.de_interleaving(),
+            generated.clone().de_interleaving()
+        );
+
+        // another process finds the traces on disk
+        let cache = FixedTraceCache::new(Some(dir.path().to_path_buf()));
+        let loaded = cache.get_or_generate("TABLE", 5, || unreachable!("cached on disk"));
+        assert_eq!(loaded.num_instances(), 5);
+        assert_eq!(loaded.de_interleaving(), generated.de_interleaving());
+
+        // another length is another table
+        let other = cache.get_or_generate("TABLE", 3, || traces(3));
+        assert_eq!(other.num_instances(), 3);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_fixed_trace_cache_corrupt() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FixedTraceCache::<Goldilocks>::new(Some(dir.path().to_path_buf()));
+        cache.get_or_generate("TABLE", 5, || traces(5));
+        let path = cache.path("TABLE", 5).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - WORD_BYTES]).unwrap();
+
+        let cache = FixedTraceCache::<Goldilocks>::new(Some(dir.path().to_path_buf()));
+        let regenerated = cache.get_or_generate("TABLE", 5, || traces(5));
+        assert_eq!(regenerated.de_interleaving(), traces(5).de_interleaving());
+    }
+}
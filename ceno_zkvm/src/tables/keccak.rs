@@ -0,0 +1,142 @@
+//! Fixed tables used by the Keccak-f[1600] permutation.
+//!
+//! 64-bit lanes are handled as two 32-bit limbs, as they do not fit in the base field.
+
+use super::{ColumnSpec, OpsTable, OpsTableCircuit, StructuredTable, StructuredTableCircuit};
+use crate::structs::ROMType;
+pub use ceno_emul::{KECCAK_RHO_OFFSETS, KECCAK_ROUND_CONSTANTS, KECCAK_ROUNDS};
+
+/// Rows `[round, rc_lo, rc_hi]`, keyed by round.
+pub struct KeccakRcTable;
+impl StructuredTable for KeccakRcTable {
+    const ROM_TYPE: ROMType = ROMType::KeccakRc;
+    fn len() -> usize {
+        KECCAK_ROUNDS
+    }
+
+    fn columns() -> Vec<ColumnSpec> {
+        vec![
+            ColumnSpec {
+                name: "round",
+                generator: |round| round as u64,
+            },
+            ColumnSpec {
+                name: "rc_lo",
+                generator: |round| KECCAK_ROUND_CONSTANTS[round] & 0xffff_ffff,
+            },
+            ColumnSpec {
+                name: "rc_hi",
+                generator: |round| KECCAK_ROUND_CONSTANTS[round] >> 32,
+            },
+        ]
+    }
+}
+pub type KeccakRcTableCircuit<E> = StructuredTableCircuit<E, KeccakRcTable>;
+
+/// Rows `[lane, offset]`, keyed by lane.
+pub struct KeccakRhoTable;
+impl StructuredTable for KeccakRhoTable {
+    const ROM_TYPE: ROMType = ROMType::KeccakRho;
+    fn len() -> usize {
+        KECCAK_RHO_OFFSETS.len()
+    }
+
+    fn columns() -> Vec<ColumnSpec> {
+        vec![
+            ColumnSpec {
+                name: "lane",
+                generator: |lane| lane as u64,
+            },
+            ColumnSpec {
+                name: "offset",
+                generator: |lane| KECCAK_RHO_OFFSETS[lane],
+            },
+        ]
+    }
+}
+pub type KeccakRhoTableCircuit<E> = StructuredTableCircuit<E, KeccakRhoTable>;
+
+/// `!a & b` where a, b are bytes, the non-linear part of the chi step.
+/// The remaining xor goes through the XorTable.
+pub struct KeccakChiTable;
+impl OpsTable for KeccakChiTable {
+    const ROM_TYPE: ROMType = ROMType::KeccakChi;
+    fn len() -> usize {
+        1 << 16
+    }
+
+    fn content() -> Vec<[u64; 3]> {
+        (0..Self::len() as u64)
+            .map(|i| {
+                let (a, b) = Self::unpack(i);
+                [a, b, !a & b & 0xff]
+            })
+            .collect()
+    }
+}
+pub type KeccakChiTableCircuit<E> = OpsTableCircuit<E, KeccakChiTable>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        tables::TableCircuit,
+    };
+    use goldilocks::{GoldilocksExt2 as E, SmallField};
+
+    #[test]
+    fn test_keccak_round_constants() {
+        // the LFSR of FIPS 202, section 3.2.5
+        let rc_bit = |t: usize| {
+            let mut r = 1u16;
+            for _ in 0..t % 255 {
+                r <<= 1;
+                if r & 0x100 != 0 {
+                    r ^= 0x171;
+                }
+            }
+            (r & 1) as u64
+        };
+        for (round, rc) in KECCAK_ROUND_CONSTANTS.iter().enumerate() {
+            let expected = (0..7).fold(0, |acc, j| acc | (rc_bit(j + 7 * round) << ((1 << j) - 1)));
+            assert_eq!(*rc, expected, "round {round}");
+        }
+    }
+
+    #[test]
+    fn test_keccak_rho_offsets() {
+        let (mut x, mut y) = (1, 0);
+        for t in 0..24 {
+            assert_eq!(KECCAK_RHO_OFFSETS[x + 5 * y], ((t + 1) * (t + 2) / 2) % 64);
+            (x, y) = (y, (2 * x + 3 * y) % 5);
+        }
+        assert_eq!(KECCAK_RHO_OFFSETS[0], 0);
+    }
+
+    #[test]
+    fn test_keccak_rc_table_fixed() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+
+        let config = KeccakRcTableCircuit::<E>::construct_circuit(&mut cb).unwrap();
+        let (lo, hi) = (
+            config.column("rc_lo").unwrap(),
+            config.column("rc_hi").unwrap(),
+        );
+        let fixed = KeccakRcTableCircuit::<E>::generate_fixed_traces(&config, cb.cs, &());
+
+        for (row, rc) in fixed.iter_rows().zip(KECCAK_ROUND_CONSTANTS) {
+            let lo = unsafe { row[lo.0].assume_init() }.to_canonical_u64();
+            let hi = unsafe { row[hi.0].assume_init() }.to_canonical_u64();
+            assert_eq!(lo | (hi << 32), rc);
+        }
+    }
+
+    #[test]
+    fn test_keccak_chi_table() {
+        for [a, b, c] in KeccakChiTable::content() {
+            assert_eq!(c, (!(a as u8) & b as u8) as u64);
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use crate::{
-    circuit_builder::CircuitBuilder, error::ZKVMError, scheme::constants::MIN_PAR_SIZE,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    error::ZKVMError,
+    expression::Fixed,
+    scheme::constants::MIN_BASE_FIELD_BITS,
     witness::RowMajorMatrix,
 };
-use ff::Field;
 use ff_ext::ExtensionField;
-use multilinear_extensions::util::max_usable_threads;
-use rayon::iter::{IndexedParallelIterator, ParallelIterator};
-use std::{collections::HashMap, mem::MaybeUninit};
+use std::collections::HashMap;
 mod range;
 pub use range::*;
 
@@ -19,6 +19,18 @@ pub use program::{InsnRecord, ProgramTableCircuit};
 mod ram;
 pub use ram::*;
 
+mod structured;
+pub use structured::*;
+
+mod batched;
+pub use batched::*;
+
+mod keccak;
+pub use keccak::*;
+
+mod cache;
+pub use cache::FixedTraceCache;
+
 pub trait TableCircuit<E: ExtensionField> {
     type TableConfig: Send + Sync;
     type FixedInput: Send + Sync + ?Sized;
@@ -26,13 +38,32 @@ pub trait TableCircuit<E: ExtensionField> {
 
     fn name() -> String;
 
+    /// The bit width the base field must reach for the circuit to be sound, e.g. to hold the
+    /// values of the table.
+    fn min_field_bits() -> u32 {
+        MIN_BASE_FIELD_BITS
+    }
+
     fn construct_circuit(
         circuit_builder: &mut CircuitBuilder<E>,
     ) -> Result<Self::TableConfig, ZKVMError>;
 
+    /// Named fixed columns exposed by the table, in record order.
+    fn fixed_columns(_config: &Self::TableConfig) -> Vec<(String, Fixed)> {
+        vec![]
+    }
+
+    /// The number of rows of the fixed traces, if they depend on nothing but the table type,
+    /// so that a [`FixedTraceCache`] keeps them. `None` if they depend on the fixed input or on
+    /// the parameters of the program.
+    fn static_fixed_len() -> Option<usize> {
+        None
+    }
+
+    /// The fixed traces of the table, with `cs.num_fixed` columns, including padding.
     fn generate_fixed_traces(
         config: &Self::TableConfig,
-        num_fixed: usize,
+        cs: &ConstraintSystem<E>,
         input: &Self::FixedInput,
     ) -> RowMajorMatrix<E::BaseField>;
 
@@ -42,29 +73,4 @@ pub trait TableCircuit<E: ExtensionField> {
         multiplicity: &[HashMap<u64, usize>],
         input: &Self::WitnessInput,
     ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError>;
-
-    fn padding_zero(
-        table: &mut RowMajorMatrix<E::BaseField>,
-        num_witin: usize,
-    ) -> Result<(), ZKVMError> {
-        // Fill the padding with zeros, if any.
-        let num_padding_instances = table.num_padding_instances();
-        if num_padding_instances > 0 {
-            let nthreads = max_usable_threads();
-            let padding_instance = vec![MaybeUninit::new(E::BaseField::ZERO); num_witin];
-            let num_padding_instance_per_batch = if num_padding_instances > 256 {
-                num_padding_instances.div_ceil(nthreads)
-            } else {
-                num_padding_instances
-            };
-            table
-                .par_batch_iter_padding_mut(num_padding_instance_per_batch)
-                .with_min_len(MIN_PAR_SIZE)
-                .for_each(|row| {
-                    row.chunks_mut(num_witin)
-                        .for_each(|instance| instance.copy_from_slice(padding_instance.as_slice()));
-                });
-        }
-        Ok(())
-    }
 }
@@ -5,7 +5,11 @@ use super::ops_impl::OpTableConfig;
 use std::{collections::HashMap, marker::PhantomData};
 
 use crate::{
-    circuit_builder::CircuitBuilder, error::ZKVMError, structs::ROMType, tables::TableCircuit,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    error::ZKVMError,
+    structs::ROMType,
+    tables::TableCircuit,
+    utils::value_field_bits,
     witness::RowMajorMatrix,
 };
 use ff_ext::ExtensionField;
@@ -39,6 +43,16 @@ impl<E: ExtensionField, OP: OpsTable> TableCircuit<E> for OpsTableCircuit<E, OP>
         format!("OPS_{:?}", OP::ROM_TYPE)
     }
 
+    fn min_field_bits() -> u32 {
+        value_field_bits(
+            OP::content()
+                .into_iter()
+                .flatten()
+                .max()
+                .unwrap_or_default(),
+        )
+    }
+
     fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<OpTableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
@@ -46,13 +60,17 @@ impl<E: ExtensionField, OP: OpsTable> TableCircuit<E> for OpsTableCircuit<E, OP>
         )
     }
 
+    fn static_fixed_len() -> Option<usize> {
+        Some(OP::len())
+    }
+
     fn generate_fixed_traces(
         config: &OpTableConfig,
-        num_fixed: usize,
+        cs: &ConstraintSystem<E>,
         _input: &(),
     ) -> RowMajorMatrix<E::BaseField> {
-        let mut table = config.generate_fixed_traces(num_fixed, OP::content());
-        Self::padding_zero(&mut table, num_fixed).expect("padding error");
+        let mut table = config.generate_fixed_traces(cs.num_fixed, OP::content());
+        table.padding_zero();
         table
     }
 
@@ -64,7 +82,7 @@ impl<E: ExtensionField, OP: OpsTable> TableCircuit<E> for OpsTableCircuit<E, OP>
     ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
         let multiplicity = &multiplicity[OP::ROM_TYPE as usize];
         let mut table = config.assign_instances(num_witin, multiplicity, OP::len())?;
-        Self::padding_zero(&mut table, num_witin)?;
+        table.padding_zero();
         Ok(table)
     }
 }
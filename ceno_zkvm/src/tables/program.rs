@@ -1,7 +1,7 @@
 use std::{collections::HashMap, marker::PhantomData, mem::MaybeUninit};
 
 use crate::{
-    circuit_builder::CircuitBuilder,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
     error::ZKVMError,
     expression::{Expression, Fixed, ToExpr, WitIn},
     scheme::constants::MIN_PAR_SIZE,
@@ -151,14 +151,14 @@ impl<E: ExtensionField> TableCircuit<E> for ProgramTableCircuit<E> {
 
     fn generate_fixed_traces(
         config: &ProgramTableConfig,
-        num_fixed: usize,
+        cs: &ConstraintSystem<E>,
         program: &Self::FixedInput,
     ) -> RowMajorMatrix<E::BaseField> {
         let num_instructions = program.instructions.len();
         let pc_base = program.base_address;
         assert!(num_instructions <= config.program_size);
 
-        let mut fixed = RowMajorMatrix::<E::BaseField>::new(config.program_size, num_fixed);
+        let mut fixed = RowMajorMatrix::<E::BaseField>::new(config.program_size, cs.num_fixed);
 
         fixed
             .par_iter_mut()
@@ -278,8 +278,7 @@ mod tests {
             }
         };
 
-        let fixed =
-            ProgramTableCircuit::<E>::generate_fixed_traces(&config, cb.cs.num_fixed, &program);
+        let fixed = ProgramTableCircuit::<E>::generate_fixed_traces(&config, cb.cs, &program);
         check(&fixed);
 
         let lkm = LkMultiplicity::default().into_finalize_result();
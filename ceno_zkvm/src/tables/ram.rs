@@ -2,7 +2,7 @@ use ceno_emul::{Addr, VMState};
 use ram_circuit::{DynVolatileRamCircuit, NonVolatileRamCircuit, PubIORamCircuit};
 
 use crate::{
-    instructions::riscv::constants::UINT_LIMBS,
+    instructions::riscv::constants::{MEM_STATE_IDX, REG_STATE_IDX, UINT_LIMBS},
     structs::{ProgramParams, RAMType},
 };
 
@@ -62,6 +62,7 @@ impl NonVolatileTable for RegTable {
     const RAM_TYPE: RAMType = RAMType::Register;
     const V_LIMBS: usize = UINT_LIMBS; // See `RegisterExpr`.
     const WRITABLE: bool = true;
+    const STATE_IDX: Option<usize> = Some(REG_STATE_IDX);
 
     fn name() -> &'static str {
         "RegTable"
@@ -81,6 +82,7 @@ impl NonVolatileTable for StaticMemTable {
     const RAM_TYPE: RAMType = RAMType::Memory;
     const V_LIMBS: usize = 1; // See `MemoryExpr`.
     const WRITABLE: bool = true;
+    const STATE_IDX: Option<usize> = Some(MEM_STATE_IDX);
 
     fn len(params: &ProgramParams) -> usize {
         params.static_memory_len
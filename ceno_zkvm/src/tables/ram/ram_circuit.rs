@@ -4,7 +4,7 @@ use ceno_emul::{Addr, Cycle, GetAddr, WORD_SIZE, Word};
 use ff_ext::ExtensionField;
 
 use crate::{
-    circuit_builder::CircuitBuilder,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
     error::ZKVMError,
     structs::{ProgramParams, RAMType},
     tables::TableCircuit,
@@ -49,6 +49,10 @@ pub trait NonVolatileTable {
     const V_LIMBS: usize;
     const WRITABLE: bool;
 
+    /// The index of the public values which hold the initial, then the final content of the table
+    /// in a segment, if `ProgramParams::segmented`, so that consecutive segments can be matched.
+    const STATE_IDX: Option<usize> = None;
+
     fn name() -> &'static str;
 
     /// Maximum number of words in the table.
@@ -60,6 +64,8 @@ pub trait NonVolatileTable {
 /// - with fixed initial content,
 /// - with witnessed final content that the program wrote, if WRITABLE,
 /// - or final content equal to initial content, if not WRITABLE.
+///
+/// In a segment, the initial and final content of a table with a `STATE_IDX` are public values.
 pub struct NonVolatileRamCircuit<E, R>(PhantomData<(E, R)>);
 
 impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCircuit<E>
@@ -82,11 +88,11 @@ impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCirc
 
     fn generate_fixed_traces(
         config: &Self::TableConfig,
-        num_fixed: usize,
+        cs: &ConstraintSystem<E>,
         init_v: &Self::FixedInput,
     ) -> RowMajorMatrix<E::BaseField> {
         // assume returned table is well-formed include padding
-        config.gen_init_state(num_fixed, init_v)
+        config.gen_init_state(cs.num_fixed, init_v)
     }
 
     fn assign_instances(
@@ -129,11 +135,11 @@ impl<E: ExtensionField, NVRAM: NonVolatileTable + Send + Sync + Clone> TableCirc
 
     fn generate_fixed_traces(
         config: &Self::TableConfig,
-        num_fixed: usize,
+        cs: &ConstraintSystem<E>,
         io_addrs: &[Addr],
     ) -> RowMajorMatrix<E::BaseField> {
         // assume returned table is well-formed including padding
-        config.gen_init_state(num_fixed, io_addrs)
+        config.gen_init_state(cs.num_fixed, io_addrs)
     }
 
     fn assign_instances(
@@ -201,7 +207,7 @@ impl<E: ExtensionField, DVRAM: DynVolatileRamTable + Send + Sync + Clone> TableC
 
     fn generate_fixed_traces(
         _config: &Self::TableConfig,
-        _num_fixed: usize,
+        _cs: &ConstraintSystem<E>,
         _init_v: &Self::FixedInput,
     ) -> RowMajorMatrix<E::BaseField> {
         RowMajorMatrix::<E::BaseField>::new(0, 0)
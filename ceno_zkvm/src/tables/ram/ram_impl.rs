@@ -23,6 +23,7 @@ use super::{
 };
 
 /// define a non-volatile memory with init value
+/// in a segment, init value and final value set by instance instead of `init_v` and `final_v`
 #[derive(Clone, Debug)]
 pub struct NonVolatileTableConfig<NVRAM: NonVolatileTable + Send + Sync + Clone> {
     init_v: Vec<Fixed>,
@@ -39,13 +40,21 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
     pub fn construct_circuit<E: ExtensionField>(
         cb: &mut CircuitBuilder<E>,
     ) -> Result<Self, ZKVMError> {
-        let init_v = (0..NVRAM::V_LIMBS)
-            .map(|i| cb.create_fixed(|| format!("init_v_limb_{i}")))
-            .collect::<Result<Vec<Fixed>, ZKVMError>>()?;
+        let state = match NVRAM::STATE_IDX {
+            Some(idx) if cb.params.segmented => Some(cb.query_segment_state(idx, NVRAM::V_LIMBS)?),
+            _ => None,
+        };
+        let init_v = if state.is_some() {
+            vec![]
+        } else {
+            (0..NVRAM::V_LIMBS)
+                .map(|i| cb.create_fixed(|| format!("init_v_limb_{i}")))
+                .collect::<Result<Vec<Fixed>, ZKVMError>>()?
+        };
         let addr = cb.create_fixed(|| "addr")?;
 
         let final_cycle = cb.create_witin(|| "final_cycle");
-        let final_v = if NVRAM::WRITABLE {
+        let final_v = if NVRAM::WRITABLE && state.is_none() {
             Some(
                 (0..NVRAM::V_LIMBS)
                     .map(|i| cb.create_witin(|| format!("final_v_limb_{i}")))
@@ -55,10 +64,25 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             None
         };
 
+        let (init_expr, final_expr) = match &state {
+            Some([init, end]) => (
+                init.iter().map(|v| v.expr()).collect_vec(),
+                end.iter().map(|v| v.expr()).collect_vec(),
+            ),
+            None => {
+                let init_expr = init_v.iter().map(|v| v.expr()).collect_vec();
+                let final_expr = final_v
+                    .as_ref()
+                    .map(|v_limb| v_limb.iter().map(|v| v.expr()).collect_vec())
+                    .unwrap_or_else(|| init_expr.clone());
+                (init_expr, final_expr)
+            }
+        };
+
         let init_table = [
             vec![(NVRAM::RAM_TYPE as usize).into()],
             vec![Expression::Fixed(addr)],
-            init_v.iter().map(|v| v.expr()).collect_vec(),
+            init_expr,
             vec![Expression::ZERO], // Initial cycle.
         ]
         .concat();
@@ -67,10 +91,7 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             // a v t
             vec![(NVRAM::RAM_TYPE as usize).into()],
             vec![Expression::Fixed(addr)],
-            final_v
-                .as_ref()
-                .map(|v_limb| v_limb.iter().map(|v| v.expr()).collect_vec())
-                .unwrap_or_else(|| init_v.iter().map(|v| v.expr()).collect_vec()),
+            final_expr,
             vec![final_cycle.expr()],
         ]
         .concat();
@@ -124,6 +145,7 @@ impl<NVRAM: NonVolatileTable + Send + Sync + Clone> NonVolatileTableConfig<NVRAM
             .with_min_len(MIN_PAR_SIZE)
             .zip_eq(init_mem.into_par_iter())
             .for_each(|(row, rec)| {
+                // in a segment, the initial values are public values instead
                 if self.init_v.len() == 1 {
                     // Assign value directly.
                     set_fixed_val!(row, self.init_v[0], (rec.value as u64).into());
@@ -332,7 +354,7 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
             DVRAM::RAM_TYPE,
             SetTableSpec {
                 addr_type: SetTableAddrType::DynamicAddr(DynamicAddr {
-                    addr_witin_id: addr.id.into(),
+                    addr_witin_id: addr.id as usize,
                     offset: DVRAM::offset_addr(&cb.params),
                 }),
                 len: DVRAM::max_len(&cb.params),
@@ -344,7 +366,7 @@ impl<DVRAM: DynVolatileRamTable + Send + Sync + Clone> DynVolatileRamTableConfig
             DVRAM::RAM_TYPE,
             SetTableSpec {
                 addr_type: SetTableAddrType::DynamicAddr(DynamicAddr {
-                    addr_witin_id: addr.id.into(),
+                    addr_witin_id: addr.id as usize,
                     offset: DVRAM::offset_addr(&cb.params),
                 }),
                 len: DVRAM::max_len(&cb.params),
@@ -5,6 +5,7 @@ mod range_impl;
 mod range_circuit;
 pub use range_circuit::{RangeTable, RangeTableCircuit};
 
+use super::{ColumnSpec, StructuredTable, StructuredTableCircuit};
 use crate::structs::ROMType;
 
 pub struct U5Table;
@@ -42,3 +43,128 @@ impl RangeTable for U16Table {
     }
 }
 pub type U16TableCircuit<E> = RangeTableCircuit<E, U16Table>;
+
+pub const BITS8_WIDTH: usize = 8;
+
+fn bit<const I: usize>(row: usize) -> u64 {
+    ((row >> I) & 1) as u64
+}
+
+/// Rows `[b_0, ..., b_7]` of every vector of 8 booleans, keyed by `sum b_i * 2^i`.
+/// A single lookup checks 8 bits at once. Range checking the packed sum instead would not
+/// be sound, as it does not constrain each term to be a bit.
+pub struct Bits8Table;
+impl StructuredTable for Bits8Table {
+    const ROM_TYPE: ROMType = ROMType::Bits8;
+    fn len() -> usize {
+        1 << BITS8_WIDTH
+    }
+
+    fn columns() -> Vec<ColumnSpec> {
+        let names = ["b0", "b1", "b2", "b3", "b4", "b5", "b6", "b7"];
+        let generators: [fn(usize) -> u64; BITS8_WIDTH] = [
+            bit::<0>, bit::<1>, bit::<2>, bit::<3>, bit::<4>, bit::<5>, bit::<6>, bit::<7>,
+        ];
+        names
+            .into_iter()
+            .zip(generators)
+            .map(|(name, generator)| ColumnSpec { name, generator })
+            .collect()
+    }
+}
+pub type Bits8TableCircuit<E> = StructuredTableCircuit<E, Bits8Table>;
+
+/// Range tables by width.
+const RANGE_TABLES: [(usize, ROMType); 4] = [
+    (5, ROMType::U5),
+    (8, ROMType::U8),
+    (14, ROMType::U14),
+    (16, ROMType::U16),
+];
+
+/// Lookups `(table, shift)` asserting that a value fits in `bits <= 16` bits, each meaning that
+/// `value << shift` is in the table. A width without a table of its own is checked in the next
+/// wider table twice: as is, then shifted to the top of the table.
+pub fn range_lookups(bits: usize) -> Vec<(ROMType, usize)> {
+    assert!(0 < bits && bits <= 16, "no range table for {bits} bits");
+    let (width, rom_type) = *RANGE_TABLES
+        .iter()
+        .find(|(width, _)| *width >= bits)
+        .unwrap();
+    if width == bits {
+        vec![(rom_type, 0)]
+    } else {
+        vec![(rom_type, 0), (rom_type, width - bits)]
+    }
+}
+
+/// Split a `bits` wide value into limbs of at most 16 bits, least significant first, that take
+/// the fewest lookups in total with `range_lookups`; ties go to the fewest limbs.
+pub fn range_limbs(bits: usize) -> Vec<usize> {
+    assert!(bits > 0);
+    // best[w] = ((lookups, limbs), width of the top limb) for a w bits wide value
+    let mut best: Vec<((usize, usize), usize)> = vec![((0, 0), 0)];
+    for w in 1..=bits {
+        let candidate = (1..=w.min(16))
+            .rev()
+            .map(|limb| {
+                let ((lookups, limbs), _) = best[w - limb];
+                ((lookups + range_lookups(limb).len(), limbs + 1), limb)
+            })
+            .min_by_key(|(cost, _)| *cost)
+            .unwrap();
+        best.push(candidate);
+    }
+
+    let mut limbs = vec![];
+    let mut w = bits;
+    while w > 0 {
+        let limb = best[w].1;
+        limbs.push(limb);
+        w -= limb;
+    }
+    limbs.reverse();
+    limbs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits8_table() {
+        let columns = Bits8Table::columns();
+        for row in 0..Bits8Table::len() {
+            let packed = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| (column.generator)(row) << i)
+                .sum::<u64>();
+            assert_eq!(packed, row as u64);
+        }
+    }
+
+    #[test]
+    fn test_range_lookups() {
+        assert_eq!(range_lookups(8), vec![(ROMType::U8, 0)]);
+        assert_eq!(range_lookups(12), vec![
+            (ROMType::U14, 0),
+            (ROMType::U14, 2)
+        ]);
+        assert_eq!(range_lookups(1), vec![(ROMType::U5, 0), (ROMType::U5, 4)]);
+    }
+
+    #[test]
+    fn test_range_limbs() {
+        let lookups =
+            |limbs: &[usize]| limbs.iter().map(|l| range_lookups(*l).len()).sum::<usize>();
+        assert_eq!(range_limbs(16), vec![16]);
+        assert_eq!(range_limbs(12), vec![12]);
+        assert_eq!(range_limbs(32), vec![16, 16]);
+        for (bits, expected) in [(20, 3), (21, 2), (30, 2), (63, 5)] {
+            let limbs = range_limbs(bits);
+            assert_eq!(limbs.iter().sum::<usize>(), bits);
+            assert_eq!(lookups(&limbs), expected, "{bits} bits");
+        }
+    }
+}
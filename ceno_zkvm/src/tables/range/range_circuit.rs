@@ -5,7 +5,11 @@ use super::range_impl::RangeTableConfig;
 use std::{collections::HashMap, marker::PhantomData};
 
 use crate::{
-    circuit_builder::CircuitBuilder, error::ZKVMError, structs::ROMType, tables::TableCircuit,
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    error::ZKVMError,
+    structs::ROMType,
+    tables::TableCircuit,
+    utils::value_field_bits,
     witness::RowMajorMatrix,
 };
 use ff_ext::ExtensionField;
@@ -32,6 +36,10 @@ impl<E: ExtensionField, RANGE: RangeTable> TableCircuit<E> for RangeTableCircuit
         format!("RANGE_{:?}", RANGE::ROM_TYPE)
     }
 
+    fn min_field_bits() -> u32 {
+        value_field_bits(RANGE::content().into_iter().max().unwrap_or_default())
+    }
+
     fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<RangeTableConfig, ZKVMError> {
         cb.namespace(
             || Self::name(),
@@ -39,13 +47,17 @@ impl<E: ExtensionField, RANGE: RangeTable> TableCircuit<E> for RangeTableCircuit
         )
     }
 
+    fn static_fixed_len() -> Option<usize> {
+        Some(RANGE::len())
+    }
+
     fn generate_fixed_traces(
         config: &RangeTableConfig,
-        num_fixed: usize,
+        cs: &ConstraintSystem<E>,
         _input: &(),
     ) -> RowMajorMatrix<E::BaseField> {
-        let mut table = config.generate_fixed_traces(num_fixed, RANGE::content());
-        Self::padding_zero(&mut table, num_fixed).expect("padding error");
+        let mut table = config.generate_fixed_traces(cs.num_fixed, RANGE::content());
+        table.padding_zero();
         table
     }
 
@@ -57,7 +69,7 @@ impl<E: ExtensionField, RANGE: RangeTable> TableCircuit<E> for RangeTableCircuit
     ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
         let multiplicity = &multiplicity[RANGE::ROM_TYPE as usize];
         let mut table = config.assign_instances(num_witin, multiplicity, RANGE::len())?;
-        Self::padding_zero(&mut table, num_witin).expect("padding error");
+        table.padding_zero();
         Ok(table)
     }
 }
@@ -0,0 +1,91 @@
+//! Definition of structured tables: a single registered table exposing several named fixed
+//! columns, each with its own generator.
+
+mod structured_impl;
+
+mod structured_circuit;
+pub use structured_circuit::{ColumnSpec, StructuredTable, StructuredTableCircuit};
+pub use structured_impl::StructuredTableConfig;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        circuit_builder::{CircuitBuilder, ConstraintSystem},
+        error::ZKVMError,
+        structs::ROMType,
+        tables::TableCircuit,
+    };
+    use goldilocks::{Goldilocks, GoldilocksExt2 as E, SmallField};
+    use std::collections::HashMap;
+
+    struct SquareTable;
+    impl StructuredTable for SquareTable {
+        const ROM_TYPE: ROMType = ROMType::U8;
+        fn len() -> usize {
+            1 << 4
+        }
+
+        fn columns() -> Vec<ColumnSpec> {
+            vec![
+                ColumnSpec {
+                    name: "x",
+                    generator: |row| row as u64,
+                },
+                ColumnSpec {
+                    name: "x_square",
+                    generator: |row| (row * row) as u64,
+                },
+            ]
+        }
+    }
+
+    #[test]
+    fn test_structured_table_columns() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+
+        let config = StructuredTableCircuit::<E, SquareTable>::construct_circuit(&mut cb).unwrap();
+        assert_eq!(cb.cs.num_fixed, 2);
+
+        let x = config.column("x").unwrap();
+        let x_square = config.column("x_square").unwrap();
+        assert!(config.column("y").is_none());
+        assert_eq!(
+            StructuredTableCircuit::<E, SquareTable>::fixed_columns(&config),
+            vec![("x".to_string(), x), ("x_square".to_string(), x_square)]
+        );
+
+        let fixed =
+            StructuredTableCircuit::<E, SquareTable>::generate_fixed_traces(&config, cb.cs, &());
+        for (i, row) in fixed.iter_rows().enumerate() {
+            let i = i as u64;
+            assert_eq!(i, unsafe { row[x.0].assume_init() }.to_canonical_u64());
+            assert_eq!(
+                i * i,
+                unsafe { row[x_square.0].assume_init() }.to_canonical_u64()
+            );
+        }
+    }
+
+    #[test]
+    fn test_structured_table_errors() {
+        let mut cs = ConstraintSystem::<E>::new(|| "riscv");
+        let mut cb = CircuitBuilder::new(&mut cs);
+        let x = ColumnSpec {
+            name: "x",
+            generator: |row| row as u64,
+        };
+        assert!(matches!(
+            StructuredTableConfig::construct_circuit(&mut cb, ROMType::U8, 16, &[x, x]),
+            Err(ZKVMError::InvalidTableColumns(ROMType::U8, _))
+        ));
+
+        let config = StructuredTableCircuit::<E, SquareTable>::construct_circuit(&mut cb).unwrap();
+        let multiplicity = HashMap::from([(SquareTable::len() as u64, 1)]);
+        assert!(matches!(
+            config.assign_instances::<Goldilocks>(1, &multiplicity, SquareTable::len()),
+            Err(ZKVMError::LkKeyOutOfRange(ROMType::U8, 16))
+        ));
+    }
+}
@@ -0,0 +1,90 @@
+//! Structured tables as circuits with trait TableCircuit.
+
+use super::structured_impl::StructuredTableConfig;
+
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::{
+    circuit_builder::{CircuitBuilder, ConstraintSystem},
+    error::ZKVMError,
+    expression::Fixed,
+    structs::ROMType,
+    tables::TableCircuit,
+    witness::RowMajorMatrix,
+};
+use ff_ext::ExtensionField;
+
+/// A logical column of a structured table.
+#[derive(Clone, Copy, Debug)]
+pub struct ColumnSpec {
+    pub name: &'static str,
+    /// The value of this column at a given row.
+    pub generator: fn(usize) -> u64,
+}
+
+/// Use this trait as parameter to StructuredTableCircuit.
+///
+/// Each row of the table is one record `[col_0, col_1, ...]`, in the order of `columns()`.
+/// Lookups into the table are keyed by row index.
+pub trait StructuredTable {
+    const ROM_TYPE: ROMType;
+
+    fn len() -> usize;
+
+    fn columns() -> Vec<ColumnSpec>;
+}
+
+pub struct StructuredTableCircuit<E, T>(PhantomData<(E, T)>);
+
+impl<E: ExtensionField, T: StructuredTable> TableCircuit<E> for StructuredTableCircuit<E, T> {
+    type TableConfig = StructuredTableConfig;
+    type FixedInput = ();
+    type WitnessInput = ();
+
+    fn name() -> String {
+        format!("STRUCTURED_{:?}", T::ROM_TYPE)
+    }
+
+    fn construct_circuit(cb: &mut CircuitBuilder<E>) -> Result<StructuredTableConfig, ZKVMError> {
+        cb.namespace(
+            || Self::name(),
+            |cb| StructuredTableConfig::construct_circuit(cb, T::ROM_TYPE, T::len(), &T::columns()),
+        )
+    }
+
+    fn fixed_columns(config: &StructuredTableConfig) -> Vec<(String, Fixed)> {
+        config
+            .columns()
+            .iter()
+            .map(|(name, f)| (name.to_string(), *f))
+            .collect()
+    }
+
+    fn static_fixed_len() -> Option<usize> {
+        Some(T::len())
+    }
+
+    fn generate_fixed_traces(
+        _config: &StructuredTableConfig,
+        cs: &ConstraintSystem<E>,
+        _input: &(),
+    ) -> RowMajorMatrix<E::BaseField> {
+        let mut table = cs
+            .generate_fixed_traces(T::len())
+            .expect("structured table column without generator");
+        table.padding_zero();
+        table
+    }
+
+    fn assign_instances(
+        config: &Self::TableConfig,
+        num_witin: usize,
+        multiplicity: &[HashMap<u64, usize>],
+        _input: &(),
+    ) -> Result<RowMajorMatrix<E::BaseField>, ZKVMError> {
+        let multiplicity = &multiplicity[T::ROM_TYPE as usize];
+        let mut table = config.assign_instances(num_witin, multiplicity, T::len())?;
+        table.padding_zero();
+        Ok(table)
+    }
+}
@@ -0,0 +1,112 @@
+//! The implementation of structured tables. No generics.
+
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+use itertools::Itertools;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use std::{collections::HashMap, mem::MaybeUninit};
+
+use crate::{
+    circuit_builder::CircuitBuilder,
+    error::ZKVMError,
+    expression::{Expression, Fixed, ToExpr, WitIn},
+    scheme::constants::MIN_PAR_SIZE,
+    set_val,
+    structs::ROMType,
+    witness::RowMajorMatrix,
+};
+
+use super::structured_circuit::ColumnSpec;
+
+#[derive(Clone, Debug)]
+pub struct StructuredTableConfig {
+    rom_type: ROMType,
+    /// Fixed columns in record order, with their names.
+    columns: Vec<(&'static str, Fixed)>,
+    mlt: WitIn,
+}
+
+impl StructuredTableConfig {
+    pub fn construct_circuit<E: ExtensionField>(
+        cb: &mut CircuitBuilder<E>,
+        rom_type: ROMType,
+        table_len: usize,
+        specs: &[ColumnSpec],
+    ) -> Result<Self, ZKVMError> {
+        if specs.is_empty() {
+            return Err(ZKVMError::InvalidTableColumns(
+                rom_type,
+                "no columns".to_string(),
+            ));
+        }
+        if let Some(name) = specs.iter().map(|spec| spec.name).duplicates().next() {
+            return Err(ZKVMError::InvalidTableColumns(
+                rom_type,
+                format!("duplicated column {name}"),
+            ));
+        }
+
+        let columns = specs
+            .iter()
+            .map(|spec| {
+                Ok((
+                    spec.name,
+                    cb.create_fixed_with(|| spec.name, spec.generator)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, ZKVMError>>()?;
+        let mlt = cb.create_witin(|| "mlt");
+
+        let record_exprs = columns
+            .iter()
+            .map(|(_, f)| Expression::Fixed(*f))
+            .collect_vec();
+
+        cb.lk_table_record(|| "record", table_len, rom_type, record_exprs, mlt.expr())?;
+
+        Ok(Self {
+            rom_type,
+            columns,
+            mlt,
+        })
+    }
+
+    /// Look up a fixed column by name.
+    pub fn column(&self, name: &str) -> Option<Fixed> {
+        self.columns
+            .iter()
+            .find(|(column_name, _)| *column_name == name)
+            .map(|(_, f)| *f)
+    }
+
+    /// Named fixed columns, in record order.
+    pub fn columns(&self) -> &[(&'static str, Fixed)] {
+        &self.columns
+    }
+
+    pub fn assign_instances<F: SmallField>(
+        &self,
+        num_witin: usize,
+        multiplicity: &HashMap<u64, usize>,
+        length: usize,
+    ) -> Result<RowMajorMatrix<F>, ZKVMError> {
+        let mut witness = RowMajorMatrix::<F>::new(length, num_witin);
+
+        let mut mlts = vec![0; length];
+        for (idx, mlt) in multiplicity {
+            *mlts
+                .get_mut(*idx as usize)
+                .ok_or(ZKVMError::LkKeyOutOfRange(self.rom_type, *idx))? = *mlt;
+        }
+
+        witness
+            .par_iter_mut()
+            .with_min_len(MIN_PAR_SIZE)
+            .zip(mlts.into_par_iter())
+            .for_each(|(row, mlt)| {
+                set_val!(row, self.mlt, F::from(mlt as u64));
+            });
+
+        Ok(witness)
+    }
+}
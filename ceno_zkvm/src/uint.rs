@@ -10,7 +10,7 @@ use crate::{
     expression::{Expression, ToExpr, WitIn},
     gadgets::{AssertLTConfig, SignedExtendConfig},
     instructions::riscv::constants::UInt,
-    utils::add_one_to_big_num,
+    utils::{add_one_to_big_num, field_bits},
     witness::LkMultiplicity,
 };
 use ark_std::iterable::Iterable;
@@ -394,6 +394,7 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
                         })
                         .collect::<Result<Vec<WitIn>, ZKVMError>>()?,
                 );
+                cb.record_carries(self.carries.as_ref().unwrap());
                 Ok(())
             })?;
         }
@@ -459,6 +460,10 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
 
     /// Get an Expression<E> from the limbs, unsafe if Uint value exceeds field limit
     pub fn value(&self) -> Expression<E> {
+        debug_assert!(
+            (M as u32) < field_bits::<E::BaseField>(),
+            "a {M}-bit value does not fit in the base field"
+        );
         self.expr()
             .into_iter()
             .rev()
@@ -505,13 +510,12 @@ impl<const M: usize, const C: usize, E: ExtensionField> TryFrom<Vec<WitIn>> for
 
     fn try_from(limbs: Vec<WitIn>) -> Result<Self, Self::Error> {
         if limbs.len() != Self::NUM_LIMBS {
-            return Err(UtilError::UIntError(format!(
-                "cannot construct UIntLimbs<{}, {}> from {} cells, requires {} cells",
-                M,
-                C,
-                limbs.len(),
-                Self::NUM_LIMBS
-            )));
+            return Err(UtilError::UIntLimbCount {
+                max_bits: M,
+                limb_bits: C,
+                expected: Self::NUM_LIMBS,
+                got: limbs.len(),
+            });
         }
 
         Ok(Self {
@@ -792,7 +796,8 @@ impl<'a, T: Into<u64> + From<u32> + Copy + Default> Value<'a, T> {
         ValueMul {
             limbs: c_limbs,
             carries,
-            max_carry_value: max_carry_word_for_multiplication(2, Self::M, Self::C),
+            // the operands may be wider than `T`, see `from_limb_unchecked`
+            max_carry_value: max_carry_word_for_multiplication(2, a_limbs.len() * Self::C, Self::C),
         }
     }
 }
@@ -31,7 +31,9 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
             Self::NUM_LIMBS,
         )?;
         let Some(carries) = &c.carries else {
-            return Err(ZKVMError::CircuitError);
+            return Err(ZKVMError::MissingConfig {
+                path: circuit_builder.cs.ns.compute_path("add_carry".into()),
+            });
         };
         carries.iter().enumerate().try_for_each(|(i, carry)| {
             circuit_builder.assert_bit(|| format!("carry_{i}_in_as_bit"), carry.expr())
@@ -76,14 +78,17 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
     ) -> Result<Self, ZKVMError> {
         circuit_builder.namespace(name_fn, |cb| {
             let Expression::Constant(c) = constant else {
-                panic!("addend is not a constant type");
+                return Err(ZKVMError::NotConstant {
+                    path: cb.cs.ns.compute_path("addend".into()),
+                });
             };
             let b = c.to_canonical_u64();
 
-            // convert Expression::Constant to limbs
+            // convert Expression::Constant to limbs, the limbs above 64 bits being zero
             let b_limbs = (0..Self::NUM_LIMBS)
                 .map(|i| {
-                    Expression::Constant(E::BaseField::from((b >> (C * i)) & Self::LIMB_BIT_MASK))
+                    let limb = b.checked_shr((C * i) as u32).unwrap_or(0);
+                    Expression::Constant(E::BaseField::from(limb & Self::LIMB_BIT_MASK))
                 })
                 .collect_vec();
 
@@ -132,6 +137,7 @@ impl<const M: usize, const C: usize, E: ExtensionField> UIntLimbs<M, C, E> {
             }
             Result::<Vec<WitIn>, ZKVMError>::Ok(c_carries)
         })?;
+        circuit_builder.record_carries(&c_carries);
         // assert carry range less than max carry value constant
         let carries_auxiliary_lt_config = c_carries
             .iter()
@@ -296,6 +302,7 @@ mod tests {
     mod add {
         use crate::{
             circuit_builder::{CircuitBuilder, ConstraintSystem},
+            error::ZKVMError,
             expression::{Expression, ToExpr},
             scheme::utils::eval_by_expr,
             uint::UIntLimbs,
@@ -421,6 +428,18 @@ mod tests {
             verify::<32, 5, E>(witness_values, Some(34), false);
         }
 
+        #[test]
+        fn test_add_const_not_constant() {
+            let mut cs = ConstraintSystem::new(|| "test_add");
+            let mut cb = CircuitBuilder::<E>::new(&mut cs);
+            let uint_a = UIntLimbs::<32, 16, E>::new(|| "uint_a", &mut cb).unwrap();
+            let b = cb.create_witin(|| "b");
+            assert!(matches!(
+                uint_a.add_const(|| "uint_c", &mut cb, b.expr(), false),
+                Err(ZKVMError::NotConstant { path }) if path == "test_add/uint_c/addend"
+            ));
+        }
+
         fn verify<const M: usize, const C: usize, E: ExtensionField>(
             witness_values: Vec<u64>,
             const_b: Option<u64>,
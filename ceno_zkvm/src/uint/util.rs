@@ -27,7 +27,6 @@ const fn max_combinations(degree: usize, num_cells: usize) -> usize {
 // for example, n = 2 means u1*u2, while n = 3 means u1*u2*u3
 pub(crate) const fn max_carry_word_for_multiplication(n: usize, m: usize, c: usize) -> u64 {
     assert!(n > 1);
-    assert!(m <= u64::BITS as usize);
     let num_cells = m.div_ceil(c);
 
     // calculate maximum multiplication value max_limb^(n)
@@ -64,5 +63,7 @@ mod tests {
     #[test]
     fn test_max_word_of_limb_degree() {
         assert_eq!(131070, max_carry_word_for_multiplication(2, 32, 16));
+        // wide numbers, e.g. the operands of the 256-bit modular arithmetic
+        assert_eq!(1048560, max_carry_word_for_multiplication(2, 256, 16));
     }
 }
@@ -20,6 +20,17 @@ pub fn i64_to_base<F: SmallField>(x: i64) -> F {
     }
 }
 
+/// The bit width of the modulus of `F`.
+pub fn field_bits<F: SmallField>() -> u32 {
+    u64::BITS - F::MODULUS_U64.leading_zeros()
+}
+
+/// The bit width a field must reach to hold all the values up to `max`: a field of `n` bits holds
+/// all the values of `n - 1` bits.
+pub fn value_field_bits(max: u64) -> u32 {
+    u64::BITS - max.leading_zeros() + 1
+}
+
 pub fn split_to_u8<T: From<u8>>(value: u32) -> Vec<T> {
     (0..(u32::BITS / 8))
         .scan(value, |acc, _| {
@@ -151,6 +162,23 @@ pub(crate) fn eq_eval_less_or_equal_than<E: ExtensionField>(max_idx: usize, a: &
     ans
 }
 
+/// Evaluate at `point` the MLE of the selector of the first `num_instances` rows, that is
+/// \sum_{t=0}^{num_instances-1} eq(point, t), with `point[0]` the variable of the lowest bit of t.
+pub(crate) fn sel_eval<E: ExtensionField>(num_instances: usize, point: &[E]) -> E {
+    if num_instances >= 1 << point.len() {
+        return E::ONE;
+    }
+    // sel_k, the selector of the first (num_instances mod 2^k) rows over point[..k]
+    point.iter().enumerate().fold(E::ZERO, |sel, (i, &x)| {
+        if (num_instances >> i) & 1 == 1 {
+            // all the rows of the lower half, then those of the upper half
+            (E::ONE - x) + x * sel
+        } else {
+            (E::ONE - x) * sel
+        }
+    })
+}
+
 /// evaluate MLE M(x0, x1, x2, ..., xn) address vector with it evaluation format a*[0, 1, 2, 3, ....2^n-1] + b
 /// on r = [r0, r1, r2, ...rn] succintly
 /// a, b, is constant
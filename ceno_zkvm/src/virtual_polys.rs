@@ -12,7 +12,7 @@ use multilinear_extensions::{
     virtual_poly_v2::{ArcMultilinearExtension, VirtualPolynomialV2},
 };
 
-use crate::{expression::Expression, utils::transpose};
+use crate::{expression::Expression, structs::WitnessId, utils::transpose};
 
 pub struct VirtualPolynomials<'a, E: ExtensionField> {
     num_threads: usize,
@@ -91,7 +91,7 @@ impl<'a, E: ExtensionField> VirtualPolynomials<'a, E> {
         challenges: &[E],
         // sumcheck batch challenge
         alpha: E,
-    ) -> BTreeSet<u16> {
+    ) -> BTreeSet<WitnessId> {
         assert!(expr.is_monomial_form());
         let monomial_terms = expr.evaluate(
             &|_| unreachable!(),
@@ -152,7 +152,7 @@ impl<'a, E: ExtensionField> VirtualPolynomials<'a, E> {
         monomial_terms
             .into_iter()
             .flat_map(|(_, monomial_term)| monomial_term.into_iter().collect_vec())
-            .collect::<BTreeSet<u16>>()
+            .collect::<BTreeSet<WitnessId>>()
     }
 
     #[cfg(test)]
@@ -317,4 +317,83 @@ mod tests {
             "wrong subclaim"
         );
     }
+
+    #[test]
+    fn test_sumcheck_padded_selector() {
+        let max_num_vars = 4;
+        let num_instances = 5;
+        let mut rng = test_rng();
+
+        // a selector on the instances, zero on the padding, as in the opcode circuits
+        let sel: ArcMultilinearExtension<E> = (0..1 << max_num_vars)
+            .map(|i| {
+                if i < num_instances {
+                    Goldilocks::random(&mut rng)
+                } else {
+                    Goldilocks::ZERO
+                }
+            })
+            .collect_vec()
+            .into_mle()
+            .into();
+        let [a, b]: [ArcMultilinearExtension<E>; 2] = std::array::from_fn(|_| {
+            (0..1 << max_num_vars)
+                .map(|_| Goldilocks::random(&mut rng))
+                .collect_vec()
+                .into_mle()
+                .into()
+        });
+        let products = [vec![&sel, &a], vec![&sel, &a, &b]];
+        let asserted_sum = products
+            .iter()
+            .map(|fs| {
+                (0..num_instances)
+                    .map(|i| {
+                        fs.iter()
+                            .map(|f| f.get_base_field_vec()[i])
+                            .product::<Goldilocks>()
+                    })
+                    .sum::<Goldilocks>()
+            })
+            .sum::<Goldilocks>();
+
+        let num_threads = 1;
+        let mut virtual_polys = VirtualPolynomials::<E>::new(num_threads, max_num_vars);
+        for fs in &products {
+            virtual_polys.add_mle_list(fs.clone(), E::ONE);
+        }
+        let mut transcript = Transcript::new(b"test");
+        let (sumcheck_proofs, _) = IOPProverStateV2::prove_batch_polys(
+            num_threads,
+            virtual_polys.get_batched_polys(),
+            &mut transcript,
+        );
+
+        let mut transcript = Transcript::new(b"test");
+        let subclaim = IOPVerifierState::<E>::verify(
+            asserted_sum.into(),
+            &sumcheck_proofs,
+            &VPAuxInfo {
+                max_degree: 3,
+                num_variables: max_num_vars,
+                phantom: std::marker::PhantomData,
+            },
+            &mut transcript,
+        );
+
+        let mut verifier_poly = VirtualPolynomialV2::new(max_num_vars);
+        for fs in products {
+            verifier_poly.add_mle_list(fs.into_iter().cloned().collect(), E::ONE);
+        }
+        assert_eq!(
+            verifier_poly.evaluate(
+                &subclaim
+                    .point
+                    .iter()
+                    .map(|c| c.elements)
+                    .collect::<Vec<_>>()
+            ),
+            subclaim.expected_evaluation
+        );
+    }
 }
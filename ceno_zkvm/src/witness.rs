@@ -1,63 +1,406 @@
 use ff::Field;
+use ff_ext::ExtensionField;
+use itertools::Itertools;
+use memmap2::MmapMut;
 use std::{
+    alloc::{GlobalAlloc, Layout, System},
     array,
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io,
+    marker::PhantomData,
     mem::{self, MaybeUninit},
-    ops::Index,
-    slice::{Chunks, ChunksMut},
-    sync::Arc,
+    ops::{Deref, DerefMut, Index},
+    ptr,
+    slice::{self, Chunks, ChunksMut},
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
 };
 
 use multilinear_extensions::{
     mle::{DenseMultilinearExtension, IntoMLEs},
-    util::create_uninit_vec,
+    util::{create_uninit_vec, max_usable_threads},
 };
 use rayon::{
-    iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator},
-    slice::ParallelSliceMut,
+    iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+    slice::{ParallelSlice, ParallelSliceMut},
 };
+use strum::IntoEnumIterator;
 use thread_local::ThreadLocal;
 
 use crate::{
+    error::ZKVMError,
+    scheme::constants::MIN_PAR_SIZE,
     structs::ROMType,
-    tables::{AndTable, LtuTable, OpsTable, OrTable, PowTable, XorTable},
+    tables::{
+        AndTable, BITS8_WIDTH, KeccakChiTable, LtuTable, OpsTable, OrTable, PowTable, XorTable,
+        range_lookups,
+    },
     utils::next_pow2_instance_padding,
 };
 
 #[macro_export]
 macro_rules! set_val {
-    ($ins:ident, $field:expr, $val:expr) => {
+    ($ins:expr, $field:expr, $val:expr) => {
         $ins[$field.id as usize] = MaybeUninit::new($val.into());
     };
 }
 
 #[macro_export]
 macro_rules! set_fixed_val {
-    ($ins:ident, $field:expr, $val:expr) => {
+    ($ins:expr, $field:expr, $val:expr) => {
         $ins[$field.0] = MaybeUninit::new($val);
     };
 }
 
+/// The buffers of dropped witness matrices, to back the next ones rather than allocate them
+/// anew. A service proving one execution after another assigns matrices of about the same sizes
+/// each time, and keeps one pool across its runs, see [`RowMajorMatrix::new_in`].
+///
+/// The pool holds at most the buffers live at once, since a buffer comes back only when its
+/// matrix is dropped. Clones share the buffers.
+pub struct WitnessPool<T> {
+    buffers: Arc<Mutex<Vec<Vec<MaybeUninit<T>>>>>,
+}
+
+impl<T> Default for WitnessPool<T> {
+    fn default() -> Self {
+        Self {
+            buffers: Arc::default(),
+        }
+    }
+}
+
+impl<T> Clone for WitnessPool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            buffers: self.buffers.clone(),
+        }
+    }
+}
+
+impl<T> WitnessPool<T> {
+    /// A buffer of `len` uninitialized values: the smallest free one which fits, if any.
+    pub fn take(&self, len: usize) -> Vec<MaybeUninit<T>> {
+        let buffer = {
+            let mut buffers = self.buffers.lock().unwrap();
+            (0..buffers.len())
+                .filter(|&i| buffers[i].capacity() >= len)
+                .min_by_key(|&i| buffers[i].capacity())
+                .map(|i| buffers.swap_remove(i))
+        };
+        match buffer {
+            Some(mut buffer) => {
+                // the values are uninitialized anyway
+                unsafe { buffer.set_len(len) };
+                buffer
+            }
+            None => create_uninit_vec(len),
+        }
+    }
+
+    /// Return a buffer to the pool.
+    pub fn give(&self, mut buffer: Vec<MaybeUninit<T>>) {
+        if buffer.capacity() > 0 {
+            buffer.clear();
+            self.buffers.lock().unwrap().push(buffer);
+        }
+    }
+
+    /// The number of values of the free buffers.
+    pub fn free_capacity(&self) -> usize {
+        let buffers = self.buffers.lock().unwrap();
+        buffers.iter().map(Vec::capacity).sum()
+    }
+}
+
+/// The size in bytes from which witness matrices spill to disk, see [`set_spill_threshold`].
+static SPILL_THRESHOLD: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Back the witness matrices of at least `bytes` bytes by a memory-mapped temporary file, in
+/// `std::env::temp_dir`, rather than by memory, or none of them with `None`, the default. The
+/// kernel then pages them in and out as memory requires, so that traces larger than the memory
+/// are assigned and committed, if slower. With [`SpillAllocator`] as the global allocator, the
+/// other allocations of at least `bytes` bytes spill as well.
+pub fn set_spill_threshold(bytes: Option<usize>) {
+    SPILL_THRESHOLD.store(bytes.unwrap_or(usize::MAX), Ordering::Relaxed);
+}
+
+/// A global allocator which backs the allocations from the spill threshold on by memory-mapped
+/// temporary files, as [`RowMajorMatrix`] does for the witness matrices. The columns which
+/// [`RowMajorMatrix::de_interleaving`] transposes a matrix into, their MLEs and the data
+/// committing to them then spill to disk as well. A binary opts in with `#[global_allocator]`.
+/// The smaller allocations, and all of them until a threshold is set, go to [`System`].
+pub struct SpillAllocator;
+
+/// The smallest allocation which spills, whatever the threshold, so that the allocations made
+/// while creating a temporary file never spill in turn.
+const MIN_SPILL_BYTES: usize = 1 << 20;
+
+/// The alignment of a mapping, at least.
+const PAGE_SIZE: usize = 1 << 12;
+
+/// The allocations of [`SpillAllocator`] which spilled, by address.
+static SPILLED_ALLOCATIONS: Mutex<BTreeMap<usize, MmapMut>> = Mutex::new(BTreeMap::new());
+
+fn spilled_allocations() -> MutexGuard<'static, BTreeMap<usize, MmapMut>> {
+    // the map is consistent after any panic, which can only come from its allocations anyway
+    SPILLED_ALLOCATIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+impl SpillAllocator {
+    fn spills(layout: Layout) -> bool {
+        layout.size() >= MIN_SPILL_BYTES
+            && layout.size() >= SPILL_THRESHOLD.load(Ordering::Relaxed)
+            && layout.align() <= PAGE_SIZE
+    }
+
+    /// A zeroed allocation of `layout` in a temporary file, or `None` if it cannot be created.
+    fn alloc_spilled(layout: Layout) -> Option<*mut u8> {
+        // the file is deleted already, and lives as long as its mapping
+        let mut map = tempfile::tempfile()
+            .and_then(|file| {
+                file.set_len(layout.size() as u64)?;
+                unsafe { MmapMut::map_mut(&file) }
+            })
+            .ok()?;
+        let ptr = map.as_mut_ptr();
+        spilled_allocations().insert(ptr as usize, map);
+        Some(ptr)
+    }
+
+    /// Whether `ptr`, allocated with `layout`, is in a temporary file.
+    fn is_spilled(ptr: *mut u8, layout: Layout) -> bool {
+        layout.size() >= MIN_SPILL_BYTES && spilled_allocations().contains_key(&(ptr as usize))
+    }
+}
+
+unsafe impl GlobalAlloc for SpillAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if Self::spills(layout) {
+            if let Some(ptr) = Self::alloc_spilled(layout) {
+                return ptr;
+            }
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // a new file reads as zeros, without paging it in
+        if Self::spills(layout) {
+            if let Some(ptr) = Self::alloc_spilled(layout) {
+                return ptr;
+            }
+        }
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() >= MIN_SPILL_BYTES {
+            // unmapped once the lock is released
+            let map = spilled_allocations().remove(&(ptr as usize));
+            if map.is_some() {
+                return;
+            }
+        }
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        if !Self::spills(new_layout) && !Self::is_spilled(ptr, layout) {
+            return System.realloc(ptr, layout, new_size);
+        }
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+/// The values of a witness matrix: in memory, or in a memory-mapped temporary file from the
+/// spill threshold on.
+enum Storage<T> {
+    Heap(Vec<MaybeUninit<T>>),
+    Mapped {
+        file: File,
+        map: MmapMut,
+        len: usize,
+        _marker: PhantomData<T>,
+    },
+}
+
+impl<T> Storage<T> {
+    /// Whether `len` values spill to disk.
+    fn spills(len: usize) -> bool {
+        len > 0
+            && len.saturating_mul(mem::size_of::<T>()) >= SPILL_THRESHOLD.load(Ordering::Relaxed)
+    }
+
+    /// `len` uninitialized values, in a temporary file if they spill, or else in memory, from
+    /// `pool` if any. A matrix which cannot spill stays in memory.
+    fn new(len: usize, pool: Option<&WitnessPool<T>>) -> Self {
+        if Self::spills(len) {
+            match Self::mapped(len) {
+                Ok(storage) => return storage,
+                Err(err) => tracing::warn!("cannot spill a witness matrix to disk: {err}"),
+            }
+        }
+        Storage::Heap(pool.map_or_else(|| create_uninit_vec(len), |pool| pool.take(len)))
+    }
+
+    fn mapped(len: usize) -> io::Result<Self> {
+        // the file is deleted already, and lives as long as its handle and its mapping
+        let file = tempfile::tempfile()?;
+        file.set_len((len * mem::size_of::<T>()) as u64)?;
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        Ok(Storage::Mapped {
+            file,
+            map,
+            len,
+            _marker: PhantomData,
+        })
+    }
+
+    /// The number of values which fit without growing the storage.
+    fn capacity(&self) -> usize {
+        match self {
+            Storage::Heap(values) => values.capacity(),
+            Storage::Mapped { map, .. } => map.len() / mem::size_of::<T>(),
+        }
+    }
+}
+
+impl<T: Copy> Storage<T> {
+    /// Resize to `len` values, the new ones uninitialized. Both kinds of storage grow
+    /// geometrically: a mapping doubles its file and maps it again, which keeps the values, so
+    /// that appending to a spilled matrix does not copy it every time. A mapping which cannot
+    /// grow moves to memory.
+    fn resize(&mut self, len: usize) {
+        let capacity = self.capacity();
+        let grown = match self {
+            Storage::Heap(values) => {
+                values.resize(len, MaybeUninit::uninit());
+                return;
+            }
+            Storage::Mapped {
+                file,
+                map,
+                len: mapped_len,
+                ..
+            } => {
+                let grown = if len > capacity {
+                    let capacity = len.max(2 * capacity);
+                    file.set_len((capacity * mem::size_of::<T>()) as u64)
+                        .and_then(|()| unsafe { MmapMut::map_mut(&*file) })
+                        .map(|grown| *map = grown)
+                } else {
+                    Ok(())
+                };
+                if grown.is_ok() {
+                    *mapped_len = len;
+                }
+                grown
+            }
+        };
+        if let Err(err) = grown {
+            tracing::warn!("cannot grow a spilled witness matrix: {err}");
+            let mut values = create_uninit_vec(len);
+            let kept = self.len().min(len);
+            values[..kept].copy_from_slice(&self[..kept]);
+            *self = Storage::Heap(values);
+        }
+    }
+}
+
+impl<T> Deref for Storage<T> {
+    type Target = [MaybeUninit<T>];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Storage::Heap(values) => values,
+            // a mapping is page aligned, and as long as the values
+            Storage::Mapped { map, len, .. } => unsafe {
+                slice::from_raw_parts(map.as_ptr().cast(), *len)
+            },
+        }
+    }
+}
+
+impl<T> DerefMut for Storage<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Storage::Heap(values) => values,
+            Storage::Mapped { map, len, .. } => unsafe {
+                slice::from_raw_parts_mut(map.as_mut_ptr().cast(), *len)
+            },
+        }
+    }
+}
+
+impl<T: Copy> Clone for Storage<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Storage::Heap(values) => Storage::Heap(values.clone()),
+            Storage::Mapped { len, .. } => {
+                let mut storage = Self::new(*len, None);
+                storage.copy_from_slice(self);
+                storage
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RowMajorMatrix<T: Sized + Sync + Clone + Send + Copy> {
     // represent 2D in 1D linear memory and avoid double indirection by Vec<Vec<T>> to improve performance
-    values: Vec<MaybeUninit<T>>,
+    values: Storage<T>,
     num_padding_rows: usize,
     num_col: usize,
+    /// The pool the buffer returns to, on drop.
+    pool: Option<WitnessPool<T>>,
 }
 
 impl<T: Sized + Sync + Clone + Send + Copy> RowMajorMatrix<T> {
+    /// A matrix of `num_rows` instances, and as many padding rows as they require. It spills to
+    /// disk from the threshold of [`set_spill_threshold`].
     pub fn new(num_rows: usize, num_col: usize) -> Self {
         let num_total_rows = next_pow2_instance_padding(num_rows);
         let num_padding_rows = num_total_rows - num_rows;
         RowMajorMatrix {
-            values: create_uninit_vec(num_total_rows * num_col),
+            values: Storage::new(num_total_rows * num_col, None),
+            num_padding_rows,
+            num_col,
+            pool: None,
+        }
+    }
+
+    /// Like [`Self::new`], with the buffer taken from `pool`, and returned to it once the matrix
+    /// is dropped, e.g. by [`Self::de_interleaving`]. A matrix which spills to disk does not use
+    /// the pool.
+    pub fn new_in(num_rows: usize, num_col: usize, pool: &WitnessPool<T>) -> Self {
+        let num_total_rows = next_pow2_instance_padding(num_rows);
+        let num_padding_rows = num_total_rows - num_rows;
+        RowMajorMatrix {
+            values: Storage::new(num_total_rows * num_col, Some(pool)),
             num_padding_rows,
             num_col,
+            pool: Some(pool.clone()),
         }
     }
 
+    /// Whether the values are in a temporary file rather than in memory.
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.values, Storage::Mapped { .. })
+    }
+
     pub fn num_instances(&self) -> usize {
         self.values.len() / self.num_col - self.num_padding_rows
     }
@@ -66,6 +409,10 @@ impl<T: Sized + Sync + Clone + Send + Copy> RowMajorMatrix<T> {
         self.num_padding_rows
     }
 
+    pub fn num_col(&self) -> usize {
+        self.num_col
+    }
+
     pub fn iter_rows(&self) -> Chunks<MaybeUninit<T>> {
         self.values.chunks(self.num_col)
     }
@@ -78,6 +425,12 @@ impl<T: Sized + Sync + Clone + Send + Copy> RowMajorMatrix<T> {
         self.values.par_chunks_mut(self.num_col)
     }
 
+    /// The instances, without the padding rows.
+    pub fn par_iter_instances_mut(&mut self) -> rayon::slice::ChunksMut<MaybeUninit<T>> {
+        let num_instances = self.num_instances();
+        self.values[..num_instances * self.num_col].par_chunks_mut(self.num_col)
+    }
+
     pub fn par_batch_iter_mut(
         &mut self,
         num_rows: usize,
@@ -95,24 +448,109 @@ impl<T: Sized + Sync + Clone + Send + Copy> RowMajorMatrix<T> {
             .par_chunks_mut(num_rows * self.num_col)
     }
 
-    pub fn de_interleaving(mut self) -> Vec<Vec<T>> {
-        (0..self.num_col)
-            .map(|i| {
-                self.values
-                    .par_iter_mut()
-                    .skip(i)
-                    .step_by(self.num_col)
-                    .map(|v| unsafe { mem::replace(v, mem::MaybeUninit::uninit()).assume_init() })
-                    .collect::<Vec<T>>()
-            })
+    /// The columns of the matrix, padding rows included, e.g. to build their MLEs.
+    ///
+    /// The transpose goes by tiles of a block of rows and a few columns, the blocks of rows on
+    /// separate threads. A tile reads whole cache lines of its rows and writes runs of each of its
+    /// columns, where gathering a column at a time would stride through the whole matrix once
+    /// per column.
+    pub fn de_interleaving(self) -> Vec<Vec<T>> {
+        /// The columns of a tile, a cache line of 64-bit field elements.
+        const TILE_COLUMNS: usize = 8;
+
+        let num_col = self.num_col;
+        if num_col == 0 {
+            return vec![];
+        }
+        let num_rows = self.values.len() / num_col;
+        let block_rows = num_rows.div_ceil(max_usable_threads()).max(1);
+
+        let mut columns = (0..num_col)
+            .map(|_| create_uninit_vec::<T>(num_rows))
+            .collect_vec();
+        // the rows of each block, by column
+        let mut blocks = (0..num_rows.div_ceil(block_rows))
+            .map(|_| Vec::with_capacity(num_col))
+            .collect_vec();
+        for column in &mut columns {
+            for (block, rows) in blocks.iter_mut().zip(column.chunks_mut(block_rows)) {
+                block.push(rows);
+            }
+        }
+        blocks
+            .into_par_iter()
+            .zip(self.values.par_chunks(block_rows * num_col))
+            .for_each(|(mut block, values)| {
+                for (tile, columns) in block.chunks_mut(TILE_COLUMNS).enumerate() {
+                    let first_column = tile * TILE_COLUMNS;
+                    for (i, row) in values.chunks(num_col).enumerate() {
+                        for (column, v) in columns.iter_mut().zip(&row[first_column..]) {
+                            column[i] = *v;
+                        }
+                    }
+                }
+            });
+
+        columns
+            .into_iter()
+            // every cell is initialized once the matrix is assigned
+            .map(|column| unsafe { mem::transmute::<Vec<MaybeUninit<T>>, Vec<T>>(column) })
             .collect()
     }
 }
 
+impl<T: Sized + Sync + Clone + Send + Copy> Drop for RowMajorMatrix<T> {
+    fn drop(&mut self) {
+        if let (Some(pool), Storage::Heap(values)) = (self.pool.take(), &mut self.values) {
+            pool.give(mem::take(values));
+        }
+    }
+}
+
 impl<F: Field> RowMajorMatrix<F> {
-    pub fn into_mles<E: ff_ext::ExtensionField<BaseField = F>>(
-        self,
-    ) -> Vec<DenseMultilinearExtension<E>> {
+    /// Append the instances of `other` after those of `self`, and pad the result again.
+    pub fn append(&mut self, other: Self) {
+        assert_eq!(self.num_col, other.num_col);
+        let num_col = self.num_col;
+        let len = self.num_instances() * num_col;
+        let other_len = other.num_instances() * num_col;
+        let num_instances = self.num_instances() + other.num_instances();
+        let num_total_rows = next_pow2_instance_padding(num_instances);
+
+        let new_len = num_total_rows * num_col;
+        if matches!(self.values, Storage::Heap(_)) && Storage::<F>::spills(new_len) {
+            // the matrix starts spilling
+            let mut values = Storage::new(new_len, self.pool.as_ref());
+            values[..len].copy_from_slice(&self.values[..len]);
+            if let (Storage::Heap(previous), Some(pool)) =
+                (mem::replace(&mut self.values, values), &self.pool)
+            {
+                pool.give(previous);
+            }
+        } else {
+            self.values.resize(new_len);
+        }
+        self.values[len..][..other_len].copy_from_slice(&other.values[..other_len]);
+        self.num_padding_rows = num_total_rows - num_instances;
+        self.padding_zero();
+    }
+
+    /// Fill the padding rows with zeros, including the witnesses derived by the constraint
+    /// system. They are neutral in any circuit: the prover excludes them from the reads, writes,
+    /// lookups and degree > 1 constraints with a selector of the instances, and a degree 1
+    /// constraint evaluates to its constant term on them, which the verifier expects there.
+    pub fn padding_zero(&mut self) {
+        if self.num_padding_rows == 0 || self.num_col == 0 {
+            return;
+        }
+        let num_instances = self.num_instances();
+        self.values[num_instances * self.num_col..]
+            .par_chunks_mut(self.num_col)
+            .with_min_len(MIN_PAR_SIZE)
+            .for_each(|row| row.fill(MaybeUninit::new(F::ZERO)));
+    }
+
+    pub fn into_mles<E: ExtensionField<BaseField = F>>(self) -> Vec<DenseMultilinearExtension<E>> {
         self.de_interleaving().into_mles()
     }
 }
@@ -125,9 +563,49 @@ impl<F: Field> Index<usize> for RowMajorMatrix<F> {
     }
 }
 
+/// The assignment of one row of a witness matrix: the row, the lookup multiplicities it counts,
+/// and where it is in the matrix. The gadgets assign their witnesses through it, see
+/// `Gadget::assign`, whichever circuit they are part of.
+///
+/// It carries no challenges, as the witnesses are committed before the challenges are drawn.
+pub struct AssignmentCtx<'a, E: ExtensionField> {
+    pub instance: &'a mut [MaybeUninit<E::BaseField>],
+    pub lk_multiplicity: &'a mut LkMultiplicity,
+    row: usize,
+}
+
+impl<'a, E: ExtensionField> AssignmentCtx<'a, E> {
+    /// The assignment of `instance`, the row of index `row` of its matrix.
+    pub fn new(
+        instance: &'a mut [MaybeUninit<E::BaseField>],
+        lk_multiplicity: &'a mut LkMultiplicity,
+        row: usize,
+    ) -> Self {
+        Self {
+            instance,
+            lk_multiplicity,
+            row,
+        }
+    }
+
+    /// The index of the row among the instances.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The number of witnesses of the row.
+    pub fn num_witin(&self) -> usize {
+        self.instance.len()
+    }
+}
+
 /// A lock-free thread safe struct to count logup multiplicity for each ROM type
 /// Lock-free by thread-local such that each thread will only have its local copy
 /// struct is cloneable, for internallly it use Arc so the clone will be low cost
+///
+/// The counters are sharded by thread: a lookup only touches the maps of its own thread, and
+/// the shards are merged once, by [`LkMultiplicity::into_finalize_result`]. Clones share the
+/// shards, so that the clones handed to the tasks of a parallel assignment count together.
 #[derive(Clone, Default, Debug)]
 #[allow(clippy::type_complexity)]
 pub struct LkMultiplicity {
@@ -143,7 +621,14 @@ impl LkMultiplicity {
             14 => self.increment(ROMType::U14, v),
             8 => self.increment(ROMType::U8, v),
             5 => self.increment(ROMType::U5, v),
-            _ => panic!("Unsupported bit range"),
+            c => self.assert_bits(v, c),
+        }
+    }
+
+    /// Track the lookups of `CircuitBuilder::assert_bits`.
+    pub fn assert_bits(&mut self, v: u64, bits: usize) {
+        for (rom_type, shift) in range_lookups(bits) {
+            self.increment(rom_type, v << shift);
         }
     }
 
@@ -176,24 +661,66 @@ impl LkMultiplicity {
         self.logic_u8::<PowTable>(2, v)
     }
 
+    /// lookup the round constant of a Keccak round
+    pub fn lookup_keccak_rc(&mut self, round: u64) {
+        self.increment(ROMType::KeccakRc, round);
+    }
+
+    /// lookup the rotation offset of a Keccak lane
+    pub fn lookup_keccak_rho(&mut self, lane: u64) {
+        self.increment(ROMType::KeccakRho, lane);
+    }
+
+    /// lookup !a & b as bytes
+    pub fn lookup_keccak_chi(&mut self, a: u64, b: u64) {
+        self.logic_u8::<KeccakChiTable>(a, b)
+    }
+
+    /// Track the lookups of `CircuitBuilder::assert_bit_batch`, for bits `b_i`.
+    pub fn assert_bit_batch(&mut self, bits: &[u64]) {
+        for chunk in bits.chunks(BITS8_WIDTH) {
+            let packed = chunk.iter().enumerate().map(|(i, b)| b << i).sum();
+            self.increment(ROMType::Bits8, packed);
+        }
+    }
+
     /// Fetch instruction at pc
     pub fn fetch(&mut self, pc: u32) {
         self.increment(ROMType::Instruction, pc as u64);
     }
 
     /// merge result from multiple thread local to single result
+    ///
+    /// The ROM types are merged in parallel, each into the largest of its shards. All the clones
+    /// must have been dropped.
     pub fn into_finalize_result(self) -> [HashMap<u64, usize>; mem::variant_count::<ROMType>()] {
-        Arc::try_unwrap(self.multiplicity)
-            .unwrap()
+        let mut shards: [Vec<HashMap<u64, usize>>; mem::variant_count::<ROMType>()] =
+            array::from_fn(|_| vec![]);
+        for shard in Arc::try_unwrap(self.multiplicity)
+            .expect("a clone of the multiplicity is still alive")
             .into_iter()
-            .fold(array::from_fn(|_| HashMap::new()), |mut x, y| {
-                x.iter_mut().zip(y.borrow().iter()).for_each(|(m1, m2)| {
-                    for (key, value) in m2 {
-                        *m1.entry(*key).or_insert(0) += value;
+        {
+            for (maps, map) in shards.iter_mut().zip(shard.into_inner()) {
+                maps.push(map);
+            }
+        }
+        shards
+            .into_iter()
+            .collect_vec()
+            .into_par_iter()
+            .map(|mut maps| {
+                let largest = (0..maps.len()).max_by_key(|&i| maps[i].len());
+                let mut merged = largest.map_or_else(HashMap::new, |i| maps.swap_remove(i));
+                for map in maps {
+                    for (key, value) in map {
+                        *merged.entry(key).or_insert(0) += value;
                     }
-                });
-                x
+                }
+                merged
             })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
     }
 
     fn increment(&mut self, rom_type: ROMType, key: u64) {
@@ -206,11 +733,211 @@ impl LkMultiplicity {
     }
 }
 
+/// Accumulate the finalized multiplicities `other` into `acc`, failing on a count overflow.
+/// Keys are merged in ascending order, so the reported key does not depend on hash order.
+pub fn merge_lk_multiplicities(
+    acc: &mut [HashMap<u64, usize>],
+    other: &[HashMap<u64, usize>],
+) -> Result<(), ZKVMError> {
+    for ((rom_type, m1), m2) in ROMType::iter().zip_eq(acc.iter_mut()).zip_eq(other) {
+        for key in m2.keys().sorted() {
+            let count = m1.entry(*key).or_insert(0);
+            *count = count
+                .checked_add(m2[key])
+                .ok_or(ZKVMError::LkMultiplicityOverflow(rom_type, *key))?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that each multiplicity, as well as the sum over each table, stays below `bound`.
+/// The first offending key in ascending order is reported.
+pub fn check_lk_multiplicities(
+    multiplicities: &[HashMap<u64, usize>],
+    bound: u64,
+) -> Result<(), ZKVMError> {
+    for (rom_type, mlt) in ROMType::iter().zip_eq(multiplicities) {
+        let mut total = 0u64;
+        for key in mlt.keys().sorted() {
+            total = u64::try_from(mlt[key])
+                .ok()
+                .and_then(|count| total.checked_add(count))
+                .filter(|total| *total < bound)
+                .ok_or(ZKVMError::LkMultiplicityOverflow(rom_type, *key))?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::thread;
+    use std::{
+        alloc::{GlobalAlloc, Layout},
+        collections::HashMap,
+        mem::MaybeUninit,
+        thread,
+    };
 
-    use crate::{structs::ROMType, witness::LkMultiplicity};
+    use goldilocks::Goldilocks as F;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    use crate::{
+        error::ZKVMError,
+        structs::ROMType,
+        witness::{
+            LkMultiplicity, MIN_SPILL_BYTES, RowMajorMatrix, SpillAllocator, Storage, WitnessPool,
+            check_lk_multiplicities, merge_lk_multiplicities,
+        },
+    };
+
+    fn matrix(rows: &[[u64; 2]]) -> RowMajorMatrix<F> {
+        let mut matrix = RowMajorMatrix::new(rows.len(), 2);
+        for (instance, row) in matrix.iter_mut().zip(rows) {
+            for (cell, v) in instance.iter_mut().zip(row) {
+                *cell = MaybeUninit::new(F::from(*v));
+            }
+        }
+        matrix
+    }
+
+    fn rows(matrix: &RowMajorMatrix<F>) -> Vec<Vec<F>> {
+        matrix
+            .iter_rows()
+            .map(|row| row.iter().map(|v| unsafe { v.assume_init() }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_row_major_matrix_append() {
+        let mut appended = matrix(&[[1, 2], [3, 4], [5, 6]]);
+        appended.append(matrix(&[[7, 8]]));
+        assert_eq!(appended.num_instances(), 4);
+        assert_eq!(
+            rows(&appended),
+            rows(&matrix(&[[1, 2], [3, 4], [5, 6], [7, 8]]))
+        );
+
+        // padded again up to the next power of two
+        appended.append(matrix(&[[9, 10]]));
+        assert_eq!(appended.num_instances(), 5);
+        assert_eq!(appended.num_padding_instances(), 3);
+        assert_eq!(rows(&appended)[4], vec![F::from(9), F::from(10)]);
+        assert_eq!(rows(&appended)[7], vec![F::from(0), F::from(0)]);
+        appended.append(matrix(&[]));
+        assert_eq!(appended.num_instances(), 5);
+        assert_eq!(rows(&appended)[7], vec![F::from(0), F::from(0)]);
+    }
+
+    #[test]
+    fn test_row_major_matrix_padding_zero() {
+        let mut padded = matrix(&[[1, 2], [3, 4], [5, 6]]);
+        padded.padding_zero();
+        assert_eq!(
+            rows(&padded),
+            rows(&matrix(&[[1, 2], [3, 4], [5, 6], [0, 0]]))
+        );
+
+        let mut empty = matrix(&[]);
+        empty.padding_zero();
+        assert_eq!(rows(&empty), vec![vec![F::from(0); 2]; 2]);
+    }
+
+    #[test]
+    fn test_row_major_matrix_spilled() {
+        let mut spilled = matrix(&[[1, 2], [3, 4], [5, 6]]);
+        let mut values = Storage::mapped(spilled.values.len()).unwrap();
+        values.copy_from_slice(&spilled.values);
+        spilled.values = values;
+        assert!(spilled.is_spilled());
+
+        // the mapping grows in place
+        spilled.append(matrix(&[[7, 8], [9, 10]]));
+        assert!(spilled.is_spilled());
+        assert_eq!(spilled.values.capacity(), 16);
+        assert_eq!(
+            rows(&spilled),
+            rows(&matrix(&[
+                [1, 2],
+                [3, 4],
+                [5, 6],
+                [7, 8],
+                [9, 10],
+                [0, 0],
+                [0, 0],
+                [0, 0]
+            ]))
+        );
+        assert_eq!(spilled.clone().de_interleaving(), vec![
+            [1, 3, 5, 7, 9, 0, 0, 0].map(F::from).to_vec(),
+            [2, 4, 6, 8, 10, 0, 0, 0].map(F::from).to_vec(),
+        ]);
+    }
+
+    #[test]
+    fn test_spill_allocator() {
+        let layout = Layout::from_size_align(MIN_SPILL_BYTES, 8).unwrap();
+        let ptr = SpillAllocator::alloc_spilled(layout).unwrap();
+        assert!(SpillAllocator::is_spilled(ptr, layout));
+        unsafe {
+            // zeroed, as a new file
+            assert_eq!(*ptr.add(layout.size() - 1), 0);
+            ptr.write_bytes(1, layout.size());
+
+            // the threshold is not set: back to memory, with the values
+            let grown_layout = Layout::from_size_align(2 * layout.size(), 8).unwrap();
+            let grown = SpillAllocator.realloc(ptr, layout, grown_layout.size());
+            assert!(!SpillAllocator::is_spilled(ptr, layout));
+            assert!(!SpillAllocator::is_spilled(grown, grown_layout));
+            assert_eq!(*grown.add(layout.size() - 1), 1);
+            SpillAllocator.dealloc(grown, grown_layout);
+        }
+    }
+
+    #[test]
+    fn test_witness_pool() {
+        let pool = WitnessPool::<F>::default();
+        let matrix = RowMajorMatrix::new_in(3, 2, &pool);
+        let capacity = matrix.values.capacity();
+        assert_eq!(pool.free_capacity(), 0);
+        drop(matrix);
+        assert_eq!(pool.free_capacity(), capacity);
+
+        // a smaller matrix takes the buffer back
+        let small = RowMajorMatrix::new_in(2, 2, &pool);
+        assert_eq!(small.values.len(), 4);
+        assert_eq!(pool.free_capacity(), 0);
+        // a larger one does not fit in any free buffer
+        drop(small);
+        let mut large = RowMajorMatrix::new_in(16, 2, &pool);
+        assert_eq!(pool.free_capacity(), capacity);
+        large
+            .iter_mut()
+            .for_each(|row| row.fill(MaybeUninit::new(F::from(1))));
+
+        // the transpose returns the buffer as well
+        drop(large.de_interleaving());
+        assert!(pool.free_capacity() >= capacity + 32);
+    }
+
+    #[test]
+    fn test_row_major_matrix_de_interleaving() {
+        // columns over several tiles, and rows over several blocks
+        let (num_rows, num_col) = (1000, 19);
+        let mut matrix = RowMajorMatrix::<F>::new(num_rows, num_col);
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = MaybeUninit::new(F::from((i * num_col + j) as u64));
+            }
+        }
+        let columns = matrix.de_interleaving();
+        assert_eq!(columns.len(), num_col);
+        for (j, column) in columns.iter().enumerate() {
+            assert_eq!(column.len(), 1024);
+            for (i, v) in column.iter().enumerate() {
+                assert_eq!(*v, F::from((i * num_col + j) as u64));
+            }
+        }
+    }
 
     #[test]
     fn test_lk_multiplicity_threads() {
@@ -228,4 +955,76 @@ mod tests {
         // check multiplicity counts of assert_byte
         assert_eq!(res[ROMType::U8 as usize][&8], thread_count);
     }
+
+    #[test]
+    fn test_lk_multiplicity_rayon() {
+        let lkm = LkMultiplicity::default();
+        (0..256 * 40u64)
+            .into_par_iter()
+            .for_each_with(lkm.clone(), |lkm, i| {
+                lkm.assert_ux::<8>(i % 256);
+                lkm.fetch((i % 7) as u32);
+            });
+        let res = lkm.into_finalize_result();
+        // every shard is merged, whatever the threads the lookups ran on
+        assert_eq!(res[ROMType::U8 as usize].len(), 256);
+        assert!(res[ROMType::U8 as usize].values().all(|&count| count == 40));
+        assert_eq!(
+            res[ROMType::Instruction as usize].values().sum::<usize>(),
+            256 * 40
+        );
+        assert!(res[ROMType::U16 as usize].is_empty());
+    }
+
+    fn single_entry(rom_type: ROMType, key: u64, count: usize) -> Vec<HashMap<u64, usize>> {
+        let mut mlts = vec![HashMap::new(); std::mem::variant_count::<ROMType>()];
+        mlts[rom_type as usize].insert(key, count);
+        mlts
+    }
+
+    #[test]
+    fn test_lk_multiplicity_merge_overflow() {
+        let mut acc = single_entry(ROMType::U8, 8, usize::MAX);
+        assert!(merge_lk_multiplicities(&mut acc, &single_entry(ROMType::U8, 9, 1)).is_ok());
+        assert!(merge_lk_multiplicities(&mut acc, &single_entry(ROMType::U16, 8, 1)).is_ok());
+        assert!(matches!(
+            merge_lk_multiplicities(&mut acc, &single_entry(ROMType::U8, 8, 1)),
+            Err(ZKVMError::LkMultiplicityOverflow(ROMType::U8, 8))
+        ));
+
+        // with several overflows, the smallest key is reported
+        let mut acc = single_entry(ROMType::U16, 7, usize::MAX);
+        acc[ROMType::U16 as usize].insert(3, usize::MAX);
+        let mut other = single_entry(ROMType::U16, 7, 1);
+        other[ROMType::U16 as usize].insert(3, 1);
+        assert!(matches!(
+            merge_lk_multiplicities(&mut acc, &other),
+            Err(ZKVMError::LkMultiplicityOverflow(ROMType::U16, 3))
+        ));
+    }
+
+    #[test]
+    fn test_lk_multiplicity_saturation() {
+        let bound = 1 << 20;
+        assert!(
+            check_lk_multiplicities(&single_entry(ROMType::Xor, 3, bound - 1), bound as u64)
+                .is_ok()
+        );
+        assert!(matches!(
+            check_lk_multiplicities(&single_entry(ROMType::Xor, 3, bound), bound as u64),
+            Err(ZKVMError::LkMultiplicityOverflow(ROMType::Xor, 3))
+        ));
+
+        // each count fits, but the table total does not
+        let mut mlts = single_entry(ROMType::Instruction, 0x2000, bound / 2);
+        mlts[ROMType::Instruction as usize].insert(0x1000, bound / 2);
+        mlts[ROMType::Instruction as usize].insert(0x1004, 1);
+        assert!(matches!(
+            check_lk_multiplicities(&mlts, bound as u64),
+            Err(ZKVMError::LkMultiplicityOverflow(
+                ROMType::Instruction,
+                0x2000
+            ))
+        ));
+    }
 }
@@ -1,21 +1,6 @@
-use std::{
-    fs::File,
-    io::{self, Write},
-    path::Path,
-    process::Command,
-};
+use std::{fs::File, io::Write, path::Path};
 
-/// Add each example to this list.
-///
-/// Contact Matthias, if your examples get complicated enough to need their own crates, instead of just being one file.
-const EXAMPLES: &[&str] = &[
-    "ceno_rt_alloc",
-    "ceno_rt_io",
-    "ceno_rt_mem",
-    "ceno_rt_mini",
-    "ceno_rt_panic",
-];
-const CARGO_MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+use ceno_build::{BuildOptions, build_guest};
 
 fn build_elfs() {
     let out_dir = std::env::var_os("OUT_DIR").unwrap();
@@ -24,25 +9,18 @@ fn build_elfs() {
 
     // TODO(Matthias): skip building the elfs if we are in clippy or check mode.
     // See git history for an attempt to do this.
-    let output = Command::new("cargo")
-        .args(["build", "--release", "--examples"])
-        .current_dir("../examples")
-        .env_clear()
-        .envs(std::env::vars().filter(|x| !x.0.starts_with("CARGO_")))
-        .output()
-        .expect("cargo command failed to run");
-    if !output.status.success() {
-        io::stdout().write_all(&output.stdout).unwrap();
-        io::stderr().write_all(&output.stderr).unwrap();
-        panic!("cargo build of examples failed.");
-    }
-    for example in EXAMPLES {
+    let elfs = build_guest("../examples", &BuildOptions {
+        examples: true,
+        ..Default::default()
+    });
+    for (example, elf) in elfs {
         writeln!(
             dest,
             r#"#[allow(non_upper_case_globals)]
-            pub const {example}: &[u8] =
-                include_bytes!(r"{CARGO_MANIFEST_DIR}/../examples/target/riscv32im-unknown-none-elf/release/examples/{example}");"#
-        ).expect("failed to write vars.rs");
+            pub const {example}: &[u8] = include_bytes!(r"{}");"#,
+            elf.display()
+        )
+        .expect("failed to write vars.rs");
     }
     let input_path = "../examples/";
     let elfs_path = "../examples/target/riscv32im-unknown-none-elf/release/examples/";
@@ -0,0 +1,17 @@
+#![no_main]
+#![no_std]
+
+ceno_guest::entry!(main);
+fn main() {
+    // Multiply `point` by a non-zero `scalar`, by doubling and adding.
+    let (point, scalar): ([u32; 16], u32) = ceno_guest::read();
+    let mut result = point;
+    for i in (0..u32::BITS - 1 - scalar.leading_zeros()).rev() {
+        let double = result;
+        ceno_guest::ed25519_add(&mut result, &double);
+        if (scalar >> i) & 1 == 1 {
+            ceno_guest::ed25519_add(&mut result, &point);
+        }
+    }
+    ceno_guest::commit(&result);
+}
@@ -0,0 +1,17 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::{string::String, vec::Vec};
+
+ceno_guest::entry!(main);
+fn main() {
+    let name: String = ceno_guest::read_public();
+    let numbers: Vec<u32> = ceno_guest::read();
+    let factor: u32 = ceno_guest::read();
+    let bytes = ceno_guest::read_bytes();
+
+    ceno_guest::commit(&(name.len() as u32));
+    ceno_guest::commit(&numbers.iter().map(|n| n * factor).sum::<u32>());
+    ceno_guest::commit(&(bytes.len() as u32));
+}
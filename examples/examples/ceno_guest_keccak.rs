@@ -0,0 +1,11 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+ceno_guest::entry!(main);
+fn main() {
+    let message: Vec<u8> = ceno_guest::read();
+    ceno_guest::commit(&ceno_guest::keccak256(&message));
+}
@@ -0,0 +1,20 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+ceno_guest::entry!(main);
+fn main() {
+    // The root of the Merkle tree of `leaf` at `index`, given the siblings on its path.
+    let (leaf, index, path): ([u64; 4], u32, Vec<[u64; 4]>) = ceno_guest::read();
+    let mut node = leaf;
+    for (level, sibling) in path.iter().enumerate() {
+        node = if (index >> level) & 1 == 0 {
+            ceno_guest::poseidon2_compress(&node, sibling)
+        } else {
+            ceno_guest::poseidon2_compress(sibling, &node)
+        };
+    }
+    ceno_guest::commit(&node);
+}
@@ -0,0 +1,16 @@
+#![no_main]
+#![no_std]
+
+ceno_guest::entry!(main);
+fn main() {
+    // Raise `base` to `exponent` modulo `modulus`, by squaring and multiplying.
+    let (base, exponent, modulus): ([u32; 8], u32, [u32; 8]) = ceno_guest::read();
+    let mut result = [1, 0, 0, 0, 0, 0, 0, 0];
+    for i in (0..u32::BITS - exponent.leading_zeros()).rev() {
+        result = ceno_guest::uint256_mulmod(&result, &result, &modulus);
+        if (exponent >> i) & 1 == 1 {
+            result = ceno_guest::uint256_mulmod(&result, &base, &modulus);
+        }
+    }
+    ceno_guest::commit(&result);
+}
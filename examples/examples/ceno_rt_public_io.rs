@@ -0,0 +1,15 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+ceno_rt::entry!(main);
+fn main() {
+    // Commit the public input, reversed.
+    let mut output: Vec<u8> = ceno_rt::read_public_input().to_vec();
+    output.reverse();
+    ceno_rt::commit(&output);
+}
@@ -0,0 +1,11 @@
+#![no_main]
+#![no_std]
+
+extern crate ceno_rt;
+use core::fmt::Write;
+
+ceno_rt::entry!(main);
+fn main() {
+    let _ = writeln!(ceno_rt::stdout(), "hello world");
+    let _ = write!(ceno_rt::stderr(), "{} warnings", 0);
+}
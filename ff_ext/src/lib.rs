@@ -1,10 +1,14 @@
 #![deny(clippy::cargo)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 pub use ff;
 use ff::FromUniformBytes;
 use goldilocks::SmallField;
 use poseidon::poseidon::Poseidon;
 use serde::Serialize;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
 pub trait ExtensionField:
     Serialize
@@ -23,7 +27,7 @@ pub trait ExtensionField:
     + for<'a> SubAssign<&'a Self::BaseField>
     + for<'a> MulAssign<&'a Self::BaseField>
     + Ord
-    + std::hash::Hash
+    + core::hash::Hash
 {
     const DEGREE: usize;
 
@@ -42,6 +46,7 @@ pub trait ExtensionField:
 
 mod impl_goldilocks {
     use crate::ExtensionField;
+    use alloc::vec::Vec;
     use goldilocks::{ExtensionField as GoldilocksEF, Goldilocks, GoldilocksExt2};
 
     impl ExtensionField for GoldilocksExt2 {
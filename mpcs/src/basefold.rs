@@ -1,5 +1,5 @@
 use crate::{
-    Error, Evaluation, NoninteractivePCS, PolynomialCommitmentScheme,
+    Error, Evaluation, MultiBatchOpening, NoninteractivePCS, PolynomialCommitmentScheme,
     sum_check::{
         SumCheck as _, VirtualPolynomial,
         classic::{ClassicSumCheck, CoefficientsProver},
@@ -11,12 +11,12 @@ use crate::{
             inner_product, inner_product_three, interpolate_field_type_over_boolean_hypercube,
         },
         expression::{Expression, Query, Rotation},
-        ext_to_usize,
+        ext_to_usize, field_type_index_ext,
         hash::{Digest, write_digest_to_transcript},
         log2_strict,
         merkle_tree::MerkleTree,
         multiply_poly,
-        plonky2_util::reverse_index_bits_in_place_field_type,
+        plonky2_util::{reverse_index_bits_in_place, reverse_index_bits_in_place_field_type},
         poly_index_ext, poly_iter_ext,
     },
     validate_input,
@@ -48,7 +48,7 @@ use multilinear_extensions::{
 };
 
 use rayon::{
-    iter::IntoParallelIterator,
+    iter::{IndexedParallelIterator, IntoParallelIterator},
     prelude::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator},
 };
 use std::borrow::Cow;
@@ -461,6 +461,24 @@ where
         comm.to_commitment()
     }
 
+    fn get_committed_polys(comm: &Self::CommitmentWithData) -> Vec<DenseMultilinearExtension<E>> {
+        comm.polynomials_bh_evals
+            .par_iter()
+            .map(|bh_evals| {
+                let mut evaluations = bh_evals.clone();
+                // only the evaluations of an encoded polynomial are stored bit-reversed, see
+                // `get_poly_bh_evals_and_codeword`
+                if !comm.is_trivial::<Spec>() {
+                    reverse_index_bits_in_place_field_type(&mut evaluations);
+                }
+                DenseMultilinearExtension {
+                    evaluations,
+                    num_vars: comm.num_vars,
+                }
+            })
+            .collect()
+    }
+
     /// Open a single polynomial commitment at one point. If the given
     /// commitment with data contains more than one polynomial, this function
     /// will panic.
@@ -718,22 +736,24 @@ where
         // them to the transcript.
 
         let point = challenges;
+        let comms = comms.iter().collect_vec();
+        let coeffs = coeffs.into_iter().map(|coeff| vec![coeff]).collect_vec();
 
         let (trees, commit_phase_proof) = batch_commit_phase::<E, Spec>(
             &pp.encoding_params,
             &point,
-            comms,
+            &comms,
             transcript,
             num_vars,
             num_vars - Spec::get_basecode_msg_size_log(),
-            coeffs.as_slice(),
+            &coeffs,
         );
 
         let query_timer = start_timer!(|| "Basefold::batch_open query phase");
         let query_result = batch_prover_query_phase(
             transcript,
             1 << (num_vars + Spec::get_rate_log()),
-            comms,
+            &comms,
             &trees,
             Spec::get_number_queries(),
         );
@@ -744,7 +764,7 @@ where
             BatchedQueriesResultWithMerklePath::from_batched_query_result(
                 query_result,
                 &trees,
-                comms,
+                &comms,
             );
         end_timer!(query_timer);
         end_timer!(timer);
@@ -852,6 +872,169 @@ where
         })
     }
 
+    /// Open several commitments of several polynomials each, every commitment at its own
+    /// point. The polynomials of all the commitments are combined with random coefficients, so
+    /// that each commitment is left with one polynomial, and those are opened together as in
+    /// `batch_open`. The commitments too small for FRI are opened by sending their polynomials.
+    fn multi_batch_open(
+        pp: &Self::ProverParam,
+        comms: &[&Self::CommitmentWithData],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        transcript: &mut impl Transcript<E>,
+    ) -> Result<Self::Proof, Error> {
+        let timer = start_timer!(|| "Basefold::multi_batch_open");
+        assert_eq!(comms.len(), points.len());
+        assert_eq!(comms.len(), evals.len());
+        comms
+            .iter()
+            .zip(points)
+            .zip(evals)
+            .for_each(|((comm, point), evals)| {
+                assert_eq!(comm.num_vars, point.len());
+                assert_eq!(comm.num_polys, evals.len());
+            });
+        validate_input(
+            "multi batch open",
+            pp.get_max_message_size_log(),
+            &[],
+            points,
+        )?;
+
+        let (trivial_comms, comms): (Vec<_>, Vec<_>) = comms
+            .iter()
+            .zip(points)
+            .zip(evals)
+            .partition(|((comm, _), _)| comm.is_trivial::<Spec>());
+        let trivial_proof = trivial_comms
+            .iter()
+            .flat_map(|((comm, _), _)| comm.polynomials_bh_evals.clone())
+            .collect_vec();
+        if comms.is_empty() {
+            return Ok(Self::Proof::trivial(trivial_proof));
+        }
+        let ((comms, points), evals): ((Vec<_>, Vec<_>), Vec<_>) = comms
+            .into_iter()
+            .map(|((comm, point), evals)| ((*comm, point.clone()), evals))
+            .unzip();
+
+        if cfg!(feature = "sanity-check") {
+            comms
+                .iter()
+                .zip(&points)
+                .zip(&evals)
+                .for_each(|((comm, point), evals)| {
+                    let num_vars = comm.num_vars;
+                    comm.polynomials_bh_evals
+                        .iter()
+                        .zip_eq(evals.iter())
+                        .for_each(|(bh_evals, eval)| {
+                            let mut bh_evals = bh_evals.clone();
+                            reverse_index_bits_in_place_field_type(&mut bh_evals);
+                            let poly = DenseMultilinearExtension {
+                                evaluations: bh_evals,
+                                num_vars,
+                            };
+                            assert_eq!(&poly.evaluate(point), eval);
+                        })
+                });
+        }
+
+        let sumcheck_timer = start_timer!(|| "Basefold::multi_batch_open::initial sumcheck");
+        // One random coefficient for every polynomial, across the commitments
+        let batch_coeffs = multi_batch_coeffs(comms.iter().map(|comm| comm.num_polys), transcript);
+        let num_vars = comms.iter().map(|comm| comm.num_vars).max().unwrap();
+        // When a polynomial is smaller, it will be repeatedly summed over the cosets of the
+        // hypercube
+        let target_sum = comms
+            .iter()
+            .zip(&evals)
+            .zip(&batch_coeffs)
+            .map(|((comm, evals), coeffs)| {
+                inner_product(evals.iter(), coeffs) * E::from(1 << (num_vars - comm.num_vars))
+            })
+            .sum::<E>();
+
+        // Merge the polynomials of every commitment. The evaluations in a commitment are
+        // bit-reversed, so the merged ones are reversed back.
+        let merged_polys = comms
+            .iter()
+            .zip(&batch_coeffs)
+            .map(|(comm, coeffs)| {
+                let mut merged = vec![E::ZERO; comm.poly_size()];
+                comm.polynomials_bh_evals
+                    .iter()
+                    .zip_eq(coeffs)
+                    .for_each(|(bh_evals, coeff)| {
+                        merged.par_iter_mut().enumerate().for_each(|(i, merged)| {
+                            *merged += field_type_index_ext(bh_evals, i) * *coeff
+                        })
+                    });
+                reverse_index_bits_in_place(&mut merged);
+                DenseMultilinearExtension::from_evaluations_ext_vec(comm.num_vars, merged)
+            })
+            .collect_vec();
+
+        let expression = (0..merged_polys.len())
+            .map(|idx| {
+                Expression::<E>::eq_xy(idx)
+                    * Expression::Polynomial(Query::new(idx, Rotation::cur()))
+            })
+            .sum();
+        let virtual_poly = VirtualPolynomial::new(
+            &expression,
+            merged_polys.iter().collect_vec(),
+            &[],
+            points.as_slice(),
+        );
+        let (challenges, _, sumcheck_proof) =
+            SumCheck::prove(&(), num_vars, virtual_poly, target_sum, transcript)?;
+        end_timer!(sumcheck_timer);
+
+        // The remaining task is to prove the sum of the polynomials at the sumcheck point,
+        // weighted by their batch coefficients and the eq polynomial of their point.
+        let coeffs = multi_batch_fold_coeffs(&points, &batch_coeffs, &challenges);
+        let point = challenges;
+
+        let (trees, commit_phase_proof) = batch_commit_phase::<E, Spec>(
+            &pp.encoding_params,
+            &point,
+            &comms,
+            transcript,
+            num_vars,
+            num_vars - Spec::get_basecode_msg_size_log(),
+            &coeffs,
+        );
+
+        let query_timer = start_timer!(|| "Basefold::multi_batch_open query phase");
+        let query_result = batch_prover_query_phase(
+            transcript,
+            1 << (num_vars + Spec::get_rate_log()),
+            &comms,
+            &trees,
+            Spec::get_number_queries(),
+        );
+        let query_result_with_merkle_path =
+            BatchedQueriesResultWithMerklePath::from_batched_query_result(
+                query_result,
+                &trees,
+                &comms,
+            );
+        end_timer!(query_timer);
+        end_timer!(timer);
+
+        Ok(Self::Proof {
+            sumcheck_messages: commit_phase_proof.sumcheck_messages,
+            roots: commit_phase_proof.roots,
+            final_message: commit_phase_proof.final_message,
+            query_result_with_merkle_path: ProofQueriesResultWithMerklePath::Batched(
+                query_result_with_merkle_path,
+            ),
+            sumcheck_proof: Some(sumcheck_proof),
+            trivial_proof,
+        })
+    }
+
     fn verify(
         vp: &Self::VerifierParam,
         comm: &Self::Commitment,
@@ -1004,6 +1187,7 @@ where
         evals.iter().enumerate().for_each(|(i, eval)| {
             coeffs[eval.poly()] += eq_xy_evals[eval.point()] * poly_index_ext(&eq_xt, i)
         });
+        let coeffs = coeffs.into_iter().map(|coeff| vec![coeff]).collect_vec();
 
         let mut fold_challenges: Vec<E> = Vec::with_capacity(num_vars);
         let roots = &proof.roots;
@@ -1165,6 +1349,246 @@ where
 
         Ok(())
     }
+
+    fn multi_batch_verify(
+        vp: &Self::VerifierParam,
+        comms: &[Self::Commitment],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        proof: &Self::Proof,
+        transcript: &mut impl Transcript<E>,
+    ) -> Result<(), Error> {
+        let timer = start_timer!(|| "Basefold::multi_batch_verify");
+        assert_eq!(comms.len(), points.len());
+        assert_eq!(comms.len(), evals.len());
+        comms
+            .iter()
+            .zip(points)
+            .zip(evals)
+            .try_for_each(|((comm, point), evals)| {
+                if comm.num_vars() != Some(point.len()) {
+                    return Err(Error::InvalidPcsOpen(
+                        "the point does not match the commitment".to_string(),
+                    ));
+                }
+                if comm
+                    .num_polys
+                    .is_some_and(|num_polys| num_polys != evals.len())
+                {
+                    return Err(Error::InvalidPcsOpen(
+                        "the evaluations do not match the commitment".to_string(),
+                    ));
+                }
+                Ok(())
+            })?;
+
+        let (trivial_comms, comms): (Vec<_>, Vec<_>) = comms
+            .iter()
+            .zip(points)
+            .zip(evals)
+            .partition(|((comm, _), _)| {
+                BasefoldCommitmentWithData::<E>::trivial_num_vars::<Spec>(comm.num_vars().unwrap())
+            });
+
+        // The small commitments are checked against the polynomials sent in the proof
+        let mut trivial_proof = proof.trivial_proof.iter();
+        for ((comm, point), evals) in trivial_comms {
+            let polys = trivial_proof
+                .by_ref()
+                .take(evals.len())
+                .cloned()
+                .collect_vec();
+            if polys.len() != evals.len()
+                || polys
+                    .iter()
+                    .any(|poly| poly.len() != 1 << comm.num_vars().unwrap())
+            {
+                return Err(Error::InvalidPcsOpen(
+                    "the polynomials of a small commitment are missing".to_string(),
+                ));
+            }
+            if MerkleTree::<E>::from_batch_leaves(polys.clone()).root() != comm.root() {
+                return Err(Error::MerkleRootMismatch);
+            }
+            for (poly, eval) in polys.into_iter().zip(evals) {
+                let poly = DenseMultilinearExtension {
+                    evaluations: poly,
+                    num_vars: point.len(),
+                };
+                if &poly.evaluate(point) != eval {
+                    return Err(Error::InvalidPcsOpen(
+                        "the evaluation of a small commitment is wrong".to_string(),
+                    ));
+                }
+            }
+        }
+        if trivial_proof.next().is_some() {
+            return Err(Error::InvalidPcsOpen(
+                "too many polynomials in the proof".to_string(),
+            ));
+        }
+        if comms.is_empty() {
+            end_timer!(timer);
+            return Ok(());
+        }
+        let ((comms, points), evals): ((Vec<_>, Vec<_>), Vec<_>) = comms
+            .into_iter()
+            .map(|((comm, point), evals)| ((comm, point.clone()), evals))
+            .unzip();
+
+        let num_vars = points.iter().map(|point| point.len()).max().unwrap();
+        let num_rounds = num_vars - Spec::get_basecode_msg_size_log();
+        validate_input("multi batch verify", num_vars, &[], &points)?;
+
+        let sumcheck_timer = start_timer!(|| "Basefold::multi_batch_verify::initial sumcheck");
+        let batch_coeffs = multi_batch_coeffs(
+            comms
+                .iter()
+                .zip(&evals)
+                .map(|(comm, evals)| comm.num_polys.unwrap_or(evals.len())),
+            transcript,
+        );
+        let target_sum = points
+            .iter()
+            .zip(&evals)
+            .zip(&batch_coeffs)
+            .map(|((point, evals), coeffs)| {
+                inner_product(evals.iter(), coeffs) * E::from(1 << (num_vars - point.len()))
+            })
+            .sum::<E>();
+
+        let (new_target_sum, verify_point) = SumCheck::verify(
+            &(),
+            num_vars,
+            2,
+            target_sum,
+            proof.sumcheck_proof.as_ref().unwrap(),
+            transcript,
+        )?;
+        end_timer!(sumcheck_timer);
+
+        let coeffs = multi_batch_fold_coeffs(&points, &batch_coeffs, &verify_point);
+
+        let mut fold_challenges: Vec<E> = Vec::with_capacity(num_vars);
+        let roots = &proof.roots;
+        let sumcheck_messages = &proof.sumcheck_messages;
+        for i in 0..num_rounds {
+            transcript.append_field_element_exts(sumcheck_messages[i].as_slice());
+            fold_challenges.push(
+                transcript
+                    .get_and_append_challenge(b"commit round")
+                    .elements,
+            );
+            if i < num_rounds - 1 {
+                write_digest_to_transcript(&roots[i], transcript);
+            }
+        }
+        let final_message = &proof.final_message;
+        transcript.append_field_element_exts(final_message.as_slice());
+
+        let queries: Vec<_> = (0..Spec::get_number_queries())
+            .map(|_| {
+                ext_to_usize(
+                    &transcript
+                        .get_and_append_challenge(b"query indices")
+                        .elements,
+                ) % (1 << (num_vars + Spec::get_rate_log()))
+            })
+            .collect();
+        let query_result_with_merkle_path = proof.query_result_with_merkle_path.as_batched();
+
+        // coeff is the eq polynomial evaluated at the last challenge.len() variables
+        // in reverse order.
+        let rev_challenges = fold_challenges.clone().into_iter().rev().collect_vec();
+        let coeff = eq_xy_eval(
+            &verify_point.as_slice()[verify_point.len() - fold_challenges.len()..],
+            &rev_challenges,
+        );
+        // Compute eq as the partially evaluated eq polynomial
+        let mut eq = build_eq_x_r_vec(
+            &verify_point.as_slice()[..verify_point.len() - fold_challenges.len()],
+        );
+        eq.par_iter_mut().for_each(|e| *e *= coeff);
+
+        batch_verifier_query_phase::<E, Spec>(
+            queries.as_slice(),
+            &vp.encoding_params,
+            query_result_with_merkle_path,
+            sumcheck_messages,
+            &fold_challenges,
+            num_rounds,
+            num_vars,
+            final_message,
+            roots,
+            &comms,
+            &coeffs,
+            eq.as_slice(),
+            &new_target_sum,
+        );
+        end_timer!(timer);
+        Ok(())
+    }
+
+    /// The openings share no check across transcripts, so they are verified in parallel.
+    fn multi_batch_verify_all<T: Transcript<E> + Send>(
+        vp: &Self::VerifierParam,
+        openings: Vec<MultiBatchOpening<'_, E, Self, T>>,
+    ) -> Vec<Result<(), Error>> {
+        openings
+            .into_par_iter()
+            .map(|mut opening| {
+                Self::multi_batch_verify(
+                    vp,
+                    &opening.comms,
+                    &opening.points,
+                    &opening.evals,
+                    opening.proof,
+                    &mut opening.transcript,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Draw the random coefficients which combine the polynomials of several commitments, given the
+/// number of polynomials of each commitment. The coefficients are the evaluations of eq(X, t) at
+/// a random t, split by commitment.
+fn multi_batch_coeffs<E: ExtensionField>(
+    num_polys: impl Iterator<Item = usize>,
+    transcript: &mut impl Transcript<E>,
+) -> Vec<Vec<E>> {
+    let num_polys = num_polys.collect_vec();
+    let batch_size_log = num_polys.iter().sum::<usize>().next_power_of_two().ilog2() as usize;
+    let t = (0..batch_size_log)
+        .map(|_| {
+            transcript
+                .get_and_append_challenge(b"batch coeffs")
+                .elements
+        })
+        .collect::<Vec<_>>();
+    let mut eq_xt = build_eq_x_r_vec(&t).into_iter();
+    num_polys
+        .into_iter()
+        .map(|num_polys| eq_xt.by_ref().take(num_polys).collect_vec())
+        .collect_vec()
+}
+
+/// The coefficients of the polynomials of each commitment in the sum left to prove after the
+/// initial sumcheck, i.e., the batch coefficients scaled by the eq polynomial of the point of
+/// the commitment.
+fn multi_batch_fold_coeffs<E: ExtensionField>(
+    points: &[Vec<E>],
+    batch_coeffs: &[Vec<E>],
+    challenges: &[E],
+) -> Vec<Vec<E>> {
+    points
+        .iter()
+        .zip_eq(batch_coeffs)
+        .map(|(point, coeffs)| {
+            let eq_xy = eq_xy_eval(&challenges[..point.len()], point);
+            coeffs.iter().map(|coeff| eq_xy * coeff).collect_vec()
+        })
+        .collect_vec()
 }
 
 impl<E: ExtensionField, Spec: BasefoldSpec<E>> NoninteractivePCS<E> for Basefold<E, Spec>
@@ -1177,10 +1601,12 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
+        PolynomialCommitmentScheme,
         basefold::Basefold,
         test_util::{
-            gen_rand_poly_base, gen_rand_poly_ext, run_batch_commit_open_verify,
-            run_commit_open_verify, run_simple_batch_commit_open_verify,
+            gen_rand_poly_base, gen_rand_poly_ext, gen_rand_polys, run_batch_commit_open_verify,
+            run_commit_open_verify, run_multi_batch_commit_open_verify,
+            run_simple_batch_commit_open_verify, setup_pcs,
         },
     };
     use goldilocks::GoldilocksExt2;
@@ -1266,4 +1692,44 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn multi_batch_commit_open_verify() {
+        for gen_rand_poly in [gen_rand_poly_base, gen_rand_poly_ext] {
+            run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksBaseCode>(
+                gen_rand_poly,
+                &[10, 8, 11],
+                3,
+            );
+            run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(
+                gen_rand_poly,
+                &[10, 8, 11],
+                3,
+            );
+            // Test the trivial proof of the small commitments, alone and with the others
+            run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(
+                gen_rand_poly,
+                &[4, 5],
+                2,
+            );
+            run_multi_batch_commit_open_verify::<GoldilocksExt2, PcsGoldilocksRSCode>(
+                gen_rand_poly,
+                &[4, 10, 5, 9],
+                2,
+            );
+        }
+    }
+
+    #[test]
+    fn get_committed_polys() {
+        for gen_rand_poly in [gen_rand_poly_base, gen_rand_poly_ext] {
+            // an encoded commitment, and a trivial one
+            for num_vars in [10, 4] {
+                let (pp, _) = setup_pcs::<GoldilocksExt2, PcsGoldilocksRSCode>(num_vars);
+                let polys = gen_rand_polys(|_| num_vars, 3, gen_rand_poly);
+                let comm = PcsGoldilocksRSCode::batch_commit(&pp, &polys).unwrap();
+                assert_eq!(PcsGoldilocksRSCode::get_committed_polys(&comm), polys);
+            }
+        }
+    }
 }
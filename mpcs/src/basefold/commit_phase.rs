@@ -179,11 +179,11 @@ where
 pub fn batch_commit_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     pp: &<Spec::EncodingScheme as EncodingScheme<E>>::ProverParameters,
     point: &[E],
-    comms: &[BasefoldCommitmentWithData<E>],
+    comms: &[&BasefoldCommitmentWithData<E>],
     transcript: &mut impl Transcript<E>,
     num_vars: usize,
     num_rounds: usize,
-    coeffs: &[E],
+    coeffs: &[Vec<E>],
 ) -> (Vec<MerkleTree<E>>, BasefoldCommitPhaseProof<E>)
 where
     E::BaseField: Serialize + DeserializeOwned,
@@ -204,8 +204,8 @@ where
         .for_each(|(index, comm)| {
             running_oracle
                 .iter_mut()
-                .zip_eq(field_type_iter_ext(&comm.get_codewords()[0]))
-                .for_each(|(r, a)| *r += a * coeffs[index]);
+                .zip_eq(comm.batch_codewords(&coeffs[index]))
+                .for_each(|(r, a)| *r += a);
         });
     end_timer!(build_oracle_timer);
 
@@ -214,21 +214,23 @@ where
     // and linearly combine all the polynomials once for all
     let mut sum_of_all_evals_for_sumcheck = vec![E::ZERO; 1 << num_vars];
     comms.iter().enumerate().for_each(|(index, comm)| {
-        sum_of_all_evals_for_sumcheck
-            .par_iter_mut()
-            .enumerate()
-            .for_each(|(pos, r)| {
-                // Evaluating the multilinear polynomial outside of its interpolation hypercube
-                // is equivalent to repeating each element in place.
-                // Here is the tricky part: the bh_evals are stored in big endian, but we want
-                // to align the polynomials to the variable with index 0 before adding them
-                // together. So each element is repeated by
-                // sum_of_all_evals_for_sumcheck.len() / bh_evals.len() times
-                *r += field_type_index_ext(
-                    &comm.polynomials_bh_evals[0],
-                    pos >> (num_vars - log2_strict(comm.polynomials_bh_evals[0].len())),
-                ) * coeffs[index]
-            });
+        for (bh_evals, coeff) in comm.polynomials_bh_evals.iter().zip_eq(&coeffs[index]) {
+            sum_of_all_evals_for_sumcheck
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(pos, r)| {
+                    // Evaluating the multilinear polynomial outside of its interpolation
+                    // hypercube is equivalent to repeating each element in place.
+                    // Here is the tricky part: the bh_evals are stored in big endian, but we
+                    // want to align the polynomials to the variable with index 0 before adding
+                    // them together. So each element is repeated by
+                    // sum_of_all_evals_for_sumcheck.len() / bh_evals.len() times
+                    *r += field_type_index_ext(
+                        bh_evals,
+                        pos >> (num_vars - log2_strict(bh_evals.len())),
+                    ) * *coeff
+                });
+        }
     });
     end_timer!(build_oracle_timer);
 
@@ -246,6 +248,8 @@ where
     let mut roots = Vec::with_capacity(num_rounds - 1);
     let mut final_message = Vec::new();
     let mut running_tree_inner = Vec::new();
+    // The leaves of the running tree, when they differ from the running oracle
+    let mut running_tree_leaves = None;
     for i in 0..num_rounds {
         let sumcheck_timer = start_timer!(|| format!("Batch basefold round {}", i));
         // For the first round, no need to send the running root, because this root is
@@ -268,7 +272,7 @@ where
         if i > 0 {
             let running_tree = MerkleTree::<E>::from_inner_leaves(
                 running_tree_inner,
-                FieldType::Ext(running_oracle),
+                FieldType::Ext(running_tree_leaves.take().unwrap_or(running_oracle)),
             );
             trees.push(running_tree);
         }
@@ -282,18 +286,24 @@ where
             write_digest_to_transcript(&running_root, transcript);
             roots.push(running_root);
 
-            // Then merge the rest polynomials whose sizes match the current running oracle
+            // Then merge the rest polynomials whose sizes match the current running oracle.
+            // The tree commits to the oracle before the merge, as the verifier adds the
+            // committed polynomials to the opened values itself.
             let running_oracle_len = new_running_oracle.len();
-            comms
+            let matching_comms = comms
                 .iter()
                 .enumerate()
                 .filter(|(_, comm)| comm.codeword_size() == running_oracle_len)
-                .for_each(|(index, comm)| {
-                    new_running_oracle
-                        .iter_mut()
-                        .zip_eq(field_type_iter_ext(&comm.get_codewords()[0]))
-                        .for_each(|(r, a)| *r += a * coeffs[index]);
-                });
+                .collect_vec();
+            if !matching_comms.is_empty() {
+                running_tree_leaves = Some(new_running_oracle.clone());
+            }
+            matching_comms.into_iter().for_each(|(index, comm)| {
+                new_running_oracle
+                    .iter_mut()
+                    .zip_eq(comm.batch_codewords(&coeffs[index]))
+                    .for_each(|(r, a)| *r += a);
+            });
             running_oracle = new_running_oracle;
         } else {
             // Clear the value so the compiler does not think they are moved
@@ -23,14 +23,15 @@ pub trait EncodingProverParameters {
 }
 
 pub trait EncodingScheme<E: ExtensionField>: std::fmt::Debug + Clone {
-    type PublicParameters: Clone + std::fmt::Debug + Serialize + DeserializeOwned;
+    type PublicParameters: Clone + std::fmt::Debug + Serialize + DeserializeOwned + Send + Sync;
     type ProverParameters: Clone
         + std::fmt::Debug
         + Serialize
         + DeserializeOwned
         + EncodingProverParameters
+        + Send
         + Sync;
-    type VerifierParameters: Clone + std::fmt::Debug + Serialize + DeserializeOwned + Sync;
+    type VerifierParameters: Clone + std::fmt::Debug + Serialize + DeserializeOwned + Send + Sync;
 
     fn setup(max_msg_size_log: usize) -> Self::PublicParameters;
 
@@ -67,7 +67,7 @@ where
 pub fn batch_prover_query_phase<E: ExtensionField>(
     transcript: &mut impl Transcript<E>,
     codeword_size: usize,
-    comms: &[BasefoldCommitmentWithData<E>],
+    comms: &[&BasefoldCommitmentWithData<E>],
     trees: &[MerkleTree<E>],
     num_verifier_queries: usize,
 ) -> BatchedQueriesResult<E>
@@ -221,7 +221,7 @@ pub fn batch_verifier_query_phase<E: ExtensionField, Spec: BasefoldSpec<E>>(
     final_message: &[E],
     roots: &[Digest<E::BaseField>],
     comms: &[&BasefoldCommitment<E>],
-    coeffs: &[E],
+    coeffs: &[Vec<E>],
     partial_eq: &[E],
     eval: &E,
 ) where
@@ -411,7 +411,7 @@ where
 }
 
 fn batch_basefold_get_query<E: ExtensionField>(
-    comms: &[BasefoldCommitmentWithData<E>],
+    comms: &[&BasefoldCommitmentWithData<E>],
     trees: &[MerkleTree<E>],
     codeword_size: usize,
     x_index: usize,
@@ -443,13 +443,30 @@ where
             let x_index = x_index >> (log2_strict(codeword_size) - comm.codeword_size_log());
             let p1 = x_index | 1;
             let p0 = p1 - 1;
-            match &comm.get_codewords()[0] {
-                FieldType::Ext(poly_codeword) => {
-                    CodewordSingleQueryResult::new_ext(poly_codeword[p0], poly_codeword[p1], p0)
-                }
-                FieldType::Base(poly_codeword) => {
-                    CodewordSingleQueryResult::new_base(poly_codeword[p0], poly_codeword[p1], p0)
-                }
+            let poly_codewords = comm.get_codewords();
+            match poly_codewords[0] {
+                FieldType::Ext(_) => SimpleBatchCommitmentSingleQueryResult::new_ext(
+                    poly_codewords
+                        .iter()
+                        .map(|c| field_type_index_ext(c, p0))
+                        .collect(),
+                    poly_codewords
+                        .iter()
+                        .map(|c| field_type_index_ext(c, p1))
+                        .collect(),
+                    p0,
+                ),
+                FieldType::Base(_) => SimpleBatchCommitmentSingleQueryResult::new_base(
+                    poly_codewords
+                        .iter()
+                        .map(|c| field_type_index_base(c, p0))
+                        .collect(),
+                    poly_codewords
+                        .iter()
+                        .map(|c| field_type_index_base(c, p1))
+                        .collect(),
+                    p0,
+                ),
                 _ => unreachable!(),
             }
         })
@@ -666,12 +683,13 @@ where
     inner: Vec<CodewordSingleQueryResult<E>>,
 }
 
+/// The leaves of all the polynomials of each commitment, at a query.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CommitmentsQueryResult<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    inner: Vec<CodewordSingleQueryResult<E>>,
+    inner: Vec<SimpleBatchCommitmentSingleQueryResult<E>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -687,23 +705,42 @@ struct CommitmentsQueryResultWithMerklePath<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    inner: Vec<CodewordSingleQueryResultWithMerklePath<E>>,
+    inner: Vec<SimpleBatchCommitmentSingleQueryResultWithMerklePath<E>>,
 }
 
-impl<E: ExtensionField> ListQueryResult<E> for OracleListQueryResult<E>
+impl<E: ExtensionField> CommitmentsQueryResultWithMerklePath<E>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
-    fn get_inner(&self) -> &Vec<CodewordSingleQueryResult<E>> {
-        &self.inner
+    fn from_query_result(
+        query_result: CommitmentsQueryResult<E>,
+        commitments: &[&BasefoldCommitmentWithData<E>],
+    ) -> Self {
+        Self {
+            inner: query_result
+                .inner
+                .into_iter()
+                .zip_eq(commitments)
+                .map(
+                    |(query, commitment)| SimpleBatchCommitmentSingleQueryResultWithMerklePath {
+                        merkle_path: commitment
+                            .codeword_tree
+                            .merkle_path_without_leaf_sibling_or_root(query.index),
+                        query,
+                    },
+                )
+                .collect(),
+        }
     }
 
-    fn get_inner_into(self) -> Vec<CodewordSingleQueryResult<E>> {
-        self.inner
+    fn check_merkle_paths(&self, roots: &[Digest<E::BaseField>]) {
+        self.inner.iter().zip(roots.iter()).for_each(|(q, root)| {
+            q.check_merkle_path(root);
+        });
     }
 }
 
-impl<E: ExtensionField> ListQueryResult<E> for CommitmentsQueryResult<E>
+impl<E: ExtensionField> ListQueryResult<E> for OracleListQueryResult<E>
 where
     E::BaseField: Serialize + DeserializeOwned,
 {
@@ -729,19 +766,6 @@ where
     }
 }
 
-impl<E: ExtensionField> ListQueryResultWithMerklePath<E> for CommitmentsQueryResultWithMerklePath<E>
-where
-    E::BaseField: Serialize + DeserializeOwned,
-{
-    fn get_inner(&self) -> &Vec<CodewordSingleQueryResultWithMerklePath<E>> {
-        &self.inner
-    }
-
-    fn new(inner: Vec<CodewordSingleQueryResultWithMerklePath<E>>) -> Self {
-        Self { inner }
-    }
-}
-
 trait ListQueryResult<E: ExtensionField>
 where
     E::BaseField: Serialize + DeserializeOwned,
@@ -1002,20 +1026,16 @@ where
     pub fn from_batched_single_query_result(
         batched_single_query_result: BatchedSingleQueryResult<E>,
         oracle_trees: &[MerkleTree<E>],
-        commitments: &[BasefoldCommitmentWithData<E>],
+        commitments: &[&BasefoldCommitmentWithData<E>],
     ) -> Self {
         Self {
             oracle_query: OracleListQueryResultWithMerklePath::from_query_and_trees(
                 batched_single_query_result.oracle_query,
                 |i, j| oracle_trees[i].merkle_path_without_leaf_sibling_or_root(j),
             ),
-            commitments_query: CommitmentsQueryResultWithMerklePath::from_query_and_trees(
+            commitments_query: CommitmentsQueryResultWithMerklePath::from_query_result(
                 batched_single_query_result.commitments_query,
-                |i, j| {
-                    commitments[i]
-                        .codeword_tree
-                        .merkle_path_without_leaf_sibling_or_root(j)
-                },
+                commitments,
             ),
         }
     }
@@ -1030,7 +1050,7 @@ where
         final_codeword: &[E],
         roots: &[Digest<E::BaseField>],
         comms: &[&BasefoldCommitment<E>],
-        coeffs: &[E],
+        coeffs: &[Vec<E>],
         index: usize,
     ) {
         self.oracle_query.check_merkle_paths(roots);
@@ -1059,10 +1079,11 @@ where
                 .collect_vec();
 
             matching_comms.iter().for_each(|index| {
-                let query = self.commitments_query.get_inner()[*index].query;
+                let query = &self.commitments_query.inner[*index].query;
                 assert_eq!(query.index >> 1, left_index >> 1);
-                curr_left += query.left_ext() * coeffs[*index];
-                curr_right += query.right_ext() * coeffs[*index];
+                let (left, right) = query.leaves.batch(&coeffs[*index]);
+                curr_left += left;
+                curr_right += right;
             });
 
             let (x0, x1, w) = Spec::EncodingScheme::verifier_folding_coeffs(
@@ -1104,13 +1125,13 @@ where
                     .collect_vec();
 
                 matching_comms.iter().for_each(|index| {
-                    let query: CodewordSingleQueryResult<E> =
-                        self.commitments_query.get_inner()[*index].query;
+                    let query = &self.commitments_query.inner[*index].query;
                     assert_eq!(query.index >> 1, next_index >> 1);
+                    let (left, right) = query.leaves.batch(&coeffs[*index]);
                     if next_index & 1 == 0 {
-                        res += query.left_ext() * coeffs[*index];
+                        res += left;
                     } else {
-                        res += query.right_ext() * coeffs[*index];
+                        res += right;
                     }
                 });
 
@@ -1147,7 +1168,7 @@ where
     pub fn from_batched_query_result(
         batched_query_result: BatchedQueriesResult<E>,
         oracle_trees: &[MerkleTree<E>],
-        commitments: &[BasefoldCommitmentWithData<E>],
+        commitments: &[&BasefoldCommitmentWithData<E>],
     ) -> Self {
         Self {
             inner: batched_query_result
@@ -1178,7 +1199,7 @@ where
         final_codeword: &[E],
         roots: &[Digest<E::BaseField>],
         comms: &[&BasefoldCommitment<E>],
-        coeffs: &[E],
+        coeffs: &[Vec<E>],
     ) {
         let timer = start_timer!(|| "BatchedQueriesResult::check");
         self.inner.par_iter().zip(indices.par_iter()).for_each(
@@ -109,13 +109,13 @@ where
 }
 
 pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
-    type Param: Clone + Debug + Serialize + DeserializeOwned;
-    type ProverParam: Clone + Debug + Serialize + DeserializeOwned;
-    type VerifierParam: Clone + Debug + Serialize + DeserializeOwned;
-    type CommitmentWithData: Clone + Debug + Default + Serialize + DeserializeOwned;
-    type Commitment: Clone + Debug + Default + Serialize + DeserializeOwned;
+    type Param: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
+    type ProverParam: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
+    type VerifierParam: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
+    type CommitmentWithData: Clone + Debug + Default + Serialize + DeserializeOwned + Send + Sync;
+    type Commitment: Clone + Debug + Default + Serialize + DeserializeOwned + Send + Sync;
     type CommitmentChunk: Clone + Debug + Default;
-    type Proof: Clone + Debug + Serialize + DeserializeOwned;
+    type Proof: Clone + Debug + Serialize + DeserializeOwned + Send + Sync;
 
     fn setup(poly_size: usize) -> Result<Self::Param, Error>;
 
@@ -146,6 +146,10 @@ pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
 
     fn get_pure_commitment(comm: &Self::CommitmentWithData) -> Self::Commitment;
 
+    /// The polynomials committed to in `comm`, in the order they were committed, so that a
+    /// prover short on memory does not have to keep its own copy.
+    fn get_committed_polys(comm: &Self::CommitmentWithData) -> Vec<DenseMultilinearExtension<E>>;
+
     fn batch_commit(
         pp: &Self::ProverParam,
         polys: &[DenseMultilinearExtension<E>],
@@ -192,6 +196,19 @@ pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
         transcript: &mut impl Transcript<E>,
     ) -> Result<Self::Proof, Error>;
 
+    /// Open several commitments, each of several polynomials, in one proof:
+    /// 1. Each commitment is opened at its own point, `points[i]` for `comms[i]`.
+    /// 2. `evals[i]` are the evaluations of the polynomials of `comms[i]`, in order.
+    /// 3. The points are already random points generated by sum-checks, and the evaluations
+    ///    are already written to the transcript.
+    fn multi_batch_open(
+        pp: &Self::ProverParam,
+        comms: &[&Self::CommitmentWithData],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        transcript: &mut impl Transcript<E>,
+    ) -> Result<Self::Proof, Error>;
+
     fn verify(
         vp: &Self::VerifierParam,
         comm: &Self::Commitment,
@@ -218,6 +235,47 @@ pub trait PolynomialCommitmentScheme<E: ExtensionField>: Clone + Debug {
         proof: &Self::Proof,
         transcript: &mut impl Transcript<E>,
     ) -> Result<(), Error>;
+
+    fn multi_batch_verify(
+        vp: &Self::VerifierParam,
+        comms: &[Self::Commitment],
+        points: &[Vec<E>],
+        evals: &[Vec<E>],
+        proof: &Self::Proof,
+        transcript: &mut impl Transcript<E>,
+    ) -> Result<(), Error>;
+
+    /// Verify the openings of several independent `multi_batch_open` proofs, each on its own
+    /// transcript, and return the result of each, in order. The default verifies them one after
+    /// the other; a scheme whose checks combine across openings verifies them at once.
+    fn multi_batch_verify_all<T: Transcript<E> + Send>(
+        vp: &Self::VerifierParam,
+        openings: Vec<MultiBatchOpening<'_, E, Self, T>>,
+    ) -> Vec<Result<(), Error>> {
+        openings
+            .into_iter()
+            .map(|mut opening| {
+                Self::multi_batch_verify(
+                    vp,
+                    &opening.comms,
+                    &opening.points,
+                    &opening.evals,
+                    opening.proof,
+                    &mut opening.transcript,
+                )
+            })
+            .collect()
+    }
+}
+
+/// The claims of a proof of [`PolynomialCommitmentScheme::multi_batch_open`], with the
+/// transcript to verify it on, see [`PolynomialCommitmentScheme::multi_batch_verify_all`].
+pub struct MultiBatchOpening<'a, E: ExtensionField, Pcs: PolynomialCommitmentScheme<E>, T> {
+    pub comms: Vec<Pcs::Commitment>,
+    pub points: Vec<Vec<E>>,
+    pub evals: Vec<Vec<E>>,
+    pub proof: &'a Pcs::Proof,
+    pub transcript: T,
 }
 
 pub trait NoninteractivePCS<E: ExtensionField>:
@@ -298,17 +356,27 @@ impl<F> Evaluation<F> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
 pub enum Error {
+    #[error("invalid sumcheck: {0}")]
     InvalidSumcheck(String),
+    #[error("invalid PCS parameters: {0}")]
     InvalidPcsParam(String),
+    #[error("invalid PCS opening: {0}")]
     InvalidPcsOpen(String),
+    #[error("invalid SNARK: {0}")]
     InvalidSnark(String),
+    #[error("serialization: {0}")]
     Serialization(String),
+    #[error("transcript: {0}")]
     Transcript(String),
+    #[error("extension field element does not fit in the base field")]
     ExtensionFieldElementNotFit,
+    #[error("polynomial of {0} variables too large")]
     PolynomialTooLarge(usize),
+    #[error("polynomials of different sizes")]
     PolynomialSizesNotEqual,
+    #[error("Merkle root mismatch")]
     MerkleRootMismatch,
 }
 
@@ -366,9 +434,9 @@ fn err_too_many_variates(function: &str, upto: usize, got: usize) -> Error {
 // compiled in the release build. Need a better solution.
 #[doc(hidden)]
 pub mod test_util {
-    #[cfg(test)]
-    use crate::Evaluation;
     use crate::PolynomialCommitmentScheme;
+    #[cfg(test)]
+    use crate::{Evaluation, MultiBatchOpening};
     use ff_ext::ExtensionField;
     use itertools::Itertools;
     #[cfg(test)]
@@ -617,4 +685,101 @@ pub mod test_util {
             }
         }
     }
+
+    #[cfg(test)]
+    pub(super) fn run_multi_batch_commit_open_verify<E, Pcs>(
+        gen_rand_poly: fn(usize) -> DenseMultilinearExtension<E>,
+        num_vars: &[usize],
+        batch_size: usize,
+    ) where
+        E: ExtensionField,
+        Pcs: PolynomialCommitmentScheme<E>,
+    {
+        let (pp, vp) = setup_pcs::<E, Pcs>(*num_vars.iter().max().unwrap());
+
+        let (comms, evals, proof, challenge) = {
+            let mut transcript = BasicTranscript::new(b"BaseFold");
+            let polys = num_vars
+                .iter()
+                .map(|num_vars| gen_rand_polys(|_| *num_vars, batch_size, gen_rand_poly))
+                .collect_vec();
+            let comms = polys
+                .iter()
+                .map(|polys| {
+                    Pcs::batch_commit_and_write(&pp, polys.as_slice(), &mut transcript).unwrap()
+                })
+                .collect_vec();
+            let points = num_vars
+                .iter()
+                .map(|num_vars| get_point_from_challenge(*num_vars, &mut transcript))
+                .collect_vec();
+            let evals = polys
+                .iter()
+                .zip(&points)
+                .map(|(polys, point)| polys.iter().map(|poly| poly.evaluate(point)).collect_vec())
+                .collect_vec();
+            evals
+                .iter()
+                .for_each(|evals| transcript.append_field_element_exts(evals));
+
+            let proof = Pcs::multi_batch_open(
+                &pp,
+                &comms.iter().collect_vec(),
+                &points,
+                &evals,
+                &mut transcript,
+            )
+            .unwrap();
+            (
+                comms.iter().map(Pcs::get_pure_commitment).collect_vec(),
+                evals,
+                proof,
+                transcript.read_challenge(),
+            )
+        };
+        // the transcript of the verifier, up to the opening, with the points
+        let replay = |evals: &[Vec<E>]| {
+            let mut transcript = BasicTranscript::new(b"BaseFold");
+            comms
+                .iter()
+                .for_each(|comm| Pcs::write_commitment(comm, &mut transcript).unwrap());
+            let points = num_vars
+                .iter()
+                .map(|num_vars| get_point_from_challenge(*num_vars, &mut transcript))
+                .collect_vec();
+            evals
+                .iter()
+                .for_each(|evals| transcript.append_field_element_exts(evals));
+            (transcript, points)
+        };
+        // Batch verify
+        {
+            let (mut transcript, points) = replay(&evals);
+            Pcs::multi_batch_verify(&vp, &comms, &points, &evals, &proof, &mut transcript).unwrap();
+
+            let v_challenge = transcript.read_challenge();
+            assert_eq!(challenge, v_challenge);
+        }
+        // Verify along with an opening of a wrong evaluation
+        {
+            let mut wrong_evals = evals.clone();
+            wrong_evals[0][0] += E::ONE;
+            let openings = [evals, wrong_evals]
+                .into_iter()
+                .map(|evals| {
+                    let (transcript, points) = replay(&evals);
+                    MultiBatchOpening::<E, Pcs, _> {
+                        comms: comms.clone(),
+                        points,
+                        evals,
+                        proof: &proof,
+                        transcript,
+                    }
+                })
+                .collect_vec();
+            let verified = Pcs::multi_batch_verify_all(&vp, openings);
+            assert!(verified[0].is_ok());
+            assert!(verified[1].is_err());
+        }
+    }
 }
@@ -0,0 +1,294 @@
+//! The hot kernels of the prover behind a device abstraction, so that an accelerator backend can
+//! take them over while [`Cpu`] stays the default. A backend must agree with [`Cpu`] bit for bit.
+//!
+//! The `cuda` feature adds the `Cuda` device, over Goldilocks.
+
+use alloc::{vec, vec::Vec};
+use ff::Field;
+use ff_ext::ExtensionField;
+
+use crate::{
+    mle::{FieldType, MultilinearExtension},
+    par::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+        ParallelSlice,
+    },
+};
+
+#[cfg(feature = "cuda")]
+mod cuda;
+#[cfg(feature = "cuda")]
+pub use cuda::Cuda;
+
+/// The evaluations of an MLE, borrowed.
+#[derive(Clone, Copy, Debug)]
+pub enum Evals<'a, E: ExtensionField> {
+    Base(&'a [E::BaseField]),
+    Ext(&'a [E]),
+}
+
+impl<'a, E: ExtensionField> Evals<'a, E> {
+    /// The evaluations of `mle`, restricted to its range if it has one.
+    pub fn of<M: MultilinearExtension<E> + ?Sized>(mle: &'a M) -> Self {
+        let (start, len) = mle
+            .evaluations_range()
+            .unwrap_or((0, mle.evaluations().len()));
+        match mle.evaluations() {
+            FieldType::Base(evals) => Evals::Base(&evals[start..][..len]),
+            FieldType::Ext(evals) => Evals::Ext(&evals[start..][..len]),
+            FieldType::Unreachable => unreachable!(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Evals::Base(evals) => evals.len(),
+            Evals::Ext(evals) => evals.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The evaluation `i`, in the extension field.
+    pub fn get(&self, i: usize) -> E {
+        match self {
+            Evals::Base(evals) => E::from(evals[i]),
+            Evals::Ext(evals) => evals[i],
+        }
+    }
+}
+
+impl<'a, E: ExtensionField> From<&'a FieldType<E>> for Evals<'a, E> {
+    fn from(evals: &'a FieldType<E>) -> Self {
+        match evals {
+            FieldType::Base(evals) => Evals::Base(evals),
+            FieldType::Ext(evals) => Evals::Ext(evals),
+            FieldType::Unreachable => unreachable!(),
+        }
+    }
+}
+
+pub trait Device<E: ExtensionField>: Send + Sync {
+    /// Fix the lowest variable of the evaluations `evals` at `r`:
+    /// `out[b] = evals[2b] + r * (evals[2b + 1] - evals[2b])`.
+    fn fold(&self, evals: Evals<'_, E>, r: E) -> Vec<E>;
+
+    /// [`Device::fold`] into the first half of `evals`, which is then truncated to it.
+    fn fold_in_place(&self, evals: &mut Vec<E>, r: E) {
+        *evals = self.fold(Evals::Ext(evals), r);
+    }
+
+    /// Evaluate the evaluations `evals` at `point`, fixing the lowest variable first.
+    fn evaluate(&self, evals: Evals<'_, E>, point: &[E]) -> E {
+        assert_eq!(
+            evals.len(),
+            1 << point.len(),
+            "MLE size does not match the point"
+        );
+        let Some((r, point)) = point.split_first() else {
+            return evals.get(0);
+        };
+        let evals = point.iter().fold(self.fold(evals, *r), |evals, r| {
+            self.fold(Evals::Ext(&evals), *r)
+        });
+        evals[0]
+    }
+
+    /// The evaluations at `0, 1, ..., mles.len()` of the sumcheck round polynomial
+    /// `X -> \sum_b \prod_i mles[i](X, b)`, over the lowest variable `X`.
+    fn sumcheck_round(&self, mles: &[Evals<'_, E>]) -> Vec<E>;
+
+    /// The products `a[i] * b[i]` of base and extension field elements.
+    fn mul_base_ext(&self, a: &[E::BaseField], b: &[E]) -> Vec<E>;
+}
+
+/// The default device, on rayon's thread pool, or sequential without the `std` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cpu;
+
+impl<E: ExtensionField> Device<E> for Cpu {
+    fn fold(&self, evals: Evals<'_, E>, r: E) -> Vec<E> {
+        match evals {
+            Evals::Base(evals) => evals
+                .par_chunks(2)
+                .with_min_len(64)
+                .map(|buf| r * (buf[1] - buf[0]) + buf[0])
+                .collect(),
+            Evals::Ext(evals) => evals
+                .par_chunks(2)
+                .with_min_len(64)
+                .map(|buf| r * (buf[1] - buf[0]) + buf[0])
+                .collect(),
+        }
+    }
+
+    fn fold_in_place(&self, evals: &mut Vec<E>, r: E) {
+        for b in (0..evals.len()).step_by(2) {
+            evals[b >> 1] = r * (evals[b + 1] - evals[b]) + evals[b];
+        }
+        evals.truncate(evals.len() >> 1);
+    }
+
+    fn sumcheck_round(&self, mles: &[Evals<'_, E>]) -> Vec<E> {
+        let len = mles[0].len();
+        assert!(mles.iter().all(|mle| mle.len() == len) && len % 2 == 0);
+        // products of base field MLEs stay in the base field
+        let base = mles
+            .iter()
+            .map(|mle| match mle {
+                Evals::Base(evals) => Some(*evals),
+                Evals::Ext(_) => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        match base {
+            Some(base) => round_sums(mles.len(), len, |i, j| base[i][j])
+                .into_iter()
+                .map(E::from)
+                .collect(),
+            None => round_sums(mles.len(), len, |i, j| mles[i].get(j)),
+        }
+    }
+
+    fn mul_base_ext(&self, a: &[E::BaseField], b: &[E]) -> Vec<E> {
+        assert_eq!(a.len(), b.len());
+        a.par_iter()
+            .zip(b.par_iter())
+            .with_min_len(64)
+            .map(|(a, b)| *b * *a)
+            .collect()
+    }
+}
+
+/// The sums over `b` of the products of `num_mles` MLEs of `len` evaluations at `X = 0, 1, ...,
+/// num_mles`, where `at(i, j)` is the evaluation `j` of the MLE `i`.
+fn round_sums<F: Field>(
+    num_mles: usize,
+    len: usize,
+    at: impl Fn(usize, usize) -> F + Sync,
+) -> Vec<F> {
+    (0..len / 2)
+        .into_par_iter()
+        .with_min_len(64)
+        .fold(
+            || (vec![F::ZERO; num_mles + 1], vec![F::ONE; num_mles + 1]),
+            |(mut sums, mut products), b| {
+                products.fill(F::ONE);
+                for i in 0..num_mles {
+                    let (lo, hi) = (at(i, 2 * b), at(i, 2 * b + 1));
+                    // a product is zero on the whole round where a factor is, e.g. a selector on
+                    // the padded instances
+                    if lo.is_zero_vartime() && hi.is_zero_vartime() {
+                        return (sums, products);
+                    }
+                    // evaluate the MLE at X = 0, 1, ..., num_mles by stepping the difference
+                    let step = hi - lo;
+                    let mut value = lo;
+                    for product in products.iter_mut() {
+                        *product *= value;
+                        value += step;
+                    }
+                }
+                sums.iter_mut()
+                    .zip(&products)
+                    .for_each(|(sum, p)| *sum += p);
+                (sums, products)
+            },
+        )
+        .map(|(sums, _)| sums)
+        .reduce(
+            || vec![F::ZERO; num_mles + 1],
+            |mut acc, sums| {
+                acc.iter_mut().zip(sums).for_each(|(acc, s)| *acc += s);
+                acc
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mle::DenseMultilinearExtension;
+    use ark_std::test_rng;
+    use goldilocks::{Goldilocks, GoldilocksExt2 as E};
+
+    #[test]
+    fn test_cpu_device() {
+        let mut rng = test_rng();
+        let num_vars = 6;
+        let base = (0..1 << num_vars)
+            .map(|_| Goldilocks::random(&mut rng))
+            .collect::<Vec<_>>();
+        let ext = (0..1 << num_vars)
+            .map(|_| E::random(&mut rng))
+            .collect::<Vec<_>>();
+        let point = (0..num_vars)
+            .map(|_| E::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        let base_mle = DenseMultilinearExtension::<E>::from_evaluations_slice(num_vars, &base);
+        let ext_mle = DenseMultilinearExtension::from_evaluations_ext_slice(num_vars, &ext);
+        for mle in [&base_mle, &ext_mle] {
+            let folded = mle.fix_variables(&point[..1]);
+            assert_eq!(
+                Cpu.fold(Evals::of(mle), point[0]),
+                folded.get_ext_field_vec()
+            );
+            let mut folded_in_place = Cpu.fold(Evals::of(mle), point[0]);
+            Cpu.fold_in_place(&mut folded_in_place, point[1]);
+            assert_eq!(
+                folded_in_place,
+                folded.fix_variables(&point[1..2]).get_ext_field_vec()
+            );
+            assert_eq!(Cpu.evaluate(Evals::of(mle), &point), mle.evaluate(&point));
+        }
+
+        let products = Cpu.mul_base_ext(&base, &ext);
+        assert_eq!(
+            products,
+            base.iter()
+                .zip(&ext)
+                .map(|(a, b)| *b * *a)
+                .collect::<Vec<_>>()
+        );
+
+        // the round polynomial of a product of two MLEs, of degree 2
+        let round = Cpu.sumcheck_round(&[Evals::Ext(&ext), Evals::Ext(&products)]);
+        assert_eq!(round.len(), 3);
+        let sum = ext.iter().zip(&products).map(|(a, b)| *a * b).sum::<E>();
+        assert_eq!(round[0] + round[1], sum);
+        let r = point[0];
+        let (ext_r, products_r) = (
+            Cpu.fold(Evals::Ext(&ext), r),
+            Cpu.fold(Evals::Ext(&products), r),
+        );
+        let sum_r = ext_r
+            .iter()
+            .zip(&products_r)
+            .map(|(a, b)| *a * b)
+            .sum::<E>();
+        // interpolate the degree 2 round polynomial at r
+        let lagrange = |i: usize| {
+            (0..3)
+                .filter(|j| *j != i)
+                .map(|j| {
+                    (r - E::from(j as u64))
+                        * (E::from(i as u64) - E::from(j as u64)).invert().unwrap()
+                })
+                .product::<E>()
+        };
+        assert_eq!((0..3).map(|i| round[i] * lagrange(i)).sum::<E>(), sum_r);
+
+        // the base field and mixed products agree with the extension field ones
+        let base_ext = base.iter().map(|a| E::from(*a)).collect::<Vec<_>>();
+        assert_eq!(
+            Cpu.sumcheck_round(&[Evals::<E>::Base(&base), Evals::Base(&base)]),
+            Cpu.sumcheck_round(&[Evals::Ext(&base_ext), Evals::Ext(&base_ext)])
+        );
+        assert_eq!(
+            Cpu.sumcheck_round(&[Evals::Base(&base), Evals::Ext(&ext)]),
+            Cpu.sumcheck_round(&[Evals::Ext(&base_ext), Evals::Ext(&ext)])
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! The CUDA device, over [`GoldilocksExt2`]. Each kernel copies its inputs to the GPU and its
+//! outputs back, so it pays off on large MLEs only.
+
+use std::sync::Arc;
+
+use cudarc::{
+    driver::{CudaDevice, CudaFunction, CudaSlice, DriverError, LaunchAsync, LaunchConfig},
+    nvrtc::compile_ptx,
+};
+use ff::Field;
+use ff_ext::ExtensionField;
+use goldilocks::{Goldilocks, GoldilocksExt2, SmallField};
+
+use super::{Device, Evals};
+
+const MODULE: &str = "ceno_mle";
+const KERNELS: [&str; 4] = ["fold_base", "fold_ext", "mul_base_ext", "sumcheck_round"];
+const BLOCK_SIZE: u32 = 256;
+const MAX_BLOCKS: u32 = 1024;
+
+/// The device on a CUDA GPU. Its kernels are compiled with NVRTC when it is created.
+pub struct Cuda {
+    device: Arc<CudaDevice>,
+    /// `w` with `x^2 = w` in the extension field.
+    w: u64,
+}
+
+impl Cuda {
+    /// The device on the GPU `ordinal`.
+    pub fn new(ordinal: usize) -> Result<Self, DriverError> {
+        let device = CudaDevice::new(ordinal)?;
+        let ptx = compile_ptx(include_str!("kernels.cu")).expect("the CUDA kernels compile");
+        device.load_ptx(ptx, MODULE, &KERNELS)?;
+        let x = GoldilocksExt2::from_bases(&[Goldilocks::ZERO, Goldilocks::ONE]);
+        let w = (x * x).as_bases()[0].to_canonical_u64();
+        Ok(Cuda { device, w })
+    }
+
+    fn kernel(&self, name: &str) -> CudaFunction {
+        self.device.get_func(MODULE, name).unwrap()
+    }
+
+    /// A grid striding over `n` elements.
+    fn config(n: usize) -> LaunchConfig {
+        let blocks = n
+            .div_ceil(BLOCK_SIZE as usize)
+            .clamp(1, MAX_BLOCKS as usize) as u32;
+        LaunchConfig {
+            grid_dim: (blocks, 1, 1),
+            block_dim: (BLOCK_SIZE, 1, 1),
+            shared_mem_bytes: 0,
+        }
+    }
+
+    fn upload(&self, values: &[u64]) -> CudaSlice<u64> {
+        self.device.htod_sync_copy(values).expect("copy to the GPU")
+    }
+
+    fn alloc(&self, len: usize) -> CudaSlice<u64> {
+        self.device.alloc_zeros(len).expect("GPU allocation")
+    }
+
+    fn download(&self, values: &CudaSlice<u64>) -> Vec<GoldilocksExt2> {
+        let values = self
+            .device
+            .dtoh_sync_copy(values)
+            .expect("copy from the GPU");
+        values
+            .chunks_exact(2)
+            .map(|e| GoldilocksExt2::from_bases(&[Goldilocks::from(e[0]), Goldilocks::from(e[1])]))
+            .collect()
+    }
+}
+
+fn base_to_u64(evals: &[Goldilocks]) -> Vec<u64> {
+    evals.iter().map(|e| e.to_canonical_u64()).collect()
+}
+
+fn ext_to_u64(evals: &[GoldilocksExt2]) -> Vec<u64> {
+    evals
+        .iter()
+        .flat_map(|e| e.as_bases().iter().map(|e| e.to_canonical_u64()))
+        .collect()
+}
+
+impl Device<GoldilocksExt2> for Cuda {
+    fn fold(&self, evals: Evals<'_, GoldilocksExt2>, r: GoldilocksExt2) -> Vec<GoldilocksExt2> {
+        let n = evals.len() / 2;
+        let [r0, r1] = [0, 1].map(|i| r.as_bases()[i].to_canonical_u64());
+        let mut out = self.alloc(2 * n);
+        let launched = match evals {
+            Evals::Base(evals) => {
+                let evals = self.upload(&base_to_u64(evals));
+                unsafe {
+                    self.kernel("fold_base")
+                        .launch(Self::config(n), (&evals, r0, r1, &mut out, n as u64))
+                }
+            }
+            Evals::Ext(evals) => {
+                let evals = self.upload(&ext_to_u64(evals));
+                unsafe {
+                    self.kernel("fold_ext").launch(
+                        Self::config(n),
+                        (&evals, r0, r1, self.w, &mut out, n as u64),
+                    )
+                }
+            }
+        };
+        launched.expect("kernel launch");
+        self.download(&out)
+    }
+
+    fn sumcheck_round(&self, mles: &[Evals<'_, GoldilocksExt2>]) -> Vec<GoldilocksExt2> {
+        let len = mles[0].len();
+        assert!(mles.iter().all(|mle| mle.len() == len) && len % 2 == 0);
+        let evals = mles
+            .iter()
+            .flat_map(|mle| (0..len).map(|j| mle.get(j)))
+            .collect::<Vec<_>>();
+        let evals = self.upload(&ext_to_u64(&evals));
+        let config = Self::config(len / 2);
+        let threads = (config.grid_dim.0 * config.block_dim.0) as usize;
+        let num_evals = mles.len() + 1;
+        let mut out = self.alloc(2 * threads * num_evals);
+        let params = (&evals, mles.len() as u64, len as u64, self.w, &mut out);
+        let launched = unsafe { self.kernel("sumcheck_round").launch(config, params) };
+        launched.expect("kernel launch");
+        // the sums of each thread
+        let sums = self.download(&out);
+        (0..num_evals)
+            .map(|x| sums.iter().skip(x).step_by(num_evals).sum())
+            .collect()
+    }
+
+    fn mul_base_ext(&self, a: &[Goldilocks], b: &[GoldilocksExt2]) -> Vec<GoldilocksExt2> {
+        assert_eq!(a.len(), b.len());
+        let n = a.len();
+        let (a, b) = (self.upload(&base_to_u64(a)), self.upload(&ext_to_u64(b)));
+        let mut out = self.alloc(2 * n);
+        let params = (&a, &b, &mut out, n as u64);
+        let launched = unsafe { self.kernel("mul_base_ext").launch(Self::config(n), params) };
+        launched.expect("kernel launch");
+        self.download(&out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::test_rng;
+
+    use super::*;
+    use crate::device::Cpu;
+
+    #[test]
+    #[ignore = "needs a CUDA GPU"]
+    fn test_cuda_device() {
+        let cuda = Cuda::new(0).unwrap();
+        let mut rng = test_rng();
+        // more elements than threads in a launch, so that the threads stride
+        let num_vars = 20;
+        let base = (0..1 << num_vars)
+            .map(|_| Goldilocks::random(&mut rng))
+            .collect::<Vec<_>>();
+        let ext = (0..1 << num_vars)
+            .map(|_| GoldilocksExt2::random(&mut rng))
+            .collect::<Vec<_>>();
+        let point = (0..num_vars)
+            .map(|_| GoldilocksExt2::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        for evals in [Evals::Base(&base), Evals::Ext(&ext)] {
+            assert_eq!(cuda.fold(evals, point[0]), Cpu.fold(evals, point[0]));
+            assert_eq!(cuda.evaluate(evals, &point), Cpu.evaluate(evals, &point));
+        }
+        assert_eq!(
+            cuda.mul_base_ext(&base, &ext),
+            Cpu.mul_base_ext(&base, &ext)
+        );
+        let rounds: [&[Evals<'_, GoldilocksExt2>]; 3] = [
+            &[Evals::Base(&base)],
+            &[Evals::Base(&base), Evals::Ext(&ext)],
+            &[Evals::Ext(&ext), Evals::Ext(&ext), Evals::Base(&base)],
+        ];
+        for mles in rounds {
+            assert_eq!(cuda.sumcheck_round(mles), Cpu.sumcheck_round(mles));
+        }
+    }
+}
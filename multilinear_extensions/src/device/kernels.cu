@@ -0,0 +1,138 @@
+// The kernels of the CUDA device, over Goldilocks and its quadratic extension.
+//
+// Field elements are canonical u64s, and an extension field element is its two coordinates
+// `(a0, a1)` for `a0 + a1 * x`, where `x^2 = w`. The kernels loop over a grid stride, so any
+// launch covers all the elements.
+
+typedef unsigned int u32;
+typedef unsigned long long u64;
+
+#define P 0xFFFFFFFF00000001ULL
+// 2^64 mod P
+#define EPSILON 0xFFFFFFFFULL
+
+__device__ __forceinline__ u64 gl_add(u64 a, u64 b) {
+    u64 s = a + b;
+    if (s < a) {
+        // a + b = s + 2^64 = s + EPSILON mod P, and it is less than P
+        return s + EPSILON;
+    }
+    return s >= P ? s - P : s;
+}
+
+__device__ __forceinline__ u64 gl_sub(u64 a, u64 b) {
+    return a >= b ? a - b : a - b + P;
+}
+
+// Reduce lo + 2^64 * hi, with 2^64 = EPSILON and 2^96 = -1 mod P.
+__device__ __forceinline__ u64 gl_reduce128(u64 lo, u64 hi) {
+    u64 hi_hi = hi >> 32;
+    u64 hi_lo = hi & EPSILON;
+    u64 t0 = lo - hi_hi;
+    if (lo < hi_hi) {
+        t0 -= EPSILON;
+    }
+    u64 t1 = hi_lo * EPSILON;
+    u64 t2 = t0 + t1;
+    if (t2 < t1) {
+        t2 += EPSILON;
+    }
+    return t2 >= P ? t2 - P : t2;
+}
+
+__device__ __forceinline__ u64 gl_mul(u64 a, u64 b) {
+    return gl_reduce128(a * b, __umul64hi(a, b));
+}
+
+struct Ext {
+    u64 a0;
+    u64 a1;
+};
+
+__device__ __forceinline__ Ext ext_load(const u64* evals, u64 i) {
+    Ext e = {evals[2 * i], evals[2 * i + 1]};
+    return e;
+}
+
+__device__ __forceinline__ void ext_store(u64* out, u64 i, Ext e) {
+    out[2 * i] = e.a0;
+    out[2 * i + 1] = e.a1;
+}
+
+__device__ __forceinline__ Ext ext_add(Ext a, Ext b) {
+    Ext e = {gl_add(a.a0, b.a0), gl_add(a.a1, b.a1)};
+    return e;
+}
+
+__device__ __forceinline__ Ext ext_sub(Ext a, Ext b) {
+    Ext e = {gl_sub(a.a0, b.a0), gl_sub(a.a1, b.a1)};
+    return e;
+}
+
+__device__ __forceinline__ Ext ext_mul(Ext a, Ext b, u64 w) {
+    Ext e = {
+        gl_add(gl_mul(a.a0, b.a0), gl_mul(w, gl_mul(a.a1, b.a1))),
+        gl_add(gl_mul(a.a0, b.a1), gl_mul(a.a1, b.a0)),
+    };
+    return e;
+}
+
+__device__ __forceinline__ Ext ext_mul_base(Ext a, u64 b) {
+    Ext e = {gl_mul(a.a0, b), gl_mul(a.a1, b)};
+    return e;
+}
+
+#define GRID_STRIDE(i, n)                                                                          \
+    for (u64 i = (u64)blockIdx.x * blockDim.x + threadIdx.x; i < (n);                              \
+         i += (u64)blockDim.x * gridDim.x)
+
+// out[b] = r * (evals[2b + 1] - evals[2b]) + evals[2b], with base field `evals`.
+extern "C" __global__ void fold_base(const u64* evals, u64 r0, u64 r1, u64* out, u64 n) {
+    Ext r = {r0, r1};
+    GRID_STRIDE(b, n) {
+        u64 lo = evals[2 * b];
+        u64 hi = evals[2 * b + 1];
+        Ext e = ext_mul_base(r, gl_sub(hi, lo));
+        e.a0 = gl_add(e.a0, lo);
+        ext_store(out, b, e);
+    }
+}
+
+// out[b] = r * (evals[2b + 1] - evals[2b]) + evals[2b], with extension field `evals`.
+extern "C" __global__ void fold_ext(const u64* evals, u64 r0, u64 r1, u64 w, u64* out, u64 n) {
+    Ext r = {r0, r1};
+    GRID_STRIDE(b, n) {
+        Ext lo = ext_load(evals, 2 * b);
+        Ext hi = ext_load(evals, 2 * b + 1);
+        ext_store(out, b, ext_add(ext_mul(r, ext_sub(hi, lo), w), lo));
+    }
+}
+
+// out[i] = a[i] * b[i], with base field `a` and extension field `b`.
+extern "C" __global__ void mul_base_ext(const u64* a, const u64* b, u64* out, u64 n) {
+    GRID_STRIDE(i, n) {
+        ext_store(out, i, ext_mul_base(ext_load(b, i), a[i]));
+    }
+}
+
+// The sums over the `b` of each thread of `\prod_i mles[i](X, b)` at `X = 0, 1, ..., num_mles`,
+// into `out[thread][X]`. The `num_mles` MLEs of `len` extension field evaluations are laid out one
+// after the other in `mles`.
+extern "C" __global__ void sumcheck_round(
+    const u64* mles, u64 num_mles, u64 len, u64 w, u64* out
+) {
+    u64 thread = (u64)blockIdx.x * blockDim.x + threadIdx.x;
+    for (u64 x = 0; x <= num_mles; x++) {
+        Ext sum = {0, 0};
+        GRID_STRIDE(b, len / 2) {
+            Ext product = {1, 0};
+            for (u64 i = 0; i < num_mles; i++) {
+                Ext lo = ext_load(mles, i * len + 2 * b);
+                Ext hi = ext_load(mles, i * len + 2 * b + 1);
+                product = ext_mul(product, ext_add(ext_mul_base(ext_sub(hi, lo), x), lo), w);
+            }
+            sum = ext_add(sum, product);
+        }
+        ext_store(out, thread * (num_mles + 1) + x, sum);
+    }
+}
@@ -1,5 +1,11 @@
 #![deny(clippy::cargo)]
+//! Without the default `std` feature the crate is `no_std`, and its kernels run sequentially.
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+pub mod device;
 pub mod mle;
+mod par;
 pub mod util;
 pub mod virtual_poly;
 pub mod virtual_poly_v2;
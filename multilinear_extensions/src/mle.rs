@@ -1,15 +1,19 @@
-use std::{any::TypeId, borrow::Cow, mem, sync::Arc};
-
-use crate::{op_mle, util::ceil_log2};
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
+use core::{any::TypeId, fmt::Debug, hash::Hash, mem};
+
+use crate::{
+    device::{Device, Evals},
+    op_mle,
+    par::{
+        IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator,
+        ParallelIterator,
+    },
+    util::ceil_log2,
+};
 use ark_std::{end_timer, rand::RngCore, start_timer};
-use core::hash::Hash;
 use ff::Field;
 use ff_ext::ExtensionField;
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
 
 pub trait MultilinearExtension<E: ExtensionField>: Send + Sync {
     type Output;
@@ -40,6 +44,8 @@ pub trait MultilinearExtension<E: ExtensionField>: Send + Sync {
 
     fn fix_variables_parallel(&self, partial_point: &[E]) -> Self::Output;
     fn fix_variables_in_place_parallel(&mut self, partial_point: &[E]);
+    /// [`MultilinearExtension::fix_variables_in_place`] with the folds on `device`.
+    fn fix_variables_in_place_on(&mut self, device: &dyn Device<E>, partial_point: &[E]);
 
     fn name(&self) -> &'static str;
 
@@ -693,6 +699,26 @@ impl<E: ExtensionField> MultilinearExtension<E> for DenseMultilinearExtension<E>
         self.num_vars = nv - partial_point.len();
     }
 
+    fn fix_variables_in_place_on(&mut self, device: &dyn Device<E>, partial_point: &[E]) {
+        assert!(
+            partial_point.len() <= self.num_vars(),
+            "partial point len {} >= num_vars {}",
+            partial_point.len(),
+            self.num_vars()
+        );
+        for point in partial_point {
+            match &mut self.evaluations {
+                FieldType::Base(evaluations) => {
+                    let evaluations_ext = device.fold(Evals::Base(evaluations), *point);
+                    self.evaluations = FieldType::Ext(evaluations_ext);
+                }
+                FieldType::Ext(evaluations) => device.fold_in_place(evaluations, *point),
+                FieldType::Unreachable => unreachable!(),
+            }
+        }
+        self.num_vars -= partial_point.len();
+    }
+
     fn evaluations(&self) -> &FieldType<E> {
         &self.evaluations
     }
@@ -768,6 +794,7 @@ impl<E: ExtensionField> MultilinearExtension<E> for DenseMultilinearExtension<E>
         num_range: usize,
         range_index: usize,
     ) -> Self {
+        #[cfg(feature = "std")]
         println!("called deprecated api");
         assert!(num_range > 0 && num_instances > 0 && new_size_per_instance > 0);
         let new_len = (new_size_per_instance * num_instances) / num_range;
@@ -782,7 +809,7 @@ impl<E: ExtensionField> MultilinearExtension<E> for DenseMultilinearExtension<E>
                             chunk
                                 .iter()
                                 .cloned()
-                                .chain(std::iter::repeat(E::BaseField::ZERO))
+                                .chain(core::iter::repeat(E::BaseField::ZERO))
                                 .take(new_size_per_instance)
                         })
                         .skip(range_index * new_len)
@@ -927,6 +954,10 @@ impl<'a, E: ExtensionField> MultilinearExtension<E> for RangedMultilinearExtensi
         unimplemented!()
     }
 
+    fn fix_variables_in_place_on(&mut self, _device: &dyn Device<E>, _partial_point: &[E]) {
+        unimplemented!()
+    }
+
     fn evaluations(&self) -> &FieldType<E> {
         &self.inner.evaluations
     }
@@ -953,6 +984,7 @@ impl<'a, E: ExtensionField> MultilinearExtension<E> for RangedMultilinearExtensi
     }
 
     fn evaluations_to_owned(self) -> FieldType<E> {
+        #[cfg(feature = "std")]
         println!("FIXME: very expensive..");
         match &self.evaluations() {
             FieldType::Base(evaluations) => {
@@ -0,0 +1,150 @@
+//! The rayon traits used by the kernels, or without the `std` feature sequential stand-ins with
+//! the same method names, so that the kernels are written once.
+
+#[cfg(feature = "std")]
+pub(crate) use rayon::{
+    iter::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+        IntoParallelRefMutIterator, ParallelIterator,
+    },
+    slice::{ParallelSlice, ParallelSliceMut},
+};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use sequential::*;
+
+#[cfg(not(feature = "std"))]
+mod sequential {
+    use alloc::vec::Vec;
+    use core::{iter, ops::Range, slice};
+
+    /// A sequential iterator with the combinators of a rayon parallel iterator. It does not
+    /// implement [`Iterator`], whose methods of the same names would be ambiguous.
+    pub struct Seq<I>(I);
+
+    impl<I: Iterator> Seq<I> {
+        pub fn with_min_len(self, _min: usize) -> Self {
+            self
+        }
+
+        pub fn map<T>(self, f: impl FnMut(I::Item) -> T) -> Seq<impl Iterator<Item = T>> {
+            Seq(self.0.map(f))
+        }
+
+        pub fn zip<J: IntoIterator>(self, other: J) -> Seq<iter::Zip<I, J::IntoIter>> {
+            Seq(self.0.zip(other))
+        }
+
+        pub fn enumerate(self) -> Seq<iter::Enumerate<I>> {
+            Seq(self.0.enumerate())
+        }
+
+        /// The items in vectors of `size`, as rayon's `chunks`.
+        pub fn chunks(mut self, size: usize) -> Seq<impl Iterator<Item = Vec<I::Item>>> {
+            Seq(iter::from_fn(move || {
+                let chunk = self.0.by_ref().take(size).collect::<Vec<_>>();
+                (!chunk.is_empty()).then_some(chunk)
+            }))
+        }
+
+        /// Fold all the items into one accumulator, as one rayon split would.
+        pub fn fold<T>(
+            self,
+            identity: impl Fn() -> T,
+            f: impl FnMut(T, I::Item) -> T,
+        ) -> Seq<iter::Once<T>> {
+            Seq(iter::once(self.0.fold(identity(), f)))
+        }
+
+        pub fn reduce(
+            self,
+            identity: impl Fn() -> I::Item,
+            f: impl FnMut(I::Item, I::Item) -> I::Item,
+        ) -> I::Item {
+            self.0.fold(identity(), f)
+        }
+
+        pub fn for_each(self, f: impl FnMut(I::Item)) {
+            self.0.for_each(f)
+        }
+
+        pub fn collect<C: FromIterator<I::Item>>(self) -> C {
+            self.0.collect()
+        }
+
+        pub fn sum<S: iter::Sum<I::Item>>(self) -> S {
+            self.0.sum()
+        }
+    }
+
+    impl<I: Iterator> IntoIterator for Seq<I> {
+        type Item = I::Item;
+        type IntoIter = I;
+
+        fn into_iter(self) -> I {
+            self.0
+        }
+    }
+
+    pub trait ParallelIterator {}
+    pub trait IndexedParallelIterator {}
+
+    pub trait IntoParallelIterator {
+        type Iter: Iterator;
+        fn into_par_iter(self) -> Seq<Self::Iter>;
+    }
+
+    impl IntoParallelIterator for Range<usize> {
+        type Iter = Range<usize>;
+
+        fn into_par_iter(self) -> Seq<Range<usize>> {
+            Seq(self)
+        }
+    }
+
+    pub trait IntoParallelRefIterator<'a> {
+        type Item: 'a;
+        fn par_iter(&'a self) -> Seq<slice::Iter<'a, Self::Item>>;
+    }
+
+    impl<'a, T: 'a> IntoParallelRefIterator<'a> for [T] {
+        type Item = T;
+
+        fn par_iter(&'a self) -> Seq<slice::Iter<'a, T>> {
+            Seq(self.iter())
+        }
+    }
+
+    pub trait IntoParallelRefMutIterator<'a> {
+        type Item: 'a;
+        fn par_iter_mut(&'a mut self) -> Seq<slice::IterMut<'a, Self::Item>>;
+    }
+
+    impl<'a, T: 'a> IntoParallelRefMutIterator<'a> for [T] {
+        type Item = T;
+
+        fn par_iter_mut(&'a mut self) -> Seq<slice::IterMut<'a, T>> {
+            Seq(self.iter_mut())
+        }
+    }
+
+    pub trait ParallelSlice<T> {
+        fn par_chunks(&self, size: usize) -> Seq<slice::Chunks<'_, T>>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_chunks(&self, size: usize) -> Seq<slice::Chunks<'_, T>> {
+            Seq(self.chunks(size))
+        }
+    }
+
+    pub trait ParallelSliceMut<T> {
+        fn par_chunks_mut(&mut self, size: usize) -> Seq<slice::ChunksMut<'_, T>>;
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_chunks_mut(&mut self, size: usize) -> Seq<slice::ChunksMut<'_, T>> {
+            Seq(self.chunks_mut(size))
+        }
+    }
+}
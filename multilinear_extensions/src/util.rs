@@ -1,4 +1,5 @@
-use std::mem::MaybeUninit;
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
 
 /// Decompose an integer into a binary vector in little endian.
 pub fn bit_decompose(input: u64, num_var: usize) -> Vec<bool> {
@@ -16,7 +17,7 @@ pub fn bit_decompose(input: u64, num_var: usize) -> Vec<bool> {
 pub fn ceil_log2(x: usize) -> usize {
     assert!(x > 0, "ceil_log2: x must be positive");
     // Calculate the number of bits in usize
-    let usize_bits = std::mem::size_of::<usize>() * 8;
+    let usize_bits = core::mem::size_of::<usize>() * 8;
     usize_bits - (x - 1).leading_zeros() as usize
 }
 
@@ -31,11 +32,13 @@ pub fn largest_even_below(n: usize) -> usize {
     if n % 2 == 0 { n } else { n.saturating_sub(1) }
 }
 
+#[cfg(feature = "std")]
 fn prev_power_of_two(n: usize) -> usize {
     (n + 1).next_power_of_two() / 2
 }
 
 /// Largest power of two that fits the available rayon threads
+#[cfg(feature = "std")]
 pub fn max_usable_threads() -> usize {
     if cfg!(test) {
         1
@@ -48,3 +51,9 @@ pub fn max_usable_threads() -> usize {
         threads
     }
 }
+
+/// Without the `std` feature there is a single thread
+#[cfg(not(feature = "std"))]
+pub fn max_usable_threads() -> usize {
+    1
+}
@@ -1,17 +1,14 @@
-use std::{cmp::max, collections::HashMap, marker::PhantomData, mem::MaybeUninit, sync::Arc};
+use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
+use core::{cmp::max, marker::PhantomData, mem::MaybeUninit};
 
 use crate::{
     mle::{ArcDenseMultilinearExtension, DenseMultilinearExtension, MultilinearExtension},
+    par::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator, ParallelSliceMut},
     util::{bit_decompose, create_uninit_vec, max_usable_threads},
 };
 use ark_std::{end_timer, iterable::Iterable, rand::Rng, start_timer};
 use ff::{Field, PrimeField};
 use ff_ext::ExtensionField;
-use rayon::{
-    iter::IntoParallelIterator,
-    prelude::{IndexedParallelIterator, ParallelIterator},
-    slice::ParallelSliceMut,
-};
 use serde::{Deserialize, Serialize};
 
 #[rustfmt::skip]
@@ -51,7 +48,7 @@ pub struct VirtualPolynomial<E: ExtensionField> {
     /// to.
     pub flattened_ml_extensions: Vec<ArcDenseMultilinearExtension<E>>,
     /// Pointers to the above poly extensions
-    raw_pointers_lookup_table: HashMap<usize, usize>,
+    raw_pointers_lookup_table: BTreeMap<usize, usize>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -77,14 +74,14 @@ impl<E: ExtensionField> VirtualPolynomial<E> {
             },
             products: Vec::new(),
             flattened_ml_extensions: Vec::new(),
-            raw_pointers_lookup_table: HashMap::new(),
+            raw_pointers_lookup_table: BTreeMap::new(),
         }
     }
 
     /// Creates an new virtual polynomial from a MLE and its coefficient.
     pub fn new_from_mle(mle: ArcDenseMultilinearExtension<E>, coefficient: E::BaseField) -> Self {
         let mle_ptr: usize = Arc::as_ptr(&mle) as usize;
-        let mut hm = HashMap::new();
+        let mut hm = BTreeMap::new();
         hm.insert(mle_ptr, 0);
 
         VirtualPolynomial {
@@ -157,7 +154,7 @@ impl<E: ExtensionField> VirtualPolynomial<E> {
     /// - add the MLE to the MLE list;
     /// - multiple each product by MLE and its coefficient.
     /// Returns an error if the MLE has a different `num_vars` from self.
-    #[tracing::instrument(skip_all, name = "mul_by_mle")]
+    #[cfg_attr(feature = "std", tracing::instrument(skip_all, name = "mul_by_mle"))]
     pub fn mul_by_mle(&mut self, mle: ArcDenseMultilinearExtension<E>, coefficient: E::BaseField) {
         let start = start_timer!(|| "mul by mle");
 
@@ -293,6 +290,7 @@ impl<E: ExtensionField> VirtualPolynomial<E> {
     }
 
     /// Print out the evaluation map for testing. Panic if the num_vars > 5.
+    #[cfg(feature = "std")]
     pub fn print_evals(&self) {
         if self.aux_info.num_variables > 5 {
             panic!("this function is used for testing only. cannot print more than 5 num_vars")
@@ -311,7 +309,7 @@ impl<E: ExtensionField> VirtualPolynomial<E> {
         let products = self.products.iter().map(|(f, v)| (*f, v.clone())).collect();
 
         let mut flattened_ml_extensions = vec![];
-        let mut hm = HashMap::new();
+        let mut hm = BTreeMap::new();
         for mle in self.flattened_ml_extensions.iter() {
             let mle_ptr = Arc::as_ptr(mle) as usize;
             let index = self.raw_pointers_lookup_table.get(&mle_ptr).unwrap();
@@ -366,7 +364,10 @@ pub fn build_eq_x_r_sequential<E: ExtensionField>(r: &[E]) -> ArcDenseMultilinea
 /// over r, which is
 ///      eq(x,y) = \prod_i=1^num_var (x_i * r_i + (1-x_i)*(1-r_i))
 
-#[tracing::instrument(skip_all, name = "multilinear_extensions::build_eq_x_r_vec_sequential")]
+#[cfg_attr(
+    feature = "std",
+    tracing::instrument(skip_all, name = "multilinear_extensions::build_eq_x_r_vec_sequential")
+)]
 pub fn build_eq_x_r_vec_sequential<E: ExtensionField>(r: &[E]) -> Vec<E> {
     // avoid unnecessary allocation
     if r.is_empty() {
@@ -386,7 +387,7 @@ pub fn build_eq_x_r_vec_sequential<E: ExtensionField>(r: &[E]) -> Vec<E> {
     let mut evals = create_uninit_vec(1 << r.len());
     build_eq_x_r_helper_sequential(r, &mut evals, E::ONE);
 
-    unsafe { std::mem::transmute(evals) }
+    unsafe { core::mem::transmute(evals) }
 }
 
 /// A helper function to build eq(x, r)*init via dynamic programing tricks.
@@ -430,7 +431,10 @@ pub fn build_eq_x_r<E: ExtensionField>(r: &[E]) -> ArcDenseMultilinearExtension<
 /// over r, which is
 ///      eq(x,y) = \prod_i=1^num_var (x_i * r_i + (1-x_i)*(1-r_i))
 
-#[tracing::instrument(skip_all, name = "multilinear_extensions::build_eq_x_r_vec")]
+#[cfg_attr(
+    feature = "std",
+    tracing::instrument(skip_all, name = "multilinear_extensions::build_eq_x_r_vec")
+)]
 pub fn build_eq_x_r_vec<E: ExtensionField>(r: &[E]) -> Vec<E> {
     // avoid unnecessary allocation
     if r.is_empty() {
@@ -468,7 +472,7 @@ pub fn build_eq_x_r_vec<E: ExtensionField>(r: &[E]) -> Vec<E> {
 
                 build_eq_x_r_helper_sequential(&r[..(r.len() - nbits)], chunks, eq_t);
             });
-        unsafe { std::mem::transmute::<Vec<MaybeUninit<E>>, Vec<E>>(ret) }
+        unsafe { core::mem::transmute::<Vec<MaybeUninit<E>>, Vec<E>>(ret) }
     }
 }
 
@@ -1,4 +1,5 @@
-use std::{cmp::max, collections::HashMap, marker::PhantomData, sync::Arc};
+use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
+use core::{cmp::max, marker::PhantomData};
 
 use crate::{
     mle::{DenseMultilinearExtension, MultilinearExtension},
@@ -48,7 +49,7 @@ pub struct VirtualPolynomialV2<'a, E: ExtensionField> {
     /// to.
     pub flattened_ml_extensions: Vec<ArcMultilinearExtension<'a, E>>,
     /// Pointers to the above poly extensions
-    raw_pointers_lookup_table: HashMap<usize, usize>,
+    raw_pointers_lookup_table: BTreeMap<usize, usize>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,14 +75,14 @@ impl<'a, E: ExtensionField> VirtualPolynomialV2<'a, E> {
             },
             products: Vec::new(),
             flattened_ml_extensions: Vec::new(),
-            raw_pointers_lookup_table: HashMap::new(),
+            raw_pointers_lookup_table: BTreeMap::new(),
         }
     }
 
     /// Creates an new virtual polynomial from a MLE and its coefficient.
     pub fn new_from_mle(mle: ArcMultilinearExtension<'a, E>, coefficient: E) -> Self {
         let mle_ptr: usize = Arc::as_ptr(&mle) as *const () as usize;
-        let mut hm = HashMap::new();
+        let mut hm = BTreeMap::new();
         hm.insert(mle_ptr, 0);
 
         VirtualPolynomialV2 {
@@ -156,7 +157,7 @@ impl<'a, E: ExtensionField> VirtualPolynomialV2<'a, E> {
     /// - add the MLE to the MLE list;
     /// - multiple each product by MLE and its coefficient.
     /// Returns an error if the MLE has a different `num_vars()` from self.
-    #[tracing::instrument(skip_all, name = "mul_by_mle")]
+    #[cfg_attr(feature = "std", tracing::instrument(skip_all, name = "mul_by_mle"))]
     pub fn mul_by_mle(&mut self, mle: ArcMultilinearExtension<'a, E>, coefficient: E::BaseField) {
         let start = start_timer!(|| "mul by mle");
 
@@ -223,6 +224,7 @@ impl<'a, E: ExtensionField> VirtualPolynomialV2<'a, E> {
     }
 
     /// Print out the evaluation map for testing. Panic if the num_vars() > 5.
+    #[cfg(feature = "std")]
     pub fn print_evals(&self) {
         if self.aux_info.max_num_variables > 5 {
             panic!("this function is used for testing only. cannot print more than 5 num_vars()")
@@ -241,7 +243,7 @@ impl<'a, E: ExtensionField> VirtualPolynomialV2<'a, E> {
     //     let products = self.products.iter().map(|(f, v)| (*f, v.clone())).collect();
 
     //     let mut flattened_ml_extensions = vec![];
-    //     let mut hm = HashMap::new();
+    //     let mut hm = BTreeMap::new();
     //     for mle in self.flattened_ml_extensions.iter() {
     //         let mle_ptr = Arc::as_ptr(mle) as *const () as usize;
     //         let index = self.raw_pointers_lookup_table.get(&mle_ptr).unwrap();
@@ -1,4 +1,5 @@
 use crate::constants::DIGEST_WIDTH;
+use alloc::{format, string::String, vec::Vec};
 use goldilocks::SmallField;
 use serde::{Deserialize, Serialize};
 
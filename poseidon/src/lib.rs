@@ -1,9 +1,12 @@
 #![deny(clippy::cargo)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
 extern crate core;
 
-pub(crate) mod constants;
+pub mod constants;
 pub mod digest;
 pub mod poseidon;
+pub mod poseidon2;
 mod poseidon_goldilocks;
 pub mod poseidon_hash;
 pub mod poseidon_permutation;
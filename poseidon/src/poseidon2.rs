@@ -0,0 +1,205 @@
+//! The Poseidon2 permutation of <https://eprint.iacr.org/2023/323> over the Goldilocks field, of
+//! width 12, with the s-box `x^7`, 8 full rounds and 22 partial rounds.
+//!
+//! The full rounds, said external, mix the state with `circ(2 M4, M4, M4)` on its blocks of 4
+//! elements, and the partial rounds, said internal, with `1 + diag(INTERNAL_DIAG)`, where `1` is
+//! the matrix of ones. Either costs a few additions and scalings, rather than a dense product, in
+//! a circuit as natively, see [`external_linear_layer`] and [`internal_linear_layer`].
+//!
+//! The round constants are drawn from the Grain LFSR of the reference implementation, seeded with
+//! these parameters, a full width for every round, of which the internal rounds keep the first.
+//! `INTERNAL_DIAG` is drawn from the same stream next, again until the characteristic
+//! polynomials of the first 24 powers of the internal matrix are irreducible, so that the
+//! internal rounds keep no subspace invariant.
+
+use core::{array, ops::Add};
+use goldilocks::Goldilocks;
+
+pub const WIDTH: usize = 12;
+pub const HALF_N_FULL_ROUNDS: usize = 4;
+pub const N_PARTIAL_ROUNDS: usize = 22;
+pub const N_ROUNDS: usize = 2 * HALF_N_FULL_ROUNDS + N_PARTIAL_ROUNDS;
+
+/// The MDS matrix of the blocks of the external layer.
+pub const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// The constants of the external rounds, the first half before the internal rounds.
+#[rustfmt::skip]
+pub const EXTERNAL_ROUND_CONSTANTS: [[u64; WIDTH]; 2 * HALF_N_FULL_ROUNDS] = [
+    [
+        0x13dcf33aba214f46, 0x30b3b654a1da6d83, 0x1fc634ada6159b56, 0x937459964dc03466,
+        0xedd2ef2ca7949924, 0xede9affde0e22f68, 0x8515b9d6bac9282d, 0x6b5c07b4e9e900d8,
+        0x1ec66368838c8a08, 0x9042367d80d1fbab, 0x400283564a3c3799, 0x4a00be0466bca75e,
+    ],
+    [
+        0x7913beee58e3817f, 0xf545e88532237d90, 0x22f8cb8736042005, 0x6f04990e247a2623,
+        0xfe22e87ba37c38cd, 0xd20e32c85ffe2815, 0x117227674048fe73, 0x4e9fb7ea98a6b145,
+        0xe0866c232b8af08b, 0x00bbc77916884964, 0x7031c0fb990d7116, 0x240a9e87cf35108f,
+    ],
+    [
+        0x2e6363a5a12244b3, 0x5e1c3787d1b5011c, 0x4132660e2a196e8b, 0x3a013b648d3d4327,
+        0xf79839f49888ea43, 0xfe85658ebafe1439, 0xb6889825a14240bd, 0x578453605541382b,
+        0x4508cda8f6b63ce9, 0x9c3ef35848684c91, 0x0812bde23c87178c, 0xfe49638f7f722c14,
+    ],
+    [
+        0x8e3f688ce885cbf5, 0xb8e110acf746a87d, 0xb4b2e8973a6dabef, 0x9e714c5da3d462ec,
+        0x6438f9033d3d0c15, 0x24312f7cf1a27199, 0x23f843bb47acbf71, 0x9183f11a34be9f01,
+        0x839062fbb9d45dbf, 0x24b56e7e6c2e43fa, 0xe1683da61c962a72, 0xa95c63971a19bfa7,
+    ],
+    [
+        0x9271d450fc9b4117, 0xcffeea06b6e3aac1, 0xfa4a44c748d1cd8e, 0xe64db01ba569b469,
+        0xd31005160e4045fe, 0x39e0fa013e025f79, 0xe243be574196a956, 0x205b2a681e3d2642,
+        0x79cae5ad93486bab, 0xfdf567844e32c295, 0x331679589bfb7189, 0xaf06ee32297b89c2,
+    ],
+    [
+        0xa6bcae311e498491, 0x9d16f52c96ac8b3e, 0x48a674b59393fa35, 0x0f9e65da3fde3796,
+        0x1e098310fc84578c, 0x559ae5fab1ae8dad, 0x56bd4d624078881d, 0xfd8bbbf8fbe817b5,
+        0x82d30695c44df534, 0x3ec0a97bc41127c5, 0x1eb8b64adaa22078, 0x82c45e418d60c983,
+    ],
+    [
+        0xb092280f484d55bf, 0xcd317c9537697939, 0xd3be2e352feb79f3, 0xca6d866539a390e5,
+        0xb5efb1a494e55ee6, 0xfa9013ac89756e9e, 0xaeb88efd1e981242, 0x13ee477cdab6e0dc,
+        0xce7df902c40da2d3, 0xf3fbaf0d4e6f5f34, 0xf96354ada6785f38, 0x13b5692812406886,
+    ],
+    [
+        0xf03cae030a0f4418, 0x7d3172887aa98e1a, 0x8a2c2644f2faf7b9, 0x80d721abee696d00,
+        0x27c8b903a4d68267, 0xaf0b7b12f90291b8, 0x00acd08cfdff3817, 0x4659ee496c634328,
+        0xf5b25c10730dbff1, 0xdde3a153297329c2, 0x50c0b70d6910a44b, 0x23c7426af725a6a0,
+    ],
+];
+
+/// The constants of the internal rounds, added to the first element only.
+#[rustfmt::skip]
+pub const INTERNAL_ROUND_CONSTANTS: [u64; N_PARTIAL_ROUNDS] = [
+    0x4adf842aa75d4316, 0x3f36b9fe72ad4e5f, 0x9717f025e7daf6a5, 0xac4bb7c627cf7c13,
+    0x047d766678f13875, 0xbfce13201f3f7e6b, 0x70971fc4e6f85305, 0xe2a6e06e61fcec9c,
+    0xdf58134c134491c2, 0x1c4bd1e816050a7e, 0xf8a6cd02e92cdb0b, 0x4c0f5fc6c0dda3d1,
+    0x0a4a11d794be40a2, 0x6d3fbd3b4a9f1de6, 0x0d0c371c5b35b850, 0x2cff3000be1fcd0a,
+    0xd5ef60d6f76a42fa, 0x942069f5d6eece7e, 0x8b62a5551e9a9797, 0x4f88cdcdfb791921,
+    0xab21b42e0f642307, 0x587fa39990b62800,
+];
+
+/// The diagonal of the internal matrix, minus the matrix of ones.
+#[rustfmt::skip]
+pub const INTERNAL_DIAG: [u64; WIDTH] = [
+    0xbb4089f5abb4ee91, 0x249a8813c8dfbe0e, 0x5a41c825f8b19755, 0x0995d4ba368ac17a,
+    0xf8f8f11aa4ff431e, 0x86ea8b4b38b0777c, 0xda2e9e874d4e24b3, 0x1e0827ba8d7dfca1,
+    0x8048f5f4815e8ae3, 0xadddbdca9aca3eb0, 0xbfbbd8e625a1de90, 0xc43094158fd380a0,
+];
+
+/// Whether the round `r` is external, applying the s-box to every element, rather than to the
+/// first one.
+pub fn is_full_round(r: usize) -> bool {
+    !(HALF_N_FULL_ROUNDS..HALF_N_FULL_ROUNDS + N_PARTIAL_ROUNDS).contains(&r)
+}
+
+/// The external layer `circ(2 M4, M4, M4)`, applied to `state` with the product `scale` by a
+/// coefficient: `M4` on every block, plus the sum of the blocks.
+pub fn external_linear_layer<T: Clone + Add<Output = T>>(
+    state: &[T; WIDTH],
+    scale: impl Fn(&T, u64) -> T,
+) -> [T; WIDTH] {
+    let blocks: [T; WIDTH] = array::from_fn(|i| {
+        let (block, row) = (i / 4, i % 4);
+        (1..4).fold(scale(&state[4 * block], M4[row][0]), |sum, j| {
+            sum + scale(&state[4 * block + j], M4[row][j])
+        })
+    });
+    array::from_fn(|i| {
+        (0..WIDTH / 4).fold(blocks[i].clone(), |sum, block| {
+            sum + blocks[4 * block + i % 4].clone()
+        })
+    })
+}
+
+/// The internal layer `1 + diag(INTERNAL_DIAG)`, applied to `state` with the product `scale` by a
+/// coefficient: every element scaled by its coefficient, plus the sum of the state.
+pub fn internal_linear_layer<T: Clone + Add<Output = T>>(
+    state: &[T; WIDTH],
+    scale: impl Fn(&T, u64) -> T,
+) -> [T; WIDTH] {
+    let sum = state[1..]
+        .iter()
+        .fold(state[0].clone(), |sum, x| sum + x.clone());
+    array::from_fn(|i| scale(&state[i], INTERNAL_DIAG[i]) + sum.clone())
+}
+
+/// Apply the permutation to `input`.
+pub fn poseidon2(input: [Goldilocks; WIDTH]) -> [Goldilocks; WIDTH] {
+    let scale = |x: &Goldilocks, c: u64| *x * Goldilocks::from(c);
+    let mut state = external_linear_layer(&input, scale);
+    for r in 0..N_ROUNDS {
+        if is_full_round(r) {
+            let constants = &EXTERNAL_ROUND_CONSTANTS[full_round_index(r)];
+            for (x, c) in state.iter_mut().zip(constants) {
+                *x = sbox(*x + Goldilocks::from(*c));
+            }
+            state = external_linear_layer(&state, scale);
+        } else {
+            let c = INTERNAL_ROUND_CONSTANTS[r - HALF_N_FULL_ROUNDS];
+            state[0] = sbox(state[0] + Goldilocks::from(c));
+            state = internal_linear_layer(&state, scale);
+        }
+    }
+    state
+}
+
+/// The index in `EXTERNAL_ROUND_CONSTANTS` of the full round `r`.
+pub fn full_round_index(r: usize) -> usize {
+    if r < HALF_N_FULL_ROUNDS {
+        r
+    } else {
+        r - N_PARTIAL_ROUNDS
+    }
+}
+
+fn sbox(x: Goldilocks) -> Goldilocks {
+    let x3 = x * x * x;
+    x3 * x3 * x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goldilocks::SmallField;
+
+    #[test]
+    fn test_poseidon2() {
+        let output = poseidon2(array::from_fn(|i| Goldilocks::from(i as u64)));
+        assert_eq!(output.map(|x| x.to_canonical_u64()), [
+            0x5286dd7462c67b85,
+            0xced2dc81725a0d27,
+            0xee99234891120e0d,
+            0x16613fe1f932ba29,
+            0x6693fc3d1bf463e4,
+            0x1c1ff4a5a11daa23,
+            0x948b8bf49ea3ac6d,
+            0x31fa539817ed2376,
+            0x0141d6b3a9a45d17,
+            0x00259ebc5f0aec9a,
+            0xdd7d12e849b7c312,
+            0x0fe2c1f41ed4fb85,
+        ]);
+    }
+
+    #[test]
+    fn test_linear_layers() {
+        // the layers are the products by their matrices
+        let state: [u64; WIDTH] = array::from_fn(|i| 1 << i);
+        let scale = |x: &u64, c: u64| x * c;
+        let external = external_linear_layer(&state, scale);
+        let internal = internal_linear_layer(&state.map(Goldilocks::from), |x, c| {
+            *x * Goldilocks::from(c)
+        });
+        for i in 0..WIDTH {
+            let row = (0..WIDTH).map(|j| {
+                let factor = if i / 4 == j / 4 { 2 } else { 1 };
+                factor * M4[i % 4][j % 4] * state[j]
+            });
+            assert_eq!(external[i], row.sum::<u64>());
+            let diagonal = Goldilocks::from(INTERNAL_DIAG[i]) * Goldilocks::from(state[i]);
+            let ones = Goldilocks::from(state.iter().sum::<u64>());
+            assert_eq!(internal[i], diagonal + ones);
+        }
+    }
+}
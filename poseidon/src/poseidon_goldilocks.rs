@@ -2,9 +2,9 @@ use crate::{
     constants::N_PARTIAL_ROUNDS,
     poseidon::{AdaptedField, Poseidon},
 };
-use goldilocks::{EPSILON, Goldilocks, SmallField};
 #[cfg(target_arch = "x86_64")]
-use std::hint::unreachable_unchecked;
+use core::hint::unreachable_unchecked;
+use goldilocks::{EPSILON, Goldilocks, SmallField};
 
 #[rustfmt::skip]
 impl Poseidon for Goldilocks {
@@ -4,6 +4,7 @@ use crate::{
     poseidon::Poseidon,
     poseidon_permutation::PoseidonPermutation,
 };
+use alloc::vec::Vec;
 
 pub struct PoseidonHash;
 
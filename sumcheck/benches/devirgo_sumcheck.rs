@@ -5,6 +5,7 @@ use std::array;
 
 use ark_std::test_rng;
 use criterion::*;
+use ff::Field;
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use sumcheck::{structs::IOPProverStateV2 as IOPProverState, util::ceil_log2};
@@ -18,12 +19,19 @@ use multilinear_extensions::{
 };
 use transcript::BasicTranscript as Transcript;
 
-criterion_group!(benches, sumcheck_fn, devirgo_sumcheck_fn,);
+criterion_group!(
+    benches,
+    sumcheck_fn,
+    devirgo_sumcheck_fn,
+    devirgo_padded_sumcheck_fn,
+);
 criterion_main!(benches);
 
 const NUM_SAMPLES: usize = 10;
 const NUM_DEGREE: usize = 3;
 const NV: [usize; 2] = [25, 26];
+/// share of the instances which are not padding, in the padded benchmarks
+const PADDED_DENSITY_LOG2: usize = 3;
 
 /// transpose 2d vector without clone
 pub fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
@@ -40,15 +48,26 @@ pub fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
         .collect()
 }
 
+/// The product of `NUM_DEGREE` random polynomials, the first of which is zero past its first
+/// `num_instances` evaluations, like the selector of a circuit padded to a power of two.
 fn prepare_input<'a, E: ExtensionField>(
     nv: usize,
+    num_instances: usize,
 ) -> (E, VirtualPolynomial<'a, E>, Vec<VirtualPolynomial<'a, E>>) {
     let mut rng = test_rng();
     let max_thread_id = max_usable_threads();
     let size_log2 = ceil_log2(max_thread_id);
-    let fs: [ArcMultilinearExtension<'a, E>; NUM_DEGREE] = array::from_fn(|_| {
-        let mle: ArcMultilinearExtension<'a, E> =
-            DenseMultilinearExtension::<E>::random(nv, &mut rng).into();
+    let fs: [ArcMultilinearExtension<'a, E>; NUM_DEGREE] = array::from_fn(|i| {
+        let mut mle = DenseMultilinearExtension::<E>::random(nv, &mut rng);
+        if i == 0 {
+            match &mut mle.evaluations {
+                multilinear_extensions::mle::FieldType::Base(evaluations) => {
+                    evaluations[num_instances..].fill(E::BaseField::ZERO)
+                }
+                _ => unreachable!(),
+            }
+        }
+        let mle: ArcMultilinearExtension<'a, E> = mle.into();
         mle
     });
 
@@ -116,7 +135,7 @@ fn sumcheck_fn(c: &mut Criterion) {
                     || {
                         let prover_transcript = Transcript::<E>::new(b"test");
                         let (asserted_sum, virtual_poly, virtual_poly_splitted) =
-                            { prepare_input(nv) };
+                            { prepare_input(nv, 1 << nv) };
                         (
                             prover_transcript,
                             asserted_sum,
@@ -161,7 +180,7 @@ fn devirgo_sumcheck_fn(c: &mut Criterion) {
                     || {
                         let prover_transcript = Transcript::<E>::new(b"test");
                         let (asserted_sum, virtual_poly, virtual_poly_splitted) =
-                            { prepare_input(nv) };
+                            { prepare_input(nv, 1 << nv) };
                         (
                             prover_transcript,
                             asserted_sum,
@@ -188,3 +207,37 @@ fn devirgo_sumcheck_fn(c: &mut Criterion) {
         group.finish();
     }
 }
+
+fn devirgo_padded_sumcheck_fn(c: &mut Criterion) {
+    type E = GoldilocksExt2;
+
+    let threads = max_usable_threads();
+    for nv in NV {
+        let mut group = c.benchmark_group(format!("devirgo_padded_nv_{}", nv));
+        group.sample_size(NUM_SAMPLES);
+
+        // Benchmark the proving time
+        group.bench_function(
+            BenchmarkId::new("prove_sumcheck", format!("devirgo_padded_nv_{}", nv)),
+            |b| {
+                b.iter_with_setup(
+                    || {
+                        let prover_transcript = Transcript::<E>::new(b"test");
+                        let (_, _, virtual_poly_splitted) =
+                            prepare_input(nv, (1 << nv) >> PADDED_DENSITY_LOG2);
+                        (prover_transcript, virtual_poly_splitted)
+                    },
+                    |(mut prover_transcript, virtual_poly_splitted)| {
+                        let (_sumcheck_proof_v2, _) = IOPProverState::<E>::prove_batch_polys(
+                            threads,
+                            virtual_poly_splitted,
+                            &mut prover_transcript,
+                        );
+                    },
+                );
+            },
+        );
+
+        group.finish();
+    }
+}
@@ -1,9 +1,16 @@
 #![deny(clippy::cargo)]
 #![feature(decl_macro)]
+//! Without the default `std` feature the crate is `no_std`, and only has the verifier.
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
 #[cfg(feature = "non_pow2_rayon_thread")]
 pub mod local_thread_pool;
+#[cfg(feature = "std")]
 pub mod macros;
+#[cfg(feature = "std")]
 mod prover;
+#[cfg(feature = "std")]
 mod prover_v2;
 pub mod structs;
 pub mod util;
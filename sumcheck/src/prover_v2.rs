@@ -1,15 +1,17 @@
-use std::{array, mem, sync::Arc};
+use std::{mem, sync::Arc};
 
 use ark_std::{end_timer, start_timer};
 use crossbeam_channel::bounded;
+use ff::Field;
 use ff_ext::ExtensionField;
 use itertools::Itertools;
 use multilinear_extensions::{
     commutative_op_mle_pair,
+    device::{Cpu, Device, Evals},
     mle::{DenseMultilinearExtension, MultilinearExtension},
     op_mle, op_mle_product_3, op_mle3_range,
     util::largest_even_below,
-    virtual_poly_v2::VirtualPolynomialV2,
+    virtual_poly_v2::{ArcMultilinearExtension, VirtualPolynomialV2},
 };
 use rayon::{
     Scope,
@@ -35,8 +37,19 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
     /// multi-threads model follow https://arxiv.org/pdf/2210.00264#page=8 "distributed sumcheck"
     /// This is experiment features. It's preferable that we move parallel level up more to
     /// "bould_poly" so it can be more isolation
-    #[tracing::instrument(skip_all, name = "sumcheck::prove_batch_polys", level = "trace")]
     pub fn prove_batch_polys(
+        max_thread_id: usize,
+        polys: Vec<VirtualPolynomialV2<'a, E>>,
+        transcript: &mut impl Transcript<E>,
+    ) -> (IOPProof<E>, IOPProverStateV2<'a, E>) {
+        Self::prove_batch_polys_on(&Cpu, max_thread_id, polys, transcript)
+    }
+
+    /// [`IOPProverStateV2::prove_batch_polys`] with the folds and the round evaluations on
+    /// `device`.
+    #[tracing::instrument(skip_all, name = "sumcheck::prove_batch_polys", level = "trace")]
+    pub fn prove_batch_polys_on(
+        device: &'a dyn Device<E>,
         max_thread_id: usize,
         mut polys: Vec<VirtualPolynomialV2<'a, E>>,
         transcript: &mut impl Transcript<E>,
@@ -88,6 +101,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
         let scoped_fn = |s: &Scope<'a>| {
             for (thread_id, poly) in polys.iter_mut().enumerate().take(num_worker_threads) {
                 let mut prover_state = Self::prover_init_with_extrapolation_aux(
+                    device,
                     mem::take(poly),
                     extrapolation_aux.clone(),
                 );
@@ -123,7 +137,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                             .for_each(|mle| {
                                 let mle = Arc::get_mut(mle).unwrap();
                                 if mle.num_vars() > 0 {
-                                    mle.fix_variables_in_place(&[p.elements]);
+                                    mle.fix_variables_in_place_on(device, &[p.elements]);
                                 }
                             });
                         tx_prover_state
@@ -138,6 +152,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
 
             let mut prover_msgs = Vec::with_capacity(num_variables);
             let mut prover_state = Self::prover_init_with_extrapolation_aux(
+                device,
                 mem::take(&mut polys[main_thread_id]),
                 extrapolation_aux.clone(),
             );
@@ -193,7 +208,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                         if num_variables == 1 {
                             // first time fix variable should be create new instance
                             if mle.num_vars() > 0 {
-                                *mle = mle.fix_variables(&[p.elements]).into();
+                                *mle = fold_on(device, mle, p.elements);
                             } else {
                                 *mle =
                                     Arc::new(DenseMultilinearExtension::from_evaluation_vec_smart(
@@ -204,7 +219,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                         } else {
                             let mle = Arc::get_mut(mle).unwrap();
                             if mle.num_vars() > 0 {
-                                mle.fix_variables_in_place(&[p.elements]);
+                                mle.fix_variables_in_place_on(device, &[p.elements]);
                             }
                         }
                     });
@@ -275,7 +290,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
         // second stage sumcheck
         let poly = merge_sumcheck_polys_v2(&prover_states, max_thread_id);
         let mut prover_state =
-            Self::prover_init_with_extrapolation_aux(poly, extrapolation_aux.clone());
+            Self::prover_init_with_extrapolation_aux(device, poly, extrapolation_aux.clone());
 
         let mut challenge = None;
         let span = entered_span!("prove_rounds_stage2");
@@ -308,7 +323,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                         if mle.num_vars() > 0 {
                             Arc::get_mut(mle)
                                 .unwrap()
-                                .fix_variables_in_place(&[p.elements]);
+                                .fix_variables_in_place_on(device, &[p.elements]);
                         }
                     },
                 );
@@ -335,6 +350,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
     /// Initialize the prover state to argue for the sum of the input polynomial
     /// over {0,1}^`num_vars`.
     pub fn prover_init_with_extrapolation_aux(
+        device: &'a dyn Device<E>,
         polynomial: VirtualPolynomialV2<'a, E>,
         extrapolation_aux: Vec<(Vec<E>, Vec<E>)>,
     ) -> Self {
@@ -352,6 +368,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
             round: 0,
             poly: polynomial,
             extrapolation_aux,
+            device,
         }
     }
 
@@ -401,7 +418,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
             if self.challenges.len() == 1 {
                 self.poly.flattened_ml_extensions.iter_mut().for_each(|f| {
                     if f.num_vars() > 0 {
-                        *f = Arc::new(f.fix_variables(&[r.elements]));
+                        *f = fold_on(self.device, f, r.elements);
                     } else {
                         panic!("calling sumcheck on constant")
                     }
@@ -417,7 +434,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                     .for_each(|f| {
                         if let Some(f) = f {
                             if f.num_vars() > 0 {
-                                f.fix_variables_in_place(&[r.elements]);
+                                f.fix_variables_in_place_on(self.device, &[r.elements]);
                             }
                         }
                     });
@@ -446,109 +463,22 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
             |mut products_sum, (coefficient, products)| {
                 let span = entered_span!("sum");
 
-                let mut sum = match products.len() {
-                    1 => {
-                        let f = &self.poly.flattened_ml_extensions[products[0]];
-                        op_mle! {
-                            |f| {
-                                let res = (0..largest_even_below(f.len()))
-                                    .step_by(2)
-                                    .fold(AdditiveArray::<_, 2>(array::from_fn(|_| 0.into())), |mut acc, b| {
-                                            acc.0[0] += f[b];
-                                            acc.0[1] += f[b+1];
-                                            acc
-                                });
-                                let res = if f.len() == 1 {
-                                    AdditiveArray::<_, 2>([f[0]; 2])
-                                } else {
-                                    res
-                                };
-                                let num_vars_multiplicity = self.poly.aux_info.max_num_variables - (ceil_log2(f.len()).max(1) + self.round - 1);
-                                if num_vars_multiplicity > 0 {
-                                    AdditiveArray(res.0.map(|e| e * E::BaseField::from(1 << num_vars_multiplicity)))
-                                } else {
-                                    res
-                                }
-                            },
-                            |sum| AdditiveArray(sum.0.map(E::from))
-                        }
-                        .to_vec()
-                    }
-                    2 => {
-                        let (f, g) = (
-                            &self.poly.flattened_ml_extensions[products[0]],
-                            &self.poly.flattened_ml_extensions[products[1]],
-                        );
-                        commutative_op_mle_pair!(
-                            |f, g| {
-                                let res = (0..largest_even_below(f.len())).step_by(2).fold(
-                                    AdditiveArray::<_, 3>(array::from_fn(|_| 0.into())),
-                                    |mut acc, b| {
-                                        acc.0[0] += f[b] * g[b];
-                                        acc.0[1] += f[b + 1] * g[b + 1];
-                                        acc.0[2] +=
-                                            (f[b + 1] + f[b + 1] - f[b]) * (g[b + 1] + g[b + 1] - g[b]);
-                                        acc
-                                });
-                                let res = if f.len() == 1 {
-                                    AdditiveArray::<_, 3>([f[0] * g[0]; 3])
-                                } else {
-                                    res
-                                };
-                                let num_vars_multiplicity = self.poly.aux_info.max_num_variables - (ceil_log2(f.len()).max(1) + self.round - 1);
-                                if num_vars_multiplicity > 0 {
-                                    AdditiveArray(res.0.map(|e| e * E::BaseField::from(1 << num_vars_multiplicity)))
-                                } else {
-                                    res
-                                }
-                            },
-                            |sum| AdditiveArray(sum.0.map(E::from))
-                        )
-                        .to_vec()
-                    }
-                    3 => {
-                        let (f1, f2, f3) = (
-                            &self.poly.flattened_ml_extensions[products[0]],
-                            &self.poly.flattened_ml_extensions[products[1]],
-                            &self.poly.flattened_ml_extensions[products[2]],
-                        );
-                        op_mle_product_3!(
-                            |f1, f2, f3| {
-                                let res = (0..largest_even_below(f1.len()))
-                                    .step_by(2)
-                                    .map(|b| {
-                                        // f = c x + d
-                                        let c1 = f1[b + 1] - f1[b];
-                                        let c2 = f2[b + 1] - f2[b];
-                                        let c3 = f3[b + 1] - f3[b];
-                                        AdditiveArray([
-                                            f1[b] * (f2[b] * f3[b]),
-                                            f1[b + 1] * (f2[b + 1] * f3[b + 1]),
-                                            (c1 + f1[b + 1])
-                                                * ((c2 + f2[b + 1]) * (c3 + f3[b + 1])),
-                                            (c1 + c1 + f1[b + 1])
-                                                * ((c2 + c2 + f2[b + 1]) * (c3 + c3 + f3[b + 1])),
-                                        ])
-                                    })
-                                    .sum::<AdditiveArray<_, 4>>();
-                                let res = if f1.len() == 1 {
-                                    AdditiveArray::<_, 4>([f1[0] * f2[0] * f3[0]; 4])
-                                } else {
-                                    res
-                                };
-                                let num_vars_multiplicity = self.poly.aux_info.max_num_variables - (ceil_log2(f1.len()).max(1) + self.round - 1);
-                                if num_vars_multiplicity > 0 {
-                                    AdditiveArray(res.0.map(|e| e * E::BaseField::from(1 << num_vars_multiplicity)))
-                                } else {
-                                    res
-                                }
-                            },
-                            |sum| AdditiveArray(sum.0.map(E::from))
-                        )
-                        .to_vec()
-                    }
-                    _ => unimplemented!("do not support degree > 3"),
+                let mles = products
+                    .iter()
+                    .map(|&i| Evals::of(self.poly.flattened_ml_extensions[i].as_ref()))
+                    .collect::<Vec<_>>();
+                let len = mles[0].len();
+                let mut sum = if len == 1 {
+                    vec![mles.iter().map(|mle| mle.get(0)).product::<E>(); products.len() + 1]
+                } else {
+                    self.device.sumcheck_round(&mles)
                 };
+                let num_vars_multiplicity =
+                    self.poly.aux_info.max_num_variables - (ceil_log2(len).max(1) + self.round - 1);
+                if num_vars_multiplicity > 0 {
+                    let multiplicity = E::from(1u64 << num_vars_multiplicity);
+                    sum.iter_mut().for_each(|sum| *sum *= multiplicity);
+                }
                 exit_span!(span);
                 sum.iter_mut().for_each(|sum| *sum *= coefficient);
 
@@ -703,6 +633,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                     (points, weights)
                 })
                 .collect(),
+            device: &Cpu,
         };
 
         end_timer!(start);
@@ -836,6 +767,7 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                                     .into_par_iter()
                                     .step_by(2)
                                     .with_min_len(64)
+                                    .filter(|&b| !(is_zero_pair(f, b) || is_zero_pair(g, b)))
                                     .map(|b| {
                                         AdditiveArray([
                                             f[b] * g[b],
@@ -871,6 +803,9 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
                                 |f1, f2, f3| {
                                     let res = (0..largest_even_below(f1.len()))
                                     .step_by(2)
+                                    .filter(|&b| {
+                                        !(is_zero_pair(f1, b) || is_zero_pair(f2, b) || is_zero_pair(f3, b))
+                                    })
                                     .map(|b| {
                                         // f = c x + d
                                         let c1 = f1[b + 1] - f1[b];
@@ -935,3 +870,22 @@ impl<'a, E: ExtensionField> IOPProverStateV2<'a, E> {
         }
     }
 }
+
+/// `mle` with its lowest variable fixed at `r`, folded on `device`.
+fn fold_on<'a, E: ExtensionField>(
+    device: &dyn Device<E>,
+    mle: &ArcMultilinearExtension<'a, E>,
+    r: E,
+) -> ArcMultilinearExtension<'a, E> {
+    Arc::new(DenseMultilinearExtension::from_evaluations_ext_vec(
+        mle.num_vars() - 1,
+        device.fold(Evals::of(mle.as_ref()), r),
+    ))
+}
+
+/// Whether `f` is zero at `b` and `b + 1`, hence on the whole line through them, so that any
+/// product with `f` as a factor contributes nothing to the round polynomial there.
+#[inline(always)]
+fn is_zero_pair<F: Field>(f: &[F], b: usize) -> bool {
+    f[b].is_zero_vartime() && f[b + 1].is_zero_vartime()
+}
@@ -1,6 +1,11 @@
+use alloc::vec::Vec;
+
 use ff_ext::ExtensionField;
+#[cfg(feature = "std")]
 use multilinear_extensions::{
-    virtual_poly::VirtualPolynomial, virtual_poly_v2::VirtualPolynomialV2,
+    device::{Cpu, Device},
+    virtual_poly::VirtualPolynomial,
+    virtual_poly_v2::VirtualPolynomialV2,
 };
 use serde::{Deserialize, Serialize};
 use transcript::Challenge;
@@ -27,7 +32,7 @@ pub struct IOPProverMessage<E: ExtensionField> {
 }
 
 /// Prover State of a PolyIOP.
-#[derive(Default)]
+#[cfg(feature = "std")]
 pub struct IOPProverStateV2<'a, E: ExtensionField> {
     /// sampled randomness given by the verifier
     pub challenges: Vec<Challenge<E>>,
@@ -38,9 +43,25 @@ pub struct IOPProverStateV2<'a, E: ExtensionField> {
     /// points with precomputed barycentric weights for extrapolating smaller
     /// degree uni-polys to `max_degree + 1` evaluations.
     pub(crate) extrapolation_aux: Vec<(Vec<E>, Vec<E>)>,
+    /// the device folding the MLEs and evaluating the rounds
+    pub(crate) device: &'a dyn Device<E>,
+}
+
+#[cfg(feature = "std")]
+impl<E: ExtensionField> Default for IOPProverStateV2<'_, E> {
+    fn default() -> Self {
+        Self {
+            challenges: Vec::default(),
+            round: 0,
+            poly: VirtualPolynomialV2::default(),
+            extrapolation_aux: Vec::default(),
+            device: &Cpu,
+        }
+    }
 }
 
 /// Prover State of a PolyIOP.
+#[cfg(feature = "std")]
 #[derive(Default)]
 pub struct IOPProverState<E: ExtensionField> {
     /// sampled randomness given by the verifier
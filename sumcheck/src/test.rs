@@ -1,15 +1,23 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
 
 use ark_std::{rand::RngCore, test_rng};
 use ff::Field;
 use ff_ext::ExtensionField;
-use goldilocks::GoldilocksExt2;
-use multilinear_extensions::{mle::MultilinearExtension, virtual_poly::VirtualPolynomial};
+use goldilocks::{Goldilocks, GoldilocksExt2};
+use multilinear_extensions::{
+    device::{Cpu, Device, Evals},
+    mle::{DenseMultilinearExtension, MultilinearExtension},
+    virtual_poly::VirtualPolynomial,
+    virtual_poly_v2::VirtualPolynomialV2,
+};
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
 use transcript::{BasicTranscript, Transcript};
 
 use crate::{
-    structs::{IOPProverState, IOPVerifierState},
+    structs::{IOPProverState, IOPProverStateV2, IOPVerifierState},
     util::interpolate_uni_poly,
 };
 
@@ -205,3 +213,97 @@ fn test_interpolation() {
 
     assert_eq!(poly.evaluate(&query), interpolate_uni_poly(&evals, query));
 }
+
+/// A serial reference device, counting the kernels it runs.
+#[derive(Default)]
+struct SerialDevice(AtomicUsize);
+
+impl<E: ExtensionField> Device<E> for SerialDevice {
+    fn fold(&self, evals: Evals<'_, E>, r: E) -> Vec<E> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        (0..evals.len() / 2)
+            .map(|b| evals.get(2 * b) + r * (evals.get(2 * b + 1) - evals.get(2 * b)))
+            .collect()
+    }
+
+    fn sumcheck_round(&self, mles: &[Evals<'_, E>]) -> Vec<E> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        (0..=mles.len())
+            .map(|x| {
+                let x = E::from(x as u64);
+                (0..mles[0].len() / 2)
+                    .map(|b| {
+                        mles.iter()
+                            .map(|mle| {
+                                let (lo, hi) = (mle.get(2 * b), mle.get(2 * b + 1));
+                                lo + x * (hi - lo)
+                            })
+                            .product::<E>()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn mul_base_ext(&self, a: &[E::BaseField], b: &[E]) -> Vec<E> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        a.iter().zip(b).map(|(a, b)| *b * *a).collect()
+    }
+}
+
+#[test]
+fn test_sumcheck_on_device() {
+    let mut rng = test_rng();
+    let nv = 6;
+    let mles = (0..4)
+        .map(|_| {
+            let evals = (0..1 << nv)
+                .map(|_| Goldilocks::random(&mut rng))
+                .collect::<Vec<_>>();
+            DenseMultilinearExtension::<GoldilocksExt2>::from_evaluations_vec(nv, evals)
+        })
+        .collect::<Vec<_>>();
+    let poly = || {
+        let mut poly = VirtualPolynomialV2::new(nv);
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| Arc::new(mles[i].clone()));
+        poly.add_mle_list(vec![a.clone()], GoldilocksExt2::from(3));
+        poly.add_mle_list(vec![a.clone(), b.clone()], GoldilocksExt2::from(5));
+        poly.add_mle_list(vec![b, c, d], GoldilocksExt2::ONE);
+        vec![poly]
+    };
+
+    let (proof, _) =
+        IOPProverStateV2::prove_batch_polys(1, poly(), &mut BasicTranscript::new(b"test"));
+    let device = SerialDevice::default();
+    let (device_proof, _) = IOPProverStateV2::prove_batch_polys_on(
+        &device,
+        1,
+        poly(),
+        &mut BasicTranscript::new(b"test"),
+    );
+    // the rounds went through the device, and agree bit for bit with the CPU
+    assert!(device.0.load(Ordering::Relaxed) > 0);
+    assert_eq!(proof, device_proof);
+
+    let [a, b, c, d] = [0, 1, 2, 3].map(|i| mles[i].get_base_field_vec());
+    let sum = (0..1 << nv)
+        .map(|i| {
+            GoldilocksExt2::from(
+                a[i] * Goldilocks::from(3) + a[i] * b[i] * Goldilocks::from(5) + b[i] * c[i] * d[i],
+            )
+        })
+        .sum::<GoldilocksExt2>();
+    assert_eq!(proof.extract_sum(), sum);
+
+    // the reference device agrees with the CPU on the base, mixed and extension products
+    let ext = (0..1 << nv)
+        .map(|_| GoldilocksExt2::random(&mut rng))
+        .collect::<Vec<_>>();
+    for mles in [
+        [Evals::Base(a), Evals::Base(b)],
+        [Evals::Base(a), Evals::Ext(&ext)],
+        [Evals::Ext(&ext), Evals::Ext(&ext)],
+    ] {
+        assert_eq!(device.sumcheck_round(&mles), Cpu.sumcheck_round(&mles));
+    }
+}
@@ -1,23 +1,27 @@
-use std::{
+use alloc::{vec, vec::Vec};
+use core::{
     array,
-    cmp::max,
     iter::Sum,
-    mem,
     ops::{Add, AddAssign, Deref, DerefMut, Mul, MulAssign},
-    sync::Arc,
 };
 
 use ark_std::{end_timer, start_timer};
 use ff::PrimeField;
+#[cfg(feature = "std")]
 use ff_ext::ExtensionField;
+#[cfg(feature = "std")]
 use multilinear_extensions::{
     mle::{DenseMultilinearExtension, FieldType},
     op_mle,
     virtual_poly::VirtualPolynomial,
     virtual_poly_v2::VirtualPolynomialV2,
 };
+#[cfg(feature = "std")]
 use rayon::{prelude::ParallelIterator, slice::ParallelSliceMut};
+#[cfg(feature = "std")]
+use std::{cmp::max, mem, sync::Arc};
 
+#[cfg(feature = "std")]
 use crate::structs::{IOPProverState, IOPProverStateV2};
 
 pub fn barycentric_weights<F: PrimeField>(points: &[F]) -> Vec<F> {
@@ -49,6 +53,7 @@ pub fn serial_batch_inversion<F: PrimeField>(v: &mut [F]) {
 }
 
 // Given a vector of field elements {v_i}, compute the vector {coeff * v_i^(-1)}
+#[cfg(feature = "std")]
 pub fn batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
     // Divide the vector v evenly between all available cores
     let min_elements_per_thread = 1;
@@ -62,6 +67,12 @@ pub fn batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
     });
 }
 
+// Without the `std` feature there is a single thread to invert on.
+#[cfg(not(feature = "std"))]
+pub fn batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
+    serial_batch_inversion_and_mul(v, coeff);
+}
+
 /// Given a vector of field elements {v_i}, compute the vector {coeff * v_i^(-1)}.
 /// This method is explicitly single-threaded.
 fn serial_batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
@@ -102,10 +113,12 @@ fn serial_batch_inversion_and_mul<F: PrimeField>(v: &mut [F], coeff: &F) {
     }
 }
 
+#[cfg(feature = "std")]
 pub(crate) fn extrapolate<F: PrimeField>(points: &[F], weights: &[F], evals: &[F], at: &F) -> F {
     inner_extrapolate::<F, true>(points, weights, evals, at)
 }
 
+#[cfg(feature = "std")]
 pub(crate) fn serial_extrapolate<F: PrimeField>(
     points: &[F],
     weights: &[F],
@@ -115,6 +128,7 @@ pub(crate) fn serial_extrapolate<F: PrimeField>(
     inner_extrapolate::<F, false>(points, weights, evals, at)
 }
 
+#[cfg(feature = "std")]
 fn inner_extrapolate<F: PrimeField, const IS_PARALLEL: bool>(
     points: &[F],
     weights: &[F],
@@ -217,10 +231,11 @@ fn field_factorial<F: PrimeField>(a: usize) -> F {
 pub fn ceil_log2(x: usize) -> usize {
     assert!(x > 0, "ceil_log2: x must be positive");
     // Calculate the number of bits in usize
-    let usize_bits = std::mem::size_of::<usize>() * 8;
+    let usize_bits = core::mem::size_of::<usize>() * 8;
     usize_bits - (x - 1).leading_zeros() as usize
 }
 
+#[cfg(feature = "std")]
 pub(crate) fn merge_sumcheck_polys<E: ExtensionField>(
     prover_states: &[IOPProverState<E>],
     max_thread_id: usize,
@@ -252,6 +267,7 @@ pub(crate) fn merge_sumcheck_polys<E: ExtensionField>(
     poly
 }
 
+#[cfg(feature = "std")]
 pub(crate) fn merge_sumcheck_polys_v2<'a, E: ExtensionField>(
     prover_states: &[IOPProverStateV2<'a, E>],
     max_thread_id: usize,
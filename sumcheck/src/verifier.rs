@@ -1,3 +1,5 @@
+use alloc::{format, vec, vec::Vec};
+
 use ark_std::{end_timer, start_timer};
 use ff_ext::ExtensionField;
 use multilinear_extensions::virtual_poly::VPAuxInfo;
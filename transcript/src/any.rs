@@ -0,0 +1,130 @@
+use alloc::{format, string::String, vec::Vec};
+use core::str::FromStr;
+
+use ff_ext::ExtensionField;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BasicTranscript, Challenge, ForkableTranscript, Transcript,
+    hash::{Blake3, HashTranscript, Keccak256},
+};
+
+/// The hash function behind the Fiat–Shamir transcript.
+///
+/// Poseidon is cheap to verify in a circuit over the base field, Keccak is cheap to verify on the
+/// EVM, and Blake3 is the fastest natively.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranscriptHash {
+    #[default]
+    Poseidon,
+    Blake3,
+    Keccak,
+}
+
+impl TranscriptHash {
+    /// Create a new IOP transcript over this hash function.
+    pub fn new_transcript<E: ExtensionField>(self, label: &'static [u8]) -> AnyTranscript<E> {
+        match self {
+            TranscriptHash::Poseidon => AnyTranscript::Poseidon(BasicTranscript::new(label)),
+            TranscriptHash::Blake3 => AnyTranscript::Blake3(HashTranscript::new(label)),
+            TranscriptHash::Keccak => AnyTranscript::Keccak(HashTranscript::new(label)),
+        }
+    }
+}
+
+impl FromStr for TranscriptHash {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "poseidon" => Ok(TranscriptHash::Poseidon),
+            "blake3" => Ok(TranscriptHash::Blake3),
+            "keccak" => Ok(TranscriptHash::Keccak),
+            _ => Err(format!("unknown transcript hash {s}")),
+        }
+    }
+}
+
+/// A transcript over a hash function chosen at runtime, see [`TranscriptHash`].
+#[derive(Clone)]
+pub enum AnyTranscript<E: ExtensionField> {
+    Poseidon(BasicTranscript<E>),
+    Blake3(HashTranscript<E, Blake3>),
+    Keccak(HashTranscript<E, Keccak256>),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $transcript:ident => $body:expr) => {
+        match $self {
+            AnyTranscript::Poseidon($transcript) => $body,
+            AnyTranscript::Blake3($transcript) => $body,
+            AnyTranscript::Keccak($transcript) => $body,
+        }
+    };
+}
+
+impl<E: ExtensionField> Transcript<E> for AnyTranscript<E> {
+    fn append_field_elements(&mut self, elements: &[E::BaseField]) {
+        dispatch!(self, t => t.append_field_elements(elements))
+    }
+
+    fn append_message(&mut self, msg: &[u8]) {
+        dispatch!(self, t => t.append_message(msg))
+    }
+
+    fn append_field_element_ext(&mut self, element: &E) {
+        dispatch!(self, t => t.append_field_element_ext(element))
+    }
+
+    fn read_challenge(&mut self) -> Challenge<E> {
+        dispatch!(self, t => t.read_challenge())
+    }
+
+    fn read_field_element_exts(&self) -> Vec<E> {
+        dispatch!(self, t => t.read_field_element_exts())
+    }
+
+    fn read_field_element(&self) -> E::BaseField {
+        dispatch!(self, t => t.read_field_element())
+    }
+
+    fn send_challenge(&self, challenge: E) {
+        dispatch!(self, t => t.send_challenge(challenge))
+    }
+
+    fn commit_rolling(&mut self) {
+        dispatch!(self, t => t.commit_rolling())
+    }
+}
+
+impl<E: ExtensionField> ForkableTranscript<E> for AnyTranscript<E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goldilocks::GoldilocksExt2 as E;
+
+    #[test]
+    fn test_transcript_hashes() {
+        let challenge = |hash: TranscriptHash, value: u64| {
+            let mut transcript = hash.new_transcript::<E>(b"test");
+            transcript.append_field_element(&value.into());
+            transcript.get_and_append_challenge(b"c").elements
+        };
+
+        let hashes = [
+            TranscriptHash::Poseidon,
+            TranscriptHash::Blake3,
+            TranscriptHash::Keccak,
+        ];
+        for hash in hashes {
+            // deterministic, and bound to the appended data
+            assert_eq!(challenge(hash, 1), challenge(hash, 1));
+            assert_ne!(challenge(hash, 1), challenge(hash, 2));
+        }
+        assert_ne!(
+            challenge(TranscriptHash::Blake3, 1),
+            challenge(TranscriptHash::Keccak, 1)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use ff::Field;
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
@@ -32,7 +33,7 @@ impl<E: ExtensionField> Transcript<E> for BasicTranscript<E> {
     }
 
     fn read_challenge(&mut self) -> Challenge<E> {
-        let r = E::from_bases(self.permutation.squeeze());
+        let r = E::from_bases(&self.permutation.squeeze()[..E::DEGREE]);
 
         Challenge { elements: r }
     }
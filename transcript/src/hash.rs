@@ -0,0 +1,121 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use ff::FromUniformBytes;
+use ff_ext::ExtensionField;
+use goldilocks::SmallField;
+
+use crate::{Challenge, ForkableTranscript, Transcript};
+
+/// A byte-oriented hash function with 32 bytes of output, see [`HashTranscript`].
+pub trait TranscriptHasher: Clone {
+    /// Hash the concatenation of `inputs`.
+    fn hash(inputs: &[&[u8]]) -> [u8; 32];
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Blake3;
+
+impl TranscriptHasher for Blake3 {
+    fn hash(inputs: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        for input in inputs {
+            hasher.update(input);
+        }
+        *hasher.finalize().as_bytes()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Keccak256;
+
+impl TranscriptHasher for Keccak256 {
+    fn hash(inputs: &[&[u8]]) -> [u8; 32] {
+        use tiny_keccak::Hasher;
+
+        let mut hasher = tiny_keccak::Keccak::v256();
+        for input in inputs {
+            hasher.update(input);
+        }
+        let mut output = [0; 32];
+        hasher.finalize(&mut output);
+        output
+    }
+}
+
+/// A transcript over a byte-oriented hash function. Each append replaces the state by the hash
+/// of the state and the data, and field elements are absorbed as canonical little-endian u64.
+#[derive(Clone)]
+pub struct HashTranscript<E: ExtensionField, H: TranscriptHasher> {
+    state: [u8; 32],
+    _marker: PhantomData<(E, H)>,
+}
+
+impl<E: ExtensionField, H: TranscriptHasher> HashTranscript<E, H> {
+    // Domain separation between the kinds of appended data.
+    const TAG_MESSAGE: &'static [u8] = b"message";
+    const TAG_FIELD: &'static [u8] = b"field";
+    const TAG_CHALLENGE: &'static [u8] = b"challenge";
+
+    /// Create a new IOP transcript.
+    pub fn new(label: &'static [u8]) -> Self {
+        Self {
+            state: H::hash(&[label]),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E: ExtensionField, H: TranscriptHasher> Transcript<E> for HashTranscript<E, H> {
+    fn append_field_elements(&mut self, elements: &[E::BaseField]) {
+        let bytes = elements
+            .iter()
+            .flat_map(|e| e.to_canonical_u64().to_le_bytes())
+            .collect::<Vec<_>>();
+        self.state = H::hash(&[&self.state, Self::TAG_FIELD, &bytes]);
+    }
+
+    fn append_message(&mut self, msg: &[u8]) {
+        self.state = H::hash(&[&self.state, Self::TAG_MESSAGE, msg]);
+    }
+
+    fn append_field_element_ext(&mut self, element: &E) {
+        self.append_field_elements(element.as_bases())
+    }
+
+    fn read_challenge(&mut self) -> Challenge<E> {
+        // 64 bytes per base element, so that its distribution is statistically uniform.
+        let bases = (0..E::DEGREE as u8)
+            .map(|i| {
+                let mut wide = [0; 64];
+                for (half, bytes) in wide.chunks_exact_mut(32).enumerate() {
+                    let counter = [i, half as u8];
+                    bytes.copy_from_slice(&H::hash(&[&self.state, Self::TAG_CHALLENGE, &counter]));
+                }
+                E::BaseField::from_uniform_bytes(&wide)
+            })
+            .collect::<Vec<_>>();
+
+        Challenge {
+            elements: E::from_bases(&bases),
+        }
+    }
+
+    fn read_field_element_exts(&self) -> Vec<E> {
+        unimplemented!()
+    }
+
+    fn read_field_element(&self) -> E::BaseField {
+        unimplemented!()
+    }
+
+    fn send_challenge(&self, _challenge: E) {
+        unimplemented!()
+    }
+
+    fn commit_rolling(&mut self) {
+        // do nothing
+    }
+}
+
+impl<E: ExtensionField, H: TranscriptHasher> ForkableTranscript<E> for HashTranscript<E, H> {}
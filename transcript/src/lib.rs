@@ -1,11 +1,27 @@
 #![deny(clippy::cargo)]
 //! This repo is not properly implemented
 //! Transcript APIs are placeholders; the actual logic is to be implemented later.
+//!
+//! Without the default `std` feature the crate is `no_std`, as are `ff_ext` and `poseidon`, and
+//! the synchronized and recording transcripts are left out. The verifier in `ceno_zkvm` still
+//! requires `std`.
 #![feature(generic_arg_infer)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
 
+pub mod any;
 pub mod basic;
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod recording;
+#[cfg(feature = "std")]
 pub mod syncronized;
+pub use any::{AnyTranscript, TranscriptHash};
 pub use basic::BasicTranscript;
+pub use hash::HashTranscript;
+#[cfg(feature = "std")]
+pub use recording::{RecordingTranscript, TranscriptLog};
+#[cfg(feature = "std")]
 pub use syncronized::TranscriptSyncronized;
 
 mod hasher;
@@ -15,6 +31,7 @@ pub struct Challenge<F> {
     pub elements: F,
 }
 
+use alloc::vec::Vec;
 use ff_ext::ExtensionField;
 use goldilocks::SmallField;
 /// The Transcript trait
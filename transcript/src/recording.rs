@@ -0,0 +1,232 @@
+//! A transcript wrapper recording every absorb and squeeze, to locate where the prover and the
+//! verifier transcripts diverge when a proof fails to verify.
+//!
+//! Run the prover on `prover_log.record(transcript)` and the verifier on
+//! `verifier_log.record(transcript)`, then compare the logs with
+//! [`TranscriptLog::first_divergence`].
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use ff_ext::ExtensionField;
+
+use crate::{Challenge, ForkableTranscript, Transcript};
+
+/// An operation on a transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptEvent<E: ExtensionField> {
+    Absorb(Vec<E::BaseField>),
+    AbsorbExt(E),
+    Message(Vec<u8>),
+    /// a squeezed challenge, with its label when drawn by `get_and_append_challenge`
+    Challenge(Option<&'static [u8]>, E),
+}
+
+/// The events of each fork, by the path of the fork.
+pub type ForkEvents<E> = BTreeMap<Vec<usize>, Vec<TranscriptEvent<E>>>;
+
+/// The events of a transcript and of its forks. A fork is identified by the path of fork
+/// indices leading to it from the recorded transcript, which is the empty path.
+#[derive(Clone, Default)]
+pub struct TranscriptLog<E: ExtensionField> {
+    forks: Arc<Mutex<ForkEvents<E>>>,
+}
+
+/// The first event where two logs differ, see [`TranscriptLog::first_divergence`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence<E: ExtensionField> {
+    /// the path of the fork, e.g. `[i]` for the fork of the `i`-th circuit
+    pub fork: Vec<usize>,
+    /// the position of the event in the fork
+    pub index: usize,
+    /// the events of both logs there, `None` when a log stops earlier
+    pub left: Option<TranscriptEvent<E>>,
+    pub right: Option<TranscriptEvent<E>>,
+}
+
+impl<E: ExtensionField> TranscriptLog<E> {
+    pub fn new() -> Self {
+        Self {
+            forks: Arc::default(),
+        }
+    }
+
+    /// Record the operations on `inner`, and on its forks, in this log.
+    pub fn record<T: Transcript<E>>(&self, inner: T) -> RecordingTranscript<E, T> {
+        RecordingTranscript {
+            inner,
+            fork: vec![],
+            log: self.clone(),
+        }
+    }
+
+    /// The recorded events, by fork.
+    pub fn events(&self) -> ForkEvents<E> {
+        self.forks.lock().unwrap().clone()
+    }
+
+    /// The first event, by fork then by position, which differs between `self` and `other`.
+    ///
+    /// A verifier stops recording where it rejects, so events which differ take precedence
+    /// over a log stopping earlier than the other; the latter is reported only when the events
+    /// of both logs otherwise agree.
+    pub fn first_divergence(&self, other: &Self) -> Option<Divergence<E>> {
+        let (left, right) = (self.events(), other.events());
+        let empty = vec![];
+        let mut forks = left.keys().chain(right.keys()).collect::<Vec<_>>();
+        forks.sort();
+        forks.dedup();
+        let divergence = |fork: &Vec<usize>, truncated: bool| {
+            let left = left.get(fork).unwrap_or(&empty);
+            let right = right.get(fork).unwrap_or(&empty);
+            (0..left.len().max(right.len()))
+                .find(|&i| match (left.get(i), right.get(i)) {
+                    (Some(l), Some(r)) => l != r,
+                    _ => truncated,
+                })
+                .map(|index| Divergence {
+                    fork: fork.clone(),
+                    index,
+                    left: left.get(index).cloned(),
+                    right: right.get(index).cloned(),
+                })
+        };
+        forks
+            .iter()
+            .find_map(|fork| divergence(fork, false))
+            .or_else(|| forks.iter().find_map(|fork| divergence(fork, true)))
+    }
+
+    fn push(&self, fork: &[usize], event: TranscriptEvent<E>) {
+        self.forks
+            .lock()
+            .unwrap()
+            .entry(fork.to_vec())
+            .or_default()
+            .push(event);
+    }
+}
+
+/// A transcript recording its operations in a [`TranscriptLog`].
+#[derive(Clone)]
+pub struct RecordingTranscript<E: ExtensionField, T> {
+    inner: T,
+    fork: Vec<usize>,
+    log: TranscriptLog<E>,
+}
+
+impl<E: ExtensionField, T: Transcript<E>> Transcript<E> for RecordingTranscript<E, T> {
+    fn append_field_elements(&mut self, elements: &[E::BaseField]) {
+        self.log
+            .push(&self.fork, TranscriptEvent::Absorb(elements.to_vec()));
+        self.inner.append_field_elements(elements)
+    }
+
+    fn append_message(&mut self, msg: &[u8]) {
+        self.log
+            .push(&self.fork, TranscriptEvent::Message(msg.to_vec()));
+        self.inner.append_message(msg)
+    }
+
+    fn append_field_element_ext(&mut self, element: &E) {
+        self.log
+            .push(&self.fork, TranscriptEvent::AbsorbExt(*element));
+        self.inner.append_field_element_ext(element)
+    }
+
+    fn get_and_append_challenge(&mut self, label: &'static [u8]) -> Challenge<E> {
+        let challenge = self.inner.get_and_append_challenge(label);
+        self.log.push(
+            &self.fork,
+            TranscriptEvent::Challenge(Some(label), challenge.elements),
+        );
+        challenge
+    }
+
+    fn read_challenge(&mut self) -> Challenge<E> {
+        let challenge = self.inner.read_challenge();
+        self.log.push(
+            &self.fork,
+            TranscriptEvent::Challenge(None, challenge.elements),
+        );
+        challenge
+    }
+
+    fn read_field_element_exts(&self) -> Vec<E> {
+        self.inner.read_field_element_exts()
+    }
+
+    fn read_field_element(&self) -> E::BaseField {
+        self.inner.read_field_element()
+    }
+
+    fn send_challenge(&self, challenge: E) {
+        self.inner.send_challenge(challenge)
+    }
+
+    fn commit_rolling(&mut self) {
+        self.inner.commit_rolling()
+    }
+}
+
+impl<E: ExtensionField, T: ForkableTranscript<E>> ForkableTranscript<E>
+    for RecordingTranscript<E, T>
+{
+    fn fork(self, n: usize) -> Vec<Self> {
+        self.inner
+            .fork(n)
+            .into_iter()
+            .enumerate()
+            .map(|(i, inner)| RecordingTranscript {
+                inner,
+                fork: [self.fork.as_slice(), &[i]].concat(),
+                log: self.log.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BasicTranscript;
+    use goldilocks::GoldilocksExt2 as E;
+
+    #[test]
+    fn test_first_divergence() {
+        let run = |value: u64| {
+            let log = TranscriptLog::<E>::new();
+            let mut transcript = log.record(BasicTranscript::new(b"test"));
+            transcript.append_field_element(&1u64.into());
+            transcript.get_and_append_challenge(b"alpha");
+            for (i, mut fork) in transcript.fork(2).into_iter().enumerate() {
+                fork.append_field_element(&(if i == 1 { value } else { 2 }).into());
+                fork.read_challenge();
+            }
+            log
+        };
+
+        let log = run(2);
+        assert_eq!(log.events().len(), 3);
+        assert_eq!(log.first_divergence(&run(2)), None);
+
+        // the second fork absorbs a different value, and draws a different challenge after it
+        let divergence = log.first_divergence(&run(3)).unwrap();
+        assert_eq!(divergence.fork, vec![1]);
+        assert_eq!(divergence.index, 0);
+        assert_eq!(
+            divergence.left,
+            Some(TranscriptEvent::Absorb(vec![2u64.into()]))
+        );
+
+        // a log stopping early diverges where it stops
+        let truncated = TranscriptLog::<E>::new();
+        let mut transcript = truncated.record(BasicTranscript::new(b"test"));
+        transcript.append_field_element(&1u64.into());
+        let divergence = log.first_divergence(&truncated).unwrap();
+        assert_eq!((divergence.fork, divergence.index), (vec![], 1));
+        assert_eq!(divergence.right, None);
+    }
+}